@@ -22,7 +22,7 @@
 //! classically to maximize the expected cut value.
 
 use ruqu_core::circuit::QuantumCircuit;
-use ruqu_core::simulator::{SimConfig, Simulator};
+use ruqu_core::simulator::{SimConfig, Simulator, StateBackend};
 use ruqu_core::types::{PauliOp, PauliString};
 
 // ---------------------------------------------------------------------------
@@ -187,6 +187,7 @@ pub fn evaluate_qaoa_cost(
         seed,
         noise: None,
         shots: None,
+        backend: StateBackend::Dense,
     };
     let result = Simulator::run_with_config(&circuit, &sim_config)?;
 
@@ -244,6 +245,7 @@ pub fn run_qaoa(config: &QaoaConfig) -> ruqu_core::error::Result<QaoaResult> {
                     seed: config.seed,
                     noise: None,
                     shots: None,
+                    backend: StateBackend::Dense,
                 },
             )?;
             let probs = sim_result.state.probabilities();
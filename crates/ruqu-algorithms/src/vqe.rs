@@ -14,7 +14,7 @@
 //! rotations on every qubit, followed by a linear CNOT entangling chain.
 
 use ruqu_core::circuit::QuantumCircuit;
-use ruqu_core::simulator::{SimConfig, Simulator};
+use ruqu_core::simulator::{SimConfig, Simulator, StateBackend};
 use ruqu_core::types::{Hamiltonian, PauliOp, PauliString};
 
 // ---------------------------------------------------------------------------
@@ -125,6 +125,7 @@ pub fn evaluate_energy(config: &VqeConfig, params: &[f64]) -> ruqu_core::error::
         seed: config.seed,
         noise: None,
         shots: None,
+        backend: StateBackend::Dense,
     };
     let result = Simulator::run_with_config(&circuit, &sim_config)?;
     Ok(result.state.expectation_hamiltonian(&config.hamiltonian))
@@ -123,7 +123,13 @@ pub fn analyze_circuit(circuit: &QuantumCircuit) -> CircuitAnalysis {
             | Gate::Rz(_, _)
             | Gate::Phase(_, _)
             | Gate::Rzz(_, _, _)
-            | Gate::Unitary1Q(_, _) => {
+            | Gate::Unitary1Q(_, _)
+            | Gate::Custom { .. } => {
+                non_clifford_gates += 1;
+            }
+            // The stabilizer backend has no classical control; count it
+            // against Clifford routing.
+            Gate::Conditional { .. } => {
                 non_clifford_gates += 1;
             }
             Gate::Measure(_) => {
@@ -1,7 +1,19 @@
 //! Quantum circuit: a fluent builder for ordered gate sequences
 
+use crate::error::{QuantumError, Result};
 use crate::gate::Gate;
-use crate::types::QubitIndex;
+use crate::state::QuantumState;
+use crate::types::{Complex, QubitIndex};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Largest register for which `is_equivalent` also probes every
+/// computational basis state.
+const EQUIVALENCE_BASIS_QUBITS: u32 = 6;
+
+/// Number of random input states probed by `is_equivalent`.
+const EQUIVALENCE_RANDOM_STATES: usize = 8;
 
 /// A quantum circuit consisting of an ordered sequence of gates on a qubit register.
 #[derive(Debug, Clone)]
@@ -124,6 +136,17 @@ impl QuantumCircuit {
         self
     }
 
+    /// Apply `gate` only if classical bit `bit`, the most recent outcome of
+    /// measuring qubit `bit`, equals `value`.
+    pub fn c_if(&mut self, bit: QubitIndex, value: bool, gate: Gate) -> &mut Self {
+        self.gates.push(Gate::Conditional {
+            bit,
+            value,
+            gate: Box::new(gate),
+        });
+        self
+    }
+
     /// Push an arbitrary gate onto the circuit.
     pub fn add_gate(&mut self, gate: Gate) -> &mut Self {
         self.gates.push(gate);
@@ -146,6 +169,56 @@ impl QuantumCircuit {
         self.gates.len()
     }
 
+    /// Number of variational parameters: the angles of the parametric gates
+    /// (see [`Gate::parameter`]), indexed in gate order.
+    pub fn num_parameters(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|g| g.parameter().is_some())
+            .count()
+    }
+
+    /// Current values of all parameters, in gate order.
+    pub fn parameters(&self) -> Vec<f64> {
+        self.gates.iter().filter_map(Gate::parameter).collect()
+    }
+
+    /// Set parameter `index` to `value`.
+    pub fn set_parameter(&mut self, index: usize, value: f64) -> Result<&mut Self> {
+        let count = self.num_parameters();
+        let gate = self
+            .gates
+            .iter_mut()
+            .filter(|g| g.parameter().is_some())
+            .nth(index)
+            .ok_or_else(|| {
+                QuantumError::CircuitError(format!(
+                    "parameter index {} out of range for circuit with {} parameters",
+                    index, count
+                ))
+            })?;
+        gate.set_parameter(value);
+        Ok(self)
+    }
+
+    /// Bind every parameter at once; `values` must have exactly
+    /// [`num_parameters`](Self::num_parameters) entries.
+    pub fn bind_parameters(&mut self, values: &[f64]) -> Result<&mut Self> {
+        let count = self.num_parameters();
+        if values.len() != count {
+            return Err(QuantumError::CircuitError(format!(
+                "expected {} parameter values, got {}",
+                count,
+                values.len()
+            )));
+        }
+        let gates = self.gates.iter_mut().filter(|g| g.parameter().is_some());
+        for (gate, &value) in gates.zip(values) {
+            gate.set_parameter(value);
+        }
+        Ok(self)
+    }
+
     /// Compute the circuit depth: the longest path through the circuit
     /// taking qubit dependencies into account.
     ///
@@ -182,4 +255,75 @@ impl QuantumCircuit {
 
         qubit_depth.into_iter().max().unwrap_or(0)
     }
+
+    /// Export the circuit as an OpenQASM 2.0 program.
+    ///
+    /// See [`crate::qasm::to_qasm2`]; fails if a gate has no OpenQASM 2.0
+    /// equivalent.
+    pub fn to_qasm(&self) -> Result<String> {
+        crate::qasm::to_qasm2(self)
+    }
+
+    /// Check whether two circuits implement the same unitary up to global phase.
+    ///
+    /// Both circuits are simulated on every computational basis state (for
+    /// registers of up to 6 qubits) and on a fixed set of random superposition
+    /// states. The circuits are equivalent if every pair of outputs satisfies
+    /// `|<psi_a|psi_b>| >= 1 - tol`. Random inputs are what catch relative
+    /// phases between basis states (e.g. `Z` versus identity).
+    ///
+    /// Returns `false` if the qubit counts differ, if either circuit contains
+    /// measurements or resets, or if simulation fails.
+    pub fn is_equivalent(&self, other: &QuantumCircuit, tol: f64) -> bool {
+        if self.num_qubits != other.num_qubits {
+            return false;
+        }
+        let non_unitary = |g: &Gate| matches!(g, Gate::Measure(_) | Gate::Reset(_));
+        if self.gates.iter().any(non_unitary) || other.gates.iter().any(non_unitary) {
+            return false;
+        }
+
+        let dim = 1usize << self.num_qubits;
+        let mut inputs: Vec<Vec<Complex>> = Vec::new();
+        if self.num_qubits <= EQUIVALENCE_BASIS_QUBITS {
+            for i in 0..dim {
+                let mut amps = vec![Complex::ZERO; dim];
+                amps[i] = Complex::ONE;
+                inputs.push(amps);
+            }
+        }
+        let mut rng = StdRng::seed_from_u64(0x5EED_E001);
+        for _ in 0..EQUIVALENCE_RANDOM_STATES {
+            let mut amps: Vec<Complex> = (0..dim)
+                .map(|_| Complex::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+                .collect();
+            let norm = amps.iter().map(|a| a.norm_sq()).sum::<f64>().sqrt();
+            for a in amps.iter_mut() {
+                *a = *a * (1.0 / norm);
+            }
+            inputs.push(amps);
+        }
+
+        inputs.into_iter().all(
+            |amps| match (self.run_on(amps.clone()), other.run_on(amps)) {
+                (Some(a), Some(b)) => {
+                    let mut inner = Complex::ZERO;
+                    for (x, y) in a.state_vector().iter().zip(b.state_vector()) {
+                        inner += x.conj() * *y;
+                    }
+                    inner.norm() >= 1.0 - tol
+                }
+                _ => false,
+            },
+        )
+    }
+
+    /// Apply every gate to the given input state.
+    fn run_on(&self, amps: Vec<Complex>) -> Option<QuantumState> {
+        let mut state = QuantumState::from_amplitudes(amps, self.num_qubits).ok()?;
+        for gate in &self.gates {
+            state.apply_gate(gate).ok()?;
+        }
+        Some(state)
+    }
 }
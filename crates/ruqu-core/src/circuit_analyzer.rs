@@ -64,7 +64,9 @@ pub fn classify_gate(gate: &Gate) -> GateClass {
         | Gate::Rz(_, _)
         | Gate::Phase(_, _)
         | Gate::Rzz(_, _, _)
-        | Gate::Unitary1Q(_, _) => GateClass::NonClifford,
+        | Gate::Unitary1Q(_, _)
+        | Gate::Custom { .. }
+        | Gate::Conditional { .. } => GateClass::NonClifford,
 
         Gate::Measure(_) => GateClass::Measurement,
         Gate::Reset(_) => GateClass::Reset,
@@ -119,7 +119,7 @@ pub fn build_interaction_graph(circuit: &QuantumCircuit) -> InteractionGraph {
     let mut edge_counts: HashMap<(u32, u32), usize> = HashMap::new();
 
     for gate in circuit.gates() {
-        let qubits = gate.qubits();
+        let qubits = gate_support(gate);
         if qubits.len() == 2 {
             let (a, b) = if qubits[0] <= qubits[1] {
                 (qubits[0], qubits[1])
@@ -236,7 +236,7 @@ pub fn temporal_decomposition(circuit: &QuantumCircuit) -> Vec<QuantumCircuit> {
                 }
             }
             _ => {
-                let qubits = gate.qubits();
+                let qubits = gate_support(gate);
 
                 // Before adding this gate, check if we have a natural breakpoint:
                 // All previously-active qubits have been measured/reset, and this
@@ -596,7 +596,7 @@ pub fn spatial_decomposition(
         // First pass: identify any extra qubits needed for cross-group gates
         // that have at least one qubit in this group.
         for gate in circuit.gates() {
-            let gate_qubits = gate.qubits();
+            let gate_qubits = gate_support(gate);
             if gate_qubits.is_empty() {
                 continue;
             }
@@ -627,7 +627,7 @@ pub fn spatial_decomposition(
 
         // Second pass: add gates that belong to this group.
         for gate in circuit.gates() {
-            let gate_qubits = gate.qubits();
+            let gate_qubits = gate_support(gate);
 
             // Barrier: include in every sub-circuit.
             if matches!(gate, Gate::Barrier) {
@@ -662,6 +662,20 @@ pub fn spatial_decomposition(
     result
 }
 
+/// Qubits a gate depends on: the ones it acts on plus, for a classically
+/// controlled gate, the qubit whose measurement it reads. Keeping these
+/// together stops a split from separating a measurement from the gates it
+/// controls.
+fn gate_support(gate: &Gate) -> Vec<u32> {
+    let mut qubits = gate.qubits();
+    if let Gate::Conditional { bit, .. } = gate {
+        if !qubits.contains(bit) {
+            qubits.push(*bit);
+        }
+    }
+    qubits
+}
+
 /// Remap qubit indices in a gate according to the given mapping.
 fn remap_gate(gate: &Gate, remap: &HashMap<u32, u32>) -> Gate {
     match gate {
@@ -685,6 +699,15 @@ fn remap_gate(gate: &Gate, remap: &HashMap<u32, u32>) -> Gate {
         Gate::Reset(q) => Gate::Reset(remap[q]),
         Gate::Barrier => Gate::Barrier,
         Gate::Unitary1Q(q, m) => Gate::Unitary1Q(remap[q], *m),
+        Gate::Custom { matrix, targets } => Gate::Custom {
+            matrix: matrix.clone(),
+            targets: targets.iter().map(|q| remap[q]).collect(),
+        },
+        Gate::Conditional { bit, value, gate } => Gate::Conditional {
+            bit: remap[bit],
+            value: *value,
+            gate: Box::new(remap_gate(gate, remap)),
+        },
     }
 }
 
@@ -1180,7 +1203,7 @@ pub fn decompose(circuit: &QuantumCircuit, max_segment_qubits: u32) -> CircuitPa
 fn active_qubit_count(circuit: &QuantumCircuit) -> u32 {
     let mut active: HashSet<u32> = HashSet::new();
     for gate in circuit.gates() {
-        for &q in &gate.qubits() {
+        for &q in &gate_support(gate) {
             active.insert(q);
         }
     }
@@ -1210,7 +1233,7 @@ fn extract_component_circuit(circuit: &QuantumCircuit, component: &HashSet<u32>)
                 sub_circuit.add_gate(Gate::Barrier);
             }
             _ => {
-                let qubits = gate.qubits();
+                let qubits = gate_support(gate);
                 if qubits.is_empty() {
                     continue;
                 }
@@ -1233,7 +1256,7 @@ fn gate_indices_for_component(circuit: &QuantumCircuit, component: &HashSet<u32>
         .iter()
         .enumerate()
         .filter_map(|(i, gate)| {
-            let qubits = gate.qubits();
+            let qubits = gate_support(gate);
             if qubits.is_empty() {
                 return Some(i); // Barrier belongs to all components.
             }
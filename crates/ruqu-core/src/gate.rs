@@ -1,8 +1,12 @@
 //! Quantum gate definitions and matrix representations
 
+use crate::error::{QuantumError, Result};
 use crate::types::{Complex, QubitIndex};
 use std::f64::consts::FRAC_1_SQRT_2;
 
+/// Entrywise tolerance on `U^dagger U = I` for custom gate matrices.
+pub const UNITARY_TOLERANCE: f64 = 1e-9;
+
 /// Quantum gate operations
 #[derive(Debug, Clone)]
 pub enum Gate {
@@ -33,9 +37,36 @@ pub enum Gate {
 
     // ----- Fused / custom single-qubit unitary (produced by optimizer) -----
     Unitary1Q(QubitIndex, [[Complex; 2]; 2]),
+
+    // ----- User-defined unitary on k qubits -----
+    /// A 2^k x 2^k unitary on `targets`. Row/column index bit `k - 1 - j`
+    /// is the value of `targets[j]`, so `targets[0]` is the most
+    /// significant, as in [`Gate::matrix_2q`]. Build with [`Gate::custom`]
+    /// to validate up front; the simulator validates again when applying.
+    Custom {
+        matrix: Vec<Vec<Complex>>,
+        targets: Vec<QubitIndex>,
+    },
+
+    // ----- Classically controlled operation -----
+    /// Apply `gate` only if classical bit `bit` equals `value`.
+    ///
+    /// Measuring qubit `i` writes classical bit `i`; the bit keeps the
+    /// most recent outcome and reads as `false` before any measurement.
+    Conditional {
+        bit: QubitIndex,
+        value: bool,
+        gate: Box<Gate>,
+    },
 }
 
 impl Gate {
+    /// Create a validated [`Gate::Custom`] gate.
+    pub fn custom(matrix: Vec<Vec<Complex>>, targets: Vec<QubitIndex>) -> Result<Gate> {
+        validate_custom(&matrix, &targets)?;
+        Ok(Gate::Custom { matrix, targets })
+    }
+
     /// Return the qubit indices this gate acts on.
     pub fn qubits(&self) -> Vec<QubitIndex> {
         match self {
@@ -55,6 +86,9 @@ impl Gate {
             | Gate::Reset(q)
             | Gate::Unitary1Q(q, _) => vec![*q],
 
+            Gate::Custom { targets, .. } => targets.clone(),
+            Gate::Conditional { gate, .. } => gate.qubits(),
+
             Gate::CNOT(q1, q2) | Gate::CZ(q1, q2) | Gate::SWAP(q1, q2) | Gate::Rzz(q1, q2, _) => {
                 vec![*q1, *q2]
             }
@@ -63,9 +97,43 @@ impl Gate {
         }
     }
 
-    /// Returns `true` for non-unitary operations (measurement, reset, barrier).
+    /// Returns `true` for non-unitary operations (measurement, reset,
+    /// barrier, classically controlled gates).
     pub fn is_non_unitary(&self) -> bool {
-        matches!(self, Gate::Measure(_) | Gate::Reset(_) | Gate::Barrier)
+        matches!(
+            self,
+            Gate::Measure(_) | Gate::Reset(_) | Gate::Barrier | Gate::Conditional { .. }
+        )
+    }
+
+    /// Rotation angle of a single-parameter gate (`Rx`, `Ry`, `Rz`, `Phase`,
+    /// `Rzz`); `None` otherwise.
+    pub fn parameter(&self) -> Option<f64> {
+        match self {
+            Gate::Rx(_, theta)
+            | Gate::Ry(_, theta)
+            | Gate::Rz(_, theta)
+            | Gate::Phase(_, theta)
+            | Gate::Rzz(_, _, theta) => Some(*theta),
+            _ => None,
+        }
+    }
+
+    /// Replace the rotation angle of a single-parameter gate.
+    ///
+    /// Returns `false`, leaving the gate unchanged, if it has no parameter.
+    pub fn set_parameter(&mut self, value: f64) -> bool {
+        match self {
+            Gate::Rx(_, theta)
+            | Gate::Ry(_, theta)
+            | Gate::Rz(_, theta)
+            | Gate::Phase(_, theta)
+            | Gate::Rzz(_, _, theta) => {
+                *theta = value;
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Return the 2x2 unitary matrix for single-qubit gates; `None` otherwise.
@@ -198,3 +266,47 @@ impl Gate {
         }
     }
 }
+
+/// Check that `matrix` is a 2^k x 2^k unitary for `k` distinct `targets`.
+pub fn validate_custom(matrix: &[Vec<Complex>], targets: &[QubitIndex]) -> Result<()> {
+    if targets.is_empty() {
+        return Err(QuantumError::CircuitError(
+            "custom gate requires at least one target qubit".into(),
+        ));
+    }
+    for (i, q) in targets.iter().enumerate() {
+        if targets[..i].contains(q) {
+            return Err(QuantumError::CircuitError(format!(
+                "custom gate targets qubit {} more than once",
+                q
+            )));
+        }
+    }
+
+    let dim = 1usize.checked_shl(targets.len() as u32).unwrap_or(0);
+    if dim == 0 || matrix.len() != dim || matrix.iter().any(|row| row.len() != dim) {
+        return Err(QuantumError::CircuitError(format!(
+            "custom gate on {} qubits needs a {}x{} matrix",
+            targets.len(),
+            dim,
+            dim
+        )));
+    }
+
+    // (U^dagger U)[i][j] = sum_r conj(U[r][i]) * U[r][j]
+    for i in 0..dim {
+        for j in 0..dim {
+            let mut entry = Complex::ZERO;
+            for row in matrix {
+                entry += row[i].conj() * row[j];
+            }
+            let expected = if i == j { Complex::ONE } else { Complex::ZERO };
+            if (entry - expected).norm() > UNITARY_TOLERANCE {
+                return Err(QuantumError::CircuitError(
+                    "custom gate matrix is not unitary".into(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Expectation values and parameter-shift gradients for variational circuits
+//!
+//! Parameters are the rotation angles of a circuit in gate order (see
+//! [`QuantumCircuit::parameters`]). Each parametric gate is, up to global
+//! phase, `exp(-i theta G)` with a generator whose eigenvalues are `+-1/2`,
+//! so the exact gradient is given by the two-term parameter-shift rule:
+//!
+//! ```text
+//! d<H>/d(theta_k) = [ <H>(theta_k + pi/2) - <H>(theta_k - pi/2) ] / 2
+//! ```
+//!
+//! Unlike finite differences this needs no step size and, on real hardware,
+//! is estimated from the same kind of circuit runs as the energy itself.
+
+use std::f64::consts::FRAC_PI_2;
+
+use crate::circuit::QuantumCircuit;
+use crate::error::Result;
+use crate::simulator::Simulator;
+use crate::types::Hamiltonian;
+
+/// Expectation value of `observable` in the state prepared by `circuit`.
+///
+/// The circuit is simulated exactly; any measurements in it collapse the
+/// state at random, so variational circuits should not contain them.
+pub fn expectation(circuit: &QuantumCircuit, observable: &Hamiltonian) -> Result<f64> {
+    Ok(Simulator::run(circuit)?.expectation(observable))
+}
+
+/// Derivative of the expectation value with respect to parameter
+/// `param_index`, by the parameter-shift rule.
+///
+/// Costs two circuit simulations. Fails if `param_index` is out of range.
+pub fn expectation_gradient(
+    circuit: &QuantumCircuit,
+    observable: &Hamiltonian,
+    param_index: usize,
+) -> Result<f64> {
+    let mut shifted = circuit.clone();
+    let theta = circuit
+        .parameters()
+        .get(param_index)
+        .copied()
+        .unwrap_or_default();
+
+    shifted.set_parameter(param_index, theta + FRAC_PI_2)?;
+    let plus = expectation(&shifted, observable)?;
+    shifted.set_parameter(param_index, theta - FRAC_PI_2)?;
+    let minus = expectation(&shifted, observable)?;
+
+    Ok((plus - minus) / 2.0)
+}
+
+/// Gradient with respect to every parameter, in parameter order.
+pub fn expectation_gradients(
+    circuit: &QuantumCircuit,
+    observable: &Hamiltonian,
+) -> Result<Vec<f64>> {
+    (0..circuit.num_parameters())
+        .map(|k| expectation_gradient(circuit, observable, k))
+        .collect()
+}
@@ -1,7 +1,8 @@
 //! # ruqu-core -- Quantum Execution Intelligence Engine
 //!
 //! Pure Rust quantum simulation and execution engine for the ruVector stack.
-//! Supports state-vector (up to 32 qubits), stabilizer (millions), Clifford+T
+//! Supports state-vector (up to 32 qubits, or more for sparse states),
+//! stabilizer (millions), Clifford+T
 //! (moderate T-count), and tensor network backends with automatic routing,
 //! noise modeling, error mitigation, and cryptographic witness logging.
 //!
@@ -24,10 +25,12 @@ pub mod circuit;
 pub mod circuit_analyzer;
 pub mod error;
 pub mod gate;
+pub mod gradient;
 pub mod mixed_precision;
 pub mod optimizer;
 pub mod simd;
 pub mod simulator;
+pub mod sparse_state;
 pub mod stabilizer;
 pub mod state;
 pub mod tensor_network;
@@ -65,8 +68,12 @@ pub mod prelude {
     pub use crate::circuit::QuantumCircuit;
     pub use crate::error::{QuantumError, Result};
     pub use crate::gate::Gate;
-    pub use crate::qasm::to_qasm3;
-    pub use crate::simulator::{ShotResult, SimConfig, SimulationResult, Simulator};
+    pub use crate::qasm::{to_qasm2, to_qasm3};
+    pub use crate::simulator::{
+        ShotResult, SimConfig, SimulationResult, Simulator, SparseSimulationResult,
+        StabilizerResult, StateBackend,
+    };
+    pub use crate::sparse_state::SparseStateVector;
     pub use crate::state::QuantumState;
     pub use crate::types::*;
 }
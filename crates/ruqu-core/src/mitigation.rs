@@ -150,6 +150,21 @@ fn gate_dagger(gate: &Gate) -> Gate {
             ];
             Gate::Unitary1Q(*q, dag)
         }
+        Gate::Custom { matrix, targets } => {
+            let dag = (0..matrix.len())
+                .map(|i| matrix.iter().map(|row| row[i].conj()).collect())
+                .collect();
+            Gate::Custom {
+                matrix: dag,
+                targets: targets.clone(),
+            }
+        }
+
+        Gate::Conditional { bit, value, gate } => Gate::Conditional {
+            bit: *bit,
+            value: *value,
+            gate: Box::new(gate_dagger(gate)),
+        },
 
         // Non-unitary ops should not reach here, but handle gracefully.
         Gate::Measure(q) => Gate::Measure(*q),
@@ -1,12 +1,15 @@
 //! Gate-fusion optimiser
 //!
-//! Scans a circuit for runs of consecutive single-qubit gates acting on the
-//! same qubit and fuses them into a single `Unitary1Q` gate by multiplying
-//! their 2x2 matrices.
+//! Scans a circuit for runs of gates acting on exactly the same wires and
+//! fuses each run into one gate by multiplying their matrices: single-qubit
+//! runs become a `Unitary1Q`, two-qubit runs on the same pair a 4x4 `Custom`.
+//! Gates in a run need not be consecutive in the gate list, only on their
+//! wires; anything scheduled in between acts on disjoint qubits and commutes
+//! with the run.
 
 use crate::circuit::QuantumCircuit;
-use crate::gate::Gate;
-use crate::types::Complex;
+use crate::gate::{self, Gate};
+use crate::types::{Complex, QubitIndex};
 
 /// Multiply two 2x2 complex matrices: C = A * B.
 pub fn mat_mul_2x2(a: &[[Complex; 2]; 2], b: &[[Complex; 2]; 2]) -> [[Complex; 2]; 2] {
@@ -39,56 +42,167 @@ pub fn can_fuse(a: &Gate, b: &Gate) -> bool {
     }
 }
 
-/// Optimise a circuit by greedily fusing consecutive single-qubit gates
-/// that act on the same qubit.
-///
-/// Returns a new, potentially shorter circuit.
-pub fn fuse_gates(circuit: &QuantumCircuit) -> QuantumCircuit {
-    let mut result = QuantumCircuit::new(circuit.num_qubits());
-    let gates = circuit.gates();
-    let len = gates.len();
-    let mut i = 0;
+/// Multiply two 4x4 complex matrices: C = A * B.
+fn mat_mul_4x4(a: &[[Complex; 4]; 4], b: &[[Complex; 4]; 4]) -> [[Complex; 4]; 4] {
+    let mut c = [[Complex::ZERO; 4]; 4];
+    for (i, row) in c.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            for k in 0..4 {
+                *entry += a[i][k] * b[k][j];
+            }
+        }
+    }
+    c
+}
 
-    while i < len {
-        // Attempt to start a fusion run if the current gate is a fusable 1Q gate.
-        if !gates[i].is_non_unitary() {
-            if let Some(first_matrix) = gates[i].matrix_1q() {
-                let q = gates[i].qubits()[0];
-                let mut fused = first_matrix;
-                let mut count = 1usize;
+/// Relabel a two-qubit matrix from qubit order (a, b) to (b, a).
+fn swap_qubit_order(m: &[[Complex; 4]; 4]) -> [[Complex; 4]; 4] {
+    const P: [usize; 4] = [0, 2, 1, 3];
+    let mut out = [[Complex::ZERO; 4]; 4];
+    for i in 0..4 {
+        for j in 0..4 {
+            out[i][j] = m[P[i]][P[j]];
+        }
+    }
+    out
+}
 
-                // Greedily absorb subsequent fusable 1Q gates on the same qubit.
-                while i + count < len {
-                    let next = &gates[i + count];
-                    if next.is_non_unitary() {
-                        break;
-                    }
-                    if let Some(next_m) = next.matrix_1q() {
-                        let nq = next.qubits();
-                        if nq.len() == 1 && nq[0] == q {
-                            // next_m is applied *after* fused, so C = next_m * fused.
-                            fused = mat_mul_2x2(&next_m, &fused);
-                            count += 1;
-                            continue;
+/// Accumulated unitary of a fusion run.
+enum Block {
+    One(QubitIndex, [[Complex; 2]; 2]),
+    /// Matrix index is `targets[0]_bit * 2 + targets[1]_bit`.
+    Two([QubitIndex; 2], [[Complex; 4]; 4]),
+}
+
+impl Block {
+    /// The fusable unitary of a gate, if it acts on one or two qubits.
+    fn of(gate: &Gate) -> Option<Self> {
+        if gate.is_non_unitary() {
+            return None;
+        }
+        if let Some(m) = gate.matrix_1q() {
+            return Some(Block::One(gate.qubits()[0], m));
+        }
+        if let Some(m) = gate.matrix_2q() {
+            let q = gate.qubits();
+            return Some(Block::Two([q[0], q[1]], m));
+        }
+        match gate {
+            Gate::Custom { matrix, targets } if gate::validate_custom(matrix, targets).is_ok() => {
+                match targets[..] {
+                    [q] => Some(Block::One(
+                        q,
+                        [[matrix[0][0], matrix[0][1]], [matrix[1][0], matrix[1][1]]],
+                    )),
+                    [a, b] => {
+                        let mut m = [[Complex::ZERO; 4]; 4];
+                        for (row, src) in m.iter_mut().zip(matrix) {
+                            row.copy_from_slice(src);
                         }
+                        Some(Block::Two([a, b], m))
                     }
-                    break;
+                    _ => None,
                 }
+            }
+            _ => None,
+        }
+    }
 
-                if count > 1 {
-                    result.add_gate(Gate::Unitary1Q(q, fused));
+    /// Apply `next` after this block if both act on the same wires.
+    fn absorb(&mut self, next: &Block) -> bool {
+        match (self, next) {
+            (Block::One(q, m), Block::One(nq, n)) if q == nq => {
+                *m = mat_mul_2x2(n, m);
+                true
+            }
+            (Block::Two(targets, m), Block::Two(next_targets, n)) => {
+                if targets == next_targets {
+                    *m = mat_mul_4x4(n, m);
+                } else if *targets == [next_targets[1], next_targets[0]] {
+                    *m = mat_mul_4x4(&swap_qubit_order(n), m);
                 } else {
-                    result.add_gate(gates[i].clone());
+                    return false;
                 }
-                i += count;
-                continue;
+                true
             }
+            _ => false,
         }
+    }
 
-        // Non-fusable gate: pass through unchanged.
-        result.add_gate(gates[i].clone());
-        i += 1;
+    fn into_gate(self) -> Gate {
+        match self {
+            Block::One(q, m) => Gate::Unitary1Q(q, m),
+            Block::Two(targets, m) => Gate::Custom {
+                matrix: m.iter().map(|row| row.to_vec()).collect(),
+                targets: targets.to_vec(),
+            },
+        }
     }
+}
+
+/// A gate of the output circuit, possibly still absorbing later gates.
+struct Slot {
+    gate: Gate,
+    block: Option<Block>,
+    fused: usize,
+}
+
+/// Optimise a circuit by fusing runs of one- and two-qubit gates that act on
+/// the same wires.
+///
+/// A gate joins the run of the last gate on its wires only if that gate
+/// acts on exactly the same qubits, so no gate is moved past another that
+/// shares a qubit with it. Measurements, resets and barriers end every run
+/// they touch. The fused circuit implements the same unitary; runs of a
+/// single gate are passed through unchanged.
+///
+/// Returns a new, potentially shorter circuit.
+pub fn fuse_gates(circuit: &QuantumCircuit) -> QuantumCircuit {
+    let n = circuit.num_qubits() as usize;
+    let mut slots: Vec<Slot> = Vec::with_capacity(circuit.gate_count());
+    // Index of the slot holding the last gate on each qubit.
+    let mut last_on: Vec<Option<usize>> = vec![None; n];
+
+    for gate in circuit.gates() {
+        let qubits: Vec<QubitIndex> = match gate {
+            Gate::Barrier => (0..n as QubitIndex).collect(),
+            _ => gate.qubits(),
+        };
+        let block = Block::of(gate);
 
+        if let (Some(next), Some(&first)) = (&block, qubits.first()) {
+            let prev = last_on.get(first as usize).copied().flatten();
+            if let Some(j) = prev {
+                let same_slot = qubits
+                    .iter()
+                    .all(|&q| last_on.get(q as usize).copied().flatten() == Some(j));
+                let slot = &mut slots[j];
+                if same_slot && slot.block.as_mut().is_some_and(|b| b.absorb(next)) {
+                    slot.fused += 1;
+                    continue;
+                }
+            }
+        }
+
+        let j = slots.len();
+        for &q in &qubits {
+            if let Some(last) = last_on.get_mut(q as usize) {
+                *last = Some(j);
+            }
+        }
+        slots.push(Slot {
+            gate: gate.clone(),
+            block,
+            fused: 0,
+        });
+    }
+
+    let mut result = QuantumCircuit::new(circuit.num_qubits());
+    for slot in slots {
+        match slot.block {
+            Some(block) if slot.fused > 0 => result.add_gate(block.into_gate()),
+            _ => result.add_gate(slot.gate),
+        };
+    }
     result
 }
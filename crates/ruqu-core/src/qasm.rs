@@ -1,15 +1,18 @@
-//! OpenQASM 3.0 export bridge for `QuantumCircuit`.
+//! OpenQASM export bridge for `QuantumCircuit`.
 //!
 //! Converts a circuit into a valid OpenQASM 3.0 program string using the
-//! `stdgates.inc` naming conventions. Arbitrary single-qubit unitaries
-//! (`Unitary1Q`) are decomposed into ZYZ Euler angles and emitted as
-//! `U(theta, phi, lambda)` gates.
+//! `stdgates.inc` naming conventions, or into an OpenQASM 2.0 program using
+//! `qelib1.inc` for toolchains and hardware that only ingest 2.0. Arbitrary
+//! single-qubit unitaries (`Unitary1Q`) are decomposed into ZYZ Euler angles
+//! and emitted as `U(theta, phi, lambda)` (3.0) or `u3(theta, phi, lambda)`
+//! (2.0) gates.
 
 use std::fmt::Write;
 
 use crate::circuit::QuantumCircuit;
-use crate::gate::Gate;
-use crate::types::Complex;
+use crate::error::{QuantumError, Result};
+use crate::gate::{self, Gate};
+use crate::types::{Complex, QubitIndex};
 
 // ---------------------------------------------------------------------------
 // ZYZ Euler decomposition
@@ -158,6 +161,10 @@ fn fmt_angle(angle: f64) -> String {
 /// specification for qubit/bit declarations, measurements, resets, and
 /// barriers.
 ///
+/// Returns [`QuantumError::CircuitError`] if the circuit contains a malformed
+/// `Custom` gate or a multi-qubit `Custom` unitary, which `stdgates.inc`
+/// cannot express.
+///
 /// # Example
 ///
 /// ```
@@ -166,10 +173,10 @@ fn fmt_angle(angle: f64) -> String {
 ///
 /// let mut circuit = QuantumCircuit::new(2);
 /// circuit.h(0).cnot(0, 1);
-/// let qasm = to_qasm3(&circuit);
+/// let qasm = to_qasm3(&circuit).unwrap();
 /// assert!(qasm.starts_with("OPENQASM 3.0;"));
 /// ```
-pub fn to_qasm3(circuit: &QuantumCircuit) -> String {
+pub fn to_qasm3(circuit: &QuantumCircuit) -> Result<String> {
     let n = circuit.num_qubits();
 
     // Pre-allocate a reasonable buffer size
@@ -185,14 +192,14 @@ pub fn to_qasm3(circuit: &QuantumCircuit) -> String {
 
     // Gate body
     for gate in circuit.gates() {
-        emit_gate(&mut out, gate);
+        emit_gate(&mut out, gate)?;
     }
 
-    out
+    Ok(out)
 }
 
 /// Emit a single gate as one or more QASM lines.
-fn emit_gate(out: &mut String, gate: &Gate) {
+fn emit_gate(out: &mut String, gate: &Gate) -> Result<()> {
     match gate {
         // --- Single-qubit standard gates ---
         Gate::H(q) => {
@@ -271,7 +278,140 @@ fn emit_gate(out: &mut String, gate: &Gate) {
                 q,
             );
         }
+
+        // --- Custom unitary: single-qubit ones as U, larger ones have no
+        // stdgates.inc equivalent ---
+        Gate::Custom { matrix, targets } => {
+            gate::validate_custom(matrix, targets)?;
+            match targets[..] {
+                [q] => {
+                    let m = [[matrix[0][0], matrix[0][1]], [matrix[1][0], matrix[1][1]]];
+                    emit_gate(out, &Gate::Unitary1Q(q, m))?;
+                }
+                _ => {
+                    return Err(QuantumError::CircuitError(format!(
+                        "custom {}-qubit unitary on qubits {:?} has no OpenQASM 3.0 equivalent",
+                        targets.len(),
+                        targets
+                    )))
+                }
+            }
+        }
+
+        // --- Classically controlled gate: reads bit c[i], written by
+        // measuring q[i] ---
+        Gate::Conditional { bit, value, gate } => {
+            let mut inner = String::new();
+            emit_gate(&mut inner, gate)?;
+            let negate = if *value { "" } else { "!" };
+            for line in inner.lines() {
+                let _ = writeln!(out, "if ({}c[{}]) {}", negate, bit, line);
+            }
+        }
     }
+    Ok(())
+}
+
+/// Convert a `QuantumCircuit` into an OpenQASM 2.0 program string.
+///
+/// The output includes `qelib1.inc` and declares `qreg q[n]` and `creg c[n]`
+/// sized to the circuit; measurements write qubit `i` to bit `i`. Phase gates
+/// are emitted as `u1` and single-qubit unitaries as `u3`.
+///
+/// Returns [`QuantumError::CircuitError`] if the circuit contains a gate with
+/// no OpenQASM 2.0 equivalent, such as a multi-qubit `Custom` unitary.
+///
+/// # Example
+///
+/// ```
+/// use ruqu_core::circuit::QuantumCircuit;
+/// use ruqu_core::qasm::to_qasm2;
+///
+/// let mut circuit = QuantumCircuit::new(2);
+/// circuit.h(0).cnot(0, 1);
+/// let qasm = to_qasm2(&circuit).unwrap();
+/// assert!(qasm.starts_with("OPENQASM 2.0;"));
+/// ```
+pub fn to_qasm2(circuit: &QuantumCircuit) -> Result<String> {
+    let n = circuit.num_qubits();
+    let mut out = String::with_capacity(256 + circuit.gates().len() * 30);
+
+    out.push_str("OPENQASM 2.0;\n");
+    out.push_str("include \"qelib1.inc\";\n");
+    let _ = writeln!(out, "qreg q[{}];", n);
+    let _ = writeln!(out, "creg c[{}];", n);
+
+    for gate in circuit.gates() {
+        emit_gate_qasm2(&mut out, gate)?;
+    }
+
+    Ok(out)
+}
+
+/// Emit a single gate as an OpenQASM 2.0 line.
+fn emit_gate_qasm2(out: &mut String, gate: &Gate) -> Result<()> {
+    let _ = match gate {
+        Gate::H(q) => writeln!(out, "h q[{}];", q),
+        Gate::X(q) => writeln!(out, "x q[{}];", q),
+        Gate::Y(q) => writeln!(out, "y q[{}];", q),
+        Gate::Z(q) => writeln!(out, "z q[{}];", q),
+        Gate::S(q) => writeln!(out, "s q[{}];", q),
+        Gate::Sdg(q) => writeln!(out, "sdg q[{}];", q),
+        Gate::T(q) => writeln!(out, "t q[{}];", q),
+        Gate::Tdg(q) => writeln!(out, "tdg q[{}];", q),
+
+        Gate::Rx(q, angle) => writeln!(out, "rx({}) q[{}];", fmt_angle(*angle), q),
+        Gate::Ry(q, angle) => writeln!(out, "ry({}) q[{}];", fmt_angle(*angle), q),
+        Gate::Rz(q, angle) => writeln!(out, "rz({}) q[{}];", fmt_angle(*angle), q),
+        Gate::Phase(q, angle) => writeln!(out, "u1({}) q[{}];", fmt_angle(*angle), q),
+
+        Gate::CNOT(ctrl, tgt) => writeln!(out, "cx q[{}],q[{}];", ctrl, tgt),
+        Gate::CZ(q1, q2) => writeln!(out, "cz q[{}],q[{}];", q1, q2),
+        Gate::SWAP(q1, q2) => writeln!(out, "swap q[{}],q[{}];", q1, q2),
+        Gate::Rzz(q1, q2, angle) => {
+            writeln!(out, "rzz({}) q[{}],q[{}];", fmt_angle(*angle), q1, q2)
+        }
+
+        Gate::Measure(q) => writeln!(out, "measure q[{}] -> c[{}];", q, q),
+        Gate::Reset(q) => writeln!(out, "reset q[{}];", q),
+        Gate::Barrier => writeln!(out, "barrier q;"),
+
+        Gate::Unitary1Q(q, matrix) => emit_u3(out, *q, matrix),
+        // qelib1-era `if` only compares a whole register against an integer.
+        Gate::Conditional { bit, .. } => {
+            return Err(QuantumError::CircuitError(format!(
+                "condition on single bit c[{}] has no OpenQASM 2.0 equivalent",
+                bit
+            )))
+        }
+        Gate::Custom { matrix, targets } => match targets[..] {
+            [q] => {
+                gate::validate_custom(matrix, targets)?;
+                let m = [[matrix[0][0], matrix[0][1]], [matrix[1][0], matrix[1][1]]];
+                emit_u3(out, q, &m)
+            }
+            _ => {
+                return Err(QuantumError::CircuitError(format!(
+                    "custom {}-qubit unitary on qubits {:?} has no OpenQASM 2.0 equivalent",
+                    targets.len(),
+                    targets
+                )))
+            }
+        },
+    };
+    Ok(())
+}
+
+fn emit_u3(out: &mut String, q: QubitIndex, matrix: &[[Complex; 2]; 2]) -> std::fmt::Result {
+    let angles = decompose_zyz(matrix);
+    writeln!(
+        out,
+        "u3({}, {}, {}) q[{}];",
+        fmt_angle(angles.theta),
+        fmt_angle(angles.phi),
+        fmt_angle(angles.lambda),
+        q,
+    )
 }
 
 // ===========================================================================
@@ -282,6 +422,7 @@ fn emit_gate(out: &mut String, gate: &Gate) {
 mod tests {
     use super::*;
     use crate::circuit::QuantumCircuit;
+    use crate::error::QuantumError;
     use crate::gate::Gate;
     use crate::types::Complex;
     use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4, PI};
@@ -312,7 +453,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(2);
         circuit.h(0).cnot(0, 1);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         assert_valid_header(&qasm);
 
         let lines = gate_lines(&qasm);
@@ -330,7 +471,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(2);
         circuit.h(0).cnot(0, 1).measure(0).measure(1);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 4);
         assert_eq!(lines[0], "h q[0];");
@@ -346,7 +487,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(3);
         circuit.h(0).cnot(0, 1).cnot(0, 2);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         assert_valid_header(&qasm);
         assert!(qasm.contains("qubit[3] q;"));
         assert!(qasm.contains("bit[3] c;"));
@@ -366,7 +507,7 @@ mod tests {
             circuit.cnot(0, i);
         }
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         assert!(qasm.contains("qubit[5] q;"));
 
         let lines = gate_lines(&qasm);
@@ -384,7 +525,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.rx(0, PI).ry(0, FRAC_PI_2).rz(0, FRAC_PI_4);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 3);
 
@@ -409,7 +550,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.phase(0, PI / 3.0);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 1);
         assert!(lines[0].starts_with("p("));
@@ -424,7 +565,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(2);
         circuit.rzz(0, 1, PI / 6.0);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 1);
         assert!(lines[0].starts_with("rzz("));
@@ -448,7 +589,7 @@ mod tests {
         circuit.t(0);
         circuit.add_gate(Gate::Tdg(0));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 8);
         assert_eq!(lines[0], "h q[0];");
@@ -468,7 +609,7 @@ mod tests {
         circuit.cz(1, 2);
         circuit.swap(0, 2);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 3);
         assert_eq!(lines[0], "cx q[0], q[1];");
@@ -483,7 +624,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.reset(0);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 1);
         assert_eq!(lines[0], "reset q[0];");
@@ -494,7 +635,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(3);
         circuit.h(0).barrier().cnot(0, 1);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 3);
         assert_eq!(lines[0], "h q[0];");
@@ -507,7 +648,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(3);
         circuit.h(0).measure_all();
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 4);
         assert_eq!(lines[0], "h q[0];");
@@ -529,7 +670,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.add_gate(Gate::Unitary1Q(0, identity));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 1);
         assert!(lines[0].starts_with("U("));
@@ -564,7 +705,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.add_gate(Gate::Unitary1Q(0, hadamard));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 1);
         assert!(lines[0].starts_with("U("));
@@ -587,7 +728,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.add_gate(Gate::Unitary1Q(0, x_matrix));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         let (theta, phi, lambda) = extract_u_angles(&lines[0]);
         let reconstructed = reconstruct_zyz(theta, phi, lambda);
@@ -605,7 +746,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.add_gate(Gate::Unitary1Q(0, s_matrix));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         let (theta, phi, lambda) = extract_u_angles(&lines[0]);
 
@@ -634,7 +775,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.add_gate(Gate::Unitary1Q(0, arb_matrix));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         let (theta, phi, lambda) = extract_u_angles(&lines[0]);
         let reconstructed = reconstruct_zyz(theta, phi, lambda);
@@ -652,7 +793,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.add_gate(Gate::Unitary1Q(0, y_matrix));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         let (theta, phi, lambda) = extract_u_angles(&lines[0]);
         let reconstructed = reconstruct_zyz(theta, phi, lambda);
@@ -687,7 +828,7 @@ mod tests {
             .measure(2)
             .measure(3);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
 
         // Structural checks
         assert_valid_header(&qasm);
@@ -722,7 +863,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(2);
         circuit.h(0).cnot(0, 1).measure(0).measure(1);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
 
         // Number of QASM gate lines should match circuit gate count
@@ -736,7 +877,7 @@ mod tests {
     #[test]
     fn test_empty_circuit() {
         let circuit = QuantumCircuit::new(1);
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         assert_valid_header(&qasm);
         assert!(qasm.contains("qubit[1] q;"));
         assert!(qasm.contains("bit[1] c;"));
@@ -750,7 +891,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(4);
         circuit.h(0).cnot(0, 3).swap(1, 2).measure(3);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         // Extract all qubit references q[N] and verify N < 4
         for line in qasm.lines().skip(4) {
             let mut remaining = line;
@@ -775,7 +916,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.rx(0, -PI / 4.0);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 1);
 
@@ -788,7 +929,7 @@ mod tests {
         let mut circuit = QuantumCircuit::new(1);
         circuit.rx(0, 0.0);
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 1);
         assert!(lines[0].starts_with("rx("));
@@ -800,7 +941,7 @@ mod tests {
         circuit.add_gate(Gate::Sdg(0));
         circuit.add_gate(Gate::Tdg(0));
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         let lines = gate_lines(&qasm);
         assert_eq!(lines.len(), 2);
         assert_eq!(lines[0], "sdg q[0];");
@@ -821,7 +962,7 @@ mod tests {
         }
         circuit.measure_all();
 
-        let qasm = to_qasm3(&circuit);
+        let qasm = to_qasm3(&circuit).unwrap();
         assert_valid_header(&qasm);
         assert!(qasm.contains("qubit[4] q;"));
 
@@ -835,6 +976,127 @@ mod tests {
 
     // ----- Test helpers -----
 
+    // ----- OpenQASM 2.0 -----
+
+    #[test]
+    fn test_qasm2_bell_state() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).cnot(0, 1).measure_all();
+
+        let qasm = circuit.to_qasm().unwrap();
+        let lines: Vec<&str> = qasm.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "OPENQASM 2.0;",
+                "include \"qelib1.inc\";",
+                "qreg q[2];",
+                "creg c[2];",
+                "h q[0];",
+                "cx q[0],q[1];",
+                "measure q[0] -> c[0];",
+                "measure q[1] -> c[1];",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_qasm2_gate_mappings() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit
+            .rx(0, FRAC_PI_2)
+            .phase(1, FRAC_PI_4)
+            .rzz(1, 2, PI)
+            .swap(0, 2)
+            .reset(1)
+            .barrier()
+            .add_gate(Gate::Sdg(2));
+
+        let qasm = to_qasm2(&circuit).unwrap();
+        assert!(qasm.contains("qreg q[3];\ncreg c[3];\n"));
+        assert!(qasm.contains("rx(1.570796326794897) q[0];"));
+        assert!(qasm.contains("u1(0.785398163397448) q[1];"));
+        assert!(qasm.contains("rzz(3.141592653589793) q[1],q[2];"));
+        assert!(qasm.contains("swap q[0],q[2];"));
+        assert!(qasm.contains("reset q[1];"));
+        assert!(qasm.contains("barrier q;"));
+        assert!(qasm.contains("sdg q[2];"));
+    }
+
+    #[test]
+    fn test_qasm2_unitaries() {
+        let h = Complex::new(FRAC_1_SQRT_2, 0.0);
+        let mut circuit = QuantumCircuit::new(1);
+        circuit.add_gate(Gate::Unitary1Q(0, [[h, h], [h, -h]]));
+        circuit.add_gate(Gate::custom(vec![vec![h, h], vec![h, -h]], vec![0]).unwrap());
+
+        let qasm = to_qasm2(&circuit).unwrap();
+        let u3: Vec<&str> = qasm.lines().filter(|l| l.starts_with("u3(")).collect();
+        assert_eq!(u3.len(), 2);
+        assert_eq!(u3[0], u3[1]);
+        assert!((extract_angle(u3[0]) - FRAC_PI_2).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_qasm2_rejects_multi_qubit_custom() {
+        let one = Complex::ONE;
+        let zero = Complex::ZERO;
+        let swap = vec![
+            vec![one, zero, zero, zero],
+            vec![zero, zero, one, zero],
+            vec![zero, one, zero, zero],
+            vec![zero, zero, zero, one],
+        ];
+        let mut circuit = QuantumCircuit::new(2);
+        circuit
+            .h(0)
+            .add_gate(Gate::custom(swap, vec![0, 1]).unwrap());
+
+        match circuit.to_qasm() {
+            Err(QuantumError::CircuitError(msg)) => assert!(msg.contains("OpenQASM 2.0")),
+            other => panic!("expected CircuitError, got {:?}", other),
+        }
+        assert!(to_qasm3(&circuit).is_err());
+    }
+
+    #[test]
+    fn test_qasm3_rejects_fused_and_malformed_custom() {
+        // A fused two-qubit run becomes a 4x4 Custom gate.
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).cnot(0, 1).cz(1, 0);
+        let fused = crate::optimizer::fuse_gates(&circuit);
+        match to_qasm3(&fused) {
+            Err(QuantumError::CircuitError(msg)) => assert!(msg.contains("OpenQASM 3.0")),
+            other => panic!("expected CircuitError, got {:?}", other),
+        }
+
+        let mut malformed = QuantumCircuit::new(1);
+        malformed.add_gate(Gate::Custom {
+            matrix: vec![vec![Complex::ONE]],
+            targets: vec![0],
+        });
+        assert!(to_qasm3(&malformed).is_err());
+        assert!(to_qasm2(&malformed).is_err());
+    }
+
+    #[test]
+    fn test_conditional_gates() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit
+            .measure(0)
+            .c_if(0, true, Gate::X(2))
+            .c_if(1, false, Gate::CZ(1, 2));
+
+        let lines = gate_lines(&to_qasm3(&circuit).unwrap());
+        assert_eq!(lines[1], "if (c[0]) x q[2];");
+        assert_eq!(lines[2], "if (!c[1]) cz q[1], q[2];");
+
+        assert!(matches!(
+            to_qasm2(&circuit),
+            Err(QuantumError::CircuitError(_))
+        ));
+    }
+
     /// Extract a single angle from a gate line like `rx(1.234) q[0];`
     fn extract_angle(line: &str) -> f64 {
         let open = line.find('(').expect("No opening parenthesis");
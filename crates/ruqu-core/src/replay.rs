@@ -5,9 +5,10 @@
 /// be replayed bit-for-bit. Also provides [`StateCheckpoint`] for snapshotting
 /// the raw amplitude vector mid-simulation.
 use crate::circuit::QuantumCircuit;
+use crate::error::Result;
 use crate::gate::Gate;
-use crate::simulator::{SimConfig, Simulator};
-use crate::types::{Complex, NoiseModel};
+use crate::simulator::{SimConfig, Simulator, StateBackend};
+use crate::types::{Complex, MeasurementOutcome, NoiseChannel, NoiseModel, QubitIndex};
 
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -23,6 +24,8 @@ pub struct NoiseConfig {
     pub depolarizing_rate: f64,
     pub bit_flip_rate: f64,
     pub phase_flip_rate: f64,
+    pub gate_channels: Vec<NoiseChannel>,
+    pub qubit_channels: Vec<(QubitIndex, NoiseChannel)>,
 }
 
 impl NoiseConfig {
@@ -32,6 +35,8 @@ impl NoiseConfig {
             depolarizing_rate: m.depolarizing_rate,
             bit_flip_rate: m.bit_flip_rate,
             phase_flip_rate: m.phase_flip_rate,
+            gate_channels: m.gate_channels.clone(),
+            qubit_channels: m.qubit_channels.clone(),
         }
     }
 
@@ -41,6 +46,8 @@ impl NoiseConfig {
             depolarizing_rate: self.depolarizing_rate,
             bit_flip_rate: self.bit_flip_rate,
             phase_flip_rate: self.phase_flip_rate,
+            gate_channels: self.gate_channels.clone(),
+            qubit_channels: self.qubit_channels.clone(),
         }
     }
 }
@@ -113,7 +120,7 @@ impl ReplayEngine {
         ExecutionRecord {
             circuit_hash: Self::circuit_hash(circuit),
             seed,
-            backend: "state_vector".to_string(),
+            backend: config.backend.name().to_string(),
             noise_config,
             shots,
             software_version: self.version.clone(),
@@ -139,34 +146,34 @@ impl ReplayEngine {
             .as_ref()
             .map(NoiseConfig::to_noise_model);
 
+        let backend = StateBackend::from_name(&record.backend).unwrap_or_default();
         let config = SimConfig {
             seed: Some(record.seed),
             noise: noise.clone(),
             shots: None,
+            backend,
         };
 
         // Run twice with the same config and compare measurements.
-        let run_a = Simulator::run_with_config(circuit, &config);
+        let run_a = run_measurements(circuit, &config);
         let config_b = SimConfig {
             seed: Some(record.seed),
             noise,
             shots: None,
+            backend,
         };
-        let run_b = Simulator::run_with_config(circuit, &config_b);
+        let run_b = run_measurements(circuit, &config_b);
 
         match (run_a, run_b) {
             (Ok(a), Ok(b)) => {
-                if a.measurements.len() != b.measurements.len() {
+                if a.len() != b.len() {
                     return false;
                 }
-                a.measurements
-                    .iter()
-                    .zip(b.measurements.iter())
-                    .all(|(ma, mb)| {
-                        ma.qubit == mb.qubit
-                            && ma.result == mb.result
-                            && (ma.probability - mb.probability).abs() < 1e-12
-                    })
+                a.iter().zip(b.iter()).all(|(ma, mb)| {
+                    ma.qubit == mb.qubit
+                        && ma.result == mb.result
+                        && (ma.probability - mb.probability).abs() < 1e-12
+                })
             }
             _ => false,
         }
@@ -297,6 +304,18 @@ impl StateCheckpoint {
 // Internal helpers
 // ---------------------------------------------------------------------------
 
+/// Run `circuit` once on the backend selected in `config` and return its
+/// measurement outcomes.
+fn run_measurements(
+    circuit: &QuantumCircuit,
+    config: &SimConfig,
+) -> Result<Vec<MeasurementOutcome>> {
+    match config.backend {
+        StateBackend::Dense => Simulator::run_with_config(circuit, config).map(|r| r.measurements),
+        StateBackend::Sparse => Simulator::run_sparse(circuit, config).map(|r| r.measurements),
+    }
+}
+
 /// Hash a byte slice using `DefaultHasher` seeded deterministically.
 ///
 /// `DefaultHasher` does not expose a seed parameter so we prepend the seed
@@ -339,6 +358,20 @@ fn gate_components(gate: &Gate) -> (u8, Vec<u32>, Vec<f64>) {
             ];
             (19, vec![*q], params)
         }
+        Gate::Custom { matrix, targets } => {
+            // Row-major (re, im) pairs; the dimension follows from the targets.
+            let params = matrix.iter().flatten().flat_map(|c| [c.re, c.im]).collect();
+            (20, targets.clone(), params)
+        }
+        Gate::Conditional { bit, value, gate } => {
+            // Condition first, then the inner gate's own encoding.
+            let (inner, inner_qubits, inner_params) = gate_components(gate);
+            let mut qubits = vec![*bit];
+            qubits.extend(inner_qubits);
+            let mut params = vec![f64::from(u8::from(*value)), f64::from(inner)];
+            params.extend(inner_params);
+            (21, qubits, params)
+        }
     }
 }
 
@@ -350,7 +383,7 @@ fn gate_components(gate: &Gate) -> (u8, Vec<u32>, Vec<f64>) {
 mod tests {
     use super::*;
     use crate::circuit::QuantumCircuit;
-    use crate::simulator::SimConfig;
+    use crate::simulator::{SimConfig, StateBackend};
     use crate::types::Complex;
 
     /// Same seed produces identical measurement results.
@@ -363,6 +396,7 @@ mod tests {
             seed: Some(42),
             noise: None,
             shots: None,
+            backend: StateBackend::Dense,
         };
 
         let r1 = Simulator::run_with_config(&circuit, &config).unwrap();
@@ -391,11 +425,13 @@ mod tests {
                 seed: Some(100 + offset),
                 noise: None,
                 shots: None,
+                backend: StateBackend::Dense,
             };
             let c2 = SimConfig {
                 seed: Some(200 + offset),
                 noise: None,
                 shots: None,
+                backend: StateBackend::Dense,
             };
             let r1 = Simulator::run_with_config(&circuit, &c1).unwrap();
             let r2 = Simulator::run_with_config(&circuit, &c2).unwrap();
@@ -425,11 +461,32 @@ mod tests {
             seed: Some(99),
             noise: None,
             shots: None,
+            backend: StateBackend::Dense,
+        };
+
+        let engine = ReplayEngine::new();
+        let record = engine.record_execution(&circuit, &config, 1);
+
+        assert!(engine.replay(&record, &circuit));
+    }
+
+    /// Sparse-backend records replay on the sparse backend.
+    #[test]
+    fn record_replay_sparse_backend() {
+        let mut circuit = QuantumCircuit::new(40);
+        circuit.h(0).cnot(0, 39).measure(0).measure(39);
+
+        let config = SimConfig {
+            seed: Some(7),
+            noise: None,
+            shots: None,
+            backend: StateBackend::Sparse,
         };
 
         let engine = ReplayEngine::new();
         let record = engine.record_execution(&circuit, &config, 1);
 
+        assert_eq!(record.backend, "sparse_state_vector");
         assert!(engine.replay(&record, &circuit));
     }
 
@@ -496,6 +553,7 @@ mod tests {
             seed: Some(42),
             noise: None,
             shots: None,
+            backend: StateBackend::Dense,
         };
 
         let engine = ReplayEngine::new();
@@ -518,8 +576,10 @@ mod tests {
                 depolarizing_rate: 0.01,
                 bit_flip_rate: 0.005,
                 phase_flip_rate: 0.002,
+                ..Default::default()
             }),
             shots: None,
+            backend: StateBackend::Dense,
         };
 
         let engine = ReplayEngine::new();
@@ -1,15 +1,47 @@
 //! High-level simulator that executes quantum circuits
 
 use crate::circuit::QuantumCircuit;
-use crate::error::Result;
+use crate::error::{QuantumError, Result};
 use crate::gate::Gate;
+use crate::sparse_state::SparseStateVector;
+use crate::stabilizer::StabilizerState;
 use crate::state::QuantumState;
 use crate::types::*;
 
+use rand::rngs::StdRng;
 use rand::Rng;
 use std::collections::HashMap;
 use std::time::Instant;
 
+/// State representation used by the state-vector simulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StateBackend {
+    /// Dense vector of all 2^n amplitudes.
+    #[default]
+    Dense,
+    /// Map of nonzero amplitudes only; see [`SparseStateVector`].
+    Sparse,
+}
+
+impl StateBackend {
+    /// Identifier recorded in execution records.
+    pub fn name(&self) -> &'static str {
+        match self {
+            StateBackend::Dense => "state_vector",
+            StateBackend::Sparse => "sparse_state_vector",
+        }
+    }
+
+    /// Inverse of [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "state_vector" => Some(StateBackend::Dense),
+            "sparse_state_vector" => Some(StateBackend::Sparse),
+            _ => None,
+        }
+    }
+}
+
 /// Configuration for a simulation run.
 pub struct SimConfig {
     /// Deterministic seed. `None` uses OS entropy.
@@ -18,6 +50,10 @@ pub struct SimConfig {
     pub noise: Option<NoiseModel>,
     /// Number of repeated shots (`None` = single run returning state).
     pub shots: Option<u32>,
+    /// State representation. [`Simulator::run_with_config`] returns a dense
+    /// state and rejects `Sparse`; use [`Simulator::run_sparse`] for a
+    /// single sparse run. [`Simulator::run_shots_with_config`] honours both.
+    pub backend: StateBackend,
 }
 
 impl Default for SimConfig {
@@ -26,6 +62,7 @@ impl Default for SimConfig {
             seed: None,
             noise: None,
             shots: None,
+            backend: StateBackend::Dense,
         }
     }
 }
@@ -37,12 +74,35 @@ pub struct SimulationResult {
     pub metrics: SimulationMetrics,
 }
 
+impl SimulationResult {
+    /// Expectation value `<psi|H|psi>` of `observable` in the final state.
+    pub fn expectation(&self, observable: &Hamiltonian) -> f64 {
+        self.state.expectation_hamiltonian(observable)
+    }
+}
+
 /// Result of a multi-shot simulation (histogram of outcomes).
 pub struct ShotResult {
     pub counts: HashMap<Vec<bool>, usize>,
     pub metrics: SimulationMetrics,
 }
 
+/// Result of a sparse state-vector simulation run.
+pub struct SparseSimulationResult {
+    pub state: SparseStateVector,
+    pub measurements: Vec<MeasurementOutcome>,
+    pub metrics: SimulationMetrics,
+}
+
+/// Result of a stabilizer (Clifford-only) simulation run.
+pub struct StabilizerResult {
+    pub state: StabilizerState,
+    pub measurements: Vec<MeasurementOutcome>,
+    /// Per-qubit probability of measuring 1 in the final state.
+    pub probabilities: Vec<f64>,
+    pub metrics: SimulationMetrics,
+}
+
 /// Stateless simulator entry-point.
 pub struct Simulator;
 
@@ -53,10 +113,23 @@ impl Simulator {
     }
 
     /// Run a circuit once with explicit configuration.
+    ///
+    /// The result holds a dense state, so [`StateBackend::Sparse`] is
+    /// rejected with a [`QuantumError::CircuitError`]; call
+    /// [`Simulator::run_sparse`] to get the sparse state instead.
     pub fn run_with_config(
         circuit: &QuantumCircuit,
         config: &SimConfig,
     ) -> Result<SimulationResult> {
+        if config.backend == StateBackend::Sparse {
+            return Err(QuantumError::CircuitError(
+                "run_with_config returns a dense state; use Simulator::run_sparse \
+                 for the sparse backend"
+                    .into(),
+            ));
+        }
+
+        validate_noise(config.noise.as_ref(), circuit.num_qubits())?;
         let start = Instant::now();
 
         let mut state = match config.seed {
@@ -100,6 +173,126 @@ impl Simulator {
         })
     }
 
+    /// Run a circuit once on the sparse state-vector backend.
+    ///
+    /// Memory and time scale with the number of nonzero amplitudes rather
+    /// than 2^n, so circuits that stay sparse can use up to
+    /// [`MAX_SPARSE_QUBITS`](crate::sparse_state::MAX_SPARSE_QUBITS) qubits.
+    /// `config.backend` is ignored. Reported peak memory is the largest
+    /// number of stored amplitudes seen, in bytes.
+    pub fn run_sparse(
+        circuit: &QuantumCircuit,
+        config: &SimConfig,
+    ) -> Result<SparseSimulationResult> {
+        validate_noise(config.noise.as_ref(), circuit.num_qubits())?;
+        let start = Instant::now();
+
+        let mut state = match config.seed {
+            Some(seed) => SparseStateVector::new_with_seed(circuit.num_qubits(), seed)?,
+            None => SparseStateVector::new(circuit.num_qubits())?,
+        };
+
+        let mut measurements = Vec::new();
+        let mut gate_count: usize = 0;
+        let mut peak_nonzero = state.num_nonzero();
+
+        for gate in circuit.gates() {
+            let outcomes = state.apply_gate(gate)?;
+            measurements.extend(outcomes);
+            if !gate.is_non_unitary() {
+                gate_count += 1;
+            }
+            if let Some(ref noise) = config.noise {
+                apply_noise(&mut state, gate, noise);
+            }
+            peak_nonzero = peak_nonzero.max(state.num_nonzero());
+        }
+
+        let elapsed = start.elapsed();
+        let metrics = SimulationMetrics {
+            num_qubits: circuit.num_qubits(),
+            gate_count,
+            execution_time_ns: elapsed.as_nanos() as u64,
+            peak_memory_bytes: SparseStateVector::estimate_memory(peak_nonzero),
+            gates_per_second: if elapsed.as_secs_f64() > 0.0 {
+                gate_count as f64 / elapsed.as_secs_f64()
+            } else {
+                0.0
+            },
+            gates_fused: 0,
+        };
+
+        Ok(SparseSimulationResult {
+            state,
+            measurements,
+            metrics,
+        })
+    }
+
+    /// Run an all-Clifford circuit on the stabilizer (tableau) backend.
+    ///
+    /// Costs O(n^2) per gate instead of O(2^n), so circuits with hundreds
+    /// of qubits are practical. Reports per-qubit marginal probabilities,
+    /// since the full distribution has 2^n entries. Returns an error if the
+    /// circuit contains a non-Clifford gate; use [`Simulator::run`] for those.
+    pub fn run_stabilizer(circuit: &QuantumCircuit) -> Result<StabilizerResult> {
+        if let Some(gate) = circuit
+            .gates()
+            .iter()
+            .find(|g| !StabilizerState::is_clifford_gate(g) && !matches!(g, Gate::Reset(_)))
+        {
+            return Err(QuantumError::CircuitError(format!(
+                "gate {:?} is not a Clifford gate; use the state-vector simulator",
+                gate
+            )));
+        }
+
+        let start = Instant::now();
+        let n = circuit.num_qubits() as usize;
+        let mut state = StabilizerState::new(n)?;
+        let mut measurements = Vec::new();
+        let mut gate_count: usize = 0;
+
+        for gate in circuit.gates() {
+            if let Gate::Reset(q) = gate {
+                if state.measure(*q as usize)?.result {
+                    state.x_gate(*q as usize);
+                }
+                continue;
+            }
+            measurements.extend(state.apply_gate(gate)?);
+            if !gate.is_non_unitary() {
+                gate_count += 1;
+            }
+        }
+
+        let probabilities = (0..n)
+            .map(|q| state.probability_of_qubit(q))
+            .collect::<Result<Vec<_>>>()?;
+
+        let elapsed = start.elapsed();
+        let metrics = SimulationMetrics {
+            num_qubits: circuit.num_qubits(),
+            gate_count,
+            execution_time_ns: elapsed.as_nanos() as u64,
+            // 2n rows of (2n + 1) tableau bits, one byte each.
+            peak_memory_bytes: 2 * n * (2 * n + 1),
+            gates_per_second: if elapsed.as_secs_f64() > 0.0 {
+                gate_count as f64 / elapsed.as_secs_f64()
+            } else {
+                0.0
+            },
+            gates_fused: 0,
+        };
+
+        Ok(StabilizerResult {
+            state,
+            measurements,
+            probabilities,
+            metrics,
+        })
+    }
+
     /// Run a circuit `shots` times, collecting a histogram of measurement outcomes.
     ///
     /// If the circuit contains no `Measure` gates, all qubits are measured
@@ -108,11 +301,31 @@ impl Simulator {
         circuit: &QuantumCircuit,
         shots: u32,
         seed: Option<u64>,
+    ) -> Result<ShotResult> {
+        let config = SimConfig {
+            seed,
+            noise: None,
+            shots: Some(shots),
+            backend: StateBackend::Dense,
+        };
+        Self::run_shots_with_config(circuit, &config)
+    }
+
+    /// Run `config.shots` shots (default 1) with the configured noise model
+    /// and backend, collecting a histogram of measurement outcomes.
+    ///
+    /// Shot `i` is seeded with `seed + i` (`seed` defaulting to 42), so each
+    /// shot samples an independent trajectory of any noise channels.
+    pub fn run_shots_with_config(
+        circuit: &QuantumCircuit,
+        config: &SimConfig,
     ) -> Result<ShotResult> {
         let start = Instant::now();
+        let shots = config.shots.unwrap_or(1);
         let mut counts: HashMap<Vec<bool>, usize> = HashMap::new();
-        let base_seed = seed.unwrap_or(42);
+        let base_seed = config.seed.unwrap_or(42);
         let mut total_gates: usize = 0;
+        let mut peak_memory: usize = 0;
         let n_qubits = circuit.num_qubits();
 
         let has_measurements = circuit
@@ -121,24 +334,36 @@ impl Simulator {
             .any(|g| matches!(g, Gate::Measure(_)));
 
         for shot in 0..shots {
-            let config = SimConfig {
+            let shot_config = SimConfig {
                 seed: Some(base_seed.wrapping_add(shot as u64)),
-                noise: None,
+                noise: config.noise.clone(),
                 shots: None,
+                backend: config.backend,
             };
 
-            let mut result = Self::run_with_config(circuit, &config)?;
-            total_gates += result.metrics.gate_count;
-
             // Implicit measurement when the circuit has none.
-            if !has_measurements {
-                let outcomes = result.state.measure_all()?;
-                result.measurements.extend(outcomes);
-            }
+            let (measurements, metrics) = match config.backend {
+                StateBackend::Dense => {
+                    let mut result = Self::run_with_config(circuit, &shot_config)?;
+                    if !has_measurements {
+                        result.measurements.extend(result.state.measure_all()?);
+                    }
+                    (result.measurements, result.metrics)
+                }
+                StateBackend::Sparse => {
+                    let mut result = Self::run_sparse(circuit, &shot_config)?;
+                    if !has_measurements {
+                        result.measurements.extend(result.state.measure_all()?);
+                    }
+                    (result.measurements, result.metrics)
+                }
+            };
+            total_gates += metrics.gate_count;
+            peak_memory = peak_memory.max(metrics.peak_memory_bytes);
 
             // Build a bit-vector keyed by qubit index.
             let mut bits = vec![false; n_qubits as usize];
-            for m in &result.measurements {
+            for m in &measurements {
                 if (m.qubit as usize) < bits.len() {
                     bits[m.qubit as usize] = m.result;
                 }
@@ -151,7 +376,7 @@ impl Simulator {
             num_qubits: n_qubits,
             gate_count: total_gates,
             execution_time_ns: elapsed.as_nanos() as u64,
-            peak_memory_bytes: QuantumState::estimate_memory(n_qubits),
+            peak_memory_bytes: peak_memory,
             gates_per_second: if elapsed.as_secs_f64() > 0.0 {
                 total_gates as f64 / elapsed.as_secs_f64()
             } else {
@@ -168,20 +393,143 @@ impl Simulator {
 // Noise channel
 // ---------------------------------------------------------------------------
 
+/// State that the noise channel can act on.
+trait NoiseTarget {
+    fn rng_mut(&mut self) -> &mut StdRng;
+    fn apply_single_qubit_gate(&mut self, qubit: QubitIndex, matrix: &[[Complex; 2]; 2]);
+    /// Reduced density matrix of `qubit`.
+    fn qubit_density(&self, qubit: QubitIndex) -> [[Complex; 2]; 2];
+}
+
+impl NoiseTarget for QuantumState {
+    fn rng_mut(&mut self) -> &mut StdRng {
+        QuantumState::rng_mut(self)
+    }
+    fn apply_single_qubit_gate(&mut self, qubit: QubitIndex, matrix: &[[Complex; 2]; 2]) {
+        QuantumState::apply_single_qubit_gate(self, qubit, matrix)
+    }
+    fn qubit_density(&self, qubit: QubitIndex) -> [[Complex; 2]; 2] {
+        let amps = self.state_vector();
+        let mask = 1usize << qubit;
+        density_from(
+            amps.iter().enumerate().map(|(i, &a)| (i as u128, a)),
+            1u128 << qubit,
+            |i| amps[i as usize | mask],
+        )
+    }
+}
+
+impl NoiseTarget for SparseStateVector {
+    fn rng_mut(&mut self) -> &mut StdRng {
+        SparseStateVector::rng_mut(self)
+    }
+    fn apply_single_qubit_gate(&mut self, qubit: QubitIndex, matrix: &[[Complex; 2]; 2]) {
+        SparseStateVector::apply_single_qubit_gate(self, qubit, matrix)
+    }
+    fn qubit_density(&self, qubit: QubitIndex) -> [[Complex; 2]; 2] {
+        let mask = 1u128 << qubit;
+        density_from(self.iter(), mask, |i| self.amplitude(i | mask))
+    }
+}
+
+/// Accumulate a single-qubit reduced density matrix from `(index, amplitude)`
+/// pairs; `partner(i)` returns the amplitude of `i` with the qubit set.
+fn density_from(
+    amplitudes: impl Iterator<Item = (u128, Complex)>,
+    mask: u128,
+    partner: impl Fn(u128) -> Complex,
+) -> [[Complex; 2]; 2] {
+    let mut rho = [[Complex::ZERO; 2]; 2];
+    for (i, a) in amplitudes {
+        if i & mask == 0 {
+            rho[0][0].re += a.norm_sq();
+            rho[0][1] += a * partner(i).conj();
+        } else {
+            rho[1][1].re += a.norm_sq();
+        }
+    }
+    rho[1][0] = rho[0][1].conj();
+    rho
+}
+
+/// Reject noise models whose per-qubit channels name qubits outside the
+/// register.
+fn validate_noise(noise: Option<&NoiseModel>, num_qubits: u32) -> Result<()> {
+    if let Some(noise) = noise {
+        for &(qubit, _) in &noise.qubit_channels {
+            if qubit >= num_qubits {
+                return Err(QuantumError::InvalidQubitIndex {
+                    index: qubit,
+                    num_qubits,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply one stochastic trajectory step of a Kraus channel to `qubit`.
+///
+/// Operator `K` is chosen with probability `Tr(K rho K^dagger)`, where `rho`
+/// is the qubit's reduced density matrix, and applied rescaled so the
+/// state stays normalised.
+fn apply_channel(state: &mut impl NoiseTarget, qubit: QubitIndex, channel: &NoiseChannel) {
+    let kraus = channel.kraus_operators();
+    let rho = state.qubit_density(qubit);
+    let probs: Vec<f64> = kraus
+        .iter()
+        .map(|k| {
+            let mut p = 0.0;
+            for row in k {
+                for j in 0..2 {
+                    for l in 0..2 {
+                        p += (row[j] * rho[j][l] * row[l].conj()).re;
+                    }
+                }
+            }
+            p.max(0.0)
+        })
+        .collect();
+
+    let r: f64 = state.rng_mut().gen();
+    let mut acc = 0.0;
+    let chosen = probs
+        .iter()
+        .position(|&p| {
+            acc += p;
+            r < acc
+        })
+        // Rounding left r above the total: take the most likely operator.
+        .unwrap_or_else(|| {
+            (0..probs.len())
+                .max_by(|&a, &b| probs[a].total_cmp(&probs[b]))
+                .unwrap_or(0)
+        });
+
+    let p = probs[chosen];
+    if p > 0.0 {
+        let scale = 1.0 / p.sqrt();
+        let k = kraus[chosen];
+        let scaled = [
+            [k[0][0] * scale, k[0][1] * scale],
+            [k[1][0] * scale, k[1][1] * scale],
+        ];
+        state.apply_single_qubit_gate(qubit, &scaled);
+    }
+}
+
 /// Apply a stochastic noise channel to the state after a gate.
 ///
 /// For each qubit that the gate touches:
 ///   - with probability `depolarizing_rate`, apply a random Pauli (X, Y, or Z
 ///     each with probability 1/3);
 ///   - with probability `bit_flip_rate`, apply X;
-///   - with probability `phase_flip_rate`, apply Z.
-fn apply_noise(state: &mut QuantumState, gate: &Gate, noise: &NoiseModel) {
-    let qubits = gate.qubits();
-    if qubits.is_empty() {
-        return;
-    }
-
-    for &qubit in &qubits {
+///   - with probability `phase_flip_rate`, apply Z;
+///   - then each of `gate_channels` in turn.
+///
+/// Afterwards each of `qubit_channels` acts on its qubit.
+fn apply_noise(state: &mut impl NoiseTarget, gate: &Gate, noise: &NoiseModel) {
+    for &qubit in &gate.qubits() {
         // Depolarising channel
         if noise.depolarizing_rate > 0.0 {
             let r: f64 = state.rng_mut().gen();
@@ -217,5 +565,13 @@ fn apply_noise(state: &mut QuantumState, gate: &Gate, noise: &NoiseModel) {
                 state.apply_single_qubit_gate(qubit, &m);
             }
         }
+
+        for channel in &noise.gate_channels {
+            apply_channel(state, qubit, channel);
+        }
+    }
+
+    for (qubit, channel) in &noise.qubit_channels {
+        apply_channel(state, *qubit, channel);
     }
 }
@@ -0,0 +1,375 @@
+//! Sparse state-vector simulator
+//!
+//! Stores only the nonzero amplitudes of the state, keyed by basis-state
+//! index, so memory grows with the number of populated basis states rather
+//! than with 2^n. Circuits that stay sparse (GHZ preparation, permutations,
+//! product states with few superposed qubits) can use far more qubits than
+//! the dense [`QuantumState`]. A state that spreads over most of its basis
+//! is slower and larger here than in the dense form.
+
+use crate::error::{QuantumError, Result};
+use crate::gate::{validate_custom, Gate};
+use crate::state::QuantumState;
+use crate::types::*;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet};
+
+/// Maximum number of qubits addressable by a 128-bit basis index.
+pub const MAX_SPARSE_QUBITS: u32 = 128;
+
+/// Amplitudes with squared magnitude at or below this are dropped.
+const PRUNE_THRESHOLD: f64 = 1e-30;
+
+/// Quantum state stored as a map from basis-state index to amplitude.
+///
+/// Bit `q` of a basis index is the value of qubit `q`, matching the
+/// indexing of [`QuantumState`]. Absent indices have amplitude zero.
+pub struct SparseStateVector {
+    amplitudes: HashMap<u128, Complex>,
+    num_qubits: u32,
+    rng: StdRng,
+    measurement_record: Vec<MeasurementOutcome>,
+}
+
+// ---------------------------------------------------------------------------
+// Construction
+// ---------------------------------------------------------------------------
+
+impl SparseStateVector {
+    /// Create the |00...0> state for `num_qubits` qubits.
+    pub fn new(num_qubits: u32) -> Result<Self> {
+        Self::with_rng(num_qubits, StdRng::from_entropy())
+    }
+
+    /// Create the |00...0> state with a deterministic seed for reproducibility.
+    pub fn new_with_seed(num_qubits: u32, seed: u64) -> Result<Self> {
+        Self::with_rng(num_qubits, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(num_qubits: u32, rng: StdRng) -> Result<Self> {
+        if num_qubits == 0 {
+            return Err(QuantumError::CircuitError(
+                "cannot create quantum state with 0 qubits".into(),
+            ));
+        }
+        if num_qubits > MAX_SPARSE_QUBITS {
+            return Err(QuantumError::QubitLimitExceeded {
+                requested: num_qubits,
+                maximum: MAX_SPARSE_QUBITS,
+            });
+        }
+        Ok(Self {
+            amplitudes: HashMap::from([(0, Complex::ONE)]),
+            num_qubits,
+            rng,
+            measurement_record: Vec::new(),
+        })
+    }
+
+    // -------------------------------------------------------------------
+    // Accessors
+    // -------------------------------------------------------------------
+
+    pub fn num_qubits(&self) -> u32 {
+        self.num_qubits
+    }
+
+    /// Number of basis states with a stored (nonzero) amplitude.
+    pub fn num_nonzero(&self) -> usize {
+        self.amplitudes.len()
+    }
+
+    /// Amplitude of basis state `index` (zero if not stored).
+    pub fn amplitude(&self, index: u128) -> Complex {
+        self.amplitudes
+            .get(&index)
+            .copied()
+            .unwrap_or(Complex::ZERO)
+    }
+
+    /// Iterate over the stored `(basis index, amplitude)` pairs in
+    /// arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (u128, Complex)> + '_ {
+        self.amplitudes.iter().map(|(&i, &a)| (i, a))
+    }
+
+    /// Probability that `qubit` is in state |1>.
+    pub fn probability_of_qubit(&self, qubit: QubitIndex) -> f64 {
+        let qubit_bit = 1u128 << qubit;
+        self.amplitudes
+            .iter()
+            .filter(|(&i, _)| i & qubit_bit != 0)
+            .map(|(_, a)| a.norm_sq())
+            .sum()
+    }
+
+    pub fn measurement_record(&self) -> &[MeasurementOutcome] {
+        &self.measurement_record
+    }
+
+    /// Classical bit `bit`: the most recent measurement outcome of qubit
+    /// `bit`, or `false` if it has not been measured.
+    pub fn classical_bit(&self, bit: QubitIndex) -> bool {
+        self.measurement_record
+            .iter()
+            .rev()
+            .find(|m| m.qubit == bit)
+            .is_some_and(|m| m.result)
+    }
+
+    /// Estimated memory (in bytes) for a state with `num_nonzero` stored
+    /// amplitudes, ignoring hash table overhead.
+    pub fn estimate_memory(num_nonzero: usize) -> usize {
+        num_nonzero * std::mem::size_of::<(u128, Complex)>()
+    }
+
+    /// Provide mutable access to the internal RNG (used by noise model).
+    pub(crate) fn rng_mut(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// Expand into a dense [`QuantumState`], keeping the RNG and
+    /// measurement record. Fails beyond [`crate::state::MAX_QUBITS`].
+    pub fn into_dense(self) -> Result<QuantumState> {
+        if self.num_qubits > crate::state::MAX_QUBITS {
+            return Err(QuantumError::QubitLimitExceeded {
+                requested: self.num_qubits,
+                maximum: crate::state::MAX_QUBITS,
+            });
+        }
+        let mut dense = vec![Complex::ZERO; 1usize << self.num_qubits];
+        for (i, a) in self.amplitudes {
+            dense[i as usize] = a;
+        }
+        Ok(QuantumState::from_parts(
+            dense,
+            self.num_qubits,
+            self.rng,
+            self.measurement_record,
+        ))
+    }
+
+    // -------------------------------------------------------------------
+    // Gate dispatch
+    // -------------------------------------------------------------------
+
+    /// Apply a gate to the state, returning any measurement outcomes.
+    pub fn apply_gate(&mut self, gate: &Gate) -> Result<Vec<MeasurementOutcome>> {
+        for &q in gate.qubits().iter() {
+            self.validate_qubit(q)?;
+        }
+
+        match gate {
+            Gate::Barrier => Ok(vec![]),
+
+            Gate::Measure(q) => {
+                let outcome = self.measure(*q)?;
+                Ok(vec![outcome])
+            }
+
+            Gate::Reset(q) => {
+                self.reset_qubit(*q)?;
+                Ok(vec![])
+            }
+
+            Gate::CNOT(q1, q2) | Gate::CZ(q1, q2) | Gate::SWAP(q1, q2) | Gate::Rzz(q1, q2, _) => {
+                if q1 == q2 {
+                    return Err(QuantumError::CircuitError(format!(
+                        "two-qubit gate requires distinct qubits, got {} and {}",
+                        q1, q2
+                    )));
+                }
+                let matrix = gate.matrix_2q().unwrap();
+                self.apply_two_qubit_gate(*q1, *q2, &matrix);
+                Ok(vec![])
+            }
+
+            Gate::Custom { matrix, targets } => {
+                validate_custom(matrix, targets)?;
+                self.apply_custom_gate(targets, matrix);
+                Ok(vec![])
+            }
+
+            Gate::Conditional { bit, value, gate } => {
+                self.validate_qubit(*bit)?;
+                if self.classical_bit(*bit) == *value {
+                    self.apply_gate(gate)
+                } else {
+                    Ok(vec![])
+                }
+            }
+
+            other => {
+                if let Some(matrix) = other.matrix_1q() {
+                    let q = other.qubits()[0];
+                    self.apply_single_qubit_gate(q, &matrix);
+                    Ok(vec![])
+                } else {
+                    Err(QuantumError::CircuitError(format!(
+                        "unsupported gate: {:?}",
+                        other
+                    )))
+                }
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------
+    // Gate kernels
+    // -------------------------------------------------------------------
+
+    /// Apply a 2x2 unitary matrix to the given qubit.
+    ///
+    /// Each populated index belongs to the pair `(i, i | bit)` with the
+    /// qubit bit of `i` clear; every such pair is transformed once and
+    /// results that vanish are not stored.
+    pub fn apply_single_qubit_gate(&mut self, qubit: QubitIndex, matrix: &[[Complex; 2]; 2]) {
+        let bit = 1u128 << qubit;
+        let bases: HashSet<u128> = self.amplitudes.keys().map(|&i| i & !bit).collect();
+
+        let mut next = HashMap::with_capacity(self.amplitudes.len() * 2);
+        for base in bases {
+            let a = self.amplitude(base);
+            let b = self.amplitude(base | bit);
+            insert_nonzero(&mut next, base, matrix[0][0] * a + matrix[0][1] * b);
+            insert_nonzero(&mut next, base | bit, matrix[1][0] * a + matrix[1][1] * b);
+        }
+        self.amplitudes = next;
+    }
+
+    /// Apply a 4x4 unitary matrix to qubits `q1` and `q2`.
+    ///
+    /// Matrix row/column index = q1_bit * 2 + q2_bit.
+    pub fn apply_two_qubit_gate(
+        &mut self,
+        q1: QubitIndex,
+        q2: QubitIndex,
+        matrix: &[[Complex; 4]; 4],
+    ) {
+        let q1_bit = 1u128 << q1;
+        let q2_bit = 1u128 << q2;
+        let bases: HashSet<u128> = self
+            .amplitudes
+            .keys()
+            .map(|&i| i & !(q1_bit | q2_bit))
+            .collect();
+
+        let mut next = HashMap::with_capacity(self.amplitudes.len() * 2);
+        for base in bases {
+            let idxs = [base, base | q2_bit, base | q1_bit, base | q1_bit | q2_bit];
+            let vals = idxs.map(|i| self.amplitude(i));
+            for r in 0..4 {
+                let amp = matrix[r][0] * vals[0]
+                    + matrix[r][1] * vals[1]
+                    + matrix[r][2] * vals[2]
+                    + matrix[r][3] * vals[3];
+                insert_nonzero(&mut next, idxs[r], amp);
+            }
+        }
+        self.amplitudes = next;
+    }
+
+    /// Apply a 2^k x 2^k unitary to the `k` qubits in `targets`, with
+    /// the same index convention as [`QuantumState::apply_custom_gate`].
+    pub fn apply_custom_gate(&mut self, targets: &[QubitIndex], matrix: &[Vec<Complex>]) {
+        let k = targets.len();
+        let offsets: Vec<u128> = (0..1usize << k)
+            .map(|r| {
+                (0..k)
+                    .filter(|&j| (r >> (k - 1 - j)) & 1 == 1)
+                    .map(|j| 1u128 << targets[j])
+                    .sum()
+            })
+            .collect();
+        let mask: u128 = targets.iter().map(|&q| 1u128 << q).sum();
+        let bases: HashSet<u128> = self.amplitudes.keys().map(|&i| i & !mask).collect();
+
+        let mut next = HashMap::with_capacity(self.amplitudes.len() * 2);
+        for base in bases {
+            let vals: Vec<Complex> = offsets.iter().map(|&o| self.amplitude(base | o)).collect();
+            for (row, &o) in matrix.iter().zip(&offsets) {
+                let mut acc = Complex::ZERO;
+                for (m, v) in row.iter().zip(&vals) {
+                    acc += *m * *v;
+                }
+                insert_nonzero(&mut next, base | o, acc);
+            }
+        }
+        self.amplitudes = next;
+    }
+
+    // -------------------------------------------------------------------
+    // Measurement
+    // -------------------------------------------------------------------
+
+    /// Measure a single qubit projectively, collapsing and renormalising
+    /// the state.
+    pub fn measure(&mut self, qubit: QubitIndex) -> Result<MeasurementOutcome> {
+        self.validate_qubit(qubit)?;
+        let random: f64 = self.rng.gen();
+
+        let qubit_bit = 1u128 << qubit;
+        let p0: f64 = self
+            .amplitudes
+            .iter()
+            .filter(|(&i, _)| i & qubit_bit == 0)
+            .map(|(_, a)| a.norm_sq())
+            .sum();
+
+        let result = random >= p0; // true  => measured |1>
+        let prob = if result { 1.0 - p0 } else { p0 };
+        let norm_factor = if prob > 0.0 { 1.0 / prob.sqrt() } else { 0.0 };
+
+        self.amplitudes
+            .retain(|&i, _| (i & qubit_bit != 0) == result);
+        for a in self.amplitudes.values_mut() {
+            *a = *a * norm_factor;
+        }
+
+        let outcome = MeasurementOutcome {
+            qubit,
+            result,
+            probability: prob,
+        };
+        self.measurement_record.push(outcome.clone());
+        Ok(outcome)
+    }
+
+    /// Measure all qubits sequentially (qubit 0 first).
+    pub fn measure_all(&mut self) -> Result<Vec<MeasurementOutcome>> {
+        (0..self.num_qubits).map(|q| self.measure(q)).collect()
+    }
+
+    /// Reset a qubit to |0> by measuring and flipping on |1>.
+    pub fn reset_qubit(&mut self, qubit: QubitIndex) -> Result<()> {
+        let outcome = self.measure(qubit)?;
+        if outcome.result {
+            let x_matrix = Gate::X(qubit).matrix_1q().unwrap();
+            self.apply_single_qubit_gate(qubit, &x_matrix);
+        }
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------
+    // Internal helpers
+    // -------------------------------------------------------------------
+
+    fn validate_qubit(&self, qubit: QubitIndex) -> Result<()> {
+        if qubit >= self.num_qubits {
+            return Err(QuantumError::InvalidQubitIndex {
+                index: qubit,
+                num_qubits: self.num_qubits,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn insert_nonzero(amplitudes: &mut HashMap<u128, Complex>, index: u128, amp: Complex) {
+    if amp.norm_sq() > PRUNE_THRESHOLD {
+        amplitudes.insert(index, amp);
+    }
+}
@@ -424,6 +424,27 @@ impl StabilizerState {
         self.num_qubits
     }
 
+    /// Probability that measuring `qubit` in the Z basis yields 1.
+    ///
+    /// Stabilizer states only admit 0, 1/2 or 1: the outcome is uniformly
+    /// random if some stabilizer anticommutes with Z on the qubit, and
+    /// otherwise fixed by the tableau phases. The state is not disturbed.
+    pub fn probability_of_qubit(&self, qubit: usize) -> Result<f64> {
+        if qubit >= self.num_qubits {
+            return Err(QuantumError::InvalidQubitIndex {
+                index: qubit as u32,
+                num_qubits: self.num_qubits as u32,
+            });
+        }
+        let n = self.num_qubits;
+        if (n..(2 * n)).any(|i| self.x(i, qubit)) {
+            return Ok(0.5);
+        }
+        // Deterministic outcome: measuring a copy leaves `self` untouched.
+        let outcome = self.clone_with_seed(0)?.measure(qubit)?;
+        Ok(if outcome.result { 1.0 } else { 0.0 })
+    }
+
     /// Return the measurement record accumulated so far.
     pub fn measurement_record(&self) -> &[MeasurementOutcome] {
         &self.measurement_record
@@ -4,7 +4,7 @@
 //! gate application, measurement, collapse, expectation values, and fidelity.
 
 use crate::error::{QuantumError, Result};
-use crate::gate::Gate;
+use crate::gate::{validate_custom, Gate};
 use crate::types::*;
 
 use rand::rngs::StdRng;
@@ -95,6 +95,22 @@ impl QuantumState {
         })
     }
 
+    /// Assemble a state from already-validated parts.
+    pub(crate) fn from_parts(
+        amplitudes: Vec<Complex>,
+        num_qubits: u32,
+        rng: StdRng,
+        measurement_record: Vec<MeasurementOutcome>,
+    ) -> Self {
+        debug_assert_eq!(amplitudes.len(), 1usize << num_qubits);
+        Self {
+            amplitudes,
+            num_qubits,
+            rng,
+            measurement_record,
+        }
+    }
+
     // -------------------------------------------------------------------
     // Accessors
     // -------------------------------------------------------------------
@@ -140,6 +156,16 @@ impl QuantumState {
         &self.measurement_record
     }
 
+    /// Classical bit `bit`: the most recent measurement outcome of qubit
+    /// `bit`, or `false` if it has not been measured.
+    pub fn classical_bit(&self, bit: QubitIndex) -> bool {
+        self.measurement_record
+            .iter()
+            .rev()
+            .find(|m| m.qubit == bit)
+            .is_some_and(|m| m.result)
+    }
+
     /// Estimated memory (in bytes) needed for a state of `num_qubits` qubits.
     pub fn estimate_memory(num_qubits: u32) -> usize {
         (1usize << num_qubits) * std::mem::size_of::<Complex>()
@@ -187,6 +213,21 @@ impl QuantumState {
                 Ok(vec![])
             }
 
+            Gate::Custom { matrix, targets } => {
+                validate_custom(matrix, targets)?;
+                self.apply_custom_gate(targets, matrix);
+                Ok(vec![])
+            }
+
+            Gate::Conditional { bit, value, gate } => {
+                self.validate_qubit(*bit)?;
+                if self.classical_bit(*bit) == *value {
+                    self.apply_gate(gate)
+                } else {
+                    Ok(vec![])
+                }
+            }
+
             // Everything else must be a single-qubit unitary
             other => {
                 if let Some(matrix) = other.matrix_1q() {
@@ -275,6 +316,47 @@ impl QuantumState {
         }
     }
 
+    // -------------------------------------------------------------------
+    // k-qubit gate kernel
+    // -------------------------------------------------------------------
+
+    /// Apply a 2^k x 2^k unitary to the `k` qubits in `targets`.
+    ///
+    /// For every index with all target bits zero, the 2^k amplitudes that
+    /// differ only in the target bits are gathered, multiplied by the
+    /// matrix and scattered back. Bit `k - 1 - j` of a matrix index is
+    /// the value of `targets[j]`.
+    pub fn apply_custom_gate(&mut self, targets: &[QubitIndex], matrix: &[Vec<Complex>]) {
+        let k = targets.len();
+        let offsets: Vec<usize> = (0..1usize << k)
+            .map(|r| {
+                (0..k)
+                    .filter(|&j| (r >> (k - 1 - j)) & 1 == 1)
+                    .map(|j| 1usize << targets[j])
+                    .sum()
+            })
+            .collect();
+        let mask: usize = targets.iter().map(|&q| 1usize << q).sum();
+        let n = self.amplitudes.len();
+
+        let mut vals = vec![Complex::ZERO; offsets.len()];
+        for base in 0..n {
+            if base & mask != 0 {
+                continue;
+            }
+            for (v, &o) in vals.iter_mut().zip(&offsets) {
+                *v = self.amplitudes[base | o];
+            }
+            for (row, &o) in matrix.iter().zip(&offsets) {
+                let mut acc = Complex::ZERO;
+                for (m, v) in row.iter().zip(&vals) {
+                    acc += *m * *v;
+                }
+                self.amplitudes[base | o] = acc;
+            }
+        }
+    }
+
     // -------------------------------------------------------------------
     // Measurement
     // -------------------------------------------------------------------
@@ -287,7 +369,54 @@ impl QuantumState {
     /// 4. Renormalise.
     pub fn measure(&mut self, qubit: QubitIndex) -> Result<MeasurementOutcome> {
         self.validate_qubit(qubit)?;
+        let random: f64 = self.rng.gen();
+        Ok(self.collapse(qubit, random))
+    }
 
+    /// Measure a qubit in the eigenbasis of a Pauli operator.
+    ///
+    /// Rotates the qubit so the chosen basis maps onto Z, measures, and
+    /// rotates back, leaving the qubit in the observed eigenstate. Returns
+    /// `false` for the +1 eigenvalue and `true` for -1, matching the
+    /// `|0>`/`|1>` convention of [`measure`](Self::measure). `PauliOp::Z`
+    /// is an ordinary measurement; `PauliOp::I` has the single outcome +1
+    /// and leaves the state untouched.
+    ///
+    /// The outcome is sampled from `seed` rather than the state's own RNG,
+    /// so the same state and seed always give the same result.
+    pub fn measure_in_basis(
+        &mut self,
+        qubit: QubitIndex,
+        basis: PauliOp,
+        seed: u64,
+    ) -> Result<bool> {
+        self.validate_qubit(qubit)?;
+
+        // U maps the basis eigenstates onto |0>/|1>; U^dagger maps back.
+        let (to_z, from_z): (&[Gate], &[Gate]) = match basis {
+            PauliOp::I => return Ok(false),
+            PauliOp::Z => (&[], &[]),
+            PauliOp::X => (&[Gate::H(qubit)], &[Gate::H(qubit)]),
+            PauliOp::Y => (
+                &[Gate::Sdg(qubit), Gate::H(qubit)],
+                &[Gate::H(qubit), Gate::S(qubit)],
+            ),
+        };
+
+        for gate in to_z {
+            self.apply_single_qubit_gate(qubit, &gate.matrix_1q().unwrap());
+        }
+        let random: f64 = StdRng::seed_from_u64(seed).gen();
+        let outcome = self.collapse(qubit, random);
+        for gate in from_z {
+            self.apply_single_qubit_gate(qubit, &gate.matrix_1q().unwrap());
+        }
+        Ok(outcome.result)
+    }
+
+    /// Collapse `qubit` using a uniform sample `random` in [0, 1) and record
+    /// the outcome.
+    fn collapse(&mut self, qubit: QubitIndex, random: f64) -> MeasurementOutcome {
         let qubit_bit = 1usize << qubit;
         let n = self.amplitudes.len();
 
@@ -299,7 +428,6 @@ impl QuantumState {
             }
         }
 
-        let random: f64 = self.rng.gen();
         let result = random >= p0; // true  => measured |1>
         let prob = if result { 1.0 - p0 } else { p0 };
 
@@ -322,7 +450,7 @@ impl QuantumState {
             probability: prob,
         };
         self.measurement_record.push(outcome.clone());
-        Ok(outcome)
+        outcome
     }
 
     /// Measure all qubits sequentially (qubit 0 first).
@@ -434,6 +562,50 @@ impl QuantumState {
         inner.norm_sq()
     }
 
+    // -------------------------------------------------------------------
+    // Composition
+    // -------------------------------------------------------------------
+
+    /// Tensor product of two states.
+    ///
+    /// The qubits of `self` keep their indices and the qubits of `other`
+    /// follow them, so an n-qubit and an m-qubit state combine into an
+    /// (n+m)-qubit state whose amplitude at `i | (j << n)` is `self[i] *
+    /// other[j]`. Fails with `QubitLimitExceeded` beyond [`MAX_QUBITS`].
+    pub fn tensor(&self, other: &QuantumState) -> Result<QuantumState> {
+        let num_qubits = self.num_qubits + other.num_qubits;
+        if num_qubits > MAX_QUBITS {
+            return Err(QuantumError::QubitLimitExceeded {
+                requested: num_qubits,
+                maximum: MAX_QUBITS,
+            });
+        }
+        let mut amplitudes = Vec::with_capacity(self.amplitudes.len() * other.amplitudes.len());
+        for b in &other.amplitudes {
+            amplitudes.extend(self.amplitudes.iter().map(|a| *a * *b));
+        }
+        Self::from_amplitudes(amplitudes, num_qubits)
+    }
+
+    /// Tensor product of `states` in order; the first state holds qubit 0.
+    pub fn from_product(states: &[QuantumState]) -> Result<QuantumState> {
+        let (first, rest) = states.split_first().ok_or_else(|| {
+            QuantumError::CircuitError("cannot form product of zero states".into())
+        })?;
+        let total: u32 = states.iter().map(|s| s.num_qubits).sum();
+        if total > MAX_QUBITS {
+            return Err(QuantumError::QubitLimitExceeded {
+                requested: total,
+                maximum: MAX_QUBITS,
+            });
+        }
+        let mut product = Self::from_amplitudes(first.amplitudes.clone(), first.num_qubits)?;
+        for state in rest {
+            product = product.tensor(state)?;
+        }
+        Ok(product)
+    }
+
     // -------------------------------------------------------------------
     // Internal helpers
     // -------------------------------------------------------------------
@@ -84,6 +84,18 @@ fn decompose(circuit: &QuantumCircuit, basis: BasisGateSet) -> QuantumCircuit {
     result
 }
 
+/// Put every gate of a decomposition under the same classical condition.
+fn conditioned(bit: u32, value: bool, gates: Vec<Gate>) -> Vec<Gate> {
+    gates
+        .into_iter()
+        .map(|gate| Gate::Conditional {
+            bit,
+            value,
+            gate: Box::new(gate),
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // IBM Eagle decomposition: basis = {CNOT, Rz, SX (Rx(pi/2)), X}
 // ---------------------------------------------------------------------------
@@ -191,6 +203,8 @@ pub fn decompose_to_ibm(gate: &Gate) -> Vec<Gate> {
         // For simplicity, keep as-is since custom unitaries are an edge case
         // and the user can re-synthesize them.
         Gate::Unitary1Q(q, m) => vec![Gate::Unitary1Q(*q, *m)],
+        Gate::Custom { .. } => vec![gate.clone()],
+        Gate::Conditional { bit, value, gate } => conditioned(*bit, *value, decompose_to_ibm(gate)),
     }
 }
 
@@ -260,6 +274,10 @@ pub fn decompose_to_rigetti(gate: &Gate) -> Vec<Gate> {
         Gate::Reset(q) => vec![Gate::Reset(*q)],
         Gate::Barrier => vec![Gate::Barrier],
         Gate::Unitary1Q(q, m) => vec![Gate::Unitary1Q(*q, *m)],
+        Gate::Custom { .. } => vec![gate.clone()],
+        Gate::Conditional { bit, value, gate } => {
+            conditioned(*bit, *value, decompose_to_rigetti(gate))
+        }
     }
 }
 
@@ -361,6 +379,10 @@ pub fn decompose_to_ionq(gate: &Gate) -> Vec<Gate> {
         Gate::Reset(q) => vec![Gate::Reset(*q)],
         Gate::Barrier => vec![Gate::Barrier],
         Gate::Unitary1Q(q, m) => vec![Gate::Unitary1Q(*q, *m)],
+        Gate::Custom { .. } => vec![gate.clone()],
+        Gate::Conditional { bit, value, gate } => {
+            conditioned(*bit, *value, decompose_to_ionq(gate))
+        }
     }
 }
 
@@ -538,6 +560,16 @@ fn remap_gate(gate: &Gate, log2phys: &[u32]) -> Gate {
         Gate::Reset(q) => Gate::Reset(log2phys[*q as usize]),
         Gate::Barrier => Gate::Barrier,
         Gate::Unitary1Q(q, m) => Gate::Unitary1Q(log2phys[*q as usize], *m),
+        Gate::Custom { matrix, targets } => Gate::Custom {
+            matrix: matrix.clone(),
+            targets: targets.iter().map(|q| log2phys[*q as usize]).collect(),
+        },
+        // Measurements are remapped too, so the bit follows its qubit.
+        Gate::Conditional { bit, value, gate } => Gate::Conditional {
+            bit: log2phys[*bit as usize],
+            value: *value,
+            gate: Box::new(remap_gate(gate, log2phys)),
+        },
     }
 }
 
@@ -250,6 +250,11 @@ pub struct NoiseModel {
     pub depolarizing_rate: f64,
     pub bit_flip_rate: f64,
     pub phase_flip_rate: f64,
+    /// Kraus channels applied, in order, to every qubit a gate acts on.
+    pub gate_channels: Vec<NoiseChannel>,
+    /// Kraus channels applied to one qubit after every gate, whether or not
+    /// the gate acts on it (e.g. T1 decay of idle qubits).
+    pub qubit_channels: Vec<(QubitIndex, NoiseChannel)>,
 }
 
 impl Default for NoiseModel {
@@ -258,6 +263,42 @@ impl Default for NoiseModel {
             depolarizing_rate: 0.0,
             bit_flip_rate: 0.0,
             phase_flip_rate: 0.0,
+            gate_channels: Vec::new(),
+            qubit_channels: Vec::new(),
+        }
+    }
+}
+
+/// Single-qubit noise channel given by Kraus operators.
+///
+/// The state-vector simulator unravels channels stochastically: each
+/// application picks one Kraus operator with its Born probability, so a
+/// single run is one quantum trajectory and shot statistics reproduce the
+/// channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseChannel {
+    /// Energy relaxation (T1): `|1>` decays to `|0>` with probability `gamma`.
+    AmplitudeDamping { gamma: f64 },
+    /// `rho -> (1 - p) rho + p I/2`: identity at `p = 0`, fully mixing at
+    /// `p = 1`.
+    ///
+    /// Note that [`NoiseModel::depolarizing_rate`] instead applies a random
+    /// X, Y or Z with the given probability.
+    Depolarizing { p: f64 },
+}
+
+impl NoiseChannel {
+    /// Kraus operators of the channel, with parameters clamped to `[0, 1]`.
+    pub fn kraus_operators(&self) -> Vec<[[Complex; 2]; 2]> {
+        match *self {
+            NoiseChannel::AmplitudeDamping { gamma } => {
+                crate::noise::amplitude_damping_kraus(gamma.clamp(0.0, 1.0))
+            }
+            // Mixing with I/2 is a uniform Pauli twirl: each of I, X, Y, Z
+            // with weight p/4.
+            NoiseChannel::Depolarizing { p } => {
+                crate::noise::depolarizing_kraus(0.75 * p.clamp(0.0, 1.0))
+            }
         }
     }
 }
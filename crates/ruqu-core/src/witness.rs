@@ -5,7 +5,7 @@
 /// tampering with any field in any entry is detectable by
 /// [`WitnessLog::verify_chain`].
 use crate::replay::ExecutionRecord;
-use crate::types::MeasurementOutcome;
+use crate::types::{MeasurementOutcome, NoiseChannel};
 
 use std::collections::hash_map::DefaultHasher;
 use std::fmt;
@@ -240,10 +240,31 @@ impl WitnessLog {
                         nc.depolarizing_rate
                     ));
                     buf.push_str(&format!("      \"bit_flip_rate\": {},\n", nc.bit_flip_rate));
+                    let has_channels =
+                        !nc.gate_channels.is_empty() || !nc.qubit_channels.is_empty();
                     buf.push_str(&format!(
-                        "      \"phase_flip_rate\": {}\n",
-                        nc.phase_flip_rate
+                        "      \"phase_flip_rate\": {}{}\n",
+                        nc.phase_flip_rate,
+                        if has_channels { "," } else { "" }
                     ));
+                    if has_channels {
+                        let gate: Vec<String> = nc.gate_channels.iter().map(channel_json).collect();
+                        let qubit: Vec<String> = nc
+                            .qubit_channels
+                            .iter()
+                            .map(|(q, ch)| {
+                                format!("{{\"qubit\": {}, \"channel\": {}}}", q, channel_json(ch))
+                            })
+                            .collect();
+                        buf.push_str(&format!(
+                            "      \"gate_channels\": [{}],\n",
+                            gate.join(", ")
+                        ));
+                        buf.push_str(&format!(
+                            "      \"qubit_channels\": [{}]\n",
+                            qubit.join(", ")
+                        ));
+                    }
                     buf.push_str("    },\n");
                 }
                 None => {
@@ -323,6 +344,19 @@ fn execution_to_bytes(exec: &ExecutionRecord) -> Vec<u8> {
         buf.extend_from_slice(&nc.depolarizing_rate.to_le_bytes());
         buf.extend_from_slice(&nc.bit_flip_rate.to_le_bytes());
         buf.extend_from_slice(&nc.phase_flip_rate.to_le_bytes());
+        // Only appended when present so records without channels keep
+        // their original encoding.
+        if !nc.gate_channels.is_empty() || !nc.qubit_channels.is_empty() {
+            buf.extend_from_slice(&(nc.gate_channels.len() as u64).to_le_bytes());
+            for ch in &nc.gate_channels {
+                channel_to_bytes(&mut buf, ch);
+            }
+            buf.extend_from_slice(&(nc.qubit_channels.len() as u64).to_le_bytes());
+            for (q, ch) in &nc.qubit_channels {
+                buf.extend_from_slice(&q.to_le_bytes());
+                channel_to_bytes(&mut buf, ch);
+            }
+        }
     } else {
         buf.push(0);
     }
@@ -330,6 +364,30 @@ fn execution_to_bytes(exec: &ExecutionRecord) -> Vec<u8> {
     buf
 }
 
+fn channel_to_bytes(buf: &mut Vec<u8>, channel: &NoiseChannel) {
+    match *channel {
+        NoiseChannel::AmplitudeDamping { gamma } => {
+            buf.push(0);
+            buf.extend_from_slice(&gamma.to_le_bytes());
+        }
+        NoiseChannel::Depolarizing { p } => {
+            buf.push(1);
+            buf.extend_from_slice(&p.to_le_bytes());
+        }
+    }
+}
+
+fn channel_json(channel: &NoiseChannel) -> String {
+    match *channel {
+        NoiseChannel::AmplitudeDamping { gamma } => {
+            format!("{{\"type\": \"amplitude_damping\", \"gamma\": {}}}", gamma)
+        }
+        NoiseChannel::Depolarizing { p } => {
+            format!("{{\"type\": \"depolarizing\", \"p\": {}}}", p)
+        }
+    }
+}
+
 /// Compute the self-hash of a witness entry.
 ///
 /// `H(sequence || prev_hash || execution_bytes || result_hash)`
@@ -565,6 +623,8 @@ mod tests {
                 depolarizing_rate: 0.01,
                 bit_flip_rate: 0.005,
                 phase_flip_rate: 0.002,
+                gate_channels: Vec::new(),
+                qubit_channels: Vec::new(),
             }),
             shots: 100,
             software_version: "test".to_string(),
@@ -692,7 +752,7 @@ mod tests {
     #[test]
     fn integration_with_replay_engine() {
         use crate::circuit::QuantumCircuit;
-        use crate::simulator::{SimConfig, Simulator};
+        use crate::simulator::{SimConfig, Simulator, StateBackend};
 
         let mut circuit = QuantumCircuit::new(2);
         circuit.h(0).cnot(0, 1).measure(0).measure(1);
@@ -701,6 +761,7 @@ mod tests {
             seed: Some(42),
             noise: None,
             shots: None,
+            backend: StateBackend::Dense,
         };
 
         let engine = ReplayEngine::new();
@@ -661,3 +661,41 @@ fn test_two_qubit_gate_indices() {
     assert_eq!(qubits2[0], 5);
     assert_eq!(qubits2[1], 3);
 }
+
+// ---------------------------------------------------------------------------
+// Custom gates
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_custom_gate_validation() {
+    let h = std::f64::consts::FRAC_1_SQRT_2;
+    let hadamard = vec![vec![c(h, 0.0), c(h, 0.0)], vec![c(h, 0.0), c(-h, 0.0)]];
+    let gate = Gate::custom(hadamard.clone(), vec![4]).unwrap();
+    assert_eq!(gate.qubits(), vec![4]);
+
+    // Not unitary: columns are not orthogonal.
+    let skewed = vec![
+        vec![c(1.0, 0.0), c(1.0, 0.0)],
+        vec![c(0.0, 0.0), c(1.0, 0.0)],
+    ];
+    assert!(Gate::custom(skewed, vec![0]).is_err());
+    // Scaled unitary is not unitary either.
+    let scaled = vec![
+        vec![c(2.0, 0.0), c(0.0, 0.0)],
+        vec![c(0.0, 0.0), c(2.0, 0.0)],
+    ];
+    assert!(Gate::custom(scaled, vec![0]).is_err());
+
+    // Shape must be 2^k x 2^k and targets distinct.
+    assert!(Gate::custom(hadamard.clone(), vec![0, 1]).is_err());
+    assert!(Gate::custom(hadamard, vec![]).is_err());
+    let identity4: Vec<Vec<Complex>> = (0..4)
+        .map(|i| {
+            (0..4)
+                .map(|j| c(if i == j { 1.0 } else { 0.0 }, 0.0))
+                .collect()
+        })
+        .collect();
+    assert!(Gate::custom(identity4.clone(), vec![2, 2]).is_err());
+    assert!(Gate::custom(identity4, vec![2, 3]).is_ok());
+}
@@ -218,6 +218,7 @@ fn test_seeded_reproducibility() {
         seed: Some(42),
         noise: None,
         shots: None,
+        backend: StateBackend::Dense,
     };
 
     let r1 = Simulator::run_with_config(&circuit, &config).unwrap();
@@ -240,11 +241,13 @@ fn test_different_seeds_may_differ() {
         seed: Some(42),
         noise: None,
         shots: None,
+        backend: StateBackend::Dense,
     };
     let c2 = SimConfig {
         seed: Some(99),
         noise: None,
         shots: None,
+        backend: StateBackend::Dense,
     };
 
     let _r1 = Simulator::run_with_config(&circuit, &c1).unwrap();
@@ -261,6 +264,7 @@ fn test_config_no_seed() {
         seed: None,
         noise: None,
         shots: None,
+        backend: StateBackend::Dense,
     };
 
     let result = Simulator::run_with_config(&circuit, &config).unwrap();
@@ -444,3 +448,744 @@ fn test_many_gate_circuit() {
     let total: f64 = result.state.probabilities().iter().sum();
     assert!(approx_eq(total, 1.0));
 }
+
+// ---------------------------------------------------------------------------
+// Circuit equivalence
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_equivalent_hh_is_identity() {
+    let mut hh = QuantumCircuit::new(2);
+    hh.h(0).h(0);
+    let identity = QuantumCircuit::new(2);
+    assert!(hh.is_equivalent(&identity, 1e-9));
+}
+
+#[test]
+fn test_equivalent_up_to_global_phase() {
+    // Rz(theta) and Phase(theta) differ only by a global phase.
+    let mut rz = QuantumCircuit::new(1);
+    rz.rz(0, 0.7);
+    let mut phase = QuantumCircuit::new(1);
+    phase.phase(0, 0.7);
+    assert!(rz.is_equivalent(&phase, 1e-9));
+}
+
+#[test]
+fn test_equivalent_after_fusion() {
+    let mut circuit = QuantumCircuit::new(3);
+    circuit
+        .h(0)
+        .t(0)
+        .rx(0, 0.3)
+        .cnot(0, 1)
+        .s(1)
+        .ry(1, 1.1)
+        .rz(1, -0.4)
+        .cz(1, 2)
+        .h(2)
+        .x(2);
+    let optimized = ruqu_core::optimizer::fuse_gates(&circuit);
+    assert!(optimized.gate_count() < circuit.gate_count());
+    assert!(optimized.is_equivalent(&circuit, 1e-9));
+}
+
+#[test]
+fn test_fusion_preserves_final_state() {
+    let mut circuit = QuantumCircuit::new(3);
+    circuit
+        .h(0)
+        .ry(2, 0.3)
+        .t(0)
+        .rz(2, -1.2)
+        .cnot(0, 1)
+        .x(2)
+        .cz(1, 0)
+        .rzz(0, 1, 0.5)
+        .h(0)
+        .cnot(1, 2)
+        .s(0);
+    let fused = ruqu_core::optimizer::fuse_gates(&circuit);
+
+    // Runs on q0 and q2 fuse across gates on other wires; the three
+    // gates on (0, 1) fuse regardless of argument order; the trailing
+    // h/s on q0 cannot join the first q0 run across the two-qubit block.
+    assert_eq!(fused.gate_count(), 5);
+    assert!(matches!(fused.gates()[0], Gate::Unitary1Q(0, _)));
+    assert!(matches!(fused.gates()[1], Gate::Unitary1Q(2, _)));
+    assert!(matches!(&fused.gates()[2], Gate::Custom { targets, .. } if targets == &[0, 1]));
+    assert!(matches!(fused.gates()[3], Gate::Unitary1Q(0, _)));
+    assert!(matches!(fused.gates()[4], Gate::CNOT(1, 2)));
+
+    let original = Simulator::run(&circuit).unwrap().state;
+    let fused = Simulator::run(&fused).unwrap().state;
+    for (a, b) in original.state_vector().iter().zip(fused.state_vector()) {
+        assert!(
+            approx_eq(a.re, b.re) && approx_eq(a.im, b.im),
+            "{} vs {}",
+            a,
+            b
+        );
+    }
+}
+
+#[test]
+fn test_fusion_stops_at_non_unitary_boundaries() {
+    let mut circuit = QuantumCircuit::new(2);
+    circuit
+        .h(0)
+        .measure(0)
+        .h(0)
+        .barrier()
+        .x(0)
+        .reset(1)
+        .cnot(0, 1);
+    let fused = ruqu_core::optimizer::fuse_gates(&circuit);
+    assert_eq!(
+        format!("{:?}", fused.gates()),
+        format!("{:?}", circuit.gates())
+    );
+
+    // A gate on an overlapping but different pair ends the run.
+    let mut circuit = QuantumCircuit::new(3);
+    circuit.cnot(0, 1).cnot(1, 2).cnot(0, 1);
+    let fused = ruqu_core::optimizer::fuse_gates(&circuit);
+    assert_eq!(fused.gate_count(), 3);
+}
+
+#[test]
+fn test_inequivalent_circuits() {
+    // Z and identity agree on every basis state up to phase; only
+    // superposition inputs tell them apart.
+    let mut z = QuantumCircuit::new(1);
+    z.z(0);
+    assert!(!z.is_equivalent(&QuantumCircuit::new(1), 1e-6));
+
+    let mut a = QuantumCircuit::new(2);
+    a.h(0).cnot(0, 1);
+    let mut b = QuantumCircuit::new(2);
+    b.h(1).cnot(1, 0).x(0);
+    assert!(!a.is_equivalent(&b, 1e-6));
+
+    assert!(!a.is_equivalent(&QuantumCircuit::new(3), 1e-6));
+
+    let mut measured = a.clone();
+    measured.measure(0);
+    assert!(!measured.is_equivalent(&a, 1e-6));
+}
+
+// ---------------------------------------------------------------------------
+// Simulator::run_stabilizer (Clifford fast path)
+// ---------------------------------------------------------------------------
+
+fn ghz_circuit(n: u32) -> QuantumCircuit {
+    let mut circuit = QuantumCircuit::new(n);
+    circuit.h(0);
+    for i in 0..(n - 1) {
+        circuit.cnot(i, i + 1);
+    }
+    circuit
+}
+
+#[test]
+fn test_stabilizer_large_ghz() {
+    let mut circuit = ghz_circuit(100);
+    let result = Simulator::run_stabilizer(&circuit).unwrap();
+    assert_eq!(result.probabilities.len(), 100);
+    assert!(result.probabilities.iter().all(|&p| approx_eq(p, 0.5)));
+    assert_eq!(result.metrics.gate_count, 100);
+
+    // All qubits of a GHZ state collapse to the same value.
+    circuit.measure_all();
+    let result = Simulator::run_stabilizer(&circuit).unwrap();
+    assert_eq!(result.measurements.len(), 100);
+    let first = result.measurements[0].result;
+    assert!(result.measurements.iter().all(|m| m.result == first));
+    let collapsed = if first { 1.0 } else { 0.0 };
+    assert!(result
+        .probabilities
+        .iter()
+        .all(|&p| approx_eq(p, collapsed)));
+}
+
+#[test]
+fn test_stabilizer_matches_state_vector() {
+    let mut circuit = ghz_circuit(4);
+    circuit
+        .s(1)
+        .h(2)
+        .x(3)
+        .cz(0, 2)
+        .swap(1, 3)
+        .y(0)
+        .h(1)
+        .add_gate(Gate::Sdg(1))
+        .h(1);
+    let stab = Simulator::run_stabilizer(&circuit).unwrap();
+    let sv = Simulator::run(&circuit).unwrap();
+    for q in 0..4 {
+        let expected = sv.state.probability_of_qubit(q);
+        assert!(
+            approx_eq(stab.probabilities[q as usize], expected),
+            "qubit {}: {} vs {}",
+            q,
+            stab.probabilities[q as usize],
+            expected
+        );
+    }
+
+    let mut deterministic = QuantumCircuit::new(3);
+    deterministic.x(0).cnot(0, 2).h(1).h(1);
+    let stab = Simulator::run_stabilizer(&deterministic).unwrap();
+    assert_eq!(stab.probabilities, vec![1.0, 0.0, 1.0]);
+}
+
+#[test]
+fn test_stabilizer_rejects_non_clifford() {
+    let mut circuit = ghz_circuit(3);
+    circuit.t(1);
+    assert!(matches!(
+        Simulator::run_stabilizer(&circuit),
+        Err(QuantumError::CircuitError(_))
+    ));
+}
+
+// ---------------------------------------------------------------------------
+// Simulator::run_sparse (sparse state-vector backend)
+// ---------------------------------------------------------------------------
+
+fn seeded(seed: u64, backend: StateBackend) -> SimConfig {
+    SimConfig {
+        seed: Some(seed),
+        noise: None,
+        shots: None,
+        backend,
+    }
+}
+
+#[test]
+fn test_sparse_matches_dense() {
+    let mut mixed = ghz_circuit(4);
+    mixed
+        .t(1)
+        .rx(2, 0.3)
+        .add_gate(Gate::Rzz(0, 3, 0.7))
+        .swap(1, 2)
+        .ry(0, 1.1)
+        .cz(2, 3)
+        .h(3);
+    let mut measured = mixed.clone();
+    measured.measure(1).h(1).measure(2);
+
+    for circuit in [ghz_circuit(5), mixed, measured] {
+        let dense = Simulator::run_with_config(&circuit, &seeded(7, StateBackend::Dense)).unwrap();
+        let sparse = Simulator::run_sparse(&circuit, &seeded(7, StateBackend::Dense)).unwrap();
+
+        for (i, amp) in dense.state.state_vector().iter().enumerate() {
+            let s = sparse.state.amplitude(i as u128);
+            assert!(
+                approx_eq(amp.re, s.re) && approx_eq(amp.im, s.im),
+                "amplitude {}: {} vs {}",
+                i,
+                amp,
+                s
+            );
+        }
+        let outcomes = |r: &[MeasurementOutcome]| r.iter().map(|m| m.result).collect::<Vec<_>>();
+        assert_eq!(
+            outcomes(&dense.measurements),
+            outcomes(&sparse.measurements)
+        );
+        assert_eq!(dense.metrics.gate_count, sparse.metrics.gate_count);
+    }
+
+    // The dense entry point does not silently densify a sparse run.
+    match Simulator::run_with_config(&ghz_circuit(3), &seeded(1, StateBackend::Sparse)) {
+        Err(QuantumError::CircuitError(msg)) => assert!(msg.contains("run_sparse")),
+        other => panic!(
+            "expected CircuitError, got {:?}",
+            other.map(|r| r.measurements)
+        ),
+    }
+}
+
+#[test]
+fn test_sparse_large_product_state() {
+    // 40 qubits: |+> on qubit 0, |1> on every odd qubit, |0> elsewhere.
+    let mut circuit = QuantumCircuit::new(40);
+    circuit.h(0);
+    for q in (1..40).step_by(2) {
+        circuit.x(q);
+    }
+    let result = Simulator::run_sparse(&circuit, &seeded(3, StateBackend::Sparse)).unwrap();
+    assert_eq!(result.state.num_nonzero(), 2);
+
+    let odd: u128 = (1..40).step_by(2).map(|q| 1u128 << q).sum();
+    assert!(approx_eq(result.state.amplitude(odd).re, 1.0 / 2f64.sqrt()));
+    assert!(approx_eq(
+        result.state.amplitude(odd | 1).re,
+        1.0 / 2f64.sqrt()
+    ));
+    assert!(approx_eq(result.state.probability_of_qubit(0), 0.5));
+    assert!(approx_eq(result.state.probability_of_qubit(39), 1.0));
+    assert!(approx_eq(result.state.probability_of_qubit(38), 0.0));
+
+    // The dense backend cannot hold 40 qubits.
+    assert!(matches!(
+        Simulator::run_with_config(&circuit, &seeded(3, StateBackend::Dense)),
+        Err(QuantumError::QubitLimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn test_sparse_large_ghz() {
+    let mut circuit = ghz_circuit(64);
+    circuit.measure_all();
+    let result = Simulator::run_sparse(&circuit, &seeded(11, StateBackend::Sparse)).unwrap();
+
+    assert_eq!(result.measurements.len(), 64);
+    let first = result.measurements[0].result;
+    assert!(result.measurements.iter().all(|m| m.result == first));
+    assert!(approx_eq(result.measurements[0].probability, 0.5));
+    assert_eq!(result.state.num_nonzero(), 1);
+}
+
+#[test]
+fn test_sparse_shots_beyond_dense_limit() {
+    let config = SimConfig {
+        seed: Some(5),
+        noise: None,
+        shots: Some(50),
+        backend: StateBackend::Sparse,
+    };
+    let result = Simulator::run_shots_with_config(&ghz_circuit(48), &config).unwrap();
+
+    assert_eq!(result.counts.values().sum::<usize>(), 50);
+    assert_eq!(result.counts.len(), 2);
+    for bits in result.counts.keys() {
+        assert_eq!(bits.len(), 48);
+        assert!(bits.iter().all(|&b| b == bits[0]));
+    }
+    assert!(result.metrics.peak_memory_bytes < 1 << 20);
+}
+
+// ---------------------------------------------------------------------------
+// Custom gates
+// ---------------------------------------------------------------------------
+
+fn real_matrix(rows: &[&[f64]]) -> Vec<Vec<Complex>> {
+    rows.iter()
+        .map(|r| r.iter().map(|&x| Complex::new(x, 0.0)).collect())
+        .collect()
+}
+
+fn assert_same_state(a: &QuantumCircuit, b: &QuantumCircuit) {
+    let sa = Simulator::run(a).unwrap().state;
+    let sb = Simulator::run(b).unwrap().state;
+    for (x, y) in sa.state_vector().iter().zip(sb.state_vector()) {
+        assert!(
+            approx_eq(x.re, y.re) && approx_eq(x.im, y.im),
+            "{} vs {}",
+            x,
+            y
+        );
+    }
+}
+
+#[test]
+fn test_custom_gate_matches_builtin() {
+    let h = std::f64::consts::FRAC_1_SQRT_2;
+    let hadamard = real_matrix(&[&[h, h], &[h, -h]]);
+    // Rows/columns ordered |control, target>.
+    let cnot = real_matrix(&[
+        &[1.0, 0.0, 0.0, 0.0],
+        &[0.0, 1.0, 0.0, 0.0],
+        &[0.0, 0.0, 0.0, 1.0],
+        &[0.0, 0.0, 1.0, 0.0],
+    ]);
+
+    let mut builtin = QuantumCircuit::new(3);
+    builtin.ry(0, 0.4).h(1).rx(2, 1.3).cnot(2, 0).t(0).h(2);
+    let mut custom = QuantumCircuit::new(3);
+    custom
+        .ry(0, 0.4)
+        .add_gate(Gate::custom(hadamard.clone(), vec![1]).unwrap())
+        .rx(2, 1.3)
+        .add_gate(Gate::custom(cnot, vec![2, 0]).unwrap())
+        .t(0)
+        .add_gate(Gate::custom(hadamard, vec![2]).unwrap());
+    assert_same_state(&builtin, &custom);
+
+    // Sparse backend applies the same matrices.
+    let dense = Simulator::run(&custom).unwrap();
+    let sparse = Simulator::run_sparse(&custom, &SimConfig::default()).unwrap();
+    for (i, amp) in dense.state.state_vector().iter().enumerate() {
+        let s = sparse.state.amplitude(i as u128);
+        assert!(approx_eq(amp.re, s.re) && approx_eq(amp.im, s.im));
+    }
+}
+
+#[test]
+fn test_custom_three_qubit_gate() {
+    // Toffoli with controls 3, 0 and target 1 flips qubit 1 of |1?01>.
+    let mut toffoli = vec![vec![Complex::ZERO; 8]; 8];
+    for (i, row) in toffoli.iter_mut().enumerate() {
+        let j = if i >= 6 { i ^ 1 } else { i };
+        row[j] = Complex::ONE;
+    }
+    let mut circuit = QuantumCircuit::new(4);
+    circuit
+        .x(0)
+        .x(3)
+        .add_gate(Gate::custom(toffoli.clone(), vec![3, 0, 1]).unwrap());
+    let result = Simulator::run(&circuit).unwrap();
+    assert!(approx_eq(result.state.probabilities()[0b1011], 1.0));
+
+    // One control off: nothing happens.
+    let mut circuit = QuantumCircuit::new(4);
+    circuit
+        .x(3)
+        .add_gate(Gate::custom(toffoli, vec![3, 0, 1]).unwrap());
+    let result = Simulator::run(&circuit).unwrap();
+    assert!(approx_eq(result.state.probabilities()[0b1000], 1.0));
+}
+
+#[test]
+fn test_custom_gate_rejected_by_simulator() {
+    // Built directly, bypassing Gate::custom: validated when applied.
+    let mut circuit = QuantumCircuit::new(2);
+    circuit.add_gate(Gate::Custom {
+        matrix: real_matrix(&[&[1.0, 1.0], &[0.0, 1.0]]),
+        targets: vec![1],
+    });
+    assert!(matches!(
+        Simulator::run(&circuit),
+        Err(QuantumError::CircuitError(_))
+    ));
+
+    let mut circuit = QuantumCircuit::new(2);
+    circuit.add_gate(Gate::Custom {
+        matrix: real_matrix(&[&[0.0, 1.0], &[1.0, 0.0]]),
+        targets: vec![5],
+    });
+    assert!(matches!(
+        Simulator::run(&circuit),
+        Err(QuantumError::InvalidQubitIndex { .. })
+    ));
+}
+
+// ---------------------------------------------------------------------------
+// Kraus noise channels
+// ---------------------------------------------------------------------------
+
+fn noisy_shots(circuit: &QuantumCircuit, noise: NoiseModel, backend: StateBackend) -> ShotResult {
+    let config = SimConfig {
+        seed: Some(2024),
+        noise: Some(noise),
+        shots: Some(4000),
+        backend,
+    };
+    Simulator::run_shots_with_config(circuit, &config).unwrap()
+}
+
+/// Fraction of shots in which `qubit` was measured as 1.
+fn fraction_one(result: &ShotResult, qubit: usize) -> f64 {
+    let total: usize = result.counts.values().sum();
+    let ones: usize = result
+        .counts
+        .iter()
+        .filter(|(bits, _)| bits[qubit])
+        .map(|(_, &n)| n)
+        .sum();
+    ones as f64 / total as f64
+}
+
+#[test]
+fn test_amplitude_damping_decay_statistics() {
+    // Qubit 0 is excited, then idles while nine gates act on qubit 1. The
+    // idle channel fires after each of the ten gates before measurement.
+    let mut circuit = QuantumCircuit::new(2);
+    circuit.x(0);
+    for _ in 0..9 {
+        circuit.z(1);
+    }
+    circuit.measure(0).measure(1);
+
+    let gamma = 0.1;
+    let noise = NoiseModel {
+        qubit_channels: vec![(0, NoiseChannel::AmplitudeDamping { gamma })],
+        ..Default::default()
+    };
+    let expected = (1.0 - gamma).powi(10);
+    for backend in [StateBackend::Dense, StateBackend::Sparse] {
+        let result = noisy_shots(&circuit, noise.clone(), backend);
+        let observed = fraction_one(&result, 0);
+        // 4000 shots: standard error ~0.0075.
+        assert!(
+            (observed - expected).abs() < 0.03,
+            "{:?}: P(1) = {}, expected {}",
+            backend,
+            observed,
+            expected
+        );
+        assert_eq!(fraction_one(&result, 1), 0.0);
+    }
+}
+
+#[test]
+fn test_amplitude_damping_relaxes_to_ground() {
+    let mut circuit = QuantumCircuit::new(1);
+    circuit.h(0);
+    for _ in 0..40 {
+        circuit.barrier();
+    }
+    let config = SimConfig {
+        seed: Some(3),
+        noise: Some(NoiseModel {
+            qubit_channels: vec![(0, NoiseChannel::AmplitudeDamping { gamma: 0.5 })],
+            ..Default::default()
+        }),
+        shots: None,
+        backend: StateBackend::Dense,
+    };
+    let result = Simulator::run_with_config(&circuit, &config).unwrap();
+    assert!(approx_eq(result.state.probabilities()[0], 1.0));
+    assert!(approx_eq(result.state.state_vector()[0].norm(), 1.0));
+}
+
+#[test]
+fn test_depolarizing_statistics() {
+    let mut circuit = QuantumCircuit::new(1);
+    circuit.x(0).measure(0);
+    let with_p = |p: f64| NoiseModel {
+        gate_channels: vec![NoiseChannel::Depolarizing { p }],
+        ..Default::default()
+    };
+
+    // p = 0 is the identity channel.
+    let clean = noisy_shots(&circuit, with_p(0.0), StateBackend::Dense);
+    assert_eq!(clean.counts.get(&vec![true]), Some(&4000));
+
+    // X and Y each fire with probability p/4, flipping |1> back to |0>.
+    let partial = noisy_shots(&circuit, with_p(0.3), StateBackend::Dense);
+    assert!((fraction_one(&partial, 0) - 0.85).abs() < 0.025);
+
+    // p = 1 fully mixes: a fair coin.
+    let mixed = noisy_shots(&circuit, with_p(1.0), StateBackend::Dense);
+    assert!((fraction_one(&mixed, 0) - 0.5).abs() < 0.03);
+}
+
+#[test]
+fn test_qubit_channel_out_of_range_rejected() {
+    let circuit = QuantumCircuit::new(2);
+    let config = SimConfig {
+        seed: Some(1),
+        noise: Some(NoiseModel {
+            qubit_channels: vec![(2, NoiseChannel::Depolarizing { p: 0.1 })],
+            ..Default::default()
+        }),
+        shots: None,
+        backend: StateBackend::Dense,
+    };
+    assert!(matches!(
+        Simulator::run_with_config(&circuit, &config),
+        Err(QuantumError::InvalidQubitIndex { index: 2, .. })
+    ));
+}
+
+// ---------------------------------------------------------------------------
+// Parametric circuits and gradients
+// ---------------------------------------------------------------------------
+
+fn z_on(qubit: QubitIndex) -> Hamiltonian {
+    Hamiltonian::new(
+        vec![(1.0, PauliString::new(vec![(qubit, PauliOp::Z)]))],
+        qubit + 1,
+    )
+}
+
+#[test]
+fn test_parameter_binding() {
+    let mut circuit = QuantumCircuit::new(2);
+    circuit
+        .ry(0, 0.1)
+        .h(1)
+        .rzz(0, 1, 0.2)
+        .rx(1, 0.3)
+        .cnot(0, 1)
+        .phase(0, 0.4);
+    assert_eq!(circuit.num_parameters(), 4);
+    assert_eq!(circuit.parameters(), vec![0.1, 0.2, 0.3, 0.4]);
+
+    circuit.set_parameter(2, -1.5).unwrap();
+    assert_eq!(circuit.parameters(), vec![0.1, 0.2, -1.5, 0.4]);
+    assert!(matches!(circuit.gates()[3], Gate::Rx(1, t) if t == -1.5));
+
+    circuit.bind_parameters(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+    assert_eq!(circuit.parameters(), vec![1.0, 2.0, 3.0, 4.0]);
+
+    assert!(circuit.bind_parameters(&[1.0]).is_err());
+    assert!(circuit.set_parameter(4, 0.0).is_err());
+}
+
+#[test]
+fn test_ry_expectation_and_gradient() {
+    let observable = z_on(0);
+    for &theta in &[0.0, 0.3, 1.0, 2.5, -2.0] {
+        let mut circuit = QuantumCircuit::new(1);
+        circuit.ry(0, theta);
+
+        let result = Simulator::run(&circuit).unwrap();
+        assert!(approx_eq(result.expectation(&observable), theta.cos()));
+
+        let grad = ruqu_core::gradient::expectation_gradient(&circuit, &observable, 0).unwrap();
+        assert!(
+            approx_eq(grad, -theta.sin()),
+            "theta={} grad={}",
+            theta,
+            grad
+        );
+    }
+}
+
+#[test]
+fn test_gradient_matches_finite_difference() {
+    use ruqu_core::gradient::{expectation, expectation_gradients};
+
+    // <Z0 Z1> + 0.5 <X1> on a small entangling ansatz.
+    let observable = Hamiltonian::new(
+        vec![
+            (
+                1.0,
+                PauliString::new(vec![(0, PauliOp::Z), (1, PauliOp::Z)]),
+            ),
+            (0.5, PauliString::new(vec![(1, PauliOp::X)])),
+        ],
+        2,
+    );
+    let mut circuit = QuantumCircuit::new(2);
+    circuit
+        .ry(0, 0.7)
+        .rx(1, -0.4)
+        .cnot(0, 1)
+        .rzz(0, 1, 1.1)
+        .rz(1, 0.9)
+        .ry(1, 0.25)
+        .phase(0, 0.6);
+
+    let grads = expectation_gradients(&circuit, &observable).unwrap();
+    assert_eq!(grads.len(), circuit.num_parameters());
+
+    let h = 1e-6;
+    let params = circuit.parameters();
+    for (k, grad) in grads.iter().enumerate() {
+        let mut plus = circuit.clone();
+        plus.set_parameter(k, params[k] + h).unwrap();
+        let mut minus = circuit.clone();
+        minus.set_parameter(k, params[k] - h).unwrap();
+        let numeric = (expectation(&plus, &observable).unwrap()
+            - expectation(&minus, &observable).unwrap())
+            / (2.0 * h);
+        assert!(
+            (grad - numeric).abs() < 1e-6,
+            "param {}: {} vs {}",
+            k,
+            grad,
+            numeric
+        );
+    }
+
+    assert!(ruqu_core::gradient::expectation_gradient(&circuit, &observable, 7).is_err());
+}
+
+// ---------------------------------------------------------------------------
+// Mid-circuit measurement and classical control
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_teleportation() {
+    let (theta, phi) = (1.1, 0.7);
+    let mut source = QuantumCircuit::new(1);
+    source.ry(0, theta).rz(0, phi);
+    let expected = Simulator::run(&source).unwrap().state;
+    let (alpha, beta) = (expected.state_vector()[0], expected.state_vector()[1]);
+
+    // q0 holds the source state; q1/q2 share a Bell pair.
+    let mut circuit = QuantumCircuit::new(3);
+    circuit
+        .ry(0, theta)
+        .rz(0, phi)
+        .h(1)
+        .cnot(1, 2)
+        .cnot(0, 1)
+        .h(0)
+        .measure(0)
+        .measure(1)
+        .c_if(1, true, Gate::X(2))
+        .c_if(0, true, Gate::Z(2));
+
+    let mut outcomes_seen = std::collections::HashSet::new();
+    for seed in 0..32 {
+        for backend in [StateBackend::Dense, StateBackend::Sparse] {
+            // q0 and q1 are collapsed; q2 must carry the source state.
+            let config = seeded(seed, backend);
+            let (m0, m1, a, b) = match backend {
+                StateBackend::Dense => {
+                    let state = Simulator::run_with_config(&circuit, &config).unwrap().state;
+                    let (m0, m1) = (state.classical_bit(0), state.classical_bit(1));
+                    let base = usize::from(m0) | usize::from(m1) << 1;
+                    let amps = state.state_vector();
+                    (m0, m1, amps[base], amps[base | 0b100])
+                }
+                StateBackend::Sparse => {
+                    let state = Simulator::run_sparse(&circuit, &config).unwrap().state;
+                    let (m0, m1) = (state.classical_bit(0), state.classical_bit(1));
+                    let base = u128::from(m0) | u128::from(m1) << 1;
+                    (m0, m1, state.amplitude(base), state.amplitude(base | 0b100))
+                }
+            };
+            outcomes_seen.insert((m0, m1));
+
+            let overlap = alpha.conj() * a + beta.conj() * b;
+            assert!(
+                approx_eq(overlap.norm_sq(), 1.0),
+                "seed {} outcome ({}, {}): fidelity {}",
+                seed,
+                m0,
+                m1,
+                overlap.norm_sq()
+            );
+        }
+    }
+    assert_eq!(outcomes_seen.len(), 4);
+}
+
+#[test]
+fn test_conditional_gate_semantics() {
+    // Unmeasured bits read as 0.
+    let mut circuit = QuantumCircuit::new(2);
+    circuit.c_if(0, true, Gate::X(1)).c_if(0, false, Gate::H(0));
+    let result = Simulator::run(&circuit).unwrap();
+    assert!(approx_eq(result.state.probabilities()[0b01], 0.5));
+    assert!(!result.state.classical_bit(0));
+
+    // The bit keeps the latest outcome: measure 1, reset, measure 0.
+    let mut circuit = QuantumCircuit::new(2);
+    circuit
+        .x(0)
+        .measure(0)
+        .c_if(0, true, Gate::X(1))
+        .reset(0)
+        .measure(0)
+        .c_if(0, true, Gate::X(1));
+    let result = Simulator::run(&circuit).unwrap();
+    assert!(approx_eq(result.state.probabilities()[0b10], 1.0));
+    assert_eq!(result.measurements.len(), 2);
+
+    let mut circuit = QuantumCircuit::new(1);
+    circuit.c_if(3, true, Gate::X(0));
+    assert!(matches!(
+        Simulator::run(&circuit),
+        Err(QuantumError::InvalidQubitIndex { index: 3, .. })
+    ));
+}
@@ -519,6 +519,74 @@ fn test_seeded_measurement_reproducibility() {
     assert_eq!(outcome1.result, outcome2.result);
 }
 
+#[test]
+fn test_measure_plus_in_x_basis_is_deterministic() {
+    for seed in 0..50 {
+        let mut state = QuantumState::new(1).unwrap();
+        state.apply_gate(&Gate::H(0)).unwrap();
+        // |+> is the +1 eigenstate of X.
+        assert!(!state.measure_in_basis(0, PauliOp::X, seed).unwrap());
+        let amp = 1.0 / 2.0_f64.sqrt();
+        assert!(approx_eq(state.state_vector()[0].re, amp));
+        assert!(approx_eq(state.state_vector()[1].re, amp));
+    }
+}
+
+#[test]
+fn test_measure_zero_in_x_basis_is_uniform() {
+    let mut minus = 0;
+    for seed in 0..400 {
+        let mut state = QuantumState::new(1).unwrap();
+        if state.measure_in_basis(0, PauliOp::X, seed).unwrap() {
+            minus += 1;
+        }
+    }
+    let ratio = minus as f64 / 400.0;
+    assert!(ratio > 0.4 && ratio < 0.6, "got {:.1}% -1", ratio * 100.0);
+
+    // Same seed, same outcome.
+    let mut a = QuantumState::new(1).unwrap();
+    let mut b = QuantumState::new(1).unwrap();
+    assert_eq!(
+        a.measure_in_basis(0, PauliOp::X, 99).unwrap(),
+        b.measure_in_basis(0, PauliOp::X, 99).unwrap()
+    );
+}
+
+#[test]
+fn test_measure_in_basis_collapses_to_eigenstate() {
+    for (basis, seed) in [(PauliOp::X, 3), (PauliOp::Y, 5), (PauliOp::Y, 8)] {
+        // Qubit 1 of a Bell pair: measuring qubit 0 also collapses qubit 1.
+        let mut state = QuantumState::new(2).unwrap();
+        state.apply_gate(&Gate::H(0)).unwrap();
+        state.apply_gate(&Gate::CNOT(0, 1)).unwrap();
+
+        let minus = state.measure_in_basis(0, basis, seed).unwrap();
+        let eigenvalue = if minus { -1.0 } else { 1.0 };
+        let on_0 = PauliString {
+            ops: vec![(0, basis)],
+        };
+        assert!(approx_eq(state.expectation_value(&on_0), eigenvalue));
+
+        // Repeating the measurement gives the same result.
+        assert_eq!(state.measure_in_basis(0, basis, seed + 1).unwrap(), minus);
+
+        // Bell correlations: <XX> = 1, <YY> = -1.
+        let partner = if basis == PauliOp::X { 1.0 } else { -1.0 };
+        let on_1 = PauliString {
+            ops: vec![(1, basis)],
+        };
+        assert!(approx_eq(
+            state.expectation_value(&on_1),
+            eigenvalue * partner
+        ));
+        assert!(approx_eq(state.probabilities().iter().sum::<f64>(), 1.0));
+    }
+
+    let mut state = QuantumState::new(1).unwrap();
+    assert!(state.measure_in_basis(1, PauliOp::X, 0).is_err());
+}
+
 // ---------------------------------------------------------------------------
 // Probability of individual qubits
 // ---------------------------------------------------------------------------
@@ -1027,3 +1095,69 @@ fn test_superdense_coding_11() {
     let probs = state.probabilities();
     assert!(approx_eq(probs[3], 1.0)); // |11>
 }
+
+// ---------------------------------------------------------------------------
+// Tensor product
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_tensor_zero_one_is_basis_state() {
+    // |0> (qubit 0) ⊗ |1> (qubit 1) = |q1=1, q0=0> = index 2
+    let zero = QuantumState::new(1).unwrap();
+    let mut one = QuantumState::new(1).unwrap();
+    one.apply_gate(&Gate::X(0)).unwrap();
+
+    let product = zero.tensor(&one).unwrap();
+    assert_eq!(product.num_qubits(), 2);
+    let probs = product.probabilities();
+    assert!(approx_eq(probs[2], 1.0));
+    assert!(approx_eq(product.probability_of_qubit(0), 0.0));
+    assert!(approx_eq(product.probability_of_qubit(1), 1.0));
+
+    let mut expected = QuantumState::new(2).unwrap();
+    expected.apply_gate(&Gate::X(1)).unwrap();
+    assert!(approx_eq(product.fidelity(&expected), 1.0));
+}
+
+#[test]
+fn test_tensor_matches_manual_kronecker() {
+    let mut a = QuantumState::new(2).unwrap();
+    a.apply_gate(&Gate::H(0)).unwrap();
+    a.apply_gate(&Gate::Ry(1, 0.7)).unwrap();
+    let mut b = QuantumState::new(1).unwrap();
+    b.apply_gate(&Gate::Rx(0, 1.3)).unwrap();
+    b.apply_gate(&Gate::S(0)).unwrap();
+
+    let product = a.tensor(&b).unwrap();
+    let (sa, sb, sp) = (a.state_vector(), b.state_vector(), product.state_vector());
+    assert_eq!(sp.len(), 8);
+    for j in 0..sb.len() {
+        for i in 0..sa.len() {
+            let expected = sa[i] * sb[j];
+            let got = sp[i | (j << 2)];
+            assert!(approx_eq(got.re, expected.re) && approx_eq(got.im, expected.im));
+        }
+    }
+    assert!(approx_eq(sp.iter().map(|c| c.norm_sq()).sum::<f64>(), 1.0));
+
+    // from_product folds left to right.
+    let folded = QuantumState::from_product(&[a, b]).unwrap();
+    assert!(approx_eq(folded.fidelity(&product), 1.0));
+}
+
+#[test]
+fn test_tensor_beyond_qubit_limit_errors() {
+    let big = QuantumState::new(17).unwrap();
+    let other = QuantumState::new(16).unwrap();
+    assert!(matches!(
+        big.tensor(&other),
+        Err(QuantumError::QubitLimitExceeded { requested: 33, .. })
+    ));
+
+    let parts: Vec<_> = (0..11).map(|_| QuantumState::new(3).unwrap()).collect();
+    assert!(matches!(
+        QuantumState::from_product(&parts),
+        Err(QuantumError::QubitLimitExceeded { .. })
+    ));
+    assert!(QuantumState::from_product(&[]).is_err());
+}
@@ -422,3 +422,91 @@ fn test_hamiltonian_ising_model() {
     assert_eq!(h.terms.len(), 5);
     assert_eq!(h.num_qubits, 3);
 }
+
+// ---------------------------------------------------------------------------
+// NoiseChannel
+// ---------------------------------------------------------------------------
+
+/// Apply a channel to a density matrix: sum_k K rho K^dagger.
+fn apply_kraus(channel: &NoiseChannel, rho: &[[Complex; 2]; 2]) -> [[Complex; 2]; 2] {
+    let mut out = [[Complex::ZERO; 2]; 2];
+    for k in channel.kraus_operators() {
+        for i in 0..2 {
+            for j in 0..2 {
+                for a in 0..2 {
+                    for b in 0..2 {
+                        out[i][j] += k[i][a] * rho[a][b] * k[j][b].conj();
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn test_noise_channels_trace_preserving() {
+    let channels = [
+        NoiseChannel::AmplitudeDamping { gamma: 0.0 },
+        NoiseChannel::AmplitudeDamping { gamma: 0.37 },
+        NoiseChannel::AmplitudeDamping { gamma: 1.0 },
+        NoiseChannel::Depolarizing { p: 0.0 },
+        NoiseChannel::Depolarizing { p: 0.6 },
+        NoiseChannel::Depolarizing { p: 1.0 },
+    ];
+    for channel in &channels {
+        // sum_k K^dagger K = I
+        let mut sum = [[Complex::ZERO; 2]; 2];
+        for k in channel.kraus_operators() {
+            for i in 0..2 {
+                for j in 0..2 {
+                    for row in k.iter() {
+                        sum[i][j] += row[i].conj() * row[j];
+                    }
+                }
+            }
+        }
+        for (i, row) in sum.iter().enumerate() {
+            for (j, v) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!(
+                    approx_eq(v.re, expected) && approx_eq(v.im, 0.0),
+                    "{:?}",
+                    channel
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_depolarizing_limits() {
+    // |+><+| has off-diagonal coherence that full mixing must erase.
+    let half = Complex::new(0.5, 0.0);
+    let plus = [[half, half], [half, half]];
+
+    let unchanged = apply_kraus(&NoiseChannel::Depolarizing { p: 0.0 }, &plus);
+    let mixed = apply_kraus(&NoiseChannel::Depolarizing { p: 1.0 }, &plus);
+    let partial = apply_kraus(&NoiseChannel::Depolarizing { p: 0.4 }, &plus);
+    for i in 0..2 {
+        for j in 0..2 {
+            assert!(approx_eq(unchanged[i][j].re, 0.5));
+            let identity = if i == j { 0.5 } else { 0.0 };
+            assert!(approx_eq(mixed[i][j].re, identity));
+            assert!(approx_eq(mixed[i][j].im, 0.0));
+            // (1 - p) rho + p I/2
+            assert!(approx_eq(partial[i][j].re, 0.6 * 0.5 + 0.4 * identity));
+        }
+    }
+}
+
+#[test]
+fn test_amplitude_damping_moves_population_to_ground() {
+    let one = [
+        [Complex::ZERO, Complex::ZERO],
+        [Complex::ZERO, Complex::ONE],
+    ];
+    let rho = apply_kraus(&NoiseChannel::AmplitudeDamping { gamma: 0.25 }, &one);
+    assert!(approx_eq(rho[0][0].re, 0.25));
+    assert!(approx_eq(rho[1][1].re, 0.75));
+}
@@ -54,11 +54,19 @@ pub fn inverse_gate(gate: &Gate) -> Result<Gate, QuantumError> {
             ];
             Ok(Gate::Unitary1Q(*q, inv))
         }
+        Gate::Custom { matrix, targets } => Ok(Gate::Custom {
+            matrix: (0..matrix.len())
+                .map(|i| matrix.iter().map(|row| row[i].conj()).collect())
+                .collect(),
+            targets: targets.clone(),
+        }),
 
         // Non-unitary: cannot invert
-        Gate::Measure(_) | Gate::Reset(_) | Gate::Barrier => Err(QuantumError::CircuitError(
-            "cannot invert non-unitary gate (Measure/Reset/Barrier)".into(),
-        )),
+        Gate::Measure(_) | Gate::Reset(_) | Gate::Barrier | Gate::Conditional { .. } => {
+            Err(QuantumError::CircuitError(
+                "cannot invert non-unitary gate (Measure/Reset/Barrier/Conditional)".into(),
+            ))
+        }
     }
 }
 
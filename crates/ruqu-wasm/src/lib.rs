@@ -467,6 +467,7 @@ pub fn qaoa_maxcut(
             seed: seed_opt,
             noise: None,
             shots: None,
+            backend: ruqu_core::simulator::StateBackend::Dense,
         },
     )
     .map_err(|e| JsValue::from_str(&e.to_string()))?;
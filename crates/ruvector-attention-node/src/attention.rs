@@ -13,7 +13,9 @@ use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use ruvector_attention::{
     attention::{MultiHeadAttention as RustMultiHead, ScaledDotProductAttention},
-    hyperbolic::{HyperbolicAttention as RustHyperbolic, HyperbolicAttentionConfig},
+    hyperbolic::{
+        HyperbolicAttention as RustHyperbolic, HyperbolicAttentionConfig, HyperbolicModel,
+    },
     moe::{MoEAttention as RustMoE, MoEConfig as RustMoEConfig},
     sparse::{
         FlashAttention as RustFlash, LinearAttention as RustLinear,
@@ -239,6 +241,7 @@ impl HyperbolicAttention {
             temperature: temperature as f32,
             frechet_max_iter: 100,
             frechet_tol: 1e-6,
+            model: HyperbolicModel::Poincare,
         };
         Self {
             inner: RustHyperbolic::new(config),
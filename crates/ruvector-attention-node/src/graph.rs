@@ -179,6 +179,7 @@ impl GraphRoPEAttention {
             max_position: config.max_position as usize,
             base: config.base.unwrap_or(10000.0) as f32,
             scaling_factor: config.scaling_factor.unwrap_or(1.0) as f32,
+            scaling: Default::default(),
         };
         Self {
             inner: RustGraphRoPE::new(rust_config),
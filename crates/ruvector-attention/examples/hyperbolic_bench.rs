@@ -15,6 +15,7 @@ use ruvector_attention::hyperbolic::{
     project_hyperboloid,
     HyperbolicAttention,
     HyperbolicAttentionConfig,
+    HyperbolicModel,
     LCAConfig,
     // Lorentz Cascade (novel)
     LorentzCascadeAttention,
@@ -122,6 +123,7 @@ fn bench_full_poincare_attention(
         temperature: 1.0,
         frechet_max_iter: 50,
         frechet_tol: 1e-5,
+        model: HyperbolicModel::Poincare,
     };
     let attention = HyperbolicAttention::new(config);
 
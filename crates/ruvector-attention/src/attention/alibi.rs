@@ -0,0 +1,187 @@
+//! ALiBi (Attention with Linear Biases) positional bias.
+//!
+//! Instead of adding position information to the inputs, ALiBi subtracts a
+//! penalty proportional to the query-key distance from each attention logit:
+//! `bias = -slope_h * |query_pos - key_pos|`. Every head gets a fixed slope,
+//! so nearby keys dominate in steep heads while shallow heads see far back.
+//! Because nothing is learned per position, the bias extrapolates to
+//! sequences longer than those seen in training.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AttentionError, AttentionResult};
+
+/// Per-head ALiBi slopes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlibiBias {
+    slopes: Vec<f32>,
+}
+
+impl AlibiBias {
+    /// Creates the bias with the paper's geometric slopes for `num_heads`.
+    pub fn new(num_heads: usize) -> AttentionResult<Self> {
+        if num_heads == 0 {
+            return Err(AttentionError::InvalidConfig(
+                "num_heads must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            slopes: Self::default_slopes(num_heads),
+        })
+    }
+
+    /// Creates the bias with explicit per-head slopes.
+    pub fn with_slopes(slopes: Vec<f32>) -> AttentionResult<Self> {
+        if slopes.is_empty() {
+            return Err(AttentionError::InvalidConfig(
+                "ALiBi needs at least one slope".to_string(),
+            ));
+        }
+        if let Some(bad) = slopes.iter().find(|s| !s.is_finite() || **s < 0.0) {
+            return Err(AttentionError::InvalidConfig(format!(
+                "ALiBi slopes must be finite and non-negative, got {}",
+                bad
+            )));
+        }
+        Ok(Self { slopes })
+    }
+
+    /// Geometric slopes from Press et al. (2022).
+    ///
+    /// For a power-of-two head count `n` the slopes are `2^(-8i/n)` for
+    /// `i = 1..=n`. Otherwise the closest smaller power of two `p` supplies
+    /// the first `p` slopes and the rest interleave from the `2p` sequence.
+    pub fn default_slopes(num_heads: usize) -> Vec<f32> {
+        fn power_of_two(n: usize) -> Vec<f32> {
+            let start = 2f32.powf(-8.0 / n as f32);
+            (1..=n).map(|i| start.powi(i as i32)).collect()
+        }
+
+        if num_heads == 0 {
+            return Vec::new();
+        }
+        if num_heads.is_power_of_two() {
+            return power_of_two(num_heads);
+        }
+        let closest = 1 << num_heads.ilog2();
+        let mut slopes = power_of_two(closest);
+        slopes.extend(
+            power_of_two(2 * closest)
+                .into_iter()
+                .step_by(2)
+                .take(num_heads - closest),
+        );
+        slopes
+    }
+
+    /// Number of heads this bias covers.
+    pub fn num_heads(&self) -> usize {
+        self.slopes.len()
+    }
+
+    /// Per-head slopes.
+    pub fn slopes(&self) -> &[f32] {
+        &self.slopes
+    }
+
+    /// Bias added to the logit of `key_pos` for a query at `query_pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `head >= num_heads`.
+    #[inline]
+    pub fn bias(&self, head: usize, query_pos: usize, key_pos: usize) -> f32 {
+        assert!(head < self.slopes.len(), "head {} out of range", head);
+        -self.slopes[head] * query_pos.abs_diff(key_pos) as f32
+    }
+
+    /// Builds the `query_len x key_len` bias matrix for one head, row-major.
+    pub fn bias_matrix(
+        &self,
+        head: usize,
+        query_len: usize,
+        key_len: usize,
+    ) -> AttentionResult<Vec<f32>> {
+        self.check_head(head)?;
+        Ok((0..query_len)
+            .flat_map(|i| (0..key_len).map(move |j| (i, j)))
+            .map(|(i, j)| self.bias(head, i, j))
+            .collect())
+    }
+
+    /// Adds the bias for a query at `query_pos` to logits over keys
+    /// `0..logits.len()`. Masked logits (`-inf`) are left untouched.
+    pub fn apply(&self, head: usize, query_pos: usize, logits: &mut [f32]) -> AttentionResult<()> {
+        self.check_head(head)?;
+        for (key_pos, logit) in logits.iter_mut().enumerate() {
+            if *logit != f32::NEG_INFINITY {
+                *logit += self.bias(head, query_pos, key_pos);
+            }
+        }
+        Ok(())
+    }
+
+    fn check_head(&self, head: usize) -> AttentionResult<()> {
+        if head >= self.slopes.len() {
+            return Err(AttentionError::InvalidConfig(format!(
+                "head {} out of range for {} heads",
+                head,
+                self.slopes.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_slopes() {
+        let eight = AlibiBias::default_slopes(8);
+        let expected: Vec<f32> = (1..=8).map(|i| 0.5f32.powi(i)).collect();
+        assert_eq!(eight, expected);
+
+        // Non-power-of-two: 8 slopes of the 8-head sequence, then every
+        // other slope of the 16-head sequence.
+        let twelve = AlibiBias::default_slopes(12);
+        assert_eq!(&twelve[..8], eight.as_slice());
+        let sixteen = AlibiBias::default_slopes(16);
+        assert_eq!(
+            &twelve[8..],
+            &[sixteen[0], sixteen[2], sixteen[4], sixteen[6]]
+        );
+    }
+
+    #[test]
+    fn test_bias_matrix_eight_heads() {
+        let alibi = AlibiBias::new(8).unwrap();
+        assert_eq!(alibi.num_heads(), 8);
+
+        for head in 0..8 {
+            let slope = 0.5f32.powi(head as i32 + 1);
+            let m = alibi.bias_matrix(head, 4, 4).unwrap();
+            for i in 0..4 {
+                for j in 0..4 {
+                    let expected = -slope * (i as f32 - j as f32).abs();
+                    assert_eq!(m[i * 4 + j], expected, "head {} ({}, {})", head, i, j);
+                }
+            }
+        }
+        let m0 = alibi.bias_matrix(0, 3, 3).unwrap();
+        assert_eq!(m0, vec![0.0, -0.5, -1.0, -0.5, 0.0, -0.5, -1.0, -0.5, 0.0]);
+        assert!(alibi.bias_matrix(8, 2, 2).is_err());
+    }
+
+    #[test]
+    fn test_apply_skips_masked() {
+        let alibi = AlibiBias::with_slopes(vec![1.0]).unwrap();
+        let mut logits = vec![0.0, 0.0, f32::NEG_INFINITY];
+        alibi.apply(0, 1, &mut logits).unwrap();
+        assert_eq!(logits, vec![-1.0, 0.0, f32::NEG_INFINITY]);
+
+        assert!(AlibiBias::with_slopes(vec![]).is_err());
+        assert!(AlibiBias::with_slopes(vec![f32::NAN]).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//! Causal attention with sliding window and attention sinks.
+//!
+//! Each query attends only to itself and earlier positions. With a window,
+//! only the most recent `window_size` positions are visible, except for the
+//! first `sink_tokens` positions, which stay visible to every query. Keeping
+//! these "attention sinks" stabilizes long streaming generation where the
+//! KV cache would otherwise evict them.
+
+use crate::{
+    error::{AttentionError, AttentionResult},
+    traits::Attention,
+};
+
+use super::kv_cache::KvCache;
+use super::scaled_dot_product::ScaledDotProductAttention;
+
+/// Causal (optionally windowed) attention with attention sink tokens.
+pub struct CausalAttention {
+    dim: usize,
+    window_size: usize,
+    sink_tokens: usize,
+    inner: ScaledDotProductAttention,
+}
+
+impl CausalAttention {
+    /// Creates causal attention over the full prefix (no window).
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The embedding dimension
+    pub fn new(dim: usize) -> Self {
+        Self {
+            dim,
+            window_size: 0,
+            sink_tokens: 0,
+            inner: ScaledDotProductAttention::new(dim),
+        }
+    }
+
+    /// Restricts each query to the `window_size` most recent positions
+    /// (including itself). 0 disables the window.
+    pub fn with_window(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+
+    /// Keeps the first `sink_tokens` positions visible to every query.
+    pub fn with_sink_tokens(mut self, sink_tokens: usize) -> Self {
+        self.sink_tokens = sink_tokens;
+        self
+    }
+
+    /// Sliding window size (0 = unbounded).
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Number of attention sink tokens.
+    pub fn sink_tokens(&self) -> usize {
+        self.sink_tokens
+    }
+
+    /// Creates a KV cache whose eviction policy matches this attention.
+    pub fn new_cache(&self) -> KvCache {
+        KvCache::new(self.dim, self.window_size).with_sink_tokens(self.sink_tokens)
+    }
+
+    /// Returns whether a query at `query_pos` may attend to `key_pos`.
+    #[inline]
+    pub fn is_visible(&self, query_pos: usize, key_pos: usize) -> bool {
+        key_pos <= query_pos
+            && (self.window_size == 0
+                || key_pos < self.sink_tokens
+                || query_pos - key_pos < self.window_size)
+    }
+
+    /// Builds the attention mask for a query at `query_pos` over `seq_len` keys.
+    pub fn mask(&self, query_pos: usize, seq_len: usize) -> Vec<bool> {
+        (0..seq_len)
+            .map(|j| self.is_visible(query_pos, j))
+            .collect()
+    }
+
+    /// Computes attention for a query at absolute position `query_pos` over
+    /// the full key/value sequence.
+    pub fn compute_at(
+        &self,
+        query: &[f32],
+        query_pos: usize,
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<Vec<f32>> {
+        if query_pos >= keys.len() {
+            return Err(AttentionError::InvalidConfig(format!(
+                "query position {} out of range for {} keys",
+                query_pos,
+                keys.len()
+            )));
+        }
+        let mask = self.mask(query_pos, keys.len());
+        self.inner
+            .compute_with_mask(query, keys, values, Some(&mask))
+    }
+
+    /// Decodes one token: appends its key/value to `cache` and attends the
+    /// query over everything the cache retains (sinks plus recent window).
+    pub fn step(
+        &self,
+        cache: &mut KvCache,
+        query: &[f32],
+        key: &[f32],
+        value: &[f32],
+    ) -> AttentionResult<Vec<f32>> {
+        cache.append(key, value)?;
+        self.inner.compute(query, &cache.keys(), &cache.values())
+    }
+}
+
+impl Attention for CausalAttention {
+    /// Treats the query as the last position of the sequence.
+    fn compute(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<Vec<f32>> {
+        if keys.is_empty() {
+            return Err(AttentionError::EmptyInput("keys".to_string()));
+        }
+        self.compute_at(query, keys.len() - 1, keys, values)
+    }
+
+    fn compute_with_mask(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+        mask: Option<&[bool]>,
+    ) -> AttentionResult<Vec<f32>> {
+        let Some(mask) = mask else {
+            return self.compute(query, keys, values);
+        };
+        if keys.is_empty() {
+            return Err(AttentionError::EmptyInput("keys".to_string()));
+        }
+        if mask.len() != keys.len() {
+            return Err(AttentionError::InvalidMask {
+                expected: format!("{}", keys.len()),
+                actual: format!("{}", mask.len()),
+            });
+        }
+
+        let combined: Vec<bool> = self
+            .mask(keys.len() - 1, keys.len())
+            .into_iter()
+            .zip(mask.iter())
+            .map(|(causal, &user)| causal && user)
+            .collect();
+        self.inner
+            .compute_with_mask(query, keys, values, Some(&combined))
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(i: usize) -> Vec<f32> {
+        vec![
+            (i as f32 * 0.37).sin(),
+            (i as f32 * 0.11).cos(),
+            0.5,
+            i as f32 * 0.01,
+        ]
+    }
+
+    fn assert_close(a: &[f32], b: &[f32]) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_sinks_survive_cache_eviction() {
+        let attn = CausalAttention::new(4).with_window(3).with_sink_tokens(2);
+        let mut cache = attn.new_cache();
+        for i in 0..12 {
+            let t = token(i);
+            attn.step(&mut cache, &t, &t, &t).unwrap();
+        }
+        assert_eq!(cache.positions(), &[0, 1, 9, 10, 11]);
+    }
+
+    #[test]
+    fn test_query_attends_to_sinks_and_window() {
+        let attn = CausalAttention::new(4).with_window(3).with_sink_tokens(2);
+        let mut cache = attn.new_cache();
+        let tokens: Vec<Vec<f32>> = (0..10).map(token).collect();
+        let mut last = Vec::new();
+        for t in &tokens {
+            last = attn.step(&mut cache, t, t, t).unwrap();
+        }
+
+        // The decoded output equals full-sequence attention with the mask.
+        let refs: Vec<&[f32]> = tokens.iter().map(|t| t.as_slice()).collect();
+        let full = attn.compute_at(&tokens[9], 9, &refs, &refs).unwrap();
+        assert_close(&last, &full);
+
+        let mask = attn.mask(9, 10);
+        let visible: Vec<usize> = (0..10).filter(|&j| mask[j]).collect();
+        assert_eq!(visible, vec![0, 1, 7, 8, 9]);
+        assert!(!attn.is_visible(4, 5));
+    }
+
+    #[test]
+    fn test_no_sinks_is_sliding_window() {
+        let attn = CausalAttention::new(4).with_window(3);
+        let mut cache = attn.new_cache();
+        let tokens: Vec<Vec<f32>> = (0..8).map(token).collect();
+        let mut last = Vec::new();
+        for t in &tokens {
+            last = attn.step(&mut cache, t, t, t).unwrap();
+        }
+        assert_eq!(cache.positions(), &[5, 6, 7]);
+
+        let window: Vec<&[f32]> = tokens[5..].iter().map(|t| t.as_slice()).collect();
+        let plain = ScaledDotProductAttention::new(4)
+            .compute(&tokens[7], &window, &window)
+            .unwrap();
+        assert_close(&last, &plain);
+    }
+
+    #[test]
+    fn test_trait_compute_uses_last_position() {
+        let attn = CausalAttention::new(4).with_window(2).with_sink_tokens(1);
+        let tokens: Vec<Vec<f32>> = (0..5).map(token).collect();
+        let refs: Vec<&[f32]> = tokens.iter().map(|t| t.as_slice()).collect();
+        let out = attn.compute(&tokens[4], &refs, &refs).unwrap();
+        let expected = attn.compute_at(&tokens[4], 4, &refs, &refs).unwrap();
+        assert_close(&out, &expected);
+        assert!(attn.compute_at(&tokens[0], 5, &refs, &refs).is_err());
+    }
+}
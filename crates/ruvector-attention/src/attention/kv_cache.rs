@@ -0,0 +1,170 @@
+//! Key/value cache for autoregressive decoding.
+//!
+//! Stores the keys and values of previously generated tokens so each new
+//! query only needs to project its own key and value. The cache can be
+//! bounded to a sliding window of recent tokens, optionally pinning the
+//! first few tokens as "attention sinks" that are never evicted.
+
+use crate::error::{AttentionError, AttentionResult};
+
+/// Key/value cache with sliding-window eviction and attention sinks.
+#[derive(Clone, Debug)]
+pub struct KvCache {
+    dim: usize,
+    window_size: usize,
+    sink_tokens: usize,
+    keys: Vec<Vec<f32>>,
+    values: Vec<Vec<f32>>,
+    positions: Vec<usize>,
+    next_position: usize,
+}
+
+impl KvCache {
+    /// Creates a new cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - Key/value dimension
+    /// * `window_size` - Number of most recent tokens to retain (0 = unbounded)
+    pub fn new(dim: usize, window_size: usize) -> Self {
+        Self {
+            dim,
+            window_size,
+            sink_tokens: 0,
+            keys: Vec::new(),
+            values: Vec::new(),
+            positions: Vec::new(),
+            next_position: 0,
+        }
+    }
+
+    /// Pins the first `sink_tokens` positions so they are never evicted.
+    pub fn with_sink_tokens(mut self, sink_tokens: usize) -> Self {
+        self.sink_tokens = sink_tokens;
+        self
+    }
+
+    /// Appends a key/value pair, evicting the oldest non-sink entry if the
+    /// window is full. Returns the absolute position of the new token.
+    pub fn append(&mut self, key: &[f32], value: &[f32]) -> AttentionResult<usize> {
+        if key.len() != self.dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.dim,
+                actual: key.len(),
+            });
+        }
+        if value.len() != self.dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.dim,
+                actual: value.len(),
+            });
+        }
+
+        let position = self.next_position;
+        self.next_position += 1;
+        self.keys.push(key.to_vec());
+        self.values.push(value.to_vec());
+        self.positions.push(position);
+
+        if self.window_size > 0 {
+            while self.keys.len() > self.sink_tokens + self.window_size {
+                // Sinks occupy the first `sink_tokens` slots, so the oldest
+                // evictable entry always sits right after them.
+                self.keys.remove(self.sink_tokens);
+                self.values.remove(self.sink_tokens);
+                self.positions.remove(self.sink_tokens);
+            }
+        }
+
+        Ok(position)
+    }
+
+    /// Cached keys, oldest first.
+    pub fn keys(&self) -> Vec<&[f32]> {
+        self.keys.iter().map(|k| k.as_slice()).collect()
+    }
+
+    /// Cached values, oldest first.
+    pub fn values(&self) -> Vec<&[f32]> {
+        self.values.iter().map(|v| v.as_slice()).collect()
+    }
+
+    /// Absolute positions of the cached tokens, oldest first.
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// Number of cached tokens.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if nothing has been cached.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Total number of tokens appended, including evicted ones.
+    pub fn total_appended(&self) -> usize {
+        self.next_position
+    }
+
+    /// Key/value dimension.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Sliding window size (0 = unbounded).
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Number of pinned sink tokens.
+    pub fn sink_tokens(&self) -> usize {
+        self.sink_tokens
+    }
+
+    /// Removes all cached tokens and resets positions.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+        self.positions.clear();
+        self.next_position = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unbounded_cache_keeps_everything() {
+        let mut cache = KvCache::new(2, 0);
+        for i in 0..10 {
+            cache.append(&[i as f32, 0.0], &[0.0, i as f32]).unwrap();
+        }
+        assert_eq!(cache.len(), 10);
+        assert_eq!(cache.positions(), (0..10).collect::<Vec<_>>().as_slice());
+    }
+
+    #[test]
+    fn test_sinks_survive_eviction() {
+        let mut cache = KvCache::new(2, 3).with_sink_tokens(2);
+        for i in 0..10 {
+            cache.append(&[i as f32, 0.0], &[0.0, i as f32]).unwrap();
+        }
+        assert_eq!(cache.len(), 5);
+        assert_eq!(cache.positions(), &[0, 1, 7, 8, 9]);
+        assert_eq!(cache.keys()[0], &[0.0, 0.0]);
+        assert_eq!(cache.values()[1], &[0.0, 1.0]);
+        assert_eq!(cache.total_appended(), 10);
+    }
+
+    #[test]
+    fn test_dimension_check() {
+        let mut cache = KvCache::new(4, 2);
+        assert!(cache.append(&[1.0; 3], &[1.0; 4]).is_err());
+        assert!(cache.append(&[1.0; 4], &[1.0; 5]).is_err());
+        assert!(cache.is_empty());
+    }
+}
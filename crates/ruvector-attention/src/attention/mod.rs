@@ -3,8 +3,20 @@
 //! This module provides concrete implementations of various attention mechanisms
 //! including scaled dot-product attention and multi-head attention.
 
+pub mod alibi;
+pub mod causal;
+pub mod kv_cache;
 pub mod multi_head;
+#[cfg(feature = "quantized")]
+pub mod quantized;
+pub mod relative_position;
 pub mod scaled_dot_product;
 
+pub use alibi::AlibiBias;
+pub use causal::CausalAttention;
+pub use kv_cache::KvCache;
 pub use multi_head::MultiHeadAttention;
+#[cfg(feature = "quantized")]
+pub use quantized::QuantizedAttention;
+pub use relative_position::RelativePositionBias;
 pub use scaled_dot_product::ScaledDotProductAttention;
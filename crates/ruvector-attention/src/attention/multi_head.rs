@@ -1,12 +1,27 @@
 //! Multi-head attention implementation.
 //!
 //! Implements parallel attention heads for diverse representation learning.
+//! Key/value heads can be shared across groups of query heads: grouped-query
+//! attention (GQA) uses fewer K/V heads than query heads, and multi-query
+//! attention (MQA) uses a single K/V head. Both shrink the KV cache.
+//!
+//! Queries and keys can be rotated per position with rotary embeddings
+//! ([`MultiHeadAttention::with_rope`]), reusing the graph module's
+//! [`RoPEConfig`].
+//!
+//! Heads that contribute little can be found with
+//! [`MultiHeadAttention::head_importance`] and dropped with
+//! [`MultiHeadAttention::prune_heads`].
 
 use crate::{
+    config::AttentionConfig,
     error::{AttentionError, AttentionResult},
+    graph::rope::{GraphRoPE, RoPEConfig},
     traits::Attention,
 };
 
+use super::alibi::AlibiBias;
+use super::kv_cache::KvCache;
 use super::scaled_dot_product::ScaledDotProductAttention;
 
 /// Multi-head attention mechanism.
@@ -14,10 +29,21 @@ use super::scaled_dot_product::ScaledDotProductAttention;
 /// Splits the input into multiple heads, applies attention in parallel,
 /// and concatenates the results. This allows the model to attend to
 /// different representation subspaces simultaneously.
+///
+/// Keys and values have `kv_heads * head_dim` elements, and query head `h`
+/// reads K/V head `h / (num_heads / kv_heads)`.
+///
+/// After pruning, inputs keep their original layout while the output holds
+/// only the remaining heads, `num_heads() * head_dim` elements.
 pub struct MultiHeadAttention {
     dim: usize,
     num_heads: usize,
+    kv_heads: usize,
     head_dim: usize,
+    /// Heads still computed, as indices into the original `num_heads`.
+    active_heads: Vec<usize>,
+    alibi: Option<AlibiBias>,
+    rope: Option<GraphRoPE>,
 }
 
 impl MultiHeadAttention {
@@ -42,32 +68,194 @@ impl MultiHeadAttention {
         Self {
             dim,
             num_heads,
+            kv_heads: num_heads,
             head_dim: dim / num_heads,
+            active_heads: (0..num_heads).collect(),
+            alibi: None,
+            rope: None,
         }
     }
 
-    /// Splits input into multiple heads.
-    fn split_heads(&self, input: &[f32]) -> Vec<Vec<f32>> {
-        (0..self.num_heads)
-            .map(|h| {
-                let start = h * self.head_dim;
-                let end = start + self.head_dim;
-                input[start..end].to_vec()
-            })
-            .collect()
+    /// Rotates each query and key head by its position before the dot
+    /// product, so scores depend only on relative position.
+    ///
+    /// `config.dim` must equal the (even) `head_dim`.
+    pub fn with_rope(mut self, config: RoPEConfig) -> AttentionResult<Self> {
+        if !self.head_dim.is_multiple_of(2) {
+            return Err(AttentionError::InvalidConfig(format!(
+                "RoPE requires an even head_dim, got {}",
+                self.head_dim
+            )));
+        }
+        if config.dim != self.head_dim {
+            return Err(AttentionError::InvalidConfig(format!(
+                "RoPE dim {} must equal head_dim {}",
+                config.dim, self.head_dim
+            )));
+        }
+        self.rope = Some(GraphRoPE::new(config));
+        Ok(self)
     }
 
-    /// Concatenates outputs from multiple heads.
-    fn concat_heads(&self, heads: Vec<Vec<f32>>) -> Vec<f32> {
-        heads.into_iter().flatten().collect()
+    /// Rotary embedding configuration, if enabled.
+    pub fn rope(&self) -> Option<&RoPEConfig> {
+        self.rope.as_ref().map(GraphRoPE::config)
     }
-}
 
-impl Attention for MultiHeadAttention {
-    fn compute(
+    /// Applies ALiBi, giving query head `h` the slope `alibi.slopes()[h]`.
+    ///
+    /// The query is treated as the last position of the key sequence.
+    pub fn with_alibi(mut self, alibi: AlibiBias) -> AttentionResult<Self> {
+        if alibi.num_heads() != self.num_heads {
+            return Err(AttentionError::InvalidConfig(format!(
+                "ALiBi has {} slopes for {} heads",
+                alibi.num_heads(),
+                self.num_heads
+            )));
+        }
+        self.alibi = Some(alibi);
+        Ok(self)
+    }
+
+    /// Creates multi-head attention from `config`, with its K/V head count
+    /// and ALiBi slopes.
+    pub fn from_config(config: &AttentionConfig) -> AttentionResult<Self> {
+        config.validate()?;
+        let attn = Self::new(config.dim, config.num_heads).with_kv_heads(config.kv_heads())?;
+        match &config.alibi {
+            Some(alibi) => attn.with_alibi(alibi.clone()),
+            None => Ok(attn),
+        }
+    }
+
+    /// Shares each K/V head across `num_heads / kv_heads` query heads.
+    ///
+    /// `kv_heads == num_heads` is standard multi-head attention, `1` is
+    /// multi-query attention, and anything in between is grouped-query
+    /// attention. `kv_heads` must be positive and divide `num_heads`.
+    pub fn with_kv_heads(mut self, kv_heads: usize) -> AttentionResult<Self> {
+        if kv_heads == 0 || !self.num_heads.is_multiple_of(kv_heads) {
+            return Err(AttentionError::InvalidConfig(format!(
+                "kv_heads {} must divide num_heads {}",
+                kv_heads, self.num_heads
+            )));
+        }
+        self.kv_heads = kv_heads;
+        Ok(self)
+    }
+
+    /// Number of key/value heads.
+    pub fn kv_heads(&self) -> usize {
+        self.kv_heads
+    }
+
+    /// Dimension of each key and value vector (`kv_heads * head_dim`).
+    pub fn kv_dim(&self) -> usize {
+        self.kv_heads * self.head_dim
+    }
+
+    /// Creates an unbounded KV cache sized for this attention's K/V heads.
+    pub fn new_cache(&self) -> KvCache {
+        KvCache::new(self.kv_dim(), 0)
+    }
+
+    /// Creates a KV cache that keeps only the `window_size` most recent
+    /// tokens.
+    pub fn new_windowed_cache(&self, window_size: usize) -> KvCache {
+        KvCache::new(self.kv_dim(), window_size)
+    }
+
+    /// Decodes one token: appends its key/value to `cache` and attends the
+    /// query over the cached history.
+    ///
+    /// The query sits at the new token's absolute position and cached keys
+    /// keep theirs, so RoPE and ALiBi see the same distances as a full
+    /// recompute even after the window has evicted older tokens. Use one
+    /// cache per layer.
+    pub fn compute_incremental(
+        &self,
+        query: &[f32],
+        key: &[f32],
+        value: &[f32],
+        cache: &mut KvCache,
+    ) -> AttentionResult<Vec<f32>> {
+        if cache.dim() != self.kv_dim() {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.kv_dim(),
+                actual: cache.dim(),
+            });
+        }
+        let query_pos = cache.append(key, value)?;
+        let positions = cache.positions().to_vec();
+        self.compute_with_positions(query, query_pos, &cache.keys(), &positions, &cache.values())
+    }
+
+    /// Keeps only the listed heads, dropping the rest from the output.
+    ///
+    /// `keep` indexes the current heads (`0..num_heads()`), so pruning can be
+    /// applied repeatedly. Remaining heads produce exactly the output they
+    /// did before, in their original order.
+    pub fn prune_heads(&mut self, keep: &[usize]) -> AttentionResult<()> {
+        let mut keep = keep.to_vec();
+        keep.sort_unstable();
+        keep.dedup();
+        if keep.is_empty() {
+            return Err(AttentionError::InvalidConfig(
+                "prune_heads must keep at least one head".to_string(),
+            ));
+        }
+        if let Some(&bad) = keep.iter().find(|&&h| h >= self.active_heads.len()) {
+            return Err(AttentionError::InvalidConfig(format!(
+                "head {} out of range for {} heads",
+                bad,
+                self.active_heads.len()
+            )));
+        }
+        self.active_heads = keep.iter().map(|&h| self.active_heads[h]).collect();
+        Ok(())
+    }
+
+    /// Scores each current head by the mean L2 norm of its output.
+    ///
+    /// Every probe attends over all probes, with each head reading its own
+    /// `head_dim` slice of the probes as query, keys and values. Heads whose
+    /// subspace carries no signal score 0. Returns one score per head in
+    /// `0..num_heads()`, all zero if there are no probes.
+    pub fn head_importance(&self, probe_inputs: &[Vec<f32>]) -> AttentionResult<Vec<f32>> {
+        if let Some(bad) = probe_inputs.iter().find(|p| p.len() != self.dim) {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.dim,
+                actual: bad.len(),
+            });
+        }
+        if probe_inputs.is_empty() {
+            return Ok(vec![0.0; self.active_heads.len()]);
+        }
+
+        let head_attn = ScaledDotProductAttention::new(self.head_dim);
+        self.active_heads
+            .iter()
+            .map(|&h| {
+                let range = h * self.head_dim..(h + 1) * self.head_dim;
+                let slices: Vec<&[f32]> = probe_inputs.iter().map(|p| &p[range.clone()]).collect();
+                let mut total = 0.0;
+                for &q in &slices {
+                    let out = head_attn.compute(q, &slices, &slices)?;
+                    total += out.iter().map(|x| x * x).sum::<f32>().sqrt();
+                }
+                Ok(total / probe_inputs.len() as f32)
+            })
+            .collect()
+    }
+
+    /// Computes attention for a query at `query_pos` over keys at
+    /// `key_positions`. Positions drive RoPE rotation and ALiBi distances.
+    pub fn compute_with_positions(
         &self,
         query: &[f32],
+        query_pos: usize,
         keys: &[&[f32]],
+        key_positions: &[usize],
         values: &[&[f32]],
     ) -> AttentionResult<Vec<f32>> {
         if query.len() != self.dim {
@@ -77,24 +265,68 @@ impl Attention for MultiHeadAttention {
             });
         }
 
-        // Split query into heads
-        let query_heads = self.split_heads(query);
+        let kv_dim = self.kv_dim();
+        if let Some(bad) = keys.iter().chain(values.iter()).find(|x| x.len() != kv_dim) {
+            return Err(AttentionError::DimensionMismatch {
+                expected: kv_dim,
+                actual: bad.len(),
+            });
+        }
 
-        // Split keys and values
-        let key_heads: Vec<Vec<Vec<f32>>> = keys.iter().map(|k| self.split_heads(k)).collect();
+        if key_positions.len() != keys.len() {
+            return Err(AttentionError::InvalidConfig(format!(
+                "{} key positions for {} keys",
+                key_positions.len(),
+                keys.len()
+            )));
+        }
+
+        // Split query into heads, rotated to the query position
+        let query_heads: Vec<Vec<f32>> = self
+            .split_heads(query, self.num_heads)
+            .into_iter()
+            .map(|q| self.rotate(q, query_pos))
+            .collect();
 
-        let value_heads: Vec<Vec<Vec<f32>>> = values.iter().map(|v| self.split_heads(v)).collect();
+        // Split keys and values into their (possibly fewer) K/V heads
+        let key_heads: Vec<Vec<Vec<f32>>> = keys
+            .iter()
+            .zip(key_positions)
+            .map(|(k, &pos)| {
+                self.split_heads(k, self.kv_heads)
+                    .into_iter()
+                    .map(|kh| self.rotate(kh, pos))
+                    .collect()
+            })
+            .collect();
 
-        // Compute attention for each head
+        let value_heads: Vec<Vec<Vec<f32>>> = values
+            .iter()
+            .map(|v| self.split_heads(v, self.kv_heads))
+            .collect();
+
+        // Compute attention for each remaining head
+        let group_size = self.num_heads / self.kv_heads;
         let mut head_outputs = Vec::new();
-        for h in 0..self.num_heads {
-            let head_attn = ScaledDotProductAttention::new(self.head_dim);
+        for &h in &self.active_heads {
+            let mut head_attn = ScaledDotProductAttention::new(self.head_dim);
+            if let Some(alibi) = &self.alibi {
+                head_attn = head_attn.with_alibi(alibi, h)?;
+            }
+            let kv = h / group_size;
 
-            let head_keys: Vec<&[f32]> = key_heads.iter().map(|kh| kh[h].as_slice()).collect();
+            let head_keys: Vec<&[f32]> = key_heads.iter().map(|kh| kh[kv].as_slice()).collect();
 
-            let head_values: Vec<&[f32]> = value_heads.iter().map(|vh| vh[h].as_slice()).collect();
+            let head_values: Vec<&[f32]> = value_heads.iter().map(|vh| vh[kv].as_slice()).collect();
 
-            let head_out = head_attn.compute(&query_heads[h], &head_keys, &head_values)?;
+            let head_out = head_attn.compute_with_positions(
+                &query_heads[h],
+                query_pos,
+                &head_keys,
+                key_positions,
+                &head_values,
+                None,
+            )?;
             head_outputs.push(head_out);
         }
 
@@ -102,6 +334,50 @@ impl Attention for MultiHeadAttention {
         Ok(self.concat_heads(head_outputs))
     }
 
+    /// Applies RoPE to one head vector, or returns it unchanged.
+    fn rotate(&self, head: Vec<f32>, position: usize) -> Vec<f32> {
+        match &self.rope {
+            Some(rope) => rope.apply_rotary(&head, position),
+            None => head,
+        }
+    }
+
+    /// Splits input into `num_heads` heads of `head_dim` elements.
+    fn split_heads(&self, input: &[f32], num_heads: usize) -> Vec<Vec<f32>> {
+        (0..num_heads)
+            .map(|h| {
+                let start = h * self.head_dim;
+                let end = start + self.head_dim;
+                input[start..end].to_vec()
+            })
+            .collect()
+    }
+
+    /// Concatenates outputs from multiple heads.
+    fn concat_heads(&self, heads: Vec<Vec<f32>>) -> Vec<f32> {
+        heads.into_iter().flatten().collect()
+    }
+}
+
+impl Attention for MultiHeadAttention {
+    /// Treats the query as the last position and keys as positions
+    /// `0..keys.len()`.
+    fn compute(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<Vec<f32>> {
+        let key_positions: Vec<usize> = (0..keys.len()).collect();
+        self.compute_with_positions(
+            query,
+            keys.len().saturating_sub(1),
+            keys,
+            &key_positions,
+            values,
+        )
+    }
+
     fn compute_with_mask(
         &self,
         query: &[f32],
@@ -118,7 +394,7 @@ impl Attention for MultiHeadAttention {
     }
 
     fn num_heads(&self) -> usize {
-        self.num_heads
+        self.active_heads.len()
     }
 }
 
@@ -141,9 +417,321 @@ mod tests {
         assert_eq!(result.len(), 8);
     }
 
+    #[test]
+    fn test_alibi_favors_recent_keys_per_head() {
+        let attn = MultiHeadAttention::new(4, 2)
+            .with_alibi(AlibiBias::with_slopes(vec![0.0, 4.0]).unwrap())
+            .unwrap();
+        // Identical keys, so only the bias separates them.
+        let keys_data = [[1.0_f32; 4]; 3];
+        let values_data: Vec<Vec<f32>> = (0..3).map(|j| vec![j as f32; 4]).collect();
+        let keys: Vec<&[f32]> = keys_data.iter().map(|k| k.as_slice()).collect();
+        let values: Vec<&[f32]> = values_data.iter().map(|v| v.as_slice()).collect();
+
+        let out = attn.compute(&[1.0; 4], &keys, &values).unwrap();
+        // Head 0 (slope 0) averages uniformly; head 1 leans to the last key.
+        assert!((out[0] - 1.0).abs() < 1e-5);
+        assert!(out[2] > 1.9);
+
+        assert!(MultiHeadAttention::new(4, 2)
+            .with_alibi(AlibiBias::new(4).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_rope_scores_invariant_to_shift() {
+        let rope = RoPEConfig::builder().dim(4).max_position(64).build();
+        let attn = MultiHeadAttention::new(4, 1).with_rope(rope).unwrap();
+        let x0 = [0.3_f32, -1.2, 0.8, 0.5];
+        let x1 = [1.0_f32, 0.4, -0.6, 0.9];
+        let score = |q_pos: usize, k_pos: usize| -> f32 {
+            let q = attn.rotate(x1.to_vec(), q_pos);
+            let k = attn.rotate(x0.to_vec(), k_pos);
+            q.iter().zip(&k).map(|(a, b)| a * b).sum()
+        };
+
+        // Only the relative distance matters.
+        for shift in [1, 7, 30] {
+            assert!((score(3, 1) - score(3 + shift, 1 + shift)).abs() < 1e-4);
+        }
+        assert!((score(3, 1) - score(3, 2)).abs() > 1e-3);
+
+        let keys = [x0.as_slice(), x1.as_slice()];
+        let values = [x0.as_slice(), x1.as_slice()];
+        let near = attn
+            .compute_with_positions(&x1, 1, &keys, &[0, 1], &values)
+            .unwrap();
+        let shifted = attn
+            .compute_with_positions(&x1, 21, &keys, &[20, 21], &values)
+            .unwrap();
+        for (a, b) in near.iter().zip(&shifted) {
+            assert!((a - b).abs() < 1e-4);
+        }
+        assert_eq!(attn.compute(&x1, &keys, &values).unwrap(), near);
+    }
+
+    #[test]
+    fn test_rope_requires_even_head_dim() {
+        let odd = MultiHeadAttention::new(6, 2).with_rope(RoPEConfig::builder().dim(3).build());
+        let err = odd.err().unwrap().to_string();
+        assert!(err.contains("even head_dim"), "{}", err);
+
+        let mismatched = MultiHeadAttention::new(8, 2).with_rope(RoPEConfig::default());
+        assert!(mismatched.is_err());
+        let ok = MultiHeadAttention::new(8, 2)
+            .with_rope(RoPEConfig::builder().dim(4).build())
+            .unwrap();
+        assert_eq!(ok.rope().unwrap().dim, 4);
+    }
+
+    #[test]
+    fn test_gqa_config_matches_standard_mha() {
+        let config = AttentionConfig::builder()
+            .dim(8)
+            .num_heads(4)
+            .num_kv_heads(4)
+            .build()
+            .unwrap();
+        let gqa = MultiHeadAttention::from_config(&config).unwrap();
+        let mha = MultiHeadAttention::new(8, 4);
+
+        let query: Vec<f32> = (0..8).map(|i| (i as f32 * 0.37).sin()).collect();
+        let kv: Vec<Vec<f32>> = (0..3)
+            .map(|t| (0..8).map(|i| ((t * 8 + i) as f32 * 0.21).cos()).collect())
+            .collect();
+        let refs: Vec<&[f32]> = kv.iter().map(|v| v.as_slice()).collect();
+
+        assert_eq!(
+            gqa.compute(&query, &refs, &refs).unwrap(),
+            mha.compute(&query, &refs, &refs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_gqa_config_output_shape() {
+        let config = AttentionConfig::builder()
+            .dim(16)
+            .num_heads(8)
+            .num_kv_heads(2)
+            .build()
+            .unwrap();
+        let attn = MultiHeadAttention::from_config(&config).unwrap();
+        assert_eq!(attn.kv_heads(), 2);
+        assert_eq!(attn.kv_dim(), 4);
+
+        let query = vec![0.25_f32; 16];
+        let kv: Vec<Vec<f32>> = (0..5).map(|t| vec![t as f32 * 0.1; 4]).collect();
+        let refs: Vec<&[f32]> = kv.iter().map(|v| v.as_slice()).collect();
+        let out = attn.compute(&query, &refs, &refs).unwrap();
+        assert_eq!(out.len(), 16);
+
+        // Full-width K/V no longer fits the grouped layout.
+        let wide = vec![0.0_f32; 16];
+        assert!(attn.compute(&query, &[&wide], &[&wide]).is_err());
+    }
+
     #[test]
     #[should_panic(expected = "divisible")]
     fn test_invalid_heads() {
         MultiHeadAttention::new(10, 3);
     }
+
+    #[test]
+    fn test_multi_query_single_kv_head() {
+        let attn = MultiHeadAttention::new(8, 4).with_kv_heads(1).unwrap();
+        assert_eq!(attn.kv_dim(), 2);
+
+        let query = vec![0.5_f32; 8];
+        let keys: Vec<Vec<f32>> = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let values: Vec<Vec<f32>> = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        let key_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let value_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+
+        let result = attn.compute(&query, &key_refs, &value_refs).unwrap();
+        assert_eq!(result.len(), 8);
+        // Identical query heads over one shared K/V head give identical outputs.
+        for head in result.chunks(2) {
+            assert_eq!(head, &result[..2]);
+        }
+
+        // Full-width keys no longer match the shared K/V head.
+        let wide = vec![1.0_f32; 8];
+        assert!(attn.compute(&query, &[&wide], &[&wide]).is_err());
+    }
+
+    #[test]
+    fn test_grouped_query_shares_kv_within_group() {
+        let gqa = MultiHeadAttention::new(8, 4).with_kv_heads(2).unwrap();
+        let query: Vec<f32> = (0..8).map(|i| i as f32 * 0.1).collect();
+        let keys: Vec<Vec<f32>> = vec![vec![1.0, 0.0, 0.2, 0.3], vec![0.0, 1.0, -0.4, 0.9]];
+        let values: Vec<Vec<f32>> = vec![vec![1.0, 2.0, 3.0, 4.0], vec![5.0, 6.0, 7.0, 8.0]];
+        let key_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let value_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+        let result = gqa.compute(&query, &key_refs, &value_refs).unwrap();
+
+        // Equivalent MHA: every K/V head repeated for each query head in its group.
+        let expand = |x: &Vec<f32>| [&x[0..2], &x[0..2], &x[2..4], &x[2..4]].concat();
+        let wide_keys: Vec<Vec<f32>> = keys.iter().map(expand).collect();
+        let wide_values: Vec<Vec<f32>> = values.iter().map(expand).collect();
+        let wide_key_refs: Vec<&[f32]> = wide_keys.iter().map(|k| k.as_slice()).collect();
+        let wide_value_refs: Vec<&[f32]> = wide_values.iter().map(|v| v.as_slice()).collect();
+        let expected = MultiHeadAttention::new(8, 4)
+            .compute(&query, &wide_key_refs, &wide_value_refs)
+            .unwrap();
+
+        for (a, b) in result.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_kv_cache_shrinks_with_kv_heads() {
+        let mha = MultiHeadAttention::new(16, 8);
+        assert_eq!(mha.kv_heads(), 8);
+        assert_eq!(mha.new_cache().dim(), 16);
+
+        for (kv_heads, expected_dim) in [(8, 16), (4, 8), (2, 4), (1, 2)] {
+            let attn = MultiHeadAttention::new(16, 8)
+                .with_kv_heads(kv_heads)
+                .unwrap();
+            let mut cache = attn.new_cache();
+            assert_eq!(cache.dim(), expected_dim);
+            let kv = vec![0.0_f32; expected_dim];
+            cache.append(&kv, &kv).unwrap();
+            assert!(cache.append(&vec![0.0; expected_dim + 1], &kv).is_err());
+        }
+
+        assert!(MultiHeadAttention::new(16, 8).with_kv_heads(3).is_err());
+        assert!(MultiHeadAttention::new(16, 8).with_kv_heads(0).is_err());
+    }
+
+    fn decode_inputs(n: usize, dim: usize, kv_dim: usize) -> [Vec<Vec<f32>>; 3] {
+        let gen = |d: usize, salt: f32| -> Vec<Vec<f32>> {
+            (0..n)
+                .map(|t| {
+                    (0..d)
+                        .map(|i| ((t * d + i) as f32 * 0.37 + salt).sin())
+                        .collect()
+                })
+                .collect()
+        };
+        [gen(dim, 0.0), gen(kv_dim, 1.0), gen(kv_dim, 2.0)]
+    }
+
+    #[test]
+    fn test_incremental_matches_full_recompute() {
+        let rope = RoPEConfig::builder().dim(2).max_position(32).build();
+        let attn = MultiHeadAttention::new(8, 4)
+            .with_kv_heads(2)
+            .unwrap()
+            .with_alibi(AlibiBias::new(4).unwrap())
+            .unwrap()
+            .with_rope(rope)
+            .unwrap();
+        let [queries, keys, values] = decode_inputs(10, 8, attn.kv_dim());
+
+        let mut cache = attn.new_cache();
+        for t in 0..10 {
+            let inc = attn
+                .compute_incremental(&queries[t], &keys[t], &values[t], &mut cache)
+                .unwrap();
+            let key_refs: Vec<&[f32]> = keys[..=t].iter().map(|k| k.as_slice()).collect();
+            let value_refs: Vec<&[f32]> = values[..=t].iter().map(|v| v.as_slice()).collect();
+            let full = attn.compute(&queries[t], &key_refs, &value_refs).unwrap();
+            for (a, b) in inc.iter().zip(&full) {
+                assert!((a - b).abs() < 1e-6, "step {}", t);
+            }
+        }
+        assert_eq!(cache.len(), 10);
+    }
+
+    #[test]
+    fn test_incremental_sliding_window() {
+        let rope = RoPEConfig::builder().dim(4).max_position(32).build();
+        let attn = MultiHeadAttention::new(8, 2).with_rope(rope).unwrap();
+        let [queries, keys, values] = decode_inputs(8, 8, 8);
+
+        let mut cache = attn.new_windowed_cache(3);
+        for t in 0..8 {
+            let inc = attn
+                .compute_incremental(&queries[t], &keys[t], &values[t], &mut cache)
+                .unwrap();
+            let start = t.saturating_sub(2);
+            let key_refs: Vec<&[f32]> = keys[start..=t].iter().map(|k| k.as_slice()).collect();
+            let value_refs: Vec<&[f32]> = values[start..=t].iter().map(|v| v.as_slice()).collect();
+            let positions: Vec<usize> = (start..=t).collect();
+            let full = attn
+                .compute_with_positions(&queries[t], t, &key_refs, &positions, &value_refs)
+                .unwrap();
+            assert_eq!(inc, full, "step {}", t);
+            assert_eq!(cache.positions(), positions.as_slice());
+        }
+
+        let mut wrong = KvCache::new(4, 0);
+        assert!(attn
+            .compute_incremental(&queries[0], &keys[0], &values[0], &mut wrong)
+            .is_err());
+    }
+
+    #[test]
+    fn test_pruning_preserves_remaining_heads() {
+        let mut attn = MultiHeadAttention::new(8, 4);
+        let query: Vec<f32> = (0..8).map(|i| (i as f32 * 0.7).sin()).collect();
+        let keys: Vec<Vec<f32>> = (0..3)
+            .map(|j| (0..8).map(|i| ((i + j * 8) as f32 * 0.3).cos()).collect())
+            .collect();
+        let values: Vec<Vec<f32>> = (0..3)
+            .map(|j| (0..8).map(|i| (i * j) as f32 * 0.1).collect())
+            .collect();
+        let key_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let value_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+        let before = attn.compute(&query, &key_refs, &value_refs).unwrap();
+
+        attn.prune_heads(&[3, 1]).unwrap();
+        assert_eq!(attn.num_heads(), 2);
+        let after = attn.compute(&query, &key_refs, &value_refs).unwrap();
+        assert_eq!(after, [&before[2..4], &before[6..8]].concat());
+
+        // Indices refer to the current heads: keep original head 3.
+        attn.prune_heads(&[1]).unwrap();
+        assert_eq!(attn.num_heads(), 1);
+        let single = attn.compute(&query, &key_refs, &value_refs).unwrap();
+        assert_eq!(single, &before[6..8]);
+
+        assert!(attn.prune_heads(&[]).is_err());
+        assert!(attn.prune_heads(&[1]).is_err());
+    }
+
+    #[test]
+    fn test_head_importance_ranks_dead_head_lowest() {
+        let attn = MultiHeadAttention::new(8, 4);
+        // Head 2's subspace is all zeros in every probe.
+        let probes: Vec<Vec<f32>> = (0..4)
+            .map(|j| {
+                (0..8)
+                    .map(|i| {
+                        if i / 2 == 2 {
+                            0.0
+                        } else {
+                            1.0 + (i + j) as f32 * 0.25
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let scores = attn.head_importance(&probes).unwrap();
+        assert_eq!(scores.len(), 4);
+        assert_eq!(scores[2], 0.0);
+        let lowest = (0..4)
+            .min_by(|&a, &b| scores[a].partial_cmp(&scores[b]).unwrap())
+            .unwrap();
+        assert_eq!(lowest, 2);
+
+        let mut pruned = MultiHeadAttention::new(8, 4);
+        pruned.prune_heads(&[0, 1, 3]).unwrap();
+        let pruned_scores = pruned.head_importance(&probes).unwrap();
+        assert_eq!(pruned_scores, vec![scores[0], scores[1], scores[3]]);
+        assert!(attn.head_importance(&[vec![0.0; 7]]).is_err());
+    }
 }
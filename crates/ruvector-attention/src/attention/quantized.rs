@@ -0,0 +1,321 @@
+//! Quantized int8 scaled dot-product attention for inference.
+//!
+//! Queries, keys, values and attention weights are quantized to int8 and
+//! both matrix products (QK^T and the weighted value sum) run through the
+//! int8 GEMM kernel from `ruvector-mincut-gated-transformer`, accumulating
+//! in integers before dequantizing:
+//!
+//! - each key row gets its own scale, as does each value channel;
+//! - attention weights are scaled by their maximum;
+//! - the query uses a static activation scale, set by [`QuantizedAttention::calibrate`].
+//!
+//! Without calibration, queries are assumed to lie in `[-1, 1]`.
+//!
+//! Available with the `quantized` feature.
+
+use ruvector_mincut_gated_transformer::kernel::qgemm::{
+    compute_scale, dequantize_i32_to_f32, qgemm_i8, quantize_f32_to_i8,
+};
+
+use crate::{
+    error::{AttentionError, AttentionResult},
+    traits::Attention,
+};
+
+/// Activation range assumed for queries before calibration.
+const DEFAULT_ACTIVATION_RANGE: f32 = 1.0;
+
+/// Int8 approximation of [`ScaledDotProductAttention`](super::ScaledDotProductAttention).
+pub struct QuantizedAttention {
+    dim: usize,
+    activation_scale: f32,
+}
+
+/// A row-major int8 matrix with one scale per row.
+struct QuantizedRows {
+    data: Vec<i8>,
+    scales: Vec<f32>,
+}
+
+impl QuantizedRows {
+    fn from_rows<'a>(rows: impl ExactSizeIterator<Item = &'a [f32]>, width: usize) -> Self {
+        let mut data = vec![0i8; rows.len() * width];
+        let mut scales = Vec::with_capacity(rows.len());
+        for (row, out) in rows.zip(data.chunks_exact_mut(width)) {
+            let scale = compute_scale(row);
+            quantize_f32_to_i8(row, scale, out);
+            scales.push(scale);
+        }
+        Self { data, scales }
+    }
+}
+
+impl QuantizedAttention {
+    /// Creates an uncalibrated quantized attention for `dim`-dimensional inputs.
+    pub fn new(dim: usize) -> Self {
+        Self {
+            dim,
+            activation_scale: DEFAULT_ACTIVATION_RANGE / 127.0,
+        }
+    }
+
+    /// Sets the query activation scale from representative queries.
+    ///
+    /// The scale maps the largest magnitude seen in `samples` to 127, so
+    /// queries within the calibrated range are never clipped.
+    pub fn calibrate(&mut self, samples: &[&[f32]]) -> AttentionResult<()> {
+        if samples.is_empty() {
+            return Err(AttentionError::EmptyInput(
+                "calibration samples".to_string(),
+            ));
+        }
+        let mut max_abs = 0.0f32;
+        for sample in samples {
+            if sample.len() != self.dim {
+                return Err(AttentionError::DimensionMismatch {
+                    expected: self.dim,
+                    actual: sample.len(),
+                });
+            }
+            max_abs = sample.iter().fold(max_abs, |m, v| m.max(v.abs()));
+        }
+        self.activation_scale = compute_scale(&[max_abs]);
+        Ok(())
+    }
+
+    /// Scale applied to queries before int8 rounding.
+    pub fn activation_scale(&self) -> f32 {
+        self.activation_scale
+    }
+
+    /// Int8 scores QK^T / √d, dequantized to f32.
+    fn compute_scores(&self, query: &[f32], keys: &[&[f32]]) -> Vec<f32> {
+        let n = keys.len();
+        let mut q = vec![0i8; self.dim];
+        quantize_f32_to_i8(query, self.activation_scale, &mut q);
+        let k = QuantizedRows::from_rows(keys.iter().copied(), self.dim);
+
+        // Unit scales keep the raw i32 dot products; the real scales are
+        // applied in f32 by the dequantization below.
+        let mut acc = vec![0i32; n];
+        qgemm_i8(
+            1,
+            n,
+            self.dim,
+            &q,
+            1.0,
+            &k.data,
+            &vec![1.0; n],
+            None,
+            &mut acc,
+        );
+
+        let mut scores = vec![0.0f32; n];
+        dequantize_i32_to_f32(&acc, self.activation_scale, &k.scales, &mut scores);
+        let inv_sqrt_d = 1.0 / (self.dim as f32).sqrt();
+        scores.iter_mut().for_each(|s| *s *= inv_sqrt_d);
+        scores
+    }
+
+    /// Int8 weighted sum of values, dequantized to f32.
+    fn weighted_values(&self, weights: &[f32], values: &[&[f32]]) -> Vec<f32> {
+        let n = values.len();
+        let w_scale = compute_scale(weights);
+        let mut w = vec![0i8; n];
+        quantize_f32_to_i8(weights, w_scale, &mut w);
+
+        // Transpose so each value channel is one row with its own scale.
+        let channels: Vec<Vec<f32>> = (0..self.dim)
+            .map(|d| values.iter().map(|v| v[d]).collect())
+            .collect();
+        let v = QuantizedRows::from_rows(channels.iter().map(Vec::as_slice), n);
+
+        let mut acc = vec![0i32; self.dim];
+        qgemm_i8(
+            1,
+            self.dim,
+            n,
+            &w,
+            1.0,
+            &v.data,
+            &vec![1.0; self.dim],
+            None,
+            &mut acc,
+        );
+
+        let mut output = vec![0.0f32; self.dim];
+        dequantize_i32_to_f32(&acc, w_scale, &v.scales, &mut output);
+        output
+    }
+
+    fn softmax(scores: &[f32]) -> Vec<f32> {
+        let max_score = scores.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+        let exp_scores: Vec<f32> = scores.iter().map(|s| (s - max_score).exp()).collect();
+        let sum: f32 = exp_scores.iter().sum();
+        exp_scores.iter().map(|e| e / sum).collect()
+    }
+
+    fn validate(&self, query: &[f32], keys: &[&[f32]], values: &[&[f32]]) -> AttentionResult<()> {
+        if query.len() != self.dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.dim,
+                actual: query.len(),
+            });
+        }
+        if keys.is_empty() || values.is_empty() {
+            return Err(AttentionError::EmptyInput("keys or values".to_string()));
+        }
+        if keys.len() != values.len() {
+            return Err(AttentionError::DimensionMismatch {
+                expected: keys.len(),
+                actual: values.len(),
+            });
+        }
+        for row in keys.iter().chain(values.iter()) {
+            if row.len() != self.dim {
+                return Err(AttentionError::DimensionMismatch {
+                    expected: self.dim,
+                    actual: row.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Attention for QuantizedAttention {
+    fn compute(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<Vec<f32>> {
+        self.compute_with_mask(query, keys, values, None)
+    }
+
+    fn compute_with_mask(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+        mask: Option<&[bool]>,
+    ) -> AttentionResult<Vec<f32>> {
+        self.validate(query, keys, values)?;
+
+        let mut scores = self.compute_scores(query, keys);
+        if let Some(mask) = mask {
+            if mask.len() != keys.len() {
+                return Err(AttentionError::InvalidMask {
+                    expected: format!("{}", keys.len()),
+                    actual: format!("{}", mask.len()),
+                });
+            }
+            for (score, &m) in scores.iter_mut().zip(mask.iter()) {
+                if !m {
+                    *score = f32::NEG_INFINITY;
+                }
+            }
+        }
+
+        let weights = Self::softmax(&scores);
+        Ok(self.weighted_values(&weights, values))
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attention::ScaledDotProductAttention;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_rows(rng: &mut StdRng, n: usize, dim: usize, range: f32) -> Vec<Vec<f32>> {
+        (0..n)
+            .map(|_| (0..dim).map(|_| rng.gen_range(-range..range)).collect())
+            .collect()
+    }
+
+    fn relative_error(approx: &[f32], exact: &[f32]) -> f32 {
+        let diff: f32 = approx.iter().zip(exact).map(|(a, e)| (a - e).powi(2)).sum();
+        let norm: f32 = exact.iter().map(|e| e * e).sum();
+        (diff / norm).sqrt()
+    }
+
+    /// Mean relative error against the f32 path over random queries.
+    fn mean_error(attn: &QuantizedAttention, queries: &[Vec<f32>], kv: &[Vec<f32>]) -> f32 {
+        let exact = ScaledDotProductAttention::new(attn.dim());
+        let keys: Vec<&[f32]> = kv.iter().map(Vec::as_slice).collect();
+        let total: f32 = queries
+            .iter()
+            .map(|q| {
+                let a = attn.compute(q, &keys, &keys).unwrap();
+                let e = exact.compute(q, &keys, &keys).unwrap();
+                relative_error(&a, &e)
+            })
+            .sum();
+        total / queries.len() as f32
+    }
+
+    #[test]
+    fn test_matches_f32_within_bound() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let dim = 32;
+        let kv = random_rows(&mut rng, 16, dim, 1.0);
+        let queries = random_rows(&mut rng, 20, dim, 1.0);
+
+        let attn = QuantizedAttention::new(dim);
+        let err = mean_error(&attn, &queries, &kv);
+        assert!(err < 0.05, "relative error {err}");
+
+        // Masked positions are excluded just like the f32 path.
+        let keys: Vec<&[f32]> = kv.iter().map(Vec::as_slice).collect();
+        let mask: Vec<bool> = (0..kv.len()).map(|i| i % 3 != 0).collect();
+        let a = attn
+            .compute_with_mask(&queries[0], &keys, &keys, Some(&mask))
+            .unwrap();
+        let e = ScaledDotProductAttention::new(dim)
+            .compute_with_mask(&queries[0], &keys, &keys, Some(&mask))
+            .unwrap();
+        assert!(relative_error(&a, &e) < 0.05);
+    }
+
+    #[test]
+    fn test_calibration_reduces_error() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let dim = 16;
+        let kv = random_rows(&mut rng, 12, dim, 1.0);
+        // Queries well outside the default [-1, 1] range get clipped.
+        let queries = random_rows(&mut rng, 20, dim, 4.0);
+        let calibration = random_rows(&mut rng, 50, dim, 4.0);
+
+        let mut attn = QuantizedAttention::new(dim);
+        let before = mean_error(&attn, &queries, &kv);
+
+        let samples: Vec<&[f32]> = calibration.iter().map(Vec::as_slice).collect();
+        attn.calibrate(&samples).unwrap();
+        let after = mean_error(&attn, &queries, &kv);
+
+        assert!(attn.activation_scale() > 1.0 / 127.0);
+        assert!(
+            after < before,
+            "calibrated {after} vs uncalibrated {before}"
+        );
+        assert!(after < 0.05, "calibrated relative error {after}");
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let mut attn = QuantizedAttention::new(4);
+        assert!(attn.calibrate(&[]).is_err());
+        assert!(attn.calibrate(&[&[1.0, 2.0]]).is_err());
+
+        let key = [1.0f32; 4];
+        let short = [1.0f32; 3];
+        assert!(attn.compute(&[0.0; 4], &[&key], &[&short]).is_err());
+        assert!(attn.compute(&[0.0; 4], &[], &[]).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+//! T5-style learned relative position bias.
+//!
+//! Instead of encoding absolute positions into the inputs, a small learned
+//! table maps the relative distance between query and key to a per-head
+//! scalar that is added to the attention logits. Distances are bucketed:
+//! small distances get their own bucket, larger ones share logarithmically
+//! spaced buckets up to `max_distance`, beyond which everything falls into
+//! the last bucket.
+
+use crate::error::{AttentionError, AttentionResult};
+
+/// Learned relative position bias with logarithmic distance bucketing.
+///
+/// The table is laid out bucket-major: entry `bucket * num_heads + head`.
+#[derive(Clone, Debug)]
+pub struct RelativePositionBias {
+    num_heads: usize,
+    num_buckets: usize,
+    max_distance: usize,
+    bidirectional: bool,
+    table: Vec<f32>,
+    grads: Vec<f32>,
+}
+
+impl RelativePositionBias {
+    /// Creates a bidirectional bias with a zero-initialized table.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_heads` - Number of attention heads (one bias per head)
+    /// * `num_buckets` - Number of distance buckets (at least 4)
+    /// * `max_distance` - Distance beyond which all positions share the last bucket
+    pub fn new(num_heads: usize, num_buckets: usize, max_distance: usize) -> AttentionResult<Self> {
+        if num_heads == 0 {
+            return Err(AttentionError::InvalidConfig(
+                "num_heads must be positive".to_string(),
+            ));
+        }
+        if num_buckets < 4 {
+            return Err(AttentionError::InvalidConfig(format!(
+                "num_buckets must be at least 4, got {}",
+                num_buckets
+            )));
+        }
+        if max_distance <= num_buckets / 2 {
+            return Err(AttentionError::InvalidConfig(format!(
+                "max_distance {} must exceed the {} exact buckets",
+                max_distance,
+                num_buckets / 2
+            )));
+        }
+
+        Ok(Self {
+            num_heads,
+            num_buckets,
+            max_distance,
+            bidirectional: true,
+            table: vec![0.0; num_buckets * num_heads],
+            grads: vec![0.0; num_buckets * num_heads],
+        })
+    }
+
+    /// Switches to causal (unidirectional) bucketing, where keys after the
+    /// query all share bucket 0 and the full bucket range covers the past.
+    pub fn causal(mut self) -> Self {
+        self.bidirectional = false;
+        self
+    }
+
+    /// Number of attention heads.
+    pub fn num_heads(&self) -> usize {
+        self.num_heads
+    }
+
+    /// Number of distance buckets.
+    pub fn num_buckets(&self) -> usize {
+        self.num_buckets
+    }
+
+    /// Whether buckets distinguish keys before and after the query.
+    pub fn is_bidirectional(&self) -> bool {
+        self.bidirectional
+    }
+
+    /// Bias table, bucket-major (`bucket * num_heads + head`).
+    pub fn table(&self) -> &[f32] {
+        &self.table
+    }
+
+    /// Mutable bias table, e.g. for loading trained weights.
+    pub fn table_mut(&mut self) -> &mut [f32] {
+        &mut self.table
+    }
+
+    /// Accumulated gradients, laid out like [`table`](Self::table).
+    pub fn gradients(&self) -> &[f32] {
+        &self.grads
+    }
+
+    /// Maps a relative position (`key_pos - query_pos`) to a bucket.
+    ///
+    /// In bidirectional mode, `d` and `-d` land in buckets of the same
+    /// magnitude in opposite halves of the table. In causal mode only the
+    /// past (`relative_position <= 0`) is distinguished.
+    pub fn bucket(&self, relative_position: isize) -> usize {
+        let mut num_buckets = self.num_buckets;
+        let mut offset = 0;
+        let n = if self.bidirectional {
+            num_buckets /= 2;
+            if relative_position > 0 {
+                offset = num_buckets;
+            }
+            relative_position.unsigned_abs()
+        } else {
+            // Distance into the past; future keys collapse to 0.
+            (-relative_position).max(0) as usize
+        };
+
+        let max_exact = num_buckets / 2;
+        if n < max_exact {
+            return offset + n;
+        }
+
+        let log_ratio =
+            (n as f32 / max_exact as f32).ln() / (self.max_distance as f32 / max_exact as f32).ln();
+        let large = max_exact + (log_ratio * (num_buckets - max_exact) as f32) as usize;
+        offset + large.min(num_buckets - 1)
+    }
+
+    /// Bias added to the logit of `key_pos` for a query at `query_pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `head >= num_heads`.
+    #[inline]
+    pub fn bias(&self, head: usize, query_pos: usize, key_pos: usize) -> f32 {
+        assert!(head < self.num_heads, "head {} out of range", head);
+        let bucket = self.bucket(key_pos as isize - query_pos as isize);
+        self.table[bucket * self.num_heads + head]
+    }
+
+    /// Builds the `query_len x key_len` bias matrix for one head, row-major.
+    pub fn bias_matrix(
+        &self,
+        head: usize,
+        query_len: usize,
+        key_len: usize,
+    ) -> AttentionResult<Vec<f32>> {
+        self.check_head(head)?;
+        Ok((0..query_len)
+            .flat_map(|i| (0..key_len).map(move |j| (i, j)))
+            .map(|(i, j)| self.bias(head, i, j))
+            .collect())
+    }
+
+    /// Adds the bias for a query at `query_pos` to logits over keys `0..logits.len()`.
+    pub fn apply(&self, head: usize, query_pos: usize, logits: &mut [f32]) -> AttentionResult<()> {
+        self.check_head(head)?;
+        for (key_pos, logit) in logits.iter_mut().enumerate() {
+            *logit += self.bias(head, query_pos, key_pos);
+        }
+        Ok(())
+    }
+
+    /// Accumulates the gradient of the loss with respect to the table.
+    ///
+    /// `grad_logits[j]` is the gradient flowing into the biased logit of key
+    /// `j` for the query at `query_pos`. Because the bias is added directly,
+    /// each value is summed into the table entry its bucket was read from.
+    pub fn accumulate_gradients(
+        &mut self,
+        head: usize,
+        query_pos: usize,
+        grad_logits: &[f32],
+    ) -> AttentionResult<()> {
+        self.check_head(head)?;
+        for (key_pos, &g) in grad_logits.iter().enumerate() {
+            let bucket = self.bucket(key_pos as isize - query_pos as isize);
+            self.grads[bucket * self.num_heads + head] += g;
+        }
+        Ok(())
+    }
+
+    /// Applies accumulated gradients with plain SGD and clears them.
+    pub fn apply_gradients(&mut self, learning_rate: f32) {
+        for (w, g) in self.table.iter_mut().zip(self.grads.iter_mut()) {
+            *w -= learning_rate * *g;
+            *g = 0.0;
+        }
+    }
+
+    /// Clears accumulated gradients without updating the table.
+    pub fn zero_grad(&mut self) {
+        self.grads.iter_mut().for_each(|g| *g = 0.0);
+    }
+
+    fn check_head(&self, head: usize) -> AttentionResult<()> {
+        if head >= self.num_heads {
+            return Err(AttentionError::InvalidConfig(format!(
+                "head {} out of range for {} heads",
+                head, self.num_heads
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded(bias: &mut RelativePositionBias) {
+        for (i, w) in bias.table_mut().iter_mut().enumerate() {
+            *w = i as f32 * 0.1;
+        }
+    }
+
+    #[test]
+    fn test_translation_invariant() {
+        let mut rpb = RelativePositionBias::new(2, 32, 128).unwrap();
+        seeded(&mut rpb);
+        for head in 0..2 {
+            for delta in -40isize..40 {
+                let base = rpb.bias(head, 50, (50 + delta) as usize);
+                assert_eq!(rpb.bias(head, 100, (100 + delta) as usize), base);
+            }
+        }
+        let m = rpb.bias_matrix(1, 6, 6).unwrap();
+        for i in 1..6 {
+            for j in 1..6 {
+                assert_eq!(m[i * 6 + j], m[(i - 1) * 6 + (j - 1)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bucketing_modes() {
+        let bidir = RelativePositionBias::new(1, 32, 128).unwrap();
+        // Small distances are exact, large ones share log-spaced buckets.
+        assert_eq!(bidir.bucket(0), 0);
+        assert_eq!(bidir.bucket(-3), 3);
+        assert_eq!(bidir.bucket(-1000), bidir.bucket(-5000));
+        assert_eq!(bidir.bucket(-1000), 15);
+        assert_eq!(bidir.bucket(-100), bidir.bucket(-101));
+        // Symmetric distances share a magnitude, in opposite halves.
+        for d in 1..300isize {
+            assert_eq!(bidir.bucket(d), bidir.bucket(-d) + 16);
+        }
+
+        let causal = RelativePositionBias::new(1, 32, 128).unwrap().causal();
+        assert_eq!(causal.bucket(5), 0);
+        assert_eq!(causal.bucket(-5), 5);
+        assert_ne!(causal.bucket(-5), causal.bucket(5));
+        assert_eq!(causal.bucket(-10_000), 31);
+    }
+
+    #[test]
+    fn test_gradients_accumulate_into_buckets() {
+        let mut rpb = RelativePositionBias::new(2, 8, 16).unwrap().causal();
+        // Query at position 3 over 4 keys: distances 3, 2, 1, 0.
+        rpb.accumulate_gradients(1, 3, &[1.0, 2.0, 3.0, 4.0])
+            .unwrap();
+        // Same distances again from position 4 with one future key.
+        rpb.accumulate_gradients(1, 4, &[0.0, 0.5, 0.0, 0.0, 0.0, 7.0])
+            .unwrap();
+
+        let g = rpb.gradients();
+        let at = |bucket: usize, head: usize| g[bucket * 2 + head];
+        assert_eq!(at(3, 1), 1.0 + 0.5);
+        assert_eq!(at(2, 1), 2.0);
+        assert_eq!(at(1, 1), 3.0);
+        assert_eq!(at(0, 1), 4.0 + 7.0);
+        assert!((0..8).all(|b| at(b, 0) == 0.0));
+
+        rpb.apply_gradients(0.1);
+        assert!((rpb.table()[3 * 2 + 1] + 0.15).abs() < 1e-6);
+        assert!(rpb.gradients().iter().all(|&g| g == 0.0));
+
+        let mut logits = vec![0.0; 4];
+        rpb.apply(1, 3, &mut logits).unwrap();
+        assert!((logits[0] + 0.15).abs() < 1e-6);
+        assert!(rpb.apply(2, 0, &mut logits).is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(RelativePositionBias::new(0, 32, 128).is_err());
+        assert!(RelativePositionBias::new(4, 2, 128).is_err());
+        assert!(RelativePositionBias::new(4, 32, 8).is_err());
+    }
+}
@@ -1,20 +1,35 @@
 //! Scaled dot-product attention implementation.
 //!
-//! Implements the fundamental attention mechanism: softmax(QK^T / √d)V
+//! Implements the fundamental attention mechanism: softmax(QK^T / √d)V,
+//! optionally with an ALiBi distance penalty added to the logits.
 
 use crate::{
+    config::AttentionConfig,
     error::{AttentionError, AttentionResult},
-    traits::Attention,
+    training::AttentionDropout,
+    traits::{Attention, Gradients, TrainableAttention},
 };
 
+use super::alibi::AlibiBias;
+
 /// Scaled dot-product attention: softmax(QK^T / √d)V
 ///
 /// This is the fundamental attention mechanism used in transformers.
 /// It computes attention scores by taking the dot product of queries
 /// and keys, scaling by the square root of the dimension, applying
 /// softmax, and using the result to weight values.
+///
+/// With ALiBi enabled, `slope * |query_pos - key_pos|` is subtracted from
+/// each unmasked logit before softmax. [`Attention::compute`] and
+/// [`Attention::compute_with_mask`] treat the query as the last position;
+/// use [`compute_at`](Self::compute_at) for any other position.
+///
+/// Attention dropout, if attached, only affects
+/// [`TrainableAttention::forward`] while in training mode.
 pub struct ScaledDotProductAttention {
     dim: usize,
+    alibi_slope: Option<f32>,
+    dropout: Option<AttentionDropout>,
 }
 
 impl ScaledDotProductAttention {
@@ -24,7 +39,190 @@ impl ScaledDotProductAttention {
     ///
     /// * `dim` - The embedding dimension
     pub fn new(dim: usize) -> Self {
-        Self { dim }
+        Self {
+            dim,
+            alibi_slope: None,
+            dropout: None,
+        }
+    }
+
+    /// Creates the attention for one head of `config`, with that head's
+    /// ALiBi slope if the config enables ALiBi and attention dropout if
+    /// `config.dropout > 0`.
+    pub fn for_head(config: &AttentionConfig, head: usize) -> AttentionResult<Self> {
+        let mut attn = Self::new(config.head_dim());
+        if config.dropout > 0.0 {
+            attn = attn.with_dropout(AttentionDropout::from_config(config)?);
+        }
+        match &config.alibi {
+            Some(alibi) => attn.with_alibi(alibi, head),
+            None => Ok(attn),
+        }
+    }
+
+    /// Applies ALiBi with the slope of `head`.
+    pub fn with_alibi(mut self, alibi: &AlibiBias, head: usize) -> AttentionResult<Self> {
+        let slope = alibi.slopes().get(head).copied().ok_or_else(|| {
+            AttentionError::InvalidConfig(format!(
+                "head {} out of range for {} ALiBi slopes",
+                head,
+                alibi.num_heads()
+            ))
+        })?;
+        self.alibi_slope = Some(slope);
+        Ok(self)
+    }
+
+    /// Attaches dropout over the softmax weights of training forward passes.
+    pub fn with_dropout(mut self, dropout: AttentionDropout) -> Self {
+        self.dropout = Some(dropout);
+        self
+    }
+
+    /// Attached attention dropout, if any.
+    pub fn dropout(&self) -> Option<&AttentionDropout> {
+        self.dropout.as_ref()
+    }
+
+    /// Switches attached dropout between training and eval mode.
+    pub fn set_training(&mut self, training: bool) {
+        if let Some(dropout) = &mut self.dropout {
+            dropout.set_training(training);
+        }
+    }
+
+    /// ALiBi slope applied to the logits, if any.
+    pub fn alibi_slope(&self) -> Option<f32> {
+        self.alibi_slope
+    }
+
+    /// Computes attention for a query at absolute position `query_pos`
+    /// over keys at positions `0..keys.len()`.
+    ///
+    /// Masked keys (`false`) get zero weight and are never biased, so an
+    /// explicit causal mask composes with ALiBi.
+    pub fn compute_at(
+        &self,
+        query: &[f32],
+        query_pos: usize,
+        keys: &[&[f32]],
+        values: &[&[f32]],
+        mask: Option<&[bool]>,
+    ) -> AttentionResult<Vec<f32>> {
+        self.compute_positioned(query, query_pos, keys, None, values, mask)
+    }
+
+    /// Like [`compute_at`](Self::compute_at), with key `j` at absolute
+    /// position `key_positions[j]`.
+    pub fn compute_with_positions(
+        &self,
+        query: &[f32],
+        query_pos: usize,
+        keys: &[&[f32]],
+        key_positions: &[usize],
+        values: &[&[f32]],
+        mask: Option<&[bool]>,
+    ) -> AttentionResult<Vec<f32>> {
+        if key_positions.len() != keys.len() {
+            return Err(AttentionError::InvalidConfig(format!(
+                "{} key positions for {} keys",
+                key_positions.len(),
+                keys.len()
+            )));
+        }
+        self.compute_positioned(query, query_pos, keys, Some(key_positions), values, mask)
+    }
+
+    fn compute_positioned(
+        &self,
+        query: &[f32],
+        query_pos: usize,
+        keys: &[&[f32]],
+        key_positions: Option<&[usize]>,
+        values: &[&[f32]],
+        mask: Option<&[bool]>,
+    ) -> AttentionResult<Vec<f32>> {
+        self.check_inputs(query, keys, values)?;
+        if let Some(mask) = mask {
+            if mask.len() != keys.len() {
+                return Err(AttentionError::InvalidMask {
+                    expected: format!("{}", keys.len()),
+                    actual: format!("{}", mask.len()),
+                });
+            }
+        }
+
+        let weights = self.attention_weights(query, query_pos, keys, key_positions, mask);
+
+        // Weight values
+        let mut output = vec![0.0; self.dim];
+        for (weight, value) in weights.iter().zip(values.iter()) {
+            for (out, val) in output.iter_mut().zip(value.iter()) {
+                *out += weight * val;
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn check_inputs(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<()> {
+        if query.len() != self.dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.dim,
+                actual: query.len(),
+            });
+        }
+
+        if keys.is_empty() || values.is_empty() {
+            return Err(AttentionError::EmptyInput("keys or values".to_string()));
+        }
+
+        if keys.len() != values.len() {
+            return Err(AttentionError::DimensionMismatch {
+                expected: keys.len(),
+                actual: values.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Masked, ALiBi-biased softmax weights for a query at `query_pos`.
+    /// Keys sit at `key_positions`, or `0..keys.len()` if `None`.
+    fn attention_weights(
+        &self,
+        query: &[f32],
+        query_pos: usize,
+        keys: &[&[f32]],
+        key_positions: Option<&[usize]>,
+        mask: Option<&[bool]>,
+    ) -> Vec<f32> {
+        let mut scores = self.compute_scores(query, keys);
+
+        // Apply mask (set masked positions to very negative value)
+        if let Some(mask) = mask {
+            for (score, &m) in scores.iter_mut().zip(mask.iter()) {
+                if !m {
+                    *score = f32::NEG_INFINITY;
+                }
+            }
+        }
+
+        if let Some(slope) = self.alibi_slope {
+            for (j, score) in scores.iter_mut().enumerate() {
+                let key_pos = key_positions.map_or(j, |p| p[j]);
+                if *score != f32::NEG_INFINITY {
+                    *score -= slope * query_pos.abs_diff(key_pos) as f32;
+                }
+            }
+        }
+
+        self.softmax(&scores)
     }
 
     /// Computes attention scores (before softmax).
@@ -58,31 +256,40 @@ impl Attention for ScaledDotProductAttention {
         keys: &[&[f32]],
         values: &[&[f32]],
     ) -> AttentionResult<Vec<f32>> {
-        if query.len() != self.dim {
-            return Err(AttentionError::DimensionMismatch {
-                expected: self.dim,
-                actual: query.len(),
-            });
-        }
-
-        if keys.is_empty() || values.is_empty() {
-            return Err(AttentionError::EmptyInput("keys or values".to_string()));
-        }
+        self.compute_at(query, keys.len().saturating_sub(1), keys, values, None)
+    }
 
-        if keys.len() != values.len() {
-            return Err(AttentionError::DimensionMismatch {
-                expected: keys.len(),
-                actual: values.len(),
-            });
-        }
+    fn compute_with_mask(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+        mask: Option<&[bool]>,
+    ) -> AttentionResult<Vec<f32>> {
+        self.compute_at(query, keys.len().saturating_sub(1), keys, values, mask)
+    }
 
-        // Compute attention scores
-        let scores = self.compute_scores(query, keys);
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
 
-        // Apply softmax
-        let weights = self.softmax(&scores);
+impl TrainableAttention for ScaledDotProductAttention {
+    /// Returns the output and the (post-dropout) attention weights, with
+    /// the query at the last position as in [`Attention::compute`].
+    fn forward(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<(Vec<f32>, Vec<f32>)> {
+        self.check_inputs(query, keys, values)?;
+        let mut weights =
+            self.attention_weights(query, keys.len().saturating_sub(1), keys, None, None);
+        if let Some(dropout) = &self.dropout {
+            dropout.apply(&mut weights);
+        }
 
-        // Weight values
         let mut output = vec![0.0; self.dim];
         for (weight, value) in weights.iter().zip(values.iter()) {
             for (out, val) in output.iter_mut().zip(value.iter()) {
@@ -90,54 +297,81 @@ impl Attention for ScaledDotProductAttention {
             }
         }
 
-        Ok(output)
+        Ok((output, weights))
     }
 
-    fn compute_with_mask(
+    /// Recomputes the softmax and treats `attention_weights[j] / softmax[j]`
+    /// as the dropout factor of key `j`, so dropped keys pass no gradient
+    /// through the softmax.
+    fn backward(
         &self,
+        grad_output: &[f32],
         query: &[f32],
         keys: &[&[f32]],
         values: &[&[f32]],
-        mask: Option<&[bool]>,
-    ) -> AttentionResult<Vec<f32>> {
-        if mask.is_none() {
-            return self.compute(query, keys, values);
+        attention_weights: &[f32],
+    ) -> AttentionResult<Gradients> {
+        self.check_inputs(query, keys, values)?;
+        if grad_output.len() != self.dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.dim,
+                actual: grad_output.len(),
+            });
         }
-
-        let mask = mask.unwrap();
-        if mask.len() != keys.len() {
-            return Err(AttentionError::InvalidMask {
-                expected: format!("{}", keys.len()),
-                actual: format!("{}", mask.len()),
+        if attention_weights.len() != keys.len() {
+            return Err(AttentionError::DimensionMismatch {
+                expected: keys.len(),
+                actual: attention_weights.len(),
             });
         }
 
-        // Compute scores
-        let mut scores = self.compute_scores(query, keys);
-
-        // Apply mask (set masked positions to very negative value)
-        for (score, &m) in scores.iter_mut().zip(mask.iter()) {
-            if !m {
-                *score = f32::NEG_INFINITY;
-            }
-        }
+        let probs = self.attention_weights(query, keys.len().saturating_sub(1), keys, None, None);
 
-        // Apply softmax
-        let weights = self.softmax(&scores);
+        // dL/dw_j = dO . v_j
+        let weights_grad: Vec<f32> = values
+            .iter()
+            .map(|v| grad_output.iter().zip(v.iter()).map(|(g, x)| g * x).sum())
+            .collect();
+        // dL/dp_j = r_j * dL/dw_j with w_j = r_j * p_j
+        let probs_grad: Vec<f32> = probs
+            .iter()
+            .zip(attention_weights)
+            .zip(&weights_grad)
+            .map(|((&p, &w), &dw)| if p > 0.0 { dw * w / p } else { 0.0 })
+            .collect();
+        let dot: f32 = probs.iter().zip(&probs_grad).map(|(p, dp)| p * dp).sum();
 
-        // Weight values
-        let mut output = vec![0.0; self.dim];
-        for (weight, value) in weights.iter().zip(values.iter()) {
-            for (out, val) in output.iter_mut().zip(value.iter()) {
-                *out += weight * val;
+        let scale = 1.0 / (self.dim as f32).sqrt();
+        let mut query_grad = vec![0.0; self.dim];
+        let mut keys_grad = Vec::with_capacity(keys.len());
+        for (j, key) in keys.iter().enumerate() {
+            let ds = probs[j] * (probs_grad[j] - dot) * scale;
+            for (dq, &k) in query_grad.iter_mut().zip(key.iter()) {
+                *dq += ds * k;
             }
+            keys_grad.push(query.iter().map(|&q| ds * q).collect());
         }
 
-        Ok(output)
+        let values_grad = attention_weights
+            .iter()
+            .map(|&w| grad_output.iter().map(|&g| w * g).collect())
+            .collect();
+
+        Ok(Gradients {
+            query_grad,
+            keys_grad,
+            values_grad,
+            attention_weights_grad: Some(weights_grad),
+        })
     }
 
-    fn dim(&self) -> usize {
-        self.dim
+    /// Scaled dot-product attention has no learnable parameters.
+    fn update_parameters(
+        &mut self,
+        _gradients: &Gradients,
+        _learning_rate: f32,
+    ) -> AttentionResult<()> {
+        Ok(())
     }
 }
 
@@ -177,4 +411,156 @@ mod tests {
             .unwrap();
         assert_eq!(result.len(), 4);
     }
+
+    #[test]
+    fn test_alibi_rows_normalize() {
+        let config = AttentionConfig::builder()
+            .dim(16)
+            .num_heads(8)
+            .alibi(true)
+            .build()
+            .unwrap();
+        let keys_data: Vec<Vec<f32>> = (0..6)
+            .map(|j| vec![(j as f32 * 0.3).sin(), (j as f32 * 0.7).cos()])
+            .collect();
+        let keys: Vec<&[f32]> = keys_data.iter().map(|k| k.as_slice()).collect();
+        let query = [0.4_f32, -0.2];
+
+        for head in 0..8 {
+            let attn = ScaledDotProductAttention::for_head(&config, head).unwrap();
+            assert_eq!(attn.alibi_slope(), Some(0.5_f32.powi(head as i32 + 1)));
+            let plain = ScaledDotProductAttention::new(2);
+            for query_pos in 0..6 {
+                let w = attn.attention_weights(&query, query_pos, &keys, None, None);
+                assert!((w.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+
+                // The bias shifts each logit by -slope * distance.
+                let p = plain.attention_weights(&query, query_pos, &keys, None, None);
+                let slope = attn.alibi_slope().unwrap();
+                for j in 0..6 {
+                    let d = query_pos.abs_diff(j) as f32;
+                    let ratio = (w[j] / w[query_pos]) / (p[j] / p[query_pos]);
+                    assert!((ratio.ln() + slope * d).abs() < 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_alibi_composes_with_causal_mask() {
+        let alibi = AlibiBias::new(8).unwrap();
+        let attn = ScaledDotProductAttention::new(2)
+            .with_alibi(&alibi, 0)
+            .unwrap();
+        let keys_data: Vec<Vec<f32>> = (0..4).map(|j| vec![j as f32, 1.0]).collect();
+        let keys: Vec<&[f32]> = keys_data.iter().map(|k| k.as_slice()).collect();
+        let values: Vec<&[f32]> = keys.clone();
+        let query = [1.0_f32, 0.0];
+
+        for query_pos in 0..4 {
+            let mask: Vec<bool> = (0..4).map(|j| j <= query_pos).collect();
+            let w = attn.attention_weights(&query, query_pos, &keys, None, Some(&mask));
+            assert!(w.iter().all(|x| x.is_finite()));
+            assert!((w.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            for &weight in w.iter().skip(query_pos + 1) {
+                assert_eq!(weight, 0.0);
+            }
+            let out = attn
+                .compute_at(&query, query_pos, &keys, &values, Some(&mask))
+                .unwrap();
+            assert!(out[0] <= query_pos as f32 + 1e-5);
+        }
+
+        assert!(ScaledDotProductAttention::new(2)
+            .with_alibi(&alibi, 8)
+            .is_err());
+    }
+
+    #[test]
+    fn test_dropout_only_in_training_forward() {
+        let config = AttentionConfig::builder()
+            .dim(4)
+            .num_heads(1)
+            .dropout(0.5)
+            .build()
+            .unwrap();
+        let mut attn = ScaledDotProductAttention::for_head(&config, 0).unwrap();
+        assert_eq!(attn.dropout().map(|d| d.p()), Some(0.5));
+
+        let keys_data: Vec<Vec<f32>> = (0..64).map(|j| vec![(j as f32 * 0.1).sin(); 4]).collect();
+        let keys: Vec<&[f32]> = keys_data.iter().map(|k| k.as_slice()).collect();
+        let query = [0.5_f32, -0.5, 0.25, 1.0];
+        let expected = attn.compute(&query, &keys, &keys).unwrap();
+
+        let (_, weights) = attn.forward(&query, &keys, &keys).unwrap();
+        assert!(weights.contains(&0.0));
+
+        attn.set_training(false);
+        let (output, weights) = attn.forward(&query, &keys, &keys).unwrap();
+        assert!(weights.iter().all(|&w| w > 0.0));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_backward_with_dropout_matches_finite_difference() {
+        let dim = 4;
+        // Re-seeding per call replays the same dropout mask.
+        let make = || {
+            ScaledDotProductAttention::new(dim)
+                .with_dropout(AttentionDropout::with_seed(0.3, 11).unwrap())
+        };
+        let query = vec![0.3_f32, -0.2, 0.8, 0.1];
+        let keys: Vec<Vec<f32>> = (0..5)
+            .map(|t| {
+                (0..dim)
+                    .map(|i| ((t * dim + i) as f32 * 0.37).cos())
+                    .collect()
+            })
+            .collect();
+        let values: Vec<Vec<f32>> = (0..5)
+            .map(|t| {
+                (0..dim)
+                    .map(|i| ((t * dim + i) as f32 * 0.61).sin())
+                    .collect()
+            })
+            .collect();
+        let grad_output = vec![1.0_f32, -0.5, 0.25, 0.75];
+
+        let loss = |q: &[f32], k: &[Vec<f32>]| -> f32 {
+            let k_refs: Vec<&[f32]> = k.iter().map(|x| x.as_slice()).collect();
+            let v_refs: Vec<&[f32]> = values.iter().map(|x| x.as_slice()).collect();
+            let (out, _) = make().forward(q, &k_refs, &v_refs).unwrap();
+            out.iter().zip(&grad_output).map(|(o, g)| o * g).sum()
+        };
+
+        let attn = make();
+        let keys_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let values_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+        let (_, weights) = attn.forward(&query, &keys_refs, &values_refs).unwrap();
+        assert!(weights.contains(&0.0));
+        let grads = attn
+            .backward(&grad_output, &query, &keys_refs, &values_refs, &weights)
+            .unwrap();
+
+        let eps = 1e-2_f32;
+        for i in 0..dim {
+            let (mut plus, mut minus) = (query.clone(), query.clone());
+            plus[i] += eps;
+            minus[i] -= eps;
+            let numeric = (loss(&plus, &keys) - loss(&minus, &keys)) / (2.0 * eps);
+            assert!((grads.query_grad[i] - numeric).abs() < 2e-3);
+        }
+        for t in 0..keys.len() {
+            for i in 0..dim {
+                let (mut plus, mut minus) = (keys.clone(), keys.clone());
+                plus[t][i] += eps;
+                minus[t][i] -= eps;
+                let numeric = (loss(&query, &plus) - loss(&query, &minus)) / (2.0 * eps);
+                assert!((grads.keys_grad[t][i] - numeric).abs() < 2e-3);
+            }
+            if weights[t] == 0.0 {
+                assert!(grads.values_grad[t].iter().all(|&g| g == 0.0));
+            }
+        }
+    }
 }
@@ -5,6 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::attention::alibi::AlibiBias;
 use crate::error::{AttentionError, AttentionResult};
 
 /// Configuration for standard attention mechanisms.
@@ -14,12 +15,18 @@ pub struct AttentionConfig {
     pub dim: usize,
     /// Number of attention heads
     pub num_heads: usize,
+    /// Number of key/value heads for grouped-query attention (`None` = `num_heads`)
+    #[serde(default)]
+    pub num_kv_heads: Option<usize>,
     /// Dropout probability (0.0 to 1.0)
     pub dropout: f32,
     /// Scaling factor (default: 1/sqrt(d_k))
     pub scale: Option<f32>,
     /// Whether to use causal masking
     pub causal: bool,
+    /// ALiBi linear position bias, one slope per head
+    #[serde(default)]
+    pub alibi: Option<AlibiBias>,
 }
 
 impl AttentionConfig {
@@ -49,6 +56,14 @@ impl AttentionConfig {
             });
         }
 
+        let kv_heads = self.kv_heads();
+        if kv_heads == 0 || !self.num_heads.is_multiple_of(kv_heads) {
+            return Err(AttentionError::InvalidConfig(format!(
+                "num_kv_heads {} must divide num_heads {}",
+                kv_heads, self.num_heads
+            )));
+        }
+
         if self.dropout < 0.0 || self.dropout > 1.0 {
             return Err(AttentionError::InvalidConfig(
                 "dropout must be in range [0.0, 1.0]".to_string(),
@@ -63,6 +78,16 @@ impl AttentionConfig {
             }
         }
 
+        if let Some(alibi) = &self.alibi {
+            if alibi.num_heads() != self.num_heads {
+                return Err(AttentionError::InvalidConfig(format!(
+                    "ALiBi has {} slopes for {} heads",
+                    alibi.num_heads(),
+                    self.num_heads
+                )));
+            }
+        }
+
         Ok(())
     }
 
@@ -72,6 +97,12 @@ impl AttentionConfig {
         self.dim / self.num_heads
     }
 
+    /// Returns the number of key/value heads.
+    #[inline]
+    pub fn kv_heads(&self) -> usize {
+        self.num_kv_heads.unwrap_or(self.num_heads)
+    }
+
     /// Returns the effective scale factor.
     #[inline]
     pub fn effective_scale(&self) -> f32 {
@@ -85,9 +116,12 @@ impl AttentionConfig {
 pub struct AttentionConfigBuilder {
     dim: Option<usize>,
     num_heads: Option<usize>,
+    num_kv_heads: Option<usize>,
     dropout: f32,
     scale: Option<f32>,
     causal: bool,
+    alibi: bool,
+    alibi_slopes: Option<Vec<f32>>,
 }
 
 impl AttentionConfigBuilder {
@@ -103,6 +137,12 @@ impl AttentionConfigBuilder {
         self
     }
 
+    /// Sets the number of key/value heads (grouped-query attention).
+    pub fn num_kv_heads(mut self, num_kv_heads: usize) -> Self {
+        self.num_kv_heads = Some(num_kv_heads);
+        self
+    }
+
     /// Sets the dropout probability.
     pub fn dropout(mut self, dropout: f32) -> Self {
         self.dropout = dropout;
@@ -121,18 +161,39 @@ impl AttentionConfigBuilder {
         self
     }
 
+    /// Enables ALiBi with the default geometric slopes for `num_heads`.
+    pub fn alibi(mut self, alibi: bool) -> Self {
+        self.alibi = alibi;
+        self
+    }
+
+    /// Enables ALiBi with explicit per-head slopes.
+    pub fn alibi_slopes(mut self, slopes: Vec<f32>) -> Self {
+        self.alibi = true;
+        self.alibi_slopes = Some(slopes);
+        self
+    }
+
     /// Builds the AttentionConfig.
     pub fn build(self) -> AttentionResult<AttentionConfig> {
+        let num_heads = self.num_heads.ok_or_else(|| {
+            AttentionError::InvalidConfig("num_heads must be specified".to_string())
+        })?;
+        let alibi = match (self.alibi, self.alibi_slopes) {
+            (_, Some(slopes)) => Some(AlibiBias::with_slopes(slopes)?),
+            (true, None) => Some(AlibiBias::new(num_heads)?),
+            (false, None) => None,
+        };
         let config = AttentionConfig {
             dim: self.dim.ok_or_else(|| {
                 AttentionError::InvalidConfig("dimension must be specified".to_string())
             })?,
-            num_heads: self.num_heads.ok_or_else(|| {
-                AttentionError::InvalidConfig("num_heads must be specified".to_string())
-            })?,
+            num_heads,
+            num_kv_heads: self.num_kv_heads,
             dropout: self.dropout,
             scale: self.scale,
             causal: self.causal,
+            alibi,
         };
 
         config.validate()?;
@@ -151,6 +212,20 @@ pub struct GraphAttentionConfig {
     pub negative_slope: f32,
     /// Whether to concatenate multi-head outputs (vs averaging)
     pub concat_heads: bool,
+    /// Size of the neighborhood aggregated per node, in hops
+    #[serde(default = "default_num_hops")]
+    pub num_hops: usize,
+    /// Weight multiplier applied per extra hop (`attenuation^(hop - 1)`)
+    #[serde(default = "default_hop_attenuation")]
+    pub hop_attenuation: f32,
+}
+
+fn default_num_hops() -> usize {
+    1
+}
+
+fn default_hop_attenuation() -> f32 {
+    0.5
 }
 
 impl GraphAttentionConfig {
@@ -177,6 +252,18 @@ impl GraphAttentionConfig {
             }
         }
 
+        if self.num_hops == 0 {
+            return Err(AttentionError::InvalidConfig(
+                "num_hops must be greater than 0".to_string(),
+            ));
+        }
+
+        if !(self.hop_attenuation > 0.0 && self.hop_attenuation <= 1.0) {
+            return Err(AttentionError::InvalidConfig(
+                "hop_attenuation must be in range (0.0, 1.0]".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -188,6 +275,8 @@ pub struct GraphAttentionConfigBuilder {
     edge_dim: Option<usize>,
     negative_slope: f32,
     concat_heads: bool,
+    num_hops: Option<usize>,
+    hop_attenuation: Option<f32>,
 }
 
 impl GraphAttentionConfigBuilder {
@@ -221,6 +310,18 @@ impl GraphAttentionConfigBuilder {
         self
     }
 
+    /// Sets the number of hops aggregated per node.
+    pub fn num_hops(mut self, num_hops: usize) -> Self {
+        self.num_hops = Some(num_hops);
+        self
+    }
+
+    /// Sets the per-hop attenuation of attention weights.
+    pub fn hop_attenuation(mut self, attenuation: f32) -> Self {
+        self.hop_attenuation = Some(attenuation);
+        self
+    }
+
     /// Builds the GraphAttentionConfig.
     pub fn build(self) -> AttentionResult<GraphAttentionConfig> {
         let config = GraphAttentionConfig {
@@ -232,6 +333,8 @@ impl GraphAttentionConfigBuilder {
                 self.negative_slope
             },
             concat_heads: self.concat_heads,
+            num_hops: self.num_hops.unwrap_or_else(default_num_hops),
+            hop_attenuation: self.hop_attenuation.unwrap_or_else(default_hop_attenuation),
         };
 
         config.validate()?;
@@ -361,6 +464,62 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_num_kv_heads() {
+        let mha = AttentionConfig::builder()
+            .dim(64)
+            .num_heads(8)
+            .build()
+            .unwrap();
+        assert_eq!(mha.kv_heads(), 8);
+
+        let gqa = AttentionConfig::builder()
+            .dim(64)
+            .num_heads(8)
+            .num_kv_heads(2)
+            .build()
+            .unwrap();
+        assert_eq!(gqa.kv_heads(), 2);
+
+        for bad in [0, 3, 16] {
+            let result = AttentionConfig::builder()
+                .dim(64)
+                .num_heads(8)
+                .num_kv_heads(bad)
+                .build();
+            assert!(result.is_err(), "num_kv_heads {}", bad);
+        }
+    }
+
+    #[test]
+    fn test_alibi_config() {
+        let config = AttentionConfig::builder()
+            .dim(64)
+            .num_heads(8)
+            .alibi(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.alibi.as_ref().unwrap().slopes(),
+            AlibiBias::default_slopes(8).as_slice()
+        );
+
+        let custom = AttentionConfig::builder()
+            .dim(64)
+            .num_heads(2)
+            .alibi_slopes(vec![0.1, 0.01])
+            .build()
+            .unwrap();
+        assert_eq!(custom.alibi.unwrap().slopes(), &[0.1, 0.01]);
+
+        let mismatched = AttentionConfig::builder()
+            .dim(64)
+            .num_heads(4)
+            .alibi_slopes(vec![0.1, 0.01])
+            .build();
+        assert!(mismatched.is_err());
+    }
+
     #[test]
     fn test_graph_attention_config() {
         let config = GraphAttentionConfig::builder()
@@ -375,6 +534,22 @@ mod tests {
         assert_eq!(config.base.dim, 256);
         assert_eq!(config.edge_dim, Some(16));
         assert!(config.concat_heads);
+        assert_eq!(config.num_hops, 1);
+
+        let multi_hop = GraphAttentionConfig::builder()
+            .dim(64)
+            .num_heads(4)
+            .num_hops(3)
+            .hop_attenuation(0.25)
+            .build()
+            .unwrap();
+        assert_eq!(multi_hop.num_hops, 3);
+        assert_eq!(multi_hop.hop_attenuation, 0.25);
+
+        let builder = || GraphAttentionConfig::builder().dim(64).num_heads(4);
+        assert!(builder().num_hops(0).build().is_err());
+        assert!(builder().hop_attenuation(0.0).build().is_err());
+        assert!(builder().hop_attenuation(1.5).build().is_err());
     }
 
     #[test]
@@ -4,11 +4,14 @@
 //! - Edge-featured attention (GAT with edge features)
 //! - Rotary position embeddings for graphs (RoPE)
 //! - Dual-space attention (Euclidean + Hyperbolic)
+//! - Multi-hop neighborhood attention
 
 pub mod dual_space;
 pub mod edge_featured;
+pub mod multi_hop;
 pub mod rope;
 
 pub use dual_space::{DualSpaceAttention, DualSpaceConfig};
 pub use edge_featured::{EdgeFeaturedAttention, EdgeFeaturedConfig};
-pub use rope::{GraphRoPE, RoPEConfig};
+pub use multi_hop::MultiHopGraphAttention;
+pub use rope::{GraphRoPE, RoPEConfig, RopeScaling};
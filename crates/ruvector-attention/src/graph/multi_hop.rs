@@ -0,0 +1,297 @@
+//! Multi-hop graph attention
+//!
+//! Each node attends over its k-hop in-neighborhood instead of only its
+//! direct neighbors. The neighborhood is found by breadth-first search
+//! against edge direction (messages flow `src -> dst`), so every node is
+//! reached once at its shortest hop distance: self-loops, cycles and
+//! parallel paths never count a node twice. Attention weights of nodes
+//! `h` hops away are scaled by `hop_attenuation^(h - 1)` before
+//! renormalization; the node itself sits at hop 0 with weight 1.
+
+use std::collections::VecDeque;
+
+use crate::config::GraphAttentionConfig;
+use crate::error::{AttentionError, AttentionResult};
+use crate::traits::{Attention, EdgeInfo, GraphAttention};
+use crate::utils::stable_softmax;
+
+/// Graph attention over the k-hop neighborhood of each node
+pub struct MultiHopGraphAttention {
+    config: GraphAttentionConfig,
+    scale: f32,
+}
+
+impl MultiHopGraphAttention {
+    /// Create multi-hop graph attention from a validated config
+    pub fn new(config: GraphAttentionConfig) -> AttentionResult<Self> {
+        config.validate()?;
+        let scale = config.base.effective_scale();
+        Ok(Self { config, scale })
+    }
+
+    /// Number of hops aggregated per node
+    pub fn num_hops(&self) -> usize {
+        self.config.num_hops
+    }
+
+    /// Nodes within `num_hops` of `node` (following edges backwards),
+    /// each paired with its hop distance. `node` itself comes first at 0.
+    pub fn neighborhood(&self, node: usize, incoming: &[Vec<usize>]) -> Vec<(usize, usize)> {
+        let mut visited = vec![false; incoming.len()];
+        let mut found = vec![(node, 0)];
+        let mut queue = VecDeque::from([(node, 0)]);
+        visited[node] = true;
+
+        while let Some((current, hop)) = queue.pop_front() {
+            if hop == self.config.num_hops {
+                continue;
+            }
+            for &src in &incoming[current] {
+                if !visited[src] {
+                    visited[src] = true;
+                    found.push((src, hop + 1));
+                    queue.push_back((src, hop + 1));
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Incoming adjacency lists, one per node
+    fn incoming(num_nodes: usize, edges: &[EdgeInfo]) -> AttentionResult<Vec<Vec<usize>>> {
+        let mut incoming = vec![Vec::new(); num_nodes];
+        for edge in edges {
+            if edge.src >= num_nodes || edge.dst >= num_nodes {
+                return Err(AttentionError::InvalidConfig(format!(
+                    "edge {} -> {} out of range for {} nodes",
+                    edge.src, edge.dst, num_nodes
+                )));
+            }
+            incoming[edge.dst].push(edge.src);
+        }
+        Ok(incoming)
+    }
+
+    fn score(&self, src: &[f32], dst: &[f32]) -> f32 {
+        let dot: f32 = src.iter().zip(dst).map(|(s, d)| s * d).sum();
+        let score = dot * self.scale;
+        if score < 0.0 {
+            self.config.negative_slope * score
+        } else {
+            score
+        }
+    }
+}
+
+impl Attention for MultiHopGraphAttention {
+    fn compute(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<Vec<f32>> {
+        self.compute_with_mask(query, keys, values, None)
+    }
+
+    fn compute_with_mask(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+        mask: Option<&[bool]>,
+    ) -> AttentionResult<Vec<f32>> {
+        let dim = self.config.base.dim;
+        if query.len() != dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: dim,
+                actual: query.len(),
+            });
+        }
+        if keys.is_empty() {
+            return Err(AttentionError::EmptyInput("keys".to_string()));
+        }
+        if keys.len() != values.len() {
+            return Err(AttentionError::DimensionMismatch {
+                expected: keys.len(),
+                actual: values.len(),
+            });
+        }
+
+        let scores: Vec<f32> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, k)| match mask {
+                Some(m) if !m.get(i).copied().unwrap_or(true) => f32::NEG_INFINITY,
+                _ => self.score(k, query),
+            })
+            .collect();
+        let weights = stable_softmax(&scores);
+
+        let mut output = vec![0.0f32; dim];
+        for (w, v) in weights.iter().zip(values) {
+            for (o, &x) in output.iter_mut().zip(v.iter()) {
+                *o += w * x;
+            }
+        }
+        Ok(output)
+    }
+
+    fn dim(&self) -> usize {
+        self.config.base.dim
+    }
+}
+
+impl GraphAttention for MultiHopGraphAttention {
+    /// Updates every node with attention over its k-hop neighborhood.
+    /// Edge features are not used by this layer.
+    fn compute_with_edges(
+        &self,
+        node_features: &[Vec<f32>],
+        edges: &[EdgeInfo],
+    ) -> AttentionResult<Vec<Vec<f32>>> {
+        let dim = self.config.base.dim;
+        if let Some(bad) = node_features.iter().find(|f| f.len() != dim) {
+            return Err(AttentionError::DimensionMismatch {
+                expected: dim,
+                actual: bad.len(),
+            });
+        }
+
+        let incoming = Self::incoming(node_features.len(), edges)?;
+        let log_attenuation = self.config.hop_attenuation.ln();
+
+        let mut outputs = Vec::with_capacity(node_features.len());
+        for (node, dst) in node_features.iter().enumerate() {
+            let neighborhood = self.neighborhood(node, &incoming);
+
+            // Adding (hop - 1) * ln(attenuation) to the logit multiplies the
+            // softmax numerator by attenuation^(hop - 1).
+            let logits: Vec<f32> = neighborhood
+                .iter()
+                .map(|&(src, hop)| {
+                    let score = self.score(&node_features[src], dst);
+                    score + hop.saturating_sub(1) as f32 * log_attenuation
+                })
+                .collect();
+            let weights = stable_softmax(&logits);
+
+            let mut output = vec![0.0f32; dim];
+            for (&(src, _), w) in neighborhood.iter().zip(&weights) {
+                for (o, &x) in output.iter_mut().zip(&node_features[src]) {
+                    *o += w * x;
+                }
+            }
+            outputs.push(output);
+        }
+
+        Ok(outputs)
+    }
+
+    fn compute_edge_attention(
+        &self,
+        src_feature: &[f32],
+        dst_feature: &[f32],
+        _edge_feature: Option<&[f32]>,
+    ) -> AttentionResult<f32> {
+        if src_feature.len() != dst_feature.len() {
+            return Err(AttentionError::DimensionMismatch {
+                expected: dst_feature.len(),
+                actual: src_feature.len(),
+            });
+        }
+        Ok(self.score(src_feature, dst_feature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(n: usize) -> (Vec<Vec<f32>>, Vec<EdgeInfo>) {
+        // One-hot features so each output coordinate tracks one source node.
+        let features = (0..n)
+            .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        let edges = (0..n - 1)
+            .flat_map(|i| [(i, i + 1), (i + 1, i)])
+            .map(|(src, dst)| EdgeInfo {
+                src,
+                dst,
+                features: None,
+            })
+            .collect();
+        (features, edges)
+    }
+
+    fn attention(dim: usize, num_hops: usize) -> MultiHopGraphAttention {
+        let config = GraphAttentionConfig::builder()
+            .dim(dim)
+            .num_heads(1)
+            .num_hops(num_hops)
+            .build()
+            .unwrap();
+        MultiHopGraphAttention::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_two_hops_reach_further_on_chain() {
+        let (features, edges) = chain(5);
+
+        let one_hop = attention(5, 1)
+            .compute_with_edges(&features, &edges)
+            .unwrap();
+        let two_hop = attention(5, 2)
+            .compute_with_edges(&features, &edges)
+            .unwrap();
+
+        // Node 0 only sees node 1 directly; node 2 is two edges away.
+        assert!(one_hop[0][1] > 0.0);
+        assert_eq!(one_hop[0][2], 0.0);
+        assert!(two_hop[0][2] > 0.0);
+        assert_eq!(two_hop[0][3], 0.0);
+
+        // Attenuation keeps the second hop below the first.
+        assert!(two_hop[0][2] < two_hop[0][1]);
+        for out in &two_hop {
+            assert!((out.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_no_double_counting() {
+        let attn = attention(4, 3);
+        let (_, mut edges) = chain(4);
+        // Self-loop and a duplicate edge must not add extra entries.
+        edges.push(EdgeInfo {
+            src: 1,
+            dst: 1,
+            features: None,
+        });
+        edges.push(EdgeInfo {
+            src: 0,
+            dst: 1,
+            features: None,
+        });
+
+        let incoming = MultiHopGraphAttention::incoming(4, &edges).unwrap();
+        let hood = attn.neighborhood(1, &incoming);
+        let mut nodes: Vec<usize> = hood.iter().map(|&(n, _)| n).collect();
+        nodes.sort_unstable();
+        assert_eq!(nodes, vec![0, 1, 2, 3]);
+        assert!(hood.contains(&(1, 0)));
+        assert!(hood.contains(&(3, 2)));
+    }
+
+    #[test]
+    fn test_invalid_edges() {
+        let attn = attention(2, 2);
+        let features = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let edges = vec![EdgeInfo {
+            src: 0,
+            dst: 5,
+            features: None,
+        }];
+        assert!(attn.compute_with_edges(&features, &edges).is_err());
+    }
+}
@@ -7,6 +7,28 @@ use crate::error::{AttentionError, AttentionResult};
 use crate::traits::Attention;
 use crate::utils::stable_softmax;
 
+/// Frequency scaling used to extend RoPE beyond its trained context length
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RopeScaling {
+    /// Plain RoPE
+    #[default]
+    None,
+    /// Position interpolation: position `p` is rotated as `p / factor`
+    Linear { factor: f32 },
+    /// NTK-aware scaling: the base grows to `base * factor^(d / (d - 2))`,
+    /// stretching low frequencies while leaving high frequencies intact
+    Ntk { factor: f32 },
+}
+
+impl RopeScaling {
+    fn factor(&self) -> f32 {
+        match *self {
+            RopeScaling::None => 1.0,
+            RopeScaling::Linear { factor } | RopeScaling::Ntk { factor } => factor.max(1.0),
+        }
+    }
+}
+
 /// Configuration for Graph RoPE
 #[derive(Clone, Debug)]
 pub struct RoPEConfig {
@@ -14,6 +36,8 @@ pub struct RoPEConfig {
     pub base: f32,
     pub max_position: usize,
     pub scaling_factor: f32,
+    /// Context extension applied on top of `base` and `scaling_factor`
+    pub scaling: RopeScaling,
 }
 
 impl Default for RoPEConfig {
@@ -23,6 +47,7 @@ impl Default for RoPEConfig {
             base: 10000.0,
             max_position: 512,
             scaling_factor: 1.0,
+            scaling: RopeScaling::None,
         }
     }
 }
@@ -31,6 +56,22 @@ impl RoPEConfig {
     pub fn builder() -> RoPEConfigBuilder {
         RoPEConfigBuilder::default()
     }
+
+    /// Base frequency after NTK-aware adjustment
+    pub fn effective_base(&self) -> f32 {
+        match self.scaling {
+            RopeScaling::Ntk { .. } if self.dim > 2 => {
+                let exponent = self.dim as f32 / (self.dim as f32 - 2.0);
+                self.base * self.scaling.factor().powf(exponent)
+            }
+            _ => self.base,
+        }
+    }
+
+    /// Number of positions covered once the context is extended by `scaling`
+    pub fn context_length(&self) -> usize {
+        (self.max_position as f32 * self.scaling.factor()).ceil() as usize
+    }
 }
 
 #[derive(Default)]
@@ -59,6 +100,11 @@ impl RoPEConfigBuilder {
         self
     }
 
+    pub fn scaling(mut self, s: RopeScaling) -> Self {
+        self.config.scaling = s;
+        self
+    }
+
     pub fn build(self) -> RoPEConfig {
         self.config
     }
@@ -76,9 +122,12 @@ pub struct GraphRoPE {
 impl GraphRoPE {
     pub fn new(config: RoPEConfig) -> Self {
         let dim = config.dim;
-        let max_pos = config.max_position;
-        let base = config.base;
-        let scaling = config.scaling_factor;
+        let max_pos = config.context_length();
+        let base = config.effective_base();
+        let scaling = match config.scaling {
+            RopeScaling::Linear { .. } => config.scaling_factor * config.scaling.factor(),
+            _ => config.scaling_factor,
+        };
 
         // Compute frequency bands
         let half_dim = dim / 2;
@@ -113,11 +162,16 @@ impl GraphRoPE {
         }
     }
 
+    /// The configuration the tables were built from
+    pub fn config(&self) -> &RoPEConfig {
+        &self.config
+    }
+
     /// Apply rotary embedding to a vector at given position
     pub fn apply_rotary(&self, x: &[f32], position: usize) -> Vec<f32> {
         let dim = self.config.dim;
         let half = dim / 2;
-        let pos = position.min(self.config.max_position - 1);
+        let pos = position.min(self.config.context_length() - 1);
         let offset = pos * dim;
 
         let mut result = vec![0.0f32; dim];
@@ -302,6 +356,88 @@ mod tests {
         assert!((norm_orig - norm_rot).abs() < 1e-5);
     }
 
+    #[test]
+    fn test_no_scaling_matches_plain_rope() {
+        let rope = GraphRoPE::new(
+            RoPEConfig::builder()
+                .dim(16)
+                .max_position(32)
+                .scaling(RopeScaling::None)
+                .build(),
+        );
+        assert_eq!(rope.config.context_length(), 32);
+
+        // theta = p * base^(-2i/d), the textbook RoPE angle
+        for p in [0usize, 7, 31] {
+            for i in 0..8 {
+                let theta = p as f32 / 10000.0f32.powf(2.0 * i as f32 / 16.0);
+                assert!((rope.cos_cache[p * 16 + i] - theta.cos()).abs() < 1e-6);
+                assert!((rope.sin_cache[p * 16 + i] - theta.sin()).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_scaling_divides_position() {
+        let x: Vec<f32> = (0..16).map(|i| (i as f32 * 0.3).sin()).collect();
+        let plain = GraphRoPE::new(RoPEConfig::builder().dim(16).max_position(64).build());
+        let linear = GraphRoPE::new(
+            RoPEConfig::builder()
+                .dim(16)
+                .max_position(64)
+                .scaling(RopeScaling::Linear { factor: 4.0 })
+                .build(),
+        );
+
+        for p in [0, 4, 20, 60] {
+            let scaled = linear.apply_rotary(&x, p);
+            let reference = plain.apply_rotary(&x, p / 4);
+            for (a, b) in scaled.iter().zip(reference.iter()) {
+                assert!((a - b).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ntk_scaling_adjusts_base() {
+        let config = RoPEConfig::builder()
+            .dim(64)
+            .base(10000.0)
+            .scaling(RopeScaling::Ntk { factor: 8.0 })
+            .build();
+        let expected = 10000.0 * 8.0f32.powf(64.0 / 62.0);
+        assert!((config.effective_base() - expected).abs() / expected < 1e-5);
+
+        let plain = RoPEConfig::builder().dim(64).base(10000.0).build();
+        assert_eq!(plain.effective_base(), 10000.0);
+    }
+
+    #[test]
+    fn test_scaled_rotations_finite_beyond_context() {
+        let x = vec![1.0; 32];
+        let norm: f32 = x.iter().map(|v| v * v).sum::<f32>().sqrt();
+
+        for scaling in [
+            RopeScaling::None,
+            RopeScaling::Linear { factor: 4.0 },
+            RopeScaling::Ntk { factor: 4.0 },
+        ] {
+            let config = RoPEConfig::builder()
+                .dim(32)
+                .max_position(128)
+                .scaling(scaling)
+                .build();
+            let rope = GraphRoPE::new(config);
+
+            for p in [127, 300, 511, 10_000] {
+                let rotated = rope.apply_rotary(&x, p);
+                assert!(rotated.iter().all(|v| v.is_finite()));
+                let rot_norm: f32 = rotated.iter().map(|v| v * v).sum::<f32>().sqrt();
+                assert!((rot_norm - norm).abs() < 1e-4);
+            }
+        }
+    }
+
     #[test]
     fn test_distance_to_position() {
         // Direct mapping for small distances
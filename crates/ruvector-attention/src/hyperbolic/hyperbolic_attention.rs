@@ -1,9 +1,24 @@
-//! Hyperbolic Attention Mechanism using Poincaré ball model
+//! Hyperbolic Attention Mechanism using the Poincaré ball or Lorentz model
 
+use super::lorentz::{lift_to_hyperboloid, lorentz_centroid};
+use super::lorentz_cascade::lorentz_distance;
 use super::poincare::{frechet_mean, poincare_distance, project_to_ball};
 use crate::error::{AttentionError, AttentionResult};
 use crate::traits::Attention;
 
+/// Model of hyperbolic space used by [`HyperbolicAttention`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HyperbolicModel {
+    /// Inputs are points in the Poincaré ball; values are aggregated with
+    /// the iterative Fréchet mean
+    #[default]
+    Poincare,
+    /// Inputs are the spatial coordinates `x₁..xₙ` of hyperboloid points;
+    /// values are aggregated with the closed-form Lorentzian centroid.
+    /// No ball boundary, so no projection or clamping near it.
+    Lorentz,
+}
+
 /// Configuration for hyperbolic attention
 #[derive(Debug, Clone)]
 pub struct HyperbolicAttentionConfig {
@@ -13,6 +28,7 @@ pub struct HyperbolicAttentionConfig {
     pub temperature: f32,
     pub frechet_max_iter: usize,
     pub frechet_tol: f32,
+    pub model: HyperbolicModel,
 }
 
 impl Default for HyperbolicAttentionConfig {
@@ -24,6 +40,7 @@ impl Default for HyperbolicAttentionConfig {
             temperature: 1.0,
             frechet_max_iter: 50,
             frechet_tol: 1e-5,
+            model: HyperbolicModel::Poincare,
         }
     }
 }
@@ -48,10 +65,19 @@ impl HyperbolicAttention {
             return vec![];
         }
 
-        let scores: Vec<f32> = keys
-            .iter()
-            .map(|k| -poincare_distance(query, k, self.current_curvature))
-            .collect();
+        let c = self.current_curvature;
+        let scores: Vec<f32> = match self.config.model {
+            HyperbolicModel::Poincare => keys
+                .iter()
+                .map(|k| -poincare_distance(query, k, c))
+                .collect(),
+            HyperbolicModel::Lorentz => {
+                let q = lift_to_hyperboloid(query, c);
+                keys.iter()
+                    .map(|k| -lorentz_distance(&q, &lift_to_hyperboloid(k, c), c))
+                    .collect()
+            }
+        };
 
         self.softmax_with_temperature(&scores)
     }
@@ -75,6 +101,14 @@ impl HyperbolicAttention {
         }
     }
 
+    /// Brings an input into the configured model's coordinates
+    fn prepare(&self, x: &[f32]) -> Vec<f32> {
+        match self.config.model {
+            HyperbolicModel::Poincare => project_to_ball(x, self.current_curvature, 1e-7),
+            HyperbolicModel::Lorentz => x.to_vec(),
+        }
+    }
+
     pub fn aggregate(&self, weights: &[f32], values: &[&[f32]]) -> Vec<f32> {
         if values.is_empty() {
             return vec![0.0; self.config.dim];
@@ -84,6 +118,13 @@ impl HyperbolicAttention {
             return values[0].to_vec();
         }
 
+        if self.config.model == HyperbolicModel::Lorentz {
+            let c = self.current_curvature;
+            let lifted: Vec<Vec<f32>> = values.iter().map(|v| lift_to_hyperboloid(v, c)).collect();
+            let lifted_refs: Vec<&[f32]> = lifted.iter().map(|v| v.as_slice()).collect();
+            return lorentz_centroid(&lifted_refs, weights, c)[1..].to_vec();
+        }
+
         frechet_mean(
             values,
             Some(weights),
@@ -107,15 +148,9 @@ impl Attention for HyperbolicAttention {
             ));
         }
 
-        let query_proj = project_to_ball(query, self.current_curvature, 1e-7);
-        let keys_proj: Vec<Vec<f32>> = keys
-            .iter()
-            .map(|k| project_to_ball(k, self.current_curvature, 1e-7))
-            .collect();
-        let values_proj: Vec<Vec<f32>> = values
-            .iter()
-            .map(|v| project_to_ball(v, self.current_curvature, 1e-7))
-            .collect();
+        let query_proj = self.prepare(query);
+        let keys_proj: Vec<Vec<f32>> = keys.iter().map(|k| self.prepare(k)).collect();
+        let values_proj: Vec<Vec<f32>> = values.iter().map(|v| self.prepare(v)).collect();
 
         let keys_refs: Vec<&[f32]> = keys_proj.iter().map(|k| k.as_slice()).collect();
         let weights = self.compute_weights(&query_proj, &keys_refs);
@@ -133,15 +168,9 @@ impl Attention for HyperbolicAttention {
         values: &[&[f32]],
         mask: Option<&[bool]>,
     ) -> AttentionResult<Vec<f32>> {
-        let query_proj = project_to_ball(query, self.current_curvature, 1e-7);
-        let keys_proj: Vec<Vec<f32>> = keys
-            .iter()
-            .map(|k| project_to_ball(k, self.current_curvature, 1e-7))
-            .collect();
-        let values_proj: Vec<Vec<f32>> = values
-            .iter()
-            .map(|v| project_to_ball(v, self.current_curvature, 1e-7))
-            .collect();
+        let query_proj = self.prepare(query);
+        let keys_proj: Vec<Vec<f32>> = keys.iter().map(|k| self.prepare(k)).collect();
+        let values_proj: Vec<Vec<f32>> = values.iter().map(|v| self.prepare(v)).collect();
 
         let keys_refs: Vec<&[f32]> = keys_proj.iter().map(|k| k.as_slice()).collect();
         let mut weights = self.compute_weights(&query_proj, &keys_refs);
@@ -169,3 +198,48 @@ impl Attention for HyperbolicAttention {
         self.config.dim
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::lorentz::lorentz_to_poincare;
+    use super::*;
+
+    #[test]
+    fn test_lorentz_model_matches_poincare_weights() {
+        let dim = 4;
+        let c = 1.0;
+        let space: Vec<Vec<f32>> = (0..5)
+            .map(|t| {
+                (0..dim)
+                    .map(|i| 2.0 * ((t * dim + i) as f32 * 0.41).sin())
+                    .collect()
+            })
+            .collect();
+        let in_ball: Vec<Vec<f32>> = space
+            .iter()
+            .map(|x| lorentz_to_poincare(&lift_to_hyperboloid(x, c), c))
+            .collect();
+
+        let lorentz = HyperbolicAttention::new(HyperbolicAttentionConfig {
+            dim,
+            model: HyperbolicModel::Lorentz,
+            ..Default::default()
+        });
+        let poincare = HyperbolicAttention::new(HyperbolicAttentionConfig {
+            dim,
+            ..Default::default()
+        });
+
+        let keys: Vec<&[f32]> = space[1..].iter().map(|k| k.as_slice()).collect();
+        let ball_keys: Vec<&[f32]> = in_ball[1..].iter().map(|k| k.as_slice()).collect();
+        let w_lorentz = lorentz.compute_weights(&space[0], &keys);
+        let w_poincare = poincare.compute_weights(&in_ball[0], &ball_keys);
+        for (a, b) in w_lorentz.iter().zip(&w_poincare) {
+            assert!((a - b).abs() < 1e-3, "{:?} vs {:?}", w_lorentz, w_poincare);
+        }
+
+        let out = lorentz.compute(&space[0], &keys, &keys).unwrap();
+        assert_eq!(out.len(), dim);
+        assert!(out.iter().all(|v| v.is_finite()));
+    }
+}
@@ -0,0 +1,193 @@
+//! Lorentz (Hyperboloid) Model Operations for Hyperbolic Geometry
+//!
+//! Points live on the upper sheet `{x : ⟨x,x⟩_L = -1/c, x₀ > 0}` in `n + 1`
+//! coordinates. Unlike the Poincaré ball there is no boundary to approach, so
+//! distances stay well conditioned for points far from the origin.
+//! Conversions to and from the Poincaré ball let the two models be mixed.
+
+use super::lorentz_cascade::{lorentz_inner, project_hyperboloid};
+
+/// Small epsilon for numerical stability
+const EPS: f32 = 1e-7;
+
+/// Lift spatial coordinates `x₁..xₙ` onto the hyperboloid by solving for `x₀`
+pub fn lift_to_hyperboloid(space: &[f32], c: f32) -> Vec<f32> {
+    let c = c.abs();
+    let mut x = Vec::with_capacity(space.len() + 1);
+    x.push(0.0);
+    x.extend_from_slice(space);
+    project_hyperboloid(&x, c)
+}
+
+/// Lorentzian norm of a tangent (space-like) vector
+#[inline]
+fn tangent_norm(v: &[f32]) -> f32 {
+    lorentz_inner(v, v).max(0.0).sqrt()
+}
+
+/// Exponential map: maps tangent vector v at point p onto the hyperboloid
+pub fn lorentz_exp_map(v: &[f32], p: &[f32], c: f32) -> Vec<f32> {
+    let c = c.abs();
+    let sqrt_c = c.sqrt();
+    let norm_v = tangent_norm(v);
+
+    if norm_v < EPS {
+        return p.to_vec();
+    }
+
+    let theta = sqrt_c * norm_v;
+    let coef_p = theta.cosh();
+    let coef_v = theta.sinh() / theta;
+
+    let result: Vec<f32> = p
+        .iter()
+        .zip(v)
+        .map(|(&pi, &vi)| coef_p * pi + coef_v * vi)
+        .collect();
+    project_hyperboloid(&result, c)
+}
+
+/// Logarithmic map: maps point y on the hyperboloid to the tangent space at p
+pub fn lorentz_log_map(y: &[f32], p: &[f32], c: f32) -> Vec<f32> {
+    let c = c.abs();
+    let inner = lorentz_inner(p, y);
+
+    // Component of y orthogonal to p in the Minkowski metric
+    let u: Vec<f32> = y
+        .iter()
+        .zip(p)
+        .map(|(&yi, &pi)| yi + c * inner * pi)
+        .collect();
+    let norm_u = tangent_norm(&u);
+
+    if norm_u < EPS {
+        return vec![0.0; y.len()];
+    }
+
+    let dist = (-c * inner).max(1.0).acosh() / c.sqrt();
+    u.iter().map(|&ui| dist * ui / norm_u).collect()
+}
+
+/// Weighted Lorentzian centroid, the closed-form minimizer of the weighted
+/// squared Lorentzian distance
+pub fn lorentz_centroid(points: &[&[f32]], weights: &[f32], c: f32) -> Vec<f32> {
+    let c = c.abs();
+    let dim = points[0].len();
+    let mut sum = vec![0.0f32; dim];
+    for (point, &weight) in points.iter().zip(weights) {
+        for (s, &x) in sum.iter_mut().zip(point.iter()) {
+            *s += weight * x;
+        }
+    }
+
+    let norm = (-lorentz_inner(&sum, &sum)).max(EPS).sqrt();
+    let scale = 1.0 / (c.sqrt() * norm);
+    let centroid: Vec<f32> = sum.iter().map(|&s| s * scale).collect();
+    project_hyperboloid(&centroid, c)
+}
+
+/// Map a point from the Poincaré ball to the hyperboloid
+pub fn poincare_to_lorentz(x: &[f32], c: f32) -> Vec<f32> {
+    let c = c.abs();
+    let norm_sq: f32 = x.iter().map(|v| v * v).sum();
+    let denom = (1.0 - c * norm_sq).max(EPS);
+
+    let mut result = Vec::with_capacity(x.len() + 1);
+    result.push((1.0 + c * norm_sq) / (c.sqrt() * denom));
+    result.extend(x.iter().map(|&xi| 2.0 * xi / denom));
+    result
+}
+
+/// Map a point from the hyperboloid to the Poincaré ball
+pub fn lorentz_to_poincare(x: &[f32], c: f32) -> Vec<f32> {
+    let c = c.abs();
+    let denom = 1.0 + c.sqrt() * x[0];
+    x[1..].iter().map(|&xi| xi / denom).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::lorentz_cascade::lorentz_distance;
+    use super::super::poincare::poincare_distance;
+    use super::*;
+
+    fn ball_point(seed: usize, dim: usize, radius: f32) -> Vec<f32> {
+        let raw: Vec<f32> = (0..dim)
+            .map(|i| ((seed * dim + i) as f32 * 0.73).sin())
+            .collect();
+        let norm: f32 = raw.iter().map(|v| v * v).sum::<f32>().sqrt();
+        raw.iter().map(|v| v * radius / norm).collect()
+    }
+
+    #[test]
+    fn test_distances_agree_across_models() {
+        for &c in &[1.0f32, 0.5, 2.0] {
+            let max_r = 1.0 / c.sqrt();
+            for seed in 0..6 {
+                let u = ball_point(seed, 5, 0.3 * max_r);
+                let v = ball_point(seed + 11, 5, 0.8 * max_r);
+
+                let d_ball = poincare_distance(&u, &v, c);
+                let lu = poincare_to_lorentz(&u, c);
+                let lv = poincare_to_lorentz(&v, c);
+                assert!((lorentz_inner(&lu, &lu) + 1.0 / c).abs() < 1e-3);
+
+                let d_lorentz = lorentz_distance(&lu, &lv, c);
+                assert!(
+                    (d_ball - d_lorentz).abs() < 1e-3 * d_ball.max(1.0),
+                    "c={} poincare {} vs lorentz {}",
+                    c,
+                    d_ball,
+                    d_lorentz
+                );
+
+                let back = lorentz_to_poincare(&lu, c);
+                for (a, b) in back.iter().zip(&u) {
+                    assert!((a - b).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hyperboloid_points_map_to_ball() {
+        let c = 1.0;
+        let x = lift_to_hyperboloid(&[1.5, -0.5, 2.0], c);
+        let y = lift_to_hyperboloid(&[-0.3, 0.8, 0.1], c);
+
+        let px = lorentz_to_poincare(&x, c);
+        let py = lorentz_to_poincare(&y, c);
+        assert!(px.iter().map(|v| v * v).sum::<f32>() < 1.0);
+
+        let d_lorentz = lorentz_distance(&x, &y, c);
+        let d_ball = poincare_distance(&px, &py, c);
+        assert!((d_lorentz - d_ball).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_exp_log_round_trip() {
+        let c = 1.0;
+        let p = lift_to_hyperboloid(&[0.4, -0.2, 0.1], c);
+        let y = lift_to_hyperboloid(&[-0.6, 0.5, 0.9], c);
+
+        let v = lorentz_log_map(&y, &p, c);
+        // Tangent vectors are Minkowski-orthogonal to the base point
+        assert!(lorentz_inner(&v, &p).abs() < 1e-4);
+        assert!((tangent_norm(&v) - lorentz_distance(&p, &y, c)).abs() < 1e-4);
+
+        let y2 = lorentz_exp_map(&v, &p, c);
+        for (a, b) in y2.iter().zip(&y) {
+            assert!((a - b).abs() < 1e-3, "{:?} vs {:?}", y2, y);
+        }
+    }
+
+    #[test]
+    fn test_centroid_of_single_point() {
+        let c = 1.0;
+        let x = lift_to_hyperboloid(&[0.7, -1.2], c);
+        let mean = lorentz_centroid(&[&x, &x], &[0.5, 0.5], c);
+        for (a, b) in mean.iter().zip(&x) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+}
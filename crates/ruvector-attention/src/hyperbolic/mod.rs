@@ -5,6 +5,7 @@
 //! - Lorentz hyperboloid model (novel - faster, more stable)
 
 pub mod hyperbolic_attention;
+pub mod lorentz;
 pub mod lorentz_cascade;
 pub mod mixed_curvature;
 pub mod poincare;
@@ -14,7 +15,12 @@ pub use poincare::{
     project_to_ball,
 };
 
-pub use hyperbolic_attention::{HyperbolicAttention, HyperbolicAttentionConfig};
+pub use hyperbolic_attention::{HyperbolicAttention, HyperbolicAttentionConfig, HyperbolicModel};
+
+pub use lorentz::{
+    lift_to_hyperboloid, lorentz_centroid, lorentz_exp_map, lorentz_log_map, lorentz_to_poincare,
+    poincare_to_lorentz,
+};
 
 pub use mixed_curvature::{MixedCurvatureAttention, MixedCurvatureConfig};
 
@@ -68,12 +68,18 @@ pub mod unified_report;
 pub mod sheaf;
 
 // Re-export main types
-pub use attention::{MultiHeadAttention, ScaledDotProductAttention};
+pub use attention::{
+    AlibiBias, CausalAttention, KvCache, MultiHeadAttention, RelativePositionBias,
+    ScaledDotProductAttention,
+};
+#[cfg(feature = "quantized")]
+pub use attention::QuantizedAttention;
 pub use config::{AttentionConfig, GraphAttentionConfig, SparseAttentionConfig};
 pub use error::{AttentionError, AttentionResult};
 pub use hyperbolic::{
-    exp_map, log_map, mobius_add, poincare_distance, project_to_ball, HyperbolicAttention,
-    HyperbolicAttentionConfig, MixedCurvatureAttention, MixedCurvatureConfig,
+    exp_map, log_map, lorentz_to_poincare, mobius_add, poincare_distance, poincare_to_lorentz,
+    project_to_ball, HyperbolicAttention, HyperbolicAttentionConfig, HyperbolicModel,
+    MixedCurvatureAttention, MixedCurvatureConfig,
 };
 pub use traits::{
     Attention, EdgeInfo, GeometricAttention, Gradients, GraphAttention, SparseAttention,
@@ -82,7 +88,8 @@ pub use traits::{
 
 // Sparse attention exports
 pub use sparse::{
-    AttentionMask, FlashAttention, LinearAttention, LocalGlobalAttention, SparseMaskBuilder,
+    AttentionMask, FlashAttention, KernelType, LinearAttention, LocalGlobalAttention,
+    SparseMaskBuilder,
 };
 
 // MoE exports
@@ -94,14 +101,15 @@ pub use moe::{
 // Graph attention exports
 pub use graph::{
     DualSpaceAttention, DualSpaceConfig, EdgeFeaturedAttention, EdgeFeaturedConfig, GraphRoPE,
-    RoPEConfig,
+    MultiHopGraphAttention, RoPEConfig, RopeScaling,
 };
 
 // Training exports
 pub use training::{
-    Adam, AdamW, CurriculumScheduler, CurriculumStage, DecayType, HardNegativeMiner, InfoNCELoss,
-    LocalContrastiveLoss, Loss, MiningStrategy, NegativeMiner, Optimizer, Reduction,
-    SpectralRegularization, TemperatureAnnealing, SGD,
+    clip_grad_norm, Adam, AdamW, AttentionDropout, CurriculumScheduler, CurriculumStage, DecayType,
+    HardNegativeMiner, InfoNCELoss, LearningRateScheduler, LocalContrastiveLoss, Loss, LrScheduler,
+    MiningStrategy, NegativeMiner, Optimizer, Reduction, SpectralRegularization,
+    TemperatureAnnealing, SGD,
 };
 
 // SDK exports
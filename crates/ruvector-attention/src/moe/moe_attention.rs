@@ -117,25 +117,41 @@ impl MoEAttention {
         let mut routing_decisions = Vec::with_capacity(queries.len());
 
         for query in queries {
-            let routes = self.router.route(query);
-            routing_decisions.push(TopKRouting {
-                selections: routes.clone(),
-            });
+            let decision = self.router.route_with_probs(query);
 
             let mut output = vec![0.0f32; self.config.dim];
-            for (expert_idx, weight) in routes {
+            for &(expert_idx, weight) in &decision.selections {
                 let expert_output = self.experts[expert_idx].compute(query, keys, values)?;
                 for (o, e) in output.iter_mut().zip(expert_output.iter()) {
                     *o += weight * e;
                 }
             }
             outputs.push(output);
+            routing_decisions.push(decision);
         }
 
         let loss = self.router.load_balance_loss(&routing_decisions);
         Ok((outputs, loss))
     }
 
+    /// Routing decisions (with gate probabilities) for a batch of queries
+    pub fn route(&self, queries: &[&[f32]]) -> Vec<TopKRouting> {
+        queries
+            .iter()
+            .map(|q| self.router.route_with_probs(q))
+            .collect()
+    }
+
+    /// Load-balancing auxiliary loss to add to the training objective
+    pub fn aux_loss(&self, routing_decisions: &[TopKRouting]) -> f32 {
+        self.router.aux_loss(routing_decisions)
+    }
+
+    /// Per-expert utilization counts for monitoring
+    pub fn expert_counts(&self, routing_decisions: &[TopKRouting]) -> Vec<usize> {
+        self.router.expert_counts(routing_decisions)
+    }
+
     /// Get expert usage statistics
     pub fn expert_statistics(&self, routing_decisions: &[TopKRouting]) -> Vec<f32> {
         self.router.expert_statistics(routing_decisions)
@@ -245,6 +261,28 @@ mod tests {
         assert!(loss >= 0.0);
     }
 
+    #[test]
+    fn test_moe_aux_loss() {
+        let config = MoEConfig::builder().dim(16).num_experts(4).top_k(2).build();
+        let moe = MoEAttention::new(config);
+
+        let queries: Vec<Vec<f32>> = (0..12)
+            .map(|t| {
+                (0..16)
+                    .map(|i| ((t * 16 + i) as f32 * 0.17).sin())
+                    .collect()
+            })
+            .collect();
+        let query_refs: Vec<&[f32]> = queries.iter().map(|q| q.as_slice()).collect();
+
+        let decisions = moe.route(&query_refs);
+        assert!(decisions.iter().all(|d| d.gate_probs.len() == 4));
+        assert_eq!(moe.expert_counts(&decisions).iter().sum::<usize>(), 24);
+
+        let loss = moe.aux_loss(&decisions);
+        assert!(loss.is_finite() && loss > 0.0);
+    }
+
     #[test]
     fn test_config_builder() {
         let config = MoEConfig::builder()
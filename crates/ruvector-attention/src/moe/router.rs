@@ -16,6 +16,8 @@ pub trait Router: Send + Sync {
 pub struct TopKRouting {
     /// Selected experts with their normalized weights
     pub selections: Vec<(usize, f32)>,
+    /// Full gate probabilities over all experts (empty if not recorded)
+    pub gate_probs: Vec<f32>,
 }
 
 /// Learned router with softmax gating
@@ -68,6 +70,84 @@ impl LearnedRouter {
         stable_softmax(&logits)
     }
 
+    /// Route `x` and keep the full gate probabilities alongside the top-k
+    /// selections, as needed by [`aux_loss`](Self::aux_loss)
+    pub fn route_with_probs(&self, x: &[f32]) -> TopKRouting {
+        let gate_probs = self.compute_gate(x);
+        TopKRouting {
+            selections: self.select_top_k(&gate_probs),
+            gate_probs,
+        }
+    }
+
+    /// Switch Transformer load-balancing auxiliary loss
+    ///
+    /// `num_experts * sum_i f_i * P_i`, where `f_i` is the fraction of
+    /// routing assignments sent to expert `i` and `P_i` is the mean gate
+    /// probability of expert `i` over the batch. Perfectly uniform routing
+    /// gives 1.0; collapsing onto few experts drives it towards
+    /// `num_experts`. Decisions without `gate_probs` are left out of `P_i`.
+    pub fn aux_loss(&self, routing_decisions: &[TopKRouting]) -> f32 {
+        let counts = self.expert_counts(routing_decisions);
+        let total: usize = counts.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let mut mean_probs = vec![0.0f32; self.num_experts];
+        let mut recorded = 0usize;
+        for decision in routing_decisions {
+            if decision.gate_probs.len() == self.num_experts {
+                for (m, p) in mean_probs.iter_mut().zip(&decision.gate_probs) {
+                    *m += p;
+                }
+                recorded += 1;
+            }
+        }
+        if recorded == 0 {
+            return 0.0;
+        }
+
+        let dot: f32 = counts
+            .iter()
+            .zip(&mean_probs)
+            .map(|(&c, &p)| (c as f32 / total as f32) * (p / recorded as f32))
+            .sum();
+        self.num_experts as f32 * dot
+    }
+
+    /// Number of routing assignments each expert received
+    pub fn expert_counts(&self, routing_decisions: &[TopKRouting]) -> Vec<usize> {
+        let mut counts = vec![0usize; self.num_experts];
+        for decision in routing_decisions {
+            for &(expert_idx, _) in &decision.selections {
+                counts[expert_idx] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Top-k experts from gate probabilities, renormalized
+    fn select_top_k(&self, probs: &[f32]) -> Vec<(usize, f32)> {
+        // Get top-k indices
+        let mut indexed: Vec<(usize, f32)> = probs.iter().copied().enumerate().collect();
+        indexed.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Take top-k and renormalize
+        let top_k: Vec<(usize, f32)> = indexed.into_iter().take(self.top_k).collect();
+        let sum: f32 = top_k.iter().map(|(_, p)| p).sum();
+
+        if sum > 1e-8 {
+            top_k.into_iter().map(|(i, p)| (i, p / sum)).collect()
+        } else {
+            // Fallback: uniform over top-k
+            top_k
+                .into_iter()
+                .map(|(i, _)| (i, 1.0 / self.top_k as f32))
+                .collect()
+        }
+    }
+
     /// Compute load balancing loss for batch
     pub fn load_balance_loss(&self, routing_decisions: &[TopKRouting]) -> f32 {
         if routing_decisions.is_empty() {
@@ -129,24 +209,7 @@ impl LearnedRouter {
 impl Router for LearnedRouter {
     fn route(&self, x: &[f32]) -> Vec<(usize, f32)> {
         let probs = self.compute_gate(x);
-
-        // Get top-k indices
-        let mut indexed: Vec<(usize, f32)> = probs.into_iter().enumerate().collect();
-        indexed.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Take top-k and renormalize
-        let top_k: Vec<(usize, f32)> = indexed.into_iter().take(self.top_k).collect();
-        let sum: f32 = top_k.iter().map(|(_, p)| p).sum();
-
-        if sum > 1e-8 {
-            top_k.into_iter().map(|(i, p)| (i, p / sum)).collect()
-        } else {
-            // Fallback: uniform over top-k
-            top_k
-                .into_iter()
-                .map(|(i, _)| (i, 1.0 / self.top_k as f32))
-                .collect()
-        }
+        self.select_top_k(&probs)
     }
 
     fn num_experts(&self) -> usize {
@@ -180,6 +243,7 @@ mod tests {
         let decisions: Vec<TopKRouting> = (0..100)
             .map(|i| TopKRouting {
                 selections: vec![(i % 4, 0.6), ((i + 1) % 4, 0.4)],
+                gate_probs: Vec::new(),
             })
             .collect();
 
@@ -194,9 +258,11 @@ mod tests {
         let decisions: Vec<TopKRouting> = vec![
             TopKRouting {
                 selections: vec![(0, 0.6), (1, 0.4)],
+                gate_probs: Vec::new(),
             },
             TopKRouting {
                 selections: vec![(0, 0.5), (2, 0.5)],
+                gate_probs: Vec::new(),
             },
         ];
 
@@ -207,4 +273,54 @@ mod tests {
         let sum: f32 = stats.iter().sum();
         assert!((sum - 1.0).abs() < 1e-5);
     }
+
+    #[test]
+    fn test_aux_loss_penalizes_imbalance() {
+        let router = LearnedRouter::new(4, 8, 1);
+
+        // Every token sent to a different expert with uniform gates.
+        let uniform: Vec<TopKRouting> = (0..8)
+            .map(|i| TopKRouting {
+                selections: vec![(i % 4, 1.0)],
+                gate_probs: vec![0.25; 4],
+            })
+            .collect();
+        // Every token collapses onto expert 0.
+        let collapsed: Vec<TopKRouting> = (0..8)
+            .map(|_| TopKRouting {
+                selections: vec![(0, 1.0)],
+                gate_probs: vec![0.7, 0.1, 0.1, 0.1],
+            })
+            .collect();
+
+        let uniform_loss = router.aux_loss(&uniform);
+        let collapsed_loss = router.aux_loss(&collapsed);
+        assert!((uniform_loss - 1.0).abs() < 1e-6);
+        assert!((collapsed_loss - 2.8).abs() < 1e-5);
+        assert!(collapsed_loss > uniform_loss);
+
+        // Partially skewed routing lands in between.
+        let skewed: Vec<TopKRouting> = (0..8)
+            .map(|i| TopKRouting {
+                selections: vec![(if i < 5 { 0 } else { i % 4 }, 1.0)],
+                gate_probs: vec![0.4, 0.2, 0.2, 0.2],
+            })
+            .collect();
+        let skewed_loss = router.aux_loss(&skewed);
+        assert!(skewed_loss > uniform_loss && skewed_loss < collapsed_loss);
+
+        assert_eq!(router.expert_counts(&uniform), vec![2, 2, 2, 2]);
+        assert_eq!(router.expert_counts(&collapsed), vec![8, 0, 0, 0]);
+        assert_eq!(router.aux_loss(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_route_with_probs_matches_route() {
+        let router = LearnedRouter::new(6, 16, 2);
+        let x: Vec<f32> = (0..16).map(|i| (i as f32 * 0.3).sin()).collect();
+
+        let decision = router.route_with_probs(&x);
+        assert_eq!(decision.selections, router.route(&x));
+        assert_eq!(decision.gate_probs, router.compute_gate(&x));
+    }
 }
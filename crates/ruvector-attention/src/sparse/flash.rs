@@ -3,7 +3,7 @@
 //! Memory: O(block_size) for attention matrix instead of O(n²)
 
 use crate::error::{AttentionError, AttentionResult};
-use crate::traits::Attention;
+use crate::traits::{Attention, Gradients};
 
 /// Flash attention with block-wise computation
 ///
@@ -55,30 +55,19 @@ impl FlashAttention {
             })
             .collect()
     }
-}
 
-impl Attention for FlashAttention {
-    fn compute(
+    /// Forward pass that also returns the row logsumexp of the scores.
+    ///
+    /// The logsumexp is all [`FlashAttention::backward`] needs to recompute
+    /// the attention probabilities block by block. It is `-inf` when every
+    /// key is masked.
+    pub fn forward_with_lse(
         &self,
         query: &[f32],
         keys: &[&[f32]],
         values: &[&[f32]],
-    ) -> AttentionResult<Vec<f32>> {
-        if keys.is_empty() {
-            return Err(AttentionError::InvalidConfig("Empty keys".to_string()));
-        }
-        if keys.len() != values.len() {
-            return Err(AttentionError::DimensionMismatch {
-                expected: keys.len(),
-                actual: values.len(),
-            });
-        }
-        if query.len() != self.dim {
-            return Err(AttentionError::DimensionMismatch {
-                expected: self.dim,
-                actual: query.len(),
-            });
-        }
+    ) -> AttentionResult<(Vec<f32>, f32)> {
+        self.check_inputs(query, keys, values)?;
 
         let n = keys.len();
         let value_dim = values[0].len();
@@ -138,7 +127,133 @@ impl Attention for FlashAttention {
             output.iter_mut().for_each(|o| *o /= sum_exp);
         }
 
-        Ok(output)
+        Ok((output, max_so_far + sum_exp.ln()))
+    }
+
+    /// Backward pass using the recomputation trick.
+    ///
+    /// Takes the `output` and `lse` saved by [`FlashAttention::forward_with_lse`]
+    /// and rebuilds the probabilities `p_j = exp(s_j - lse)` one block of keys
+    /// at a time, so the attention row is never materialized. With
+    /// `D = dO . O`, each key contributes `dV_j = p_j dO` and
+    /// `dS_j = p_j (dO . v_j - D)`, from which `dq` and `dk_j` follow.
+    pub fn backward(
+        &self,
+        grad_output: &[f32],
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+        output: &[f32],
+        lse: f32,
+    ) -> AttentionResult<Gradients> {
+        self.check_inputs(query, keys, values)?;
+        let value_dim = values[0].len();
+        if grad_output.len() != value_dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: value_dim,
+                actual: grad_output.len(),
+            });
+        }
+        if output.len() != value_dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: value_dim,
+                actual: output.len(),
+            });
+        }
+
+        let n = keys.len();
+        let mut query_grad = vec![0.0f32; self.dim];
+        let mut keys_grad = vec![vec![0.0f32; self.dim]; n];
+        let mut values_grad = vec![vec![0.0f32; value_dim]; n];
+
+        if !lse.is_finite() {
+            // Every key was masked: the output is constant zero.
+            return Ok(Gradients {
+                query_grad,
+                keys_grad,
+                values_grad,
+                attention_weights_grad: None,
+            });
+        }
+
+        let delta: f32 = grad_output.iter().zip(output).map(|(g, o)| g * o).sum();
+
+        for block_start in (0..n).step_by(self.block_size) {
+            let block_end = (block_start + self.block_size).min(n);
+            let block_scores =
+                self.compute_block_scores(query, &keys[block_start..block_end], block_start);
+
+            for (local_idx, &score) in block_scores.iter().enumerate() {
+                if !score.is_finite() {
+                    continue;
+                }
+                let idx = block_start + local_idx;
+                let p = (score - lse).exp();
+
+                for (dv, &g) in values_grad[idx].iter_mut().zip(grad_output) {
+                    *dv = p * g;
+                }
+
+                let dp: f32 = grad_output
+                    .iter()
+                    .zip(values[idx])
+                    .map(|(g, v)| g * v)
+                    .sum();
+                let ds = p * (dp - delta) * self.scale;
+
+                for ((dq, dk), (&q, &k)) in query_grad
+                    .iter_mut()
+                    .zip(keys_grad[idx].iter_mut())
+                    .zip(query.iter().zip(keys[idx]))
+                {
+                    *dq += ds * k;
+                    *dk = ds * q;
+                }
+            }
+        }
+
+        Ok(Gradients {
+            query_grad,
+            keys_grad,
+            values_grad,
+            attention_weights_grad: None,
+        })
+    }
+
+    fn check_inputs(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<()> {
+        if keys.is_empty() {
+            return Err(AttentionError::InvalidConfig("Empty keys".to_string()));
+        }
+        if keys.len() != values.len() {
+            return Err(AttentionError::DimensionMismatch {
+                expected: keys.len(),
+                actual: values.len(),
+            });
+        }
+        if query.len() != self.dim {
+            return Err(AttentionError::DimensionMismatch {
+                expected: self.dim,
+                actual: query.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Attention for FlashAttention {
+    fn compute(
+        &self,
+        query: &[f32],
+        keys: &[&[f32]],
+        values: &[&[f32]],
+    ) -> AttentionResult<Vec<f32>> {
+        self.forward_with_lse(query, keys, values)
+            .map(|(output, _)| output)
     }
 
     fn compute_with_mask(
@@ -224,4 +339,98 @@ mod tests {
         let result = attention.compute(&query, &keys_refs, &values_refs).unwrap();
         assert_eq!(result.len(), 32);
     }
+
+    #[test]
+    fn test_backward_matches_finite_difference() {
+        let dim = 8;
+        let flash = FlashAttention::new(dim, 3);
+        let naive = ScaledDotProductAttention::new(dim);
+
+        let query: Vec<f32> = (0..dim).map(|i| ((i as f32) * 0.7).sin()).collect();
+        let keys: Vec<Vec<f32>> = (0..4)
+            .map(|t| {
+                (0..dim)
+                    .map(|i| ((t * dim + i) as f32 * 0.31).cos())
+                    .collect()
+            })
+            .collect();
+        let values: Vec<Vec<f32>> = (0..4)
+            .map(|t| {
+                (0..dim)
+                    .map(|i| ((t * dim + i) as f32 * 0.53).sin())
+                    .collect()
+            })
+            .collect();
+        let grad_output: Vec<f32> = (0..dim).map(|i| 0.5 - (i as f32) * 0.1).collect();
+
+        // Scalar loss L = dO . naive_attention(q, K, V).
+        let loss = |q: &[f32], k: &[Vec<f32>], v: &[Vec<f32>]| -> f32 {
+            let k_refs: Vec<&[f32]> = k.iter().map(|x| x.as_slice()).collect();
+            let v_refs: Vec<&[f32]> = v.iter().map(|x| x.as_slice()).collect();
+            naive
+                .compute(q, &k_refs, &v_refs)
+                .unwrap()
+                .iter()
+                .zip(&grad_output)
+                .map(|(o, g)| o * g)
+                .sum()
+        };
+
+        let keys_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let values_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+        let (output, lse) = flash
+            .forward_with_lse(&query, &keys_refs, &values_refs)
+            .unwrap();
+        let grads = flash
+            .backward(&grad_output, &query, &keys_refs, &values_refs, &output, lse)
+            .unwrap();
+
+        let eps = 1e-2f32;
+        let tol = 2e-3f32;
+        for i in 0..dim {
+            let (mut plus, mut minus) = (query.clone(), query.clone());
+            plus[i] += eps;
+            minus[i] -= eps;
+            let numeric =
+                (loss(&plus, &keys, &values) - loss(&minus, &keys, &values)) / (2.0 * eps);
+            assert!(
+                (grads.query_grad[i] - numeric).abs() < tol,
+                "dq[{}]: {} vs {}",
+                i,
+                grads.query_grad[i],
+                numeric
+            );
+        }
+        for t in 0..4 {
+            for i in 0..dim {
+                let (mut plus, mut minus) = (keys.clone(), keys.clone());
+                plus[t][i] += eps;
+                minus[t][i] -= eps;
+                let numeric =
+                    (loss(&query, &plus, &values) - loss(&query, &minus, &values)) / (2.0 * eps);
+                assert!(
+                    (grads.keys_grad[t][i] - numeric).abs() < tol,
+                    "dk[{}][{}]: {} vs {}",
+                    t,
+                    i,
+                    grads.keys_grad[t][i],
+                    numeric
+                );
+
+                let (mut plus, mut minus) = (values.clone(), values.clone());
+                plus[t][i] += eps;
+                minus[t][i] -= eps;
+                let numeric =
+                    (loss(&query, &keys, &plus) - loss(&query, &keys, &minus)) / (2.0 * eps);
+                assert!(
+                    (grads.values_grad[t][i] - numeric).abs() < tol,
+                    "dv[{}][{}]: {} vs {}",
+                    t,
+                    i,
+                    grads.values_grad[t][i],
+                    numeric
+                );
+            }
+        }
+    }
 }
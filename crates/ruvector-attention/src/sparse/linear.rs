@@ -14,8 +14,18 @@ pub enum KernelType {
     ReLU,
     /// ELU kernel
     ELU,
+    /// `elu(x) + 1` applied to the inputs directly (Katharopoulos et al.),
+    /// no random projection; the feature dimension equals `dim`
+    EluPlusOne,
+    /// Softmax approximation with orthogonal random features (Performer
+    /// FAVOR+); rows are orthogonalized in blocks of `dim` and keep the
+    /// norms of the original Gaussian draws
+    Favor,
 }
 
+/// Seed used for the random projection unless overridden
+const DEFAULT_SEED: u64 = 42;
+
 /// Linear attention with random feature maps
 ///
 /// Uses kernel trick to achieve O(n * k * d) complexity instead of O(n² * d).
@@ -23,6 +33,7 @@ pub struct LinearAttention {
     dim: usize,
     num_features: usize,
     kernel: KernelType,
+    seed: u64,
     /// Random projection matrix [num_features x dim]
     random_features: Vec<f32>,
 }
@@ -35,22 +46,60 @@ impl LinearAttention {
 
     /// Create with specific kernel type
     pub fn with_kernel(dim: usize, num_features: usize, kernel: KernelType) -> Self {
-        // Initialize random features using Box-Muller for Gaussian
-        let random_features = Self::generate_random_features(dim, num_features);
+        let random_features = Self::projection(&kernel, dim, num_features, DEFAULT_SEED);
 
         Self {
             dim,
             num_features,
             kernel,
+            seed: DEFAULT_SEED,
             random_features,
         }
     }
 
-    fn generate_random_features(dim: usize, num_features: usize) -> Vec<f32> {
+    /// Regenerate the random projection from `seed`
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.random_features = Self::projection(&self.kernel, self.dim, self.num_features, seed);
+        self
+    }
+
+    /// Kernel used for the feature map
+    pub fn kernel(&self) -> &KernelType {
+        &self.kernel
+    }
+
+    /// Seed of the random projection
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Length of the feature vectors phi(x)
+    pub fn feature_dim(&self) -> usize {
+        match self.kernel {
+            KernelType::EluPlusOne => self.dim,
+            _ => self.num_features,
+        }
+    }
+
+    fn projection(kernel: &KernelType, dim: usize, num_features: usize, seed: u64) -> Vec<f32> {
+        match kernel {
+            KernelType::EluPlusOne => Vec::new(),
+            KernelType::Favor => {
+                let mut features = Self::generate_random_features(dim, num_features, seed);
+                Self::orthogonalize(&mut features, dim, num_features);
+                features
+            }
+            _ => Self::generate_random_features(dim, num_features, seed),
+        }
+    }
+
+    fn generate_random_features(dim: usize, num_features: usize, seed: u64) -> Vec<f32> {
         use std::f32::consts::PI;
 
+        // Initialize random features using Box-Muller for Gaussian
         let mut features = Vec::with_capacity(num_features * dim);
-        let mut seed = 42u64;
+        let mut seed = seed;
 
         for _ in 0..((num_features * dim + 1) / 2) {
             // Simple LCG for reproducibility
@@ -78,8 +127,49 @@ impl LinearAttention {
         features
     }
 
+    /// Gram-Schmidt over each block of `dim` rows, then restore every row's
+    /// original norm so the marginals stay Gaussian
+    fn orthogonalize(features: &mut [f32], dim: usize, num_features: usize) {
+        for block_start in (0..num_features).step_by(dim.max(1)) {
+            let block_end = (block_start + dim).min(num_features);
+            let norms: Vec<f32> = (block_start..block_end)
+                .map(|r| {
+                    let row = &features[r * dim..(r + 1) * dim];
+                    row.iter().map(|x| x * x).sum::<f32>().sqrt()
+                })
+                .collect();
+
+            for r in block_start..block_end {
+                for prev in block_start..r {
+                    let dot: f32 = (0..dim)
+                        .map(|j| features[r * dim + j] * features[prev * dim + j])
+                        .sum();
+                    for j in 0..dim {
+                        features[r * dim + j] -= dot * features[prev * dim + j];
+                    }
+                }
+                let row = &mut features[r * dim..(r + 1) * dim];
+                let norm = row.iter().map(|x| x * x).sum::<f32>().sqrt().max(1e-10);
+                row.iter_mut().for_each(|x| *x /= norm);
+            }
+
+            for (r, norm) in (block_start..block_end).zip(norms) {
+                features[r * dim..(r + 1) * dim]
+                    .iter_mut()
+                    .for_each(|x| *x *= norm);
+            }
+        }
+    }
+
     /// Apply feature map to input
     fn feature_map(&self, x: &[f32]) -> Vec<f32> {
+        if let KernelType::EluPlusOne = self.kernel {
+            return x
+                .iter()
+                .map(|&xi| if xi >= 0.0 { xi + 1.0 } else { xi.exp() })
+                .collect();
+        }
+
         let mut phi = vec![0.0f32; self.num_features];
 
         for (i, phi_i) in phi.iter_mut().enumerate() {
@@ -90,7 +180,7 @@ impl LinearAttention {
                 .sum();
 
             *phi_i = match self.kernel {
-                KernelType::Softmax => {
+                KernelType::Softmax | KernelType::Favor => {
                     // FAVOR+: exp(projection - ||x||²/2) / sqrt(num_features)
                     let norm_sq: f32 = x.iter().map(|xi| xi * xi).sum();
                     (projection - norm_sq / 2.0).exp() / (self.num_features as f32).sqrt()
@@ -103,6 +193,7 @@ impl LinearAttention {
                         projection.exp() - 1.0
                     }
                 }
+                KernelType::EluPlusOne => unreachable!("handled above"),
             };
         }
 
@@ -138,8 +229,9 @@ impl Attention for LinearAttention {
 
         // Compute sum_i phi(K_i)^T * V_i  and  sum_i phi(K_i)
         let value_dim = values[0].len();
-        let mut kv_sum = vec![0.0f32; self.num_features * value_dim]; // [num_features x value_dim]
-        let mut k_sum = vec![0.0f32; self.num_features];
+        let feature_dim = self.feature_dim();
+        let mut kv_sum = vec![0.0f32; feature_dim * value_dim]; // [feature_dim x value_dim]
+        let mut k_sum = vec![0.0f32; feature_dim];
 
         for (key, value) in keys.iter().zip(values.iter()) {
             let phi_k = self.feature_map(key);
@@ -220,7 +312,13 @@ mod tests {
 
     #[test]
     fn test_kernel_types() {
-        for kernel in [KernelType::Softmax, KernelType::ReLU, KernelType::ELU] {
+        for kernel in [
+            KernelType::Softmax,
+            KernelType::ReLU,
+            KernelType::ELU,
+            KernelType::EluPlusOne,
+            KernelType::Favor,
+        ] {
             let attention = LinearAttention::with_kernel(32, 16, kernel);
 
             let query = vec![1.0; 32];
@@ -234,4 +332,97 @@ mod tests {
             assert_eq!(result.len(), 32);
         }
     }
+
+    fn inputs(dim: usize, n: usize) -> (Vec<f32>, Vec<Vec<f32>>, Vec<Vec<f32>>) {
+        let query = (0..dim).map(|i| (i as f32 * 0.37).sin()).collect();
+        let keys = (0..n)
+            .map(|t| {
+                (0..dim)
+                    .map(|i| ((t * dim + i) as f32 * 0.23).cos())
+                    .collect()
+            })
+            .collect();
+        let values = (0..n)
+            .map(|t| {
+                (0..dim)
+                    .map(|i| ((t * dim + i) as f32 * 0.51).sin())
+                    .collect()
+            })
+            .collect();
+        (query, keys, values)
+    }
+
+    #[test]
+    fn test_default_kernel_unchanged() {
+        let (query, keys, values) = inputs(16, 12);
+        let keys_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let values_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+
+        let default = LinearAttention::new(16, 8);
+        assert!(matches!(default.kernel(), KernelType::Softmax));
+        assert_eq!(default.seed(), 42);
+
+        let explicit = LinearAttention::with_kernel(16, 8, KernelType::Softmax).with_seed(42);
+        assert_eq!(
+            default.compute(&query, &keys_refs, &values_refs).unwrap(),
+            explicit.compute(&query, &keys_refs, &values_refs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_elu_plus_one_matches_reference() {
+        let dim = 8;
+        let (query, keys, values) = inputs(dim, 6);
+        let keys_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let values_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+
+        let attention = LinearAttention::with_kernel(dim, 4, KernelType::EluPlusOne);
+        assert_eq!(attention.feature_dim(), dim);
+        let out = attention.compute(&query, &keys_refs, &values_refs).unwrap();
+
+        // sim(q, k) = phi(q) . phi(k); output = sum sim * v / sum sim
+        let phi = |x: &[f32]| -> Vec<f32> {
+            x.iter()
+                .map(|&v| if v > 0.0 { v + 1.0 } else { v.exp() })
+                .collect()
+        };
+        let phi_q = phi(&query);
+        let sims: Vec<f32> = keys
+            .iter()
+            .map(|k| phi_q.iter().zip(phi(k)).map(|(a, b)| a * b).sum())
+            .collect();
+        let total: f32 = sims.iter().sum();
+        for j in 0..dim {
+            let expected: f32 =
+                sims.iter().zip(&values).map(|(s, v)| s * v[j]).sum::<f32>() / total;
+            assert!((out[j] - expected).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_favor_orthogonal_and_seeded() {
+        let dim = 8;
+        let attention = LinearAttention::with_kernel(dim, 12, KernelType::Favor).with_seed(7);
+        let rows: Vec<&[f32]> = attention.random_features.chunks(dim).collect();
+
+        // Rows inside the first block of `dim` are mutually orthogonal.
+        for a in 0..dim {
+            for b in 0..a {
+                let dot: f32 = rows[a].iter().zip(rows[b]).map(|(x, y)| x * y).sum();
+                assert!(dot.abs() < 1e-4, "rows {} and {}: {}", a, b, dot);
+            }
+        }
+
+        let (query, keys, values) = inputs(dim, 5);
+        let keys_refs: Vec<&[f32]> = keys.iter().map(|k| k.as_slice()).collect();
+        let values_refs: Vec<&[f32]> = values.iter().map(|v| v.as_slice()).collect();
+        let run = |seed: u64| {
+            LinearAttention::with_kernel(dim, 12, KernelType::Favor)
+                .with_seed(seed)
+                .compute(&query, &keys_refs, &values_refs)
+                .unwrap()
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
 }
@@ -2,6 +2,15 @@
 
 use std::collections::HashSet;
 
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::error::{AttentionError, AttentionResult};
+
+/// Seed for the random blocks of [`SparseMaskBuilder::block_sparse`]
+const BLOCK_SPARSE_SEED: u64 = 42;
+
 /// Sparse mask for attention patterns
 #[derive(Clone, Debug)]
 pub struct AttentionMask {
@@ -112,6 +121,109 @@ impl SparseMaskBuilder {
         }
     }
 
+    /// Start from a causal sliding window: token `i` attends to
+    /// positions `[i - window, i]`.
+    ///
+    /// Chain [`with_global_tokens`](Self::with_global_tokens) for
+    /// Longformer-style local + global patterns.
+    pub fn sliding_window(seq_len: usize, window: usize) -> Self {
+        Self::new(seq_len).with_sliding_window(window)
+    }
+
+    /// Add causal sliding window pattern (each token sees itself and the
+    /// `window` tokens before it)
+    pub fn with_sliding_window(mut self, window: usize) -> Self {
+        for i in 0..self.n {
+            for j in i.saturating_sub(window)..=i {
+                self.indices.push((i, j));
+            }
+        }
+        self
+    }
+
+    /// Start from a BigBird-style block-sparse pattern
+    ///
+    /// The sequence is split into blocks of `block_size` tokens. Every
+    /// query block attends to the global first block, to the
+    /// `window_blocks` blocks on each side of itself, and to
+    /// `num_random_blocks` further blocks drawn at random; the global block
+    /// also attends to every block. Uses a fixed seed, see
+    /// [`block_sparse_with_seed`](Self::block_sparse_with_seed).
+    pub fn block_sparse(
+        seq_len: usize,
+        block_size: usize,
+        num_random_blocks: usize,
+        window_blocks: usize,
+    ) -> AttentionResult<Self> {
+        Self::block_sparse_with_seed(
+            seq_len,
+            block_size,
+            num_random_blocks,
+            window_blocks,
+            BLOCK_SPARSE_SEED,
+        )
+    }
+
+    /// [`block_sparse`](Self::block_sparse) with an explicit seed for the
+    /// random blocks
+    pub fn block_sparse_with_seed(
+        seq_len: usize,
+        block_size: usize,
+        num_random_blocks: usize,
+        window_blocks: usize,
+        seed: u64,
+    ) -> AttentionResult<Self> {
+        if seq_len == 0 || block_size == 0 {
+            return Err(AttentionError::InvalidConfig(
+                "seq_len and block_size must be greater than 0".to_string(),
+            ));
+        }
+        let num_blocks = seq_len.div_ceil(block_size);
+        let requested = 1 + 2 * window_blocks + 1 + num_random_blocks;
+        if requested > num_blocks {
+            return Err(AttentionError::InvalidConfig(format!(
+                "block-sparse pattern needs {} blocks per row (1 global, {} window, {} random) \
+                 but the sequence has only {}",
+                requested,
+                2 * window_blocks + 1,
+                num_random_blocks,
+                num_blocks
+            )));
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut builder = Self::new(seq_len);
+        for row_block in 0..num_blocks {
+            let cols: Vec<usize> = if row_block == 0 {
+                (0..num_blocks).collect()
+            } else {
+                let window_end = (row_block + window_blocks).min(num_blocks - 1);
+                let mut cols: Vec<usize> = std::iter::once(0)
+                    .chain(row_block.saturating_sub(window_blocks)..=window_end)
+                    .collect();
+                cols.dedup();
+                let candidates: Vec<usize> =
+                    (1..num_blocks).filter(|b| !cols.contains(b)).collect();
+                cols.extend(candidates.choose_multiple(&mut rng, num_random_blocks));
+                cols
+            };
+            for col_block in cols {
+                builder.push_block(row_block, col_block, block_size);
+            }
+        }
+        Ok(builder)
+    }
+
+    fn push_block(&mut self, row_block: usize, col_block: usize, block_size: usize) {
+        let rows = row_block * block_size..((row_block + 1) * block_size).min(self.n);
+        let cols = col_block * block_size..((col_block + 1) * block_size).min(self.n);
+        for i in rows {
+            for j in cols.clone() {
+                self.indices.push((i, j));
+            }
+        }
+    }
+
     /// Add local window pattern
     pub fn with_local_window(mut self, window_size: usize) -> Self {
         let half_window = window_size / 2;
@@ -204,4 +316,138 @@ mod tests {
             assert!(mask.is_attended(i, 0));
         }
     }
+
+    #[test]
+    fn test_sliding_window_density() {
+        let (n, window) = (16, 3);
+        let mask = SparseMaskBuilder::sliding_window(n, window).build();
+
+        // Row i holds min(i, window) + 1 entries.
+        let expected: usize = (0..n).map(|i| i.min(window) + 1).sum();
+        assert_eq!(mask.nnz(), expected);
+        assert_eq!(mask.density(), expected as f32 / (n * n) as f32);
+
+        assert!(mask.is_attended(10, 7));
+        assert!(mask.is_attended(10, 10));
+        assert!(!mask.is_attended(10, 6));
+        assert!(!mask.is_attended(10, 11));
+    }
+
+    #[test]
+    fn test_sliding_window_with_global_tokens() {
+        let (n, window) = (16, 2);
+        let globals = [0, 9];
+        let mask = SparseMaskBuilder::sliding_window(n, window)
+            .with_global_tokens(&globals)
+            .build();
+
+        for &g in &globals {
+            for i in 0..n {
+                assert!(mask.is_attended(g, i), "global {} -> {}", g, i);
+                assert!(mask.is_attended(i, g), "{} -> global {}", i, g);
+            }
+        }
+
+        // Non-global pairs outside the band stay masked.
+        assert!(!mask.is_attended(12, 5));
+        assert!(!mask.is_attended(5, 12));
+
+        // Band plus full rows and columns for each global, minus overlaps.
+        let expected = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| {
+                (j <= i && i - j <= window) || globals.contains(&i) || globals.contains(&j)
+            })
+            .count();
+        assert_eq!(mask.nnz(), expected);
+    }
+
+    /// Active column blocks for each row block of a block-sparse mask.
+    fn active_blocks(mask: &AttentionMask, block_size: usize) -> Vec<Vec<usize>> {
+        let num_blocks = mask.shape.0.div_ceil(block_size);
+        (0..num_blocks)
+            .map(|rb| {
+                (0..num_blocks)
+                    .filter(|&cb| mask.is_attended(rb * block_size, cb * block_size))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_block_sparse_row_counts() {
+        let (block_size, random, window) = (4, 2, 1);
+        let mask = SparseMaskBuilder::block_sparse(64, block_size, random, window)
+            .unwrap()
+            .build();
+        let blocks = active_blocks(&mask, block_size);
+        assert_eq!(blocks.len(), 16);
+
+        // The global block attends everywhere.
+        assert_eq!(blocks[0].len(), 16);
+        // Rows whose window doesn't touch the global block: 1 + 3 + 2.
+        for row in &blocks[2..15] {
+            assert_eq!(row.len(), 1 + (2 * window + 1) + random);
+        }
+        // Edge rows lose window blocks past the ends or shared with block 0.
+        assert_eq!(blocks[1].len(), 3 + random);
+        assert_eq!(blocks[15].len(), 1 + 2 + random);
+
+        // Blocks are all-or-nothing: every token pair inside is attended.
+        for (rb, row) in blocks.iter().enumerate() {
+            for &cb in row {
+                for i in rb * block_size..(rb + 1) * block_size {
+                    for j in cb * block_size..(cb + 1) * block_size {
+                        assert!(mask.is_attended(i, j));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_block_sparse_fixed_blocks_every_seed() {
+        let (block_size, window) = (2, 2);
+        let num_blocks = 12;
+        let mut draws = HashSet::new();
+        for seed in 0..20 {
+            let mask = SparseMaskBuilder::block_sparse_with_seed(
+                num_blocks * block_size,
+                block_size,
+                3,
+                window,
+                seed,
+            )
+            .unwrap()
+            .build();
+            let blocks = active_blocks(&mask, block_size);
+            for (rb, row) in blocks.iter().enumerate() {
+                assert!(row.contains(&0), "row {} misses global block", rb);
+                assert!(blocks[0].contains(&rb));
+                for wb in rb.saturating_sub(window)..=(rb + window).min(num_blocks - 1) {
+                    assert!(row.contains(&wb), "row {} misses window block {}", rb, wb);
+                }
+            }
+            draws.insert(blocks);
+        }
+        // The random component actually varies with the seed.
+        assert!(draws.len() > 1);
+
+        let a = SparseMaskBuilder::block_sparse_with_seed(24, 2, 3, 2, 5)
+            .unwrap()
+            .build();
+        let b = SparseMaskBuilder::block_sparse_with_seed(24, 2, 3, 2, 5)
+            .unwrap()
+            .build();
+        assert_eq!(a.indices, b.indices);
+    }
+
+    #[test]
+    fn test_block_sparse_rejects_oversubscription() {
+        // 8 blocks: 1 global + 5 window + 2 random fits, 3 random does not.
+        assert!(SparseMaskBuilder::block_sparse(32, 4, 2, 2).is_ok());
+        assert!(SparseMaskBuilder::block_sparse(32, 4, 3, 2).is_err());
+        assert!(SparseMaskBuilder::block_sparse(32, 0, 1, 1).is_err());
+        assert!(SparseMaskBuilder::block_sparse(0, 4, 0, 0).is_err());
+    }
 }
@@ -8,6 +8,6 @@ pub mod local_global;
 pub mod mask;
 
 pub use flash::FlashAttention;
-pub use linear::LinearAttention;
+pub use linear::{KernelType, LinearAttention};
 pub use local_global::LocalGlobalAttention;
 pub use mask::{AttentionMask, SparseMaskBuilder};
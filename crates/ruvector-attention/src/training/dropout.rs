@@ -0,0 +1,173 @@
+//! Attention dropout
+//!
+//! Randomly zeroes softmax weights during training and rescales the
+//! survivors by `1 / (1 - p)` so the expected output is unchanged.
+//! In eval mode the weights pass through untouched.
+
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::AttentionConfig;
+use crate::error::{AttentionError, AttentionResult};
+
+/// Dropout over attention weights with a train/eval switch
+pub struct AttentionDropout {
+    p: f32,
+    training: bool,
+    // Forward passes take `&self`, so the RNG sits behind a lock.
+    rng: Mutex<StdRng>,
+}
+
+impl AttentionDropout {
+    /// Create dropout with probability `p`, seeded from OS entropy
+    pub fn new(p: f32) -> AttentionResult<Self> {
+        Self::with_rng(p, StdRng::from_entropy())
+    }
+
+    /// Create dropout with a fixed seed for reproducible masks
+    pub fn with_seed(p: f32, seed: u64) -> AttentionResult<Self> {
+        Self::with_rng(p, StdRng::seed_from_u64(seed))
+    }
+
+    /// Create dropout drawing from the given RNG
+    pub fn with_rng(p: f32, rng: StdRng) -> AttentionResult<Self> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(AttentionError::InvalidConfig(
+                "dropout must be in range [0.0, 1.0]".to_string(),
+            ));
+        }
+        Ok(Self {
+            p,
+            training: true,
+            rng: Mutex::new(rng),
+        })
+    }
+
+    /// Create dropout using `config.dropout`
+    pub fn from_config(config: &AttentionConfig) -> AttentionResult<Self> {
+        Self::new(config.dropout)
+    }
+
+    /// Dropout probability
+    pub fn p(&self) -> f32 {
+        self.p
+    }
+
+    /// Whether dropout is currently applied
+    pub fn is_training(&self) -> bool {
+        self.training
+    }
+
+    /// Switch between training (dropout active) and eval (no-op)
+    pub fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+
+    /// Enable dropout
+    pub fn train(&mut self) {
+        self.training = true;
+    }
+
+    /// Disable dropout
+    pub fn eval(&mut self) {
+        self.training = false;
+    }
+
+    /// Apply dropout to `weights` in place
+    pub fn apply(&self, weights: &mut [f32]) {
+        if !self.training || self.p == 0.0 {
+            return;
+        }
+        if self.p >= 1.0 {
+            weights.iter_mut().for_each(|w| *w = 0.0);
+            return;
+        }
+
+        let keep_scale = 1.0 / (1.0 - self.p);
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        for w in weights.iter_mut() {
+            if rng.gen::<f32>() < self.p {
+                *w = 0.0;
+            } else {
+                *w *= keep_scale;
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for AttentionDropout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AttentionDropout")
+            .field("p", &self.p)
+            .field("training", &self.training)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zeroing_rate() {
+        let p = 0.3;
+        let dropout = AttentionDropout::with_seed(p, 7).unwrap();
+
+        let n = 20_000;
+        let mut weights = vec![1.0_f32; n];
+        dropout.apply(&mut weights);
+
+        let zeroed = weights.iter().filter(|&&w| w == 0.0).count();
+        let rate = zeroed as f32 / n as f32;
+        assert!((rate - p).abs() < 0.02, "zeroing rate {}", rate);
+
+        // Survivors are rescaled so the mean is preserved
+        let keep_scale = 1.0 / (1.0 - p);
+        assert!(weights
+            .iter()
+            .all(|&w| w == 0.0 || (w - keep_scale).abs() < 1e-6));
+        let mean = weights.iter().sum::<f32>() / n as f32;
+        assert!((mean - 1.0).abs() < 0.05, "mean {}", mean);
+    }
+
+    #[test]
+    fn test_seeded_masks_repeat() {
+        let a = AttentionDropout::with_seed(0.5, 42).unwrap();
+        let b = AttentionDropout::with_seed(0.5, 42).unwrap();
+
+        let mut wa = vec![0.25_f32; 64];
+        let mut wb = wa.clone();
+        a.apply(&mut wa);
+        b.apply(&mut wb);
+        assert_eq!(wa, wb);
+    }
+
+    #[test]
+    fn test_eval_mode_is_noop() {
+        let mut dropout = AttentionDropout::with_seed(0.9, 1).unwrap();
+        dropout.eval();
+        assert!(!dropout.is_training());
+
+        let original = vec![0.1_f32, 0.2, 0.3, 0.4];
+        let mut weights = original.clone();
+        dropout.apply(&mut weights);
+        assert_eq!(weights, original);
+
+        dropout.train();
+        dropout.apply(&mut weights);
+        assert_ne!(weights, original);
+    }
+
+    #[test]
+    fn test_invalid_probability() {
+        assert!(AttentionDropout::new(-0.1).is_err());
+        assert!(AttentionDropout::new(1.5).is_err());
+
+        let all = AttentionDropout::with_seed(1.0, 0).unwrap();
+        let mut weights = vec![0.5_f32; 4];
+        all.apply(&mut weights);
+        assert_eq!(weights, vec![0.0; 4]);
+    }
+}
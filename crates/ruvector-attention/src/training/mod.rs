@@ -5,16 +5,21 @@
 //! - Optimizers (SGD, Adam, AdamW)
 //! - Curriculum learning schedulers
 //! - Hard negative mining strategies
+//! - Attention dropout
 
 pub mod curriculum;
+pub mod dropout;
 pub mod loss;
 pub mod mining;
 pub mod optimizer;
 
 pub use curriculum::{CurriculumScheduler, CurriculumStage, DecayType, TemperatureAnnealing};
+pub use dropout::AttentionDropout;
 pub use loss::{InfoNCELoss, LocalContrastiveLoss, Loss, Reduction, SpectralRegularization};
 pub use mining::{HardNegativeMiner, MiningStrategy, NegativeMiner};
-pub use optimizer::{Adam, AdamW, Optimizer, SGD};
+pub use optimizer::{
+    clip_grad_norm, Adam, AdamW, LearningRateScheduler, LrScheduler, Optimizer, SGD,
+};
 
 #[cfg(test)]
 mod tests {
@@ -2,6 +2,10 @@
 //!
 //! Provides standard optimizers with momentum and adaptive learning rates.
 
+use std::borrow::Cow;
+
+use crate::traits::Gradients;
+
 /// Optimizer trait for parameter updates
 pub trait Optimizer: Send + Sync {
     /// Update parameters using gradients
@@ -17,6 +21,51 @@ pub trait Optimizer: Send + Sync {
     fn set_learning_rate(&mut self, lr: f32);
 }
 
+/// Clip gradients by their global L2 norm
+///
+/// Computes the norm across the query, key and value gradients together
+/// and, if it exceeds `max_norm`, rescales all of them in place so the
+/// norm equals `max_norm`. `attention_weights_grad` is diagnostic only and
+/// left untouched. Returns the norm before clipping.
+pub fn clip_grad_norm(grads: &mut Gradients, max_norm: f32) -> f32 {
+    let sum_sq: f32 = grads.query_grad.iter().map(|g| g * g).sum::<f32>()
+        + grads
+            .keys_grad
+            .iter()
+            .chain(&grads.values_grad)
+            .flatten()
+            .map(|g| g * g)
+            .sum::<f32>();
+    let norm = sum_sq.sqrt();
+
+    if norm > max_norm && norm > 0.0 {
+        let scale = max_norm / norm;
+        grads
+            .query_grad
+            .iter_mut()
+            .chain(grads.keys_grad.iter_mut().flatten())
+            .chain(grads.values_grad.iter_mut().flatten())
+            .for_each(|g| *g *= scale);
+    }
+
+    norm
+}
+
+/// Gradients rescaled to at most `max_norm` (L2), borrowed when no
+/// clipping is needed
+fn clip_to_norm(gradients: &[f32], max_norm: Option<f32>) -> Cow<'_, [f32]> {
+    let Some(max_norm) = max_norm else {
+        return Cow::Borrowed(gradients);
+    };
+    let norm = gradients.iter().map(|g| g * g).sum::<f32>().sqrt();
+    if norm > max_norm && norm > 0.0 {
+        let scale = max_norm / norm;
+        Cow::Owned(gradients.iter().map(|g| g * scale).collect())
+    } else {
+        Cow::Borrowed(gradients)
+    }
+}
+
 /// Stochastic Gradient Descent with momentum
 pub struct SGD {
     lr: f32,
@@ -24,6 +73,7 @@ pub struct SGD {
     weight_decay: f32,
     velocity: Vec<f32>,
     nesterov: bool,
+    max_grad_norm: Option<f32>,
 }
 
 impl SGD {
@@ -34,6 +84,7 @@ impl SGD {
             weight_decay: 0.0,
             velocity: vec![0.0; dim],
             nesterov: false,
+            max_grad_norm: None,
         }
     }
 
@@ -51,6 +102,12 @@ impl SGD {
         self.nesterov = nesterov;
         self
     }
+
+    /// Clip gradients to this global L2 norm before every step
+    pub fn with_max_grad_norm(mut self, max_norm: f32) -> Self {
+        self.max_grad_norm = Some(max_norm);
+        self
+    }
 }
 
 impl Optimizer for SGD {
@@ -58,6 +115,7 @@ impl Optimizer for SGD {
         if self.velocity.len() != params.len() {
             self.velocity = vec![0.0; params.len()];
         }
+        let gradients = clip_to_norm(gradients, self.max_grad_norm);
 
         for i in 0..params.len() {
             let mut g = gradients[i];
@@ -102,6 +160,7 @@ pub struct Adam {
     m: Vec<f32>, // First moment
     v: Vec<f32>, // Second moment
     t: usize,    // Timestep
+    max_grad_norm: Option<f32>,
 }
 
 impl Adam {
@@ -115,6 +174,7 @@ impl Adam {
             m: vec![0.0; dim],
             v: vec![0.0; dim],
             t: 0,
+            max_grad_norm: None,
         }
     }
 
@@ -133,6 +193,12 @@ impl Adam {
         self.weight_decay = wd;
         self
     }
+
+    /// Clip gradients to this global L2 norm before every step
+    pub fn with_max_grad_norm(mut self, max_norm: f32) -> Self {
+        self.max_grad_norm = Some(max_norm);
+        self
+    }
 }
 
 impl Optimizer for Adam {
@@ -141,6 +207,7 @@ impl Optimizer for Adam {
             self.m = vec![0.0; params.len()];
             self.v = vec![0.0; params.len()];
         }
+        let gradients = clip_to_norm(gradients, self.max_grad_norm);
 
         self.t += 1;
         let bias_correction1 = 1.0 - self.beta1.powi(self.t as i32);
@@ -201,6 +268,12 @@ impl AdamW {
         self.inner = self.inner.with_betas(beta1, beta2);
         self
     }
+
+    /// Clip gradients to this global L2 norm before every step
+    pub fn with_max_grad_norm(mut self, max_norm: f32) -> Self {
+        self.inner = self.inner.with_max_grad_norm(max_norm);
+        self
+    }
 }
 
 impl Optimizer for AdamW {
@@ -209,6 +282,7 @@ impl Optimizer for AdamW {
             self.inner.m = vec![0.0; params.len()];
             self.inner.v = vec![0.0; params.len()];
         }
+        let gradients = clip_to_norm(gradients, self.inner.max_grad_norm);
 
         self.inner.t += 1;
         let bias_correction1 = 1.0 - self.inner.beta1.powi(self.inner.t as i32);
@@ -246,7 +320,8 @@ impl Optimizer for AdamW {
     }
 }
 
-/// Learning rate scheduler
+/// Learning rate scheduler: linear warmup to `initial_lr` over
+/// `warmup_steps`, then cosine decay to `min_lr` over `decay_steps`
 pub struct LearningRateScheduler {
     initial_lr: f32,
     warmup_steps: usize,
@@ -290,23 +365,44 @@ impl LearningRateScheduler {
 
     /// Get learning rate without advancing
     pub fn get_lr(&self) -> f32 {
-        if self.current_step < self.warmup_steps {
+        self.lr_at(self.current_step)
+    }
+
+    /// Learning rate at an arbitrary `step`, independent of the internal
+    /// counter. Reaches `min_lr` at `total_steps()` and stays there.
+    pub fn lr_at(&self, step: usize) -> f32 {
+        if step < self.warmup_steps {
             // Linear warmup
-            self.initial_lr * (self.current_step + 1) as f32 / self.warmup_steps as f32
+            self.initial_lr * (step + 1) as f32 / self.warmup_steps as f32
         } else {
             // Cosine decay
-            let progress = (self.current_step - self.warmup_steps) as f32 / self.decay_steps as f32;
+            let progress = (step - self.warmup_steps) as f32 / self.decay_steps.max(1) as f32;
             let decay = 0.5 * (1.0 + (std::f32::consts::PI * progress.min(1.0)).cos());
             self.min_lr + (self.initial_lr - self.min_lr) * decay
         }
     }
 
+    /// Step at which the decay reaches `min_lr`
+    pub fn total_steps(&self) -> usize {
+        self.warmup_steps + self.decay_steps
+    }
+
+    /// Set the optimizer's learning rate for the current step and advance
+    pub fn apply<O: Optimizer + ?Sized>(&mut self, optimizer: &mut O) -> f32 {
+        let lr = self.step();
+        optimizer.set_learning_rate(lr);
+        lr
+    }
+
     /// Reset scheduler
     pub fn reset(&mut self) {
         self.current_step = 0;
     }
 }
 
+/// Short alias for [`LearningRateScheduler`]
+pub type LrScheduler = LearningRateScheduler;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -397,4 +493,112 @@ mod tests {
         let lr_end = scheduler.get_lr();
         assert!((lr_end - 0.0001).abs() < 1e-5);
     }
+
+    #[test]
+    fn test_lr_warmup_then_cosine() {
+        let max_lr = 0.01;
+        let floor = 0.001;
+        let scheduler = LrScheduler::new(max_lr)
+            .with_warmup(10)
+            .with_decay(90)
+            .with_min_lr(floor);
+        assert_eq!(scheduler.total_steps(), 100);
+
+        // Linear rise: equal increments, peak at the last warmup step
+        let increment = scheduler.lr_at(1) - scheduler.lr_at(0);
+        for step in 1..10 {
+            let delta = scheduler.lr_at(step) - scheduler.lr_at(step - 1);
+            assert!((delta - increment).abs() < 1e-7);
+        }
+        assert!((scheduler.lr_at(9) - max_lr).abs() < 1e-7);
+        assert!((scheduler.lr_at(10) - max_lr).abs() < 1e-7);
+
+        // Monotone decay down to the floor at the final step
+        for step in 11..=100 {
+            assert!(scheduler.lr_at(step) <= scheduler.lr_at(step - 1));
+        }
+        assert!((scheduler.lr_at(100) - floor).abs() < 1e-7);
+        assert!((scheduler.lr_at(500) - floor).abs() < 1e-7);
+        assert!((scheduler.lr_at(55) - (floor + max_lr) / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lr_scheduler_drives_optimizer() {
+        let mut scheduler = LrScheduler::new(0.01).with_warmup(4).with_decay(4);
+        let mut opt = AdamW::new(8, 1.0);
+
+        for step in 0..8 {
+            let lr = scheduler.apply(&mut opt);
+            assert_eq!(lr, scheduler.lr_at(step));
+            assert_eq!(opt.learning_rate(), lr);
+        }
+        assert_eq!(scheduler.get_lr(), scheduler.lr_at(8));
+    }
+
+    fn sample_gradients(scale: f32) -> Gradients {
+        // Global norm = 5 * scale (3-4-0 / 0-0 / 0 split across tensors)
+        Gradients {
+            query_grad: vec![3.0 * scale, 0.0],
+            keys_grad: vec![vec![0.0, 4.0 * scale], vec![0.0, 0.0]],
+            values_grad: vec![vec![0.0, 0.0]],
+            attention_weights_grad: Some(vec![100.0]),
+        }
+    }
+
+    #[test]
+    fn test_clip_grad_norm_below_cap() {
+        let mut grads = sample_gradients(1.0);
+        let norm = clip_grad_norm(&mut grads, 10.0);
+        assert!((norm - 5.0).abs() < 1e-6);
+        assert_eq!(grads.query_grad, vec![3.0, 0.0]);
+        assert_eq!(grads.keys_grad[0], vec![0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_clip_grad_norm_above_cap() {
+        let mut grads = sample_gradients(4.0);
+        let norm = clip_grad_norm(&mut grads, 2.0);
+        assert!((norm - 20.0).abs() < 1e-5);
+
+        let clipped: f32 = grads
+            .query_grad
+            .iter()
+            .chain(grads.keys_grad.iter().flatten())
+            .chain(grads.values_grad.iter().flatten())
+            .map(|g| g * g)
+            .sum::<f32>()
+            .sqrt();
+        assert!((clipped - 2.0).abs() < 1e-6);
+        // Direction is preserved
+        assert!((grads.query_grad[0] / grads.keys_grad[0][1] - 0.75).abs() < 1e-6);
+        assert_eq!(grads.attention_weights_grad, Some(vec![100.0]));
+    }
+
+    #[test]
+    fn test_optimizer_max_grad_norm() {
+        let spike = vec![300.0, 400.0];
+
+        // SGD step size equals lr * |g|, so clipping bounds the update.
+        let mut sgd = SGD::new(2, 0.1).with_max_grad_norm(1.0);
+        let mut params = vec![0.0; 2];
+        sgd.step(&mut params, &spike);
+        assert!((params[0] + 0.06).abs() < 1e-6);
+        assert!((params[1] + 0.08).abs() < 1e-6);
+
+        // Clipping a gradient already under the cap changes nothing.
+        let small = vec![0.3, 0.4];
+        let mut clipped = Adam::new(2, 0.01).with_max_grad_norm(1.0);
+        let mut plain = Adam::new(2, 0.01);
+        let (mut a, mut b) = (vec![1.0; 2], vec![1.0; 2]);
+        for _ in 0..5 {
+            clipped.step(&mut a, &small);
+            plain.step(&mut b, &small);
+        }
+        assert_eq!(a, b);
+
+        let mut adamw = AdamW::new(2, 0.01).with_max_grad_norm(1.0);
+        let mut params = vec![1.0; 2];
+        adamw.step(&mut params, &spike);
+        assert!(params.iter().all(|p| p.is_finite()));
+    }
 }
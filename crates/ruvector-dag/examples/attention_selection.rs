@@ -22,6 +22,7 @@ fn main() {
     let topo = TopologicalAttention::new(TopologicalConfig {
         decay_factor: 0.9,
         max_depth: 10,
+        use_edge_weights: false,
     });
 
     let scores = topo.forward(&dag).unwrap();
@@ -42,6 +43,7 @@ fn main() {
         time_window_ms: 1000,
         future_discount: 0.85,
         ancestor_weight: 0.5,
+        max_depth: None,
     });
 
     let causal_scores = causal.forward(&dag).unwrap();
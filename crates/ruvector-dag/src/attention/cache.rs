@@ -1,7 +1,10 @@
 //! Attention Cache: LRU cache for computed attention scores
 //!
 //! Caches attention scores to avoid redundant computation for identical DAGs.
-//! Uses LRU eviction policy to manage memory usage.
+//! Uses LRU eviction policy to manage memory usage. Entries older than the
+//! configured TTL are treated as misses and evicted when next looked up.
+//! Keys hash the DAG's content, not just its shape, so a DAG whose
+//! operators or estimates change never hits scores computed before.
 
 use super::trait_def::AttentionScores;
 use crate::dag::QueryDag;
@@ -62,6 +65,22 @@ impl AttentionCache {
         // Hash number of nodes
         dag.node_count().hash(&mut hasher);
 
+        // Hash node contents that attention scores depend on
+        let mut node_ids: Vec<usize> = dag.node_ids().collect();
+        node_ids.sort_unstable();
+        for id in node_ids {
+            let node = dag.get_node(id).unwrap();
+            id.hash(&mut hasher);
+            node.op_type.hash(&mut hasher);
+            node.estimated_rows.to_bits().hash(&mut hasher);
+            node.estimated_cost.to_bits().hash(&mut hasher);
+            if let Some(embedding) = &node.embedding {
+                for x in embedding {
+                    x.to_bits().hash(&mut hasher);
+                }
+            }
+        }
+
         // Hash edges structure
         let mut edge_list: Vec<(usize, usize)> = Vec::new();
         for node_id in dag.node_ids() {
@@ -300,6 +319,49 @@ mod tests {
         assert!(cache.get(&dag, "mech").is_none());
     }
 
+    #[test]
+    fn test_expired_entry_evicted_on_lookup() {
+        let mut cache = AttentionCache::new(CacheConfig {
+            capacity: 100,
+            ttl: Some(Duration::from_millis(20)),
+        });
+
+        let dag = create_test_dag(2);
+        cache.insert(&dag, "mech", AttentionScores::new(vec![0.5, 0.5]));
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert_eq!(cache.stats().size, 1);
+        assert!(cache.get(&dag, "mech").is_none());
+        assert_eq!(cache.stats().size, 0);
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn test_mutation_invalidates_within_ttl() {
+        let mut cache = AttentionCache::new(CacheConfig::default());
+        let mut dag = create_test_dag(3);
+        cache.insert(&dag, "mech", AttentionScores::new(vec![0.2, 0.3, 0.5]));
+        assert!(cache.get(&dag, "mech").is_some());
+
+        // Same shape, different cost estimate
+        dag.get_node_mut(2).unwrap().estimated_cost = 10.0;
+        assert!(cache.get(&dag, "mech").is_none());
+
+        // Same shape, different operator
+        let mut dag = create_test_dag(3);
+        dag.get_node_mut(1).unwrap().op_type = OperatorType::Filter {
+            predicate: "x > 1".to_string(),
+        };
+        assert!(cache.get(&dag, "mech").is_none());
+
+        // Structural change
+        let mut dag = create_test_dag(3);
+        dag.add_edge(1, 2).unwrap();
+        assert!(cache.get(&dag, "mech").is_none());
+
+        assert!(cache.get(&create_test_dag(3), "mech").is_some());
+    }
+
     #[test]
     fn test_hash_consistency() {
         let dag = create_test_dag(3);
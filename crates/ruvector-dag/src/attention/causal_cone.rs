@@ -2,13 +2,15 @@
 
 use super::{AttentionError, AttentionScores, DagAttention};
 use crate::dag::QueryDag;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct CausalConeConfig {
     pub time_window_ms: u64,
     pub future_discount: f32,
     pub ancestor_weight: f32,
+    /// Limit the cone to ancestors within this many hops (`None` = unbounded)
+    pub max_depth: Option<usize>,
 }
 
 impl Default for CausalConeConfig {
@@ -17,6 +19,7 @@ impl Default for CausalConeConfig {
             time_window_ms: 1000,
             future_discount: 0.8,
             ancestor_weight: 0.9,
+            max_depth: None,
         }
     }
 }
@@ -33,6 +36,14 @@ impl CausalConeAttention {
     pub fn with_defaults() -> Self {
         Self::new(CausalConeConfig::default())
     }
+
+    /// Ancestors of `node_id` that make up its causal cone
+    pub fn cone(&self, dag: &QueryDag, node_id: usize) -> HashSet<usize> {
+        match self.config.max_depth {
+            Some(depth) => dag.ancestors_within(node_id, depth),
+            None => dag.ancestors(node_id),
+        }
+    }
 }
 
 impl DagAttention for CausalConeAttention {
@@ -53,8 +64,7 @@ impl DagAttention for CausalConeAttention {
                 continue;
             }
 
-            let ancestors = dag.ancestors(node_id);
-            let ancestor_count = ancestors.len();
+            let ancestor_count = self.cone(dag, node_id).len();
 
             // Base score is proportional to causal influence (number of ancestors)
             let mut score = 1.0 + (ancestor_count as f32 * self.config.ancestor_weight);
@@ -124,4 +134,77 @@ mod tests {
             assert!(score >= 0.0 && score <= 1.0);
         }
     }
+
+    /// scan -> filter -> sort -> limit -> project, plus a second scan
+    /// joined in after the filter
+    fn deep_dag() -> (QueryDag, Vec<usize>) {
+        let mut dag = QueryDag::new();
+        let scan = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let filter = dag.add_node(OperatorNode::filter(0, "users.age > 18"));
+        let other = dag.add_node(OperatorNode::seq_scan(0, "orders"));
+        let join = dag.add_node(OperatorNode::hash_join(0, "user_id"));
+        let sort = dag.add_node(OperatorNode::sort(0, vec!["users.name".to_string()]));
+        let limit = dag.add_node(OperatorNode::limit(0, 10));
+        let project = dag.add_node(OperatorNode::project(0, vec!["users.name".to_string()]));
+        dag.add_edge(scan, filter).unwrap();
+        dag.add_edge(filter, join).unwrap();
+        dag.add_edge(other, join).unwrap();
+        dag.add_edge(join, sort).unwrap();
+        dag.add_edge(sort, limit).unwrap();
+        dag.add_edge(limit, project).unwrap();
+        (dag, vec![scan, filter, other, join, sort, limit, project])
+    }
+
+    fn with_depth(max_depth: Option<usize>) -> CausalConeAttention {
+        CausalConeAttention::new(CausalConeConfig {
+            max_depth,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_depth_one_cone_is_immediate_parents() {
+        let (dag, ids) = deep_dag();
+        let attention = with_depth(Some(1));
+        for &id in &ids {
+            let parents: HashSet<usize> = dag.parents(id).iter().copied().collect();
+            assert_eq!(attention.cone(&dag, id), parents);
+        }
+        assert!(attention.cone(&dag, ids[0]).is_empty());
+        assert!(with_depth(Some(0)).cone(&dag, ids[6]).is_empty());
+    }
+
+    #[test]
+    fn test_cone_grows_monotonically_with_depth() {
+        let (dag, ids) = deep_dag();
+        let project = ids[6];
+        let full = with_depth(None).cone(&dag, project);
+
+        let mut previous = HashSet::new();
+        for depth in 1..=6 {
+            let cone = with_depth(Some(depth)).cone(&dag, project);
+            assert!(cone.is_superset(&previous));
+            assert!(cone.is_subset(&full));
+            previous = cone;
+        }
+        assert_eq!(with_depth(Some(2)).cone(&dag, project).len(), 2);
+        assert_eq!(previous, full);
+
+        // A depth covering the whole plan matches the unbounded default.
+        assert_eq!(
+            with_depth(Some(6)).forward(&dag).unwrap(),
+            CausalConeAttention::with_defaults().forward(&dag).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bounded_scores_normalized() {
+        let (dag, _) = deep_dag();
+        for depth in [Some(0), Some(1), Some(3), None] {
+            let scores = with_depth(depth).forward(&dag).unwrap();
+            let sum: f32 = scores.values().sum();
+            assert!((sum - 1.0).abs() < 1e-5);
+            assert!(scores.values().all(|&s| (0.0..=1.0).contains(&s)));
+        }
+    }
 }
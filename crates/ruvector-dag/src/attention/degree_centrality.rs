@@ -0,0 +1,195 @@
+//! Degree Centrality Attention: Cheap baseline scoring nodes by connectivity
+
+use super::trait_def::{
+    AttentionError as AttentionErrorV2, AttentionScores as AttentionScoresV2, DagAttentionMechanism,
+};
+use super::{AttentionError, AttentionScores, DagAttention};
+use crate::dag::QueryDag;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct DegreeCentralityConfig {
+    pub temperature: f32, // 1.0 default
+    /// Weight of each incoming edge (inputs consumed)
+    pub in_weight: f32, // 1.0 default
+    /// Weight of each outgoing edge (consumers fed)
+    pub out_weight: f32, // 1.0 default
+}
+
+impl Default for DegreeCentralityConfig {
+    fn default() -> Self {
+        Self {
+            temperature: 1.0,
+            in_weight: 1.0,
+            out_weight: 1.0,
+        }
+    }
+}
+
+pub struct DegreeCentralityAttention {
+    config: DegreeCentralityConfig,
+}
+
+impl DegreeCentralityAttention {
+    pub fn new(config: DegreeCentralityConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn with_defaults() -> Self {
+        Self::new(DegreeCentralityConfig::default())
+    }
+
+    /// Weighted degree `in_weight * in_degree + out_weight * out_degree`
+    pub fn degree(&self, dag: &QueryDag, node_id: usize) -> f32 {
+        self.config.in_weight * dag.parents(node_id).len() as f32
+            + self.config.out_weight * dag.children(node_id).len() as f32
+    }
+
+    /// Softmax of weighted degree over temperature
+    fn compute_scores(&self, dag: &QueryDag) -> HashMap<usize, f32> {
+        let temperature = self.config.temperature.max(f32::EPSILON);
+        let mut ids: Vec<usize> = dag.node_ids().collect();
+        ids.sort_unstable();
+        let degrees: Vec<(usize, f32)> = ids
+            .into_iter()
+            .map(|id| (id, self.degree(dag, id) / temperature))
+            .collect();
+
+        let max = degrees
+            .iter()
+            .map(|&(_, d)| d)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let exps: Vec<(usize, f32)> = degrees
+            .into_iter()
+            .map(|(id, d)| (id, (d - max).exp()))
+            .collect();
+
+        // Sum in ascending node-id order so the scores don't depend on
+        // HashMap iteration order
+        let total: f32 = exps.iter().map(|&(_, e)| e).sum();
+        exps.into_iter()
+            .map(|(id, e)| (id, if total > 0.0 { e / total } else { e }))
+            .collect()
+    }
+}
+
+impl DagAttention for DegreeCentralityAttention {
+    fn forward(&self, dag: &QueryDag) -> Result<AttentionScores, AttentionError> {
+        if dag.node_count() == 0 {
+            return Err(AttentionError::EmptyDag);
+        }
+        Ok(self.compute_scores(dag))
+    }
+
+    fn update(&mut self, _dag: &QueryDag, _times: &HashMap<usize, f64>) {
+        // Degree centrality is static, no updates needed
+    }
+
+    fn name(&self) -> &'static str {
+        "degree_centrality"
+    }
+
+    fn complexity(&self) -> &'static str {
+        "O(n)"
+    }
+}
+
+/// Lets the mechanism be registered with
+/// [`AttentionSelector`](super::AttentionSelector)
+impl DagAttentionMechanism for DegreeCentralityAttention {
+    fn forward(&self, dag: &QueryDag) -> Result<AttentionScoresV2, AttentionErrorV2> {
+        if dag.node_count() == 0 {
+            return Err(AttentionErrorV2::InvalidDag("Empty DAG".to_string()));
+        }
+
+        let scores = self.compute_scores(dag);
+        let len = scores.keys().max().map_or(0, |&id| id + 1);
+        let mut score_vec = vec![0.0; len];
+        for (id, score) in scores {
+            score_vec[id] = score;
+        }
+
+        Ok(AttentionScoresV2::new(score_vec)
+            .with_metadata("mechanism".to_string(), "degree_centrality".to_string()))
+    }
+
+    fn name(&self) -> &'static str {
+        "degree_centrality"
+    }
+
+    fn complexity(&self) -> &'static str {
+        "O(n)"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dag::OperatorNode;
+
+    /// Two scans joined, then sorted: the join has the highest degree
+    fn join_dag() -> (QueryDag, [usize; 4]) {
+        let mut dag = QueryDag::new();
+        let users = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let orders = dag.add_node(OperatorNode::seq_scan(0, "orders"));
+        let join = dag.add_node(OperatorNode::hash_join(0, "user_id"));
+        let sort = dag.add_node(OperatorNode::sort(0, vec!["users.name".to_string()]));
+        dag.add_edge(users, join).unwrap();
+        dag.add_edge(orders, join).unwrap();
+        dag.add_edge(join, sort).unwrap();
+        (dag, [users, orders, join, sort])
+    }
+
+    #[test]
+    fn test_hub_gets_most_attention() {
+        let (dag, [users, orders, join, sort]) = join_dag();
+        let attention = DegreeCentralityAttention::with_defaults();
+        let scores = DagAttention::forward(&attention, &dag).unwrap();
+
+        let sum: f32 = scores.values().sum();
+        assert!((sum - 1.0).abs() < 1e-5);
+        assert!(scores[&join] > scores[&users]);
+        assert_eq!(scores[&users], scores[&orders]);
+        assert_eq!(scores[&users], scores[&sort]);
+    }
+
+    #[test]
+    fn test_in_out_weighting_and_temperature() {
+        let (dag, [users, _, join, sort]) = join_dag();
+
+        // Only outgoing edges count: the sort has none, the scans one each.
+        let out_only = DegreeCentralityAttention::new(DegreeCentralityConfig {
+            in_weight: 0.0,
+            ..Default::default()
+        });
+        let scores = DagAttention::forward(&out_only, &dag).unwrap();
+        assert_eq!(scores[&users], scores[&join]);
+        assert!(scores[&sort] < scores[&users]);
+
+        let sharp = DegreeCentralityAttention::new(DegreeCentralityConfig {
+            temperature: 0.1,
+            ..Default::default()
+        });
+        let smooth = DegreeCentralityAttention::new(DegreeCentralityConfig {
+            temperature: 10.0,
+            ..Default::default()
+        });
+        let sharp = DagAttention::forward(&sharp, &dag).unwrap();
+        let smooth = DagAttention::forward(&smooth, &dag).unwrap();
+        assert!(sharp[&join] > smooth[&join]);
+        assert!(sharp[&join] > 0.99);
+    }
+
+    #[test]
+    fn test_mechanism_scores_indexed_by_node() {
+        let (dag, ids) = join_dag();
+        let attention = DegreeCentralityAttention::with_defaults();
+        let by_id = DagAttention::forward(&attention, &dag).unwrap();
+        let vec = DagAttentionMechanism::forward(&attention, &dag).unwrap();
+        assert_eq!(vec.scores.len(), dag.node_count());
+        for id in ids {
+            assert_eq!(vec.scores[id], by_id[&id]);
+        }
+        assert!(DagAttentionMechanism::forward(&attention, &QueryDag::new()).is_err());
+    }
+}
@@ -11,10 +11,28 @@ pub enum FlowCapacity {
     RowBased,
 }
 
+/// How the cut threshold `lambda` is chosen.
+///
+/// Besides the edges of the minimum cut, any edge whose flow saturation
+/// (flow / capacity) exceeds `lambda` is gated as a bottleneck. At 1.0 only
+/// the minimum cut is gated; lower values gate more aggressively.
+#[derive(Debug, Clone)]
+pub enum LambdaSchedule {
+    /// Constant threshold.
+    Fixed(f32),
+    /// Linear move from `start` to `end` over `steps` training steps, then
+    /// held at `end`.
+    Annealed { start: f32, end: f32, steps: usize },
+    /// `1 - density`, where density is the DAG's edge count over the
+    /// maximum possible `n(n-1)/2`; denser graphs gate more edges.
+    DensityAdaptive,
+}
+
 #[derive(Debug, Clone)]
 pub struct MinCutConfig {
     pub gate_threshold: f32,
     pub flow_capacity: FlowCapacity,
+    pub lambda_schedule: LambdaSchedule,
 }
 
 impl Default for MinCutConfig {
@@ -22,26 +40,72 @@ impl Default for MinCutConfig {
         Self {
             gate_threshold: 0.5,
             flow_capacity: FlowCapacity::UnitCapacity,
+            lambda_schedule: LambdaSchedule::Fixed(1.0),
         }
     }
 }
 
 pub struct MinCutGatedAttention {
     config: MinCutConfig,
+    step: usize,
 }
 
 impl MinCutGatedAttention {
     pub fn new(config: MinCutConfig) -> Self {
-        Self { config }
+        Self { config, step: 0 }
     }
 
     pub fn with_defaults() -> Self {
         Self::new(MinCutConfig::default())
     }
 
-    /// Compute min-cut between leaves and root using Ford-Fulkerson
-    fn compute_min_cut(&self, dag: &QueryDag) -> HashSet<usize> {
-        let mut cut_nodes = HashSet::new();
+    /// Current training step, advanced by each `update`.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Set the training step consulted by an annealed schedule.
+    pub fn set_step(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    /// The cut threshold for `dag` at the current step.
+    pub fn lambda(&self, dag: &QueryDag) -> f32 {
+        match self.config.lambda_schedule {
+            LambdaSchedule::Fixed(lambda) => lambda,
+            LambdaSchedule::Annealed { start, end, steps } => {
+                let t = if steps == 0 {
+                    1.0
+                } else {
+                    self.step.min(steps) as f32 / steps as f32
+                };
+                start + (end - start) * t
+            }
+            LambdaSchedule::DensityAdaptive => {
+                let n = dag.node_count();
+                if n < 2 {
+                    return 1.0;
+                }
+                let max_edges = (n * (n - 1) / 2) as f32;
+                1.0 - (dag.edge_count() as f32 / max_edges).min(1.0)
+            }
+        }
+    }
+
+    /// Edges gated as bottlenecks at the current lambda, sorted.
+    pub fn gated_edges(&self, dag: &QueryDag) -> Vec<(usize, usize)> {
+        let mut edges: Vec<_> = self
+            .compute_min_cut(dag, self.lambda(dag))
+            .into_iter()
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Compute min-cut between leaves and root using Ford-Fulkerson, plus
+    /// any edge whose flow saturation exceeds `lambda`.
+    fn compute_min_cut(&self, dag: &QueryDag, lambda: f32) -> HashSet<(usize, usize)> {
+        let mut cut_edges = HashSet::new();
 
         // Build capacity matrix from the DAG structure
         let mut capacity: HashMap<(usize, usize), f64> = HashMap::new();
@@ -68,12 +132,12 @@ impl MinCutGatedAttention {
         // Find source (root) and sink (any leaf)
         let source = match dag.root() {
             Some(root) => root,
-            None => return cut_nodes,
+            None => return cut_edges,
         };
 
         let leaves = dag.leaves();
         if leaves.is_empty() {
-            return cut_nodes;
+            return cut_edges;
         }
 
         // Use first leaf as sink
@@ -149,20 +213,18 @@ impl MinCutGatedAttention {
             }
         }
 
-        // Nodes in the cut are those with edges crossing from reachable to non-reachable
-        for node_id in 0..dag.node_count() {
-            if dag.get_node(node_id).is_none() {
-                continue;
-            }
-            for &child in dag.children(node_id) {
-                if reachable.contains(&node_id) && !reachable.contains(&child) {
-                    cut_nodes.insert(node_id);
-                    cut_nodes.insert(child);
-                }
+        // The cut is the edges crossing from reachable to non-reachable;
+        // edges saturated beyond lambda are gated as well
+        for (&(u, v), &cap) in &capacity {
+            let crossing = reachable.contains(&u) && !reachable.contains(&v);
+            let flow = cap - residual.get(&(u, v)).copied().unwrap_or(0.0);
+            let saturated = cap > 0.0 && (flow / cap) as f32 > lambda;
+            if crossing || saturated {
+                cut_edges.insert((u, v));
             }
         }
 
-        cut_nodes
+        cut_edges
     }
 }
 
@@ -172,7 +234,11 @@ impl DagAttentionMechanism for MinCutGatedAttention {
             return Err(AttentionError::InvalidDag("Empty DAG".to_string()));
         }
 
-        let cut_nodes = self.compute_min_cut(dag);
+        let cut_nodes: HashSet<usize> = self
+            .compute_min_cut(dag, self.lambda(dag))
+            .into_iter()
+            .flat_map(|(u, v)| [u, v])
+            .collect();
         let n = dag.node_count();
         let mut score_vec = vec![0.0; n];
         let mut total = 0.0f32;
@@ -214,6 +280,14 @@ impl DagAttentionMechanism for MinCutGatedAttention {
     fn complexity(&self) -> &'static str {
         "O(n * e^2)"
     }
+
+    fn update(&mut self, _dag: &QueryDag, _execution_times: &HashMap<usize, f64>) {
+        self.step += 1;
+    }
+
+    fn reset(&mut self) {
+        self.step = 0;
+    }
 }
 
 #[cfg(test)]
@@ -250,4 +324,76 @@ mod tests {
             assert!(score >= 0.0 && score <= 1.0);
         }
     }
+
+    #[test]
+    fn test_annealed_lambda_moves_over_horizon() {
+        let mut dag = QueryDag::new();
+        let a = dag.add_node(OperatorNode::seq_scan(0, "t"));
+        let b = dag.add_node(OperatorNode::filter(0, "x > 1"));
+        dag.add_edge(a, b).unwrap();
+
+        let mut attention = MinCutGatedAttention::new(MinCutConfig {
+            lambda_schedule: LambdaSchedule::Annealed {
+                start: 1.0,
+                end: 0.2,
+                steps: 4,
+            },
+            ..Default::default()
+        });
+        assert!((attention.lambda(&dag) - 1.0).abs() < 1e-6);
+
+        let mut previous = attention.lambda(&dag);
+        for _ in 0..4 {
+            attention.update(&dag, &HashMap::new());
+            let lambda = attention.lambda(&dag);
+            assert!(lambda < previous);
+            previous = lambda;
+        }
+        assert!((previous - 0.2).abs() < 1e-6);
+
+        // Held at `end` past the horizon, and back to `start` on reset.
+        attention.update(&dag, &HashMap::new());
+        assert!((attention.lambda(&dag) - 0.2).abs() < 1e-6);
+        attention.reset();
+        assert_eq!(attention.step(), 0);
+        assert!((attention.lambda(&dag) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_density_adaptive_gates_more_on_dense_dag() {
+        // Sparse: a chain of five operators.
+        let mut sparse = QueryDag::new();
+        let ids: Vec<_> = (0..5)
+            .map(|i| sparse.add_node(OperatorNode::new(i, OperatorType::Result)))
+            .collect();
+        for w in ids.windows(2) {
+            sparse.add_edge(w[0], w[1]).unwrap();
+        }
+
+        // Dense: every earlier operator feeds every later one.
+        let mut dense = QueryDag::new();
+        let ids: Vec<_> = (0..5)
+            .map(|i| dense.add_node(OperatorNode::new(i, OperatorType::Result)))
+            .collect();
+        for i in 0..ids.len() {
+            for j in i + 1..ids.len() {
+                dense.add_edge(ids[i], ids[j]).unwrap();
+            }
+        }
+
+        let attention = MinCutGatedAttention::new(MinCutConfig {
+            lambda_schedule: LambdaSchedule::DensityAdaptive,
+            ..Default::default()
+        });
+        assert!(attention.lambda(&dense) < attention.lambda(&sparse));
+
+        let dense_gated = attention.gated_edges(&dense);
+        let sparse_gated = attention.gated_edges(&sparse);
+        assert!(dense_gated.len() > sparse_gated.len());
+
+        // A fixed lambda of 1.0 gates only a minimum cut, four edges wide.
+        let fixed = MinCutGatedAttention::with_defaults();
+        assert_eq!(fixed.gated_edges(&dense).len(), 4);
+        assert!(dense_gated.len() > 4);
+    }
 }
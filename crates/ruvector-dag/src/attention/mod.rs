@@ -8,6 +8,7 @@
 // Team 2 (Agent #2) - Base attention mechanisms
 mod causal_cone;
 mod critical_path;
+mod degree_centrality;
 mod mincut_gated;
 mod topological;
 mod traits;
@@ -23,7 +24,8 @@ mod trait_def;
 // Export base mechanisms
 pub use causal_cone::{CausalConeAttention, CausalConeConfig};
 pub use critical_path::{CriticalPathAttention, CriticalPathConfig};
-pub use mincut_gated::{FlowCapacity, MinCutConfig, MinCutGatedAttention};
+pub use degree_centrality::{DegreeCentralityAttention, DegreeCentralityConfig};
+pub use mincut_gated::{FlowCapacity, LambdaSchedule, MinCutConfig, MinCutGatedAttention};
 pub use topological::{TopologicalAttention, TopologicalConfig};
 pub use traits::{AttentionConfig, AttentionError, AttentionScores, DagAttention};
 
@@ -8,6 +8,9 @@ use std::collections::HashMap;
 pub struct TopologicalConfig {
     pub decay_factor: f32, // 0.9 default
     pub max_depth: usize,  // 10 default
+    /// Scale each node's score by the weight of its incoming edges
+    /// (see [`QueryDag::edge_weight`]) relative to the mean edge weight
+    pub use_edge_weights: bool, // false default
 }
 
 impl Default for TopologicalConfig {
@@ -15,6 +18,7 @@ impl Default for TopologicalConfig {
         Self {
             decay_factor: 0.9,
             max_depth: 10,
+            use_edge_weights: false,
         }
     }
 }
@@ -31,6 +35,43 @@ impl TopologicalAttention {
     pub fn with_defaults() -> Self {
         Self::new(TopologicalConfig::default())
     }
+
+    /// Per-node multiplier from incoming edge weights.
+    ///
+    /// Each edge weight is divided by the mean weight over all edges, and a
+    /// node takes the mean of its incoming normalized weights. Nodes without
+    /// inputs, and DAGs whose edges carry no weight, get 1.0.
+    fn edge_weight_factors(dag: &QueryDag) -> HashMap<usize, f32> {
+        let weights: Vec<(usize, f64)> = dag
+            .node_ids()
+            .flat_map(|child| {
+                dag.parents(child)
+                    .iter()
+                    .filter_map(move |&parent| dag.edge_weight(parent, child))
+                    .map(move |w| (child, w))
+            })
+            .collect();
+        let mean = weights.iter().map(|&(_, w)| w).sum::<f64>() / weights.len().max(1) as f64;
+
+        let mut factors: HashMap<usize, f32> = dag.node_ids().map(|id| (id, 1.0)).collect();
+        if mean <= 0.0 {
+            return factors;
+        }
+        for id in dag.node_ids() {
+            let incoming: Vec<f64> = weights
+                .iter()
+                .filter(|&&(child, _)| child == id)
+                .map(|&(_, w)| w / mean)
+                .collect();
+            if !incoming.is_empty() {
+                factors.insert(
+                    id,
+                    (incoming.iter().sum::<f64>() / incoming.len() as f64) as f32,
+                );
+            }
+        }
+        factors
+    }
 }
 
 impl DagAttention for TopologicalAttention {
@@ -41,6 +82,10 @@ impl DagAttention for TopologicalAttention {
 
         let depths = dag.compute_depths();
         let max_depth = depths.values().max().copied().unwrap_or(0);
+        let factors = self
+            .config
+            .use_edge_weights
+            .then(|| Self::edge_weight_factors(dag));
 
         let mut scores = HashMap::new();
         let mut total = 0.0f32;
@@ -48,7 +93,10 @@ impl DagAttention for TopologicalAttention {
         for (&node_id, &depth) in &depths {
             // Higher attention for nodes closer to root (higher depth from leaves)
             let normalized_depth = depth as f32 / (max_depth.max(1) as f32);
-            let score = self.config.decay_factor.powf(1.0 - normalized_depth);
+            let mut score = self.config.decay_factor.powf(1.0 - normalized_depth);
+            if let Some(factors) = &factors {
+                score *= factors[&node_id];
+            }
             scores.insert(node_id, score);
             total += score;
         }
@@ -106,4 +154,59 @@ mod tests {
             assert!(score >= 0.0 && score <= 1.0);
         }
     }
+
+    #[test]
+    fn test_edge_weights_bias_downstream_nodes() {
+        let mut dag = QueryDag::new();
+
+        // Two scans feeding a join; the orders scan carries far more rows
+        let users = dag.add_node(OperatorNode::seq_scan(0, "users").with_estimates(10.0, 1.0));
+        let orders =
+            dag.add_node(OperatorNode::seq_scan(0, "orders").with_estimates(10_000.0, 1.0));
+        let filter_u =
+            dag.add_node(OperatorNode::filter(0, "users.active").with_estimates(5.0, 1.0));
+        let filter_o =
+            dag.add_node(OperatorNode::filter(0, "orders.total > 0").with_estimates(5.0, 1.0));
+        let join = dag.add_node(OperatorNode::hash_join(0, "user_id").with_estimates(5.0, 1.0));
+        dag.add_edge(users, filter_u).unwrap();
+        dag.add_edge(orders, filter_o).unwrap();
+        dag.add_edge(filter_u, join).unwrap();
+        dag.add_edge(filter_o, join).unwrap();
+
+        let unweighted = TopologicalAttention::with_defaults().forward(&dag).unwrap();
+        let weighted = TopologicalAttention::new(TopologicalConfig {
+            use_edge_weights: true,
+            ..Default::default()
+        })
+        .forward(&dag)
+        .unwrap();
+
+        assert!(weighted[&filter_o] > unweighted[&filter_o]);
+        assert!(weighted[&filter_o] > weighted[&filter_u]);
+        assert_eq!(unweighted[&filter_o], unweighted[&filter_u]);
+
+        let sum: f32 = weighted.values().sum();
+        assert!((sum - 1.0).abs() < 1e-5);
+        assert_eq!(dag.edge_weight(orders, filter_o), Some(10_000.0));
+        assert_eq!(dag.edge_weight(users, filter_o), None);
+    }
+
+    #[test]
+    fn test_edge_weights_without_estimates_match_unweighted() {
+        let mut dag = QueryDag::new();
+        let scan = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let filter = dag.add_node(OperatorNode::filter(0, "users.age > 18"));
+        let result = dag.add_node(OperatorNode::result(0));
+        dag.add_edge(scan, filter).unwrap();
+        dag.add_edge(filter, result).unwrap();
+
+        let unweighted = TopologicalAttention::with_defaults().forward(&dag).unwrap();
+        let weighted = TopologicalAttention::new(TopologicalConfig {
+            use_edge_weights: true,
+            ..Default::default()
+        })
+        .forward(&dag)
+        .unwrap();
+        assert_eq!(weighted, unweighted);
+    }
 }
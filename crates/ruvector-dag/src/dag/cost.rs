@@ -0,0 +1,216 @@
+//! Cardinality and cost estimation for query DAGs
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::operator_node::{OperatorNode, OperatorType};
+use super::query_dag::{DagError, QueryDag};
+
+/// Estimated output size and cost of a node
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Rows the node produces
+    pub rows: f64,
+    /// Total cost of producing those rows, including the node's inputs
+    pub cost: f64,
+}
+
+impl CostEstimate {
+    /// Create a new estimate
+    pub fn new(rows: f64, cost: f64) -> Self {
+        Self { rows, cost }
+    }
+}
+
+/// Estimates a node's output from the estimates of its inputs
+pub trait CostModel {
+    /// Estimate `node` given the estimates of its inputs, in
+    /// [`QueryDag::parents`] order. Scans usually have no inputs.
+    fn estimate(&self, node: &OperatorNode, inputs: &[CostEstimate]) -> CostEstimate;
+}
+
+/// Rule-of-thumb cost model in abstract cost units (one unit per row
+/// processed)
+///
+/// Relation sizes come from [`with_relation_rows`](Self::with_relation_rows),
+/// keyed by table or index name, falling back to `default_rows`.
+#[derive(Debug, Clone)]
+pub struct HeuristicCostModel {
+    /// Rows assumed for relations without a registered size
+    pub default_rows: f64,
+    /// Fraction of rows kept by a filter
+    pub filter_selectivity: f64,
+    /// Fraction of rows returned by an index scan
+    pub index_selectivity: f64,
+    /// Fraction of the cross product kept by a join
+    pub join_selectivity: f64,
+    /// Number of IVF lists, used to turn `nprobe` into a scanned fraction
+    pub ivf_lists: f64,
+    /// Relative cost of a vector distance computation per row
+    pub distance_cost: f64,
+    /// Relative cost of reranking one row
+    pub rerank_cost: f64,
+    relation_rows: HashMap<String, f64>,
+}
+
+impl Default for HeuristicCostModel {
+    fn default() -> Self {
+        Self {
+            default_rows: 1000.0,
+            filter_selectivity: 0.33,
+            index_selectivity: 0.1,
+            join_selectivity: 0.1,
+            ivf_lists: 100.0,
+            distance_cost: 4.0,
+            rerank_cost: 20.0,
+            relation_rows: HashMap::new(),
+        }
+    }
+}
+
+impl HeuristicCostModel {
+    /// Create a model with default parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the row count of a table or index
+    pub fn with_relation_rows(mut self, name: &str, rows: f64) -> Self {
+        self.relation_rows.insert(name.to_string(), rows);
+        self
+    }
+
+    fn relation_rows(&self, name: &str) -> f64 {
+        self.relation_rows
+            .get(name)
+            .copied()
+            .unwrap_or(self.default_rows)
+    }
+}
+
+impl CostModel for HeuristicCostModel {
+    fn estimate(&self, node: &OperatorNode, inputs: &[CostEstimate]) -> CostEstimate {
+        let input_rows: f64 = inputs.iter().map(|i| i.rows).sum();
+        let input_cost: f64 = inputs.iter().map(|i| i.cost).sum();
+        let join_rows = || inputs.iter().map(|i| i.rows).product::<f64>() * self.join_selectivity;
+
+        let (rows, cost) = match &node.op_type {
+            OperatorType::SeqScan { table } => {
+                let rows = self.relation_rows(table);
+                (rows, rows)
+            }
+            OperatorType::IndexScan { index, table } => {
+                let total = self.relation_rows(table);
+                let rows = total * self.index_selectivity;
+                let depth = self.relation_rows(index).max(2.0).log2();
+                (rows, depth + rows)
+            }
+            // Search does roughly ef_search * log(n) distance computations.
+            OperatorType::HnswScan { index, ef_search } => {
+                let total = self.relation_rows(index);
+                let ef = *ef_search as f64;
+                let cost = ef * total.max(2.0).log2() * self.distance_cost;
+                (ef.min(total), cost)
+            }
+            OperatorType::IvfFlatScan { index, nprobe } => {
+                let total = self.relation_rows(index);
+                let scanned = total * (*nprobe as f64 / self.ivf_lists).min(1.0);
+                (scanned, scanned * self.distance_cost)
+            }
+            OperatorType::HashJoin { .. } | OperatorType::MergeJoin { .. } => {
+                (join_rows(), input_rows)
+            }
+            OperatorType::NestedLoopJoin => {
+                let pairs: f64 = inputs.iter().map(|i| i.rows).product();
+                (join_rows(), pairs)
+            }
+            OperatorType::Filter { .. } => (input_rows * self.filter_selectivity, input_rows),
+            OperatorType::Aggregate { .. } => (1.0, input_rows),
+            OperatorType::GroupBy { .. } => (input_rows.sqrt().max(1.0), input_rows),
+            OperatorType::Sort { .. } => (input_rows, input_rows * input_rows.max(2.0).log2()),
+            OperatorType::Limit { count } => (input_rows.min(*count as f64), 0.0),
+            OperatorType::VectorDistance { .. } => (input_rows, input_rows * self.distance_cost),
+            OperatorType::Rerank { .. } => (input_rows, input_rows * self.rerank_cost),
+            OperatorType::Project { .. } | OperatorType::Materialize | OperatorType::Result => {
+                (input_rows, input_rows)
+            }
+            #[allow(deprecated)]
+            OperatorType::Scan => (self.default_rows, self.default_rows),
+            #[allow(deprecated)]
+            OperatorType::Join => (join_rows(), input_rows),
+        };
+
+        CostEstimate::new(rows, input_cost + cost)
+    }
+}
+
+impl QueryDag {
+    /// Estimate rows and cost for every node.
+    ///
+    /// Nodes are visited in topological order so each node is estimated
+    /// from the already-computed estimates of its inputs. The stored
+    /// `estimated_rows`/`estimated_cost` of the nodes are not consulted
+    /// or changed.
+    pub fn estimate_costs(
+        &self,
+        model: &impl CostModel,
+    ) -> Result<HashMap<usize, CostEstimate>, DagError> {
+        let mut estimates = HashMap::with_capacity(self.nodes.len());
+        for id in self.topological_sort()? {
+            let inputs: Vec<CostEstimate> = self.parents(id).iter().map(|p| estimates[p]).collect();
+            let estimate = model.estimate(&self.nodes[&id], &inputs);
+            estimates.insert(id, estimate);
+        }
+        Ok(estimates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Doubles its input rows and charges one unit per node
+    struct Doubling;
+
+    impl CostModel for Doubling {
+        fn estimate(&self, _node: &OperatorNode, inputs: &[CostEstimate]) -> CostEstimate {
+            if inputs.is_empty() {
+                return CostEstimate::new(1.0, 1.0);
+            }
+            CostEstimate::new(
+                inputs.iter().map(|i| i.rows * 2.0).sum(),
+                inputs.iter().map(|i| i.cost).sum::<f64>() + 1.0,
+            )
+        }
+    }
+
+    #[test]
+    fn test_custom_model_propagates() {
+        let mut dag = QueryDag::new();
+        let a = dag.add_node(OperatorNode::seq_scan(0, "a"));
+        let b = dag.add_node(OperatorNode::seq_scan(0, "b"));
+        let join = dag.add_node(OperatorNode::nested_loop_join(0));
+        let sort = dag.add_node(OperatorNode::sort(0, vec!["a.x".to_string()]));
+        dag.add_edge(a, join).unwrap();
+        dag.add_edge(b, join).unwrap();
+        dag.add_edge(join, sort).unwrap();
+
+        let est = dag.estimate_costs(&Doubling).unwrap();
+        assert_eq!(est[&join], CostEstimate::new(4.0, 3.0));
+        assert_eq!(est[&sort], CostEstimate::new(8.0, 4.0));
+    }
+
+    #[test]
+    fn test_heuristic_scans() {
+        let model = HeuristicCostModel::new().with_relation_rows("idx", 1024.0);
+        let narrow = model.estimate(&OperatorNode::hnsw_scan(0, "idx", 16), &[]);
+        let wide = model.estimate(&OperatorNode::hnsw_scan(0, "idx", 64), &[]);
+        assert_eq!(narrow.rows, 16.0);
+        assert_eq!(wide.cost, 4.0 * narrow.cost);
+
+        let limit = model.estimate(&OperatorNode::limit(0, 10), &[narrow]);
+        assert_eq!(limit.rows, 10.0);
+        assert_eq!(limit.cost, narrow.cost);
+    }
+}
@@ -0,0 +1,257 @@
+//! Structural equality and diffing for query DAGs
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use super::operator_node::OperatorType;
+use super::query_dag::QueryDag;
+
+/// Differences between two DAGs, keyed by node ID
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DagDiff {
+    /// Node IDs present in `other` but not in `self`
+    pub added_nodes: Vec<usize>,
+    /// Node IDs present in `self` but not in `other`
+    pub removed_nodes: Vec<usize>,
+    /// Node IDs present in both whose operator type differs
+    pub changed_nodes: Vec<usize>,
+    /// `(parent, child)` edges present in `other` but not in `self`
+    pub added_edges: Vec<(usize, usize)>,
+    /// `(parent, child)` edges present in `self` but not in `other`
+    pub removed_edges: Vec<(usize, usize)>,
+}
+
+impl DagDiff {
+    /// Returns true if the two DAGs were identical
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+impl QueryDag {
+    /// Check whether two DAGs are isomorphic up to node relabeling.
+    ///
+    /// Nodes match when their operator types are equal, and the mapping must
+    /// preserve every edge (including duplicate edges). Cost estimates,
+    /// actuals and embeddings are ignored.
+    ///
+    /// This is an exact backtracking search over nodes in topological order.
+    /// Candidates for each node are looked up by operator type, then pruned
+    /// by degree and already-mapped parents. When operator types are mostly
+    /// distinct each node has a single candidate and no backtracking
+    /// happens, so the cost is O(n + e) plus a parent check quadratic in
+    /// each node's in-degree. The worst case is exponential for large DAGs
+    /// made of identical operators, so it is intended for plan-sized graphs.
+    pub fn structural_eq(&self, other: &QueryDag) -> bool {
+        if self.node_count() != other.node_count() || self.edge_count() != other.edge_count() {
+            return false;
+        }
+
+        let order = match self.topological_sort() {
+            Ok(order) => order,
+            Err(_) => return false,
+        };
+
+        let mut by_type: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (&id, node) in &other.nodes {
+            by_type
+                .entry(op_type_hash(&node.op_type))
+                .or_default()
+                .push(id);
+        }
+
+        let mut mapping = HashMap::with_capacity(order.len());
+        let mut used = HashSet::with_capacity(order.len());
+        self.match_from(other, &by_type, &order, 0, &mut mapping, &mut used)
+    }
+
+    /// Compute the node and edge differences from `self` to `other`.
+    ///
+    /// Nodes are aligned by ID rather than by isomorphism, which matches how
+    /// plans built by the same planner assign IDs. All lists are sorted.
+    pub fn diff(&self, other: &QueryDag) -> DagDiff {
+        let mut diff = DagDiff::default();
+
+        for (&id, node) in &self.nodes {
+            match other.nodes.get(&id) {
+                None => diff.removed_nodes.push(id),
+                Some(theirs) if theirs.op_type != node.op_type => diff.changed_nodes.push(id),
+                Some(_) => {}
+            }
+        }
+        diff.added_nodes = other
+            .nodes
+            .keys()
+            .filter(|id| !self.nodes.contains_key(id))
+            .copied()
+            .collect();
+
+        let ours = self.edge_counts();
+        let theirs = other.edge_counts();
+        for (&edge, &count) in &ours {
+            let other_count = theirs.get(&edge).copied().unwrap_or(0);
+            diff.removed_edges
+                .extend(std::iter::repeat(edge).take(count.saturating_sub(other_count)));
+        }
+        for (&edge, &count) in &theirs {
+            let our_count = ours.get(&edge).copied().unwrap_or(0);
+            diff.added_edges
+                .extend(std::iter::repeat(edge).take(count.saturating_sub(our_count)));
+        }
+
+        diff.added_nodes.sort_unstable();
+        diff.removed_nodes.sort_unstable();
+        diff.changed_nodes.sort_unstable();
+        diff.added_edges.sort_unstable();
+        diff.removed_edges.sort_unstable();
+        diff
+    }
+
+    /// Count `(parent, child)` edges, keeping duplicates distinct
+    fn edge_counts(&self) -> HashMap<(usize, usize), usize> {
+        let mut counts = HashMap::new();
+        for (&parent, children) in &self.edges {
+            for &child in children {
+                *counts.entry((parent, child)).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Backtracking step for `structural_eq`: map `order[idx..]` into `other`
+    fn match_from(
+        &self,
+        other: &QueryDag,
+        by_type: &HashMap<u64, Vec<usize>>,
+        order: &[usize],
+        idx: usize,
+        mapping: &mut HashMap<usize, usize>,
+        used: &mut HashSet<usize>,
+    ) -> bool {
+        let Some(&u) = order.get(idx) else {
+            return true;
+        };
+        let node = &self.nodes[&u];
+        let candidates = by_type
+            .get(&op_type_hash(&node.op_type))
+            .map_or(&[][..], Vec::as_slice);
+
+        for &v in candidates {
+            let candidate = &other.nodes[&v];
+            if used.contains(&v)
+                || candidate.op_type != node.op_type
+                || self.parents(u).len() != other.parents(v).len()
+                || self.children(u).len() != other.children(v).len()
+            {
+                continue;
+            }
+
+            // Every parent of `u` is already mapped (topological order), so
+            // checking parent edge multiplicities fixes all incoming edges.
+            let parents_match = self.parents(u).iter().all(|p| {
+                let mapped = mapping[p];
+                let ours = self.parents(u).iter().filter(|&&q| q == *p).count();
+                let theirs = other.parents(v).iter().filter(|&&q| q == mapped).count();
+                ours == theirs
+            });
+            if !parents_match {
+                continue;
+            }
+
+            mapping.insert(u, v);
+            used.insert(v);
+            if self.match_from(other, by_type, order, idx + 1, mapping, used) {
+                return true;
+            }
+            mapping.remove(&u);
+            used.remove(&v);
+        }
+
+        false
+    }
+}
+
+/// Bucket key for candidate lookup; equal operator types share a bucket
+fn op_type_hash(op_type: &OperatorType) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    op_type.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OperatorNode;
+
+    fn scan_filter_sort() -> QueryDag {
+        let mut dag = QueryDag::new();
+        let scan = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let filter = dag.add_node(OperatorNode::filter(0, "age > 18"));
+        let sort = dag.add_node(OperatorNode::sort(0, vec!["name".to_string()]));
+        dag.add_edge(scan, filter).unwrap();
+        dag.add_edge(filter, sort).unwrap();
+        dag
+    }
+
+    #[test]
+    fn test_relabeled_dags_are_structurally_equal() {
+        let a = scan_filter_sort();
+
+        // Same plan with nodes inserted in reverse order
+        let mut b = QueryDag::new();
+        let sort = b.add_node(OperatorNode::sort(0, vec!["name".to_string()]));
+        let filter = b.add_node(OperatorNode::filter(0, "age > 18"));
+        let scan = b.add_node(OperatorNode::seq_scan(0, "users"));
+        b.add_edge(scan, filter).unwrap();
+        b.add_edge(filter, sort).unwrap();
+
+        assert!(a.structural_eq(&b));
+        assert!(b.structural_eq(&a));
+        assert!(!a.diff(&b).is_empty());
+    }
+
+    #[test]
+    fn test_extra_edge_is_not_structurally_equal() {
+        let a = scan_filter_sort();
+        let mut b = scan_filter_sort();
+        b.add_edge(0, 2).unwrap();
+
+        assert!(!a.structural_eq(&b));
+
+        let diff = a.diff(&b);
+        assert_eq!(diff.added_edges, vec![(0, 2)]);
+        assert!(diff.removed_edges.is_empty());
+        assert!(diff.added_nodes.is_empty());
+        assert!(diff.removed_nodes.is_empty());
+        assert!(diff.changed_nodes.is_empty());
+    }
+
+    #[test]
+    fn test_operator_mismatch() {
+        let a = scan_filter_sort();
+        let mut b = scan_filter_sort();
+        b.get_node_mut(1).unwrap().op_type = OperatorNode::filter(0, "age > 21").op_type;
+
+        assert!(!a.structural_eq(&b));
+        assert_eq!(a.diff(&b).changed_nodes, vec![1]);
+    }
+
+    #[test]
+    fn test_diff_nodes() {
+        let a = scan_filter_sort();
+        let mut b = scan_filter_sort();
+        b.remove_node(2);
+        let limit = b.add_node(OperatorNode::limit(0, 10));
+
+        let diff = a.diff(&b);
+        assert_eq!(diff.removed_nodes, vec![2]);
+        assert_eq!(diff.added_nodes, vec![limit]);
+        assert_eq!(diff.removed_edges, vec![(1, 2)]);
+        assert!(a.diff(&a).is_empty());
+    }
+}
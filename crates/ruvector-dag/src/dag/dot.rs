@@ -0,0 +1,220 @@
+//! Graphviz DOT export for query DAGs
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::operator_node::OperatorType;
+use super::query_dag::QueryDag;
+
+impl QueryDag {
+    /// Render the DAG as a Graphviz DOT digraph.
+    ///
+    /// Nodes are labelled from their operator, e.g. `SeqScan(users)` or
+    /// `HashJoin(user_id)`, and edges run from input to consumer. Render
+    /// with `dot -Tpng plan.dot -o plan.png`.
+    pub fn to_dot(&self) -> String {
+        self.render_dot(None)
+    }
+
+    /// Render the DAG as DOT with nodes shaded by score.
+    ///
+    /// Scores are min-max normalized across the map; the highest scoring
+    /// node is filled red and the lowest white. Nodes without a score
+    /// are left unfilled. Attention scores from
+    /// [`DagAttention::forward`](crate::attention::DagAttention::forward)
+    /// can be passed directly.
+    pub fn to_dot_with_scores(&self, scores: &HashMap<usize, f32>) -> String {
+        self.render_dot(Some(scores))
+    }
+
+    fn render_dot(&self, scores: Option<&HashMap<usize, f32>>) -> String {
+        let range = scores.and_then(|s| {
+            let finite = s.values().copied().filter(|v| v.is_finite());
+            let min = finite.clone().reduce(f32::min)?;
+            let max = finite.reduce(f32::max)?;
+            Some((min, max))
+        });
+
+        let mut ids: Vec<usize> = self.node_ids().collect();
+        ids.sort_unstable();
+
+        let mut out = String::from("digraph query_dag {\n");
+        out.push_str("    rankdir=BT;\n");
+        out.push_str("    node [shape=box, fontname=\"Helvetica\"];\n");
+
+        for &id in &ids {
+            let mut label = operator_label(&self.nodes[&id].op_type);
+            let mut style = String::new();
+            if let (Some(score), Some((min, max))) =
+                (scores.and_then(|s| s.get(&id)).copied(), range)
+            {
+                if score.is_finite() {
+                    let t = if max > min {
+                        (score - min) / (max - min)
+                    } else {
+                        1.0
+                    };
+                    let fade = (255.0 * (1.0 - t)).round() as u8;
+                    let _ = write!(label, "\n{:.3}", score);
+                    let _ = write!(
+                        style,
+                        ", style=filled, fillcolor=\"#ff{:02x}{:02x}\"",
+                        fade, fade
+                    );
+                }
+            }
+            let _ = writeln!(
+                out,
+                "    n{} [label=\"{}\"{}];",
+                id,
+                escape_dot(&label),
+                style
+            );
+        }
+
+        for &id in &ids {
+            for &child in self.children(id) {
+                let _ = writeln!(out, "    n{} -> n{};", id, child);
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Short human-readable label for an operator
+fn operator_label(op: &OperatorType) -> String {
+    match op {
+        OperatorType::SeqScan { table } => format!("SeqScan({})", table),
+        OperatorType::IndexScan { index, table } => format!("IndexScan({} on {})", index, table),
+        OperatorType::HnswScan { index, ef_search } => {
+            format!("HnswScan({}, ef={})", index, ef_search)
+        }
+        OperatorType::IvfFlatScan { index, nprobe } => {
+            format!("IvfFlatScan({}, nprobe={})", index, nprobe)
+        }
+        OperatorType::NestedLoopJoin => "NestedLoopJoin".to_string(),
+        OperatorType::HashJoin { hash_key } => format!("HashJoin({})", hash_key),
+        OperatorType::MergeJoin { merge_key } => format!("MergeJoin({})", merge_key),
+        OperatorType::Aggregate { functions } => format!("Aggregate({})", functions.join(", ")),
+        OperatorType::GroupBy { keys } => format!("GroupBy({})", keys.join(", ")),
+        OperatorType::Filter { predicate } => format!("Filter({})", predicate),
+        OperatorType::Project { columns } => format!("Project({})", columns.join(", ")),
+        OperatorType::Sort { keys, descending } => {
+            let keys: Vec<String> = keys
+                .iter()
+                .zip(descending.iter().chain(std::iter::repeat(&false)))
+                .map(|(k, &desc)| {
+                    if desc {
+                        format!("{} DESC", k)
+                    } else {
+                        k.clone()
+                    }
+                })
+                .collect();
+            format!("Sort({})", keys.join(", "))
+        }
+        OperatorType::Limit { count } => format!("Limit({})", count),
+        OperatorType::VectorDistance { metric } => format!("VectorDistance({})", metric),
+        OperatorType::Rerank { model } => format!("Rerank({})", model),
+        OperatorType::Materialize => "Materialize".to_string(),
+        OperatorType::Result => "Result".to_string(),
+        #[allow(deprecated)]
+        OperatorType::Scan => "Scan".to_string(),
+        #[allow(deprecated)]
+        OperatorType::Join => "Join".to_string(),
+    }
+}
+
+/// Escape a string for use inside a double-quoted DOT ID
+fn escape_dot(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OperatorNode;
+
+    fn join_dag() -> QueryDag {
+        let mut dag = QueryDag::new();
+        let users = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let vectors = dag.add_node(OperatorNode::hnsw_scan(0, "vectors_idx", 64));
+        let join = dag.add_node(OperatorNode::hash_join(0, "user_id"));
+        let filter = dag.add_node(OperatorNode::filter(0, "users.name = \"bob\\\""));
+        let result = dag.add_node(OperatorNode::result(0));
+        dag.add_edge(users, join).unwrap();
+        dag.add_edge(vectors, join).unwrap();
+        dag.add_edge(join, filter).unwrap();
+        dag.add_edge(filter, result).unwrap();
+        dag
+    }
+
+    /// Checks that every line is a statement and every quoted string closes
+    fn assert_well_formed(dot: &str) {
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines.first(), Some(&"digraph query_dag {"));
+        assert_eq!(lines.last(), Some(&"}"));
+        for line in &lines[1..lines.len() - 1] {
+            assert!(line.ends_with(';'), "unterminated statement: {}", line);
+
+            let mut in_string = false;
+            let mut chars = line.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' if in_string => {
+                        chars.next();
+                    }
+                    '"' => in_string = !in_string,
+                    _ => {}
+                }
+            }
+            assert!(!in_string, "unbalanced quotes: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dag = join_dag();
+        let dot = dag.to_dot();
+        assert_well_formed(&dot);
+
+        let node_lines = dot.lines().filter(|l| l.contains("[label=")).count();
+        let edge_lines = dot.lines().filter(|l| l.contains(" -> ")).count();
+        assert_eq!(node_lines, dag.node_count());
+        assert_eq!(edge_lines, dag.edge_count());
+
+        assert!(dot.contains("n0 [label=\"SeqScan(users)\"];"));
+        assert!(dot.contains("n2 [label=\"HashJoin(user_id)\"];"));
+        assert!(dot.contains("HnswScan(vectors_idx, ef=64)"));
+        assert!(dot.contains(r#"Filter(users.name = \"bob\\\")"#));
+        assert!(dot.contains("n0 -> n2;"));
+        assert!(dot.contains("n1 -> n2;"));
+        assert!(!dot.contains("fillcolor"));
+    }
+
+    #[test]
+    fn test_to_dot_with_scores() {
+        let dag = join_dag();
+        let scores = HashMap::from([(0, 0.1), (2, 0.6), (4, 0.35)]);
+        let dot = dag.to_dot_with_scores(&scores);
+        assert_well_formed(&dot);
+
+        assert_eq!(dot.matches("style=filled").count(), 3);
+        assert!(dot.contains("HashJoin(user_id)\\n0.600\", style=filled, fillcolor=\"#ff0000\""));
+        assert!(dot.contains("fillcolor=\"#ffffff\""));
+        // Unscored nodes keep the plain label
+        assert!(dot.contains("n1 [label=\"HnswScan(vectors_idx, ef=64)\"];"));
+    }
+}
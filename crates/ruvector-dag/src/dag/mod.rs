@@ -1,10 +1,16 @@
 //! Core DAG data structures and algorithms
 
+mod cost;
+mod diff;
+mod dot;
 mod operator_node;
+mod pushdown;
 mod query_dag;
 mod serialization;
 mod traversal;
 
+pub use cost::{CostEstimate, CostModel, HeuristicCostModel};
+pub use diff::DagDiff;
 pub use operator_node::{OperatorNode, OperatorType};
 pub use query_dag::{DagError, QueryDag};
 pub use serialization::{DagDeserializer, DagSerializer};
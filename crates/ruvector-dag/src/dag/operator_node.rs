@@ -1,9 +1,11 @@
 //! Operator node types and definitions for query DAG
 
+use std::collections::{HashMap, HashSet};
+
 use serde::{Deserialize, Serialize};
 
 /// Types of operators in a query DAG
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub enum OperatorType {
     // Scan operators
     SeqScan {
@@ -85,6 +87,9 @@ pub struct OperatorNode {
     pub actual_rows: Option<f64>,
     pub actual_time_ms: Option<f64>,
     pub embedding: Option<Vec<f32>>,
+    /// Free-form key/value metadata such as planner hints or profiling data
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub annotations: HashMap<String, String>,
 }
 
 impl OperatorNode {
@@ -98,6 +103,7 @@ impl OperatorNode {
             actual_rows: None,
             actual_time_ms: None,
             embedding: None,
+            annotations: HashMap::new(),
         }
     }
 
@@ -259,6 +265,100 @@ impl OperatorNode {
         self.embedding = Some(embedding);
         self
     }
+
+    /// Set an annotation
+    pub fn with_annotation(mut self, key: &str, value: &str) -> Self {
+        self.set_annotation(key, value);
+        self
+    }
+
+    /// Set an annotation, returning the previous value for `key`
+    pub fn set_annotation(&mut self, key: &str, value: &str) -> Option<String> {
+        self.annotations.insert(key.to_string(), value.to_string())
+    }
+
+    /// Get an annotation
+    pub fn get_annotation(&self, key: &str) -> Option<&str> {
+        self.annotations.get(key).map(String::as_str)
+    }
+
+    /// Predicate of a filter node
+    pub fn predicate(&self) -> Option<&str> {
+        match &self.op_type {
+            OperatorType::Filter { predicate } => Some(predicate),
+            _ => None,
+        }
+    }
+
+    /// Table read by a scan node, if the scan names one
+    pub fn source_table(&self) -> Option<&str> {
+        match &self.op_type {
+            OperatorType::SeqScan { table } | OperatorType::IndexScan { table, .. } => Some(table),
+            _ => None,
+        }
+    }
+
+    /// Tables referenced by a filter node's predicate.
+    ///
+    /// Column references must be qualified as `table.column`. Returns `None`
+    /// for non-filter nodes and for predicates with unqualified columns,
+    /// whose source table cannot be determined.
+    pub fn predicate_tables(&self) -> Option<HashSet<String>> {
+        predicate_tables(self.predicate()?)
+    }
+}
+
+/// Words in predicates that are operators or literals, not columns
+const PREDICATE_KEYWORDS: &[&str] = &[
+    "and", "or", "not", "is", "null", "in", "like", "ilike", "between", "true", "false",
+];
+
+/// Extract the table qualifiers of all column references in a predicate.
+fn predicate_tables(predicate: &str) -> Option<HashSet<String>> {
+    let mut tables = HashSet::new();
+    let mut chars = predicate.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c == '\'' {
+            // Skip string literals ('' escapes a quote)
+            while let Some((_, c)) = chars.next() {
+                if c == '\'' && chars.next_if(|&(_, c)| c == '\'').is_none() {
+                    break;
+                }
+            }
+        } else if c.is_ascii_digit() {
+            while chars
+                .next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+                .is_some()
+            {}
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            let mut qualifier = None;
+            while let Some((i, c)) =
+                chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+            {
+                if c == '.' && qualifier.is_none() {
+                    qualifier = Some(&predicate[start..i]);
+                }
+                end = i + c.len_utf8();
+            }
+            let word = &predicate[start..end];
+
+            match qualifier {
+                Some(table) => {
+                    tables.insert(table.to_string());
+                }
+                // Function names are followed by a call
+                None if predicate[end..].trim_start().starts_with('(') => {}
+                None if PREDICATE_KEYWORDS
+                    .iter()
+                    .any(|k| k.eq_ignore_ascii_case(word)) => {}
+                None => return None,
+            }
+        }
+    }
+
+    Some(tables)
 }
 
 #[cfg(test)]
@@ -291,4 +391,22 @@ mod tests {
         let deserialized: OperatorNode = serde_json::from_str(&json).unwrap();
         assert_eq!(node.id, deserialized.id);
     }
+
+    #[test]
+    fn test_predicate_tables() {
+        let tables = |p: &str| OperatorNode::filter(0, p).predicate_tables();
+        let set = |names: &[&str]| names.iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(
+            tables("users.age > 18 AND users.name LIKE 'a.b'"),
+            Some(set(&["users"]))
+        );
+        assert_eq!(
+            tables("lower(users.email) = orders.email OR orders.total > 1.5"),
+            Some(set(&["users", "orders"]))
+        );
+        assert_eq!(tables("age > 18"), None);
+        assert_eq!(tables("users.note = 'it''s' AND x IS NULL"), None);
+        assert_eq!(OperatorNode::seq_scan(0, "users").predicate_tables(), None);
+    }
 }
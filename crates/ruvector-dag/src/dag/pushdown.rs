@@ -0,0 +1,215 @@
+//! Predicate pushdown for query DAGs
+
+use std::collections::{HashMap, HashSet};
+
+use super::operator_node::OperatorType;
+use super::query_dag::QueryDag;
+
+impl QueryDag {
+    /// Move `Filter` nodes below joins and other row-preserving operators.
+    ///
+    /// Edges run from input to consumer, so a filter is pushed by swapping
+    /// it with its input. A filter moves below a join when every table its
+    /// predicate references is produced by a single join input, and below
+    /// `Sort`/`Materialize` unconditionally. Filters whose input has other
+    /// consumers, or whose predicate references unqualified columns, stay
+    /// in place. These rewrites do not change the set of qualifying rows.
+    ///
+    /// Filters are pushed repeatedly until none can move further. Returns
+    /// the number of filters that moved.
+    pub fn pushdown_predicates(&mut self) -> usize {
+        let mut pushed = HashSet::new();
+
+        loop {
+            let mut moved = false;
+            let mut filters: Vec<usize> = self
+                .nodes
+                .values()
+                .filter(|n| n.predicate().is_some())
+                .map(|n| n.id)
+                .collect();
+            filters.sort_unstable();
+
+            for filter in filters {
+                if let Some((input, source)) = self.pushdown_target(filter) {
+                    self.swap_below(filter, input, source);
+                    pushed.insert(filter);
+                    moved = true;
+                }
+            }
+
+            if !moved {
+                return pushed.len();
+            }
+        }
+    }
+
+    /// Find the operator a filter can move below, and the input of that
+    /// operator the filter should be placed on
+    fn pushdown_target(&self, filter: usize) -> Option<(usize, usize)> {
+        let &[input] = self.parents(filter) else {
+            return None;
+        };
+        if self.children(input) != [filter] {
+            return None;
+        }
+
+        match &self.nodes[&input].op_type {
+            OperatorType::Sort { .. } | OperatorType::Materialize => match self.parents(input) {
+                &[source] => Some((input, source)),
+                _ => None,
+            },
+            OperatorType::HashJoin { .. }
+            | OperatorType::MergeJoin { .. }
+            | OperatorType::NestedLoopJoin => {
+                let tables = self.nodes[&filter].predicate_tables()?;
+                if tables.is_empty() {
+                    return None;
+                }
+                let mut cache = HashMap::new();
+                let mut sources = self
+                    .parents(input)
+                    .iter()
+                    .filter(|&&source| tables.is_subset(self.output_tables(source, &mut cache)));
+                match (sources.next(), sources.next()) {
+                    (Some(&source), None) => Some((input, source)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Tables whose columns are available in the output of `id`
+    fn output_tables<'a>(
+        &self,
+        id: usize,
+        cache: &'a mut HashMap<usize, HashSet<String>>,
+    ) -> &'a HashSet<String> {
+        if !cache.contains_key(&id) {
+            let mut tables = HashSet::new();
+            if let Some(table) = self.nodes[&id].source_table() {
+                tables.insert(table.to_string());
+            }
+            for &parent in self.parents(id) {
+                tables.extend(self.output_tables(parent, cache).iter().cloned());
+            }
+            cache.insert(id, tables);
+        }
+        &cache[&id]
+    }
+
+    /// Rewire `source -> input -> filter -> consumers` into
+    /// `source -> filter -> input -> consumers`, keeping input order
+    fn swap_below(&mut self, filter: usize, input: usize, source: usize) {
+        let consumers = std::mem::take(self.edges.get_mut(&filter).unwrap());
+        for &consumer in &consumers {
+            for parent in self.reverse_edges.get_mut(&consumer).unwrap() {
+                if *parent == filter {
+                    *parent = input;
+                }
+            }
+        }
+
+        let source_edge = self.edges.get_mut(&source).unwrap();
+        let pos = source_edge.iter().position(|&c| c == input).unwrap();
+        source_edge[pos] = filter;
+        let input_parents = self.reverse_edges.get_mut(&input).unwrap();
+        let pos = input_parents.iter().position(|&p| p == source).unwrap();
+        input_parents[pos] = filter;
+
+        self.edges.insert(input, consumers);
+        self.edges.insert(filter, vec![input]);
+        self.reverse_edges.insert(filter, vec![source]);
+
+        // Trading places keeps the cached order valid: `source` still
+        // precedes both and the consumers still follow both.
+        self.swap_topological_positions(filter, input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{OperatorNode, QueryDag};
+
+    /// users, orders -> join -> filter -> result
+    fn join_then_filter(predicate: &str) -> (QueryDag, [usize; 5]) {
+        let mut dag = QueryDag::new();
+        let users = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let orders = dag.add_node(OperatorNode::seq_scan(0, "orders"));
+        let join = dag.add_node(OperatorNode::hash_join(0, "user_id"));
+        let filter = dag.add_node(OperatorNode::filter(0, predicate));
+        let result = dag.add_node(OperatorNode::result(0));
+        dag.add_edge(users, join).unwrap();
+        dag.add_edge(orders, join).unwrap();
+        dag.add_edge(join, filter).unwrap();
+        dag.add_edge(filter, result).unwrap();
+        (dag, [users, orders, join, filter, result])
+    }
+
+    #[test]
+    fn test_single_table_predicate_pushed_below_join() {
+        let (mut dag, [users, orders, join, filter, result]) =
+            join_then_filter("orders.total > 100");
+
+        assert_eq!(dag.pushdown_predicates(), 1);
+        assert_eq!(dag.parents(filter), &[orders]);
+        assert_eq!(dag.children(filter), &[join]);
+        assert_eq!(dag.parents(join), &[users, filter]);
+        assert_eq!(dag.children(join), &[result]);
+        assert_eq!(dag.parents(result), &[join]);
+        assert_eq!(dag.node_count(), 5);
+        assert_eq!(dag.edge_count(), 4);
+
+        // Already at the scan: nothing left to push
+        assert_eq!(dag.pushdown_predicates(), 0);
+    }
+
+    #[test]
+    fn test_cross_table_predicate_left_in_place() {
+        let (mut dag, [_, _, join, filter, result]) =
+            join_then_filter("users.region = orders.region");
+        let before = dag.clone();
+
+        assert_eq!(dag.pushdown_predicates(), 0);
+        assert!(before.diff(&dag).is_empty());
+        assert_eq!(dag.parents(filter), &[join]);
+        assert_eq!(dag.children(filter), &[result]);
+
+        // Unqualified columns cannot be attributed to a table either
+        let (mut dag, _) = join_then_filter("total > 100");
+        assert_eq!(dag.pushdown_predicates(), 0);
+    }
+
+    #[test]
+    fn test_pushdown_through_sort_and_nested_joins() {
+        let mut dag = QueryDag::new();
+        let users = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let orders = dag.add_node(OperatorNode::seq_scan(0, "orders"));
+        let items = dag.add_node(OperatorNode::seq_scan(0, "items"));
+        let inner = dag.add_node(OperatorNode::hash_join(0, "user_id"));
+        let outer = dag.add_node(OperatorNode::nested_loop_join(0));
+        let sort = dag.add_node(OperatorNode::sort(0, vec!["users.name".to_string()]));
+        let filter = dag.add_node(OperatorNode::filter(0, "users.age >= 18"));
+        dag.add_edge(users, inner).unwrap();
+        dag.add_edge(orders, inner).unwrap();
+        dag.add_edge(inner, outer).unwrap();
+        dag.add_edge(items, outer).unwrap();
+        dag.add_edge(outer, sort).unwrap();
+        dag.add_edge(sort, filter).unwrap();
+
+        assert_eq!(dag.pushdown_predicates(), 1);
+        assert_eq!(dag.parents(filter), &[users]);
+        assert_eq!(dag.children(filter), &[inner]);
+        assert_eq!(dag.parents(inner), &[filter, orders]);
+        assert_eq!(dag.parents(outer), &[inner, items]);
+        assert_eq!(dag.parents(sort), &[outer]);
+        assert!(dag.children(sort).is_empty());
+        assert!(dag.topological_sort().is_ok());
+        let order = dag.topological_order_cached();
+        let pos = |id| order.iter().position(|&n| n == id).unwrap();
+        for id in dag.node_ids() {
+            assert!(dag.children(id).iter().all(|&c| pos(id) < pos(c)));
+        }
+    }
+}
@@ -25,6 +25,10 @@ pub struct QueryDag {
     pub(crate) reverse_edges: HashMap<usize, Vec<usize>>, // child -> parents
     pub(crate) root: Option<usize>,
     next_id: usize,
+    /// Topological order maintained incrementally by `add_edge`
+    topo_order: Vec<usize>,
+    /// Position of each node in `topo_order`
+    topo_index: HashMap<usize, usize>,
 }
 
 impl QueryDag {
@@ -36,6 +40,8 @@ impl QueryDag {
             reverse_edges: HashMap::new(),
             root: None,
             next_id: 0,
+            topo_order: Vec::new(),
+            topo_index: HashMap::new(),
         }
     }
 
@@ -48,6 +54,8 @@ impl QueryDag {
         self.nodes.insert(id, node);
         self.edges.insert(id, Vec::new());
         self.reverse_edges.insert(id, Vec::new());
+        self.topo_index.insert(id, self.topo_order.len());
+        self.topo_order.push(id);
 
         // If this is the first node, set it as root
         if self.nodes.len() == 1 {
@@ -67,10 +75,8 @@ impl QueryDag {
             return Err(DagError::NodeNotFound(child));
         }
 
-        // Check if adding this edge would create a cycle
-        if self.would_create_cycle(parent, child) {
-            return Err(DagError::CycleDetected);
-        }
+        // Restore the topological order, failing if the edge closes a cycle
+        self.reorder_for_edge(parent, child)?;
 
         // Add edge
         self.edges.get_mut(&parent).unwrap().push(child);
@@ -93,6 +99,12 @@ impl QueryDag {
     pub fn remove_node(&mut self, id: usize) -> Option<OperatorNode> {
         let node = self.nodes.remove(&id)?;
 
+        let pos = self.topo_index.remove(&id).unwrap();
+        self.topo_order.remove(pos);
+        for (i, &nid) in self.topo_order.iter().enumerate().skip(pos) {
+            self.topo_index.insert(nid, i);
+        }
+
         // Remove all edges involving this node
         if let Some(children) = self.edges.remove(&id) {
             for child in children {
@@ -145,6 +157,17 @@ impl QueryDag {
             .unwrap_or(&[])
     }
 
+    /// Estimated data-flow volume along the edge `parent -> child`
+    ///
+    /// This is the estimated row count `parent` produces. Returns `None` if
+    /// there is no such edge.
+    pub fn edge_weight(&self, parent: usize, child: usize) -> Option<f64> {
+        if !self.children(parent).contains(&child) {
+            return None;
+        }
+        Some(self.nodes[&parent].estimated_rows.max(0.0))
+    }
+
     /// Get the root node ID
     pub fn root(&self) -> Option<usize> {
         self.root
@@ -179,38 +202,71 @@ impl QueryDag {
         self.nodes.values()
     }
 
-    /// Check if adding an edge would create a cycle
-    fn would_create_cycle(&self, from: usize, to: usize) -> bool {
-        // If 'to' can reach 'from', adding edge from->to would create cycle
-        self.can_reach(to, from)
-    }
-
-    /// Check if 'from' can reach 'to' through existing edges
-    fn can_reach(&self, from: usize, to: usize) -> bool {
-        if from == to {
-            return true;
+    /// Pearce-Kelly update of the cached order for a new edge `from -> to`.
+    ///
+    /// Nothing moves if `from` already precedes `to`. Otherwise only nodes
+    /// positioned between the endpoints can be affected: descendants of `to`
+    /// up to `from`'s position and ancestors of `from` down to `to`'s
+    /// position. Those are reassigned the positions they already occupy,
+    /// ancestors first. Reaching `from` while searching from `to` means the
+    /// edge would create a cycle; the order is then left untouched.
+    fn reorder_for_edge(&mut self, from: usize, to: usize) -> Result<(), DagError> {
+        let upper = self.topo_index[&from];
+        let lower = self.topo_index[&to];
+        if upper < lower {
+            return Ok(());
         }
 
-        let mut visited = HashSet::new();
-        let mut queue = VecDeque::new();
-        queue.push_back(from);
-        visited.insert(from);
-
-        while let Some(current) = queue.pop_front() {
-            if current == to {
-                return true;
+        let mut forward = Vec::new();
+        let mut visited = HashSet::from([to]);
+        let mut stack = vec![to];
+        while let Some(node) = stack.pop() {
+            if node == from {
+                return Err(DagError::CycleDetected);
             }
+            forward.push(node);
+            for &child in self.children(node) {
+                if self.topo_index[&child] <= upper && visited.insert(child) {
+                    stack.push(child);
+                }
+            }
+        }
 
-            if let Some(children) = self.edges.get(&current) {
-                for &child in children {
-                    if visited.insert(child) {
-                        queue.push_back(child);
-                    }
+        let mut backward = Vec::new();
+        let mut visited = HashSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            backward.push(node);
+            for &parent in self.parents(node) {
+                if self.topo_index[&parent] >= lower && visited.insert(parent) {
+                    stack.push(parent);
                 }
             }
         }
 
-        false
+        forward.sort_unstable_by_key(|n| self.topo_index[n]);
+        backward.sort_unstable_by_key(|n| self.topo_index[n]);
+        let mut slots: Vec<usize> = backward
+            .iter()
+            .chain(&forward)
+            .map(|n| self.topo_index[n])
+            .collect();
+        slots.sort_unstable();
+
+        for (node, slot) in backward.into_iter().chain(forward).zip(slots) {
+            self.topo_order[slot] = node;
+            self.topo_index.insert(node, slot);
+        }
+        Ok(())
+    }
+
+    /// Exchange the positions of two nodes in the cached order. Callers
+    /// rewiring edges directly must keep the order valid.
+    pub(crate) fn swap_topological_positions(&mut self, a: usize, b: usize) {
+        let (pa, pb) = (self.topo_index[&a], self.topo_index[&b]);
+        self.topo_order.swap(pa, pb);
+        self.topo_index.insert(a, pb);
+        self.topo_index.insert(b, pa);
     }
 
     /// Compute depth of each node from leaves (leaves have depth 0)
@@ -275,6 +331,29 @@ impl QueryDag {
         result
     }
 
+    /// Get ancestors of a node reachable within `max_hops` edges
+    pub fn ancestors_within(&self, id: usize, max_hops: usize) -> HashSet<usize> {
+        let mut result = HashSet::new();
+        let mut frontier = vec![id];
+
+        for _ in 0..max_hops {
+            let mut next = Vec::new();
+            for node in frontier {
+                for &parent in self.parents(node) {
+                    if result.insert(parent) {
+                        next.push(parent);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        result
+    }
+
     /// Get all descendants of a node
     pub fn descendants(&self, id: usize) -> HashSet<usize> {
         let mut result = HashSet::new();
@@ -300,6 +379,43 @@ impl QueryDag {
         result
     }
 
+    /// Copy the nodes in `ids` and the edges between them into a new DAG.
+    ///
+    /// Nodes are cloned, annotations included, and renumbered so that the
+    /// node at `ids[i]` becomes node `i`.
+    pub fn extract_subgraph(&self, ids: &[usize]) -> Result<QueryDag, DagError> {
+        let mut id_map = HashMap::with_capacity(ids.len());
+        let mut sub = QueryDag::new();
+        for &id in ids {
+            let node = self.nodes.get(&id).ok_or(DagError::NodeNotFound(id))?;
+            if id_map.contains_key(&id) {
+                return Err(DagError::InvalidOperation(format!(
+                    "Node {} listed twice",
+                    id
+                )));
+            }
+            id_map.insert(id, sub.add_node(node.clone()));
+        }
+
+        for &id in ids {
+            for child in self.children(id) {
+                if let Some(&new_child) = id_map.get(child) {
+                    sub.add_edge(id_map[&id], new_child)?;
+                }
+            }
+        }
+        Ok(sub)
+    }
+
+    /// Topological order kept up to date as nodes and edges are added or
+    /// removed (dependencies first)
+    ///
+    /// Unlike [`topological_sort`](Self::topological_sort) this does no
+    /// work. Both are valid orders but need not be identical.
+    pub fn topological_order_cached(&self) -> &[usize] {
+        &self.topo_order
+    }
+
     /// Return nodes in topological order as Vec (dependencies first)
     pub fn topological_sort(&self) -> Result<Vec<usize>, DagError> {
         let mut result = Vec::new();
@@ -449,4 +565,93 @@ mod tests {
         assert!(descendants.contains(&id2));
         assert!(descendants.contains(&id3));
     }
+
+    /// Every edge must point forward in the cached order
+    fn assert_cached_order_valid(dag: &QueryDag) {
+        let order = dag.topological_order_cached();
+        let pos: HashMap<usize, usize> = order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        assert_eq!(pos.len(), dag.node_count());
+        for id in dag.node_ids() {
+            for &child in dag.children(id) {
+                assert!(
+                    pos[&id] < pos[&child],
+                    "edge {} -> {} out of order",
+                    id,
+                    child
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cached_order_tracks_insertions() {
+        // Chain built back to front: every edge forces a reorder, and the
+        // only valid order is the one a full sort finds.
+        let mut dag = QueryDag::new();
+        let ids: Vec<usize> = (0..6)
+            .map(|i| dag.add_node(OperatorNode::filter(0, &format!("x > {}", i))))
+            .collect();
+        for w in ids.windows(2).rev() {
+            dag.add_edge(w[1], w[0]).unwrap();
+            assert_cached_order_valid(&dag);
+        }
+        let expected: Vec<usize> = ids.iter().rev().copied().collect();
+        assert_eq!(dag.topological_order_cached(), expected.as_slice());
+        assert_eq!(dag.topological_sort().unwrap(), expected);
+
+        // Pseudo-random edges, some of which would close cycles
+        let mut dag = QueryDag::new();
+        for i in 0..40 {
+            dag.add_node(OperatorNode::filter(0, &format!("x > {}", i)));
+        }
+        let mut state: u64 = 7;
+        for _ in 0..200 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let (a, b) = ((state >> 33) as usize % 40, (state >> 13) as usize % 40);
+            let before = dag.topological_order_cached().to_vec();
+            match dag.add_edge(a, b) {
+                Ok(()) => assert_cached_order_valid(&dag),
+                Err(DagError::CycleDetected) => {
+                    assert_eq!(dag.topological_order_cached(), before.as_slice())
+                }
+                Err(e) => panic!("unexpected error: {}", e),
+            }
+        }
+        let mut cached = dag.topological_order_cached().to_vec();
+        let mut sorted = dag.topological_sort().unwrap();
+        cached.sort_unstable();
+        sorted.sort_unstable();
+        assert_eq!(cached, sorted);
+
+        dag.remove_node(17);
+        assert_cached_order_valid(&dag);
+    }
+
+    #[test]
+    fn test_extract_subgraph_keeps_annotations() {
+        let mut dag = QueryDag::new();
+        let scan = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let filter =
+            dag.add_node(OperatorNode::filter(0, "age > 18").with_annotation("hint", "pushdown"));
+        let sort = dag.add_node(OperatorNode::sort(0, vec!["name".to_string()]));
+        dag.add_edge(scan, filter).unwrap();
+        dag.add_edge(filter, sort).unwrap();
+
+        let sub = dag.extract_subgraph(&[filter, sort]).unwrap();
+        assert_eq!(sub.node_count(), 2);
+        assert_eq!(sub.children(0), &[1]);
+        assert_eq!(
+            sub.get_node(0).unwrap().get_annotation("hint"),
+            Some("pushdown")
+        );
+        assert_eq!(sub.get_node(0).unwrap().id, 0);
+
+        assert!(matches!(
+            dag.extract_subgraph(&[scan, 99]),
+            Err(DagError::NodeNotFound(99))
+        ));
+        assert!(dag.extract_subgraph(&[scan, scan]).is_err());
+    }
 }
@@ -181,4 +181,34 @@ mod tests {
         assert_eq!(restored.node_count(), 0);
         assert_eq!(restored.edge_count(), 0);
     }
+
+    #[test]
+    fn test_annotations_round_trip() {
+        let mut dag = QueryDag::new();
+        let scan = dag.add_node(
+            OperatorNode::seq_scan(0, "users").with_annotation("chosen_index", "users_pkey"),
+        );
+        let filter = dag.add_node(OperatorNode::filter(0, "age > 18"));
+        dag.get_node_mut(filter)
+            .unwrap()
+            .set_annotation("observed_rows", "412");
+        dag.add_edge(scan, filter).unwrap();
+
+        let restored = QueryDag::from_json(&dag.to_json().unwrap()).unwrap();
+        let annotated: Vec<_> = restored
+            .nodes()
+            .filter_map(|n| {
+                n.get_annotation("chosen_index")
+                    .or(n.get_annotation("observed_rows"))
+            })
+            .collect();
+        assert_eq!(annotated.len(), 2);
+        assert!(annotated.contains(&"users_pkey"));
+        assert!(annotated.contains(&"412"));
+
+        // JSON written before annotations existed still loads
+        let json = dag.to_json().unwrap().replace("annotations", "ignored");
+        let restored = QueryDag::from_json(&json).unwrap();
+        assert!(restored.nodes().all(|n| n.annotations.is_empty()));
+    }
 }
@@ -19,7 +19,7 @@ impl Default for AnomalyConfig {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AnomalyType {
     LatencySpike,
     PatternDrift,
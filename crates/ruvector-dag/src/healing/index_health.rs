@@ -10,7 +10,7 @@ pub struct IndexHealth {
     pub last_rebalanced: Option<std::time::Instant>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IndexType {
     Hnsw,
     IvfFlat,
@@ -1,9 +1,11 @@
 //! Healing Orchestrator - Main coordination
 
 use super::{
-    AnomalyConfig, AnomalyDetector, IndexHealthChecker, IndexThresholds, LearningDriftDetector,
-    RepairResult, RepairStrategy,
+    Anomaly, AnomalyConfig, AnomalyDetector, AnomalyType, CacheFlushStrategy, IndexHealthChecker,
+    IndexRebalanceStrategy, IndexThresholds, IndexType, LearningDriftDetector,
+    PatternResetStrategy, RepairResult, RepairStrategy,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct HealingOrchestrator {
@@ -11,6 +13,12 @@ pub struct HealingOrchestrator {
     index_checker: IndexHealthChecker,
     drift_detector: LearningDriftDetector,
     repair_strategies: Vec<Arc<dyn RepairStrategy>>,
+    /// Strategies registered per anomaly type
+    type_strategies: HashMap<AnomalyType, Arc<dyn RepairStrategy>>,
+    /// Strategies registered per index type and anomaly type
+    index_strategies: HashMap<(IndexType, AnomalyType), Arc<dyn RepairStrategy>>,
+    /// Index type backing each monitored component
+    component_indexes: HashMap<String, IndexType>,
     repair_history: Vec<RepairResult>,
     max_history_size: usize,
 }
@@ -22,6 +30,9 @@ impl HealingOrchestrator {
             index_checker: IndexHealthChecker::new(IndexThresholds::default()),
             drift_detector: LearningDriftDetector::new(0.1, 100),
             repair_strategies: Vec::new(),
+            type_strategies: HashMap::new(),
+            index_strategies: HashMap::new(),
+            component_indexes: HashMap::new(),
             repair_history: Vec::new(),
             max_history_size: 1000,
         }
@@ -37,6 +48,9 @@ impl HealingOrchestrator {
             index_checker: IndexHealthChecker::new(index_thresholds),
             drift_detector: LearningDriftDetector::new(drift_threshold, drift_window),
             repair_strategies: Vec::new(),
+            type_strategies: HashMap::new(),
+            index_strategies: HashMap::new(),
+            component_indexes: HashMap::new(),
             repair_history: Vec::new(),
             max_history_size: 1000,
         }
@@ -47,10 +61,75 @@ impl HealingOrchestrator {
             .insert(name.to_string(), AnomalyDetector::new(config));
     }
 
+    /// Add a fallback strategy, tried in insertion order for anomalies
+    /// without a registered strategy if its `can_repair` accepts them
     pub fn add_repair_strategy(&mut self, strategy: Arc<dyn RepairStrategy>) {
         self.repair_strategies.push(strategy);
     }
 
+    /// Handle every anomaly of `anomaly_type` with `strategy`, replacing any
+    /// strategy previously registered for that type
+    pub fn register_strategy(
+        &mut self,
+        anomaly_type: AnomalyType,
+        strategy: Arc<dyn RepairStrategy>,
+    ) {
+        self.type_strategies.insert(anomaly_type, strategy);
+    }
+
+    /// Handle anomalies of `anomaly_type` on components backed by
+    /// `index_type` with `strategy`. Takes precedence over
+    /// [`register_strategy`](Self::register_strategy).
+    pub fn register_index_strategy(
+        &mut self,
+        index_type: IndexType,
+        anomaly_type: AnomalyType,
+        strategy: Arc<dyn RepairStrategy>,
+    ) {
+        self.index_strategies
+            .insert((index_type, anomaly_type), strategy);
+    }
+
+    /// Declare which index type backs a monitored component
+    pub fn set_component_index(&mut self, component: &str, index_type: IndexType) {
+        self.component_indexes
+            .insert(component.to_string(), index_type);
+    }
+
+    /// Register the built-in strategies for each anomaly type: index
+    /// rebalancing for latency spikes, pattern resets for drift and
+    /// stalls, and cache flushes for eviction and memory pressure
+    pub fn register_default_strategies(&mut self) {
+        let rebalance: Arc<dyn RepairStrategy> = Arc::new(IndexRebalanceStrategy::new(0.95));
+        let reset: Arc<dyn RepairStrategy> = Arc::new(PatternResetStrategy::new(0.8));
+        let flush: Arc<dyn RepairStrategy> = Arc::new(CacheFlushStrategy);
+
+        self.register_strategy(AnomalyType::LatencySpike, rebalance);
+        self.register_strategy(AnomalyType::PatternDrift, reset.clone());
+        self.register_strategy(AnomalyType::LearningStall, reset);
+        self.register_strategy(AnomalyType::CacheEviction, flush.clone());
+        self.register_strategy(AnomalyType::MemoryPressure, flush);
+    }
+
+    /// Strategy the orchestrator dispatches `anomaly` to: one registered
+    /// for the component's index type, then one registered for the anomaly
+    /// type, then the first fallback strategy that can repair it
+    pub fn strategy_for(&self, anomaly: &Anomaly) -> Option<Arc<dyn RepairStrategy>> {
+        let by_index = self
+            .component_indexes
+            .get(&anomaly.component)
+            .and_then(|&index| self.index_strategies.get(&(index, anomaly.anomaly_type)));
+
+        by_index
+            .or_else(|| self.type_strategies.get(&anomaly.anomaly_type))
+            .or_else(|| {
+                self.repair_strategies
+                    .iter()
+                    .find(|s| s.can_repair(anomaly))
+            })
+            .cloned()
+    }
+
     pub fn observe(&mut self, component: &str, value: f64) {
         if let Some(detector) = self.anomaly_detectors.get_mut(component) {
             detector.observe(value);
@@ -86,17 +165,18 @@ impl HealingOrchestrator {
         let drifts = self.drift_detector.check_all_drifts();
 
         // Apply repairs
+        let mut repairs = Vec::new();
         for anomaly in &all_anomalies {
-            for strategy in &self.repair_strategies {
-                if strategy.can_repair(anomaly) {
-                    repairs_attempted += 1;
-                    let result = strategy.repair(anomaly);
-                    if result.success {
-                        repairs_succeeded += 1;
-                    }
-                    self.add_repair_result(result);
-                    break;
+            if let Some(strategy) = self.strategy_for(anomaly) {
+                repairs_attempted += 1;
+                let mut result = strategy.repair(anomaly);
+                result.strategy_name = strategy.name().to_string();
+                result.anomaly = Some(anomaly.clone());
+                if result.success {
+                    repairs_succeeded += 1;
                 }
+                self.add_repair_result(result.clone());
+                repairs.push(result);
             }
         }
 
@@ -105,6 +185,7 @@ impl HealingOrchestrator {
             drifts_detected: drifts.len(),
             repairs_attempted,
             repairs_succeeded,
+            repairs,
         }
     }
 
@@ -165,6 +246,8 @@ pub struct HealingCycleResult {
     pub drifts_detected: usize,
     pub repairs_attempted: usize,
     pub repairs_succeeded: usize,
+    /// Repairs run this cycle, each tagged with its strategy and anomaly
+    pub repairs: Vec<RepairResult>,
 }
 
 #[derive(Debug, Clone)]
@@ -183,7 +266,55 @@ impl Default for HealingOrchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::healing::{Anomaly, AnomalyType, IndexRebalanceStrategy};
+    use crate::healing::IndexRebalanceStrategy;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Strategy that counts its invocations and reports failure
+    struct CountingStrategy {
+        name: &'static str,
+        calls: AtomicUsize,
+    }
+
+    impl CountingStrategy {
+        fn new(name: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    impl RepairStrategy for CountingStrategy {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn can_repair(&self, _anomaly: &Anomaly) -> bool {
+            true
+        }
+
+        fn repair(&self, anomaly: &Anomaly) -> RepairResult {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            RepairResult {
+                strategy_name: "unreported".to_string(),
+                success: false,
+                duration_ms: 0.0,
+                details: format!("quarantined {}", anomaly.component),
+                anomaly: None,
+            }
+        }
+    }
+
+    /// Orchestrator whose `component` detector reports one latency spike
+    fn spiking(component: &str) -> HealingOrchestrator {
+        let mut orchestrator = HealingOrchestrator::new();
+        orchestrator.add_detector(component, AnomalyConfig::default());
+        for i in 0..20 {
+            orchestrator.observe(component, 10.0 + (i as f64) * 0.1);
+        }
+        orchestrator.observe(component, 100.0);
+        orchestrator
+    }
 
     #[test]
     fn test_orchestrator_creation() {
@@ -236,4 +367,90 @@ mod tests {
         let result = orchestrator.run_cycle();
         assert!(result.drifts_detected > 0);
     }
+
+    #[test]
+    fn test_registered_strategy_handles_anomaly_type() {
+        let mut orchestrator = spiking("latency");
+        let custom = CountingStrategy::new("quarantine");
+        let unrelated = CountingStrategy::new("unrelated");
+        orchestrator.add_repair_strategy(Arc::new(IndexRebalanceStrategy::new(0.95)));
+        orchestrator.register_strategy(AnomalyType::LatencySpike, custom.clone());
+        orchestrator.register_strategy(AnomalyType::MemoryPressure, unrelated.clone());
+
+        let result = orchestrator.run_cycle();
+        assert_eq!(custom.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(unrelated.calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(result.repairs_attempted, 1);
+        assert_eq!(result.repairs_succeeded, 0);
+        assert_eq!(result.repairs.len(), 1);
+        let repair = &result.repairs[0];
+        assert_eq!(repair.strategy_name, "quarantine");
+        assert_eq!(repair.details, "quarantined latency");
+        let anomaly = repair.anomaly.as_ref().unwrap();
+        assert_eq!(anomaly.anomaly_type, AnomalyType::LatencySpike);
+        assert_eq!(anomaly.component, "latency");
+
+        assert_eq!(orchestrator.repair_history()[0].strategy_name, "quarantine");
+        assert_eq!(orchestrator.health_score(), 0.0);
+    }
+
+    #[test]
+    fn test_index_strategy_takes_precedence() {
+        let mut orchestrator = spiking("vectors");
+        let relink = CountingStrategy::new("hnsw_relink");
+        let rebuild = CountingStrategy::new("rebuild");
+        orchestrator.register_default_strategies();
+        orchestrator.register_strategy(AnomalyType::LatencySpike, rebuild.clone());
+        orchestrator.register_index_strategy(
+            IndexType::Hnsw,
+            AnomalyType::LatencySpike,
+            relink.clone(),
+        );
+
+        // Without an index mapping the per-type strategy applies.
+        orchestrator.run_cycle();
+        assert_eq!(rebuild.calls.load(Ordering::SeqCst), 1);
+
+        orchestrator.set_component_index("vectors", IndexType::Hnsw);
+        let result = orchestrator.run_cycle();
+        assert_eq!(relink.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(rebuild.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(result.repairs[0].strategy_name, "hnsw_relink");
+    }
+
+    #[test]
+    fn test_default_strategies() {
+        let mut orchestrator = HealingOrchestrator::new();
+        orchestrator.register_default_strategies();
+
+        let anomaly = |anomaly_type| Anomaly {
+            anomaly_type,
+            z_score: 4.0,
+            value: 1.0,
+            expected: 0.0,
+            timestamp: std::time::Instant::now(),
+            component: "c".to_string(),
+        };
+        let name = |t| {
+            orchestrator
+                .strategy_for(&anomaly(t))
+                .map(|s| s.name().to_string())
+        };
+        assert_eq!(
+            name(AnomalyType::LatencySpike).as_deref(),
+            Some("index_rebalance")
+        );
+        assert_eq!(
+            name(AnomalyType::LearningStall).as_deref(),
+            Some("pattern_reset")
+        );
+        assert_eq!(
+            name(AnomalyType::MemoryPressure).as_deref(),
+            Some("cache_flush")
+        );
+        assert!(HealingOrchestrator::new()
+            .strategy_for(&anomaly(AnomalyType::LatencySpike))
+            .is_none());
+    }
 }
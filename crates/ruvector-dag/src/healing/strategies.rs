@@ -8,6 +8,8 @@ pub struct RepairResult {
     pub success: bool,
     pub duration_ms: f64,
     pub details: String,
+    /// Anomaly the repair addressed
+    pub anomaly: Option<Anomaly>,
 }
 
 pub trait RepairStrategy: Send + Sync {
@@ -50,6 +52,7 @@ impl RepairStrategy for IndexRebalanceStrategy {
                 "Rebalanced index for component: {} (target recall: {:.2})",
                 anomaly.component, self.target_recall
             ),
+            anomaly: Some(anomaly.clone()),
         }
     }
 }
@@ -90,6 +93,7 @@ impl RepairStrategy for PatternResetStrategy {
                 "Reset patterns below quality {} for component: {}",
                 self.quality_threshold, anomaly.component
             ),
+            anomaly: Some(anomaly.clone()),
         }
     }
 }
@@ -122,6 +126,7 @@ impl RepairStrategy for CacheFlushStrategy {
                 "Flushed attention and pattern caches for component: {}",
                 anomaly.component
             ),
+            anomaly: Some(anomaly.clone()),
         }
     }
 }
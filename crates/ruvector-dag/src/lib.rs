@@ -52,8 +52,8 @@ pub mod qudag;
 pub mod sona;
 
 pub use dag::{
-    BfsIterator, DagDeserializer, DagError, DagSerializer, DfsIterator, OperatorNode, OperatorType,
-    QueryDag, TopologicalIterator,
+    BfsIterator, CostEstimate, CostModel, DagDeserializer, DagDiff, DagError, DagSerializer,
+    DfsIterator, HeuristicCostModel, OperatorNode, OperatorType, QueryDag, TopologicalIterator,
 };
 
 pub use mincut::{
@@ -63,9 +63,9 @@ pub use mincut::{
 
 pub use attention::{
     AttentionConfig, AttentionError, AttentionScores, CausalConeAttention, CausalConeConfig,
-    CriticalPathAttention, CriticalPathConfig, DagAttention, FlowCapacity,
-    MinCutConfig as AttentionMinCutConfig, MinCutGatedAttention, TopologicalAttention,
-    TopologicalConfig,
+    CriticalPathAttention, CriticalPathConfig, DagAttention, DegreeCentralityAttention,
+    DegreeCentralityConfig, FlowCapacity, LambdaSchedule, MinCutConfig as AttentionMinCutConfig,
+    MinCutGatedAttention, TopologicalAttention, TopologicalConfig,
 };
 
 #[cfg(feature = "full")]
@@ -87,8 +87,9 @@ pub use healing::{
 
 #[cfg(feature = "full")]
 pub use sona::{
-    DagPattern, DagReasoningBank, DagSonaEngine, DagTrajectory, DagTrajectoryBuffer, EwcConfig,
-    EwcPlusPlus, MicroLoRA, MicroLoRAConfig, ReasoningBankConfig,
+    mine_frequent_subgraphs, DagPattern, DagReasoningBank, DagSonaEngine, DagTrajectory,
+    DagTrajectoryBuffer, EwcConfig, EwcPlusPlus, FrequentSubplan, MicroLoRA, MicroLoRAConfig,
+    ReasoningBankConfig,
 };
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 //! DagMinCutEngine: Main min-cut computation engine
 
 use super::local_kcut::LocalKCut;
+use super::max_flow::ResidualGraph;
 use crate::dag::QueryDag;
 use std::collections::{HashMap, HashSet};
 
@@ -120,6 +121,23 @@ impl DagMinCutEngine {
         result
     }
 
+    /// Compute the exact min-cut between source and sink
+    ///
+    /// Unlike [`compute_mincut`](Self::compute_mincut), which approximates
+    /// the cut from a depth-limited local search, this runs Edmonds-Karp
+    /// max-flow to completion. With the `rayon` feature, graphs of 1024 or
+    /// more nodes search augmenting paths with a parallel BFS; the result
+    /// is identical to the sequential search. Results are not cached.
+    pub fn compute_exact_mincut(&self, source: usize, sink: usize) -> MinCutResult {
+        #[cfg(feature = "rayon")]
+        let parallel = self.node_count >= super::max_flow::PARALLEL_THRESHOLD;
+        #[cfg(not(feature = "rayon"))]
+        let parallel = false;
+
+        ResidualGraph::from_adjacency(&self.adjacency, self.node_count)
+            .min_cut(source, sink, parallel)
+    }
+
     /// Dynamic update after edge weight change - O(n^0.12) amortized
     pub fn update_edge(&mut self, from: usize, to: usize, new_capacity: f64) {
         if let Some(edges) = self.adjacency.get_mut(&from) {
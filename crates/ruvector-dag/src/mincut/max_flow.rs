@@ -0,0 +1,305 @@
+//! Exact max-flow / min-cut via Edmonds-Karp
+//!
+//! Augmenting paths are found with a level-synchronous BFS. With the
+//! `rayon` feature the expansion of each BFS level runs in parallel on
+//! large graphs; discoveries are then merged in frontier order, so the
+//! parallel search picks exactly the same paths as the sequential one and
+//! both produce identical flows and cuts.
+
+use super::engine::{FlowEdge, MinCutResult};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Residual capacities at or below this are treated as saturated
+const EPS: f64 = 1e-9;
+
+/// Graphs with at least this many nodes use the parallel BFS
+#[cfg(feature = "rayon")]
+pub(crate) const PARALLEL_THRESHOLD: usize = 1024;
+
+/// Arc of the residual graph; arcs `2k` and `2k + 1` are a forward arc
+/// and its reverse
+struct Arc {
+    to: usize,
+    residual: f64,
+}
+
+/// Residual graph over dense node indices `0..node_count`
+pub(crate) struct ResidualGraph {
+    arcs: Vec<Arc>,
+    /// Outgoing arc indices per node
+    out: Vec<Vec<usize>>,
+    /// Original `(from, to, capacity)` of each forward arc
+    edges: Vec<(usize, usize, f64)>,
+}
+
+impl ResidualGraph {
+    /// Build from an engine adjacency map. Zero-capacity entries (the
+    /// placeholder reverse edges) are skipped; the residual graph adds its
+    /// own reverse arcs.
+    pub(crate) fn from_adjacency(
+        adjacency: &HashMap<usize, Vec<FlowEdge>>,
+        node_count: usize,
+    ) -> Self {
+        let mut graph = Self {
+            arcs: Vec::new(),
+            out: vec![Vec::new(); node_count],
+            edges: Vec::new(),
+        };
+
+        // Sort sources so arc order, and with it tie-breaking, is stable.
+        let mut sources: Vec<usize> = adjacency.keys().copied().collect();
+        sources.sort_unstable();
+        for from in sources {
+            for edge in &adjacency[&from] {
+                if edge.capacity > 0.0 && edge.from < node_count && edge.to < node_count {
+                    graph.add_arc(edge.from, edge.to, edge.capacity);
+                }
+            }
+        }
+        graph
+    }
+
+    fn add_arc(&mut self, from: usize, to: usize, capacity: f64) {
+        let id = self.arcs.len();
+        self.arcs.push(Arc {
+            to,
+            residual: capacity,
+        });
+        self.arcs.push(Arc {
+            to: from,
+            residual: 0.0,
+        });
+        self.out[from].push(id);
+        self.out[to].push(id + 1);
+        self.edges.push((from, to, capacity));
+    }
+
+    /// Unvisited nodes reachable over one unsaturated arc from `node`,
+    /// with the arc used
+    fn expand(&self, node: usize, visited: &[bool]) -> Vec<(usize, usize)> {
+        self.out[node]
+            .iter()
+            .filter_map(|&a| {
+                let arc = &self.arcs[a];
+                (arc.residual > EPS && !visited[arc.to]).then_some((arc.to, a))
+            })
+            .collect()
+    }
+
+    /// Level-synchronous BFS from `source`, recording the arc each node was
+    /// reached by. Stops once `sink` is reached; returns the visited set.
+    fn bfs(
+        &self,
+        source: usize,
+        sink: Option<usize>,
+        parent: &mut [usize],
+        parallel: bool,
+    ) -> Vec<bool> {
+        let mut visited = vec![false; self.out.len()];
+        visited[source] = true;
+        let mut frontier = vec![source];
+
+        while !frontier.is_empty() {
+            let found = self.expand_level(&frontier, &visited, parallel);
+
+            let mut next = Vec::new();
+            for (node, arc) in found.into_iter().flatten() {
+                if !visited[node] {
+                    visited[node] = true;
+                    parent[node] = arc;
+                    next.push(node);
+                }
+            }
+            if sink.is_some_and(|t| visited[t]) {
+                break;
+            }
+            frontier = next;
+        }
+        visited
+    }
+
+    #[cfg(feature = "rayon")]
+    fn expand_level(
+        &self,
+        frontier: &[usize],
+        visited: &[bool],
+        parallel: bool,
+    ) -> Vec<Vec<(usize, usize)>> {
+        if parallel {
+            // Indexed collect keeps frontier order.
+            frontier
+                .par_iter()
+                .map(|&u| self.expand(u, visited))
+                .collect()
+        } else {
+            frontier.iter().map(|&u| self.expand(u, visited)).collect()
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn expand_level(
+        &self,
+        frontier: &[usize],
+        visited: &[bool],
+        _parallel: bool,
+    ) -> Vec<Vec<(usize, usize)>> {
+        frontier.iter().map(|&u| self.expand(u, visited)).collect()
+    }
+
+    /// Saturate augmenting paths until none remain and return the min cut.
+    ///
+    /// If an augmenting path of unbounded capacity exists the cut value is
+    /// infinite and the search stops there.
+    pub(crate) fn min_cut(&mut self, source: usize, sink: usize, parallel: bool) -> MinCutResult {
+        let n = self.out.len();
+        if source >= n || sink >= n || source == sink {
+            return MinCutResult {
+                cut_value: 0.0,
+                source_side: HashSet::from([source]),
+                sink_side: HashSet::from([sink]),
+                cut_edges: Vec::new(),
+            };
+        }
+
+        let mut parent = vec![usize::MAX; n];
+        let mut unbounded = false;
+        loop {
+            let visited = self.bfs(source, Some(sink), &mut parent, parallel);
+            if !visited[sink] {
+                break;
+            }
+
+            let mut bottleneck = f64::INFINITY;
+            let mut node = sink;
+            while node != source {
+                let arc = parent[node];
+                bottleneck = bottleneck.min(self.arcs[arc].residual);
+                node = self.arcs[arc ^ 1].to;
+            }
+            if bottleneck.is_infinite() {
+                unbounded = true;
+                break;
+            }
+
+            let mut node = sink;
+            while node != source {
+                let arc = parent[node];
+                self.arcs[arc].residual -= bottleneck;
+                self.arcs[arc ^ 1].residual += bottleneck;
+                node = self.arcs[arc ^ 1].to;
+            }
+        }
+
+        let reachable = self.bfs(source, None, &mut parent, parallel);
+        let source_side: HashSet<usize> = (0..n).filter(|&v| reachable[v]).collect();
+        let sink_side: HashSet<usize> = (0..n).filter(|&v| !reachable[v]).collect();
+
+        let mut cut_edges = Vec::new();
+        let mut cut_value = 0.0;
+        for &(from, to, capacity) in &self.edges {
+            if reachable[from] && !reachable[to] {
+                cut_edges.push((from, to));
+                cut_value += capacity;
+            }
+        }
+
+        MinCutResult {
+            cut_value: if unbounded { f64::INFINITY } else { cut_value },
+            source_side,
+            sink_side,
+            cut_edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adjacency(edges: &[(usize, usize, f64)]) -> HashMap<usize, Vec<FlowEdge>> {
+        let mut adjacency: HashMap<usize, Vec<FlowEdge>> = HashMap::new();
+        for &(from, to, capacity) in edges {
+            adjacency.entry(from).or_default().push(FlowEdge {
+                from,
+                to,
+                capacity,
+                flow: 0.0,
+            });
+        }
+        adjacency
+    }
+
+    /// Random layered DAG with `n` nodes from a fixed LCG
+    fn generated_network(n: usize) -> HashMap<usize, Vec<FlowEdge>> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as usize
+        };
+
+        let mut edges = Vec::new();
+        for from in 0..n - 1 {
+            for _ in 0..4 {
+                let to = (from + 1 + next() % 25).min(n - 1);
+                let capacity = (1 + next() % 20) as f64;
+                edges.push((from, to, capacity));
+            }
+        }
+        adjacency(&edges)
+    }
+
+    #[test]
+    fn test_textbook_network() {
+        // CLRS figure 26.1, max flow 23
+        let adj = adjacency(&[
+            (0, 1, 16.0),
+            (0, 2, 13.0),
+            (1, 3, 12.0),
+            (2, 1, 4.0),
+            (2, 4, 14.0),
+            (3, 2, 9.0),
+            (3, 5, 20.0),
+            (4, 3, 7.0),
+            (4, 5, 4.0),
+        ]);
+        let result = ResidualGraph::from_adjacency(&adj, 6).min_cut(0, 5, false);
+
+        assert_eq!(result.cut_value, 23.0);
+        let mut cut = result.cut_edges.clone();
+        cut.sort_unstable();
+        assert_eq!(cut, vec![(1, 3), (4, 3), (4, 5)]);
+        assert_eq!(result.source_side, HashSet::from([0, 1, 2, 4]));
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let n = 500;
+        let adj = generated_network(n);
+
+        let sequential = ResidualGraph::from_adjacency(&adj, n).min_cut(0, n - 1, false);
+        let parallel = ResidualGraph::from_adjacency(&adj, n).min_cut(0, n - 1, true);
+
+        assert!(sequential.cut_value > 0.0);
+        assert_eq!(sequential.cut_value, parallel.cut_value);
+        assert_eq!(sequential.cut_edges, parallel.cut_edges);
+        assert_eq!(sequential.source_side, parallel.source_side);
+        assert_eq!(sequential.sink_side, parallel.sink_side);
+
+        // Max-flow min-cut: the cut capacity leaving the source side is the
+        // reported value.
+        let crossing: f64 = adj
+            .values()
+            .flatten()
+            .filter(|e| {
+                sequential.source_side.contains(&e.from) && !sequential.source_side.contains(&e.to)
+            })
+            .map(|e| e.capacity)
+            .sum();
+        assert_eq!(crossing, sequential.cut_value);
+    }
+}
@@ -4,6 +4,7 @@ mod bottleneck;
 mod dynamic_updates;
 mod engine;
 mod local_kcut;
+mod max_flow;
 mod redundancy;
 
 pub use bottleneck::{Bottleneck, BottleneckAnalysis};
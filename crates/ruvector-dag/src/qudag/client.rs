@@ -1,8 +1,12 @@
 //! QuDAG Network Client
 
+use super::crypto::{MlDsa65, MlDsa65PublicKey, Signature};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 #[derive(Debug, Clone)]
 pub struct QuDagConfig {
     pub endpoint: String,
@@ -63,6 +67,28 @@ impl QuDagClient {
         &self.node_id
     }
 
+    /// Verify a batch of ML-DSA-65 signatures, one result per item.
+    ///
+    /// ML-DSA has no aggregate verification, so every signature is checked
+    /// on its own exactly as [`MlDsa65::verify`] would; the batch only
+    /// spreads the work across threads (with the `rayon` feature). An item
+    /// is `false` if its signature is invalid or cannot be parsed, and one
+    /// bad item never affects the others.
+    pub fn verify_batch(&self, items: &[(MlDsa65PublicKey, &[u8], &Signature)]) -> Vec<bool> {
+        let verify_one = |(pk, message, signature): &(MlDsa65PublicKey, &[u8], &Signature)| {
+            MlDsa65::verify(pk, message, signature).unwrap_or(false)
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            items.par_iter().map(verify_one).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            items.iter().map(verify_one).collect()
+        }
+    }
+
     pub async fn propose_pattern(
         &self,
         _pattern: super::proposal::PatternProposal,
@@ -145,3 +171,60 @@ pub enum QuDagError {
     #[error("Timeout")]
     Timeout,
 }
+
+// The test keys are built for the placeholder signature scheme.
+#[cfg(all(test, not(feature = "production-crypto")))]
+mod tests {
+    use super::*;
+    use crate::qudag::crypto::{
+        MlDsa65SecretKey, ML_DSA_65_PUBLIC_KEY_SIZE, ML_DSA_65_SECRET_KEY_SIZE,
+    };
+
+    /// Deterministic placeholder keypair whose public key matches the key
+    /// hash `sign` embeds
+    fn keypair(seed: u8) -> (MlDsa65PublicKey, MlDsa65SecretKey) {
+        let mut sk = [0u8; ML_DSA_65_SECRET_KEY_SIZE];
+        for (i, b) in sk.iter_mut().enumerate() {
+            *b = seed.wrapping_mul(31).wrapping_add(i as u8);
+        }
+        let mut pk = [seed; ML_DSA_65_PUBLIC_KEY_SIZE];
+        pk[..32].copy_from_slice(&sk[32..64]);
+        (MlDsa65PublicKey(pk), MlDsa65SecretKey(sk))
+    }
+
+    #[test]
+    fn test_verify_batch_mixed() {
+        let client = QuDagClient::new(QuDagConfig::default());
+        let keys: Vec<_> = (1..=3).map(keypair).collect();
+        let messages: Vec<Vec<u8>> = (0..6)
+            .map(|i| format!("pattern update {}", i).into_bytes())
+            .collect();
+        let mut signatures: Vec<Signature> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| MlDsa65::sign(&keys[i % 3].1, m).unwrap())
+            .collect();
+
+        // Tamper with two signatures and check one against the wrong key.
+        signatures[1].0[40] ^= 0xff;
+        signatures[4].0[..32].fill(0);
+        let items: Vec<(MlDsa65PublicKey, &[u8], &Signature)> = messages
+            .iter()
+            .zip(&signatures)
+            .enumerate()
+            .map(|(i, (m, s))| {
+                let signer = if i == 3 { 1 } else { i % 3 };
+                (keys[signer].0.clone(), m.as_slice(), s)
+            })
+            .collect();
+
+        let results = client.verify_batch(&items);
+        assert_eq!(results, vec![true, false, true, false, false, true]);
+
+        // Same answers as verifying one at a time.
+        for ((pk, message, signature), &ok) in items.iter().zip(&results) {
+            assert_eq!(MlDsa65::verify(pk, message, signature).unwrap(), ok);
+        }
+        assert!(client.verify_batch(&[]).is_empty());
+    }
+}
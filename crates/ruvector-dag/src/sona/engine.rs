@@ -6,16 +6,39 @@ use super::{
 };
 use crate::dag::{OperatorType, QueryDag};
 use ndarray::Array1;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+
+/// Format version written by [`DagSonaEngine::save_checkpoint`]
+const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct CheckpointRef<'a> {
+    version: u32,
+    embedding_dim: usize,
+    micro_lora: &'a MicroLoRA,
+    reasoning_bank: &'a DagReasoningBank,
+    ewc: &'a EwcPlusPlus,
+}
+
+#[derive(Deserialize)]
+struct Checkpoint {
+    version: u32,
+    embedding_dim: usize,
+    micro_lora: MicroLoRA,
+    reasoning_bank: DagReasoningBank,
+    ewc: EwcPlusPlus,
+}
 
 pub struct DagSonaEngine {
     micro_lora: MicroLoRA,
     trajectory_buffer: DagTrajectoryBuffer,
     reasoning_bank: DagReasoningBank,
-    #[allow(dead_code)]
     ewc: EwcPlusPlus,
     embedding_dim: usize,
+    record_plans: bool,
 }
 
 impl DagSonaEngine {
@@ -29,9 +52,17 @@ impl DagSonaEngine {
             }),
             ewc: EwcPlusPlus::new(EwcConfig::default()),
             embedding_dim,
+            record_plans: false,
         }
     }
 
+    /// Attach a copy of each executed plan to its trajectory in
+    /// [`post_query`](Self::post_query), as input for subplan mining.
+    /// Off by default, since every trajectory then holds a full DAG.
+    pub fn set_record_plans(&mut self, record: bool) {
+        self.record_plans = record;
+    }
+
     /// Pre-query instant adaptation (<100μs)
     pub fn pre_query(&mut self, dag: &QueryDag) -> Vec<f32> {
         let embedding = self.compute_dag_embedding(dag);
@@ -61,13 +92,16 @@ impl DagSonaEngine {
         attention_mechanism: &str,
     ) {
         let embedding = self.compute_dag_embedding(dag);
-        let trajectory = DagTrajectory::new(
+        let mut trajectory = DagTrajectory::new(
             self.hash_dag(dag),
             embedding,
             attention_mechanism.to_string(),
             execution_time_ms,
             baseline_time_ms,
         );
+        if self.record_plans {
+            trajectory = trajectory.with_plan(dag.clone());
+        }
 
         self.trajectory_buffer.push(trajectory);
     }
@@ -289,6 +323,60 @@ impl DagSonaEngine {
         hasher.finish()
     }
 
+    /// Write the learned state as JSON: MicroLoRA adapter weights,
+    /// reasoning bank patterns and clusters, and EWC++ Fisher information.
+    ///
+    /// Trajectories still waiting in the buffer for
+    /// [`background_learn`](Self::background_learn) are not included.
+    pub fn save_checkpoint(&self, writer: impl Write) -> io::Result<()> {
+        let checkpoint = CheckpointRef {
+            version: CHECKPOINT_VERSION,
+            embedding_dim: self.embedding_dim,
+            micro_lora: &self.micro_lora,
+            reasoning_bank: &self.reasoning_bank,
+            ewc: &self.ewc,
+        };
+        serde_json::to_writer(writer, &checkpoint).map_err(io::Error::from)
+    }
+
+    /// Restore an engine from a checkpoint written by
+    /// [`save_checkpoint`](Self::save_checkpoint), with an empty
+    /// trajectory buffer
+    pub fn load_checkpoint(reader: impl Read) -> io::Result<Self> {
+        let checkpoint: Checkpoint = serde_json::from_reader(reader).map_err(io::Error::from)?;
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(invalid(format!(
+                "unsupported checkpoint version {}",
+                checkpoint.version
+            )));
+        }
+        let dim = checkpoint.embedding_dim;
+        if !checkpoint.micro_lora.is_consistent() || checkpoint.micro_lora.dim() != dim {
+            return Err(invalid(format!(
+                "MicroLoRA weights do not match embedding dim {}",
+                dim
+            )));
+        }
+        if checkpoint.reasoning_bank.config().pattern_dim != dim {
+            return Err(invalid(format!(
+                "reasoning bank pattern dim {} does not match embedding dim {}",
+                checkpoint.reasoning_bank.config().pattern_dim,
+                dim
+            )));
+        }
+
+        Ok(Self {
+            micro_lora: checkpoint.micro_lora,
+            trajectory_buffer: DagTrajectoryBuffer::new(1000),
+            reasoning_bank: checkpoint.reasoning_bank,
+            ewc: checkpoint.ewc,
+            embedding_dim: dim,
+            record_plans: false,
+        })
+    }
+
     pub fn pattern_count(&self) -> usize {
         self.reasoning_bank.pattern_count()
     }
@@ -307,3 +395,94 @@ impl Default for DagSonaEngine {
         Self::new(256)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dag::OperatorNode;
+
+    fn plan(table: &str, joins: usize) -> QueryDag {
+        let mut dag = QueryDag::new();
+        let mut prev = dag.add_node(OperatorNode::seq_scan(0, table).with_estimates(100.0, 10.0));
+        for i in 0..joins {
+            let scan =
+                dag.add_node(OperatorNode::hnsw_scan(0, "idx", 32).with_estimates(50.0, 5.0));
+            let join = dag.add_node(
+                OperatorNode::hash_join(0, "id").with_estimates(80.0, 20.0 * (i + 1) as f64),
+            );
+            dag.add_edge(prev, join).unwrap();
+            dag.add_edge(scan, join).unwrap();
+            prev = join;
+        }
+        dag
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let mut engine = DagSonaEngine::new(32);
+        let plans: Vec<QueryDag> = (0..4).map(|j| plan("users", j)).collect();
+        for (i, dag) in plans.iter().enumerate() {
+            engine.pre_query(dag);
+            engine.post_query(dag, 10.0 + i as f64, 50.0, "topological");
+        }
+        engine.background_learn();
+        engine.micro_lora.adapt(&Array1::from_elem(32, 0.5), 0.1);
+        let fisher = EwcPlusPlus::compute_fisher(&[Array1::from_elem(4, 0.3)]);
+        engine.ewc.consolidate(&Array1::from_elem(4, 1.0), &fisher);
+        assert!(engine.pattern_count() > 0);
+
+        let mut bytes = Vec::new();
+        engine.save_checkpoint(&mut bytes).unwrap();
+        let mut restored = DagSonaEngine::load_checkpoint(bytes.as_slice()).unwrap();
+
+        assert_eq!(restored.pattern_count(), engine.pattern_count());
+        assert_eq!(restored.ewc.fisher_diag(), engine.ewc.fisher_diag());
+        assert_eq!(restored.ewc.task_count(), 1);
+        for dag in &plans {
+            let embedding = engine.compute_dag_embedding(dag);
+            assert_eq!(
+                restored.reasoning_bank.query_similar(&embedding, 3),
+                engine.reasoning_bank.query_similar(&embedding, 3)
+            );
+            assert_eq!(restored.pre_query(dag), engine.pre_query(dag));
+        }
+
+        // Learning continues identically after the restore.
+        for engine in [&mut engine, &mut restored] {
+            engine.post_query(&plans[3], 5.0, 50.0, "critical_path");
+            engine.background_learn();
+        }
+        assert_eq!(restored.pattern_count(), engine.pattern_count());
+        assert_eq!(restored.pre_query(&plans[2]), engine.pre_query(&plans[2]));
+    }
+
+    #[test]
+    fn test_plan_recording_is_opt_in() {
+        let mut engine = DagSonaEngine::new(16);
+        let dag = plan("users", 1);
+        engine.post_query(&dag, 10.0, 50.0, "topological");
+        assert!(engine.trajectory_buffer.drain()[0].plan.is_none());
+
+        engine.set_record_plans(true);
+        engine.post_query(&dag, 10.0, 50.0, "topological");
+        let recorded = engine.trajectory_buffer.drain();
+        assert_eq!(recorded[0].plan.as_ref().unwrap().node_count(), 3);
+    }
+
+    #[test]
+    fn test_checkpoint_rejects_mismatched_state() {
+        let engine = DagSonaEngine::new(16);
+        let mut bytes = Vec::new();
+        engine.save_checkpoint(&mut bytes).unwrap();
+
+        let mut json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        json["embedding_dim"] = 8.into();
+        let bad = serde_json::to_vec(&json).unwrap();
+        let err = DagSonaEngine::load_checkpoint(bad.as_slice())
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        assert!(DagSonaEngine::load_checkpoint(&b"{"[..]).is_err());
+    }
+}
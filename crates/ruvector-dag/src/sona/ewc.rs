@@ -1,8 +1,9 @@
 //! EWC++: Elastic Weight Consolidation to prevent forgetting
 
 use ndarray::Array1;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EwcConfig {
     pub lambda: f32,  // Importance weight (2000-15000)
     pub decay: f32,   // Fisher decay rate
@@ -19,6 +20,7 @@ impl Default for EwcConfig {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct EwcPlusPlus {
     config: EwcConfig,
     fisher_diag: Option<Array1<f32>>,
@@ -90,6 +92,11 @@ impl EwcPlusPlus {
         fisher / gradients.len() as f32
     }
 
+    /// Accumulated diagonal Fisher information, once consolidated
+    pub fn fisher_diag(&self) -> Option<&Array1<f32>> {
+        self.fisher_diag.as_ref()
+    }
+
     pub fn has_prior(&self) -> bool {
         self.fisher_diag.is_some()
     }
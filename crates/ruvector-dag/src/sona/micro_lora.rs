@@ -1,8 +1,9 @@
 //! MicroLoRA: Ultra-fast per-query adaptation
 
 use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MicroLoRAConfig {
     pub rank: usize,  // 1-2 for micro
     pub alpha: f32,   // Scaling factor
@@ -19,6 +20,7 @@ impl Default for MicroLoRAConfig {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct MicroLoRA {
     config: MicroLoRAConfig,
     a_matrix: Array2<f32>, // (in_dim, rank)
@@ -73,6 +75,17 @@ impl MicroLoRA {
         self.b_matrix.fill(0.0);
     }
 
+    /// Input/output dimension
+    pub fn dim(&self) -> usize {
+        self.in_dim
+    }
+
+    /// Whether the matrix shapes agree with the configured rank and dims
+    pub(crate) fn is_consistent(&self) -> bool {
+        self.a_matrix.dim() == (self.in_dim, self.config.rank)
+            && self.b_matrix.dim() == (self.config.rank, self.out_dim)
+    }
+
     /// Get parameter count
     pub fn param_count(&self) -> usize {
         self.a_matrix.len() + self.b_matrix.len()
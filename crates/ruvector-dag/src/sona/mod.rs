@@ -4,10 +4,12 @@ mod engine;
 mod ewc;
 mod micro_lora;
 mod reasoning_bank;
+mod subplan_mining;
 mod trajectory;
 
 pub use engine::DagSonaEngine;
 pub use ewc::{EwcConfig, EwcPlusPlus};
 pub use micro_lora::{MicroLoRA, MicroLoRAConfig};
 pub use reasoning_bank::{DagPattern, DagReasoningBank, ReasoningBankConfig};
+pub use subplan_mining::{mine_frequent_subgraphs, FrequentSubplan, MAX_SUBPLAN_NODES};
 pub use trajectory::{DagTrajectory, DagTrajectoryBuffer};
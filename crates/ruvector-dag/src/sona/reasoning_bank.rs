@@ -1,8 +1,9 @@
 //! Reasoning Bank: K-means++ clustering for pattern storage
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DagPattern {
     pub id: u64,
     pub vector: Vec<f32>,
@@ -11,7 +12,7 @@ pub struct DagPattern {
     pub metadata: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReasoningBankConfig {
     pub num_clusters: usize,
     pub pattern_dim: usize,
@@ -30,6 +31,7 @@ impl Default for ReasoningBankConfig {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct DagReasoningBank {
     config: ReasoningBankConfig,
     patterns: Vec<DagPattern>,
@@ -172,6 +174,10 @@ impl DagReasoningBank {
         }
     }
 
+    pub fn config(&self) -> &ReasoningBankConfig {
+        &self.config
+    }
+
     pub fn pattern_count(&self) -> usize {
         self.patterns.len()
     }
@@ -0,0 +1,209 @@
+//! Subplan Mining: frequent operator subgraphs across trajectories
+//!
+//! Finds connected operator-type subgraphs that recur in the plans of many
+//! trajectories. Operators are compared by kind only (a `Filter` matches any
+//! other `Filter` regardless of predicate), so the mined subplans can seed
+//! reusable plan templates. Subgraphs are bounded to [`MAX_SUBPLAN_NODES`]
+//! nodes to keep enumeration tractable.
+
+use super::DagTrajectory;
+use crate::dag::{OperatorType, QueryDag};
+use std::collections::{HashMap, HashSet};
+
+/// Largest subplan (in operators) considered by the miner
+pub const MAX_SUBPLAN_NODES: usize = 4;
+
+/// Canonical form of a subplan: operator kinds plus parent -> child edges
+type SubplanCode = (Vec<&'static str>, Vec<(usize, usize)>);
+
+/// A connected subplan that recurs across trajectories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrequentSubplan {
+    /// Operator kinds, in canonical order
+    pub operators: Vec<&'static str>,
+    /// Parent -> child edges, as indices into `operators`
+    pub edges: Vec<(usize, usize)>,
+    /// Number of trajectories whose plan contains this subplan
+    pub support: usize,
+}
+
+impl FrequentSubplan {
+    /// Number of operators in the subplan
+    pub fn size(&self) -> usize {
+        self.operators.len()
+    }
+
+    /// Whether the subplan contains an edge `parent -> child` between the
+    /// given operator kinds
+    pub fn has_edge(&self, parent: &str, child: &str) -> bool {
+        self.edges
+            .iter()
+            .any(|&(p, c)| self.operators[p] == parent && self.operators[c] == child)
+    }
+}
+
+/// Mine connected subplans of 2 to [`MAX_SUBPLAN_NODES`] operators that appear
+/// in at least `min_support` trajectories.
+///
+/// Trajectories without a recorded plan are ignored. Results are ordered by
+/// support (descending), then size (descending).
+pub fn mine_frequent_subgraphs(
+    trajectories: &[DagTrajectory],
+    min_support: usize,
+) -> Vec<FrequentSubplan> {
+    let mut support: HashMap<SubplanCode, usize> = HashMap::new();
+
+    for plan in trajectories.iter().filter_map(|t| t.plan.as_ref()) {
+        // Count each subplan once per trajectory
+        let codes: HashSet<SubplanCode> = connected_subsets(plan, MAX_SUBPLAN_NODES)
+            .iter()
+            .map(|nodes| canonical_code(plan, nodes))
+            .collect();
+        for code in codes {
+            *support.entry(code).or_insert(0) += 1;
+        }
+    }
+
+    let mut mined: Vec<FrequentSubplan> = support
+        .into_iter()
+        .filter(|&(_, count)| count >= min_support.max(1))
+        .map(|((operators, edges), support)| FrequentSubplan {
+            operators,
+            edges,
+            support,
+        })
+        .collect();
+
+    mined.sort_by(|a, b| {
+        b.support
+            .cmp(&a.support)
+            .then(b.size().cmp(&a.size()))
+            .then_with(|| (&a.operators, &a.edges).cmp(&(&b.operators, &b.edges)))
+    });
+    mined
+}
+
+/// Enumerate connected node sets of size 2..=max_nodes (as sorted id lists)
+fn connected_subsets(dag: &QueryDag, max_nodes: usize) -> HashSet<Vec<usize>> {
+    let mut seen: HashSet<Vec<usize>> = HashSet::new();
+    let mut frontier: Vec<Vec<usize>> = dag.node_ids().map(|id| vec![id]).collect();
+
+    for _ in 1..max_nodes {
+        let mut next = Vec::new();
+        for set in &frontier {
+            for &member in set {
+                let neighbours = dag.children(member).iter().chain(dag.parents(member));
+                for &n in neighbours {
+                    if set.contains(&n) {
+                        continue;
+                    }
+                    let mut grown = set.clone();
+                    grown.push(n);
+                    grown.sort_unstable();
+                    if seen.insert(grown.clone()) {
+                        next.push(grown);
+                    }
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    seen
+}
+
+/// Canonical code of the subgraph induced by `nodes`.
+///
+/// Nodes are ordered by operator kind; ties are broken by trying every
+/// ordering of equal kinds and keeping the smallest edge list. Exact for the
+/// small subgraphs enumerated here.
+fn canonical_code(dag: &QueryDag, nodes: &[usize]) -> SubplanCode {
+    let labels: Vec<&'static str> = nodes
+        .iter()
+        .map(|&id| {
+            dag.get_node(id)
+                .map_or("Unknown", |n| operator_kind(&n.op_type))
+        })
+        .collect();
+    let edges: Vec<(usize, usize)> = nodes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &id)| {
+            dag.children(id)
+                .iter()
+                .filter_map(|c| nodes.iter().position(|n| n == c))
+                .map(move |j| (i, j))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..nodes.len()).collect();
+    order.sort_by_key(|&i| labels[i]);
+    let sorted_labels: Vec<&'static str> = order.iter().map(|&i| labels[i]).collect();
+
+    let mut best: Option<Vec<(usize, usize)>> = None;
+    for_each_label_preserving_order(&mut order, &labels, 0, &mut |order| {
+        let mut position = vec![0; order.len()];
+        for (pos, &i) in order.iter().enumerate() {
+            position[i] = pos;
+        }
+        let mut mapped: Vec<(usize, usize)> = edges
+            .iter()
+            .map(|&(p, c)| (position[p], position[c]))
+            .collect();
+        mapped.sort_unstable();
+        if best.as_ref().map_or(true, |b| mapped < *b) {
+            best = Some(mapped);
+        }
+    });
+
+    (sorted_labels, best.unwrap_or_default())
+}
+
+/// Visit every permutation of `order` that keeps `labels` sorted
+fn for_each_label_preserving_order(
+    order: &mut [usize],
+    labels: &[&'static str],
+    start: usize,
+    visit: &mut impl FnMut(&[usize]),
+) {
+    if start >= order.len() {
+        visit(order);
+        return;
+    }
+    for i in start..order.len() {
+        if labels[order[i]] != labels[order[start]] {
+            break;
+        }
+        order.swap(start, i);
+        for_each_label_preserving_order(order, labels, start + 1, visit);
+        order.swap(start, i);
+    }
+}
+
+/// Operator kind, ignoring parameters
+fn operator_kind(op: &OperatorType) -> &'static str {
+    match op {
+        OperatorType::SeqScan { .. } => "SeqScan",
+        OperatorType::IndexScan { .. } => "IndexScan",
+        OperatorType::HnswScan { .. } => "HnswScan",
+        OperatorType::IvfFlatScan { .. } => "IvfFlatScan",
+        OperatorType::NestedLoopJoin => "NestedLoopJoin",
+        OperatorType::HashJoin { .. } => "HashJoin",
+        OperatorType::MergeJoin { .. } => "MergeJoin",
+        OperatorType::Aggregate { .. } => "Aggregate",
+        OperatorType::GroupBy { .. } => "GroupBy",
+        OperatorType::Filter { .. } => "Filter",
+        OperatorType::Project { .. } => "Project",
+        OperatorType::Sort { .. } => "Sort",
+        OperatorType::Limit { .. } => "Limit",
+        OperatorType::VectorDistance { .. } => "VectorDistance",
+        OperatorType::Rerank { .. } => "Rerank",
+        OperatorType::Materialize => "Materialize",
+        OperatorType::Result => "Result",
+        #[allow(deprecated)]
+        OperatorType::Scan => "SeqScan",
+        #[allow(deprecated)]
+        OperatorType::Join => "NestedLoopJoin",
+    }
+}
@@ -1,5 +1,6 @@
 //! Trajectory Buffer: Lock-free buffer for learning trajectories
 
+use crate::dag::QueryDag;
 use crossbeam::queue::ArrayQueue;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -12,6 +13,8 @@ pub struct DagTrajectory {
     pub execution_time_ms: f64,
     pub improvement_ratio: f32,
     pub timestamp: std::time::Instant,
+    /// The executed plan, when recorded (used for subplan mining)
+    pub plan: Option<QueryDag>,
 }
 
 impl DagTrajectory {
@@ -35,9 +38,16 @@ impl DagTrajectory {
             execution_time_ms,
             improvement_ratio,
             timestamp: std::time::Instant::now(),
+            plan: None,
         }
     }
 
+    /// Attach the executed plan
+    pub fn with_plan(mut self, plan: QueryDag) -> Self {
+        self.plan = Some(plan);
+        self
+    }
+
     /// Compute quality score (0-1)
     pub fn quality(&self) -> f32 {
         // Quality based on improvement and execution time
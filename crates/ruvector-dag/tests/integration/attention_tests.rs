@@ -41,6 +41,50 @@ fn test_topological_attention() {
     assert!(scores.values().all(|&s| s >= 0.0 && s <= 1.0));
 }
 
+#[test]
+fn test_degree_centrality_attention() {
+    let dag = create_test_dag();
+    let attention = DegreeCentralityAttention::new(DegreeCentralityConfig::default());
+
+    let scores = DagAttention::forward(&attention, &dag).unwrap();
+
+    // Verify normalization
+    let sum: f32 = scores.values().sum();
+    assert!(
+        (sum - 1.0).abs() < 0.001,
+        "Attention scores should sum to 1.0"
+    );
+
+    // Verify all scores in [0, 1]
+    assert!(scores.values().all(|&s| (0.0..=1.0).contains(&s)));
+
+    // Interior nodes of the chain have degree 2, the ends degree 1
+    assert!(scores[&2] > scores[&0]);
+    assert!((scores[&0] - scores[&4]).abs() < 1e-6);
+}
+
+#[test]
+fn test_degree_centrality_empty_dag() {
+    let dag = QueryDag::new();
+    let attention = DegreeCentralityAttention::with_defaults();
+
+    // Empty DAG returns error, like TopologicalAttention
+    assert!(DagAttention::forward(&attention, &dag).is_err());
+}
+
+#[test]
+fn test_degree_centrality_single_node() {
+    let mut dag = QueryDag::new();
+    dag.add_node(OperatorNode::new(0, OperatorType::Result));
+
+    let attention = DegreeCentralityAttention::with_defaults();
+    let scores = DagAttention::forward(&attention, &dag).unwrap();
+
+    // Single node should get score of 1.0
+    assert_eq!(scores.len(), 1);
+    assert!((scores[&0] - 1.0).abs() < 0.001);
+}
+
 // Mock mechanism for testing selector with DagAttentionMechanism trait
 struct MockMechanism {
     name: &'static str,
@@ -112,6 +156,7 @@ fn test_attention_decay_factor() {
     let config_low = TopologicalConfig {
         decay_factor: 0.5,
         max_depth: 10,
+        use_edge_weights: false,
     };
     let attention_low = TopologicalAttention::new(config_low);
     let scores_low = attention_low.forward(&dag).unwrap();
@@ -120,6 +165,7 @@ fn test_attention_decay_factor() {
     let config_high = TopologicalConfig {
         decay_factor: 0.99,
         max_depth: 10,
+        use_edge_weights: false,
     };
     let attention_high = TopologicalAttention::new(config_high);
     let scores_high = attention_high.forward(&dag).unwrap();
@@ -209,3 +255,25 @@ fn test_multi_mechanism_selector() {
 
     assert!(used.len() >= 1, "At least one mechanism should be selected");
 }
+
+#[test]
+fn test_selector_with_degree_centrality() {
+    let mechanisms: Vec<Box<dyn DagAttentionMechanism>> = vec![
+        Box::new(DegreeCentralityAttention::with_defaults()),
+        Box::new(MockMechanism {
+            name: "mock",
+            score_value: 0.5,
+        }),
+    ];
+    let mut selector = AttentionSelector::new(mechanisms, SelectorConfig::default());
+    let dag = create_test_dag();
+
+    let mut used = std::collections::HashSet::new();
+    for _ in 0..20 {
+        let (scores, idx) = selector.forward(&dag).unwrap();
+        assert_eq!(scores.scores.len(), dag.node_count());
+        used.insert(idx);
+        selector.update(idx, 0.5);
+    }
+    assert!(used.contains(&0));
+}
@@ -1,6 +1,6 @@
 //! DAG integration tests
 
-use ruvector_dag::dag::{OperatorNode, OperatorType, QueryDag};
+use ruvector_dag::dag::{CostModel, HeuristicCostModel, OperatorNode, OperatorType, QueryDag};
 
 #[test]
 fn test_complex_query_dag() {
@@ -40,6 +40,52 @@ fn test_complex_query_dag() {
     assert!(scan2_pos < join_pos);
 }
 
+#[test]
+fn test_cost_estimates_follow_upstream_cardinality() {
+    let build = |ef_search| {
+        let mut dag = QueryDag::new();
+        let users = dag.add_node(OperatorNode::seq_scan(0, "users"));
+        let vectors = dag.add_node(OperatorNode::hnsw_scan(1, "vectors_idx", ef_search));
+        let join = dag.add_node(OperatorNode::hash_join(2, "user_id"));
+        dag.add_edge(users, join).unwrap();
+        dag.add_edge(vectors, join).unwrap();
+        let filter = dag.add_node(OperatorNode::filter(3, "score > 0.5"));
+        dag.add_edge(join, filter).unwrap();
+        let result = dag.add_node(OperatorNode::new(4, OperatorType::Result));
+        dag.add_edge(filter, result).unwrap();
+        (dag, vectors, join, filter, result)
+    };
+    let model = HeuristicCostModel::new()
+        .with_relation_rows("users", 500.0)
+        .with_relation_rows("vectors_idx", 100_000.0);
+
+    let (dag, vectors, join, filter, result) = build(64);
+    let est = dag.estimate_costs(&model).unwrap();
+
+    // Each downstream estimate is what the model gives for its inputs.
+    let scans = [est[&0], est[&vectors]];
+    assert_eq!(
+        est[&join],
+        model.estimate(dag.get_node(join).unwrap(), &scans)
+    );
+    assert_eq!(
+        est[&filter],
+        model.estimate(dag.get_node(filter).unwrap(), &[est[&join]])
+    );
+    assert_eq!(est[&result].rows, est[&filter].rows);
+    assert!(est[&result].cost >= est[&filter].cost);
+    assert!(est[&filter].rows < est[&join].rows);
+
+    // A wider HNSW search yields more rows and cost all the way down.
+    let (wide_dag, _, _, _, _) = build(256);
+    let wide = wide_dag.estimate_costs(&model).unwrap();
+    assert_eq!(wide[&vectors].rows, 4.0 * est[&vectors].rows);
+    for node in [join, filter, result] {
+        assert!((wide[&node].rows - 4.0 * est[&node].rows).abs() < 1e-6);
+        assert!(wide[&node].cost > est[&node].cost);
+    }
+}
+
 #[test]
 fn test_dag_depths() {
     let mut dag = QueryDag::new();
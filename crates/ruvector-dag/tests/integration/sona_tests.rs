@@ -234,3 +234,75 @@ fn test_reasoning_bank_cluster_count() {
     // Should have created clusters
     assert!(bank.cluster_count() <= 4);
 }
+
+fn motif_plan(i: usize, extra_sort: bool) -> QueryDag {
+    // scan -> filter -> join <- index scan, join -> result
+    let mut dag = QueryDag::new();
+    let scan = dag.add_node(OperatorNode::seq_scan(0, &format!("t{}", i)));
+    let filter = dag.add_node(OperatorNode::filter(0, &format!("x > {}", i)));
+    let probe = dag.add_node(OperatorNode::index_scan(0, "idx", "dim"));
+    let join = dag.add_node(OperatorNode::hash_join(0, "id"));
+    let result = dag.add_node(OperatorNode::result(0));
+    dag.add_edge(scan, filter).unwrap();
+    dag.add_edge(filter, join).unwrap();
+    dag.add_edge(probe, join).unwrap();
+    if extra_sort {
+        let sort = dag.add_node(OperatorNode::sort(0, vec!["id".to_string()]));
+        let limit = dag.add_node(OperatorNode::limit(0, 10));
+        dag.add_edge(join, sort).unwrap();
+        dag.add_edge(sort, limit).unwrap();
+        dag.add_edge(limit, result).unwrap();
+    } else {
+        dag.add_edge(join, result).unwrap();
+    }
+    dag
+}
+
+#[test]
+fn test_mine_frequent_subgraphs_finds_shared_motif() {
+    let mut trajectories: Vec<DagTrajectory> = (0..5)
+        .map(|i| {
+            DagTrajectory::new(
+                i as u64,
+                vec![0.1; 16],
+                "topological".to_string(),
+                10.0,
+                20.0,
+            )
+            .with_plan(motif_plan(i, i == 0))
+        })
+        .collect();
+    // Trajectories without a plan are ignored
+    trajectories.push(DagTrajectory::new(
+        99,
+        vec![0.1; 16],
+        "topological".to_string(),
+        10.0,
+        20.0,
+    ));
+
+    let mined = mine_frequent_subgraphs(&trajectories, 3);
+
+    let motif = mined
+        .iter()
+        .find(|s| {
+            s.size() == 3
+                && s.edges.len() == 2
+                && s.has_edge("SeqScan", "Filter")
+                && s.has_edge("Filter", "HashJoin")
+        })
+        .expect("scan -> filter -> join motif should be mined");
+    assert_eq!(motif.support, 5);
+
+    // Sort -> Limit only appears in one plan
+    assert!(mined.iter().all(|s| !s.has_edge("Sort", "Limit")));
+    assert!(mined.iter().all(|s| s.support >= 3));
+    assert!(mined.iter().all(|s| s.size() <= MAX_SUBPLAN_NODES));
+
+    let all = mine_frequent_subgraphs(&trajectories, 1);
+    let rare = all
+        .iter()
+        .find(|s| s.size() == 2 && s.has_edge("Sort", "Limit"))
+        .expect("rare subplan is mined at support 1");
+    assert_eq!(rare.support, 1);
+}
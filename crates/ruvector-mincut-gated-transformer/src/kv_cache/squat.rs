@@ -153,11 +153,10 @@ impl SQuatQuantizer {
             for i in 0..size {
                 for j in 0..size {
                     let val = basis[i * dim + j];
-                    // Top-left: H
+                    // Top-left: H (already in place)
                     // Top-right: H
                     // Bottom-left: H
                     // Bottom-right: -H
-                    basis[i * dim + j] = val;
                     basis[i * dim + (j + size)] = val;
                     basis[(i + size) * dim + j] = val;
                     basis[(i + size) * dim + (j + size)] = -val;
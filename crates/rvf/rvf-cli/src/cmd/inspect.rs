@@ -38,6 +38,8 @@ fn segment_type_name(seg_type: u8) -> &'static str {
         t if t == SegmentType::Refcount as u8 => "Refcount",
         t if t == SegmentType::Membership as u8 => "Membership",
         t if t == SegmentType::Delta as u8 => "Delta",
+        t if t == SegmentType::CompressionDict as u8 => "CompressionDict",
+        t if t == SegmentType::Settings as u8 => "Settings",
         _ => "Unknown",
     }
 }
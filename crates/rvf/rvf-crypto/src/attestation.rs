@@ -434,6 +434,193 @@ pub trait QuoteVerifier {
     ) -> Result<bool, RvfError>;
 }
 
+// ---------------------------------------------------------------------------
+// 7. AMD SEV-SNP Attestation Reports
+// ---------------------------------------------------------------------------
+
+/// Size of an SEV-SNP `ATTESTATION_REPORT` structure (SNP ABI spec, Table 22).
+pub const SNP_REPORT_SIZE: usize = 0x4A0;
+
+/// Lowest supported SNP report version.
+pub const SNP_REPORT_MIN_VERSION: u32 = 2;
+
+/// Highest supported SNP report version.
+pub const SNP_REPORT_MAX_VERSION: u32 = 3;
+
+/// Guest policy bit allowing the guest to be debugged.
+const SNP_POLICY_DEBUG: u64 = 1 << 19;
+
+/// Fields of an SEV-SNP attestation report relevant to RVF.
+///
+/// ```text
+/// Offset  Type        Field
+/// 0x000   u32         version
+/// 0x004   u32         guest_svn
+/// 0x008   u64         policy
+/// 0x030   u32         vmpl
+/// 0x050   [u8; 64]    report_data
+/// 0x090   [u8; 48]    measurement (launch digest)
+/// 0x0C0   [u8; 32]    host_data
+/// 0x0E0   [u8; 48]    id_key_digest
+/// 0x110   [u8; 48]    author_key_digest
+/// 0x180   u64         reported_tcb
+/// 0x1A0   [u8; 64]    chip_id
+/// 0x2A0   [u8; 512]   signature (ECDSA P-384, not checked here)
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnpReport {
+    /// Report format version.
+    pub version: u32,
+    /// Guest security version number.
+    pub guest_svn: u32,
+    /// Guest policy.
+    pub policy: u64,
+    /// VM privilege level that requested the report.
+    pub vmpl: u32,
+    /// Guest-supplied data bound into the report.
+    pub report_data: [u8; 64],
+    /// Launch measurement (SHA-384 launch digest).
+    pub measurement: [u8; 48],
+    /// Host-supplied data.
+    pub host_data: [u8; 32],
+    /// SHA-384 digest of the ID public key.
+    pub id_key_digest: [u8; 48],
+    /// SHA-384 digest of the author public key.
+    pub author_key_digest: [u8; 48],
+    /// TCB version reported to the guest.
+    pub reported_tcb: u64,
+    /// Unique chip identifier.
+    pub chip_id: [u8; 64],
+}
+
+impl SnpReport {
+    /// Returns `true` if the guest policy allows debugging.
+    pub fn is_debuggable(&self) -> bool {
+        self.policy & SNP_POLICY_DEBUG != 0
+    }
+
+    /// Build an `AttestationHeader` describing this report.
+    ///
+    /// The 48-byte launch measurement and ID key digest are truncated to
+    /// the header's 32-byte `measurement` and `signer_id` fields; use
+    /// [`SnpReport::measurement`] when the full digest is needed. The
+    /// record is expected to carry the 64-byte `report_data` followed by
+    /// the raw report as the quote.
+    pub fn to_attestation_header(&self, timestamp_ns: u64) -> AttestationHeader {
+        let mut header = AttestationHeader::new(
+            TeePlatform::SevSnp as u8,
+            AttestationWitnessType::SnpReport as u8,
+        );
+        header.quote_length = SNP_REPORT_SIZE as u16;
+        header.measurement.copy_from_slice(&self.measurement[..32]);
+        header.signer_id.copy_from_slice(&self.id_key_digest[..32]);
+        header.timestamp_ns = timestamp_ns;
+        header.nonce.copy_from_slice(&self.report_data[..16]);
+        header.svn = self.guest_svn.min(u16::MAX as u32) as u16;
+        header.flags = AttestationHeader::FLAG_HAS_REPORT_DATA;
+        if self.is_debuggable() {
+            header.flags |= AttestationHeader::FLAG_DEBUGGABLE;
+        }
+        header.report_data_len = self.report_data.len() as u64;
+        header
+    }
+}
+
+/// Parse an SEV-SNP attestation report.
+///
+/// Returns `ErrorCode::TruncatedSegment` if `data` is shorter than
+/// [`SNP_REPORT_SIZE`] and `ErrorCode::InvalidVersion` if the report
+/// version is outside the supported range.
+pub fn parse_snp_report(data: &[u8]) -> Result<SnpReport, RvfError> {
+    if data.len() < SNP_REPORT_SIZE {
+        return Err(RvfError::Code(ErrorCode::TruncatedSegment));
+    }
+    let u32_at = |o: usize| u32::from_le_bytes(data[o..o + 4].try_into().unwrap());
+    let u64_at = |o: usize| u64::from_le_bytes(data[o..o + 8].try_into().unwrap());
+
+    let version = u32_at(0x000);
+    if !(SNP_REPORT_MIN_VERSION..=SNP_REPORT_MAX_VERSION).contains(&version) {
+        return Err(RvfError::Code(ErrorCode::InvalidVersion));
+    }
+
+    let mut report_data = [0u8; 64];
+    report_data.copy_from_slice(&data[0x050..0x090]);
+    let mut measurement = [0u8; 48];
+    measurement.copy_from_slice(&data[0x090..0x0C0]);
+    let mut host_data = [0u8; 32];
+    host_data.copy_from_slice(&data[0x0C0..0x0E0]);
+    let mut id_key_digest = [0u8; 48];
+    id_key_digest.copy_from_slice(&data[0x0E0..0x110]);
+    let mut author_key_digest = [0u8; 48];
+    author_key_digest.copy_from_slice(&data[0x110..0x140]);
+    let mut chip_id = [0u8; 64];
+    chip_id.copy_from_slice(&data[0x1A0..0x1E0]);
+
+    Ok(SnpReport {
+        version,
+        guest_svn: u32_at(0x004),
+        policy: u64_at(0x008),
+        vmpl: u32_at(0x030),
+        report_data,
+        measurement,
+        host_data,
+        id_key_digest,
+        author_key_digest,
+        reported_tcb: u64_at(0x180),
+        chip_id,
+    })
+}
+
+/// Parse an SEV-SNP report and check its launch measurement.
+///
+/// Returns the parsed report on success, or `ErrorCode::AttestationInvalid`
+/// if the measurement differs from `expected_measurement`. The report
+/// signature is not verified; that requires the platform's VCEK
+/// certificate chain.
+pub fn verify_snp_report(
+    data: &[u8],
+    expected_measurement: &[u8; 48],
+) -> Result<SnpReport, RvfError> {
+    let report = parse_snp_report(data)?;
+    if report.measurement != *expected_measurement {
+        return Err(RvfError::Code(ErrorCode::AttestationInvalid));
+    }
+    Ok(report)
+}
+
+/// [`QuoteVerifier`] for SEV-SNP records whose quote is a raw report.
+///
+/// Checks the report version, that the header's measurement matches the
+/// report's launch measurement, and that the record's report data matches
+/// the data bound into the report. The report signature is not verified.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SnpQuoteVerifier;
+
+impl QuoteVerifier for SnpQuoteVerifier {
+    fn platform(&self) -> TeePlatform {
+        TeePlatform::SevSnp
+    }
+
+    fn verify_quote(
+        &self,
+        header: &AttestationHeader,
+        report_data: &[u8],
+        quote: &[u8],
+    ) -> Result<bool, RvfError> {
+        if header.platform != TeePlatform::SevSnp as u8 {
+            return Err(RvfError::Code(ErrorCode::PlatformUnsupported));
+        }
+        let report = parse_snp_report(quote)?;
+        if header.measurement[..] != report.measurement[..32] {
+            return Ok(false);
+        }
+        if !report_data.is_empty() && report_data != report.report_data {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -836,4 +1023,90 @@ mod tests {
         );
         assert!(result.is_ok());
     }
+
+    // -----------------------------------------------------------------------
+    // 17. snp_report_fixture
+    // -----------------------------------------------------------------------
+
+    /// Version-2 SNP report with the ABI-spec layout and known field values.
+    const SNP_REPORT_FIXTURE: &[u8] = include_bytes!("../tests/fixtures/snp_report_v2.bin");
+
+    /// SHA-384 launch digest stored in the fixture.
+    const SNP_FIXTURE_MEASUREMENT: [u8; 48] = [
+        0x9f, 0x60, 0x7e, 0x42, 0xa1, 0x28, 0xf6, 0x19, 0xc5, 0xf9, 0x19, 0xa3, 0x2b, 0x3c, 0x79,
+        0x57, 0xf1, 0x57, 0x52, 0x41, 0x2d, 0x08, 0x0e, 0xba, 0x86, 0x69, 0x03, 0xed, 0xad, 0x6c,
+        0x6c, 0xf9, 0x44, 0x0b, 0x5f, 0xf4, 0x70, 0x0d, 0x13, 0x9d, 0xd3, 0x26, 0xef, 0x0a, 0xb8,
+        0x6d, 0x19, 0xfa,
+    ];
+
+    #[test]
+    fn snp_report_measurement_extraction() {
+        assert_eq!(SNP_REPORT_FIXTURE.len(), SNP_REPORT_SIZE);
+        let report = parse_snp_report(SNP_REPORT_FIXTURE).unwrap();
+        assert_eq!(report.version, 2);
+        assert_eq!(report.guest_svn, 3);
+        assert_eq!(report.vmpl, 0);
+        assert!(!report.is_debuggable());
+        assert_eq!(report.measurement, SNP_FIXTURE_MEASUREMENT);
+
+        let verified = verify_snp_report(SNP_REPORT_FIXTURE, &SNP_FIXTURE_MEASUREMENT).unwrap();
+        assert_eq!(verified, report);
+
+        let mut wrong = SNP_FIXTURE_MEASUREMENT;
+        wrong[47] ^= 0x01;
+        assert_eq!(
+            verify_snp_report(SNP_REPORT_FIXTURE, &wrong),
+            Err(RvfError::Code(ErrorCode::AttestationInvalid))
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // 18. snp_report_bad_version_and_truncated
+    // -----------------------------------------------------------------------
+    #[test]
+    fn snp_report_bad_version_and_truncated() {
+        let mut data = SNP_REPORT_FIXTURE.to_vec();
+        data[0..4].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(
+            parse_snp_report(&data),
+            Err(RvfError::Code(ErrorCode::InvalidVersion))
+        );
+        assert_eq!(
+            parse_snp_report(&SNP_REPORT_FIXTURE[..SNP_REPORT_SIZE - 1]),
+            Err(RvfError::Code(ErrorCode::TruncatedSegment))
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // 19. snp_record_round_trip_and_quote_verifier
+    // -----------------------------------------------------------------------
+    #[test]
+    fn snp_record_round_trip_and_quote_verifier() {
+        let report = parse_snp_report(SNP_REPORT_FIXTURE).unwrap();
+        let header = report.to_attestation_header(1_700_000_000_000_000_000);
+        assert_eq!(header.platform, TeePlatform::SevSnp as u8);
+        assert_eq!(
+            header.attestation_type,
+            AttestationWitnessType::SnpReport as u8
+        );
+        assert_eq!(header.measurement[..], SNP_FIXTURE_MEASUREMENT[..32]);
+
+        let record = encode_attestation_record(&header, &report.report_data, SNP_REPORT_FIXTURE);
+        let (dec_header, dec_rd, dec_quote) = decode_attestation_record(&record).unwrap();
+        assert_eq!(dec_header.measurement, header.measurement);
+
+        let verifier = SnpQuoteVerifier;
+        assert_eq!(verifier.platform(), TeePlatform::SevSnp);
+        assert_eq!(
+            verifier.verify_quote(&dec_header, &dec_rd, &dec_quote),
+            Ok(true)
+        );
+
+        let mut tampered = dec_header;
+        tampered.measurement[0] ^= 0xFF;
+        assert_eq!(
+            verifier.verify_quote(&tampered, &dec_rd, &dec_quote),
+            Ok(false)
+        );
+    }
 }
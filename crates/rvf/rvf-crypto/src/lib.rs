@@ -18,15 +18,16 @@ pub mod witness;
 pub use attestation::{
     attestation_witness_entry, build_attestation_witness_payload, decode_attestation_header,
     decode_attestation_record, decode_tee_bound_key, encode_attestation_header,
-    encode_attestation_record, encode_tee_bound_key, verify_attestation_witness_payload,
-    verify_key_binding, QuoteVerifier, TeeBoundKeyRecord, VerifiedAttestationEntry,
+    encode_attestation_record, encode_tee_bound_key, parse_snp_report,
+    verify_attestation_witness_payload, verify_key_binding, verify_snp_report, QuoteVerifier,
+    SnpQuoteVerifier, SnpReport, TeeBoundKeyRecord, VerifiedAttestationEntry, SNP_REPORT_SIZE,
 };
 pub use footer::{decode_signature_footer, encode_signature_footer};
 pub use hash::{shake256_128, shake256_256, shake256_hash};
 pub use lineage::{
-    compute_manifest_hash, lineage_record_from_bytes, lineage_record_to_bytes,
-    lineage_witness_entry, verify_lineage_chain,
+    compute_manifest_hash, lineage_merge_witness_entry, lineage_record_from_bytes,
+    lineage_record_to_bytes, lineage_witness_entry, verify_lineage_chain,
 };
 #[cfg(feature = "ed25519")]
-pub use sign::{sign_segment, verify_segment};
+pub use sign::{sign_segment, sign_segment_prehashed, verify_segment};
 pub use witness::{create_witness_chain, verify_witness_chain, WitnessEntry};
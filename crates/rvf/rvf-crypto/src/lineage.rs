@@ -4,8 +4,8 @@
 //! that track file derivation history through witness chain entries.
 
 use rvf_types::{
-    DerivationType, ErrorCode, FileIdentity, LineageRecord, RvfError, LINEAGE_RECORD_SIZE,
-    WITNESS_DERIVATION,
+    ErrorCode, FileIdentity, LineageRecord, RvfError, LINEAGE_RECORD_SIZE, WITNESS_DERIVATION,
+    WITNESS_LINEAGE_MERGE,
 };
 
 use crate::hash::shake256_256;
@@ -13,54 +13,14 @@ use crate::witness::WitnessEntry;
 
 /// Serialize a `LineageRecord` to a fixed 128-byte array.
 pub fn lineage_record_to_bytes(record: &LineageRecord) -> [u8; LINEAGE_RECORD_SIZE] {
-    let mut buf = [0u8; LINEAGE_RECORD_SIZE];
-    buf[0x00..0x10].copy_from_slice(&record.file_id);
-    buf[0x10..0x20].copy_from_slice(&record.parent_id);
-    buf[0x20..0x40].copy_from_slice(&record.parent_hash);
-    buf[0x40] = record.derivation_type as u8;
-    // 3 bytes padding at 0x41..0x44
-    buf[0x44..0x48].copy_from_slice(&record.mutation_count.to_le_bytes());
-    buf[0x48..0x50].copy_from_slice(&record.timestamp_ns.to_le_bytes());
-    buf[0x50] = record.description_len;
-    let desc_len = (record.description_len as usize).min(47);
-    buf[0x51..0x51 + desc_len].copy_from_slice(&record.description[..desc_len]);
-    buf
+    record.to_bytes()
 }
 
 /// Deserialize a `LineageRecord` from a 128-byte slice.
 pub fn lineage_record_from_bytes(
     data: &[u8; LINEAGE_RECORD_SIZE],
 ) -> Result<LineageRecord, RvfError> {
-    let mut file_id = [0u8; 16];
-    file_id.copy_from_slice(&data[0x00..0x10]);
-    let mut parent_id = [0u8; 16];
-    parent_id.copy_from_slice(&data[0x10..0x20]);
-    let mut parent_hash = [0u8; 32];
-    parent_hash.copy_from_slice(&data[0x20..0x40]);
-
-    let derivation_type =
-        DerivationType::try_from(data[0x40]).map_err(|v| RvfError::InvalidEnumValue {
-            type_name: "DerivationType",
-            value: v as u64,
-        })?;
-
-    let mutation_count = u32::from_le_bytes(data[0x44..0x48].try_into().unwrap());
-    let timestamp_ns = u64::from_le_bytes(data[0x48..0x50].try_into().unwrap());
-    let description_len = data[0x50].min(47);
-    let mut description = [0u8; 47];
-    description[..description_len as usize]
-        .copy_from_slice(&data[0x51..0x51 + description_len as usize]);
-
-    Ok(LineageRecord {
-        file_id,
-        parent_id,
-        parent_hash,
-        derivation_type,
-        mutation_count,
-        timestamp_ns,
-        description_len,
-        description,
-    })
+    LineageRecord::from_bytes(data)
 }
 
 /// Create a witness entry for a lineage derivation event.
@@ -78,6 +38,31 @@ pub fn lineage_witness_entry(record: &LineageRecord, prev_hash: [u8; 32]) -> Wit
     }
 }
 
+/// Create a witness entry recording how a lineage merge was resolved.
+///
+/// `ours` and `theirs` are the records that were merged (typically a pair
+/// for which `LineageRecord::detect_conflict` reported a conflict) and
+/// `resolved` is the record kept. The `action_hash` is SHAKE-256-256 of the
+/// three serialized records in that order, and the timestamp is taken from
+/// `resolved`. Uses witness type `WITNESS_LINEAGE_MERGE` (0x0A).
+pub fn lineage_merge_witness_entry(
+    ours: &LineageRecord,
+    theirs: &LineageRecord,
+    resolved: &LineageRecord,
+    prev_hash: [u8; 32],
+) -> WitnessEntry {
+    let mut buf = [0u8; 3 * LINEAGE_RECORD_SIZE];
+    buf[..LINEAGE_RECORD_SIZE].copy_from_slice(&ours.to_bytes());
+    buf[LINEAGE_RECORD_SIZE..2 * LINEAGE_RECORD_SIZE].copy_from_slice(&theirs.to_bytes());
+    buf[2 * LINEAGE_RECORD_SIZE..].copy_from_slice(&resolved.to_bytes());
+    WitnessEntry {
+        prev_hash,
+        action_hash: shake256_256(&buf),
+        timestamp_ns: resolved.timestamp_ns,
+        witness_type: WITNESS_LINEAGE_MERGE,
+    }
+}
+
 /// Compute the SHAKE-256-256 hash of a 4096-byte manifest for use as parent_hash.
 pub fn compute_manifest_hash(manifest: &[u8; 4096]) -> [u8; 32] {
     shake256_256(manifest)
@@ -124,6 +109,7 @@ pub fn verify_lineage_chain(entries: &[(FileIdentity, [u8; 32])]) -> Result<(),
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rvf_types::DerivationType;
 
     fn sample_record() -> LineageRecord {
         LineageRecord::new(
@@ -172,6 +158,28 @@ mod tests {
         assert_ne!(entry.action_hash, [0u8; 32]);
     }
 
+    #[test]
+    fn lineage_merge_witness_records_resolution() {
+        use rvf_types::ConflictKind;
+
+        let ours = sample_record();
+        let mut theirs = sample_record();
+        theirs.derivation_type = DerivationType::Quantize;
+        assert!(matches!(
+            ours.detect_conflict(&theirs),
+            Some(ConflictKind::TransformMismatch { .. })
+        ));
+
+        let entry = lineage_merge_witness_entry(&ours, &theirs, &ours, [7u8; 32]);
+        assert_eq!(entry.witness_type, WITNESS_LINEAGE_MERGE);
+        assert_eq!(entry.prev_hash, [7u8; 32]);
+        assert_eq!(entry.timestamp_ns, ours.timestamp_ns);
+
+        // Choosing the other side is a different resolution.
+        let other = lineage_merge_witness_entry(&ours, &theirs, &theirs, [7u8; 32]);
+        assert_ne!(entry.action_hash, other.action_hash);
+    }
+
     #[test]
     fn compute_manifest_hash_deterministic() {
         let manifest = [0xABu8; 4096];
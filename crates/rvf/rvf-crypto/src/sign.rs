@@ -2,16 +2,27 @@
 //!
 //! Signs the canonical representation: header bytes || content_hash || context.
 //! ML-DSA-65 is a future TODO behind a feature flag.
+//!
+//! Large segments can instead be signed with Ed25519ph (RFC 8032 prehash
+//! variant), which signs a SHA-512 digest of the canonical header fields and
+//! the payload, so the payload is read exactly once.
 
 use alloc::vec::Vec;
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use rvf_types::{SegmentHeader, SignatureFooter};
+use sha2::{Digest, Sha512};
 
 use crate::hash::shake256_128;
 
 /// Ed25519 algorithm identifier (matches `SignatureAlgo::Ed25519`).
 const SIG_ALGO_ED25519: u16 = 0;
 
+/// Ed25519ph algorithm identifier (matches `SignatureAlgo::Ed25519ph`).
+const SIG_ALGO_ED25519PH: u16 = 3;
+
+/// Ed25519ph context string for domain separation.
+const PREHASH_CONTEXT: &[u8] = b"RVF-v1-segment";
+
 /// Build the canonical message to sign for a segment.
 ///
 /// signed_data = segment_header_bytes[0..40] || content_hash || context_string || segment_id
@@ -36,6 +47,19 @@ fn build_signed_data(header: &SegmentHeader, payload: &[u8]) -> Vec<u8> {
     msg
 }
 
+/// SHA-512 prehash of a segment for Ed25519ph.
+///
+/// prehash = SHA-512(segment_header_bytes[0..40] || content_hash || segment_id || payload)
+fn build_prehash(header: &SegmentHeader, payload: &[u8]) -> Sha512 {
+    let header_bytes = header_to_sign_bytes(header);
+    let mut digest = Sha512::new();
+    digest.update(&header_bytes[..40]);
+    digest.update(header.content_hash);
+    digest.update(header.segment_id.to_le_bytes());
+    digest.update(payload);
+    digest
+}
+
 /// Safely serialize a `SegmentHeader` into its 64-byte wire representation.
 ///
 /// This mirrors the layout in `write_path::header_to_bytes` but lives here to
@@ -77,7 +101,31 @@ pub fn sign_segment(header: &SegmentHeader, payload: &[u8], key: &SigningKey) ->
     }
 }
 
-/// Verify a segment signature using Ed25519.
+/// Sign a segment with Ed25519ph over a SHA-512 prehash, producing a
+/// `SignatureFooter`.
+pub fn sign_segment_prehashed(
+    header: &SegmentHeader,
+    payload: &[u8],
+    key: &SigningKey,
+) -> SignatureFooter {
+    let sig = key
+        .sign_prehashed(build_prehash(header, payload), Some(PREHASH_CONTEXT))
+        .expect("context is shorter than 256 bytes");
+    let sig_bytes = sig.to_bytes();
+
+    let mut signature = [0u8; SignatureFooter::MAX_SIG_LEN];
+    signature[..64].copy_from_slice(&sig_bytes);
+
+    SignatureFooter {
+        sig_algo: SIG_ALGO_ED25519PH,
+        sig_length: 64,
+        signature,
+        footer_length: SignatureFooter::compute_footer_length(64),
+    }
+}
+
+/// Verify a segment signature using Ed25519 or Ed25519ph, as selected by
+/// `footer.sig_algo`.
 ///
 /// Returns `true` if the signature is valid, `false` otherwise.
 pub fn verify_segment(
@@ -86,18 +134,24 @@ pub fn verify_segment(
     footer: &SignatureFooter,
     pubkey: &VerifyingKey,
 ) -> bool {
-    if footer.sig_algo != SIG_ALGO_ED25519 {
+    if footer.sig_algo != SIG_ALGO_ED25519 && footer.sig_algo != SIG_ALGO_ED25519PH {
         return false;
     }
     if footer.sig_length != 64 {
         return false;
     }
-    let msg = build_signed_data(header, payload);
     let sig_bytes: [u8; 64] = match footer.signature[..64].try_into() {
         Ok(b) => b,
         Err(_) => return false,
     };
     let sig = Signature::from_bytes(&sig_bytes);
+    if footer.sig_algo == SIG_ALGO_ED25519PH {
+        let prehash = build_prehash(header, payload);
+        return pubkey
+            .verify_prehashed(prehash, Some(PREHASH_CONTEXT), &sig)
+            .is_ok();
+    }
+    let msg = build_signed_data(header, payload);
     pubkey.verify(&msg, &sig).is_ok()
 }
 
@@ -185,4 +239,52 @@ mod tests {
             SignatureFooter::compute_footer_length(64)
         );
     }
+
+    #[test]
+    fn prehashed_sign_verify_10mb() {
+        let key = SigningKey::generate(&mut OsRng);
+        let mut header = make_test_header();
+        let payload: Vec<u8> = (0..10 * 1024 * 1024u32).map(|i| (i * 31) as u8).collect();
+        header.payload_length = payload.len() as u64;
+
+        let footer = sign_segment_prehashed(&header, &payload, &key);
+        assert_eq!(footer.sig_algo, 3);
+        assert_eq!(footer.sig_length, 64);
+        assert!(verify_segment(
+            &header,
+            &payload,
+            &footer,
+            &key.verifying_key()
+        ));
+    }
+
+    #[test]
+    fn prehashed_flipped_byte_fails() {
+        let key = SigningKey::generate(&mut OsRng);
+        let header = make_test_header();
+        let mut payload = alloc::vec![0x5Au8; 4096];
+
+        let footer = sign_segment_prehashed(&header, &payload, &key);
+        let pubkey = key.verifying_key();
+        assert!(verify_segment(&header, &payload, &footer, &pubkey));
+
+        payload[1234] ^= 0x01;
+        assert!(!verify_segment(&header, &payload, &footer, &pubkey));
+    }
+
+    #[test]
+    fn prehashed_and_plain_signatures_not_interchangeable() {
+        let key = SigningKey::generate(&mut OsRng);
+        let header = make_test_header();
+        let payload = b"payload";
+        let pubkey = key.verifying_key();
+
+        let mut footer = sign_segment_prehashed(&header, payload, &key);
+        footer.sig_algo = 0;
+        assert!(!verify_segment(&header, payload, &footer, &pubkey));
+
+        let mut footer = sign_segment(&header, payload, &key);
+        footer.sig_algo = 3;
+        assert!(!verify_segment(&header, payload, &footer, &pubkey));
+    }
 }
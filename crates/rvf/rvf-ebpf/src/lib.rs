@@ -665,7 +665,7 @@ fn infer_program_type(path: &Path) -> EbpfProgramType {
 /// Infer the attach type from the program type.
 fn infer_attach_type(ptype: EbpfProgramType) -> EbpfAttachType {
     match ptype {
-        EbpfProgramType::XdpDistance => EbpfAttachType::XdpIngress,
+        EbpfProgramType::XdpDistance | EbpfProgramType::Xdp => EbpfAttachType::XdpIngress,
         EbpfProgramType::TcFilter => EbpfAttachType::TcIngress,
         EbpfProgramType::SocketFilter => EbpfAttachType::SocketFilter,
         EbpfProgramType::CgroupSkb => EbpfAttachType::CgroupIngress,
@@ -39,6 +39,23 @@ fn map_rvf_err(e: RvfError) -> napi::Error {
         RvfError::QualityBelowThreshold { quality, reason } => {
             format!("Quality below threshold ({quality:?}): {reason}")
         }
+        RvfError::InvalidKernelConfig { flags, reason } => {
+            format!("Invalid kernel config (flags 0x{flags:08X}): {reason}")
+        }
+        RvfError::InvalidTlv { tag, reason } => {
+            format!("Invalid TLV record (tag 0x{tag:04X}): {reason}")
+        }
+        RvfError::InvalidDictionary { reason } => {
+            format!("Invalid compression dictionary: {reason}")
+        }
+        RvfError::DimensionMismatch { expected, got } => {
+            format!("Dimension mismatch: expected {expected}, got {got}")
+        }
+        RvfError::MetadataSchemaViolation { field_id, reason } => {
+            format!("Metadata field {field_id} violates schema: {reason}")
+        }
+        RvfError::TaskFailed { reason } => format!("Blocking task {reason}"),
+        RvfError::IdConflict { id, reason } => format!("Id {id} conflicts: {reason}"),
     };
     napi::Error::from_reason(msg)
 }
@@ -254,9 +271,9 @@ fn json_to_filter(val: &JsonValue) -> Result<RustFilterExpr> {
             let vals: Vec<RustFilterValue> = arr
                 .iter()
                 .map(|v| {
-                    let s = v
-                        .as_str()
-                        .ok_or_else(|| napi::Error::from_reason("'values' entries must be strings"))?;
+                    let s = v.as_str().ok_or_else(|| {
+                        napi::Error::from_reason("'values' entries must be strings")
+                    })?;
                     parse_filter_value(&vt, s)
                 })
                 .collect::<Result<_>>()?;
@@ -679,9 +696,12 @@ impl RvfDatabase {
     /// Get this file's unique identifier as a hex string.
     #[napi]
     pub fn file_id(&self) -> Result<String> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
         Ok(hex_encode(store.file_id()))
     }
@@ -689,9 +709,12 @@ impl RvfDatabase {
     /// Get the parent file's identifier as a hex string (all zeros if root).
     #[napi]
     pub fn parent_id(&self) -> Result<String> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
         Ok(hex_encode(store.parent_id()))
     }
@@ -699,9 +722,12 @@ impl RvfDatabase {
     /// Get the lineage depth (0 for root files).
     #[napi]
     pub fn lineage_depth(&self) -> Result<u32> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
         Ok(store.lineage_depth())
     }
@@ -709,9 +735,12 @@ impl RvfDatabase {
     /// Derive a child store from this parent.
     #[napi]
     pub fn derive(&self, child_path: String, options: Option<RvfOptions>) -> Result<RvfDatabase> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         let child_opts = match options {
@@ -719,11 +748,13 @@ impl RvfDatabase {
             None => None,
         };
 
-        let child_store = store.derive(
-            Path::new(&child_path),
-            rvf_types::DerivationType::Filter,
-            child_opts,
-        ).map_err(map_rvf_err)?;
+        let child_store = store
+            .derive(
+                Path::new(&child_path),
+                rvf_types::DerivationType::Filter,
+                child_opts,
+            )
+            .map_err(map_rvf_err)?;
 
         Ok(RvfDatabase {
             inner: Mutex::new(Some(child_store)),
@@ -744,19 +775,24 @@ impl RvfDatabase {
         api_port: u32,
         cmdline: Option<String>,
     ) -> Result<i64> {
-        let mut guard = self.inner.lock()
+        let mut guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_mut()
+        let store = guard
+            .as_mut()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
-        let seg_id = store.embed_kernel(
-            arch as u8,
-            kernel_type as u8,
-            flags,
-            &image,
-            api_port as u16,
-            cmdline.as_deref(),
-        ).map_err(map_rvf_err)?;
+        let seg_id = store
+            .embed_kernel(
+                arch as u8,
+                kernel_type as u8,
+                flags,
+                &image,
+                api_port as u16,
+                cmdline.as_deref(),
+            )
+            .map_err(map_rvf_err)?;
 
         Ok(seg_id as i64)
     }
@@ -765,9 +801,12 @@ impl RvfDatabase {
     /// Returns null if no kernel segment is present.
     #[napi]
     pub fn extract_kernel(&self) -> Result<Option<RvfKernelData>> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         match store.extract_kernel().map_err(map_rvf_err)? {
@@ -790,19 +829,24 @@ impl RvfDatabase {
         bytecode: Buffer,
         btf: Option<Buffer>,
     ) -> Result<i64> {
-        let mut guard = self.inner.lock()
+        let mut guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_mut()
+        let store = guard
+            .as_mut()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         let btf_ref = btf.as_ref().map(|b| b.as_ref());
-        let seg_id = store.embed_ebpf(
-            program_type as u8,
-            attach_type as u8,
-            max_dimension as u16,
-            &bytecode,
-            btf_ref,
-        ).map_err(map_rvf_err)?;
+        let seg_id = store
+            .embed_ebpf(
+                program_type as u8,
+                attach_type as u8,
+                max_dimension as u16,
+                &bytecode,
+                btf_ref,
+            )
+            .map_err(map_rvf_err)?;
 
         Ok(seg_id as i64)
     }
@@ -811,9 +855,12 @@ impl RvfDatabase {
     /// Returns null if no eBPF segment is present.
     #[napi]
     pub fn extract_ebpf(&self) -> Result<Option<RvfEbpfData>> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         match store.extract_ebpf().map_err(map_rvf_err)? {
@@ -830,28 +877,35 @@ impl RvfDatabase {
     /// Get the list of segments in the store.
     #[napi]
     pub fn segments(&self) -> Result<Vec<RvfSegmentInfo>> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         let seg_dir = store.segment_dir();
-        Ok(seg_dir.iter().map(|&(id, offset, payload_len, seg_type)| {
-            RvfSegmentInfo {
+        Ok(seg_dir
+            .iter()
+            .map(|&(id, offset, payload_len, seg_type)| RvfSegmentInfo {
                 id: id as i64,
                 offset: offset as i64,
                 payload_length: payload_len as i64,
                 seg_type: segment_type_name(seg_type),
-            }
-        }).collect())
+            })
+            .collect())
     }
 
     /// Get the vector dimensionality of this store.
     #[napi]
     pub fn dimension(&self) -> Result<u32> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
         Ok(store.dimension() as u32)
     }
@@ -861,9 +915,12 @@ impl RvfDatabase {
     /// Get HNSW index statistics for this store.
     #[napi]
     pub fn index_stats(&self) -> Result<RvfIndexStats> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         let status = store.status();
@@ -884,15 +941,20 @@ impl RvfDatabase {
     /// hash).  Returns the number of witness entries and validity status.
     #[napi]
     pub fn verify_witness(&self) -> Result<RvfWitnessResult> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         // Witness segment type discriminator (0x0A).
         const WITNESS_SEG_TYPE: u8 = 0x0A;
 
-        let witness_count = store.segment_dir().iter()
+        let witness_count = store
+            .segment_dir()
+            .iter()
             .filter(|&&(_, _, _, seg_type)| seg_type == WITNESS_SEG_TYPE)
             .count() as u32;
 
@@ -903,7 +965,9 @@ impl RvfDatabase {
             Ok(RvfWitnessResult {
                 valid: false,
                 entries: witness_count,
-                error: Some("Witness segments exist but chain hash is zero (corrupt or reset)".to_string()),
+                error: Some(
+                    "Witness segments exist but chain hash is zero (corrupt or reset)".to_string(),
+                ),
             })
         } else {
             Ok(RvfWitnessResult {
@@ -920,9 +984,12 @@ impl RvfDatabase {
     /// Returns the manifest epoch at freeze time.
     #[napi]
     pub fn freeze(&self) -> Result<u32> {
-        let mut guard = self.inner.lock()
+        let mut guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_mut()
+        let store = guard
+            .as_mut()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         let epoch = store.epoch();
@@ -933,9 +1000,12 @@ impl RvfDatabase {
     /// Get the distance metric used by this store.
     #[napi]
     pub fn metric(&self) -> Result<String> {
-        let guard = self.inner.lock()
+        let guard = self
+            .inner
+            .lock()
             .map_err(|_| napi::Error::from_reason("Lock poisoned"))?;
-        let store = guard.as_ref()
+        let store = guard
+            .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Store is closed"))?;
 
         let metric_str = match store.metric() {
@@ -959,8 +1029,7 @@ fn hex_encode(bytes: &[u8]) -> String {
 }
 
 const HEX_CHARS: [char; 16] = [
-    '0', '1', '2', '3', '4', '5', '6', '7',
-    '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
 ];
 
 fn segment_type_name(seg_type: u8) -> String {
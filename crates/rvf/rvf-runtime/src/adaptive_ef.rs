@@ -0,0 +1,222 @@
+//! Recall-driven `ef_search` tuning.
+//!
+//! `ef_search` trades recall for latency, and the right value drifts as the
+//! data distribution changes. [`AdaptiveEf`] keeps a current value and
+//! nudges it from sampled recall measurements: one query in every
+//! `sample_interval` is re-run as an exact scan, and the overlap between the
+//! approximate and exact top-k is recorded with [`AdaptiveEf::observe`].
+//! Once `window` samples have accumulated, their mean recall is compared to
+//! the target and `ef_search` moves by `step`, within `[min_ef, max_ef]`.
+//!
+//! The controller uses interior mutability so it can be sampled and fed from
+//! `&self` query paths.
+//!
+//! `RvfStore` itself answers queries with an exact scan and has no
+//! `ef_search` to tune; the controller is for callers that drive an
+//! approximate index such as `rvf-index`'s HNSW graph.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU16, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Configuration for [`AdaptiveEf`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveEfConfig {
+    /// Recall@k the controller steers toward.
+    pub target_recall: f32,
+    /// Recall above `target_recall + headroom` lowers `ef_search`.
+    pub headroom: f32,
+    /// Lower bound for `ef_search`.
+    pub min_ef: u16,
+    /// Upper bound for `ef_search`.
+    pub max_ef: u16,
+    /// Starting `ef_search`, clamped to the bounds.
+    pub initial_ef: u16,
+    /// Amount `ef_search` moves per adjustment.
+    pub step: u16,
+    /// Sample one query in every `sample_interval`.
+    pub sample_interval: u32,
+    /// Recall samples averaged before each adjustment.
+    pub window: usize,
+}
+
+impl Default for AdaptiveEfConfig {
+    fn default() -> Self {
+        Self {
+            target_recall: 0.95,
+            headroom: 0.03,
+            min_ef: 16,
+            max_ef: 512,
+            initial_ef: 100,
+            step: 16,
+            sample_interval: 100,
+            window: 8,
+        }
+    }
+}
+
+/// Recall@k of `approx` against the exact top-k `exact`.
+///
+/// Returns 1.0 when `exact` is empty (there was nothing to find).
+pub fn recall_at_k(approx: &[u64], exact: &[u64]) -> f32 {
+    if exact.is_empty() {
+        return 1.0;
+    }
+    let truth: HashSet<u64> = exact.iter().copied().collect();
+    let hits = approx.iter().filter(|id| truth.contains(id)).count();
+    hits.min(exact.len()) as f32 / exact.len() as f32
+}
+
+/// Adaptive `ef_search` controller.
+#[derive(Debug)]
+pub struct AdaptiveEf {
+    config: AdaptiveEfConfig,
+    ef_search: AtomicU16,
+    queries_seen: AtomicU64,
+    samples: Mutex<Vec<f32>>,
+}
+
+impl AdaptiveEf {
+    /// Create a controller starting at `config.initial_ef`.
+    pub fn new(config: AdaptiveEfConfig) -> Self {
+        let min_ef = config.min_ef.max(1);
+        let config = AdaptiveEfConfig {
+            min_ef,
+            max_ef: config.max_ef.max(min_ef),
+            step: config.step.max(1),
+            sample_interval: config.sample_interval.max(1),
+            window: config.window.max(1),
+            ..config
+        };
+        Self {
+            ef_search: AtomicU16::new(config.initial_ef.clamp(config.min_ef, config.max_ef)),
+            config,
+            queries_seen: AtomicU64::new(0),
+            samples: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The controller's configuration (with bounds normalized).
+    pub fn config(&self) -> &AdaptiveEfConfig {
+        &self.config
+    }
+
+    /// Current `ef_search`.
+    pub fn ef_search(&self) -> u16 {
+        self.ef_search.load(Ordering::Relaxed)
+    }
+
+    /// Count one query and report whether it should be sampled for recall.
+    pub fn should_sample(&self) -> bool {
+        let n = self.queries_seen.fetch_add(1, Ordering::Relaxed);
+        n.is_multiple_of(self.config.sample_interval as u64)
+    }
+
+    /// Record the recall of one sampled query, adjusting `ef_search` once a
+    /// full window has accumulated. Returns the measured recall.
+    pub fn observe(&self, approx: &[u64], exact: &[u64]) -> f32 {
+        let recall = recall_at_k(approx, exact);
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.push(recall);
+        if samples.len() >= self.config.window {
+            let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+            samples.clear();
+            self.adjust(mean);
+        }
+        recall
+    }
+
+    fn adjust(&self, mean_recall: f32) {
+        let c = &self.config;
+        let ef = self.ef_search();
+        let next = if mean_recall < c.target_recall {
+            ef.saturating_add(c.step).min(c.max_ef)
+        } else if mean_recall > c.target_recall + c.headroom {
+            ef.saturating_sub(c.step).max(c.min_ef)
+        } else {
+            ef
+        };
+        self.ef_search.store(next, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stand-in index whose recall grows with ef: it finds the first
+    /// `k * min(ef, saturation) / saturation` true neighbors and pads the
+    /// rest with misses.
+    fn simulated_search(exact: &[u64], ef: u16, saturation: u16) -> Vec<u64> {
+        let found = exact.len() * ef.min(saturation) as usize / saturation as usize;
+        let mut ids: Vec<u64> = exact[..found].to_vec();
+        ids.extend((0..exact.len() - found).map(|i| u64::MAX - i as u64));
+        ids
+    }
+
+    fn config() -> AdaptiveEfConfig {
+        AdaptiveEfConfig {
+            target_recall: 0.9,
+            headroom: 0.05,
+            min_ef: 16,
+            max_ef: 400,
+            initial_ef: 40,
+            step: 20,
+            sample_interval: 1,
+            window: 4,
+        }
+    }
+
+    #[test]
+    fn recall_at_k_counts_overlap() {
+        assert_eq!(recall_at_k(&[1, 2, 3, 4], &[1, 2, 5, 6]), 0.5);
+        assert_eq!(recall_at_k(&[], &[]), 1.0);
+        assert_eq!(recall_at_k(&[1, 1, 1], &[1, 2]), 1.0);
+    }
+
+    #[test]
+    fn raises_ef_when_under_recalling() {
+        let ctl = AdaptiveEf::new(config());
+        let exact: Vec<u64> = (0..10).collect();
+
+        for _ in 0..200 {
+            if ctl.should_sample() {
+                let approx = simulated_search(&exact, ctl.ef_search(), 200);
+                ctl.observe(&approx, &exact);
+            }
+        }
+
+        // Recall 0.9 needs ef >= 180 in the simulated index.
+        let ef = ctl.ef_search();
+        assert!(ef >= 180, "ef_search {ef}");
+        let approx = simulated_search(&exact, ef, 200);
+        assert!(recall_at_k(&approx, &exact) >= 0.9);
+    }
+
+    #[test]
+    fn lowers_ef_when_comfortably_above_target() {
+        let ctl = AdaptiveEf::new(AdaptiveEfConfig {
+            initial_ef: 400,
+            ..config()
+        });
+        let exact: Vec<u64> = (0..10).collect();
+
+        for _ in 0..200 {
+            if ctl.should_sample() {
+                // Perfect recall at any ef.
+                ctl.observe(&exact, &exact);
+            }
+        }
+        assert_eq!(ctl.ef_search(), 16);
+    }
+
+    #[test]
+    fn samples_one_query_per_interval() {
+        let ctl = AdaptiveEf::new(AdaptiveEfConfig {
+            sample_interval: 10,
+            ..config()
+        });
+        let sampled = (0..100).filter(|_| ctl.should_sample()).count();
+        assert_eq!(sampled, 10);
+    }
+}
@@ -7,6 +7,7 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use rvf_types::agi_container::*;
+use rvf_types::{TlvReader, TlvWriter};
 
 use crate::seed_crypto;
 
@@ -206,12 +207,9 @@ impl AgiContainerBuilder {
 
     /// Build the manifest TLV payload (sections only, no header).
     fn build_sections(&self) -> Vec<u8> {
-        let mut payload = Vec::new();
-
+        let mut tlv = TlvWriter::new();
         let mut write_section = |tag: u16, data: &[u8]| {
-            payload.extend_from_slice(&tag.to_le_bytes());
-            payload.extend_from_slice(&(data.len() as u32).to_le_bytes());
-            payload.extend_from_slice(data);
+            tlv.bytes(tag, data);
         };
 
         write_section(AGI_TAG_CONTAINER_ID, &self.container_id);
@@ -266,7 +264,7 @@ impl AgiContainerBuilder {
             write_section(AGI_TAG_DOMAIN_PROFILE, dp);
         }
 
-        payload
+        tlv.into_bytes()
     }
 
     /// Build the manifest: header + TLV sections.
@@ -393,20 +391,13 @@ impl<'a> ParsedAgiManifest<'a> {
         };
 
         // Parse TLV sections after header.
-        let mut pos = AGI_HEADER_SIZE;
-        while pos + 6 <= data.len() {
-            let tag = u16::from_le_bytes([data[pos], data[pos + 1]]);
-            let length =
-                u32::from_le_bytes([data[pos + 2], data[pos + 3], data[pos + 4], data[pos + 5]])
-                    as usize;
-            pos += 6;
-
-            if pos + length > data.len() {
-                break;
-            }
-
-            let value = &data[pos..pos + length];
-            match tag {
+        let sections = data.get(AGI_HEADER_SIZE..).unwrap_or(&[]);
+        for record in TlvReader::new(sections) {
+            let Ok(record) = record else {
+                return Err(ContainerError::InvalidConfig("malformed manifest section"));
+            };
+            let value = record.value;
+            match record.tag {
                 AGI_TAG_MODEL_ID => result.model_id = Some(value),
                 AGI_TAG_POLICY => result.policy = Some(value),
                 AGI_TAG_ORCHESTRATOR => result.orchestrator_config = Some(value),
@@ -425,8 +416,6 @@ impl<'a> ParsedAgiManifest<'a> {
                 AGI_TAG_DOMAIN_PROFILE => result.domain_profile = Some(value),
                 _ => {} // forward-compat: ignore unknown tags
             }
-
-            pos += length;
         }
 
         Ok(result)
@@ -0,0 +1,286 @@
+//! Staged async open for [`RvfStore`].
+//!
+//! [`AsyncBoot`] loads a store progressively: the manifest first, from the
+//! 64 KB tail of the file, then the journal and settings segments it
+//! references, then one VEC segment at a time. Each stage reads only the
+//! byte ranges it needs with `tokio::fs`, and the store can be queried
+//! between stages, seeing the vectors loaded so far. Lock acquisition and
+//! intent-log recovery are synchronous file operations and run on tokio's
+//! blocking pool.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use rvf_types::{ErrorCode, RvfError, SegmentType, SEGMENT_HEADER_SIZE};
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::task::JoinError;
+
+use crate::encryption;
+use crate::options::AsyncOpenOptions;
+use crate::read_path::{ParsedManifest, SegDirEntry};
+use crate::store::RvfStore;
+
+/// Bytes read from the end of the file to locate the manifest: the tail
+/// `read_path::find_latest_manifest` scans.
+const MANIFEST_TAIL: u64 = 65_536;
+
+/// A store part way through an async open.
+///
+/// [`AsyncBoot::load_next`] loads one more VEC segment, and queries on
+/// [`AsyncBoot::store`] in between see only the vectors loaded so far.
+/// [`AsyncBoot::finish`] loads the rest and returns the store; until then
+/// the store cannot be mutated.
+pub struct AsyncBoot {
+    store: RvfStore,
+    file: File,
+    file_len: u64,
+    manifest: ParsedManifest,
+    /// `(offset, payload_length)` of the VEC segments not yet loaded.
+    pending: VecDeque<(u64, u64)>,
+    total: usize,
+}
+
+impl AsyncBoot {
+    /// Open the store at `path` and load its manifest, journals and
+    /// settings. No vectors are loaded yet.
+    pub async fn open(path: &Path, options: AsyncOpenOptions) -> Result<Self, RvfError> {
+        if options.encryption.is_some() && !encryption::is_supported() {
+            return Err(err(ErrorCode::AlgoUnsupported));
+        }
+        let AsyncOpenOptions {
+            read_only,
+            encryption,
+            lock,
+        } = options;
+        let owned = path.to_path_buf();
+        let mut store = tokio::task::spawn_blocking(move || {
+            RvfStore::open_unbooted(&owned, read_only, encryption, lock)
+        })
+        .await
+        .map_err(task_failed)??;
+
+        // Opened after recovery so a rolled-back append is not seen.
+        let mut file = File::open(path)
+            .await
+            .map_err(|_| err(ErrorCode::InvalidManifest))?;
+        let file_len = file
+            .metadata()
+            .await
+            .map_err(|_| err(ErrorCode::InvalidManifest))?
+            .len();
+
+        let mut tail = Prefetched::new(file_len);
+        let tail_len = file_len.min(MANIFEST_TAIL);
+        tail.fetch(&mut file, file_len - tail_len, tail_len).await?;
+        let manifest = RvfStore::locate_manifest(&mut tail)?;
+        store.apply_manifest(&manifest);
+
+        let mut side = Prefetched::new(file_len);
+        let journals = manifest
+            .segment_dir
+            .iter()
+            .filter(|e| e.seg_type == SegmentType::Journal as u8);
+        let settings = manifest
+            .segment_dir
+            .iter()
+            .rfind(|e| e.seg_type == SegmentType::Settings as u8);
+        for entry in journals.chain(settings) {
+            side.fetch_segment(&mut file, entry).await?;
+        }
+        store.load_side_segments(&mut side, &manifest)?;
+
+        let pending: VecDeque<(u64, u64)> = manifest
+            .segment_dir
+            .iter()
+            .filter(|e| e.seg_type == SegmentType::Vec as u8)
+            .map(|e| (e.offset, e.payload_length))
+            .collect();
+        Ok(Self {
+            store,
+            file,
+            file_len,
+            manifest,
+            total: pending.len(),
+            pending,
+        })
+    }
+
+    /// The store as loaded so far.
+    pub fn store(&self) -> &RvfStore {
+        &self.store
+    }
+
+    /// VEC segments loaded so far, and in total.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.total - self.pending.len(), self.total)
+    }
+
+    /// Load the next VEC segment. Returns false once all are loaded.
+    pub async fn load_next(&mut self) -> Result<bool, RvfError> {
+        let Some((offset, payload_length)) = self.pending.pop_front() else {
+            return Ok(false);
+        };
+        let mut segment = Prefetched::new(self.file_len);
+        segment
+            .fetch(
+                &mut self.file,
+                offset,
+                SEGMENT_HEADER_SIZE as u64 + payload_length,
+            )
+            .await?;
+        self.store.load_vec_segment(&mut segment, offset)?;
+        Ok(true)
+    }
+
+    /// Load the remaining VEC segments, check the membership filter
+    /// against them, and return the open store.
+    pub async fn finish(mut self) -> Result<RvfStore, RvfError> {
+        while self.load_next().await? {}
+
+        let mut rest = Prefetched::new(self.file_len);
+        if let Some(entry) = self
+            .manifest
+            .segment_dir
+            .iter()
+            .rfind(|e| e.seg_type == SegmentType::Membership as u8)
+        {
+            rest.fetch_segment(&mut self.file, entry).await?;
+        }
+        self.store.finish_boot(&mut rest, &self.manifest)?;
+        Ok(self.store)
+    }
+}
+
+/// Map a failed blocking task to `TaskFailed`.
+pub(crate) fn task_failed(e: JoinError) -> RvfError {
+    RvfError::TaskFailed {
+        reason: if e.is_panic() {
+            "panicked"
+        } else {
+            "cancelled"
+        },
+    }
+}
+
+fn err(code: ErrorCode) -> RvfError {
+    RvfError::Code(code)
+}
+
+/// `Read + Seek` over byte ranges of the file fetched ahead of time, so
+/// the synchronous segment parsers can run on them. Positions are file
+/// offsets; reading outside every fetched range hits end-of-file.
+struct Prefetched {
+    file_len: u64,
+    ranges: Vec<(u64, Vec<u8>)>,
+    pos: u64,
+}
+
+impl Prefetched {
+    fn new(file_len: u64) -> Self {
+        Self {
+            file_len,
+            ranges: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Read `len` bytes at `offset`. The manifest has already been checked
+    /// to lie within the file, so a short read means it was truncated.
+    async fn fetch(&mut self, file: &mut File, offset: u64, len: u64) -> Result<(), RvfError> {
+        let mut bytes = vec![0u8; len as usize];
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|_| err(ErrorCode::InvalidManifest))?;
+        file.read_exact(&mut bytes)
+            .await
+            .map_err(|_| err(ErrorCode::TruncatedSegment))?;
+        self.ranges.push((offset, bytes));
+        Ok(())
+    }
+
+    async fn fetch_segment(
+        &mut self,
+        file: &mut File,
+        entry: &SegDirEntry,
+    ) -> Result<(), RvfError> {
+        let len = SEGMENT_HEADER_SIZE as u64 + entry.payload_length;
+        self.fetch(file, entry.offset, len).await
+    }
+}
+
+impl Read for Prefetched {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let pos = self.pos;
+        let Some((start, bytes)) = self
+            .ranges
+            .iter()
+            .find(|(start, bytes)| pos >= *start && pos - start < bytes.len() as u64)
+        else {
+            return Ok(0);
+        };
+        let from = (pos - start) as usize;
+        let n = out.len().min(bytes.len() - from);
+        out[..n].copy_from_slice(&bytes[from..from + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for Prefetched {
+    fn seek(&mut self, target: SeekFrom) -> io::Result<u64> {
+        let next = match target {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.file_len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+        self.pos = next.ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefetched_reads_only_fetched_ranges() {
+        let mut reader = Prefetched::new(100);
+        reader.ranges.push((10, vec![1, 2, 3, 4]));
+        reader.ranges.push((50, vec![9, 8]));
+
+        let mut buf = [0u8; 3];
+        reader.seek(SeekFrom::Start(11)).unwrap();
+        assert_eq!(reader.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf, [2, 3, 4]);
+        // A read stops at the end of its range.
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+        assert_eq!(reader.seek(SeekFrom::End(-50)).unwrap(), 50);
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, [9, 8]);
+
+        assert!(reader.seek(SeekFrom::End(-101)).is_err());
+    }
+
+    #[tokio::test]
+    async fn join_failures_map_to_task_failed() {
+        let panicked = tokio::spawn(async { panic!("boom") }).await.unwrap_err();
+        assert_eq!(
+            task_failed(panicked),
+            RvfError::TaskFailed { reason: "panicked" }
+        );
+
+        let handle = tokio::spawn(std::future::pending::<()>());
+        handle.abort();
+        let cancelled = handle.await.unwrap_err();
+        assert_eq!(
+            task_failed(cancelled),
+            RvfError::TaskFailed {
+                reason: "cancelled"
+            }
+        );
+    }
+}
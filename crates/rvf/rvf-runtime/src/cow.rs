@@ -308,6 +308,11 @@ impl CowEngine {
         self.snapshot_epoch
     }
 
+    /// Number of vectors addressed by each cluster.
+    pub fn vectors_per_cluster(&self) -> u32 {
+        self.vectors_per_cluster
+    }
+
     /// Get COW statistics.
     pub fn stats(&self) -> CowStats {
         CowStats {
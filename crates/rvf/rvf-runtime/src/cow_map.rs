@@ -11,6 +11,7 @@ use rvf_types::{ErrorCode, RvfError};
 ///
 /// Each cluster is either local (written to this file), inherited from the
 /// parent (ParentRef), or unallocated.
+#[derive(Clone)]
 pub struct CowMap {
     format: MapFormat,
     entries: Vec<CowMapEntry>,
@@ -50,6 +51,33 @@ impl CowMap {
         self.entries[idx] = entry;
     }
 
+    /// Iterate live entries in cluster-ID order.
+    ///
+    /// Unallocated clusters (never written, or cleared) are skipped. Each
+    /// cluster holds only its newest copy-on-write entry, so shadowed
+    /// versions never appear.
+    ///
+    /// The iterator borrows the map, so it always sees a consistent
+    /// snapshot: writers need `&mut CowMap` and cannot run until it is
+    /// dropped. To audit while writes continue, iterate over a `clone()`.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &CowMapEntry)> + '_ {
+        self.range(0, self.cluster_count())
+    }
+
+    /// Iterate live entries with cluster IDs in `start..end`, in order.
+    ///
+    /// Same semantics as [`CowMap::iter`]. An empty or inverted range, or
+    /// one past the end of the map, yields nothing.
+    pub fn range(&self, start: u32, end: u32) -> impl Iterator<Item = (u32, &CowMapEntry)> + '_ {
+        let end = (end as usize).min(self.entries.len());
+        let start = (start as usize).min(end);
+        self.entries[start..end]
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| !matches!(e, CowMapEntry::Unallocated))
+            .map(move |(i, e)| ((start + i) as u32, e))
+    }
+
     /// Serialize the map to bytes.
     ///
     /// Wire format (flat_array):
@@ -223,4 +251,86 @@ mod tests {
         let result = CowMap::deserialize(&bytes, MapFormat::ArtTree);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn iter_yields_live_entries_in_order() {
+        let mut map = CowMap::new_flat(6);
+        map.update(4, CowMapEntry::LocalOffset(0x400));
+        map.update(1, CowMapEntry::ParentRef);
+        map.update(2, CowMapEntry::LocalOffset(0x200));
+
+        let live: Vec<(u32, CowMapEntry)> = map.iter().map(|(id, e)| (id, *e)).collect();
+        assert_eq!(
+            live,
+            vec![
+                (1, CowMapEntry::ParentRef),
+                (2, CowMapEntry::LocalOffset(0x200)),
+                (4, CowMapEntry::LocalOffset(0x400)),
+            ]
+        );
+    }
+
+    #[test]
+    fn iter_skips_overwritten_and_deleted() {
+        let mut map = CowMap::new_parent_ref(4);
+        // Overwritten: only the newest version is visible.
+        map.update(0, CowMapEntry::LocalOffset(0x100));
+        map.update(0, CowMapEntry::LocalOffset(0x900));
+        // Deleted.
+        map.update(2, CowMapEntry::Unallocated);
+
+        let live: Vec<(u32, CowMapEntry)> = map.iter().map(|(id, e)| (id, *e)).collect();
+        assert_eq!(
+            live,
+            vec![
+                (0, CowMapEntry::LocalOffset(0x900)),
+                (1, CowMapEntry::ParentRef),
+                (3, CowMapEntry::ParentRef),
+            ]
+        );
+    }
+
+    #[test]
+    fn range_scans_key_range() {
+        let mut map = CowMap::new_flat(10);
+        for id in 0..10 {
+            if id % 3 != 0 {
+                map.update(id, CowMapEntry::LocalOffset(id as u64 * 0x10));
+            }
+        }
+
+        let ids: Vec<u32> = map.range(2, 8).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![2, 4, 5, 7]);
+
+        // End past the map is clamped.
+        let ids: Vec<u32> = map.range(8, 100).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![8]);
+    }
+
+    #[test]
+    fn range_empty_cases() {
+        let mut map = CowMap::new_flat(4);
+        map.update(1, CowMapEntry::LocalOffset(0x10));
+
+        assert_eq!(map.range(1, 1).count(), 0);
+        assert_eq!(map.range(3, 1).count(), 0);
+        assert_eq!(map.range(50, 60).count(), 0);
+        assert_eq!(CowMap::new_flat(0).iter().count(), 0);
+        assert_eq!(CowMap::new_flat(5).iter().count(), 0);
+    }
+
+    #[test]
+    fn cloned_snapshot_is_unaffected_by_writes() {
+        let mut map = CowMap::new_flat(3);
+        map.update(0, CowMapEntry::LocalOffset(0x10));
+        let snapshot = map.clone();
+
+        map.update(0, CowMapEntry::Unallocated);
+        map.update(2, CowMapEntry::LocalOffset(0x30));
+
+        let ids: Vec<u32> = snapshot.iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![0]);
+        let ids: Vec<u32> = map.iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![2]);
+    }
 }
@@ -0,0 +1,304 @@
+//! Cuckoo filters for membership sets that need deletion.
+//!
+//! A cuckoo filter (Fan et al., 2014) stores a 16-bit fingerprint of each
+//! key in one of two candidate buckets of four slots. Unlike Bloom or XOR
+//! filters it can delete keys, which is what tombstone tracking needs as
+//! entries are compacted away.
+//!
+//! - No false negatives for keys that were inserted and not deleted.
+//! - False-positive rate is at most `2 * BUCKET_SIZE / 2^16` (~0.012%).
+//! - Deleting a key that was never inserted may remove a colliding key's
+//!   fingerprint; only delete keys known to be present.
+//! - Inserting the same key twice stores two fingerprints; delete it twice.
+//!
+//! When both candidate buckets are full, an insert relocates existing
+//! fingerprints for up to `MAX_KICKS` steps. If that fails, every
+//! relocation is undone and `MembershipFull` is returned, so the filter is
+//! never left missing a key.
+//!
+//! Wire format (after the `MembershipHeader`):
+//!   bucket_count(u32) | slots[bucket_count * BUCKET_SIZE] (u16 LE, 0 = empty)
+
+use crate::xor_filter::mix;
+use rvf_types::{ErrorCode, RvfError};
+
+/// Fingerprint slots per bucket.
+pub const BUCKET_SIZE: usize = 4;
+
+/// Relocations attempted before an insert reports the filter full.
+const MAX_KICKS: usize = 500;
+
+/// Target load factor used to size the table from a capacity.
+const TARGET_LOAD: f64 = 0.95;
+
+/// A cuckoo filter over 64-bit keys.
+#[derive(Clone, Debug)]
+pub struct CuckooFilter {
+    /// `bucket_count * BUCKET_SIZE` fingerprints; 0 marks an empty slot.
+    slots: Vec<u16>,
+    /// Power of two, so alternate buckets can be found with a mask.
+    bucket_count: usize,
+    len: u64,
+    /// Victim selection state for relocations.
+    rng: u64,
+}
+
+impl CuckooFilter {
+    /// Create an empty filter sized for about `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let buckets = ((capacity as f64 / (BUCKET_SIZE as f64 * TARGET_LOAD)).ceil() as usize)
+            .max(1)
+            .next_power_of_two();
+        Self {
+            slots: vec![0; buckets * BUCKET_SIZE],
+            bucket_count: buckets,
+            len: 0,
+            rng: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// Insert a key.
+    ///
+    /// Fails with `MembershipFull` when no slot can be freed; the filter is
+    /// unchanged in that case.
+    pub fn insert(&mut self, key: u64) -> Result<(), RvfError> {
+        let (fp, i1) = self.index_and_fingerprint(key);
+        let i2 = self.alt_index(i1, fp);
+        if self.try_place(i1, fp) || self.try_place(i2, fp) {
+            self.len += 1;
+            return Ok(());
+        }
+
+        let mut bucket = if self.next_random() & 1 == 0 { i1 } else { i2 };
+        let mut fp = fp;
+        let mut path: Vec<usize> = Vec::with_capacity(MAX_KICKS);
+        for _ in 0..MAX_KICKS {
+            let slot = bucket * BUCKET_SIZE + (self.next_random() as usize % BUCKET_SIZE);
+            core::mem::swap(&mut self.slots[slot], &mut fp);
+            path.push(slot);
+            bucket = self.alt_index(bucket, fp);
+            if self.try_place(bucket, fp) {
+                self.len += 1;
+                return Ok(());
+            }
+        }
+
+        // Undo the relocations so the evicted fingerprints are restored.
+        for &slot in path.iter().rev() {
+            core::mem::swap(&mut self.slots[slot], &mut fp);
+        }
+        Err(RvfError::Code(ErrorCode::MembershipFull))
+    }
+
+    /// Whether `key` may be in the set.
+    pub fn contains(&self, key: u64) -> bool {
+        let (fp, i1) = self.index_and_fingerprint(key);
+        let i2 = self.alt_index(i1, fp);
+        self.bucket(i1).contains(&fp) || self.bucket(i2).contains(&fp)
+    }
+
+    /// Remove one copy of `key`. Returns whether a fingerprint was removed.
+    pub fn delete(&mut self, key: u64) -> bool {
+        let (fp, i1) = self.index_and_fingerprint(key);
+        let i2 = self.alt_index(i1, fp);
+        for b in [i1, i2] {
+            let start = b * BUCKET_SIZE;
+            if let Some(pos) = self.slots[start..start + BUCKET_SIZE]
+                .iter()
+                .position(|&s| s == fp)
+            {
+                self.slots[start + pos] = 0;
+                self.len -= 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Number of stored fingerprints.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the filter holds no fingerprints.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Total number of slots.
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Fraction of slots in use.
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.slots.len() as f64
+    }
+
+    /// Serialize the filter (see the module docs for the layout).
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(4 + self.slots.len() * 2);
+        buf.extend_from_slice(&(self.bucket_count as u32).to_le_bytes());
+        for &s in &self.slots {
+            buf.extend_from_slice(&s.to_le_bytes());
+        }
+        buf
+    }
+
+    /// Deserialize a filter produced by [`CuckooFilter::serialize`].
+    pub fn deserialize(data: &[u8]) -> Result<Self, RvfError> {
+        if data.len() < 4 {
+            return Err(RvfError::Code(ErrorCode::MembershipInvalid));
+        }
+        let bucket_count = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        if !bucket_count.is_power_of_two() {
+            return Err(RvfError::Code(ErrorCode::MembershipInvalid));
+        }
+        let slot_count = bucket_count * BUCKET_SIZE;
+        if data.len() < 4 + slot_count * 2 {
+            return Err(RvfError::Code(ErrorCode::MembershipInvalid));
+        }
+        let slots: Vec<u16> = data[4..4 + slot_count * 2]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        let len = slots.iter().filter(|&&s| s != 0).count() as u64;
+        Ok(Self {
+            slots,
+            bucket_count,
+            len,
+            rng: 0x9E37_79B9_7F4A_7C15,
+        })
+    }
+
+    fn index_and_fingerprint(&self, key: u64) -> (u16, usize) {
+        let h = mix(key, 0);
+        // 0 marks an empty slot, so it is never a fingerprint.
+        let fp = ((h >> 48) as u16).max(1);
+        (fp, h as usize & (self.bucket_count - 1))
+    }
+
+    fn alt_index(&self, index: usize, fp: u16) -> usize {
+        let h = (fp as u64).wrapping_mul(0x5BD1_E995) as usize;
+        (index ^ h) & (self.bucket_count - 1)
+    }
+
+    fn bucket(&self, index: usize) -> &[u16] {
+        &self.slots[index * BUCKET_SIZE..(index + 1) * BUCKET_SIZE]
+    }
+
+    fn try_place(&mut self, index: usize, fp: u16) -> bool {
+        let start = index * BUCKET_SIZE;
+        match self.slots[start..start + BUCKET_SIZE]
+            .iter()
+            .position(|&s| s == 0)
+        {
+            Some(pos) => {
+                self.slots[start + pos] = fp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        // xorshift64
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(n: u64, offset: u64) -> Vec<u64> {
+        (0..n).map(|i| mix(i + offset, 0xC0FF_EE00)).collect()
+    }
+
+    #[test]
+    fn insert_delete_half() {
+        let all = keys(10_000, 0);
+        let mut filter = CuckooFilter::with_capacity(10_000);
+        for &k in &all {
+            filter.insert(k).unwrap();
+        }
+        assert_eq!(filter.len(), 10_000);
+
+        let (deleted, surviving) = all.split_at(5_000);
+        for &k in deleted {
+            assert!(filter.delete(k));
+        }
+        assert_eq!(filter.len(), 5_000);
+
+        // Surviving keys are always present.
+        assert!(surviving.iter().all(|&k| filter.contains(k)));
+
+        // Deleted keys are absent up to the false-positive rate
+        // (2 * 4 / 65536 ~ 0.012%).
+        let stale = deleted.iter().filter(|&&k| filter.contains(k)).count();
+        assert!(stale <= 5, "{stale} deleted keys still reported present");
+    }
+
+    #[test]
+    fn false_positive_rate_is_low() {
+        let mut filter = CuckooFilter::with_capacity(10_000);
+        for k in keys(10_000, 0) {
+            filter.insert(k).unwrap();
+        }
+        let probes = keys(100_000, 1 << 32);
+        let fp = probes.iter().filter(|&&k| filter.contains(k)).count();
+        assert!(fp < 30, "{fp} false positives in 100k probes");
+    }
+
+    #[test]
+    fn full_filter_reports_error_without_losing_keys() {
+        let mut filter = CuckooFilter::with_capacity(64);
+        let slots = filter.slot_count();
+        let mut inserted = Vec::new();
+        let mut full = None;
+        for k in keys(slots as u64 * 2, 7) {
+            match filter.insert(k) {
+                Ok(()) => inserted.push(k),
+                Err(e) => {
+                    full = Some(e);
+                    break;
+                }
+            }
+        }
+        assert_eq!(full, Some(RvfError::Code(ErrorCode::MembershipFull)));
+        assert_eq!(filter.len(), inserted.len() as u64);
+        assert!(inserted.len() <= slots);
+        // A failed insert must not evict anything.
+        assert!(inserted.iter().all(|&k| filter.contains(k)));
+    }
+
+    #[test]
+    fn duplicate_insert_needs_two_deletes() {
+        let mut filter = CuckooFilter::with_capacity(16);
+        filter.insert(42).unwrap();
+        filter.insert(42).unwrap();
+        assert!(filter.delete(42));
+        assert!(filter.contains(42));
+        assert!(filter.delete(42));
+        assert!(!filter.contains(42));
+        assert!(!filter.delete(42));
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let mut filter = CuckooFilter::with_capacity(1_000);
+        let ks = keys(800, 3);
+        for &k in &ks {
+            filter.insert(k).unwrap();
+        }
+        let bytes = filter.serialize();
+        let restored = CuckooFilter::deserialize(&bytes).unwrap();
+        assert_eq!(restored.len(), 800);
+        assert!(ks.iter().all(|&k| restored.contains(k)));
+        assert!(CuckooFilter::deserialize(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -8,8 +8,15 @@
 //! 5. fsync (deletion now visible to all new readers)
 //!
 //! Physical reclamation happens during compaction.
+//!
+//! A tombstone may carry an expiry (see `RvfStore::delete_with_ttl`).
+//! Compaction reclaims a tombstone and its vector only once it has expired
+//! and no cluster reference count from a REFCOUNT_SEG still shares it.
+//! Tombstones without an expiry are reclaimable immediately.
+
+use std::collections::{HashMap, HashSet};
 
-use std::collections::HashSet;
+use rvf_types::{RefcountHeader, RvfError};
 
 /// In-memory deletion bitmap.
 ///
@@ -18,12 +25,15 @@ use std::collections::HashSet;
 /// for correctness and clarity.
 pub(crate) struct DeletionBitmap {
     deleted: HashSet<u64>,
+    /// Expiry (Unix milliseconds) of tombstones deleted with a TTL.
+    expires_at: HashMap<u64, u64>,
 }
 
 impl DeletionBitmap {
     pub(crate) fn new() -> Self {
         Self {
             deleted: HashSet::new(),
+            expires_at: HashMap::new(),
         }
     }
 
@@ -31,9 +41,27 @@ impl DeletionBitmap {
     pub(crate) fn from_ids(ids: &[u64]) -> Self {
         Self {
             deleted: ids.iter().copied().collect(),
+            expires_at: HashMap::new(),
         }
     }
 
+    /// Set the expiry of an existing tombstone. Ignored if `id` is not deleted.
+    pub(crate) fn set_expiry(&mut self, id: u64, expires_at_ms: u64) {
+        if self.deleted.contains(&id) {
+            self.expires_at.insert(id, expires_at_ms);
+        }
+    }
+
+    /// Expiry of a tombstone, or `None` if it never had a TTL.
+    pub(crate) fn expiry(&self, id: u64) -> Option<u64> {
+        self.expires_at.get(&id).copied()
+    }
+
+    /// Whether the tombstone for `id` has expired at `now_ms`.
+    pub(crate) fn is_expired(&self, id: u64, now_ms: u64) -> bool {
+        self.expiry(id).is_none_or(|at| at <= now_ms)
+    }
+
     /// Mark a vector ID as soft-deleted.
     pub(crate) fn delete(&mut self, id: u64) {
         self.deleted.insert(id);
@@ -58,6 +86,7 @@ impl DeletionBitmap {
     pub(crate) fn clear_ids(&mut self, ids: &[u64]) {
         for &id in ids {
             self.deleted.remove(&id);
+            self.expires_at.remove(&id);
         }
     }
 
@@ -74,8 +103,58 @@ impl DeletionBitmap {
     }
 
     /// Clear all entries.
+    #[allow(dead_code)]
     pub(crate) fn clear(&mut self) {
         self.deleted.clear();
+        self.expires_at.clear();
+    }
+}
+
+/// Cluster reference counts parsed from a REFCOUNT_SEG payload.
+///
+/// Vector `id` lives in cluster `id / vectors_per_cluster`. A count above
+/// one means another snapshot or branch still shares the cluster.
+pub(crate) struct ClusterRefcounts {
+    vectors_per_cluster: u64,
+    counts: Vec<u32>,
+}
+
+impl ClusterRefcounts {
+    /// Parse `RefcountHeader` plus its refcount array.
+    pub(crate) fn parse(payload: &[u8], vectors_per_cluster: u64) -> Result<Self, RvfError> {
+        let truncated = |got| RvfError::SizeMismatch { expected: 32, got };
+        let header_bytes: &[u8; 32] = payload
+            .get(..32)
+            .and_then(|b| b.try_into().ok())
+            .ok_or(truncated(payload.len()))?;
+        let header = RefcountHeader::from_bytes(header_bytes)?;
+        let width = header.refcount_width as usize;
+        let start = header.array_offset as usize;
+        let end = start.saturating_add(header.cluster_count as usize * width);
+        let array = payload.get(start..end).ok_or(RvfError::SizeMismatch {
+            expected: end,
+            got: payload.len(),
+        })?;
+        let counts = array
+            .chunks_exact(width)
+            .map(|c| match width {
+                1 => c[0] as u32,
+                2 => u16::from_le_bytes([c[0], c[1]]) as u32,
+                _ => u32::from_le_bytes([c[0], c[1], c[2], c[3]]),
+            })
+            .collect();
+        Ok(Self {
+            vectors_per_cluster: vectors_per_cluster.max(1),
+            counts,
+        })
+    }
+
+    /// Whether the cluster holding `vector_id` is still shared.
+    pub(crate) fn is_shared(&self, vector_id: u64) -> bool {
+        usize::try_from(vector_id / self.vectors_per_cluster)
+            .ok()
+            .and_then(|cluster| self.counts.get(cluster))
+            .is_some_and(|&count| count > 1)
     }
 }
 
@@ -104,6 +183,46 @@ mod tests {
         assert!(bm.is_deleted(100));
     }
 
+    #[test]
+    fn tombstone_expiry() {
+        let mut bm = DeletionBitmap::new();
+        bm.delete_batch(&[1, 2]);
+        bm.set_expiry(2, 1_000);
+        bm.set_expiry(3, 1_000); // not deleted: ignored
+        assert!(bm.is_expired(1, 0));
+        assert!(!bm.is_expired(2, 999));
+        assert!(bm.is_expired(2, 1_000));
+        assert_eq!(bm.expiry(3), None);
+        bm.clear_ids(&[2]);
+        assert_eq!(bm.expiry(2), None);
+    }
+
+    #[test]
+    fn cluster_refcounts_parse() {
+        let header = RefcountHeader {
+            magic: rvf_types::REFCOUNT_MAGIC,
+            version: 1,
+            refcount_width: 2,
+            _pad: 0,
+            cluster_count: 3,
+            max_refcount: u16::MAX as u32,
+            array_offset: 32,
+            snapshot_epoch: 0,
+            _reserved: 0,
+        };
+        let mut payload = header.to_bytes().to_vec();
+        for count in [1u16, 2, 0] {
+            payload.extend_from_slice(&count.to_le_bytes());
+        }
+        let refs = ClusterRefcounts::parse(&payload, 4).unwrap();
+        assert!(!refs.is_shared(3));
+        assert!(refs.is_shared(4));
+        assert!(refs.is_shared(7));
+        assert!(!refs.is_shared(8));
+        assert!(!refs.is_shared(100));
+        assert!(ClusterRefcounts::parse(&payload[..36], 4).is_err());
+    }
+
     #[test]
     fn bitmap_from_ids() {
         let bm = DeletionBitmap::from_ids(&[1, 2, 3]);
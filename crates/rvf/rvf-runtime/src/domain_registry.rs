@@ -0,0 +1,171 @@
+//! Registry of domain profiles and their search defaults.
+//!
+//! The built-in [`DomainProfile`]s occupy ids `0..=4`. Applications with
+//! their own domain (say, geospatial tiles) can register a
+//! [`DomainPreset`] under any other id so that stores created for that
+//! domain pick up a default metric and compression profile. Built-in ids
+//! are reserved and cannot be shadowed.
+
+use std::collections::BTreeMap;
+
+use rvf_types::{DomainProfile, RvfError};
+
+use crate::options::{CompressionProfile, DistanceMetric, RvfOptions};
+
+/// Defaults associated with a domain profile id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainPreset {
+    /// Stable human-readable name.
+    pub name: String,
+    /// Default distance metric for stores in this domain.
+    pub metric: DistanceMetric,
+    /// Default compression profile for stores in this domain.
+    pub compression: CompressionProfile,
+}
+
+impl DomainPreset {
+    /// Create a preset.
+    pub fn new(name: &str, metric: DistanceMetric, compression: CompressionProfile) -> Self {
+        Self {
+            name: name.to_string(),
+            metric,
+            compression,
+        }
+    }
+
+    /// Copy this preset's defaults into `options`.
+    pub fn apply(&self, options: &mut RvfOptions) {
+        options.metric = self.metric;
+        options.compression = self.compression;
+    }
+}
+
+/// Domain presets keyed by profile id, with the built-ins preloaded.
+#[derive(Clone, Debug)]
+pub struct DomainRegistry {
+    presets: BTreeMap<u8, DomainPreset>,
+}
+
+impl Default for DomainRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DomainRegistry {
+    /// Create a registry holding the presets of every built-in profile.
+    pub fn new() -> Self {
+        let presets = DomainProfile::ALL
+            .into_iter()
+            .map(|p| (p as u8, builtin_preset(p)))
+            .collect();
+        Self { presets }
+    }
+
+    /// Register `preset` under a custom profile id.
+    ///
+    /// Replaces an earlier custom registration with the same id. Fails with
+    /// `InvalidEnumValue` if `id` belongs to a built-in `DomainProfile`.
+    pub fn register(&mut self, id: u8, preset: DomainPreset) -> Result<(), RvfError> {
+        if DomainProfile::try_from(id).is_ok() {
+            return Err(RvfError::InvalidEnumValue {
+                type_name: "DomainProfile",
+                value: id as u64,
+            });
+        }
+        self.presets.insert(id, preset);
+        Ok(())
+    }
+
+    /// The preset registered under `id`.
+    pub fn get(&self, id: u8) -> Option<&DomainPreset> {
+        self.presets.get(&id)
+    }
+
+    /// The preset of a built-in profile.
+    pub fn builtin(&self, profile: DomainProfile) -> &DomainPreset {
+        &self.presets[&(profile as u8)]
+    }
+
+    /// Look up a preset by name (case-insensitive).
+    pub fn find_by_name(&self, name: &str) -> Option<(u8, &DomainPreset)> {
+        self.presets
+            .iter()
+            .find(|(_, p)| p.name.eq_ignore_ascii_case(name))
+            .map(|(&id, p)| (id, p))
+    }
+
+    /// Iterate over all presets in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &DomainPreset)> {
+        self.presets.iter().map(|(&id, p)| (id, p))
+    }
+}
+
+/// Defaults for the built-in profiles. Text and vision embeddings are
+/// usually compared by angle; genomic and graph embeddings by distance.
+fn builtin_preset(profile: DomainProfile) -> DomainPreset {
+    let (name, metric) = match profile {
+        DomainProfile::Generic => ("generic", DistanceMetric::L2),
+        DomainProfile::Rvdna => ("rvdna", DistanceMetric::L2),
+        DomainProfile::RvText => ("rvtext", DistanceMetric::Cosine),
+        DomainProfile::RvGraph => ("rvgraph", DistanceMetric::InnerProduct),
+        DomainProfile::RvVision => ("rvvision", DistanceMetric::Cosine),
+    };
+    DomainPreset::new(name, metric, CompressionProfile::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEO_TILES: u8 = 0x40;
+
+    fn geo_preset() -> DomainPreset {
+        DomainPreset::new("geotiles", DistanceMetric::L2, CompressionProfile::Scalar)
+    }
+
+    #[test]
+    fn builtins_are_preloaded() {
+        let registry = DomainRegistry::new();
+        assert_eq!(registry.iter().count(), DomainProfile::ALL.len());
+        for p in DomainProfile::ALL {
+            assert_eq!(registry.get(p as u8), Some(registry.builtin(p)));
+        }
+        assert_eq!(
+            registry.builtin(DomainProfile::RvText).metric,
+            DistanceMetric::Cosine
+        );
+    }
+
+    #[test]
+    fn register_and_look_up_custom_profile() {
+        let mut registry = DomainRegistry::new();
+        assert!(registry.get(GEO_TILES).is_none());
+        registry.register(GEO_TILES, geo_preset()).unwrap();
+
+        assert_eq!(registry.get(GEO_TILES), Some(&geo_preset()));
+        assert_eq!(
+            registry.find_by_name("GeoTiles"),
+            Some((GEO_TILES, &geo_preset()))
+        );
+
+        let mut options = RvfOptions::default();
+        registry.get(GEO_TILES).unwrap().apply(&mut options);
+        assert_eq!(options.compression, CompressionProfile::Scalar);
+    }
+
+    #[test]
+    fn builtin_ids_cannot_be_shadowed() {
+        let mut registry = DomainRegistry::new();
+        for p in DomainProfile::ALL {
+            assert_eq!(
+                registry.register(p as u8, geo_preset()),
+                Err(RvfError::InvalidEnumValue {
+                    type_name: "DomainProfile",
+                    value: p as u64,
+                })
+            );
+        }
+        assert_eq!(registry.builtin(DomainProfile::Rvdna).name, "rvdna");
+    }
+}
@@ -6,12 +6,41 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
+/// Tuning for [`BudgetTokenBucket::with_adaptive`].
+///
+/// The bucket keeps an exponentially weighted average of the adversarial
+/// rate fed to [`BudgetTokenBucket::observe_adversarial_rate`]. Above
+/// `tighten_threshold` the per-window budget shrinks linearly, reaching
+/// `min_fraction` of the configured budget when every query is adversarial.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveBudgetConfig {
+    /// Adversarial rate (0.0..=1.0) above which the budget is tightened.
+    pub tighten_threshold: f64,
+    /// Smallest fraction of the configured budget the bucket shrinks to.
+    pub min_fraction: f64,
+    /// Weight (0.0..=1.0) given to each new observation in the average.
+    pub smoothing: f64,
+}
+
+impl Default for AdaptiveBudgetConfig {
+    fn default() -> Self {
+        Self {
+            tighten_threshold: 0.1,
+            min_fraction: 0.1,
+            smoothing: 0.3,
+        }
+    }
+}
+
 /// Per-connection token bucket for rate-limiting distance operations.
 ///
 /// Each query consumes tokens from the bucket. When tokens are exhausted,
 /// queries are rejected until the bucket refills.
 pub struct BudgetTokenBucket {
-    /// Maximum tokens (distance ops) per window.
+    /// Configured tokens (distance ops) per window.
+    base_tokens: u64,
+    /// Effective tokens per window; below `base_tokens` while adaptive
+    /// mode is tightening.
     max_tokens: u64,
     /// Current available tokens.
     tokens: u64,
@@ -19,6 +48,10 @@ pub struct BudgetTokenBucket {
     window: Duration,
     /// Start of current window.
     window_start: Instant,
+    /// Adaptive tuning, if enabled.
+    adaptive: Option<AdaptiveBudgetConfig>,
+    /// Smoothed adversarial rate observed so far.
+    adversarial_rate: f64,
 }
 
 impl BudgetTokenBucket {
@@ -29,13 +62,64 @@ impl BudgetTokenBucket {
     /// * `window` - Duration of each refill window.
     pub fn new(max_tokens: u64, window: Duration) -> Self {
         Self {
+            base_tokens: max_tokens,
             max_tokens,
             tokens: max_tokens,
             window,
             window_start: Instant::now(),
+            adaptive: None,
+            adversarial_rate: 0.0,
         }
     }
 
+    /// Enable adaptive mode, which scales the per-window budget down while
+    /// the observed adversarial rate is high and back up as it subsides.
+    pub fn with_adaptive(mut self, config: AdaptiveBudgetConfig) -> Self {
+        self.adaptive = Some(config);
+        self
+    }
+
+    /// Feed back the fraction (0.0..=1.0) of recent queries flagged as
+    /// adversarial, e.g. rejected via `QuerySignature` / `NegativeCache`.
+    ///
+    /// Ignored unless adaptive mode is enabled. Tightening takes effect
+    /// immediately; the current window keeps no more than the new budget.
+    pub fn observe_adversarial_rate(&mut self, rate: f64) {
+        let Some(config) = self.adaptive else {
+            return;
+        };
+        let rate = if rate.is_nan() {
+            0.0
+        } else {
+            rate.clamp(0.0, 1.0)
+        };
+        let alpha = config.smoothing.clamp(0.0, 1.0);
+        self.adversarial_rate = alpha * rate + (1.0 - alpha) * self.adversarial_rate;
+
+        let threshold = config.tighten_threshold.clamp(0.0, 1.0);
+        let min_fraction = config.min_fraction.clamp(0.0, 1.0);
+        let scale = if self.adversarial_rate <= threshold || threshold >= 1.0 {
+            1.0
+        } else {
+            let excess = (self.adversarial_rate - threshold) / (1.0 - threshold);
+            1.0 - excess * (1.0 - min_fraction)
+        };
+        self.max_tokens = ((self.base_tokens as f64 * scale).round() as u64)
+            .max(1)
+            .min(self.base_tokens);
+        self.tokens = self.tokens.min(self.max_tokens);
+    }
+
+    /// Smoothed adversarial rate used by adaptive mode.
+    pub fn adversarial_rate(&self) -> f64 {
+        self.adversarial_rate
+    }
+
+    /// Tokens granted per window after adaptive scaling.
+    pub fn effective_max_tokens(&self) -> u64 {
+        self.max_tokens
+    }
+
     /// Try to consume `cost` tokens. Returns `Ok(remaining)` if sufficient
     /// tokens are available, `Err(deficit)` if not.
     pub fn try_consume(&mut self, cost: u64) -> Result<u64, u64> {
@@ -288,6 +372,39 @@ mod tests {
         assert_eq!(bucket.remaining(), 100);
     }
 
+    #[test]
+    fn adaptive_bucket_tightens_under_attack_and_recovers() {
+        let mut bucket = BudgetTokenBucket::new(1000, Duration::from_secs(60))
+            .with_adaptive(AdaptiveBudgetConfig::default());
+        bucket.observe_adversarial_rate(0.02);
+        assert_eq!(bucket.effective_max_tokens(), 1000);
+
+        // Attack burst: most queries hit blacklisted signatures.
+        for _ in 0..10 {
+            bucket.observe_adversarial_rate(0.9);
+        }
+        let tightened = bucket.effective_max_tokens();
+        assert!(tightened < 300, "budget only dropped to {tightened}");
+        assert!(tightened >= 100);
+        assert!(bucket.remaining() <= tightened);
+
+        // Attack subsides.
+        for _ in 0..30 {
+            bucket.observe_adversarial_rate(0.0);
+        }
+        assert_eq!(bucket.effective_max_tokens(), 1000);
+        bucket.refill();
+        assert_eq!(bucket.remaining(), 1000);
+    }
+
+    #[test]
+    fn non_adaptive_bucket_ignores_feedback() {
+        let mut bucket = BudgetTokenBucket::new(100, Duration::from_secs(60));
+        bucket.observe_adversarial_rate(1.0);
+        assert_eq!(bucket.effective_max_tokens(), 100);
+        assert_eq!(bucket.adversarial_rate(), 0.0);
+    }
+
     #[test]
     fn query_signature_deterministic() {
         let query = vec![0.1, 0.2, 0.3, 0.4];
@@ -0,0 +1,202 @@
+//! Segment-level encryption at rest (AES-256-GCM).
+//!
+//! When a store is configured with an [`EncryptionConfig`], the payloads of
+//! data-bearing segments (VEC, INDEX, META) are encrypted on write. The
+//! segment header itself stays in plaintext so tools can walk the file
+//! layout without the key:
+//!
+//! - `flags` carries `SegmentFlags::ENCRYPTED`
+//! - `payload_length` covers the ciphertext plus the 16-byte GCM tag
+//! - the 96-bit nonce is `timestamp_ns` (8 bytes LE) followed by
+//!   `reserved_1` (4 random bytes LE)
+//! - `content_hash` is computed over the ciphertext
+//!
+//! The segment type and ID are bound as associated data, so a ciphertext
+//! cannot be transplanted into another segment. Requires the `encryption`
+//! feature; without it, encrypted stores cannot be created or read.
+
+use rvf_types::{ErrorCode, RvfError, SegmentFlags, SegmentHeader, SegmentType};
+use std::fmt;
+use std::io;
+
+/// Size of the GCM authentication tag appended to each encrypted payload.
+pub const GCM_TAG_SIZE: usize = 16;
+
+/// Key material for segment encryption.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionConfig {
+    key: [u8; 32],
+}
+
+impl EncryptionConfig {
+    /// Use the given 256-bit key directly.
+    pub fn from_key(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+}
+
+impl fmt::Debug for EncryptionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionConfig")
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Whether encryption support was compiled in.
+pub(crate) const fn is_supported() -> bool {
+    cfg!(feature = "encryption")
+}
+
+/// Whether payloads of this segment type are encrypted.
+pub(crate) fn is_encrypted_type(seg_type: u8) -> bool {
+    seg_type == SegmentType::Vec as u8
+        || seg_type == SegmentType::Index as u8
+        || seg_type == SegmentType::Meta as u8
+}
+
+/// Associated data binding a ciphertext to its segment.
+#[cfg_attr(not(feature = "encryption"), allow(dead_code))]
+fn associated_data(header: &SegmentHeader) -> [u8; 9] {
+    let mut aad = [0u8; 9];
+    aad[0] = header.seg_type;
+    aad[1..].copy_from_slice(&header.segment_id.to_le_bytes());
+    aad
+}
+
+#[cfg_attr(not(feature = "encryption"), allow(dead_code))]
+fn nonce_bytes(header: &SegmentHeader) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..8].copy_from_slice(&header.timestamp_ns.to_le_bytes());
+    nonce[8..].copy_from_slice(&header.reserved_1.to_le_bytes());
+    nonce
+}
+
+/// Encrypt `payload`, recording the nonce and ENCRYPTED flag in `header`.
+#[cfg(feature = "encryption")]
+pub(crate) fn encrypt_payload(
+    config: &EncryptionConfig,
+    header: &mut SegmentHeader,
+    payload: &[u8],
+) -> io::Result<Vec<u8>> {
+    use aes_gcm::aead::rand_core::RngCore;
+    use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    header.timestamp_ns = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    header.reserved_1 = OsRng.next_u32();
+    header.flags |= SegmentFlags::ENCRYPTED;
+
+    let cipher = Aes256Gcm::new(&config.key.into());
+    let nonce = nonce_bytes(header);
+    let aad = associated_data(header);
+    cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: payload,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| io::Error::other("segment encryption failed"))
+}
+
+/// Encrypt `payload` (unavailable without the `encryption` feature).
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn encrypt_payload(
+    _config: &EncryptionConfig,
+    _header: &mut SegmentHeader,
+    _payload: &[u8],
+) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "segment encryption requires the `encryption` feature",
+    ))
+}
+
+/// Return the plaintext payload of a segment read from disk.
+///
+/// Unencrypted segments pass through unchanged. Encrypted segments fail
+/// with `DecryptFailed` if no key is configured, the key is wrong, or the
+/// ciphertext was tampered with.
+pub(crate) fn decrypt_payload(
+    config: Option<&EncryptionConfig>,
+    header: &SegmentHeader,
+    payload: Vec<u8>,
+) -> Result<Vec<u8>, RvfError> {
+    if header.flags & SegmentFlags::ENCRYPTED == 0 {
+        return Ok(payload);
+    }
+    let config = config.ok_or(RvfError::Code(ErrorCode::DecryptFailed))?;
+    decrypt_with(config, header, &payload)
+}
+
+#[cfg(feature = "encryption")]
+fn decrypt_with(
+    config: &EncryptionConfig,
+    header: &SegmentHeader,
+    payload: &[u8],
+) -> Result<Vec<u8>, RvfError> {
+    use aes_gcm::aead::{Aead, KeyInit, Payload};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    let cipher = Aes256Gcm::new(&config.key.into());
+    let nonce = nonce_bytes(header);
+    let aad = associated_data(header);
+    cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: payload,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| RvfError::Code(ErrorCode::DecryptFailed))
+}
+
+#[cfg(not(feature = "encryption"))]
+fn decrypt_with(
+    _config: &EncryptionConfig,
+    _header: &SegmentHeader,
+    _payload: &[u8],
+) -> Result<Vec<u8>, RvfError> {
+    Err(RvfError::Code(ErrorCode::AlgoUnsupported))
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_and_tamper_detection() {
+        let config = EncryptionConfig::from_key([7u8; 32]);
+        let mut header = SegmentHeader::new(SegmentType::Vec as u8, 3);
+        let ciphertext = encrypt_payload(&config, &mut header, b"vector payload").unwrap();
+        assert_eq!(ciphertext.len(), 14 + GCM_TAG_SIZE);
+        assert_ne!(header.flags & SegmentFlags::ENCRYPTED, 0);
+
+        let plain = decrypt_payload(Some(&config), &header, ciphertext.clone()).unwrap();
+        assert_eq!(plain, b"vector payload");
+
+        // Moving the ciphertext to another segment breaks authentication.
+        let mut moved = header;
+        moved.segment_id = 4;
+        assert_eq!(
+            decrypt_payload(Some(&config), &moved, ciphertext.clone()),
+            Err(RvfError::Code(ErrorCode::DecryptFailed))
+        );
+        assert_eq!(
+            decrypt_payload(None, &header, ciphertext),
+            Err(RvfError::Code(ErrorCode::DecryptFailed))
+        );
+    }
+
+    #[test]
+    fn debug_redacts_key() {
+        let config = EncryptionConfig::from_key([0xAB; 32]);
+        assert!(!format!("{config:?}").contains("171"));
+    }
+}
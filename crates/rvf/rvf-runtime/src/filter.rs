@@ -4,8 +4,16 @@
 //! per-vector metadata. The runtime selects a strategy (pre-filter,
 //! intra-filter, or post-filter) based on estimated selectivity.
 
+use rvf_types::{ErrorCode, RvfError};
+
 use crate::options::MetadataValue;
 
+/// Maximum nesting depth accepted for a filter expression.
+///
+/// Evaluation recurses through `And`/`Or`/`Not` nodes, so deeper trees are
+/// rejected up front with `FilterParseError` rather than risking the stack.
+pub const MAX_FILTER_DEPTH: usize = 64;
+
 /// A filter expression for metadata-based vector filtering.
 ///
 /// Leaf nodes compare a metadata field against a literal value.
@@ -28,6 +36,24 @@ pub enum FilterExpr {
     In(u16, Vec<FilterValue>),
     /// field in [low, high)
     Range(u16, FilterValue, FilterValue),
+    /// field between optional bounds, each inclusive or exclusive.
+    ///
+    /// A `None` bound is unbounded. Integer and float values compare
+    /// numerically across types. A missing field, or one that cannot be
+    /// compared with a bound, does not match; with neither bound set, only
+    /// numeric fields match.
+    Interval {
+        /// Metadata field to test.
+        field: u16,
+        /// Lower bound, if any.
+        lower: Option<FilterValue>,
+        /// Upper bound, if any.
+        upper: Option<FilterValue>,
+        /// Whether `field == lower` matches.
+        inclusive_lower: bool,
+        /// Whether `field == upper` matches.
+        inclusive_upper: bool,
+    },
     /// All sub-expressions must match.
     And(Vec<FilterExpr>),
     /// Any sub-expression must match.
@@ -36,6 +62,35 @@ pub enum FilterExpr {
     Not(Box<FilterExpr>),
 }
 
+impl FilterExpr {
+    /// Nesting depth of the expression; a leaf predicate has depth 1.
+    ///
+    /// Computed iteratively, so it is safe to call on arbitrarily deep trees.
+    pub fn depth(&self) -> usize {
+        let mut max = 0;
+        let mut stack = vec![(self, 1usize)];
+        while let Some((expr, depth)) = stack.pop() {
+            max = max.max(depth);
+            match expr {
+                FilterExpr::And(exprs) | FilterExpr::Or(exprs) => {
+                    stack.extend(exprs.iter().map(|e| (e, depth + 1)));
+                }
+                FilterExpr::Not(inner) => stack.push((inner, depth + 1)),
+                _ => {}
+            }
+        }
+        max
+    }
+
+    /// Reject expressions nested deeper than [`MAX_FILTER_DEPTH`].
+    pub fn validate(&self) -> Result<(), RvfError> {
+        if self.depth() > MAX_FILTER_DEPTH {
+            return Err(RvfError::Code(ErrorCode::FilterParseError));
+        }
+        Ok(())
+    }
+}
+
 /// A typed value used in filter comparisons.
 #[derive(Clone, Debug, PartialEq)]
 pub enum FilterValue {
@@ -48,16 +103,40 @@ pub enum FilterValue {
 
 impl FilterValue {
     /// Compare two filter values. Returns None if types are incompatible.
+    ///
+    /// Numeric types compare by value: `U64` and `I64` exactly, and either
+    /// against `F64` as floats. NaN compares with nothing.
     fn partial_cmp_value(&self, other: &Self) -> Option<std::cmp::Ordering> {
         match (self, other) {
             (FilterValue::U64(a), FilterValue::U64(b)) => a.partial_cmp(b),
             (FilterValue::I64(a), FilterValue::I64(b)) => a.partial_cmp(b),
             (FilterValue::F64(a), FilterValue::F64(b)) => a.partial_cmp(b),
+            (FilterValue::U64(a), FilterValue::I64(b)) => (*a as i128).partial_cmp(&(*b as i128)),
+            (FilterValue::I64(a), FilterValue::U64(b)) => (*a as i128).partial_cmp(&(*b as i128)),
+            (FilterValue::U64(a), FilterValue::F64(b)) => (*a as f64).partial_cmp(b),
+            (FilterValue::I64(a), FilterValue::F64(b)) => (*a as f64).partial_cmp(b),
+            (FilterValue::F64(a), FilterValue::U64(b)) => a.partial_cmp(&(*b as f64)),
+            (FilterValue::F64(a), FilterValue::I64(b)) => a.partial_cmp(&(*b as f64)),
             (FilterValue::String(a), FilterValue::String(b)) => a.partial_cmp(b),
             (FilterValue::Bool(a), FilterValue::Bool(b)) => a.partial_cmp(b),
             _ => None,
         }
     }
+
+    /// Equality under the same cross-type rules as ordering, so `U64(50)`
+    /// equals `F64(50.0)` and NaN equals nothing.
+    fn eq_value(&self, other: &Self) -> bool {
+        self.partial_cmp_value(other) == Some(std::cmp::Ordering::Equal)
+    }
+
+    /// Whether the value is a number that compares with other numbers.
+    fn is_numeric(&self) -> bool {
+        match self {
+            FilterValue::U64(_) | FilterValue::I64(_) => true,
+            FilterValue::F64(f) => !f.is_nan(),
+            FilterValue::String(_) | FilterValue::Bool(_) => false,
+        }
+    }
 }
 
 /// In-memory metadata store for filter evaluation.
@@ -94,6 +173,12 @@ impl MetadataStore {
             .map(|(_, v)| v)
     }
 
+    /// Get all (field_id, value) pairs stored for a vector.
+    pub(crate) fn get_all(&self, vector_id: u64) -> Option<&[(u16, FilterValue)]> {
+        let pos = self.id_to_pos.get(&vector_id)?;
+        self.entries.get(*pos).map(|fields| fields.as_slice())
+    }
+
     /// Remove all metadata for the given vector IDs.
     pub(crate) fn remove_ids(&mut self, ids: &[u64]) {
         for id in ids {
@@ -113,11 +198,11 @@ pub(crate) fn evaluate(expr: &FilterExpr, vector_id: u64, meta: &MetadataStore)
     match expr {
         FilterExpr::Eq(field_id, val) => meta
             .get_field(vector_id, *field_id)
-            .map(|v| v == val)
+            .map(|v| v.eq_value(val))
             .unwrap_or(false),
         FilterExpr::Ne(field_id, val) => meta
             .get_field(vector_id, *field_id)
-            .map(|v| v != val)
+            .map(|v| !v.eq_value(val))
             .unwrap_or(true),
         FilterExpr::Lt(field_id, val) => meta
             .get_field(vector_id, *field_id)
@@ -141,7 +226,7 @@ pub(crate) fn evaluate(expr: &FilterExpr, vector_id: u64, meta: &MetadataStore)
             .unwrap_or(false),
         FilterExpr::In(field_id, vals) => meta
             .get_field(vector_id, *field_id)
-            .map(|v| vals.contains(v))
+            .map(|v| vals.iter().any(|val| v.eq_value(val)))
             .unwrap_or(false),
         FilterExpr::Range(field_id, low, high) => meta
             .get_field(vector_id, *field_id)
@@ -155,6 +240,38 @@ pub(crate) fn evaluate(expr: &FilterExpr, vector_id: u64, meta: &MetadataStore)
                 Some(ge_low && lt_high)
             })
             .unwrap_or(false),
+        FilterExpr::Interval {
+            field,
+            lower,
+            upper,
+            inclusive_lower,
+            inclusive_upper,
+        } => meta
+            .get_field(vector_id, *field)
+            .and_then(|v| {
+                use std::cmp::Ordering;
+                if lower.is_none() && upper.is_none() {
+                    return Some(v.is_numeric());
+                }
+                let above = match lower {
+                    Some(low) => match v.partial_cmp_value(low)? {
+                        Ordering::Greater => true,
+                        Ordering::Equal => *inclusive_lower,
+                        Ordering::Less => false,
+                    },
+                    None => true,
+                };
+                let below = match upper {
+                    Some(high) => match v.partial_cmp_value(high)? {
+                        Ordering::Less => true,
+                        Ordering::Equal => *inclusive_upper,
+                        Ordering::Greater => false,
+                    },
+                    None => true,
+                };
+                Some(above && below)
+            })
+            .unwrap_or(false),
         FilterExpr::And(exprs) => exprs.iter().all(|e| evaluate(e, vector_id, meta)),
         FilterExpr::Or(exprs) => exprs.iter().any(|e| evaluate(e, vector_id, meta)),
         FilterExpr::Not(expr) => !evaluate(expr, vector_id, meta),
@@ -172,6 +289,17 @@ pub(crate) fn metadata_value_to_filter(mv: &MetadataValue) -> FilterValue {
     }
 }
 
+/// Convert a stored FilterValue back to a MetadataValue for callers.
+pub(crate) fn filter_value_to_metadata(fv: &FilterValue) -> MetadataValue {
+    match fv {
+        FilterValue::U64(v) => MetadataValue::U64(*v),
+        FilterValue::I64(v) => MetadataValue::I64(*v),
+        FilterValue::F64(v) => MetadataValue::F64(*v),
+        FilterValue::String(v) => MetadataValue::String(v.clone()),
+        FilterValue::Bool(v) => MetadataValue::U64(*v as u64),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +387,152 @@ mod tests {
         assert!(!evaluate(&expr, 1, &store));
         assert!(evaluate(&expr, 2, &store));
     }
+
+    fn price_store() -> MetadataStore {
+        let mut store = MetadataStore::new();
+        store.insert(0, vec![(1, FilterValue::U64(10))]);
+        store.insert(1, vec![(1, FilterValue::F64(49.5))]);
+        store.insert(2, vec![(1, FilterValue::I64(50))]);
+        store.insert(3, vec![(1, FilterValue::String("cheap".into()))]);
+        store.insert(4, vec![(0, FilterValue::U64(20))]);
+        store
+    }
+
+    fn price_between(
+        lower: u64,
+        upper: u64,
+        inclusive_lower: bool,
+        inclusive_upper: bool,
+    ) -> FilterExpr {
+        FilterExpr::Interval {
+            field: 1,
+            lower: Some(FilterValue::U64(lower)),
+            upper: Some(FilterValue::U64(upper)),
+            inclusive_lower,
+            inclusive_upper,
+        }
+    }
+
+    #[test]
+    fn filter_interval_bounds() {
+        let store = price_store();
+        let matching = |expr: &FilterExpr| -> Vec<u64> {
+            (0..5).filter(|&id| evaluate(expr, id, &store)).collect()
+        };
+        // price >= 10 AND price < 50, across u64, f64 and i64 values.
+        assert_eq!(matching(&price_between(10, 50, true, false)), vec![0, 1]);
+        assert_eq!(matching(&price_between(10, 50, false, false)), vec![1]);
+        assert_eq!(matching(&price_between(10, 50, true, true)), vec![0, 1, 2]);
+        assert_eq!(matching(&price_between(10, 50, false, true)), vec![1, 2]);
+
+        let at_least_float = FilterExpr::Interval {
+            field: 1,
+            lower: Some(FilterValue::F64(49.5)),
+            upper: None,
+            inclusive_lower: true,
+            inclusive_upper: false,
+        };
+        assert_eq!(matching(&at_least_float), vec![1, 2]);
+
+        let negative = FilterExpr::Interval {
+            field: 1,
+            lower: Some(FilterValue::I64(-5)),
+            upper: None,
+            inclusive_lower: false,
+            inclusive_upper: false,
+        };
+        assert_eq!(matching(&negative), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn filter_interval_missing_or_mismatched_field() {
+        let store = price_store();
+        let unbounded = FilterExpr::Interval {
+            field: 1,
+            lower: None,
+            upper: None,
+            inclusive_lower: false,
+            inclusive_upper: false,
+        };
+        // Vector 4 has no price; vector 3's price is a string.
+        assert!(!evaluate(&price_between(0, 100, true, true), 4, &store));
+        assert!(!evaluate(&price_between(0, 100, true, true), 3, &store));
+        assert!(!evaluate(&unbounded, 3, &store));
+        assert!(!evaluate(&unbounded, 4, &store));
+        assert!((0..3).all(|id| evaluate(&unbounded, id, &store)));
+    }
+
+    #[test]
+    fn filter_eq_compares_numbers_across_types() {
+        let store = price_store();
+        let fifty = FilterValue::F64(50.0);
+        assert!(evaluate(&FilterExpr::Eq(1, fifty.clone()), 2, &store));
+        assert!(!evaluate(&FilterExpr::Ne(1, fifty), 2, &store));
+        assert!(evaluate(
+            &FilterExpr::Eq(1, FilterValue::F64(10.0)),
+            0,
+            &store
+        ));
+        assert!(!evaluate(
+            &FilterExpr::Eq(1, FilterValue::U64(49)),
+            1,
+            &store
+        ));
+        let expr = FilterExpr::In(1, vec![FilterValue::I64(10), FilterValue::U64(50)]);
+        assert!(evaluate(&expr, 0, &store));
+        assert!(evaluate(&expr, 2, &store));
+        assert!(!evaluate(&expr, 1, &store));
+
+        // NaN equals nothing, itself included.
+        let mut nan = MetadataStore::new();
+        nan.insert(0, vec![(1, FilterValue::F64(f64::NAN))]);
+        assert!(!evaluate(
+            &FilterExpr::Eq(1, FilterValue::F64(f64::NAN)),
+            0,
+            &nan
+        ));
+    }
+
+    #[test]
+    fn filter_nested_and_or() {
+        let store = make_store();
+        // (fruit == "apple" OR fruit == "banana") AND NOT price < 150
+        let expr = FilterExpr::And(vec![
+            FilterExpr::Or(vec![
+                FilterExpr::Eq(0, FilterValue::String("apple".into())),
+                FilterExpr::Eq(0, FilterValue::String("banana".into())),
+            ]),
+            FilterExpr::Not(Box::new(FilterExpr::Lt(1, FilterValue::U64(150)))),
+        ]);
+        let matching: Vec<u64> = (0..4).filter(|&id| evaluate(&expr, id, &store)).collect();
+        assert_eq!(matching, vec![1, 2]);
+        assert_eq!(expr.depth(), 3);
+        assert!(expr.validate().is_ok());
+    }
+
+    #[test]
+    fn filter_double_negation() {
+        let store = make_store();
+        let leaf = FilterExpr::Eq(0, FilterValue::String("apple".into()));
+        let expr = FilterExpr::Not(Box::new(FilterExpr::Not(Box::new(leaf.clone()))));
+        for id in 0..4 {
+            assert_eq!(evaluate(&expr, id, &store), evaluate(&leaf, id, &store));
+        }
+    }
+
+    #[test]
+    fn filter_depth_guard() {
+        let mut expr = FilterExpr::Eq(0, FilterValue::U64(1));
+        for _ in 1..MAX_FILTER_DEPTH {
+            expr = FilterExpr::Not(Box::new(expr));
+        }
+        assert_eq!(expr.depth(), MAX_FILTER_DEPTH);
+        assert!(expr.validate().is_ok());
+
+        let expr = FilterExpr::And(vec![expr]);
+        assert_eq!(
+            expr.validate(),
+            Err(RvfError::Code(ErrorCode::FilterParseError))
+        );
+    }
 }
@@ -0,0 +1,255 @@
+//! Write-ahead intent log for crash-consistent appends.
+//!
+//! A mutation appends one or more segments and then a manifest that makes
+//! them visible. If the process dies part way, the file ends in a torn
+//! segment or an unreferenced tail, and a large enough tail can hide the
+//! previous manifest from the tail scan in `read_path`.
+//!
+//! Before the first append of a mutation the store records an intent
+//! ("about to append segment X at offset Y") in a sidecar file at
+//! `{path}.intent`, and appends a commit record once the manifest is
+//! durable. On open, an intent without a commit means the append never
+//! completed: the file is truncated back to the recorded offset, which is
+//! the end of the last committed manifest. The recorded segment ID guards
+//! against applying a stale log to a file that was replaced: if a complete
+//! segment header at the offset carries a different ID, nothing is cut.
+//!
+//! Each record is 24 bytes, little-endian:
+//!
+//! | Offset | Size | Field        |
+//! |--------|------|--------------|
+//! | 0x00   | 4    | magic "RVIL" |
+//! | 0x04   | 1    | kind         |
+//! | 0x05   | 3    | reserved     |
+//! | 0x08   | 8    | segment_id   |
+//! | 0x10   | 8    | offset       |
+//!
+//! A torn trailing record is ignored. The intent record is synced before
+//! any segment byte is written, so a torn intent means nothing was appended.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use rvf_types::SEGMENT_MAGIC;
+
+/// The intent log magic: "RVIL" in ASCII (big-endian).
+const INTENT_MAGIC: u32 = 0x5256494C;
+
+/// Size of one log record in bytes.
+const RECORD_SIZE: usize = 24;
+
+/// Record kind: an append is about to start.
+const KIND_INTENT: u8 = 1;
+
+/// Record kind: the manifest covering the append is durable.
+const KIND_COMMIT: u8 = 2;
+
+/// An append that was started but never committed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PendingAppend {
+    /// ID of the first segment the append was going to write.
+    pub segment_id: u64,
+    /// File length before the append began.
+    pub offset: u64,
+}
+
+/// Sidecar log recording in-flight appends for one RVF file.
+pub(crate) struct IntentLog {
+    log_path: PathBuf,
+    pending: bool,
+}
+
+impl IntentLog {
+    /// Start a log for a freshly created file, discarding any stale log
+    /// left behind by an earlier file at the same path.
+    pub(crate) fn create(rvf_path: &Path) -> io::Result<Self> {
+        let log_path = intent_path_for(rvf_path);
+        remove_if_exists(&log_path)?;
+        Ok(Self {
+            log_path,
+            pending: false,
+        })
+    }
+
+    /// Roll back an uncommitted append left by a crashed writer.
+    ///
+    /// Truncates `file` to the offset recorded by the pending intent, if
+    /// any, and returns what was discarded.
+    pub(crate) fn recover(
+        rvf_path: &Path,
+        file: &File,
+    ) -> io::Result<(Self, Option<PendingAppend>)> {
+        let log_path = intent_path_for(rvf_path);
+        let pending = match fs::read(&log_path) {
+            Ok(bytes) => parse_pending(&bytes),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        if let Some(append) = pending {
+            if file.metadata()?.len() > append.offset && written_by(file, append)? {
+                // Wait out read-only opens that have the file mapped.
+                #[cfg(unix)]
+                let _mapped = crate::locking::FileLock::exclusive(file)?;
+                file.set_len(append.offset)?;
+                file.sync_all()?;
+            }
+        }
+        remove_if_exists(&log_path)?;
+
+        Ok((
+            Self {
+                log_path,
+                pending: false,
+            },
+            pending,
+        ))
+    }
+
+    /// Record that an append starting at `offset` is about to begin.
+    ///
+    /// No-op while an earlier intent is still pending: its offset already
+    /// covers everything appended since.
+    pub(crate) fn begin(&mut self, segment_id: u64, offset: u64) -> io::Result<()> {
+        if self.pending {
+            return Ok(());
+        }
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&self.log_path)?;
+        f.write_all(&encode_record(KIND_INTENT, segment_id, offset))?;
+        f.sync_all()?;
+        self.pending = true;
+        Ok(())
+    }
+
+    /// Record that the pending append is covered by a durable manifest.
+    pub(crate) fn commit(&mut self) -> io::Result<()> {
+        if !self.pending {
+            return Ok(());
+        }
+        let mut f = OpenOptions::new().append(true).open(&self.log_path)?;
+        f.write_all(&encode_record(KIND_COMMIT, 0, 0))?;
+        f.sync_all()?;
+        self.pending = false;
+        Ok(())
+    }
+
+    /// Remove the log on clean shutdown. A pending intent is kept so the
+    /// next open can roll it back.
+    pub(crate) fn close(self) -> io::Result<()> {
+        if self.pending {
+            return Ok(());
+        }
+        remove_if_exists(&self.log_path)
+    }
+}
+
+/// Compute the intent log path for a given RVF file.
+pub(crate) fn intent_path_for(rvf_path: &Path) -> PathBuf {
+    let mut p = rvf_path.as_os_str().to_os_string();
+    p.push(".intent");
+    PathBuf::from(p)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Whether the bytes at the intent's offset could have come from it: true
+/// unless a complete segment header there carries another segment ID.
+fn written_by(mut file: &File, append: PendingAppend) -> io::Result<bool> {
+    let mut head = [0u8; 16];
+    file.seek(SeekFrom::Start(append.offset))?;
+    let n = file.take(head.len() as u64).read(&mut head)?;
+    if n < head.len() || head[0x00..0x04] != SEGMENT_MAGIC.to_le_bytes() {
+        return Ok(true);
+    }
+    Ok(head[0x08..0x10] == append.segment_id.to_le_bytes())
+}
+
+fn encode_record(kind: u8, segment_id: u64, offset: u64) -> [u8; RECORD_SIZE] {
+    let mut buf = [0u8; RECORD_SIZE];
+    buf[0x00..0x04].copy_from_slice(&INTENT_MAGIC.to_le_bytes());
+    buf[0x04] = kind;
+    buf[0x08..0x10].copy_from_slice(&segment_id.to_le_bytes());
+    buf[0x10..0x18].copy_from_slice(&offset.to_le_bytes());
+    buf
+}
+
+/// Find the last intent not followed by a commit.
+fn parse_pending(bytes: &[u8]) -> Option<PendingAppend> {
+    let mut pending = None;
+    for rec in bytes.chunks_exact(RECORD_SIZE) {
+        if rec[0x00..0x04] != INTENT_MAGIC.to_le_bytes() {
+            break;
+        }
+        match rec[0x04] {
+            KIND_INTENT => {
+                pending = Some(PendingAppend {
+                    segment_id: u64::from_le_bytes(rec[0x08..0x10].try_into().unwrap()),
+                    offset: u64::from_le_bytes(rec[0x10..0x18].try_into().unwrap()),
+                });
+            }
+            KIND_COMMIT => pending = None,
+            _ => break,
+        }
+    }
+    pending
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tracks_last_uncommitted_intent() {
+        let intent = encode_record(KIND_INTENT, 7, 4096);
+        let commit = encode_record(KIND_COMMIT, 0, 0);
+
+        assert_eq!(parse_pending(&[]), None);
+        assert_eq!(
+            parse_pending(&intent),
+            Some(PendingAppend {
+                segment_id: 7,
+                offset: 4096
+            })
+        );
+        assert_eq!(parse_pending(&[intent, commit].concat()), None);
+
+        // A torn commit does not count.
+        let torn = [&intent[..], &commit[..RECORD_SIZE - 1]].concat();
+        assert_eq!(parse_pending(&torn).map(|p| p.offset), Some(4096));
+        // A torn intent means nothing was appended yet.
+        assert_eq!(parse_pending(&intent[..10]), None);
+    }
+
+    #[test]
+    fn begin_commit_close_lifecycle() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let rvf_path = dir.path().join("data.rvf");
+        let log_path = intent_path_for(&rvf_path);
+
+        let mut log = IntentLog::create(&rvf_path).unwrap();
+        log.begin(3, 100).unwrap();
+        log.begin(4, 200).unwrap();
+        assert_eq!(
+            parse_pending(&fs::read(&log_path).unwrap()),
+            Some(PendingAppend {
+                segment_id: 3,
+                offset: 100
+            })
+        );
+
+        log.commit().unwrap();
+        assert_eq!(parse_pending(&fs::read(&log_path).unwrap()), None);
+        log.close().unwrap();
+        assert!(!log_path.exists());
+    }
+}
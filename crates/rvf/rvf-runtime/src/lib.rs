@@ -12,56 +12,84 @@
 //! - **Single-writer / multi-reader**: Advisory lock file enforces exclusivity.
 //! - **Background compaction**: Dead space is reclaimed without blocking queries.
 
+pub mod adaptive_ef;
 pub mod adversarial;
 pub mod agi_authority;
 pub mod agi_coherence;
 pub mod agi_container;
+#[cfg(feature = "tokio")]
+pub mod async_open;
 pub mod compaction;
 pub mod compress;
 pub mod cow;
 pub mod cow_compact;
 pub mod cow_map;
+pub mod cuckoo_filter;
 pub mod deletion;
+pub mod domain_registry;
 pub mod dos;
+pub mod encryption;
 pub mod ffi;
 pub mod filter;
+pub mod intent_log;
 pub mod locking;
 pub mod membership;
+pub mod metrics;
+pub mod multi_vector;
 pub mod options;
 #[cfg(feature = "qr")]
 pub mod qr_encode;
 pub mod qr_seed;
 pub mod read_path;
+pub mod replication;
 pub mod safety_net;
 pub mod seed_crypto;
+pub(crate) mod settings;
+pub mod snapshot;
 pub mod status;
 pub mod store;
 pub mod witness;
 pub mod write_path;
+pub mod xor_filter;
 
+pub use adaptive_ef::{recall_at_k, AdaptiveEf, AdaptiveEfConfig};
 pub use adversarial::{
     adaptive_n_probe, centroid_distance_cv, combined_effective_n_probe,
     effective_n_probe_with_drift, is_degenerate_distribution, DEGENERATE_CV_THRESHOLD,
 };
 pub use agi_container::{AgiContainerBuilder, ParsedAgiManifest};
+#[cfg(feature = "tokio")]
+pub use async_open::AsyncBoot;
 pub use compress::{compress, decompress, CompressError};
 pub use cow::{CowEngine, CowStats, WitnessEvent};
 pub use cow_compact::CowCompactor;
 pub use cow_map::CowMap;
-pub use dos::{BudgetTokenBucket, NegativeCache, ProofOfWork, QuerySignature};
+pub use cuckoo_filter::CuckooFilter;
+pub use domain_registry::{DomainPreset, DomainRegistry};
+pub use dos::{
+    AdaptiveBudgetConfig, BudgetTokenBucket, NegativeCache, ProofOfWork, QuerySignature,
+};
+pub use encryption::EncryptionConfig;
 pub use filter::FilterExpr;
+pub use locking::LockOptions;
 pub use membership::MembershipFilter;
+pub use metrics::{LatencyHistogram, StoreMetrics};
+pub use multi_vector::MultiVectorRecord;
 pub use options::{
-    CompactionResult, DeleteResult, IngestResult, MetadataEntry, MetadataValue, QualityEnvelope,
-    QueryOptions, RvfOptions, SearchResult, WitnessConfig,
+    AsyncOpenOptions, CompactionProgress, CompactionResult, DeleteResult, IngestResult,
+    IngestWarning, MetadataEntry, MetadataKind, MetadataSchema, MetadataValue, MmapOptions,
+    NormalizationReport, NormalizePolicy, Projection, QualityEnvelope, QueryOptions,
+    RerankedResult, Reranker, RvfOptions, SearchPlan, SearchResult, WitnessConfig,
 };
 #[cfg(feature = "qr")]
 pub use qr_encode::{EcLevel, QrCode, QrEncoder, QrError};
 pub use qr_seed::{
     make_host_entry, BootstrapProgress, DownloadManifest, ParsedSeed, SeedBuilder, SeedError,
 };
+pub use replication::{Lsn, ReplicationOp, ReplicationRecord};
 pub use safety_net::{
-    selective_safety_net_scan, should_activate_safety_net, Candidate, SafetyNetResult,
+    safety_net_scan_with_strategy, selective_safety_net_scan, should_activate_safety_net,
+    Candidate, SafetyNetResult, SafetyNetStrategy,
 };
 pub use seed_crypto::{
     full_content_hash, layer_content_hash, seed_content_hash, sign_seed, verify_layer, verify_seed,
@@ -69,8 +97,10 @@ pub use seed_crypto::{
 };
 #[cfg(feature = "ed25519")]
 pub use seed_crypto::{sign_seed_ed25519, verify_seed_ed25519, SIG_ALGO_ED25519};
+pub use snapshot::{Checkpoint, Snapshot};
 pub use status::StoreStatus;
 pub use store::RvfStore;
 pub use witness::{
     GovernancePolicy, ParsedWitness, ScorecardBuilder, WitnessBuilder, WitnessError,
 };
+pub use xor_filter::{XorFilter, XorFilterBuilder};
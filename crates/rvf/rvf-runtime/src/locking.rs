@@ -2,71 +2,160 @@
 //!
 //! Implements the advisory lock file protocol from spec 09:
 //! - Lock file at `{path}.lock` with PID, hostname, timestamp, UUID
-//! - Stale lock detection via PID liveness and age threshold
+//! - Stale lock detection via PID liveness and heartbeat age
 //! - Atomic creation via O_CREAT | O_EXCL
+//! - Generation counter bumped on every stale-lock reclaim, so a writer
+//!   that was presumed dead cannot keep appending once it resumes
 
 use std::fs;
-use std::io::{self, Read, Write};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// The lock file magic: "RVLF" in ASCII (big-endian).
 const LOCK_MAGIC: u32 = 0x52564C46;
 
-/// Lock protocol version.
-const LOCK_VERSION: u32 = 1;
+/// Lock protocol version. Version 2 appends the generation counter.
+const LOCK_VERSION: u32 = 2;
 
 /// Lock file total size in bytes.
-const LOCK_FILE_SIZE: usize = 104;
+const LOCK_FILE_SIZE: usize = 112;
+
+/// Size of a version 1 lock file (no generation counter).
+const LOCK_FILE_SIZE_V1: usize = 104;
+
+/// Stale lock age threshold for same-host (30 seconds).
+const STALE_AGE: Duration = Duration::from_secs(30);
+
+/// Cross-host locks cannot be probed by PID, so they must be this many
+/// times older than the same-host threshold before they are reclaimed.
+const CROSS_HOST_STALE_FACTOR: u32 = 10;
+
+/// How long to sleep between acquisition attempts while waiting.
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Writer lock acquisition settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockOptions {
+    /// How long to keep retrying while another live writer holds the lock.
+    /// Zero fails immediately.
+    pub acquire_timeout: Duration,
+    /// Heartbeat age after which a lock whose holder PID is dead may be
+    /// reclaimed.
+    pub stale_after: Duration,
+}
 
-/// Stale lock age threshold for same-host (30 seconds in nanoseconds).
-const STALE_AGE_NS: u64 = 30_000_000_000;
+impl Default for LockOptions {
+    fn default() -> Self {
+        Self {
+            acquire_timeout: Duration::ZERO,
+            stale_after: STALE_AGE,
+        }
+    }
+}
 
 /// Represents an acquired writer lock.
 pub(crate) struct WriterLock {
     lock_path: PathBuf,
     writer_id: [u8; 16],
+    generation: u64,
 }
 
 impl WriterLock {
-    /// Attempt to acquire the writer lock for the given RVF file path.
+    /// Acquire the writer lock for the given RVF file path, waiting up to
+    /// `options.acquire_timeout` for a live holder to release it.
     ///
     /// Returns `Ok(WriterLock)` on success, or an `io::Error` if the lock
     /// is held by another active writer.
-    pub(crate) fn acquire(rvf_path: &Path) -> io::Result<Self> {
+    pub(crate) fn acquire_with(rvf_path: &Path, options: &LockOptions) -> io::Result<Self> {
         let lock_path = lock_path_for(rvf_path);
-        let pid = std::process::id();
-        let hostname = get_hostname();
-        let timestamp_ns = now_ns();
+        let deadline = Instant::now() + options.acquire_timeout;
         let writer_id = random_uuid();
-
-        // Build lock file content.
-        let content = build_lock_content(pid, &hostname, timestamp_ns, &writer_id);
-
-        // Attempt atomic creation.
-        match atomic_create_file(&lock_path, &content) {
-            Ok(()) => Ok(WriterLock {
-                lock_path,
-                writer_id,
-            }),
-            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
-                // Check for stale lock.
-                if try_break_stale_lock(&lock_path)? {
-                    // Retry after breaking stale lock.
-                    atomic_create_file(&lock_path, &content)?;
-                    Ok(WriterLock {
+        let mut generation = 1;
+
+        loop {
+            let content = build_lock_content(
+                std::process::id(),
+                &get_hostname(),
+                now_ns(),
+                &writer_id,
+                generation,
+            );
+
+            // Attempt atomic creation.
+            match atomic_create_file(&lock_path, &content) {
+                Ok(()) => {
+                    return Ok(WriterLock {
                         lock_path,
                         writer_id,
+                        generation,
                     })
-                } else {
-                    Err(io::Error::new(
-                        io::ErrorKind::WouldBlock,
-                        "another writer holds the lock",
-                    ))
                 }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e),
             }
-            Err(e) => Err(e),
+
+            // Break a stale lock and retry straight away, one generation on.
+            if let Some(prev) = try_break_stale_lock(&lock_path, options.stale_after)? {
+                generation = generation.max(prev + 1);
+                continue;
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "another writer holds the lock",
+                ));
+            }
+            std::thread::sleep(LOCK_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
+    /// Generation this lock was acquired at.
+    #[allow(dead_code)]
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Confirm the lock is still ours and refresh its heartbeat timestamp.
+    ///
+    /// Returns `Ok(false)` if the lock was reclaimed by another writer,
+    /// which the caller must treat as losing write access.
+    pub(crate) fn heartbeat(&self) -> io::Result<bool> {
+        let mut file = match fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.lock_path)
+        {
+            Ok(f) => f,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let mut content = Vec::with_capacity(LOCK_FILE_SIZE);
+        file.read_to_end(&mut content)?;
+        if !self.owns(&content) {
+            return Ok(false);
         }
+
+        // The check and rewrite go through the same handle: if a reclaimer
+        // replaced the file in between, this writes to the unlinked inode.
+        let refreshed = build_lock_content(
+            std::process::id(),
+            &get_hostname(),
+            now_ns(),
+            &self.writer_id,
+            self.generation,
+        );
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&refreshed)?;
+        Ok(true)
+    }
+
+    fn owns(&self, content: &[u8]) -> bool {
+        content.len() >= LOCK_FILE_SIZE_V1
+            && content[0x50..0x60] == self.writer_id
+            && read_generation(content) == self.generation
     }
 
     /// Release the writer lock.
@@ -76,11 +165,8 @@ impl WriterLock {
     pub(crate) fn release(self) -> io::Result<()> {
         // Verify our writer_id is still in the lock.
         if let Ok(content) = fs::read(&self.lock_path) {
-            if content.len() >= LOCK_FILE_SIZE {
-                let stored_id = &content[0x50..0x60];
-                if stored_id == self.writer_id {
-                    let _ = fs::remove_file(&self.lock_path);
-                }
+            if self.owns(&content) {
+                let _ = fs::remove_file(&self.lock_path);
             }
         }
         Ok(())
@@ -90,10 +176,7 @@ impl WriterLock {
     #[allow(dead_code)]
     pub(crate) fn is_valid(&self) -> bool {
         if let Ok(content) = fs::read(&self.lock_path) {
-            if content.len() >= LOCK_FILE_SIZE {
-                let stored_id = &content[0x50..0x60];
-                return stored_id == self.writer_id;
-            }
+            return self.owns(&content);
         }
         false
     }
@@ -103,11 +186,8 @@ impl Drop for WriterLock {
     fn drop(&mut self) {
         // Best-effort release on drop.
         if let Ok(content) = fs::read(&self.lock_path) {
-            if content.len() >= LOCK_FILE_SIZE {
-                let stored_id = &content[0x50..0x60];
-                if stored_id == self.writer_id {
-                    let _ = fs::remove_file(&self.lock_path);
-                }
+            if self.owns(&content) {
+                let _ = fs::remove_file(&self.lock_path);
             }
         }
     }
@@ -120,25 +200,28 @@ pub(crate) fn lock_path_for(rvf_path: &Path) -> PathBuf {
     PathBuf::from(p)
 }
 
-/// Try to break a stale lock. Returns `true` if the lock was broken.
-fn try_break_stale_lock(lock_path: &Path) -> io::Result<bool> {
+/// Try to break a stale lock.
+///
+/// Returns the broken lock's generation (0 if it had none), or `None` if
+/// the holder may still be alive.
+fn try_break_stale_lock(lock_path: &Path, stale_after: Duration) -> io::Result<Option<u64>> {
     let content = match fs::read(lock_path) {
         Ok(c) => c,
-        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Some(0)),
         Err(e) => return Err(e),
     };
 
-    if content.len() < LOCK_FILE_SIZE {
+    if content.len() < LOCK_FILE_SIZE_V1 {
         // Invalid lock file — delete it.
-        let _ = fs::remove_file(lock_path);
-        return Ok(true);
+        remove_if_unchanged(lock_path, &content)?;
+        return Ok(Some(0));
     }
 
     // Validate magic.
     let magic = u32::from_le_bytes([content[0], content[1], content[2], content[3]]);
     if magic != LOCK_MAGIC {
-        let _ = fs::remove_file(lock_path);
-        return Ok(true);
+        remove_if_unchanged(lock_path, &content)?;
+        return Ok(Some(0));
     }
 
     // Read PID and timestamp.
@@ -171,25 +254,45 @@ fn try_break_stale_lock(lock_path: &Path) -> io::Result<bool> {
     };
 
     // Stale conditions:
-    // - PID is dead AND age > threshold (same host)
-    // - Age > extended threshold (cross-host)
+    // - PID is dead AND heartbeat age > threshold (same host)
+    // - Heartbeat age > extended threshold (cross-host)
     let threshold = if same_host {
-        STALE_AGE_NS
+        stale_after
     } else {
-        300_000_000_000
+        stale_after.saturating_mul(CROSS_HOST_STALE_FACTOR)
     };
+    let stale = age > threshold.as_nanos() as u64 && (!pid_alive || !same_host);
 
-    if !pid_alive && age > threshold {
-        let _ = fs::remove_file(lock_path);
-        return Ok(true);
+    if stale {
+        remove_if_unchanged(lock_path, &content)?;
+        return Ok(Some(read_generation(&content)));
     }
 
-    if !same_host && age > threshold {
-        let _ = fs::remove_file(lock_path);
-        return Ok(true);
+    Ok(None)
+}
+
+/// Remove the lock file only if it still holds `expected`, so a lock that
+/// another reclaimer already replaced is left alone.
+fn remove_if_unchanged(lock_path: &Path, expected: &[u8]) -> io::Result<()> {
+    match fs::read(lock_path) {
+        Ok(current) if current == expected => match fs::remove_file(lock_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        },
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
     }
+}
 
-    Ok(false)
+/// Read the generation counter; version 1 lock files have none.
+fn read_generation(content: &[u8]) -> u64 {
+    if content.len() < LOCK_FILE_SIZE {
+        return 0;
+    }
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&content[0x68..0x70]);
+    u64::from_le_bytes(buf)
 }
 
 fn build_lock_content(
@@ -197,6 +300,7 @@ fn build_lock_content(
     hostname: &str,
     timestamp_ns: u64,
     writer_id: &[u8; 16],
+    generation: u64,
 ) -> Vec<u8> {
     let mut buf = vec![0u8; LOCK_FILE_SIZE];
 
@@ -218,6 +322,8 @@ fn build_lock_content(
     // CRC32 (0x64) — simplified: we use a basic checksum.
     let crc = simple_crc32(&buf[0..0x64]);
     buf[0x64..0x68].copy_from_slice(&crc.to_le_bytes());
+    // Generation (0x68).
+    buf[0x68..0x70].copy_from_slice(&generation.to_le_bytes());
 
     buf
 }
@@ -294,9 +400,64 @@ fn is_pid_alive(pid: u32) -> bool {
     }
 }
 
+/// Advisory `flock` on the RVF file itself.
+///
+/// Truncation is the one write that can fault a reader's memory map, so
+/// intent-log recovery holds the exclusive lock while it cuts the file
+/// and an mmap boot holds the shared lock until its map is dropped. The
+/// lock is released on drop.
+#[cfg(unix)]
+pub(crate) struct FileLock<'a> {
+    file: &'a fs::File,
+}
+
+#[cfg(unix)]
+impl<'a> FileLock<'a> {
+    /// Block until no other handle holds the exclusive lock.
+    pub(crate) fn shared(file: &'a fs::File) -> io::Result<Self> {
+        Self::acquire(file, LOCK_SH)
+    }
+
+    /// Block until no other handle holds any lock.
+    pub(crate) fn exclusive(file: &'a fs::File) -> io::Result<Self> {
+        Self::acquire(file, LOCK_EX)
+    }
+
+    fn acquire(file: &'a fs::File, op: i32) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        loop {
+            if unsafe { flock(file.as_raw_fd(), op) } == 0 {
+                return Ok(Self { file });
+            }
+            let e = io::Error::last_os_error();
+            if e.kind() != io::ErrorKind::Interrupted {
+                return Err(e);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for FileLock<'_> {
+    fn drop(&mut self) {
+        use std::os::unix::io::AsRawFd;
+
+        unsafe { flock(self.file.as_raw_fd(), LOCK_UN) };
+    }
+}
+
+#[cfg(unix)]
+const LOCK_SH: i32 = 1;
+#[cfg(unix)]
+const LOCK_EX: i32 = 2;
+#[cfg(unix)]
+const LOCK_UN: i32 = 8;
+
 #[cfg(unix)]
 extern "C" {
     fn kill(pid: i32, sig: i32) -> i32;
+    fn flock(fd: i32, operation: i32) -> i32;
 }
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
@@ -363,17 +524,17 @@ mod tests {
         let rvf_path = dir.path().join("test.rvf");
         fs::write(&rvf_path, b"").unwrap();
 
-        let lock = WriterLock::acquire(&rvf_path).unwrap();
+        let lock = WriterLock::acquire_with(&rvf_path, &LockOptions::default()).unwrap();
         assert!(lock.is_valid());
 
         // Second acquisition should fail.
-        let result = WriterLock::acquire(&rvf_path);
+        let result = WriterLock::acquire_with(&rvf_path, &LockOptions::default());
         assert!(result.is_err());
 
         lock.release().unwrap();
 
         // Now acquisition should succeed again.
-        let lock2 = WriterLock::acquire(&rvf_path).unwrap();
+        let lock2 = WriterLock::acquire_with(&rvf_path, &LockOptions::default()).unwrap();
         assert!(lock2.is_valid());
     }
 
@@ -388,12 +549,99 @@ mod tests {
         let fake_pid = 999999999u32;
         let old_ts = now_ns().saturating_sub(60_000_000_000); // 60s ago
         let fake_id = [0xABu8; 16];
-        let content = build_lock_content(fake_pid, &get_hostname(), old_ts, &fake_id);
+        let content = build_lock_content(fake_pid, &get_hostname(), old_ts, &fake_id, 1);
         fs::write(&lock_path, &content).unwrap();
 
         // Should be able to acquire despite existing lock (stale).
-        let lock = WriterLock::acquire(&rvf_path).unwrap();
+        let lock = WriterLock::acquire_with(&rvf_path, &LockOptions::default()).unwrap();
+        assert!(lock.is_valid());
+    }
+
+    #[test]
+    fn dead_holder_reclaim_bumps_generation() {
+        let dir = TempDir::new().unwrap();
+        let rvf_path = dir.path().join("dead.rvf");
+        let lock_path = lock_path_for(&rvf_path);
+
+        let old_ts = now_ns().saturating_sub(5_000_000_000); // 5s ago
+        let content = build_lock_content(999999999, &get_hostname(), old_ts, &[0xCD; 16], 4);
+        fs::write(&lock_path, &content).unwrap();
+
+        // Default threshold (30s) keeps the lock; a 1s threshold reclaims it.
+        assert!(WriterLock::acquire_with(&rvf_path, &LockOptions::default()).is_err());
+        let options = LockOptions {
+            stale_after: Duration::from_secs(1),
+            ..LockOptions::default()
+        };
+        let lock = WriterLock::acquire_with(&rvf_path, &options).unwrap();
+        assert_eq!(lock.generation(), 5);
+        assert!(lock.is_valid());
+    }
+
+    #[test]
+    fn revived_holder_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let rvf_path = dir.path().join("revived.rvf");
+        let lock_path = lock_path_for(&rvf_path);
+
+        // A writer whose process looked dead long enough to be reclaimed.
+        let old_ts = now_ns().saturating_sub(60_000_000_000);
+        let stale_id = [0xEF; 16];
+        let content = build_lock_content(999999999, &get_hostname(), old_ts, &stale_id, 1);
+        fs::write(&lock_path, &content).unwrap();
+        let revived = WriterLock {
+            lock_path: lock_path.clone(),
+            writer_id: stale_id,
+            generation: 1,
+        };
+        assert!(revived.heartbeat().unwrap());
+
+        // Age the heartbeat again, then let a new writer reclaim.
+        fs::write(&lock_path, &content).unwrap();
+        let current = WriterLock::acquire_with(&rvf_path, &LockOptions::default()).unwrap();
+        assert_eq!(current.generation(), 2);
+
+        // The revived writer sees it lost the lock and cannot remove it.
+        assert!(!revived.heartbeat().unwrap());
+        assert!(!revived.is_valid());
+        drop(revived);
+        assert!(current.is_valid());
+        assert!(current.heartbeat().unwrap());
+    }
+
+    #[test]
+    fn acquire_times_out_while_holder_alive() {
+        let dir = TempDir::new().unwrap();
+        let rvf_path = dir.path().join("busy.rvf");
+        let _held = WriterLock::acquire_with(&rvf_path, &LockOptions::default()).unwrap();
+
+        let options = LockOptions {
+            acquire_timeout: Duration::from_millis(100),
+            ..LockOptions::default()
+        };
+        let start = Instant::now();
+        let err = WriterLock::acquire_with(&rvf_path, &options).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn acquire_waits_for_release() {
+        let dir = TempDir::new().unwrap();
+        let rvf_path = dir.path().join("handoff.rvf");
+        let held = WriterLock::acquire_with(&rvf_path, &LockOptions::default()).unwrap();
+
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            held.release().unwrap();
+        });
+        let options = LockOptions {
+            acquire_timeout: Duration::from_secs(5),
+            ..LockOptions::default()
+        };
+        let lock = WriterLock::acquire_with(&rvf_path, &options).unwrap();
         assert!(lock.is_valid());
+        releaser.join().unwrap();
     }
 
     #[test]
@@ -9,11 +9,40 @@
 //! - Excluded nodes MAY be pushed onto exploration heap (routing waypoints)
 //! - Excluded nodes MUST NOT be pushed onto result heap
 //! - Excluded nodes DO NOT decrement `ef_remaining`
+//!
+//! Static sets can instead be backed by an immutable XOR filter
+//! (`FilterType::Xor8`/`Xor16`), which may report false positives but
+//! takes ~1.23 fingerprints per member instead of one bit per vector ID.
+//!
+//! Sets that need deletion (e.g. tombstones removed on compaction) can be
+//! backed by a cuckoo filter (`FilterType::Cuckoo`) and updated with
+//! `insert`/`delete`.
+//!
+//! Persisted MEMBERSHIP_SEG payload (see `to_segment_payload`):
+//!   MembershipHeader(96) | filter[filter_size] | content_hash(32)
+//!
+//! `content_hash` identifies the store contents the filter was built
+//! against, so a reader can tell whether the stored filter is stale.
 
-use rvf_types::membership::{FilterMode, MembershipHeader, MEMBERSHIP_MAGIC};
+use crate::cuckoo_filter::CuckooFilter;
+use crate::xor_filter::XorFilter;
+use rvf_types::membership::{FilterMode, FilterType, MembershipHeader, MEMBERSHIP_MAGIC};
 use rvf_types::{ErrorCode, RvfError};
 
-/// Membership filter backed by a dense bitmap.
+/// Size of the `MembershipHeader` at the start of a MEMBERSHIP_SEG payload.
+const HEADER_SIZE: usize = 96;
+
+/// Storage behind a `MembershipFilter`.
+enum Backing {
+    /// Dense bitmap in `MembershipFilter::bitmap`.
+    Bitmap,
+    /// Immutable XOR filter.
+    Xor(XorFilter),
+    /// Cuckoo filter supporting deletion.
+    Cuckoo(CuckooFilter),
+}
+
+/// Membership filter backed by a dense bitmap, an XOR filter or a cuckoo filter.
 pub struct MembershipFilter {
     /// Include or exclude mode.
     mode: FilterMode,
@@ -25,6 +54,8 @@ pub struct MembershipFilter {
     member_count: u64,
     /// Generation counter for optimistic concurrency.
     generation_id: u32,
+    /// Filter storage; `bitmap` is empty and unused unless `Backing::Bitmap`.
+    backing: Backing,
 }
 
 impl MembershipFilter {
@@ -37,6 +68,7 @@ impl MembershipFilter {
             vector_count,
             member_count: 0,
             generation_id: 0,
+            backing: Backing::Bitmap,
         }
     }
 
@@ -49,12 +81,96 @@ impl MembershipFilter {
             vector_count,
             member_count: 0,
             generation_id: 0,
+            backing: Backing::Bitmap,
+        }
+    }
+
+    /// Create a filter backed by a built XOR filter.
+    ///
+    /// Any 64-bit key may be queried; `vector_count` and `member_count`
+    /// both report the number of keys the XOR filter was built from.
+    pub fn new_xor(mode: FilterMode, filter: XorFilter) -> Self {
+        Self {
+            mode,
+            bitmap: Vec::new(),
+            vector_count: filter.len(),
+            member_count: filter.len(),
+            generation_id: 0,
+            backing: Backing::Xor(filter),
+        }
+    }
+
+    /// Create an empty filter backed by a cuckoo filter sized for about
+    /// `capacity` keys. Any 64-bit key may be inserted.
+    pub fn new_cuckoo(mode: FilterMode, capacity: usize) -> Self {
+        let filter = CuckooFilter::with_capacity(capacity);
+        Self {
+            mode,
+            bitmap: Vec::new(),
+            vector_count: filter.slot_count() as u64,
+            member_count: 0,
+            generation_id: 0,
+            backing: Backing::Cuckoo(filter),
+        }
+    }
+
+    /// Filter storage type.
+    pub fn filter_type(&self) -> FilterType {
+        match &self.backing {
+            Backing::Bitmap => FilterType::Bitmap,
+            Backing::Xor(xor) => xor.filter_type(),
+            Backing::Cuckoo(_) => FilterType::Cuckoo,
         }
     }
 
-    /// Add a vector ID to the filter.
+    /// Insert a key.
+    ///
+    /// Bitmap-backed filters behave like [`add`](Self::add). Fails with
+    /// `MembershipFull` when a cuckoo filter has no room (the filter is left
+    /// unchanged) and with `SnapshotFrozen` for immutable XOR filters.
+    pub fn insert(&mut self, key: u64) -> Result<(), RvfError> {
+        match &mut self.backing {
+            Backing::Bitmap => {
+                self.add(key);
+                Ok(())
+            }
+            Backing::Xor(_) => Err(RvfError::Code(ErrorCode::SnapshotFrozen)),
+            Backing::Cuckoo(cuckoo) => {
+                cuckoo.insert(key)?;
+                self.member_count += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Delete a key. Returns whether it was present.
+    ///
+    /// Only delete keys that were inserted into a cuckoo-backed filter:
+    /// deleting an absent key may remove a colliding key's fingerprint.
+    /// Fails with `SnapshotFrozen` for immutable XOR filters.
+    pub fn delete(&mut self, key: u64) -> Result<bool, RvfError> {
+        match &mut self.backing {
+            Backing::Bitmap => {
+                let present = self.bitmap_contains(key);
+                self.remove(key);
+                Ok(present)
+            }
+            Backing::Xor(_) => Err(RvfError::Code(ErrorCode::SnapshotFrozen)),
+            Backing::Cuckoo(cuckoo) => {
+                let removed = cuckoo.delete(key);
+                if removed {
+                    self.member_count -= 1;
+                }
+                Ok(removed)
+            }
+        }
+    }
+
+    /// Add a vector ID to the filter. Ignored for XOR-backed filters,
+    /// which are immutable; rebuild them with `XorFilterBuilder` instead.
+    /// Cuckoo-backed filters use [`insert`](Self::insert).
     pub fn add(&mut self, vector_id: u64) {
-        if vector_id >= self.vector_count {
+        if !matches!(self.backing, Backing::Bitmap) || vector_id >= self.vector_count {
             return;
         }
         let word = (vector_id / 64) as usize;
@@ -68,9 +184,10 @@ impl MembershipFilter {
         }
     }
 
-    /// Remove a vector ID from the filter.
+    /// Remove a vector ID from the filter. Ignored for XOR- and
+    /// cuckoo-backed filters; the latter use [`delete`](Self::delete).
     pub fn remove(&mut self, vector_id: u64) {
-        if vector_id >= self.vector_count {
+        if !matches!(self.backing, Backing::Bitmap) || vector_id >= self.vector_count {
             return;
         }
         let word = (vector_id / 64) as usize;
@@ -86,6 +203,11 @@ impl MembershipFilter {
 
     /// Check if a vector ID is in the filter bitmap.
     fn bitmap_contains(&self, vector_id: u64) -> bool {
+        match &self.backing {
+            Backing::Bitmap => {}
+            Backing::Xor(xor) => return xor.contains(vector_id),
+            Backing::Cuckoo(cuckoo) => return cuckoo.contains(vector_id),
+        }
         if vector_id >= self.vector_count {
             return false;
         }
@@ -134,8 +256,13 @@ impl MembershipFilter {
         self.generation_id += 1;
     }
 
-    /// Serialize the bitmap to bytes (just the raw bitmap words).
+    /// Serialize the filter body: raw bitmap words, or the XOR/cuckoo filter.
     pub fn serialize(&self) -> Vec<u8> {
+        match &self.backing {
+            Backing::Bitmap => {}
+            Backing::Xor(xor) => return xor.serialize(),
+            Backing::Cuckoo(cuckoo) => return cuckoo.serialize(),
+        }
         let mut buf = Vec::with_capacity(self.bitmap.len() * 8);
         for &word in &self.bitmap {
             buf.extend_from_slice(&word.to_le_bytes());
@@ -143,10 +270,35 @@ impl MembershipFilter {
         buf
     }
 
-    /// Deserialize a MembershipFilter from bitmap bytes and a header.
+    /// Deserialize a MembershipFilter from filter bytes and a header.
     pub fn deserialize(data: &[u8], header: &MembershipHeader) -> Result<Self, RvfError> {
         let mode = FilterMode::try_from(header.filter_mode)
             .map_err(|_| RvfError::Code(ErrorCode::MembershipInvalid))?;
+        let filter_type = FilterType::try_from(header.filter_type)
+            .map_err(|_| RvfError::Code(ErrorCode::MembershipInvalid))?;
+        match filter_type {
+            FilterType::Bitmap => {}
+            FilterType::Xor8 | FilterType::Xor16 => {
+                let xor = XorFilter::deserialize(data, filter_type, header.member_count)?;
+                let mut filter = Self::new_xor(mode, xor);
+                filter.generation_id = header.generation_id;
+                return Ok(filter);
+            }
+            FilterType::Cuckoo => {
+                let cuckoo = CuckooFilter::deserialize(data)?;
+                return Ok(Self {
+                    mode,
+                    bitmap: Vec::new(),
+                    vector_count: cuckoo.slot_count() as u64,
+                    member_count: cuckoo.len(),
+                    generation_id: header.generation_id,
+                    backing: Backing::Cuckoo(cuckoo),
+                });
+            }
+            FilterType::RoaringBitmap => {
+                return Err(RvfError::Code(ErrorCode::MembershipInvalid));
+            }
+        }
 
         let word_count = header.vector_count.div_ceil(64) as usize;
         let expected_bytes = word_count * 8;
@@ -179,6 +331,7 @@ impl MembershipFilter {
             vector_count: header.vector_count,
             member_count,
             generation_id: header.generation_id,
+            backing: Backing::Bitmap,
         })
     }
 
@@ -190,7 +343,7 @@ impl MembershipFilter {
         MembershipHeader {
             magic: MEMBERSHIP_MAGIC,
             version: 1,
-            filter_type: rvf_types::membership::FilterType::Bitmap as u8,
+            filter_type: self.filter_type() as u8,
             filter_mode: self.mode as u8,
             vector_count: self.vector_count,
             member_count: self.member_count,
@@ -204,6 +357,48 @@ impl MembershipFilter {
             _reserved2: [0u8; 8],
         }
     }
+
+    /// Build a MEMBERSHIP_SEG payload: header, filter body and the hash of
+    /// the store contents this filter was built against.
+    pub fn to_segment_payload(&self, content_hash: &[u8; 32]) -> Vec<u8> {
+        let header = self.to_header();
+        let body = self.serialize();
+        let mut payload = Vec::with_capacity(HEADER_SIZE + body.len() + 32);
+        payload.extend_from_slice(&header.to_bytes());
+        payload.extend_from_slice(&body);
+        payload.extend_from_slice(content_hash);
+        payload
+    }
+
+    /// Parse a payload produced by [`MembershipFilter::to_segment_payload`].
+    ///
+    /// Returns the filter, its header and the recorded content hash. Fails
+    /// with `MembershipInvalid` if the payload is truncated or the filter
+    /// body does not match the header's `filter_hash`.
+    pub fn from_segment_payload(
+        payload: &[u8],
+    ) -> Result<(Self, MembershipHeader, [u8; 32]), RvfError> {
+        let invalid = || RvfError::Code(ErrorCode::MembershipInvalid);
+        let header_bytes: &[u8; HEADER_SIZE] = payload
+            .get(..HEADER_SIZE)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(invalid)?;
+        let header = MembershipHeader::from_bytes(header_bytes)?;
+        let start = usize::try_from(header.filter_offset).map_err(|_| invalid())?;
+        let end = start
+            .checked_add(header.filter_size as usize)
+            .ok_or_else(invalid)?;
+        let body = payload.get(start..end).ok_or_else(invalid)?;
+        let content_hash: [u8; 32] = payload
+            .get(end..end + 32)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(invalid)?;
+        if crate::store::simple_shake256_256(body) != header.filter_hash {
+            return Err(invalid());
+        }
+        let filter = Self::deserialize(body, &header)?;
+        Ok((filter, header, content_hash))
+    }
 }
 
 #[cfg(test)]
@@ -298,6 +493,28 @@ mod tests {
         assert!(!filter2.contains(100));
     }
 
+    #[test]
+    fn segment_payload_round_trip() {
+        let mut filter = MembershipFilter::new_include(100);
+        for id in (0..100).step_by(3) {
+            filter.add(id);
+        }
+        let content_hash = [0x5A; 32];
+        let payload = filter.to_segment_payload(&content_hash);
+
+        let (restored, header, hash) = MembershipFilter::from_segment_payload(&payload).unwrap();
+        assert_eq!(hash, content_hash);
+        assert_eq!(header.member_count, filter.member_count());
+        for id in 0..100 {
+            assert_eq!(restored.contains(id), filter.contains(id));
+        }
+
+        let mut corrupt = payload.clone();
+        corrupt[HEADER_SIZE] ^= 0xFF;
+        assert!(MembershipFilter::from_segment_payload(&corrupt).is_err());
+        assert!(MembershipFilter::from_segment_payload(&payload[..payload.len() - 1]).is_err());
+    }
+
     #[test]
     fn generation_bump() {
         let mut filter = MembershipFilter::new_include(10);
@@ -321,4 +538,115 @@ mod tests {
         assert!(!filter.contains(65));
         assert!(!filter.contains(129));
     }
+
+    #[test]
+    fn xor_backed_filter_contains() {
+        use crate::xor_filter::XorFilterBuilder;
+
+        let mut builder = XorFilterBuilder::new(FilterType::Xor8).unwrap();
+        let members: Vec<u64> = (0..1_000u64).map(|i| i * 7919 + 13).collect();
+        builder.extend(members.iter().copied()).unwrap();
+        let mut filter =
+            MembershipFilter::new_xor(FilterMode::Include, builder.finalize().unwrap());
+
+        assert_eq!(filter.filter_type(), FilterType::Xor8);
+        assert_eq!(filter.member_count(), 1_000);
+        assert!(members.iter().all(|&id| filter.contains(id)));
+
+        // Immutable: add/remove leave the filter unchanged.
+        filter.remove(members[0]);
+        assert!(filter.contains(members[0]));
+        assert_eq!(filter.member_count(), 1_000);
+    }
+
+    #[test]
+    fn xor_backed_serialize_round_trip() {
+        use crate::xor_filter::XorFilterBuilder;
+
+        let mut builder = XorFilterBuilder::new(FilterType::Xor16).unwrap();
+        builder.extend([3, 1 << 40, u64::MAX]).unwrap();
+        let mut filter =
+            MembershipFilter::new_xor(FilterMode::Exclude, builder.finalize().unwrap());
+        filter.bump_generation();
+
+        let header = filter.to_header();
+        assert_eq!(header.filter_type, FilterType::Xor16 as u8);
+        let data = filter.serialize();
+        assert_eq!(header.filter_size as usize, data.len());
+
+        let restored = MembershipFilter::deserialize(&data, &header).unwrap();
+        assert_eq!(restored.filter_type(), FilterType::Xor16);
+        assert_eq!(restored.mode(), FilterMode::Exclude);
+        assert_eq!(restored.generation_id(), 1);
+        // Exclude mode: members are hidden.
+        assert!(!restored.contains(3));
+        assert!(!restored.contains(1 << 40));
+        assert!(!restored.contains(u64::MAX));
+    }
+
+    #[test]
+    fn cuckoo_backed_insert_delete() {
+        let mut filter = MembershipFilter::new_cuckoo(FilterMode::Include, 10_000);
+        assert_eq!(filter.filter_type(), FilterType::Cuckoo);
+
+        let keys: Vec<u64> = (0..10_000u64).map(|i| i * 2_654_435_761 + 17).collect();
+        for &k in &keys {
+            filter.insert(k).unwrap();
+        }
+        assert_eq!(filter.member_count(), 10_000);
+
+        let (deleted, surviving) = keys.split_at(5_000);
+        for &k in deleted {
+            assert!(filter.delete(k).unwrap());
+        }
+        assert_eq!(filter.member_count(), 5_000);
+        assert!(surviving.iter().all(|&k| filter.contains(k)));
+        // False-positive bound: 2 * 4 / 2^16 per query.
+        let stale = deleted.iter().filter(|&&k| filter.contains(k)).count();
+        assert!(stale <= 5, "{stale} deleted keys still reported present");
+
+        let header = filter.to_header();
+        assert_eq!(header.filter_type, FilterType::Cuckoo as u8);
+        let restored = MembershipFilter::deserialize(&filter.serialize(), &header).unwrap();
+        assert_eq!(restored.filter_type(), FilterType::Cuckoo);
+        assert_eq!(restored.member_count(), 5_000);
+        assert!(surviving.iter().all(|&k| restored.contains(k)));
+    }
+
+    #[test]
+    fn cuckoo_backed_full_is_an_error() {
+        let mut filter = MembershipFilter::new_cuckoo(FilterMode::Exclude, 8);
+        let mut inserted = Vec::new();
+        let err = (0..1_000u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .find_map(|k| match filter.insert(k) {
+                Ok(()) => {
+                    inserted.push(k);
+                    None
+                }
+                Err(e) => Some(e),
+            });
+        assert_eq!(err, Some(RvfError::Code(ErrorCode::MembershipFull)));
+        // Exclude mode: every inserted key is still hidden.
+        assert!(inserted.iter().all(|&k| !filter.contains(k)));
+        assert_eq!(filter.member_count(), inserted.len() as u64);
+    }
+
+    #[test]
+    fn xor_backed_rejects_insert_and_delete() {
+        use crate::xor_filter::XorFilterBuilder;
+
+        let mut builder = XorFilterBuilder::new(FilterType::Xor8).unwrap();
+        builder.extend([1, 2, 3]).unwrap();
+        let mut filter =
+            MembershipFilter::new_xor(FilterMode::Include, builder.finalize().unwrap());
+        assert_eq!(
+            filter.insert(4),
+            Err(RvfError::Code(ErrorCode::SnapshotFrozen))
+        );
+        assert_eq!(
+            filter.delete(1),
+            Err(RvfError::Code(ErrorCode::SnapshotFrozen))
+        );
+    }
 }
@@ -0,0 +1,128 @@
+//! Store-level monitoring metrics.
+//!
+//! [`StoreMetrics`] is a point-in-time snapshot assembled from counters the
+//! store already maintains, so taking one does not scan vectors or re-read
+//! the file. Query counters are atomics because queries take `&self`.
+//!
+//! There is no cache hit rate: the store keeps every vector in memory from
+//! open onward and has no query or page cache whose hits could be counted.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::options::DistanceMetric;
+
+/// Upper bounds (inclusive, microseconds) of the query latency buckets.
+/// A final overflow bucket catches everything slower.
+pub const LATENCY_BUCKETS_US: [u64; 6] = [100, 500, 1_000, 10_000, 100_000, 1_000_000];
+
+/// Distribution of query latencies since the store was opened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Upper bound of each bucket in microseconds (see `LATENCY_BUCKETS_US`).
+    pub bounds_us: &'static [u64],
+    /// Per-bucket counts; one longer than `bounds_us`, the last entry
+    /// counting queries slower than every bound. Not cumulative.
+    pub counts: Vec<u64>,
+    /// Sum of all recorded latencies in microseconds.
+    pub sum_us: u64,
+}
+
+impl LatencyHistogram {
+    /// Total number of recorded queries.
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Mean latency in microseconds (0.0 if nothing was recorded).
+    pub fn mean_us(&self) -> f64 {
+        let count = self.count();
+        if count == 0 {
+            0.0
+        } else {
+            self.sum_us as f64 / count as f64
+        }
+    }
+}
+
+/// A consolidated monitoring snapshot of an open store.
+#[derive(Clone, Debug)]
+pub struct StoreMetrics {
+    /// Vectors held in memory, including soft-deleted ones.
+    pub total_vectors: u64,
+    /// Vectors not marked deleted.
+    pub live_vectors: u64,
+    /// Number of segments keyed by raw segment type.
+    pub segments_by_type: BTreeMap<u8, u32>,
+    /// Total number of segments in the file.
+    pub total_segments: u32,
+    /// Vector dimensionality.
+    pub dimension: u16,
+    /// Distance metric.
+    pub metric: DistanceMetric,
+    /// Queries executed since the store was opened.
+    pub query_count: u64,
+    /// Latency distribution of those queries.
+    pub query_latency: LatencyHistogram,
+    /// Size of the file on disk in bytes.
+    pub file_bytes: u64,
+    /// Size of the live vector data as f32 (`live_vectors * dimension * 4`).
+    pub logical_bytes: u64,
+    /// Current manifest epoch.
+    pub epoch: u32,
+}
+
+/// Cumulative query counters, updated from `&self` query paths.
+#[derive(Debug, Default)]
+pub(crate) struct QueryStats {
+    buckets: [AtomicU64; LATENCY_BUCKETS_US.len() + 1],
+    sum_us: AtomicU64,
+}
+
+impl QueryStats {
+    /// Record one completed query.
+    pub(crate) fn record(&self, elapsed: Duration) {
+        let us = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        let bucket = LATENCY_BUCKETS_US
+            .iter()
+            .position(|&bound| us <= bound)
+            .unwrap_or(LATENCY_BUCKETS_US.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(us, Ordering::Relaxed);
+    }
+
+    /// Snapshot the latency histogram.
+    pub(crate) fn histogram(&self) -> LatencyHistogram {
+        LatencyHistogram {
+            bounds_us: &LATENCY_BUCKETS_US,
+            counts: self
+                .buckets
+                .iter()
+                .map(|b| b.load(Ordering::Relaxed))
+                .collect(),
+            sum_us: self.sum_us.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latencies_land_in_buckets() {
+        let stats = QueryStats::default();
+        stats.record(Duration::from_micros(50));
+        stats.record(Duration::from_micros(100));
+        stats.record(Duration::from_millis(5));
+        stats.record(Duration::from_secs(3));
+
+        let h = stats.histogram();
+        assert_eq!(h.counts, vec![2, 0, 0, 1, 0, 0, 1]);
+        assert_eq!(h.count(), 4);
+        assert_eq!(h.sum_us, 50 + 100 + 5_000 + 3_000_000);
+        assert!((h.mean_us() - 3_005_150.0 / 4.0).abs() < 1e-9);
+        assert_eq!(QueryStats::default().histogram().mean_us(), 0.0);
+    }
+}
@@ -0,0 +1,85 @@
+//! Multi-vector records: several embeddings stored under one entry id.
+//!
+//! A document may carry a title, body and summary embedding that should
+//! live and die together. Each sub-vector is stored as an ordinary vector
+//! in the same VEC_SEG, under an id that packs the entry id with a
+//! sub-field tag:
+//!
+//! ```text
+//! bit 63 = 1 | bits 62..8 = entry id | bits 7..0 = sub-field tag
+//! ```
+//!
+//! Plain ids and entry ids share one id space: `ingest_batch` rejects ids
+//! with `MULTI_VECTOR_ID_FLAG` set or that name an existing entry, and an
+//! entry may not reuse a plain vector's id. Entry ids must stay at or
+//! below `MAX_MULTI_VECTOR_ENTRY_ID`. Queries skip sub-vectors unless
+//! `QueryOptions::sub_field` names a tag, in which case they search only
+//! that tag and report entry ids.
+
+/// Id bit marking a stored vector as a sub-vector of a multi-vector entry.
+pub const MULTI_VECTOR_ID_FLAG: u64 = 1 << 63;
+
+/// Largest entry id a multi-vector record can use.
+pub const MAX_MULTI_VECTOR_ENTRY_ID: u64 = (1 << 55) - 1;
+
+/// One logical entry with a vector per sub-field tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiVectorRecord {
+    /// Entry id, reported in query results.
+    pub id: u64,
+    /// `(sub-field tag, vector)` pairs, in tag order once fetched.
+    pub fields: Vec<(u8, Vec<f32>)>,
+}
+
+impl MultiVectorRecord {
+    /// Create a record with no sub-fields.
+    pub fn new(id: u64) -> Self {
+        Self {
+            id,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add the vector for sub-field `tag`.
+    pub fn with_field(mut self, tag: u8, vector: Vec<f32>) -> Self {
+        self.fields.push((tag, vector));
+        self
+    }
+
+    /// The vector stored for sub-field `tag`.
+    pub fn field(&self, tag: u8) -> Option<&[f32]> {
+        self.fields
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, v)| v.as_slice())
+    }
+}
+
+/// Storage id of sub-field `tag` of entry `entry`.
+pub fn sub_vector_id(entry: u64, tag: u8) -> u64 {
+    debug_assert!(entry <= MAX_MULTI_VECTOR_ENTRY_ID);
+    MULTI_VECTOR_ID_FLAG | (entry << 8) | tag as u64
+}
+
+/// Split a storage id into `(entry, tag)` if it names a sub-vector.
+pub fn split_sub_vector_id(id: u64) -> Option<(u64, u8)> {
+    if id & MULTI_VECTOR_ID_FLAG == 0 {
+        return None;
+    }
+    Some(((id & !MULTI_VECTOR_ID_FLAG) >> 8, id as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_vector_ids_round_trip() {
+        for (entry, tag) in [(0, 0), (42, 3), (MAX_MULTI_VECTOR_ENTRY_ID, 255)] {
+            let id = sub_vector_id(entry, tag);
+            assert_eq!(split_sub_vector_id(id), Some((entry, tag)));
+        }
+        assert_eq!(split_sub_vector_id(42), None);
+        assert_eq!(split_sub_vector_id(MULTI_VECTOR_ID_FLAG - 1), None);
+    }
+}
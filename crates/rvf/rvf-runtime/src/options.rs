@@ -1,11 +1,19 @@
 //! Configuration types for the RVF runtime.
 
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::encryption::EncryptionConfig;
 use crate::filter::FilterExpr;
+use crate::locking::LockOptions;
 use rvf_types::quality::{
     BudgetReport, DegradationReport, QualityPreference, ResponseQuality, SafetyNetBudget,
     SearchEvidenceSummary,
 };
 use rvf_types::security::SecurityPolicy;
+use rvf_types::RvfError;
 
 /// Distance metric used for vector similarity search.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -19,6 +27,121 @@ pub enum DistanceMetric {
     Cosine,
 }
 
+/// When vectors are L2-normalized to unit length.
+///
+/// Cosine rankings depend only on direction, so normalizing on ingest lets
+/// inner-product search stand in for cosine, and normalizing queries keeps
+/// distances comparable across callers that forget to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NormalizePolicy {
+    /// Store and query vectors exactly as given.
+    #[default]
+    None,
+    /// Normalize vectors before they are written.
+    L2OnIngest,
+    /// Normalize query vectors before distances are computed.
+    L2OnQuery,
+    /// Normalize both ingested and query vectors.
+    L2Both,
+}
+
+impl NormalizePolicy {
+    /// Whether ingested vectors are normalized.
+    pub fn on_ingest(self) -> bool {
+        matches!(self, Self::L2OnIngest | Self::L2Both)
+    }
+
+    /// Whether query vectors are normalized.
+    pub fn on_query(self) -> bool {
+        matches!(self, Self::L2OnQuery | Self::L2Both)
+    }
+}
+
+/// How ingest reshapes a vector whose dimension differs from the store's.
+///
+/// Without a projection, a mismatched vector fails the whole batch with
+/// `RvfError::DimensionMismatch`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Projection {
+    /// Keep the leading components of longer vectors.
+    Truncate,
+    /// Zero-extend shorter vectors.
+    Pad,
+    /// Multiply by a row-major `dimension x input_dim` matrix.
+    Matrix {
+        /// Input dimension the matrix accepts.
+        input_dim: usize,
+        /// Row-major weights, `dimension * input_dim` entries.
+        weights: Vec<f32>,
+    },
+}
+
+impl Projection {
+    /// A sparse random projection (Achlioptas) from `input_dim` to
+    /// `dimension`, generated deterministically from `seed`.
+    ///
+    /// Entries are `+-sqrt(3 / dimension)` with probability 1/6 each and 0
+    /// otherwise, which approximately preserves pairwise distances.
+    pub fn random(input_dim: usize, dimension: usize, seed: u64) -> Self {
+        let scale = (3.0 / dimension.max(1) as f32).sqrt();
+        let mut state = seed;
+        let weights = (0..input_dim * dimension)
+            .map(|_| {
+                // splitmix64
+                state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                match (z ^ (z >> 31)) % 6 {
+                    0 => scale,
+                    1 => -scale,
+                    _ => 0.0,
+                }
+            })
+            .collect();
+        Self::Matrix { input_dim, weights }
+    }
+
+    /// Reshape `vector` to `dimension` components.
+    ///
+    /// Fails with `DimensionMismatch` when the projection does not cover the
+    /// input (e.g. `Pad` given a longer vector), and with `SizeMismatch` when
+    /// a matrix has the wrong number of weights.
+    pub fn apply(&self, vector: &[f32], dimension: usize) -> Result<Vec<f32>, RvfError> {
+        let mismatch = || RvfError::DimensionMismatch {
+            expected: dimension,
+            got: vector.len(),
+        };
+        match self {
+            Self::Truncate if vector.len() >= dimension => Ok(vector[..dimension].to_vec()),
+            Self::Pad if vector.len() <= dimension => {
+                let mut out = vector.to_vec();
+                out.resize(dimension, 0.0);
+                Ok(out)
+            }
+            Self::Truncate | Self::Pad => Err(mismatch()),
+            Self::Matrix { input_dim, weights } => {
+                if vector.len() != *input_dim {
+                    return Err(RvfError::DimensionMismatch {
+                        expected: *input_dim,
+                        got: vector.len(),
+                    });
+                }
+                if weights.len() != dimension * input_dim {
+                    return Err(RvfError::SizeMismatch {
+                        expected: dimension * input_dim,
+                        got: weights.len(),
+                    });
+                }
+                Ok(weights
+                    .chunks_exact(*input_dim)
+                    .map(|row| row.iter().zip(vector).map(|(w, x)| w * x).sum())
+                    .collect())
+            }
+        }
+    }
+}
+
 /// Compression profile for stored vectors.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum CompressionProfile {
@@ -79,6 +202,17 @@ pub struct RvfOptions {
     pub witness: WitnessConfig,
     /// Security policy for manifest signature verification (ADR-033 §4).
     pub security_policy: SecurityPolicy,
+    /// Encrypt VEC/INDEX/META payloads at rest (requires the `encryption` feature).
+    pub encryption: Option<EncryptionConfig>,
+    /// L2 normalization applied to ingested and/or query vectors.
+    pub normalization: NormalizePolicy,
+    /// Reshape ingested vectors of the wrong dimension instead of failing.
+    /// Each reshaped vector is reported in `IngestResult::warnings`.
+    pub auto_project: Option<Projection>,
+    /// Expected metadata value kinds; ingests that violate it are rejected.
+    pub metadata_schema: Option<MetadataSchema>,
+    /// Writer lock acquisition timeout and stale-lock threshold.
+    pub lock: LockOptions,
 }
 
 impl Default for RvfOptions {
@@ -94,6 +228,56 @@ impl Default for RvfOptions {
             ef_construction: 200,
             witness: WitnessConfig::default(),
             security_policy: SecurityPolicy::Strict,
+            encryption: None,
+            normalization: NormalizePolicy::None,
+            auto_project: None,
+            metadata_schema: None,
+            lock: LockOptions::default(),
+        }
+    }
+}
+
+/// Options for `RvfStore::open_mmap`.
+#[derive(Clone, Debug)]
+pub struct MmapOptions {
+    /// Open without the writer lock; mutations fail with `ReadOnly`.
+    pub read_only: bool,
+    /// Issue `madvise` access-pattern hints on the mapping.
+    pub advise: bool,
+    /// Key for an encrypted store.
+    pub encryption: Option<EncryptionConfig>,
+    /// Writer-lock timeout and stale threshold; ignored when `read_only`.
+    pub lock: LockOptions,
+}
+
+impl Default for MmapOptions {
+    fn default() -> Self {
+        Self {
+            read_only: true,
+            advise: true,
+            encryption: None,
+            lock: LockOptions::default(),
+        }
+    }
+}
+
+/// Options for `AsyncBoot::open`.
+#[derive(Clone, Debug)]
+pub struct AsyncOpenOptions {
+    /// Open without the writer lock; mutations fail with `ReadOnly`.
+    pub read_only: bool,
+    /// Key for an encrypted store.
+    pub encryption: Option<EncryptionConfig>,
+    /// Writer-lock timeout and stale threshold; ignored when `read_only`.
+    pub lock: LockOptions,
+}
+
+impl Default for AsyncOpenOptions {
+    fn default() -> Self {
+        Self {
+            read_only: true,
+            encryption: None,
+            lock: LockOptions::default(),
         }
     }
 }
@@ -107,11 +291,55 @@ pub struct QueryOptions {
     pub filter: Option<FilterExpr>,
     /// Query timeout in milliseconds (0 = no timeout).
     pub timeout_ms: u32,
+    /// Hard deadline for the scan. When reached, the query returns the best
+    /// top-k among the vectors scanned so far instead of running over.
+    pub deadline: Option<Duration>,
     /// Quality vs latency preference (ADR-033).
     pub quality_preference: QualityPreference,
     /// Safety net budget caps. Callers may tighten but not loosen
     /// beyond the mode default (unless PreferQuality, which extends to 4x).
     pub safety_net_budget: SafetyNetBudget,
+    /// Number of candidates fetched for query-time reranking (`rerank`
+    /// or `RvfStore::search_with_reranker`). Raised to `k` if smaller.
+    pub rerank_pool_size: usize,
+    /// Search one sub-field of multi-vector entries instead of plain
+    /// vectors (see `crate::multi_vector`). Results carry entry ids.
+    pub sub_field: Option<u8>,
+    /// Rescore the `rerank_pool_size` nearest candidates before the final
+    /// top-k is taken (applied by `RvfStore::query`).
+    pub rerank: Option<Reranker>,
+}
+
+/// Post-search rescoring hook for [`QueryOptions::rerank`].
+///
+/// Called once per candidate with the candidate and its stored vector; the
+/// returned value replaces `SearchResult::distance` (lower = more similar).
+/// Candidates are then re-sorted by the new distance, ties in ANN order,
+/// and truncated to `k`.
+#[derive(Clone)]
+pub struct Reranker(Arc<RerankFn>);
+
+type RerankFn = dyn Fn(&SearchResult, &[f32]) -> f32 + Send + Sync;
+
+impl Reranker {
+    /// Wrap a scoring function.
+    pub fn new<F>(score: F) -> Self
+    where
+        F: Fn(&SearchResult, &[f32]) -> f32 + Send + Sync + 'static,
+    {
+        Self(Arc::new(score))
+    }
+
+    /// Score one candidate.
+    pub fn score(&self, result: &SearchResult, vector: &[f32]) -> f32 {
+        (self.0)(result, vector)
+    }
+}
+
+impl fmt::Debug for Reranker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Reranker(..)")
+    }
 }
 
 impl Default for QueryOptions {
@@ -120,8 +348,12 @@ impl Default for QueryOptions {
             ef_search: 100,
             filter: None,
             timeout_ms: 0,
+            deadline: None,
             quality_preference: QualityPreference::Auto,
             safety_net_budget: SafetyNetBudget::LAYER_A,
+            rerank_pool_size: 100,
+            sub_field: None,
+            rerank: None,
         }
     }
 }
@@ -137,6 +369,15 @@ pub struct SearchResult {
     pub retrieval_quality: rvf_types::quality::RetrievalQuality,
 }
 
+/// A search result rescored by a user-supplied reranker.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RerankedResult {
+    /// The original ANN result, including its distance.
+    pub result: SearchResult,
+    /// Score assigned by the reranker (lower = better, like a distance).
+    pub score: f32,
+}
+
 /// The mandatory outer return type for all query APIs (ADR-033 §2.4).
 ///
 /// This is not optional. This is not a nested field.
@@ -156,6 +397,45 @@ pub struct QualityEnvelope {
     pub degradation: Option<DegradationReport>,
 }
 
+/// Execution plan for a query, as reported by `RvfStore::explain_search`.
+///
+/// Mirrors SQL `EXPLAIN`: describes what a search would touch without
+/// running it. The store has no ANN index, so every query is a full scan
+/// of the segments listed here.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchPlan {
+    /// IDs of the VEC segments the scan reads.
+    pub segments: Vec<u64>,
+    /// Live (non-deleted) vectors in the store.
+    pub live_vectors: u64,
+    /// Vectors the scan computes a distance for: live vectors that pass
+    /// the filter and sub-field selection.
+    pub candidates: u64,
+    /// Whether `query_with_envelope` would follow the scan with a safety
+    /// net pass, unless a deadline cuts the scan short.
+    pub safety_net_likely: bool,
+}
+
+/// Result of sampling stored vectors for unit L2 norm.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NormalizationReport {
+    /// Number of live vectors inspected.
+    pub sampled: u64,
+    /// Sampled vectors whose norm differs from 1 by more than `tolerance`.
+    pub deviating: u64,
+    /// Largest observed `|norm - 1|`.
+    pub max_deviation: f32,
+    /// Tolerance used to classify a vector as deviating.
+    pub tolerance: f32,
+}
+
+impl NormalizationReport {
+    /// Whether every sampled vector had unit norm within tolerance.
+    pub fn is_normalized(&self) -> bool {
+        self.deviating == 0
+    }
+}
+
 /// Result of a batch ingest operation.
 #[derive(Clone, Debug)]
 pub struct IngestResult {
@@ -165,6 +445,22 @@ pub struct IngestResult {
     pub rejected: u64,
     /// Manifest epoch after the ingest commit.
     pub epoch: u32,
+    /// Non-fatal adjustments made while ingesting.
+    pub warnings: Vec<IngestWarning>,
+}
+
+/// A non-fatal adjustment made by `RvfStore::ingest_batch`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IngestWarning {
+    /// A vector was reshaped by `RvfOptions::auto_project`.
+    Projected {
+        /// ID of the reshaped vector.
+        id: u64,
+        /// Dimension the vector was given with.
+        from: usize,
+        /// Store dimension it was projected to.
+        to: usize,
+    },
 }
 
 /// Result of a delete operation.
@@ -174,6 +470,9 @@ pub struct DeleteResult {
     pub deleted: u64,
     /// Manifest epoch after the delete commit.
     pub epoch: u32,
+    /// When the tombstones become reclaimable by compaction (Unix
+    /// milliseconds); `None` for a TTL of zero, i.e. reclaimable at once.
+    pub expires_at_ms: Option<u64>,
 }
 
 /// Result of a compaction operation.
@@ -187,6 +486,43 @@ pub struct CompactionResult {
     pub epoch: u32,
 }
 
+/// Progress of a running compaction, reported at segment boundaries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompactionProgress {
+    /// Segments written to the compacted file so far.
+    pub segments_processed: u32,
+    /// Segments the compacted file will hold, including its manifest.
+    pub segments_total: u32,
+    /// Bytes of dead space dropped so far.
+    pub bytes_reclaimed: u64,
+    /// Estimated bytes still to be written.
+    pub bytes_remaining: u64,
+}
+
+impl CompactionProgress {
+    /// Fraction of segments written, in `[0.0, 1.0]`.
+    pub fn fraction(&self) -> f64 {
+        if self.segments_total == 0 {
+            1.0
+        } else {
+            self.segments_processed as f64 / self.segments_total as f64
+        }
+    }
+
+    /// Whether the compacted file has been committed.
+    pub fn is_done(&self) -> bool {
+        self.segments_processed == self.segments_total
+    }
+
+    /// Record one more segment written and return the updated progress.
+    pub(crate) fn advance(&mut self, written: u64, reclaimed: u64) -> Self {
+        self.segments_processed += 1;
+        self.bytes_reclaimed += reclaimed;
+        self.bytes_remaining = self.bytes_remaining.saturating_sub(written);
+        *self
+    }
+}
+
 /// A single metadata entry for a vector.
 #[derive(Clone, Debug)]
 pub struct MetadataEntry {
@@ -205,3 +541,93 @@ pub enum MetadataValue {
     String(String),
     Bytes(Vec<u8>),
 }
+
+impl MetadataValue {
+    /// The kind of this value, as declared in a [`MetadataSchema`].
+    pub fn kind(&self) -> MetadataKind {
+        match self {
+            Self::U64(_) => MetadataKind::U64,
+            Self::I64(_) => MetadataKind::I64,
+            Self::F64(_) => MetadataKind::F64,
+            Self::String(_) => MetadataKind::String,
+            Self::Bytes(_) => MetadataKind::Bytes,
+        }
+    }
+}
+
+/// The type of a [`MetadataValue`], without its payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataKind {
+    U64,
+    I64,
+    F64,
+    String,
+    Bytes,
+}
+
+/// Expected metadata value kind per field, checked on ingest.
+///
+/// Fields not declared in the schema are accepted unless the schema is
+/// strict.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MetadataSchema {
+    fields: BTreeMap<u16, MetadataKind>,
+    strict: bool,
+}
+
+impl MetadataSchema {
+    /// An empty, non-strict schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the kind expected for `field_id`.
+    pub fn with_field(mut self, field_id: u16, kind: MetadataKind) -> Self {
+        self.fields.insert(field_id, kind);
+        self
+    }
+
+    /// Reject fields that are not declared in the schema.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Kind declared for `field_id`, if any.
+    pub fn field(&self, field_id: u16) -> Option<MetadataKind> {
+        self.fields.get(&field_id).copied()
+    }
+
+    /// Declared fields in ascending id order.
+    pub fn fields(&self) -> impl Iterator<Item = (u16, MetadataKind)> + '_ {
+        self.fields.iter().map(|(&id, &kind)| (id, kind))
+    }
+
+    /// Whether undeclared fields are rejected.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Check `entries` against the schema.
+    ///
+    /// Fails with `MetadataSchemaViolation` naming the first offending field.
+    pub fn validate(&self, entries: &[MetadataEntry]) -> Result<(), RvfError> {
+        for entry in entries {
+            let reason = match self.fields.get(&entry.field_id) {
+                Some(&expected) if expected == entry.value.kind() => continue,
+                Some(MetadataKind::U64) => "expected u64",
+                Some(MetadataKind::I64) => "expected i64",
+                Some(MetadataKind::F64) => "expected f64",
+                Some(MetadataKind::String) => "expected string",
+                Some(MetadataKind::Bytes) => "expected bytes",
+                None if self.strict => "field not declared in strict schema",
+                None => continue,
+            };
+            return Err(RvfError::MetadataSchemaViolation {
+                field_id: entry.field_id,
+                reason,
+            });
+        }
+        Ok(())
+    }
+}
@@ -6,8 +6,11 @@
 //! 3. Background: parse Level 1 -> full segment directory
 //! 4. On-demand: load cold segments as queries need them
 
+use crate::multi_vector::split_sub_vector_id;
+use crate::write_path::MANIFEST_LSN_MARKER;
 use rvf_types::{FileIdentity, SegmentHeader, SegmentType, SEGMENT_HEADER_SIZE, SEGMENT_MAGIC};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
 use std::io::{self, Read, Seek, SeekFrom};
 
 /// A parsed segment directory entry.
@@ -30,6 +33,10 @@ pub(crate) struct ParsedManifest {
     pub segment_dir: Vec<SegDirEntry>,
     pub deleted_ids: Vec<u64>,
     pub file_identity: Option<FileIdentity>,
+    /// LSN of the last replication record applied (0 if none).
+    pub replicated_lsn: u64,
+    /// File offset of the manifest segment header (0 until located).
+    pub offset: u64,
 }
 
 /// In-memory vector storage loaded from VEC_SEGs.
@@ -38,6 +45,8 @@ pub(crate) struct VectorData {
     /// Maps vector_id -> (dimension-sized f32 slice stored as Vec<f32>).
     pub vectors: HashMap<u64, Vec<f32>>,
     pub dimension: u16,
+    /// Multi-vector entry id -> tags of its stored sub-vectors.
+    sub_fields: HashMap<u64, BTreeSet<u8>>,
 }
 
 impl VectorData {
@@ -45,6 +54,7 @@ impl VectorData {
         Self {
             vectors: HashMap::new(),
             dimension,
+            sub_fields: HashMap::new(),
         }
     }
 
@@ -53,11 +63,35 @@ impl VectorData {
     }
 
     pub(crate) fn insert(&mut self, id: u64, data: Vec<f32>) {
+        if let Some((entry, tag)) = split_sub_vector_id(id) {
+            self.sub_fields.entry(entry).or_default().insert(tag);
+        }
         self.vectors.insert(id, data);
     }
 
     pub(crate) fn remove(&mut self, id: u64) {
-        self.vectors.remove(&id);
+        if self.vectors.remove(&id).is_none() {
+            return;
+        }
+        if let Some((entry, tag)) = split_sub_vector_id(id) {
+            if let Some(tags) = self.sub_fields.get_mut(&entry) {
+                tags.remove(&tag);
+                if tags.is_empty() {
+                    self.sub_fields.remove(&entry);
+                }
+            }
+        }
+    }
+
+    /// Tags of the sub-vectors stored for multi-vector entry `entry`, in
+    /// ascending order.
+    pub(crate) fn sub_field_tags(&self, entry: u64) -> impl Iterator<Item = u8> + '_ {
+        self.sub_fields.get(&entry).into_iter().flatten().copied()
+    }
+
+    /// Whether `entry` names a multi-vector entry with stored sub-vectors.
+    pub(crate) fn is_multi_vector_entry(&self, entry: u64) -> bool {
+        self.sub_fields.contains_key(&entry)
     }
 
     pub(crate) fn len(&self) -> usize {
@@ -69,6 +103,101 @@ impl VectorData {
     }
 }
 
+/// A `(distance, id)` candidate, ordered by distance and then id.
+#[derive(Clone, Copy, Debug)]
+struct Nearest(f32, u64);
+
+impl PartialEq for Nearest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Nearest {}
+
+impl PartialOrd for Nearest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Nearest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// Bounded max-heap keeping the `k` nearest `(distance, id)` candidates.
+///
+/// Ties on distance are broken by id, so the kept set does not depend on
+/// the order candidates arrive in. Partial heaps from a parallel scan
+/// therefore merge into exactly the sequential result.
+pub(crate) struct TopK {
+    k: usize,
+    heap: BinaryHeap<Nearest>,
+}
+
+impl TopK {
+    pub(crate) fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k.min(4096)),
+        }
+    }
+
+    pub(crate) fn push(&mut self, distance: f32, id: u64) {
+        let candidate = Nearest(distance, id);
+        if self.heap.len() < self.k {
+            self.heap.push(candidate);
+        } else if self.heap.peek().is_some_and(|worst| candidate < *worst) {
+            self.heap.pop();
+            self.heap.push(candidate);
+        }
+    }
+
+    /// Fold another partial result into this one.
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    pub(crate) fn merge(mut self, other: Self) -> Self {
+        for Nearest(distance, id) in other.heap {
+            self.push(distance, id);
+        }
+        self
+    }
+
+    /// Candidates sorted nearest first.
+    pub(crate) fn into_sorted_vec(self) -> Vec<(f32, u64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Nearest(distance, id)| (distance, id))
+            .collect()
+    }
+}
+
+/// Scan `ids` in `partitions` chunks on the rayon pool and merge the
+/// partial top-k heaps.
+///
+/// `score` maps a stored vector id to its `(distance, result_id)`, or
+/// `None` to skip it. The result equals a sequential [`TopK`] over `ids`.
+#[cfg(feature = "parallel")]
+pub(crate) fn par_scan_top_k<F>(ids: &[u64], partitions: usize, k: usize, score: F) -> TopK
+where
+    F: Fn(u64) -> Option<(f32, u64)> + Sync,
+{
+    use rayon::prelude::*;
+
+    let chunk = ids.len().div_ceil(partitions.max(1)).max(1);
+    ids.par_chunks(chunk)
+        .map(|chunk| {
+            let mut top = TopK::new(k);
+            for (distance, id) in chunk.iter().filter_map(|&id| score(id)) {
+                top.push(distance, id);
+            }
+            top
+        })
+        .reduce(|| TopK::new(k), TopK::merge)
+}
+
 /// Scan backwards from EOF to find and parse the latest valid manifest.
 ///
 /// Reads a tail chunk and scans byte-by-byte for the magic + manifest-type
@@ -128,7 +257,9 @@ pub(crate) fn find_latest_manifest<R: Read + Seek>(
 
             if payload_end <= buf.len() {
                 // Payload is within our buffer — parse directly.
-                if let Some(manifest) = parse_manifest_payload(&buf[payload_start..payload_end]) {
+                if let Some(mut manifest) = parse_manifest_payload(&buf[payload_start..payload_end])
+                {
+                    manifest.offset = scan_start + i as u64;
                     return Ok(Some(manifest));
                 }
             } else {
@@ -137,7 +268,8 @@ pub(crate) fn find_latest_manifest<R: Read + Seek>(
                 reader.seek(SeekFrom::Start(file_offset))?;
                 let mut payload = vec![0u8; payload_length];
                 if reader.read_exact(&mut payload).is_ok() {
-                    if let Some(manifest) = parse_manifest_payload(&payload) {
+                    if let Some(mut manifest) = parse_manifest_payload(&payload) {
+                        manifest.offset = scan_start + i as u64;
                         return Ok(Some(manifest));
                     }
                 }
@@ -149,7 +281,7 @@ pub(crate) fn find_latest_manifest<R: Read + Seek>(
 }
 
 /// Parse a manifest payload into structured data.
-fn parse_manifest_payload(payload: &[u8]) -> Option<ParsedManifest> {
+pub(crate) fn parse_manifest_payload(payload: &[u8]) -> Option<ParsedManifest> {
     // Minimum header: epoch(4) + dim(2) + total_vectors(8) + seg_count(4) + profile(1) + pad(3) = 22
     if payload.len() < 22 {
         return None;
@@ -266,6 +398,7 @@ fn parse_manifest_payload(payload: &[u8]) -> Option<ParsedManifest> {
         if marker == 0x4649_4449 {
             offset += 4;
             let fi_data: &[u8; 68] = payload[offset..offset + 68].try_into().ok()?;
+            offset += 68;
             Some(FileIdentity::from_bytes(fi_data))
         } else {
             None
@@ -274,6 +407,14 @@ fn parse_manifest_payload(payload: &[u8]) -> Option<ParsedManifest> {
         None
     };
 
+    // Replicated LSN trailer, after the identity if there is one.
+    let replicated_lsn = match payload.get(offset..offset + 12) {
+        Some(t) if t[..4] == MANIFEST_LSN_MARKER.to_le_bytes() => {
+            u64::from_le_bytes(t[4..].try_into().ok()?)
+        }
+        _ => 0,
+    };
+
     Some(ParsedManifest {
         epoch,
         dimension,
@@ -282,6 +423,8 @@ fn parse_manifest_payload(payload: &[u8]) -> Option<ParsedManifest> {
         segment_dir,
         deleted_ids,
         file_identity,
+        replicated_lsn,
+        offset: 0,
     })
 }
 
@@ -335,6 +478,97 @@ pub(crate) fn read_vec_seg_payload(payload: &[u8]) -> Option<Vec<(u64, Vec<f32>)
     Some(result)
 }
 
+/// Read a JOURNAL_SEG payload and return the IDs of its DELETE_VECTOR entries.
+pub(crate) fn read_journal_seg_payload(payload: &[u8]) -> Option<Vec<u64>> {
+    if payload.len() < 16 {
+        return None;
+    }
+
+    let entry_count = u32::from_le_bytes([payload[0], payload[1], payload[2], payload[3]]) as usize;
+    let expected_size = entry_count.checked_mul(12)?.checked_add(16)?;
+    if payload.len() < expected_size {
+        return None;
+    }
+
+    let ids = payload[16..expected_size]
+        .chunks_exact(12)
+        .filter(|entry| entry[0] == 0x01)
+        .map(|entry| u64::from_le_bytes(entry[4..12].try_into().unwrap()))
+        .collect();
+    Some(ids)
+}
+
+/// Read the tombstone expiries (vector ID, Unix milliseconds) recorded in
+/// a JOURNAL_SEG payload.
+pub(crate) fn read_journal_expiries(payload: &[u8]) -> Vec<(u64, u64)> {
+    let Some(entry_count) = payload
+        .get(..4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    else {
+        return Vec::new();
+    };
+    let end = entry_count.saturating_mul(12).saturating_add(16);
+    let Some(entries) = payload.get(16..end) else {
+        return Vec::new();
+    };
+
+    let mut expiries = Vec::new();
+    let mut last_deleted = None;
+    for entry in entries.chunks_exact(12) {
+        let value = u64::from_le_bytes(entry[4..12].try_into().unwrap());
+        match entry[0] {
+            0x01 => last_deleted = Some(value),
+            0x02 => {
+                if let Some(id) = last_deleted.take() {
+                    expiries.push((id, value));
+                }
+            }
+            _ => last_deleted = None,
+        }
+    }
+    expiries
+}
+
+/// A reader over the first `len` bytes of `inner`.
+///
+/// Booting through one reads a file as it stood when that prefix was the
+/// whole file, ignoring anything appended later.
+pub(crate) struct PrefixReader<R> {
+    inner: R,
+    len: u64,
+    pos: u64,
+}
+
+impl<R: Read + Seek> PrefixReader<R> {
+    pub(crate) fn new(mut inner: R, len: u64) -> io::Result<Self> {
+        inner.seek(SeekFrom::Start(0))?;
+        Ok(Self { inner, len, pos: 0 })
+    }
+}
+
+impl<R: Read + Seek> Read for PrefixReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.pos);
+        let max = buf.len().min(remaining.min(usize::MAX as u64) as usize);
+        let n = self.inner.read(&mut buf[..max])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for PrefixReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(d) => self.len.checked_add_signed(d),
+            SeekFrom::Current(d) => self.pos.checked_add_signed(d),
+        }
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek before start"))?;
+        self.pos = self.inner.seek(SeekFrom::Start(target))?;
+        Ok(self.pos)
+    }
+}
+
 /// Maximum allowed payload size when reading segments (256 MiB).
 /// This prevents a malicious payload_length field from causing OOM.
 const MAX_READ_PAYLOAD: u64 = 256 * 1024 * 1024;
@@ -486,6 +720,29 @@ fn crc32_for_verify(data: &[u8]) -> u32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn top_k_is_order_independent() {
+        let candidates: Vec<(f32, u64)> = (0..100u64).map(|i| ((i % 7) as f32, i)).collect();
+        let mut forward = TopK::new(10);
+        for &(d, id) in &candidates {
+            forward.push(d, id);
+        }
+        let mut halves = (TopK::new(10), TopK::new(10));
+        for &(d, id) in candidates.iter().rev() {
+            if id % 2 == 0 {
+                halves.0.push(d, id);
+            } else {
+                halves.1.push(d, id);
+            }
+        }
+        let merged = halves.0.merge(halves.1).into_sorted_vec();
+        let forward = forward.into_sorted_vec();
+        assert_eq!(forward, merged);
+        // Distance 0 ties resolve to the smallest ids.
+        assert_eq!(forward[0], (0.0, 0));
+        assert_eq!(forward[9], (0.0, 63));
+    }
+
     #[test]
     fn parse_empty_manifest() {
         assert!(parse_manifest_payload(&[]).is_none());
@@ -516,4 +773,16 @@ mod tests {
         assert_eq!(result[1].0, 20);
         assert_eq!(result[1].1, vec![3.0, 4.0]);
     }
+
+    #[test]
+    fn prefix_reader_hides_tail() {
+        let data: Vec<u8> = (0..100u8).collect();
+        let mut reader = PrefixReader::new(io::Cursor::new(&data), 40).unwrap();
+        assert_eq!(reader.seek(SeekFrom::End(0)).unwrap(), 40);
+        reader.seek(SeekFrom::End(-10)).unwrap();
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, (30..40u8).collect::<Vec<_>>());
+        assert!(reader.seek(SeekFrom::Current(-100)).is_err());
+    }
 }
@@ -0,0 +1,139 @@
+//! Epoch-ordered replication stream between stores.
+//!
+//! Every mutation appends its data segments followed by a manifest carrying
+//! a new epoch, so the file itself is the replication log: the epoch of the
+//! manifest closing a group of segments is that group's log sequence number
+//! (LSN). A leader reads the groups back in file order with
+//! [`RvfStore::replication_stream`](crate::RvfStore::replication_stream) and
+//! a follower replays them with
+//! [`RvfStore::apply_replication`](crate::RvfStore::apply_replication).
+//!
+//! Compaction rewrites the file from scratch, dropping the history before
+//! it. A compacted file therefore starts with a manifest whose epoch is
+//! non-zero, and the vectors it covers are emitted as a single
+//! [`ReplicationOp::Snapshot`] that replaces the follower's contents.
+//!
+//! Only vector data and deletions are replicated; metadata, witness and
+//! other auxiliary segments stay local to the leader.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+
+use rvf_types::{ErrorCode, RvfError, SegmentType, SEGMENT_HEADER_SIZE};
+
+use crate::encryption::{self, EncryptionConfig};
+use crate::read_path;
+
+/// Log sequence number: the manifest epoch that made a record visible.
+pub type Lsn = u64;
+
+/// One operation carried by a replication record.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplicationOp {
+    /// Vectors appended by an ingest.
+    Ingest {
+        ids: Vec<u64>,
+        vectors: Vec<Vec<f32>>,
+    },
+    /// Vectors soft-deleted by a delete.
+    Delete { ids: Vec<u64> },
+    /// The full live set after a compaction.
+    Snapshot {
+        ids: Vec<u64>,
+        vectors: Vec<Vec<f32>>,
+    },
+}
+
+/// The operations made visible by one manifest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplicationRecord {
+    /// Epoch of the manifest that committed these operations.
+    pub lsn: Lsn,
+    /// LSN of the preceding record in the stream (0 for the first).
+    pub prev_lsn: Lsn,
+    /// Operations in the order they were appended.
+    pub ops: Vec<ReplicationOp>,
+}
+
+impl ReplicationRecord {
+    /// Whether this record replaces the follower's state wholesale.
+    pub fn is_snapshot(&self) -> bool {
+        self.ops
+            .iter()
+            .any(|op| matches!(op, ReplicationOp::Snapshot { .. }))
+    }
+}
+
+/// Walk `file` from the start and group its data segments by the manifest
+/// that committed them.
+///
+/// Stops at the first torn or corrupt segment; anything after the last
+/// manifest is uncommitted and is not emitted.
+pub(crate) fn read_records(
+    file: &File,
+    encryption: Option<&EncryptionConfig>,
+) -> Result<Vec<ReplicationRecord>, RvfError> {
+    let file_len = file
+        .metadata()
+        .map_err(|_| RvfError::Code(ErrorCode::FsyncFailed))?
+        .len();
+    let mut reader = BufReader::new(file);
+
+    let mut records = Vec::new();
+    let mut pending = Vec::new();
+    let mut first_manifest = true;
+    let mut prev_lsn = 0;
+    let mut offset = 0u64;
+
+    while offset + SEGMENT_HEADER_SIZE as u64 <= file_len {
+        let Ok((header, payload)) = read_path::read_segment_payload(&mut reader, offset) else {
+            break;
+        };
+        offset += SEGMENT_HEADER_SIZE as u64 + header.payload_length;
+
+        if header.seg_type == SegmentType::Vec as u8 {
+            let payload = encryption::decrypt_payload(encryption, &header, payload)?;
+            if let Some(entries) = read_path::read_vec_seg_payload(&payload) {
+                let (ids, vectors) = entries.into_iter().unzip();
+                pending.push(ReplicationOp::Ingest { ids, vectors });
+            }
+        } else if header.seg_type == SegmentType::Journal as u8 {
+            if let Some(ids) = read_path::read_journal_seg_payload(&payload) {
+                pending.push(ReplicationOp::Delete { ids });
+            }
+        } else if header.seg_type == SegmentType::Manifest as u8 {
+            let Some(manifest) = read_path::parse_manifest_payload(&payload) else {
+                break;
+            };
+            let lsn = manifest.epoch as Lsn;
+            let mut ops = std::mem::take(&mut pending);
+
+            // A fresh file opens with an epoch-0 manifest; anything else
+            // means history before this point was compacted away.
+            if first_manifest && lsn > 0 {
+                let deleted: HashSet<u64> = manifest.deleted_ids.iter().copied().collect();
+                let (ids, vectors) = ops
+                    .into_iter()
+                    .filter_map(|op| match op {
+                        ReplicationOp::Ingest { ids, vectors } => {
+                            Some(ids.into_iter().zip(vectors))
+                        }
+                        _ => None,
+                    })
+                    .flatten()
+                    .filter(|(id, _)| !deleted.contains(id))
+                    .unzip();
+                ops = vec![ReplicationOp::Snapshot { ids, vectors }];
+            }
+            first_manifest = false;
+
+            if !ops.is_empty() {
+                records.push(ReplicationRecord { lsn, prev_lsn, ops });
+                prev_lsn = lsn;
+            }
+        }
+    }
+
+    Ok(records)
+}
@@ -8,6 +8,10 @@
 //! 3. **Recency window**: recently ingested vectors not yet indexed
 //!
 //! All phases respect triple budget caps (time, candidates, distance ops).
+//!
+//! Callers that need a different trade-off can pick a [`SafetyNetStrategy`]
+//! (exhaustive, coarse-quantized, or abort) via
+//! [`safety_net_scan_with_strategy`].
 
 use std::time::Instant;
 
@@ -35,6 +39,35 @@ pub struct SafetyNetResult {
     pub budget_exhausted: bool,
     /// If degraded, the full report.
     pub degradation: Option<DegradationReport>,
+    /// Which fallback path the safety net took.
+    pub fallback_path: FallbackPath,
+}
+
+/// Fallback strategy the safety net follows once it activates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SafetyNetStrategy {
+    /// Three-phase selective scan (ADR-033 default).
+    #[default]
+    Selective,
+    /// Exact distances against every stored vector, within budget.
+    Exhaustive,
+    /// Int8-quantized distances against every stored vector; the best
+    /// `2 * k` are re-ranked with exact distances.
+    CoarseQuantized,
+    /// Skip the scan and report degradation; the index candidates stand.
+    AbortWithDegradation,
+}
+
+impl SafetyNetStrategy {
+    /// The fallback path recorded when this strategy is taken.
+    pub fn fallback_path(self) -> FallbackPath {
+        match self {
+            Self::Selective => FallbackPath::SafetyNetSelective,
+            Self::Exhaustive => FallbackPath::SafetyNetExhaustive,
+            Self::CoarseQuantized => FallbackPath::SafetyNetCoarseQuantized,
+            Self::AbortWithDegradation => FallbackPath::SafetyNetAborted,
+        }
+    }
 }
 
 /// Budget tracker enforcing all three caps simultaneously.
@@ -145,6 +178,7 @@ pub fn selective_safety_net_scan(
             budget_report: BudgetReport::default(),
             budget_exhausted: false,
             degradation: None,
+            fallback_path: FallbackPath::None,
         };
     }
 
@@ -269,7 +303,219 @@ pub fn selective_safety_net_scan(
         budget_report,
         budget_exhausted: tracker.exhausted,
         degradation,
+        fallback_path: FallbackPath::SafetyNetSelective,
+    }
+}
+
+/// Execute the safety net using a caller-chosen fallback strategy.
+///
+/// `trigger` is the reason the safety net activated (for example a
+/// [`DegradationReason::DegenerateDistribution`]). Unlike
+/// [`selective_safety_net_scan`], a degradation report is always returned
+/// when the scan runs: its `fallback_path` is the strategy's path, or
+/// [`FallbackPath::SafetyNetBudgetExhausted`] if a budget cap was hit.
+#[allow(clippy::too_many_arguments)]
+pub fn safety_net_scan_with_strategy(
+    query: &[f32],
+    k: usize,
+    hnsw_candidates: &[SearchResult],
+    all_vectors: &[(u64, &[f32])],
+    budget: &SafetyNetBudget,
+    vector_count: u64,
+    strategy: SafetyNetStrategy,
+    trigger: DegradationReason,
+) -> SafetyNetResult {
+    if budget.is_disabled() {
+        return selective_safety_net_scan(
+            query,
+            k,
+            hnsw_candidates,
+            all_vectors,
+            budget,
+            vector_count,
+        );
+    }
+
+    let fallback_path = strategy.fallback_path();
+    let (candidates, budget_report, tracker) = match strategy {
+        SafetyNetStrategy::Selective => {
+            let mut result = selective_safety_net_scan(
+                query,
+                k,
+                hnsw_candidates,
+                all_vectors,
+                budget,
+                vector_count,
+            );
+            if result.degradation.is_none() {
+                result.degradation = Some(DegradationReport {
+                    fallback_path,
+                    reason: trigger,
+                    guarantee_lost: "recall may be below target; selective scan only",
+                });
+            }
+            return result;
+        }
+        SafetyNetStrategy::AbortWithDegradation => {
+            return SafetyNetResult {
+                candidates: Vec::new(),
+                budget_report: BudgetReport::default(),
+                budget_exhausted: false,
+                degradation: Some(DegradationReport {
+                    fallback_path,
+                    reason: trigger,
+                    guarantee_lost: "recall may be below target; safety net aborted",
+                }),
+                fallback_path,
+            };
+        }
+        SafetyNetStrategy::Exhaustive => {
+            let mut tracker = BudgetTracker::new(budget);
+            let candidates = exhaustive_scan(query, hnsw_candidates, all_vectors, &mut tracker);
+            (candidates, tracker_report(&tracker, budget), tracker)
+        }
+        SafetyNetStrategy::CoarseQuantized => {
+            let mut tracker = BudgetTracker::new(budget);
+            let candidates =
+                coarse_quantized_scan(query, k, hnsw_candidates, all_vectors, &mut tracker);
+            (candidates, tracker_report(&tracker, budget), tracker)
+        }
+    };
+
+    let degradation = if tracker.exhausted {
+        DegradationReport {
+            fallback_path: FallbackPath::SafetyNetBudgetExhausted,
+            reason: DegradationReason::BudgetExhausted {
+                scanned: tracker.candidates_scanned,
+                total: vector_count,
+                budget_type: tracker.exhausted_type.unwrap_or(BudgetType::DistanceOps),
+            },
+            guarantee_lost: "recall may be below target; safety net budget exhausted",
+        }
+    } else {
+        DegradationReport {
+            fallback_path,
+            reason: trigger,
+            guarantee_lost: match strategy {
+                SafetyNetStrategy::CoarseQuantized => {
+                    "ranking approximated by int8 quantization before re-rank"
+                }
+                _ => "index routing bypassed; latency bound by budget",
+            },
+        }
+    };
+
+    SafetyNetResult {
+        candidates,
+        budget_report,
+        budget_exhausted: tracker.exhausted,
+        degradation: Some(degradation),
+        fallback_path,
+    }
+}
+
+fn tracker_report(tracker: &BudgetTracker, budget: &SafetyNetBudget) -> BudgetReport {
+    let elapsed = tracker.elapsed_us();
+    BudgetReport {
+        safety_net_scan_us: elapsed,
+        total_us: elapsed,
+        distance_ops: tracker.distance_ops,
+        distance_ops_budget: budget.max_distance_ops,
+        linear_scan_count: tracker.candidates_scanned,
+        linear_scan_budget: budget.max_scan_candidates,
+        ..BudgetReport::default()
+    }
+}
+
+/// Exact distance against every vector not already in `hnsw_candidates`.
+fn exhaustive_scan(
+    query: &[f32],
+    hnsw_candidates: &[SearchResult],
+    all_vectors: &[(u64, &[f32])],
+    tracker: &mut BudgetTracker,
+) -> Vec<Candidate> {
+    let existing_ids: std::collections::HashSet<u64> =
+        hnsw_candidates.iter().map(|c| c.id).collect();
+    let mut candidates = Vec::new();
+
+    for &(id, vec) in all_vectors {
+        if tracker.is_exceeded() {
+            break;
+        }
+        if existing_ids.contains(&id) || vec.len() != query.len() {
+            continue;
+        }
+        let distance = l2_distance_sq(query, vec);
+        candidates.push(Candidate { id, distance });
+        if !tracker.record_distance_op() {
+            break;
+        }
+    }
+    candidates
+}
+
+/// Int8 distance against every vector, then exact re-rank of the best `2 * k`.
+///
+/// Components are quantized on a shared scale covering the largest magnitude
+/// in the query and the scanned vectors, so neither side saturates and the
+/// coarse pass preserves ordering well enough to shortlist without touching
+/// full-precision distances.
+fn coarse_quantized_scan(
+    query: &[f32],
+    k: usize,
+    hnsw_candidates: &[SearchResult],
+    all_vectors: &[(u64, &[f32])],
+    tracker: &mut BudgetTracker,
+) -> Vec<Candidate> {
+    let existing_ids: std::collections::HashSet<u64> =
+        hnsw_candidates.iter().map(|c| c.id).collect();
+    let scanned: Vec<(u64, &[f32])> = all_vectors
+        .iter()
+        .copied()
+        .filter(|(id, vec)| !existing_ids.contains(id) && vec.len() == query.len())
+        .collect();
+
+    let max_abs = scanned
+        .iter()
+        .flat_map(|(_, vec)| vec.iter())
+        .chain(query)
+        .fold(0.0f32, |m, x| m.max(x.abs()));
+    let scale = if max_abs > f32::EPSILON {
+        127.0 / max_abs
+    } else {
+        1.0
+    };
+    let quantize = |x: f32| (x * scale).round() as i32;
+    let q: Vec<i32> = query.iter().map(|&x| quantize(x)).collect();
+
+    let mut coarse: Vec<(u64, i64, &[f32])> = Vec::new();
+    for (id, vec) in scanned {
+        if tracker.is_exceeded() {
+            break;
+        }
+        let d: i64 = q
+            .iter()
+            .zip(vec.iter())
+            .map(|(&a, &b)| {
+                let diff = (a - quantize(b)) as i64;
+                diff * diff
+            })
+            .sum();
+        coarse.push((id, d, vec));
+        if !tracker.record_distance_op() {
+            break;
+        }
     }
+
+    coarse.sort_by_key(|&(id, d, _)| (d, id));
+    coarse.truncate(2 * k.max(1));
+    coarse
+        .into_iter()
+        .map(|(id, _, vec)| Candidate {
+            id,
+            distance: l2_distance_sq(query, vec),
+        })
+        .collect()
 }
 
 /// Determine if the safety net should activate.
@@ -379,6 +625,148 @@ mod tests {
         }
     }
 
+    fn degenerate_trigger() -> (Vec<(u64, Vec<f32>)>, DegradationReason) {
+        // Every vector sits on the unit sphere around the query: all
+        // distances are identical, so centroid routing has nothing to go on.
+        let vecs: Vec<(u64, Vec<f32>)> = (0..64)
+            .map(|i| {
+                let angle = i as f32 * core::f32::consts::TAU / 64.0;
+                (i as u64, vec![angle.cos(), angle.sin()])
+            })
+            .collect();
+        let distances: Vec<f32> = vecs
+            .iter()
+            .map(|(_, v)| l2_distance_sq(&[0.0; 2], v))
+            .collect();
+        assert!(crate::adversarial::is_degenerate_distribution(
+            &distances, 8
+        ));
+        let cv = crate::adversarial::centroid_distance_cv(&distances, 8);
+        let reason = DegradationReason::DegenerateDistribution {
+            cv,
+            threshold: crate::adversarial::DEGENERATE_CV_THRESHOLD,
+        };
+        (vecs, reason)
+    }
+
+    #[test]
+    fn strategy_paths_taken_on_degenerate_trigger() {
+        let (vecs, trigger) = degenerate_trigger();
+        let refs: Vec<(u64, &[f32])> = vecs.iter().map(|(id, v)| (*id, v.as_slice())).collect();
+        let query = [0.0f32; 2];
+
+        for strategy in [
+            SafetyNetStrategy::Selective,
+            SafetyNetStrategy::Exhaustive,
+            SafetyNetStrategy::CoarseQuantized,
+            SafetyNetStrategy::AbortWithDegradation,
+        ] {
+            let result = safety_net_scan_with_strategy(
+                &query,
+                4,
+                &[],
+                &refs,
+                &SafetyNetBudget::LAYER_A,
+                64,
+                strategy,
+                trigger,
+            );
+            assert_eq!(result.fallback_path, strategy.fallback_path());
+            assert!(!result.budget_exhausted);
+            let deg = result.degradation.expect("degradation reported");
+            assert_eq!(deg.fallback_path, strategy.fallback_path());
+            assert_eq!(deg.reason, trigger);
+
+            match strategy {
+                SafetyNetStrategy::Exhaustive => assert_eq!(result.candidates.len(), 64),
+                SafetyNetStrategy::CoarseQuantized => assert_eq!(result.candidates.len(), 8),
+                SafetyNetStrategy::AbortWithDegradation => {
+                    assert!(result.candidates.is_empty());
+                    assert_eq!(result.budget_report.distance_ops, 0);
+                }
+                SafetyNetStrategy::Selective => assert!(!result.candidates.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn coarse_quantized_reranks_with_exact_distance() {
+        let query = vec![0.0; 4];
+        let vecs = make_vectors(200, 4);
+        let refs: Vec<(u64, &[f32])> = vecs.iter().map(|(id, v)| (*id, v.as_slice())).collect();
+        let trigger = DegradationReason::DegenerateDistribution {
+            cv: 0.0,
+            threshold: crate::adversarial::DEGENERATE_CV_THRESHOLD,
+        };
+
+        let result = safety_net_scan_with_strategy(
+            &query,
+            5,
+            &[],
+            &refs,
+            &SafetyNetBudget::LAYER_A,
+            200,
+            SafetyNetStrategy::CoarseQuantized,
+            trigger,
+        );
+        assert_eq!(result.candidates.len(), 10);
+        assert_eq!(result.candidates[0].id, 0);
+        for c in &result.candidates {
+            let exact = l2_distance_sq(&query, &vecs[c.id as usize].1);
+            assert!((c.distance - exact).abs() < f32::EPSILON);
+        }
+    }
+
+    #[test]
+    fn coarse_quantized_scale_covers_stored_vectors() {
+        // A query much smaller than the data: scaling by the query alone
+        // would saturate every stored component and shortlist by id.
+        let query = vec![0.01; 4];
+        let vecs: Vec<(u64, Vec<f32>)> = (0..200u64)
+            .map(|i| (i, vec![(200 - i) as f32; 4]))
+            .collect();
+        let refs: Vec<(u64, &[f32])> = vecs.iter().map(|(id, v)| (*id, v.as_slice())).collect();
+        let mut tracker = BudgetTracker::new(&SafetyNetBudget::LAYER_A);
+
+        let candidates = coarse_quantized_scan(&query, 5, &[], &refs, &mut tracker);
+        let mut ids: Vec<u64> = candidates.iter().map(|c| c.id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (190..200).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn strategy_budget_exhaustion_overrides_path() {
+        let query = vec![0.0; 4];
+        let vecs = make_vectors(1_000, 4);
+        let refs: Vec<(u64, &[f32])> = vecs.iter().map(|(id, v)| (*id, v.as_slice())).collect();
+        let tiny_budget = SafetyNetBudget {
+            max_scan_time_us: 1_000_000,
+            max_scan_candidates: 5,
+            max_distance_ops: 5,
+        };
+        let trigger = DegradationReason::DegenerateDistribution {
+            cv: 0.0,
+            threshold: crate::adversarial::DEGENERATE_CV_THRESHOLD,
+        };
+
+        let result = safety_net_scan_with_strategy(
+            &query,
+            10,
+            &[],
+            &refs,
+            &tiny_budget,
+            1_000,
+            SafetyNetStrategy::Exhaustive,
+            trigger,
+        );
+        assert!(result.budget_exhausted);
+        assert_eq!(result.fallback_path, FallbackPath::SafetyNetExhaustive);
+        assert_eq!(
+            result.degradation.unwrap().fallback_path,
+            FallbackPath::SafetyNetBudgetExhausted
+        );
+    }
+
     #[test]
     fn should_activate_when_insufficient() {
         assert!(should_activate_safety_net(3, 5));
@@ -0,0 +1,252 @@
+//! Store settings persisted with the file.
+//!
+//! Options that change how vectors are ingested or queried must survive a
+//! reopen: a store created with L2 normalization that forgot it on `open`
+//! would mix normalized and raw vectors. They are written once, when the
+//! store is created, as a SETTINGS_SEG of TLV records listed in the
+//! manifest's segment directory. Only settings that differ from the
+//! defaults are recorded, unknown tags are skipped, and a store without
+//! the segment opens with the defaults.
+
+use rvf_types::{RvfError, TlvReader, TlvWriter};
+
+use crate::options::{MetadataKind, MetadataSchema, NormalizePolicy, Projection, RvfOptions};
+
+/// `RvfOptions::normalization`, as a u64 code.
+const TAG_NORMALIZATION: u16 = 1;
+/// `RvfOptions::auto_project`, as nested `PROJECTION_*` records.
+const TAG_AUTO_PROJECT: u16 = 2;
+/// `RvfOptions::metadata_schema`, as nested `SCHEMA_*` records.
+const TAG_METADATA_SCHEMA: u16 = 3;
+
+/// Projection kind: 0 = truncate, 1 = pad, 2 = matrix.
+const PROJECTION_KIND: u16 = 1;
+/// Matrix input dimension.
+const PROJECTION_INPUT_DIM: u16 = 2;
+/// Matrix weights, row-major little-endian f32.
+const PROJECTION_WEIGHTS: u16 = 3;
+
+/// 1 if undeclared fields are rejected, else 0.
+const SCHEMA_STRICT: u16 = 1;
+/// Declared fields, each `field_id(u16) | kind(u8)`.
+const SCHEMA_FIELDS: u16 = 2;
+
+/// Encode the settings in `options` that differ from the defaults, or
+/// `None` when there is nothing to record.
+pub(crate) fn encode(options: &RvfOptions) -> Option<Vec<u8>> {
+    let mut tlv = TlvWriter::new();
+    if options.normalization != NormalizePolicy::None {
+        tlv.u64(TAG_NORMALIZATION, normalize_code(options.normalization));
+    }
+    if let Some(projection) = &options.auto_project {
+        tlv.nested(TAG_AUTO_PROJECT, |inner| {
+            encode_projection(inner, projection)
+        });
+    }
+    if let Some(schema) = &options.metadata_schema {
+        tlv.nested(TAG_METADATA_SCHEMA, |inner| encode_schema(inner, schema));
+    }
+    let bytes = tlv.into_bytes();
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+/// Restore the settings recorded in `payload` into `options`.
+pub(crate) fn apply(payload: &[u8], options: &mut RvfOptions) -> Result<(), RvfError> {
+    for record in TlvReader::new(payload) {
+        let record = record?;
+        match record.tag {
+            TAG_NORMALIZATION => options.normalization = normalize_from_code(record.as_u64()?)?,
+            TAG_AUTO_PROJECT => options.auto_project = Some(decode_projection(record.nested())?),
+            TAG_METADATA_SCHEMA => {
+                options.metadata_schema = Some(decode_schema(record.nested())?);
+            }
+            _ => {} // forward-compat: ignore unknown tags
+        }
+    }
+    Ok(())
+}
+
+fn encode_projection(tlv: &mut TlvWriter, projection: &Projection) {
+    match projection {
+        Projection::Truncate => {
+            tlv.u64(PROJECTION_KIND, 0);
+        }
+        Projection::Pad => {
+            tlv.u64(PROJECTION_KIND, 1);
+        }
+        Projection::Matrix { input_dim, weights } => {
+            let bytes: Vec<u8> = weights.iter().flat_map(|w| w.to_le_bytes()).collect();
+            tlv.u64(PROJECTION_KIND, 2)
+                .u64(PROJECTION_INPUT_DIM, *input_dim as u64)
+                .bytes(PROJECTION_WEIGHTS, &bytes);
+        }
+    }
+}
+
+fn decode_projection(tlv: TlvReader<'_>) -> Result<Projection, RvfError> {
+    let missing = |tag| RvfError::InvalidTlv {
+        tag,
+        reason: "missing projection field",
+    };
+    let kind = tlv
+        .find(PROJECTION_KIND)?
+        .ok_or_else(|| missing(PROJECTION_KIND))?
+        .as_u64()?;
+    match kind {
+        0 => Ok(Projection::Truncate),
+        1 => Ok(Projection::Pad),
+        2 => {
+            let input_dim = tlv
+                .find(PROJECTION_INPUT_DIM)?
+                .ok_or_else(|| missing(PROJECTION_INPUT_DIM))?
+                .as_u64()?;
+            let weights = tlv
+                .find(PROJECTION_WEIGHTS)?
+                .ok_or_else(|| missing(PROJECTION_WEIGHTS))?;
+            if weights.value.len() % 4 != 0 {
+                return Err(RvfError::InvalidTlv {
+                    tag: PROJECTION_WEIGHTS,
+                    reason: "weights must be whole f32 values",
+                });
+            }
+            Ok(Projection::Matrix {
+                input_dim: input_dim as usize,
+                weights: weights
+                    .value
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+            })
+        }
+        value => Err(RvfError::InvalidEnumValue {
+            type_name: "Projection",
+            value,
+        }),
+    }
+}
+
+fn encode_schema(tlv: &mut TlvWriter, schema: &MetadataSchema) {
+    let fields: Vec<u8> = schema
+        .fields()
+        .flat_map(|(id, kind)| {
+            let [lo, hi] = id.to_le_bytes();
+            [lo, hi, kind_code(kind)]
+        })
+        .collect();
+    tlv.u64(SCHEMA_STRICT, u64::from(schema.is_strict()))
+        .bytes(SCHEMA_FIELDS, &fields);
+}
+
+fn decode_schema(tlv: TlvReader<'_>) -> Result<MetadataSchema, RvfError> {
+    let strict = match tlv.find(SCHEMA_STRICT)? {
+        Some(record) => record.as_u64()? != 0,
+        None => false,
+    };
+    let mut schema = MetadataSchema::new().strict(strict);
+    if let Some(record) = tlv.find(SCHEMA_FIELDS)? {
+        if record.value.len() % 3 != 0 {
+            return Err(RvfError::InvalidTlv {
+                tag: SCHEMA_FIELDS,
+                reason: "fields must be 3-byte entries",
+            });
+        }
+        for entry in record.value.chunks_exact(3) {
+            let id = u16::from_le_bytes([entry[0], entry[1]]);
+            schema = schema.with_field(id, kind_from_code(entry[2])?);
+        }
+    }
+    Ok(schema)
+}
+
+fn kind_code(kind: MetadataKind) -> u8 {
+    match kind {
+        MetadataKind::U64 => 0,
+        MetadataKind::I64 => 1,
+        MetadataKind::F64 => 2,
+        MetadataKind::String => 3,
+        MetadataKind::Bytes => 4,
+    }
+}
+
+fn kind_from_code(code: u8) -> Result<MetadataKind, RvfError> {
+    match code {
+        0 => Ok(MetadataKind::U64),
+        1 => Ok(MetadataKind::I64),
+        2 => Ok(MetadataKind::F64),
+        3 => Ok(MetadataKind::String),
+        4 => Ok(MetadataKind::Bytes),
+        value => Err(RvfError::InvalidEnumValue {
+            type_name: "MetadataKind",
+            value: value as u64,
+        }),
+    }
+}
+
+fn normalize_code(policy: NormalizePolicy) -> u64 {
+    match policy {
+        NormalizePolicy::None => 0,
+        NormalizePolicy::L2OnIngest => 1,
+        NormalizePolicy::L2OnQuery => 2,
+        NormalizePolicy::L2Both => 3,
+    }
+}
+
+fn normalize_from_code(code: u64) -> Result<NormalizePolicy, RvfError> {
+    match code {
+        0 => Ok(NormalizePolicy::None),
+        1 => Ok(NormalizePolicy::L2OnIngest),
+        2 => Ok(NormalizePolicy::L2OnQuery),
+        3 => Ok(NormalizePolicy::L2Both),
+        value => Err(RvfError::InvalidEnumValue {
+            type_name: "NormalizePolicy",
+            value,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_not_recorded() {
+        assert_eq!(encode(&RvfOptions::default()), None);
+    }
+
+    #[test]
+    fn settings_round_trip_and_skip_unknown_tags() {
+        let options = RvfOptions {
+            normalization: NormalizePolicy::L2Both,
+            auto_project: Some(Projection::random(6, 4, 11)),
+            metadata_schema: Some(
+                MetadataSchema::new()
+                    .with_field(0, MetadataKind::String)
+                    .with_field(300, MetadataKind::F64)
+                    .strict(true),
+            ),
+            ..Default::default()
+        };
+        let mut payload = encode(&options).unwrap();
+        payload.extend_from_slice(TlvWriter::new().u64(0xFFFF, 7).as_bytes());
+
+        let mut restored = RvfOptions::default();
+        apply(&payload, &mut restored).unwrap();
+        assert_eq!(restored.normalization, NormalizePolicy::L2Both);
+        assert_eq!(restored.auto_project, options.auto_project);
+        assert_eq!(restored.metadata_schema, options.metadata_schema);
+
+        for projection in [Projection::Truncate, Projection::Pad] {
+            let options = RvfOptions {
+                auto_project: Some(projection.clone()),
+                ..Default::default()
+            };
+            let mut restored = RvfOptions::default();
+            apply(&encode(&options).unwrap(), &mut restored).unwrap();
+            assert_eq!(restored.auto_project, Some(projection));
+        }
+
+        let mut bad = TlvWriter::new();
+        bad.u64(TAG_NORMALIZATION, 9);
+        assert!(apply(bad.as_bytes(), &mut restored).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Point-in-time read views of a store.
+//!
+//! Writes are append-only and every mutation ends with a manifest, so the
+//! file prefix that ends at a manifest is a consistent image of the store.
+//! A [`Checkpoint`] names such a manifest by offset and epoch; a
+//! [`Snapshot`] is a read-only store loaded from that prefix. Later
+//! appends by the writer are invisible to it.
+//!
+//! Compaction rewrites the file, so checkpoints taken before a compaction
+//! no longer resolve afterwards. Snapshots already open are unaffected:
+//! they hold their vectors in memory.
+
+use rvf_types::{ErrorCode, RvfError};
+
+use crate::options::{QueryOptions, SearchResult};
+use crate::store::RvfStore;
+
+/// Size of a serialized [`Checkpoint`].
+pub const CHECKPOINT_SIZE: usize = 16;
+
+/// Marker for a manifest a store can later be reopened at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// File offset of the manifest segment header.
+    pub manifest_offset: u64,
+    /// Store epoch recorded in that manifest.
+    pub epoch: u32,
+}
+
+impl Checkpoint {
+    /// Serialize as `manifest_offset(u64) | epoch(u32) | reserved(u32)`,
+    /// little-endian.
+    pub fn to_bytes(&self) -> [u8; CHECKPOINT_SIZE] {
+        let mut buf = [0u8; CHECKPOINT_SIZE];
+        buf[0..8].copy_from_slice(&self.manifest_offset.to_le_bytes());
+        buf[8..12].copy_from_slice(&self.epoch.to_le_bytes());
+        buf
+    }
+
+    /// Deserialize a checkpoint written by [`Checkpoint::to_bytes`].
+    pub fn from_bytes(data: &[u8]) -> Result<Self, RvfError> {
+        if data.len() < CHECKPOINT_SIZE {
+            return Err(RvfError::SizeMismatch {
+                expected: CHECKPOINT_SIZE,
+                got: data.len(),
+            });
+        }
+        if data[12..16] != [0; 4] {
+            return Err(RvfError::Code(ErrorCode::InvalidManifest));
+        }
+        Ok(Self {
+            manifest_offset: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            epoch: u32::from_le_bytes(data[8..12].try_into().unwrap()),
+        })
+    }
+}
+
+/// A read-only view of a store as of one manifest.
+pub struct Snapshot {
+    pub(crate) checkpoint: Checkpoint,
+    pub(crate) view: RvfStore,
+}
+
+impl Snapshot {
+    /// The manifest this snapshot was loaded from.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.checkpoint
+    }
+
+    /// Store epoch at the snapshot.
+    pub fn epoch(&self) -> u32 {
+        self.checkpoint.epoch
+    }
+
+    /// Query the snapshot for the k nearest neighbors of `vector`.
+    pub fn query(
+        &self,
+        vector: &[f32],
+        k: usize,
+        options: &QueryOptions,
+    ) -> Result<Vec<SearchResult>, RvfError> {
+        self.view.query(vector, k, options)
+    }
+
+    /// The underlying read-only store, for APIs beyond `query`.
+    pub fn store(&self) -> &RvfStore {
+        &self.view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_bytes_round_trip() {
+        let cp = Checkpoint {
+            manifest_offset: 0x1234_5678_9ABC,
+            epoch: 42,
+        };
+        let bytes = cp.to_bytes();
+        assert_eq!(Checkpoint::from_bytes(&bytes), Ok(cp));
+        assert!(Checkpoint::from_bytes(&bytes[..15]).is_err());
+
+        let mut reserved = bytes;
+        reserved[15] = 1;
+        assert!(Checkpoint::from_bytes(&reserved).is_err());
+    }
+}
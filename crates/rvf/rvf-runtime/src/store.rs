@@ -3,10 +3,12 @@
 //! Ties together the write path, read path, indexing, deletion, and
 //! compaction into a single cohesive store.
 
-use std::collections::BinaryHeap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use rvf_types::dashboard::{DashboardHeader, DASHBOARD_MAGIC, DASHBOARD_MAX_SIZE};
 use rvf_types::ebpf::{EbpfHeader, EBPF_MAGIC};
@@ -14,19 +16,54 @@ use rvf_types::kernel::{KernelHeader, KERNEL_MAGIC};
 use rvf_types::kernel_binding::KernelBinding;
 use rvf_types::wasm_bootstrap::{WasmHeader, WasmRole, WASM_MAGIC};
 use rvf_types::{
-    DomainProfile, ErrorCode, FileIdentity, RvfError, SegmentType, SEGMENT_HEADER_SIZE,
-    SEGMENT_MAGIC,
+    DomainProfile, ErrorCode, FileIdentity, RefcountHeader, RvfError, SegmentType, REFCOUNT_MAGIC,
+    SEGMENT_HEADER_SIZE, SEGMENT_MAGIC,
 };
 
+#[cfg(feature = "tokio")]
+use crate::async_open::AsyncBoot;
 use crate::cow::{CowEngine, CowStats};
-use crate::deletion::DeletionBitmap;
-use crate::filter::{self, metadata_value_to_filter, FilterExpr, FilterValue, MetadataStore};
-use crate::locking::WriterLock;
+use crate::deletion::{ClusterRefcounts, DeletionBitmap};
+use crate::encryption::{self, EncryptionConfig};
+use crate::filter::{
+    self, filter_value_to_metadata, metadata_value_to_filter, FilterExpr, FilterValue,
+    MetadataStore,
+};
+use crate::intent_log::IntentLog;
+#[cfg(unix)]
+use crate::locking::FileLock;
+use crate::locking::{LockOptions, WriterLock};
 use crate::membership::MembershipFilter;
+use crate::metrics::{QueryStats, StoreMetrics};
+use crate::multi_vector::{
+    split_sub_vector_id, sub_vector_id, MultiVectorRecord, MAX_MULTI_VECTOR_ENTRY_ID,
+    MULTI_VECTOR_ID_FLAG,
+};
 use crate::options::*;
-use crate::read_path::{self, VectorData};
+use crate::read_path::{self, TopK, VectorData};
+use crate::replication::{self, Lsn, ReplicationOp, ReplicationRecord};
+use crate::settings;
+use crate::snapshot::{Checkpoint, Snapshot};
 use crate::status::{CompactionState, StoreStatus};
-use crate::write_path::SegmentWriter;
+use crate::write_path::{self, SegmentWriter};
+
+/// Maximum number of live vectors inspected by `verify_normalization`.
+const NORMALIZATION_SAMPLE: usize = 1024;
+
+/// Allowed `|norm - 1|` before a stored vector counts as unnormalized.
+const NORMALIZATION_TOLERANCE: f32 = 1e-3;
+
+/// Vectors scanned between query deadline checks, so the clock is read
+/// once per block rather than once per distance computation.
+const DEADLINE_CHECK_INTERVAL: usize = 256;
+
+/// Outcome of an exact scan, including how far it got before a deadline.
+struct ScanOutcome {
+    results: Vec<SearchResult>,
+    scanned: u64,
+    total: u64,
+    truncated: bool,
+}
 
 /// Helper to convert any error into an RvfError with the given code.
 fn err(code: ErrorCode) -> RvfError {
@@ -51,6 +88,8 @@ pub struct RvfStore {
     file: File,
     seg_writer: Option<SegmentWriter>,
     writer_lock: Option<WriterLock>,
+    /// Write-ahead intent log (None for read-only stores).
+    intent_log: Option<IntentLog>,
     vectors: VectorData,
     deletion_bitmap: DeletionBitmap,
     metadata: MetadataStore,
@@ -63,11 +102,20 @@ pub struct RvfStore {
     cow_engine: Option<CowEngine>,
     /// Membership filter for branch-level vector visibility (None if unused).
     membership_filter: Option<MembershipFilter>,
+    /// Whether the membership filter was handed out mutably since it was
+    /// last persisted.
+    membership_dirty: bool,
     /// Path to the parent file (for COW reads that need parent data).
     parent_path: Option<PathBuf>,
     /// Hash of the last witness entry, used to chain-link successive witnesses.
     /// All zeros when no witness has been written yet (genesis).
     last_witness_hash: [u8; 32],
+    /// Cumulative query count and latency since the store was opened.
+    query_stats: QueryStats,
+    /// LSN of the last replication record applied, persisted in the manifest.
+    replicated_lsn: Lsn,
+    /// File offset of the latest manifest segment.
+    manifest_offset: u64,
 }
 
 impl RvfStore {
@@ -76,6 +124,9 @@ impl RvfStore {
         if options.dimension == 0 {
             return Err(err(ErrorCode::InvalidManifest));
         }
+        if options.encryption.is_some() && !encryption::is_supported() {
+            return Err(err(ErrorCode::AlgoUnsupported));
+        }
 
         let file = OpenOptions::new()
             .read(true)
@@ -84,7 +135,9 @@ impl RvfStore {
             .open(path)
             .map_err(|_| err(ErrorCode::FsyncFailed))?;
 
-        let writer_lock = WriterLock::acquire(path).map_err(|_| err(ErrorCode::LockHeld))?;
+        let writer_lock =
+            WriterLock::acquire_with(path, &options.lock).map_err(|_| err(ErrorCode::LockHeld))?;
+        let intent_log = IntentLog::create(path).map_err(|_| err(ErrorCode::FsyncFailed))?;
 
         // Generate a random file_id from path hash + timestamp
         let file_id = generate_file_id(path);
@@ -98,13 +151,15 @@ impl RvfStore {
 
         let mut opts = options.clone();
         opts.domain_profile = domain_profile;
+        let seg_writer = SegmentWriter::new(1).with_encryption(opts.encryption.clone());
 
         let mut store = Self {
             path: path.to_path_buf(),
             options: opts,
             file,
-            seg_writer: Some(SegmentWriter::new(1)),
+            seg_writer: Some(seg_writer),
             writer_lock: Some(writer_lock),
+            intent_log: Some(intent_log),
             vectors: VectorData::new(options.dimension),
             deletion_bitmap: DeletionBitmap::new(),
             metadata: MetadataStore::new(),
@@ -115,75 +170,168 @@ impl RvfStore {
             file_identity: FileIdentity::new_root(file_id),
             cow_engine: None,
             membership_filter: None,
+            membership_dirty: false,
             parent_path: None,
             last_witness_hash: [0u8; 32],
+            query_stats: QueryStats::default(),
+            replicated_lsn: 0,
+            manifest_offset: 0,
         };
 
+        store.write_settings()?;
         store.write_manifest()?;
         Ok(store)
     }
 
     /// Open an existing RVF store for read-write access.
     pub fn open(path: &Path) -> Result<Self, RvfError> {
-        if !path.exists() {
-            return Err(err(ErrorCode::ManifestNotFound));
+        Self::open_with(path, None, LockOptions::default())
+    }
+
+    /// Open an existing RVF store for read-write access, waiting up to
+    /// `lock.acquire_timeout` for another writer to release the lock.
+    ///
+    /// A lock whose holder process is dead and whose heartbeat is older
+    /// than `lock.stale_after` is reclaimed.
+    pub fn open_with_lock_options(path: &Path, lock: LockOptions) -> Result<Self, RvfError> {
+        Self::open_with(path, None, lock)
+    }
+
+    /// Open an existing encrypted RVF store for read-write access.
+    ///
+    /// Fails with `DecryptFailed` if `encryption` does not hold the key the
+    /// store was written with.
+    pub fn open_with_encryption(
+        path: &Path,
+        encryption: EncryptionConfig,
+    ) -> Result<Self, RvfError> {
+        if !encryption::is_supported() {
+            return Err(err(ErrorCode::AlgoUnsupported));
         }
+        Self::open_with(path, Some(encryption), LockOptions::default())
+    }
 
-        let writer_lock = WriterLock::acquire(path).map_err(|_| err(ErrorCode::LockHeld))?;
+    fn open_with(
+        path: &Path,
+        encryption: Option<EncryptionConfig>,
+        lock: LockOptions,
+    ) -> Result<Self, RvfError> {
+        let mut store = Self::open_unbooted(path, false, encryption, lock)?;
+        store.boot()?;
+        Ok(store)
+    }
 
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(path)
-            .map_err(|_| err(ErrorCode::InvalidManifest))?;
+    /// Open an existing RVF store for read-only access (no lock required).
+    pub fn open_readonly(path: &Path) -> Result<Self, RvfError> {
+        let mut store = Self::open_unbooted(path, true, None, LockOptions::default())?;
+        store.boot()?;
+        Ok(store)
+    }
 
-        // Detect domain profile from extension
-        let domain_profile = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .and_then(DomainProfile::from_extension)
-            .unwrap_or(DomainProfile::Generic);
+    /// Open an existing RVF store, reading it through a memory map.
+    ///
+    /// Every segment the manifest points at is checked against the mapped
+    /// length before it is touched, so a truncated file fails with
+    /// `TruncatedSegment` at open instead of faulting on access. The map
+    /// only lives for the boot: it is given `WILLNEED` hints for the
+    /// manifest tail and the vector segments boot copies out, and is
+    /// released once the store is loaded. While it is live a shared
+    /// `flock` keeps a recovering writer from truncating the file under
+    /// it. Falls back to a buffered open where mmap is not available.
+    pub fn open_mmap(path: &Path, options: MmapOptions) -> Result<Self, RvfError> {
+        if options.encryption.is_some() && !encryption::is_supported() {
+            return Err(err(ErrorCode::AlgoUnsupported));
+        }
+        let mut store =
+            Self::open_unbooted(path, options.read_only, options.encryption, options.lock)?;
+        #[cfg(unix)]
+        store.boot_mmap(options.advise)?;
+        #[cfg(not(unix))]
+        store.boot()?;
+        Ok(store)
+    }
 
-        let opts = RvfOptions {
-            domain_profile,
+    /// Open an existing RVF store for read-write access from async code.
+    ///
+    /// Runs an [`AsyncBoot`] to completion: lock acquisition and
+    /// intent-log recovery run on tokio's blocking pool, and the manifest
+    /// and each segment are read with `tokio::fs`, so the executor thread
+    /// is never parked on disk I/O. Use [`AsyncBoot`] directly to query
+    /// the store while its segments are still loading.
+    ///
+    /// [`AsyncBoot`]: crate::async_open::AsyncBoot
+    #[cfg(feature = "tokio")]
+    pub async fn open_async(path: &Path) -> Result<Self, RvfError> {
+        let options = AsyncOpenOptions {
+            read_only: false,
             ..Default::default()
         };
+        AsyncBoot::open(path, options).await?.finish().await
+    }
 
-        let mut store = Self {
-            path: path.to_path_buf(),
-            options: opts,
-            file,
-            seg_writer: None,
-            writer_lock: Some(writer_lock),
-            vectors: VectorData::new(0),
-            deletion_bitmap: DeletionBitmap::new(),
-            metadata: MetadataStore::new(),
-            epoch: 0,
-            segment_dir: Vec::new(),
+    /// Open an existing RVF store for read-only access from async code.
+    #[cfg(feature = "tokio")]
+    pub async fn open_readonly_async(path: &Path) -> Result<Self, RvfError> {
+        AsyncBoot::open(path, AsyncOpenOptions::default())
+            .await?
+            .finish()
+            .await
+    }
+
+    /// Open an existing encrypted RVF store for read-write access from
+    /// async code.
+    ///
+    /// Fails with `DecryptFailed` if `encryption` does not hold the key the
+    /// store was written with.
+    #[cfg(feature = "tokio")]
+    pub async fn open_async_with_encryption(
+        path: &Path,
+        encryption: EncryptionConfig,
+    ) -> Result<Self, RvfError> {
+        let options = AsyncOpenOptions {
             read_only: false,
-            last_compaction_time: 0,
-            file_identity: FileIdentity::zeroed(),
-            cow_engine: None,
-            membership_filter: None,
-            parent_path: None,
-            last_witness_hash: [0u8; 32],
+            encryption: Some(encryption),
+            ..Default::default()
         };
-
-        store.boot()?;
-        Ok(store)
+        AsyncBoot::open(path, options).await?.finish().await
     }
 
-    /// Open an existing RVF store for read-only access (no lock required).
-    pub fn open_readonly(path: &Path) -> Result<Self, RvfError> {
+    /// Open the file and build an empty store handle; `boot` loads it.
+    ///
+    /// Read-write handles take the writer lock and roll back any torn
+    /// append first.
+    pub(crate) fn open_unbooted(
+        path: &Path,
+        read_only: bool,
+        encryption: Option<EncryptionConfig>,
+        lock: LockOptions,
+    ) -> Result<Self, RvfError> {
         if !path.exists() {
             return Err(err(ErrorCode::ManifestNotFound));
         }
 
+        let writer_lock = if read_only {
+            None
+        } else {
+            Some(WriterLock::acquire_with(path, &lock).map_err(|_| err(ErrorCode::LockHeld))?)
+        };
+
         let file = OpenOptions::new()
             .read(true)
+            .write(!read_only)
             .open(path)
             .map_err(|_| err(ErrorCode::InvalidManifest))?;
 
+        // Roll back a partial append left by a writer that crashed.
+        let intent_log = if read_only {
+            None
+        } else {
+            let (log, _) =
+                IntentLog::recover(path, &file).map_err(|_| err(ErrorCode::FsyncFailed))?;
+            Some(log)
+        };
+
+        // Detect domain profile from extension
         let domain_profile = path
             .extension()
             .and_then(|ext| ext.to_str())
@@ -192,39 +340,75 @@ impl RvfStore {
 
         let opts = RvfOptions {
             domain_profile,
+            encryption,
+            lock,
             ..Default::default()
         };
 
-        let mut store = Self {
+        Ok(Self {
             path: path.to_path_buf(),
             options: opts,
             file,
             seg_writer: None,
-            writer_lock: None,
+            writer_lock,
+            intent_log,
             vectors: VectorData::new(0),
             deletion_bitmap: DeletionBitmap::new(),
             metadata: MetadataStore::new(),
             epoch: 0,
             segment_dir: Vec::new(),
-            read_only: true,
+            read_only,
             last_compaction_time: 0,
             file_identity: FileIdentity::zeroed(),
             cow_engine: None,
             membership_filter: None,
+            membership_dirty: false,
             parent_path: None,
             last_witness_hash: [0u8; 32],
-        };
-
-        store.boot()?;
-        Ok(store)
+            query_stats: QueryStats::default(),
+            replicated_lsn: 0,
+            manifest_offset: 0,
+        })
     }
 
     /// Ingest a batch of vectors into the store.
+    ///
+    /// Every vector must match the store dimension unless
+    /// `RvfOptions::auto_project` is set; otherwise the batch fails with
+    /// `RvfError::DimensionMismatch` and nothing is written.
+    ///
+    /// Ids with `MULTI_VECTOR_ID_FLAG` set, or that already name a
+    /// multi-vector entry, are rejected with `RvfError::IdConflict`: they
+    /// would share deletion and metadata state with that entry.
     pub fn ingest_batch(
         &mut self,
         vectors: &[&[f32]],
         ids: &[u64],
         metadata: Option<&[MetadataEntry]>,
+    ) -> Result<IngestResult, RvfError> {
+        for &id in ids {
+            if id & MULTI_VECTOR_ID_FLAG != 0 {
+                return Err(RvfError::IdConflict {
+                    id,
+                    reason: "reserved for multi-vector sub-vectors",
+                });
+            }
+            if self.vectors.is_multi_vector_entry(id) {
+                return Err(RvfError::IdConflict {
+                    id,
+                    reason: "names a multi-vector entry",
+                });
+            }
+        }
+        self.ingest_vectors(vectors, ids, metadata)
+    }
+
+    /// Ingest under storage ids as given, sub-vector ids included.
+    fn ingest_vectors(
+        &mut self,
+        vectors: &[&[f32]],
+        ids: &[u64],
+        metadata: Option<&[MetadataEntry]>,
     ) -> Result<IngestResult, RvfError> {
         if self.read_only {
             return Err(err(ErrorCode::ReadOnly));
@@ -233,32 +417,58 @@ impl RvfStore {
             return Err(err(ErrorCode::DimensionMismatch));
         }
 
-        let dim = self.options.dimension as usize;
-        let mut accepted = 0u64;
-        let mut rejected = 0u64;
-
-        let mut valid_vectors: Vec<&[f32]> = Vec::with_capacity(vectors.len());
-        let mut valid_ids: Vec<u64> = Vec::with_capacity(ids.len());
+        if let (Some(schema), Some(entries)) = (&self.options.metadata_schema, metadata) {
+            schema.validate(entries)?;
+        }
 
-        for (i, &vec_data) in vectors.iter().enumerate() {
-            if vec_data.len() != dim {
-                rejected += 1;
+        let dim = self.options.dimension as usize;
+        let mut warnings = Vec::new();
+
+        // Validate (or reshape) every vector before anything is written, so a
+        // bad batch leaves the store untouched.
+        let mut shaped: Vec<Cow<'_, [f32]>> = Vec::with_capacity(vectors.len());
+        for (&vec_data, &id) in vectors.iter().zip(ids) {
+            if vec_data.len() == dim {
+                shaped.push(Cow::Borrowed(vec_data));
                 continue;
             }
-            valid_vectors.push(vec_data);
-            valid_ids.push(ids[i]);
-            accepted += 1;
+            let projection =
+                self.options
+                    .auto_project
+                    .as_ref()
+                    .ok_or(RvfError::DimensionMismatch {
+                        expected: dim,
+                        got: vec_data.len(),
+                    })?;
+            shaped.push(Cow::Owned(projection.apply(vec_data, dim)?));
+            warnings.push(IngestWarning::Projected {
+                id,
+                from: vec_data.len(),
+                to: dim,
+            });
+        }
+
+        if self.options.normalization.on_ingest() {
+            for v in shaped.iter_mut() {
+                *v = Cow::Owned(l2_normalized(v));
+            }
         }
 
+        let valid_vectors: Vec<&[f32]> = shaped.iter().map(|v| v.as_ref()).collect();
+        let valid_ids: Vec<u64> = ids.to_vec();
+        let accepted = valid_vectors.len() as u64;
+
         if valid_vectors.is_empty() {
             self.epoch += 1;
             return Ok(IngestResult {
                 accepted: 0,
-                rejected,
+                rejected: 0,
                 epoch: self.epoch,
+                warnings,
             });
         }
 
+        self.begin_append()?;
         let writer = self
             .seg_writer
             .as_mut()
@@ -280,7 +490,8 @@ impl RvfStore {
         };
 
         let bytes_per_vec = (self.options.dimension as usize) * 4;
-        let vec_payload_len = (2 + 4 + valid_vectors.len() * (8 + bytes_per_vec)) as u64;
+        let vec_payload_len = (2 + 4 + valid_vectors.len() * (8 + bytes_per_vec)) as u64
+            + writer.payload_overhead(SegmentType::Vec as u8);
 
         self.segment_dir.push((
             vec_seg_id,
@@ -324,68 +535,409 @@ impl RvfStore {
 
         Ok(IngestResult {
             accepted,
-            rejected,
+            rejected: 0,
             epoch: self.epoch,
+            warnings,
         })
     }
 
     /// Query the store for the k nearest neighbors of the given vector.
+    ///
+    /// With `options.rerank` set, `options.rerank_pool_size` candidates
+    /// (at least `k`) are fetched and rescored, and the top `k` are
+    /// returned with the reranker's score as their distance.
     pub fn query(
         &self,
         vector: &[f32],
         k: usize,
         options: &QueryOptions,
     ) -> Result<Vec<SearchResult>, RvfError> {
+        let start = Instant::now();
+        let results = match &options.rerank {
+            Some(reranker) => self
+                .rerank_pool(vector, k, options, start, |result| {
+                    // Sub-field results carry entry ids; the vector is
+                    // stored under the sub-vector id.
+                    let vec_id = match options.sub_field {
+                        Some(tag) => sub_vector_id(result.id, tag),
+                        None => result.id,
+                    };
+                    self.vectors
+                        .get(vec_id)
+                        .map(|stored| reranker.score(result, stored))
+                })?
+                .into_iter()
+                .map(|r| SearchResult {
+                    distance: r.score,
+                    ..r.result
+                })
+                .collect(),
+            None => self.scan_nearest(vector, k, options, start)?.results,
+        };
+        self.query_stats.record(start.elapsed());
+        Ok(results)
+    }
+
+    /// Fetch `options.rerank_pool_size` candidates (at least `k`), score
+    /// each with `score` and keep the `k` lowest scores. Ties keep the ANN
+    /// order; candidates scored `None` are dropped.
+    fn rerank_pool(
+        &self,
+        vector: &[f32],
+        k: usize,
+        options: &QueryOptions,
+        start: Instant,
+        score: impl Fn(&SearchResult) -> Option<f32>,
+    ) -> Result<Vec<RerankedResult>, RvfError> {
+        let pool_size = options.rerank_pool_size.max(k);
+        let candidates = self.scan_nearest(vector, pool_size, options, start)?;
+        let mut reranked: Vec<RerankedResult> = candidates
+            .results
+            .into_iter()
+            .filter_map(|result| {
+                let score = score(&result)?;
+                Some(RerankedResult { result, score })
+            })
+            .collect();
+        reranked.sort_by(|a, b| a.score.total_cmp(&b.score));
+        reranked.truncate(k);
+        Ok(reranked)
+    }
+
+    /// Ingest multi-vector records, one VEC_SEG for the whole batch.
+    ///
+    /// Every sub-vector goes through the same validation as
+    /// [`RvfStore::ingest_batch`]; `IngestResult::accepted` counts
+    /// sub-vectors and warnings carry their storage ids. `metadata` is
+    /// attached to entry ids, split evenly across `records` as in
+    /// `ingest_batch`, so filters apply to whole entries.
+    pub fn ingest_multi_vector(
+        &mut self,
+        records: &[MultiVectorRecord],
+        metadata: Option<&[MetadataEntry]>,
+    ) -> Result<IngestResult, RvfError> {
+        let mut ids = Vec::new();
+        let mut vectors: Vec<&[f32]> = Vec::new();
+        for record in records {
+            // An entry may not reuse the id of a plain vector.
+            if record.id > MAX_MULTI_VECTOR_ENTRY_ID {
+                return Err(RvfError::InvalidEnumValue {
+                    type_name: "MultiVectorRecord",
+                    value: record.id,
+                });
+            }
+            if self.vectors.get(record.id).is_some() {
+                return Err(RvfError::IdConflict {
+                    id: record.id,
+                    reason: "names a plain vector",
+                });
+            }
+            for (i, (tag, vector)) in record.fields.iter().enumerate() {
+                if record.fields[..i].iter().any(|(t, _)| t == tag) {
+                    return Err(RvfError::IdConflict {
+                        id: sub_vector_id(record.id, *tag),
+                        reason: "duplicate sub-field tag",
+                    });
+                }
+                ids.push(sub_vector_id(record.id, *tag));
+                vectors.push(vector);
+            }
+        }
+
+        if let (Some(schema), Some(entries)) = (&self.options.metadata_schema, metadata) {
+            schema.validate(entries)?;
+        }
+        let result = self.ingest_vectors(&vectors, &ids, None)?;
+
+        if let Some(meta_entries) = metadata {
+            let entries_per_record = meta_entries.len() / records.len().max(1);
+            if entries_per_record > 0 {
+                for (record, chunk) in records.iter().zip(meta_entries.chunks(entries_per_record)) {
+                    let fields: Vec<(u16, FilterValue)> = chunk
+                        .iter()
+                        .map(|e| (e.field_id, metadata_value_to_filter(&e.value)))
+                        .collect();
+                    self.metadata.insert(record.id, fields);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Fetch every live sub-vector of multi-vector entry `id`, in tag order.
+    pub fn get_multi_vector(&self, id: u64) -> Option<MultiVectorRecord> {
+        if id > MAX_MULTI_VECTOR_ENTRY_ID || self.deletion_bitmap.is_deleted(id) {
+            return None;
+        }
+        let fields: Vec<(u8, Vec<f32>)> = self
+            .vectors
+            .sub_field_tags(id)
+            .filter_map(|tag| {
+                let sub_id = sub_vector_id(id, tag);
+                if self.deletion_bitmap.is_deleted(sub_id) {
+                    return None;
+                }
+                self.vectors.get(sub_id).map(|v| (tag, v.to_vec()))
+            })
+            .collect();
+        if fields.is_empty() {
+            None
+        } else {
+            Some(MultiVectorRecord { id, fields })
+        }
+    }
+
+    /// Storage ids of the sub-vectors of entry `id`.
+    fn sub_vector_ids(&self, id: u64) -> Vec<u64> {
+        self.vectors
+            .sub_field_tags(id)
+            .map(|tag| sub_vector_id(id, tag))
+            .collect()
+    }
+
+    /// Query the store from async code.
+    ///
+    /// The scan is CPU-bound. On a multi-threaded runtime it runs under
+    /// `block_in_place`, so the worker's other tasks move elsewhere while
+    /// it runs; on a current-thread runtime it runs inline.
+    #[cfg(feature = "tokio")]
+    pub async fn query_async(
+        &self,
+        vector: &[f32],
+        k: usize,
+        options: &QueryOptions,
+    ) -> Result<Vec<SearchResult>, RvfError> {
+        use tokio::runtime::{Handle, RuntimeFlavor};
+
+        let multi_thread = Handle::try_current()
+            .is_ok_and(|handle| handle.runtime_flavor() == RuntimeFlavor::MultiThread);
+        if multi_thread {
+            tokio::task::block_in_place(|| self.query(vector, k, options))
+        } else {
+            self.query(vector, k, options)
+        }
+    }
+
+    /// The result ID a scan reports for stored vector `vec_id`, or None
+    /// when the vector is deleted, filtered out, or not in the queried
+    /// sub-field. Sub-vectors are reported under their entry id, and only
+    /// when the query targets their sub-field.
+    fn scan_candidate(&self, vec_id: u64, options: &QueryOptions) -> Option<u64> {
+        let result_id = match (split_sub_vector_id(vec_id), options.sub_field) {
+            (None, None) => vec_id,
+            (Some((entry, tag)), Some(wanted)) if tag == wanted => entry,
+            _ => return None,
+        };
+        if self.deletion_bitmap.is_deleted(vec_id) || self.deletion_bitmap.is_deleted(result_id) {
+            return None;
+        }
+        if let Some(ref filter_expr) = options.filter {
+            if !filter::evaluate(filter_expr, result_id, &self.metadata) {
+                return None;
+            }
+        }
+        Some(result_id)
+    }
+
+    /// Exact k-NN scan over live vectors that pass the filter.
+    ///
+    /// If `options.deadline` is set, the clock is checked every
+    /// `DEADLINE_CHECK_INTERVAL` vectors and the scan stops early once the
+    /// deadline measured from `start` has passed.
+    fn scan_nearest(
+        &self,
+        vector: &[f32],
+        k: usize,
+        options: &QueryOptions,
+        start: Instant,
+    ) -> Result<ScanOutcome, RvfError> {
         let dim = self.options.dimension as usize;
         if vector.len() != dim {
             return Err(err(ErrorCode::DimensionMismatch));
         }
-
-        if self.vectors.len() == 0 {
-            return Ok(Vec::new());
+        if let Some(filter_expr) = &options.filter {
+            filter_expr.validate()?;
         }
 
-        // Max-heap: peek() returns the largest (farthest) distance in our k set.
-        // When a closer vector is found, evict the farthest.
-        let mut heap: BinaryHeap<(OrderedFloat, u64)> = BinaryHeap::new();
+        let total = self.vectors.len() as u64;
+        if total == 0 {
+            return Ok(ScanOutcome {
+                results: Vec::new(),
+                scanned: 0,
+                total,
+                truncated: false,
+            });
+        }
+        let query = self.prepare_query(vector);
+        let vector = query.as_ref();
+
+        // Maps a stored vector to its (distance, result id), or None when
+        // it is not a candidate for this query.
+        let score = |vec_id: u64| -> Option<(f32, u64)> {
+            let result_id = self.scan_candidate(vec_id, options)?;
+            let stored_vec = self.vectors.get(vec_id)?;
+            Some((
+                compute_distance(vector, stored_vec, &self.options.metric),
+                result_id,
+            ))
+        };
 
-        for &vec_id in self.vectors.ids() {
-            if self.deletion_bitmap.is_deleted(vec_id) {
-                continue;
+        let mut scanned = 0u64;
+        let mut truncated = false;
+        let top = match self.parallel_scan_partitions(options) {
+            #[cfg(feature = "parallel")]
+            Some(partitions) => {
+                let ids: Vec<u64> = self.vectors.ids().copied().collect();
+                scanned = total;
+                read_path::par_scan_top_k(&ids, partitions, k, score)
             }
-            if let Some(ref filter_expr) = options.filter {
-                if !filter::evaluate(filter_expr, vec_id, &self.metadata) {
-                    continue;
-                }
-            }
-            if let Some(stored_vec) = self.vectors.get(vec_id) {
-                let dist = compute_distance(vector, stored_vec, &self.options.metric);
-                if heap.len() < k {
-                    heap.push((OrderedFloat(dist), vec_id));
-                } else if let Some(&(OrderedFloat(worst), _)) = heap.peek() {
-                    if dist < worst {
-                        heap.pop();
-                        heap.push((OrderedFloat(dist), vec_id));
+            _ => {
+                let mut top = TopK::new(k);
+                for &vec_id in self.vectors.ids() {
+                    if let Some(deadline) = options.deadline {
+                        if scanned > 0
+                            && scanned.is_multiple_of(DEADLINE_CHECK_INTERVAL as u64)
+                            && start.elapsed() >= deadline
+                        {
+                            truncated = true;
+                            break;
+                        }
+                    }
+                    scanned += 1;
+                    if let Some((dist, result_id)) = score(vec_id) {
+                        top.push(dist, result_id);
                     }
                 }
+                top
             }
-        }
+        };
 
-        // Drain the max-heap into sorted results (closest first).
-        let mut results: Vec<SearchResult> = heap
+        // Sorted closest first, ties by id. A truncated scan is exact only
+        // over the vectors it reached.
+        let retrieval_quality = if truncated {
+            rvf_types::quality::RetrievalQuality::BruteForceBudgeted
+        } else {
+            rvf_types::quality::RetrievalQuality::Full
+        };
+        let results: Vec<SearchResult> = top
+            .into_sorted_vec()
             .into_iter()
-            .map(|(OrderedFloat(dist), id)| SearchResult {
+            .map(|(distance, id)| SearchResult {
                 id,
-                distance: dist,
-                retrieval_quality: rvf_types::quality::RetrievalQuality::Full,
+                distance,
+                retrieval_quality,
             })
             .collect();
-        results.sort_by(|a, b| {
-            a.distance
-                .partial_cmp(&b.distance)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        Ok(results)
+        Ok(ScanOutcome {
+            results,
+            scanned,
+            total,
+            truncated,
+        })
+    }
+
+    /// Number of partitions for a parallel scan, or `None` to scan
+    /// sequentially.
+    ///
+    /// Parallel scans fan out one partition per VEC segment, so they only
+    /// pay off (and only run) with the `parallel` feature, more than one
+    /// VEC segment, and no deadline, which needs the sequential scan order.
+    fn parallel_scan_partitions(&self, options: &QueryOptions) -> Option<usize> {
+        if !cfg!(feature = "parallel") || options.deadline.is_some() {
+            return None;
+        }
+        let vec_segments = self
+            .segment_dir
+            .iter()
+            .filter(|&&(_, _, _, seg_type)| seg_type == SegmentType::Vec as u8)
+            .count();
+        (vec_segments > 1).then_some(vec_segments)
+    }
+
+    /// Query the store and rerank the candidates with a user-supplied scorer.
+    ///
+    /// Same pipeline as [`QueryOptions::rerank`], for scorers that need the
+    /// candidate's metadata rather than its vector: fetches
+    /// `options.rerank_pool_size` nearest neighbors (at least `k`), calls
+    /// `rerank` once per candidate, and returns the top `k` by ascending
+    /// score (lower = better, like a distance). Ties keep the original ANN
+    /// order, and each result retains its ANN distance. `rerank` takes the
+    /// place of `options.rerank`, so candidates are rescored only once.
+    pub fn search_with_reranker<F>(
+        &self,
+        vector: &[f32],
+        k: usize,
+        options: &QueryOptions,
+        rerank: F,
+    ) -> Result<Vec<RerankedResult>, RvfError>
+    where
+        F: Fn(&SearchResult, &[MetadataEntry]) -> f32,
+    {
+        let start = Instant::now();
+        let reranked = self.rerank_pool(vector, k, options, start, |result| {
+            Some(rerank(result, &self.metadata_entries(result.id)))
+        })?;
+        self.query_stats.record(start.elapsed());
+        Ok(reranked)
+    }
+
+    /// Describe how a query would execute without running it.
+    ///
+    /// Queries are exact scans: the plan lists the VEC segments scanned and
+    /// counts the candidates, the live vectors that pass the filter and
+    /// sub-field selection, exactly as the scan selects them. Metadata is
+    /// evaluated but no distance is computed.
+    pub fn explain_search(
+        &self,
+        vector: &[f32],
+        k: usize,
+        options: &QueryOptions,
+    ) -> Result<SearchPlan, RvfError> {
+        use rvf_types::quality::{QualityPreference, SafetyNetBudget};
+
+        if vector.len() != self.options.dimension as usize {
+            return Err(err(ErrorCode::DimensionMismatch));
+        }
+        if let Some(filter_expr) = &options.filter {
+            filter_expr.validate()?;
+        }
+
+        let segments: Vec<u64> = self
+            .segment_dir
+            .iter()
+            .filter(|&&(_, _, _, seg_type)| seg_type == SegmentType::Vec as u8)
+            .map(|&(seg_id, _, _, _)| seg_id)
+            .collect();
+
+        let live_vectors = self
+            .vectors
+            .ids()
+            .filter(|&&id| !self.deletion_bitmap.is_deleted(id))
+            .count() as u64;
+        let candidates = self
+            .vectors
+            .ids()
+            .filter(|&&id| self.scan_candidate(id, options).is_some())
+            .count() as u64;
+
+        // Same decision as `query_with_envelope`, made on the number of
+        // results the scan would return.
+        let budget = match options.quality_preference {
+            QualityPreference::PreferQuality => options.safety_net_budget.extended_4x(),
+            QualityPreference::PreferLatency => SafetyNetBudget::DISABLED,
+            _ => options.safety_net_budget,
+        };
+        let returned = (candidates as usize).min(k);
+        let safety_net_likely = self.vectors.len() > 0
+            && !budget.is_disabled()
+            && crate::safety_net::should_activate_safety_net(returned, k);
+
+        Ok(SearchPlan {
+            segments,
+            live_vectors,
+            candidates,
+            safety_net_likely,
+        })
     }
 
     /// Query the store and return a full QualityEnvelope (ADR-033 §2.4).
@@ -393,6 +945,10 @@ impl RvfStore {
     /// This is the preferred query API. The QualityEnvelope is the mandatory
     /// outer return type — consumers MUST inspect the `quality` field before
     /// using results.
+    ///
+    /// When `options.deadline` cuts the scan short, the partial top-k is
+    /// returned with a `DeadlineExceeded` degradation report rather than an
+    /// error, regardless of `quality_preference`.
     pub fn query_with_envelope(
         &self,
         vector: &[f32],
@@ -416,18 +972,33 @@ impl RvfStore {
         };
 
         // Execute the base query.
-        let results = self.query(vector, k, options)?;
+        let outcome = self.scan_nearest(vector, k, options, start)?;
+        self.query_stats.record(start.elapsed());
+        let results = outcome.results;
         let hnsw_candidate_count = results.len() as u32;
 
-        // Determine if safety net should activate.
+        // Determine if safety net should activate. Past the deadline there
+        // is no time left for it.
         let needs_safety_net = crate::safety_net::should_activate_safety_net(results.len(), k)
-            && !budget.is_disabled();
+            && !budget.is_disabled()
+            && !outcome.truncated;
 
         let mut all_results = results;
         let mut safety_net_candidate_count = 0u32;
         let mut budget_report = BudgetReport::default();
         let mut degradation: Option<DegradationReport> = None;
 
+        if outcome.truncated {
+            degradation = Some(DegradationReport {
+                fallback_path: FallbackPath::DeadlineTruncated,
+                reason: DegradationReason::DeadlineExceeded {
+                    scanned: outcome.scanned,
+                    total: outcome.total,
+                },
+                guarantee_lost: "results cover only the vectors scanned before the deadline",
+            });
+        }
+
         if needs_safety_net && self.vectors.len() > 0 {
             // Build vector refs for safety net scan.
             let vec_refs: Vec<(u64, &[f32])> = self
@@ -482,11 +1053,10 @@ impl RvfStore {
         let quality = derive_response_quality(&retrieval_qualities);
 
         let evidence = SearchEvidenceSummary {
+            // An exact scan consults no index layer.
             layers_used: IndexLayersUsed {
-                layer_a: true,
-                layer_b: false,
-                layer_c: false,
                 hot_cache: needs_safety_net,
+                ..IndexLayersUsed::default()
             },
             n_probe_effective: 0,
             degenerate_detected: false,
@@ -503,14 +1073,16 @@ impl RvfStore {
             degradation,
         };
 
-        // Enforce quality threshold policy.
+        // Enforce quality threshold policy. Setting a deadline opts in to
+        // partial results, so a truncated scan is returned, not rejected.
         if matches!(
             quality,
             ResponseQuality::Degraded | ResponseQuality::Unreliable
         ) && !matches!(
             options.quality_preference,
             QualityPreference::AcceptDegraded
-        ) {
+        ) && !outcome.truncated
+        {
             return Err(RvfError::QualityBelowThreshold {
                 quality,
                 reason: "result quality below threshold; set AcceptDegraded to use partial results",
@@ -553,10 +1125,28 @@ impl RvfStore {
 
     /// Soft-delete vectors by ID.
     pub fn delete(&mut self, ids: &[u64]) -> Result<DeleteResult, RvfError> {
-        if self.read_only {
-            return Err(err(ErrorCode::ReadOnly));
-        }
+        self.delete_with_ttl(ids, Duration::ZERO)
+    }
+
+    /// Soft-delete vectors by ID, keeping them reclaimable only after `ttl`.
+    ///
+    /// The vectors are hidden immediately. Compaction keeps a tombstone and
+    /// its vector until the TTL has elapsed and no REFCOUNT_SEG cluster
+    /// still shares the vector. A zero TTL behaves like [`RvfStore::delete`].
+    pub fn delete_with_ttl(
+        &mut self,
+        ids: &[u64],
+        ttl: Duration,
+    ) -> Result<DeleteResult, RvfError> {
+        if self.read_only {
+            return Err(err(ErrorCode::ReadOnly));
+        }
+        let expires_at_ms =
+            (!ttl.is_zero()).then(|| now_millis().saturating_add(ttl.as_millis() as u64));
+        let tombstones: Vec<(u64, Option<u64>)> =
+            ids.iter().map(|&id| (id, expires_at_ms)).collect();
 
+        self.begin_append()?;
         let writer = self
             .seg_writer
             .as_mut()
@@ -569,11 +1159,11 @@ impl RvfStore {
                 .seek(SeekFrom::End(0))
                 .map_err(|_| err(ErrorCode::FsyncFailed))?;
             writer
-                .write_journal_seg(&mut buf_writer, ids, epoch)
+                .write_journal_seg(&mut buf_writer, &tombstones, epoch)
                 .map_err(|_| err(ErrorCode::FsyncFailed))?
         };
 
-        let journal_payload_len = (16 + ids.len() * 12) as u64;
+        let journal_payload_len = (16 + write_path::journal_entry_count(&tombstones) * 12) as u64;
         self.segment_dir.push((
             journal_seg_id,
             journal_offset,
@@ -587,8 +1177,12 @@ impl RvfStore {
 
         let mut deleted = 0u64;
         for &id in ids {
-            if self.vectors.get(id).is_some() && !self.deletion_bitmap.is_deleted(id) {
+            let exists = self.vectors.get(id).is_some() || self.vectors.is_multi_vector_entry(id);
+            if exists && !self.deletion_bitmap.is_deleted(id) {
                 self.deletion_bitmap.delete(id);
+                if let Some(at) = expires_at_ms {
+                    self.deletion_bitmap.set_expiry(id, at);
+                }
                 deleted += 1;
             }
         }
@@ -606,32 +1200,42 @@ impl RvfStore {
         Ok(DeleteResult {
             deleted,
             epoch: self.epoch,
+            expires_at_ms,
         })
     }
 
-    /// Soft-delete vectors matching a filter expression.
+    /// Soft-delete the visible vectors matching a filter expression.
+    ///
+    /// Candidates are the live vectors visible through the membership filter
+    /// (all live vectors when there is none), and only candidates are
+    /// checked against `filter_expr`, so a branch never tombstones inherited
+    /// vectors it cannot see.
     pub fn delete_by_filter(&mut self, filter_expr: &FilterExpr) -> Result<DeleteResult, RvfError> {
         if self.read_only {
             return Err(err(ErrorCode::ReadOnly));
         }
+        filter_expr.validate()?;
 
-        let matching_ids: Vec<u64> = self
+        let membership = self.membership_filter.as_ref();
+        let mut matching_ids: Vec<u64> = self
             .vectors
             .ids()
-            .filter(|&&id| {
-                !self.deletion_bitmap.is_deleted(id)
-                    && filter::evaluate(filter_expr, id, &self.metadata)
-            })
             .copied()
+            .filter(|&id| {
+                !self.deletion_bitmap.is_deleted(id) && membership.is_none_or(|m| m.contains(id))
+            })
+            .filter(|&id| filter::evaluate(filter_expr, id, &self.metadata))
             .collect();
 
         if matching_ids.is_empty() {
             return Ok(DeleteResult {
                 deleted: 0,
                 epoch: self.epoch,
+                expires_at_ms: None,
             });
         }
 
+        matching_ids.sort_unstable();
         self.delete(&matching_ids)
     }
 
@@ -662,26 +1266,135 @@ impl RvfStore {
         }
     }
 
+    /// Check whether stored vectors have unit L2 norm.
+    ///
+    /// Inspects at most `NORMALIZATION_SAMPLE` evenly spaced live vectors
+    /// and counts those whose norm deviates from 1 by more than
+    /// `NORMALIZATION_TOLERANCE`. Useful to catch cosine stores that were
+    /// ingested without normalization.
+    pub fn verify_normalization(&self) -> NormalizationReport {
+        let live_ids: Vec<u64> = self
+            .vectors
+            .ids()
+            .copied()
+            .filter(|&id| !self.deletion_bitmap.is_deleted(id))
+            .collect();
+        let stride = live_ids.len().div_ceil(NORMALIZATION_SAMPLE).max(1);
+
+        let mut report = NormalizationReport {
+            sampled: 0,
+            deviating: 0,
+            max_deviation: 0.0,
+            tolerance: NORMALIZATION_TOLERANCE,
+        };
+        for v in live_ids
+            .iter()
+            .step_by(stride)
+            .filter_map(|&id| self.vectors.get(id))
+        {
+            let deviation = (l2_norm(v) - 1.0).abs();
+            report.sampled += 1;
+            report.max_deviation = report.max_deviation.max(deviation);
+            if deviation > NORMALIZATION_TOLERANCE {
+                report.deviating += 1;
+            }
+        }
+        report
+    }
+
+    /// Apply the store's query normalization policy.
+    fn prepare_query<'a>(&self, vector: &'a [f32]) -> Cow<'a, [f32]> {
+        if self.options.normalization.on_query() {
+            Cow::Owned(l2_normalized(vector))
+        } else {
+            Cow::Borrowed(vector)
+        }
+    }
+
+    /// Take a monitoring snapshot of the store.
+    ///
+    /// Built from in-memory counters and the segment directory; the only
+    /// I/O is a metadata call for the file size. Query counters cover every
+    /// query issued through this handle since it was opened.
+    pub fn metrics(&self) -> StoreMetrics {
+        let total_vectors = self.vectors.len() as u64;
+        let live_vectors = total_vectors.saturating_sub(self.deletion_bitmap.count() as u64);
+        let mut segments_by_type = BTreeMap::new();
+        for &(_, _, _, seg_type) in &self.segment_dir {
+            *segments_by_type.entry(seg_type).or_insert(0u32) += 1;
+        }
+        let query_latency = self.query_stats.histogram();
+
+        StoreMetrics {
+            total_vectors,
+            live_vectors,
+            segments_by_type,
+            total_segments: self.segment_dir.len() as u32,
+            dimension: self.options.dimension,
+            metric: self.options.metric,
+            query_count: query_latency.count(),
+            query_latency,
+            file_bytes: self.file.metadata().map(|m| m.len()).unwrap_or(0),
+            logical_bytes: live_vectors * self.options.dimension as u64 * 4,
+            epoch: self.epoch,
+        }
+    }
+
     /// Run compaction to reclaim dead space.
     ///
     /// Preserves all non-Vec, non-Manifest, non-Journal segments byte-for-byte
     /// to maintain forward compatibility with segment types this version does
     /// not understand (e.g., future Kernel, Ebpf, or vendor-extension segments).
     pub fn compact(&mut self) -> Result<CompactionResult, RvfError> {
+        self.compact_with_progress(|_| {})
+    }
+
+    /// Run compaction, reporting progress to `progress` at segment
+    /// boundaries.
+    ///
+    /// The callback first sees zero segments processed, then one event per
+    /// segment written to the compacted file. The last event arrives after
+    /// the compacted file has replaced the original. Its
+    /// `bytes_reclaimed` equals the returned result's.
+    pub fn compact_with_progress(
+        &mut self,
+        mut progress: impl FnMut(CompactionProgress),
+    ) -> Result<CompactionResult, RvfError> {
         if self.read_only {
             return Err(err(ErrorCode::ReadOnly));
         }
 
-        let deleted_ids = self.deletion_bitmap.to_sorted_ids();
+        // Tombstones still within their TTL, or whose cluster is shared per
+        // the latest REFCOUNT_SEG, survive compaction with their vectors.
+        let refcounts = self.cluster_refcounts()?;
+        let now_ms = now_millis();
+        let deleted_ids: Vec<u64> = self
+            .deletion_bitmap
+            .to_sorted_ids()
+            .into_iter()
+            .filter(|&id| {
+                self.deletion_bitmap.is_expired(id, now_ms)
+                    && !refcounts.as_ref().is_some_and(|r| r.is_shared(id))
+            })
+            .collect();
         for &id in &deleted_ids {
             self.vectors.remove(id);
+            for sub_id in self.sub_vector_ids(id) {
+                self.vectors.remove(sub_id);
+            }
         }
         self.metadata.remove_ids(&deleted_ids);
 
         let segments_compacted = deleted_ids.len() as u32;
         let bytes_reclaimed = (deleted_ids.len() as u64) * (self.options.dimension as u64) * 4;
 
-        self.deletion_bitmap.clear();
+        self.deletion_bitmap.clear_ids(&deleted_ids);
+        let retained: Vec<(u64, Option<u64>)> = self
+            .deletion_bitmap
+            .to_sorted_ids()
+            .into_iter()
+            .map(|id| (id, self.deletion_bitmap.expiry(id)))
+            .collect();
 
         // Read the entire original file into memory so we can scan for segments
         // that may not be in the manifest (e.g., unknown types appended by newer tools).
@@ -697,9 +1410,59 @@ impl RvfStore {
             buf
         };
 
+        let live_ids: Vec<u64> = self.vectors.ids().copied().collect();
+        let bytes_per_vec = (self.options.dimension as usize) * 4;
+        let vec_payload_len = (2 + 4 + live_ids.len() * (8 + bytes_per_vec)) as u64;
+        let preserved = scan_preservable_segments(&original_bytes);
+        let membership_payload = self
+            .membership_filter
+            .as_ref()
+            .map(|f| f.to_segment_payload(&self.visible_content_hash()));
+        let journal_payload_len = (16 + write_path::journal_entry_count(&retained) * 12) as u64;
+
+        // Plan the output so progress can be reported against it.
+        let has_identity = self.file_identity.file_id != [0u8; 16];
+        let segments_total = u32::from(!live_ids.is_empty())
+            + preserved.len() as u32
+            + u32::from(membership_payload.is_some())
+            + u32::from(!retained.is_empty())
+            + 1;
+        let manifest_estimate = (SEGMENT_HEADER_SIZE
+            + 22
+            + (segments_total as usize - 1) * 25
+            + 4
+            + retained.len() * 8
+            + if has_identity { 4 + 68 } else { 0 }
+            + if self.replicated_lsn != 0 { 4 + 8 } else { 0 })
+            as u64;
+        let mut state = CompactionProgress {
+            segments_processed: 0,
+            segments_total,
+            bytes_reclaimed: 0,
+            bytes_remaining: preserved
+                .iter()
+                .map(|&(_, _, len, _)| SEGMENT_HEADER_SIZE as u64 + len)
+                .sum::<u64>()
+                + if live_ids.is_empty() {
+                    0
+                } else {
+                    SEGMENT_HEADER_SIZE as u64 + vec_payload_len
+                }
+                + membership_payload
+                    .as_ref()
+                    .map_or(0, |p| (SEGMENT_HEADER_SIZE + p.len()) as u64)
+                + if retained.is_empty() {
+                    0
+                } else {
+                    SEGMENT_HEADER_SIZE as u64 + journal_payload_len
+                }
+                + manifest_estimate,
+        };
+        progress(state);
+
         let temp_path = self.path.with_extension("rvf.compact.tmp");
         let mut new_segment_dir = Vec::new();
-        let mut seg_writer = SegmentWriter::new(1);
+        let mut seg_writer = SegmentWriter::new(1).with_encryption(self.options.encryption.clone());
         {
             let temp_file = OpenOptions::new()
                 .read(true)
@@ -711,7 +1474,6 @@ impl RvfStore {
 
             let mut temp_writer = BufWriter::new(&temp_file);
 
-            let live_ids: Vec<u64> = self.vectors.ids().copied().collect();
             let live_vecs: Vec<Vec<f32>> = live_ids
                 .iter()
                 .filter_map(|&id| self.vectors.get(id).map(|v| v.to_vec()))
@@ -728,27 +1490,25 @@ impl RvfStore {
                     )
                     .map_err(|_| err(ErrorCode::FsyncFailed))?;
 
-                let bytes_per_vec = (self.options.dimension as usize) * 4;
-                let payload_len = (2 + 4 + live_ids.len() * (8 + bytes_per_vec)) as u64;
+                let payload_len =
+                    vec_payload_len + seg_writer.payload_overhead(SegmentType::Vec as u8);
                 new_segment_dir.push((seg_id, offset, payload_len, SegmentType::Vec as u8));
+                progress(state.advance(
+                    SEGMENT_HEADER_SIZE as u64 + vec_payload_len,
+                    bytes_reclaimed,
+                ));
             }
 
             // Preserve non-Vec, non-Manifest, non-Journal segments from the
             // original file. This includes both segments recorded in the old
             // manifest and segments appended after it (e.g., unknown types from
             // newer format versions).
-            let preserved = scan_preservable_segments(&original_bytes);
+            //
+            // `scan_preservable_segments` only returns segments that fit in
+            // the file, so every one counted in `segments_total` is written.
             for (orig_offset, seg_id, payload_len, seg_type) in &preserved {
-                // Use checked arithmetic for bounds safety.
-                let total_bytes = match (*payload_len as usize).checked_add(SEGMENT_HEADER_SIZE) {
-                    Some(t) => t,
-                    None => continue, // skip segment with implausible size
-                };
-                let end = match orig_offset.checked_add(total_bytes) {
-                    Some(e) if e <= original_bytes.len() => e,
-                    _ => continue, // skip out-of-bounds segment
-                };
-                let src = &original_bytes[*orig_offset..end];
+                let total_bytes = *payload_len as usize + SEGMENT_HEADER_SIZE;
+                let src = &original_bytes[*orig_offset..*orig_offset + total_bytes];
 
                 // Flush the BufWriter so stream_position reflects the true offset.
                 temp_writer
@@ -768,11 +1528,45 @@ impl RvfStore {
                 }
 
                 new_segment_dir.push((*seg_id, new_offset, *payload_len, *seg_type));
+                progress(state.advance(total_bytes as u64, 0));
+            }
+
+            // Carry the membership filter over, hashed against the vectors
+            // that survive compaction.
+            if let Some(payload) = &membership_payload {
+                temp_writer
+                    .flush()
+                    .map_err(|_| err(ErrorCode::FsyncFailed))?;
+                let (seg_id, offset) = seg_writer
+                    .write_membership_seg(&mut temp_writer, payload)
+                    .map_err(|_| err(ErrorCode::FsyncFailed))?;
+                let payload_len = payload.len() as u64
+                    + seg_writer.payload_overhead(SegmentType::Membership as u8);
+                new_segment_dir.push((seg_id, offset, payload_len, SegmentType::Membership as u8));
+                progress(state.advance((SEGMENT_HEADER_SIZE + payload.len()) as u64, 0));
+            }
+
+            // Re-journal surviving tombstones so their expiries outlive the
+            // journals dropped above.
+            if !retained.is_empty() {
+                temp_writer
+                    .flush()
+                    .map_err(|_| err(ErrorCode::FsyncFailed))?;
+                let (seg_id, offset) = seg_writer
+                    .write_journal_seg(&mut temp_writer, &retained, self.epoch + 1)
+                    .map_err(|_| err(ErrorCode::FsyncFailed))?;
+                new_segment_dir.push((
+                    seg_id,
+                    offset,
+                    journal_payload_len + seg_writer.payload_overhead(SegmentType::Journal as u8),
+                    SegmentType::Journal as u8,
+                ));
+                progress(state.advance(SEGMENT_HEADER_SIZE as u64 + journal_payload_len, 0));
             }
 
             self.epoch += 1;
             let total_vectors = live_ids.len() as u64;
-            let empty_dels: Vec<u64> = Vec::new();
+            let retained_ids: Vec<u64> = retained.iter().map(|&(id, _)| id).collect();
             let fi = if self.file_identity.file_id != [0u8; 16] {
                 Some(&self.file_identity)
             } else {
@@ -790,8 +1584,9 @@ impl RvfStore {
                     total_vectors,
                     self.options.profile,
                     &new_segment_dir,
-                    &empty_dels,
+                    &retained_ids,
                     fi,
+                    self.replicated_lsn,
                 )
                 .map_err(|_| err(ErrorCode::FsyncFailed))?;
 
@@ -818,9 +1613,16 @@ impl RvfStore {
             .open(&self.path)
             .map_err(|_| err(ErrorCode::InvalidManifest))?;
 
+        // The file was replaced wholesale, so any pending intent no longer
+        // describes it.
+        if let Some(log) = self.intent_log.as_mut() {
+            log.commit().map_err(|_| err(ErrorCode::FsyncFailed))?;
+        }
+
         self.segment_dir = new_segment_dir;
         self.seg_writer = Some(seg_writer);
         self.last_compaction_time = now_secs();
+        self.membership_dirty = false;
 
         // Reset witness chain after compaction (the file has been rewritten).
         self.last_witness_hash = [0u8; 32];
@@ -837,6 +1639,14 @@ impl RvfStore {
                 .map_err(|_| err(ErrorCode::FsyncFailed))?;
         }
 
+        // The manifest is the last segment; report it once the compacted
+        // file is committed. If no live vectors were left to rewrite, the
+        // reclaimed bytes have not been reported yet.
+        progress(state.advance(
+            state.bytes_remaining,
+            bytes_reclaimed - state.bytes_reclaimed,
+        ));
+
         Ok(CompactionResult {
             segments_compacted,
             bytes_reclaimed,
@@ -845,11 +1655,20 @@ impl RvfStore {
     }
 
     /// Close the store, releasing the writer lock.
-    pub fn close(self) -> Result<(), RvfError> {
+    pub fn close(mut self) -> Result<(), RvfError> {
+        if self.membership_dirty && !self.read_only {
+            self.begin_append()?;
+            self.write_manifest()?;
+        }
+
         self.file
             .sync_all()
             .map_err(|_| err(ErrorCode::FsyncFailed))?;
 
+        if let Some(log) = self.intent_log {
+            log.close().map_err(|_| err(ErrorCode::FsyncFailed))?;
+        }
+
         if let Some(lock) = self.writer_lock {
             lock.release().map_err(|_| err(ErrorCode::LockHeld))?;
         }
@@ -904,6 +1723,7 @@ impl RvfStore {
 
         let cmdline_bytes = cmdline.map(|s| s.as_bytes());
 
+        self.begin_append()?;
         let writer = self
             .seg_writer
             .as_mut()
@@ -992,6 +1812,7 @@ impl RvfStore {
         payload.extend_from_slice(cmdline_slice);
         payload.extend_from_slice(kernel_image);
 
+        self.begin_append()?;
         let writer = self
             .seg_writer
             .as_mut()
@@ -1127,6 +1948,7 @@ impl RvfStore {
         };
         let header_bytes = header.to_bytes();
 
+        self.begin_append()?;
         let writer = self
             .seg_writer
             .as_mut()
@@ -1227,6 +2049,7 @@ impl RvfStore {
         };
         let header_bytes = header.to_bytes();
 
+        self.begin_append()?;
         let writer = self
             .seg_writer
             .as_mut()
@@ -1336,6 +2159,7 @@ impl RvfStore {
         };
         let header_bytes = header.to_bytes();
 
+        self.begin_append()?;
         let writer = self
             .seg_writer
             .as_mut()
@@ -1535,6 +2359,8 @@ impl RvfStore {
             }
         }
         child.membership_filter = Some(filter);
+        child.begin_append()?;
+        child.write_manifest()?;
 
         Ok(child)
     }
@@ -1570,10 +2396,86 @@ impl RvfStore {
     }
 
     /// Get a mutable reference to the membership filter.
+    ///
+    /// Changes are persisted with the next write, or on [`RvfStore::close`].
     pub fn membership_filter_mut(&mut self) -> Option<&mut MembershipFilter> {
+        self.membership_dirty |= self.membership_filter.is_some();
         self.membership_filter.as_mut()
     }
 
+    /// Record cluster reference counts in a REFCOUNT_SEG.
+    ///
+    /// `counts[c]` is the number of snapshots or branches referencing
+    /// cluster `c`. Compaction keeps tombstoned vectors whose cluster count
+    /// is above one. Vector `id` maps to cluster `id / vectors_per_cluster`
+    /// of the COW engine, or to cluster `id` for stores without one.
+    pub fn write_refcounts(&mut self, counts: &[u32]) -> Result<u64, RvfError> {
+        if self.read_only {
+            return Err(err(ErrorCode::ReadOnly));
+        }
+        let header = RefcountHeader {
+            magic: REFCOUNT_MAGIC,
+            version: 1,
+            refcount_width: 4,
+            _pad: 0,
+            cluster_count: counts.len() as u32,
+            max_refcount: u32::MAX,
+            array_offset: 32,
+            snapshot_epoch: self.cow_engine.as_ref().map_or(0, |e| e.snapshot_epoch()),
+            _reserved: 0,
+        };
+        let array: Vec<u8> = counts.iter().flat_map(|c| c.to_le_bytes()).collect();
+
+        self.begin_append()?;
+        let writer = self
+            .seg_writer
+            .as_mut()
+            .ok_or_else(|| err(ErrorCode::InvalidManifest))?;
+        let (seg_id, seg_offset) = {
+            let mut buf_writer = BufWriter::new(&self.file);
+            buf_writer
+                .seek(SeekFrom::End(0))
+                .map_err(|_| err(ErrorCode::FsyncFailed))?;
+            writer
+                .write_refcount_seg(&mut buf_writer, &header.to_bytes(), &array)
+                .map_err(|_| err(ErrorCode::FsyncFailed))?
+        };
+        let payload_len =
+            (32 + array.len()) as u64 + writer.payload_overhead(SegmentType::Refcount as u8);
+        self.segment_dir
+            .push((seg_id, seg_offset, payload_len, SegmentType::Refcount as u8));
+
+        self.file
+            .sync_all()
+            .map_err(|_| err(ErrorCode::FsyncFailed))?;
+        self.epoch += 1;
+        self.write_manifest()?;
+        Ok(seg_id)
+    }
+
+    /// Cluster reference counts from the latest REFCOUNT_SEG, if any.
+    fn cluster_refcounts(&self) -> Result<Option<ClusterRefcounts>, RvfError> {
+        let Some(&(_, offset, _, _)) = self
+            .segment_dir
+            .iter()
+            .rfind(|&&(_, _, _, stype)| stype == SegmentType::Refcount as u8)
+        else {
+            return Ok(None);
+        };
+        let (header, payload) = {
+            let mut reader = BufReader::new(&self.file);
+            read_path::read_segment_payload(&mut reader, offset)
+                .map_err(|_| err(ErrorCode::InvalidChecksum))?
+        };
+        let payload =
+            encryption::decrypt_payload(self.options.encryption.as_ref(), &header, payload)?;
+        let vectors_per_cluster = self
+            .cow_engine
+            .as_ref()
+            .map_or(1, |e| e.vectors_per_cluster() as u64);
+        ClusterRefcounts::parse(&payload, vectors_per_cluster).map(Some)
+    }
+
     /// Get the parent file path, if this is a COW child.
     pub fn parent_path(&self) -> Option<&Path> {
         self.parent_path.as_deref()
@@ -1617,7 +2519,9 @@ impl RvfStore {
             .open(child_path)
             .map_err(|_| err(ErrorCode::FsyncFailed))?;
 
-        let writer_lock = WriterLock::acquire(child_path).map_err(|_| err(ErrorCode::LockHeld))?;
+        let writer_lock = WriterLock::acquire_with(child_path, &self.options.lock)
+            .map_err(|_| err(ErrorCode::LockHeld))?;
+        let intent_log = IntentLog::create(child_path).map_err(|_| err(ErrorCode::FsyncFailed))?;
 
         // Detect domain profile from child extension
         let domain_profile = child_path
@@ -1628,13 +2532,15 @@ impl RvfStore {
 
         let mut child_opts = opts;
         child_opts.domain_profile = domain_profile;
+        let seg_writer = SegmentWriter::new(1).with_encryption(child_opts.encryption.clone());
 
         let mut store = Self {
             path: child_path.to_path_buf(),
             options: child_opts,
             file,
-            seg_writer: Some(SegmentWriter::new(1)),
+            seg_writer: Some(seg_writer),
             writer_lock: Some(writer_lock),
+            intent_log: Some(intent_log),
             vectors: VectorData::new(self.options.dimension),
             deletion_bitmap: DeletionBitmap::new(),
             metadata: MetadataStore::new(),
@@ -1645,10 +2551,15 @@ impl RvfStore {
             file_identity: child_identity,
             cow_engine: None,
             membership_filter: None,
+            membership_dirty: false,
             parent_path: Some(self.path.clone()),
             last_witness_hash: [0u8; 32],
+            query_stats: QueryStats::default(),
+            replicated_lsn: 0,
+            manifest_offset: 0,
         };
 
+        store.write_settings()?;
         store.write_manifest()?;
         Ok(store)
     }
@@ -1697,8 +2608,159 @@ impl RvfStore {
         self.epoch
     }
 
+    /// Read the committed operations with an LSN greater than `since`.
+    ///
+    /// Records come back in LSN order. If the file was compacted after
+    /// `since`, the stream starts with a snapshot of the live set instead of
+    /// the individual operations that were compacted away.
+    pub fn replication_stream(
+        &self,
+        since: Option<Lsn>,
+    ) -> Result<impl Iterator<Item = ReplicationRecord>, RvfError> {
+        let since = since.unwrap_or(0);
+        let records = replication::read_records(&self.file, self.options.encryption.as_ref())?;
+        Ok(records.into_iter().filter(move |r| r.lsn > since))
+    }
+
+    /// Replay a record from a leader's replication stream.
+    ///
+    /// Returns `Ok(false)` without changing anything if the record's LSN was
+    /// already applied. A non-snapshot record must follow the last applied
+    /// one directly; a gap fails with `LineageBroken`.
+    pub fn apply_replication(&mut self, record: ReplicationRecord) -> Result<bool, RvfError> {
+        if self.read_only {
+            return Err(err(ErrorCode::ReadOnly));
+        }
+        if record.lsn <= self.replicated_lsn {
+            return Ok(false);
+        }
+        if !record.is_snapshot() && record.prev_lsn != self.replicated_lsn {
+            return Err(err(ErrorCode::LineageBroken));
+        }
+
+        for op in record.ops {
+            match op {
+                ReplicationOp::Ingest { ids, vectors } => {
+                    let refs: Vec<&[f32]> = vectors.iter().map(|v| v.as_slice()).collect();
+                    self.ingest_vectors(&refs, &ids, None)?;
+                }
+                ReplicationOp::Delete { ids } => {
+                    self.delete(&ids)?;
+                }
+                ReplicationOp::Snapshot { ids, vectors } => {
+                    let keep: HashSet<u64> = ids.iter().copied().collect();
+                    let mut stale: Vec<u64> = self
+                        .vectors
+                        .ids()
+                        .copied()
+                        .filter(|id| !self.deletion_bitmap.is_deleted(*id) && !keep.contains(id))
+                        .collect();
+                    stale.sort_unstable();
+                    if !stale.is_empty() {
+                        self.delete(&stale)?;
+                    }
+                    self.deletion_bitmap.clear_ids(&ids);
+                    if !ids.is_empty() {
+                        let refs: Vec<&[f32]> = vectors.iter().map(|v| v.as_slice()).collect();
+                        self.ingest_vectors(&refs, &ids, None)?;
+                    }
+                }
+            }
+        }
+
+        // Record the LSN in a fresh manifest so a reopened follower
+        // resumes after this record instead of replaying it.
+        self.replicated_lsn = record.lsn;
+        self.begin_append()?;
+        self.write_manifest()?;
+        Ok(true)
+    }
+
+    /// Pin a read view of the store as of its latest manifest.
+    ///
+    /// The view is loaded from the file up to the end of that manifest, so
+    /// vectors ingested or deleted afterwards do not change its results.
+    pub fn snapshot(&self) -> Result<Snapshot, RvfError> {
+        let checkpoint = Checkpoint {
+            manifest_offset: self.manifest_offset,
+            epoch: self.epoch,
+        };
+        Self::open_view(&self.path, checkpoint, self.options.encryption.clone())
+    }
+
+    /// Write and sync a fresh manifest and return a marker for it.
+    ///
+    /// [`RvfStore::open_checkpoint`] reopens the store at the marker
+    /// directly, without scanning for the latest manifest.
+    pub fn checkpoint(&mut self) -> Result<Checkpoint, RvfError> {
+        if self.read_only {
+            return Err(err(ErrorCode::ReadOnly));
+        }
+        self.begin_append()?;
+        self.write_manifest()?;
+        Ok(Checkpoint {
+            manifest_offset: self.manifest_offset,
+            epoch: self.epoch,
+        })
+    }
+
+    /// Open a read-only view of the store at `path` as of `checkpoint`.
+    ///
+    /// Fails with `InvalidManifest` if the checkpoint does not name a
+    /// manifest in this file, e.g. after the store was compacted.
+    pub fn open_checkpoint(path: &Path, checkpoint: Checkpoint) -> Result<Snapshot, RvfError> {
+        Self::open_view(path, checkpoint, None)
+    }
+
+    /// Boot a read-only store from the file prefix ending at the manifest
+    /// named by `checkpoint`.
+    fn open_view(
+        path: &Path,
+        checkpoint: Checkpoint,
+        encryption: Option<EncryptionConfig>,
+    ) -> Result<Snapshot, RvfError> {
+        let mut view = Self::open_unbooted(path, true, encryption, LockOptions::default())?;
+        let mut reader = BufReader::new(
+            view.file
+                .try_clone()
+                .map_err(|_| err(ErrorCode::InvalidManifest))?,
+        );
+        let (header, payload) =
+            read_path::read_segment_payload(&mut reader, checkpoint.manifest_offset)
+                .map_err(|_| err(ErrorCode::InvalidManifest))?;
+        if header.seg_type != SegmentType::Manifest as u8 {
+            return Err(err(ErrorCode::InvalidManifest));
+        }
+        let end = checkpoint.manifest_offset + (SEGMENT_HEADER_SIZE + payload.len()) as u64;
+        let mut prefix = read_path::PrefixReader::new(reader, end)
+            .map_err(|_| err(ErrorCode::InvalidManifest))?;
+        view.boot_from(&mut prefix, |_| {})?;
+        if view.manifest_offset != checkpoint.manifest_offset || view.epoch != checkpoint.epoch {
+            return Err(err(ErrorCode::InvalidManifest));
+        }
+        Ok(Snapshot { checkpoint, view })
+    }
+
+    /// LSN of the last replication record applied to this store.
+    pub fn replicated_lsn(&self) -> Lsn {
+        self.replicated_lsn
+    }
+
     // ── Internal methods ──────────────────────────────────────────────
 
+    /// Stored metadata for a vector, converted to caller-facing entries.
+    fn metadata_entries(&self, id: u64) -> Vec<MetadataEntry> {
+        self.metadata
+            .get_all(id)
+            .unwrap_or(&[])
+            .iter()
+            .map(|(field_id, value)| MetadataEntry {
+                field_id: *field_id,
+                value: filter_value_to_metadata(value),
+            })
+            .collect()
+    }
+
     /// Append a witness segment to the file and update the witness chain.
     ///
     /// `witness_type` is one of the `witness_types::*` constants.
@@ -1753,52 +2815,195 @@ impl RvfStore {
     }
 
     fn boot(&mut self) -> Result<(), RvfError> {
-        let manifest = {
-            let mut reader = BufReader::new(&self.file);
-            read_path::find_latest_manifest(&mut reader)
-                .map_err(|_| err(ErrorCode::ManifestNotFound))?
-        };
+        let file = self
+            .file
+            .try_clone()
+            .map_err(|_| err(ErrorCode::InvalidManifest))?;
+        self.boot_from(&mut BufReader::new(file), |_| {})
+    }
 
-        let manifest = match manifest {
-            Some(m) => m,
-            None => return Err(err(ErrorCode::ManifestNotFound)),
-        };
+    /// Load the store through a read-only memory map of the file.
+    #[cfg(unix)]
+    fn boot_mmap(&mut self, advise: bool) -> Result<(), RvfError> {
+        use memmap2::{Advice, Mmap};
+
+        let file = self
+            .file
+            .try_clone()
+            .map_err(|_| err(ErrorCode::InvalidManifest))?;
+        let _shared = FileLock::shared(&file).map_err(|_| err(ErrorCode::LockHeld))?;
+
+        let file_len = file
+            .metadata()
+            .map_err(|_| err(ErrorCode::InvalidManifest))?
+            .len();
+        if file_len < SEGMENT_HEADER_SIZE as u64 {
+            return Err(err(ErrorCode::ManifestNotFound));
+        }
+
+        // SAFETY: a mapped page faults if the file is cut below it, and
+        // between cooperating processes the file only shrinks in intent-log
+        // recovery, which takes the exclusive `FileLock` first. The shared
+        // lock above is held until the map is dropped at the end of this
+        // function, so the file is at least `map.len()` bytes for the map's
+        // whole life; appends past that are never seen. Compaction replaces
+        // the file by rename and leaves this inode alone. As with any mmap,
+        // truncation by a process that ignores the lock is not covered.
+        let map = unsafe { Mmap::map(&file) }.map_err(|_| err(ErrorCode::InvalidManifest))?;
+
+        if advise {
+            // The manifest is found by scanning the tail of the file.
+            let tail = map.len().min(65_536);
+            let _ = map.advise_range(Advice::WillNeed, map.len() - tail, tail);
+        }
+
+        self.boot_from(&mut std::io::Cursor::new(&map[..]), |segment_dir| {
+            if !advise {
+                return;
+            }
+            // Boot copies every vector out of the map; prefetch them.
+            for entry in segment_dir {
+                if entry.seg_type == SegmentType::Vec as u8 {
+                    let len = SEGMENT_HEADER_SIZE + entry.payload_length as usize;
+                    let _ = map.advise_range(Advice::WillNeed, entry.offset as usize, len);
+                }
+            }
+        })
+    }
+
+    /// Load the latest manifest and the vectors it references from `reader`.
+    ///
+    /// `on_manifest` sees the segment directory once every entry has been
+    /// checked to lie within the file.
+    fn boot_from<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        on_manifest: impl FnOnce(&[read_path::SegDirEntry]),
+    ) -> Result<(), RvfError> {
+        let manifest = Self::locate_manifest(reader)?;
+        on_manifest(&manifest.segment_dir);
+
+        self.apply_manifest(&manifest);
+        self.load_side_segments(reader, &manifest)?;
+        for entry in manifest
+            .segment_dir
+            .iter()
+            .filter(|e| e.seg_type == SegmentType::Vec as u8)
+        {
+            self.load_vec_segment(reader, entry.offset)?;
+        }
+        self.finish_boot(reader, &manifest)
+    }
 
+    /// Find the latest manifest in `reader` and check that every segment it
+    /// references lies within the file.
+    pub(crate) fn locate_manifest<R: Read + Seek>(
+        reader: &mut R,
+    ) -> Result<read_path::ParsedManifest, RvfError> {
+        let manifest = read_path::find_latest_manifest(reader)
+            .map_err(|_| err(ErrorCode::ManifestNotFound))?
+            .ok_or_else(|| err(ErrorCode::ManifestNotFound))?;
+
+        let file_len = reader
+            .seek(SeekFrom::End(0))
+            .map_err(|_| err(ErrorCode::InvalidManifest))?;
+        for entry in &manifest.segment_dir {
+            let end = entry
+                .offset
+                .checked_add(SEGMENT_HEADER_SIZE as u64)
+                .and_then(|o| o.checked_add(entry.payload_length));
+            if end.is_none_or(|end| end > file_len) {
+                return Err(err(ErrorCode::TruncatedSegment));
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Reset the in-memory state to what `manifest` describes, with no
+    /// vectors loaded yet.
+    pub(crate) fn apply_manifest(&mut self, manifest: &read_path::ParsedManifest) {
         self.epoch = manifest.epoch;
+        self.manifest_offset = manifest.offset;
         self.options.dimension = manifest.dimension;
         self.options.profile = manifest.profile_id;
         self.vectors = VectorData::new(manifest.dimension);
         self.deletion_bitmap = DeletionBitmap::from_ids(&manifest.deleted_ids);
-
         self.segment_dir = manifest
             .segment_dir
             .iter()
             .map(|e| (e.seg_id, e.offset, e.payload_length, e.seg_type))
             .collect();
+        self.replicated_lsn = manifest.replicated_lsn;
+        if let Some(fi) = manifest.file_identity {
+            self.file_identity = fi;
+        }
+    }
 
-        let vec_seg_entries: Vec<_> = manifest
+    /// Load the journal expiries and persisted settings `manifest`
+    /// references. Neither depends on the vectors.
+    pub(crate) fn load_side_segments<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        manifest: &read_path::ParsedManifest,
+    ) -> Result<(), RvfError> {
+        for entry in manifest
             .segment_dir
             .iter()
-            .filter(|e| e.seg_type == SegmentType::Vec as u8)
-            .collect();
+            .filter(|e| e.seg_type == SegmentType::Journal as u8)
+        {
+            let (header, payload) = read_path::read_segment_payload(reader, entry.offset)
+                .map_err(|_| err(ErrorCode::InvalidChecksum))?;
+            let payload =
+                encryption::decrypt_payload(self.options.encryption.as_ref(), &header, payload)?;
+            for (id, expires_at_ms) in read_path::read_journal_expiries(&payload) {
+                self.deletion_bitmap.set_expiry(id, expires_at_ms);
+            }
+        }
 
-        for entry in vec_seg_entries {
-            let (_header, payload) = {
-                let mut reader = BufReader::new(&self.file);
-                read_path::read_segment_payload(&mut reader, entry.offset)
-                    .map_err(|_| err(ErrorCode::InvalidChecksum))?
-            };
+        if let Some(entry) = manifest
+            .segment_dir
+            .iter()
+            .rfind(|e| e.seg_type == SegmentType::Settings as u8)
+        {
+            let (_, payload) = read_path::read_segment_payload(reader, entry.offset)
+                .map_err(|_| err(ErrorCode::InvalidChecksum))?;
+            settings::apply(&payload, &mut self.options)?;
+        }
+        Ok(())
+    }
 
-            if let Some(vec_entries) = read_path::read_vec_seg_payload(&payload) {
-                for (vec_id, vec_data) in vec_entries {
-                    self.vectors.insert(vec_id, vec_data);
-                }
+    /// Load the vectors of the VEC segment at `offset`.
+    pub(crate) fn load_vec_segment<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        offset: u64,
+    ) -> Result<(), RvfError> {
+        let (header, payload) = read_path::read_segment_payload(reader, offset)
+            .map_err(|_| err(ErrorCode::InvalidChecksum))?;
+        let payload =
+            encryption::decrypt_payload(self.options.encryption.as_ref(), &header, payload)?;
+        if let Some(vec_entries) = read_path::read_vec_seg_payload(&payload) {
+            for (vec_id, vec_data) in vec_entries {
+                self.vectors.insert(vec_id, vec_data);
             }
         }
+        Ok(())
+    }
 
-        // Restore FileIdentity from manifest if present
-        if let Some(fi) = manifest.file_identity {
-            self.file_identity = fi;
+    /// Complete a boot once every VEC segment is loaded: check the
+    /// membership filter against the vectors and, for writable stores,
+    /// set up the segment writer.
+    pub(crate) fn finish_boot<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        manifest: &read_path::ParsedManifest,
+    ) -> Result<(), RvfError> {
+        if let Some(entry) = manifest
+            .segment_dir
+            .iter()
+            .rfind(|e| e.seg_type == SegmentType::Membership as u8)
+        {
+            self.load_membership_filter(reader, entry.offset)?;
         }
 
         if !self.read_only {
@@ -1808,23 +3013,98 @@ impl RvfStore {
                 .map(|&(id, _, _, _)| id)
                 .max()
                 .unwrap_or(0);
-            self.seg_writer = Some(SegmentWriter::new(max_seg_id + 1));
+            self.seg_writer = Some(
+                SegmentWriter::new(max_seg_id + 1).with_encryption(self.options.encryption.clone()),
+            );
         }
 
         Ok(())
     }
 
-    fn write_manifest(&mut self) -> Result<(), RvfError> {
+    /// Reload the membership filter persisted at `offset`.
+    ///
+    /// The filter is rewritten with every manifest, so one whose recorded
+    /// content hash does not match the live vectors (or that fails to
+    /// parse) is corrupt and fails the open with `MembershipInvalid`.
+    fn load_membership_filter<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        offset: u64,
+    ) -> Result<(), RvfError> {
+        let (header, payload) = read_path::read_segment_payload(reader, offset)
+            .map_err(|_| err(ErrorCode::InvalidChecksum))?;
+        let payload =
+            encryption::decrypt_payload(self.options.encryption.as_ref(), &header, payload)?;
+
+        let (filter, _, content_hash) = MembershipFilter::from_segment_payload(&payload)
+            .map_err(|_| err(ErrorCode::MembershipInvalid))?;
+        if content_hash != self.visible_content_hash() {
+            return Err(err(ErrorCode::MembershipInvalid));
+        }
+        self.membership_filter = Some(filter);
+        Ok(())
+    }
+
+    /// Content hash of the live (non-deleted) vector IDs.
+    fn visible_content_hash(&self) -> [u8; 32] {
+        let visible = self
+            .vectors
+            .ids()
+            .filter(|&&id| !self.deletion_bitmap.is_deleted(id));
+        Self::membership_content_hash(visible)
+    }
+
+    /// Append the membership filter, if any, so the manifest about to be
+    /// written points at a copy matching the current vectors.
+    fn write_membership(&mut self) -> Result<(), RvfError> {
+        let Some(filter) = self.membership_filter.as_ref() else {
+            return Ok(());
+        };
+        let payload = filter.to_segment_payload(&self.visible_content_hash());
         let writer = self
             .seg_writer
             .as_mut()
             .ok_or_else(|| err(ErrorCode::InvalidManifest))?;
-
-        let total_vectors = self.vectors.len() as u64;
-        let deleted_ids = self.deletion_bitmap.to_sorted_ids();
-
-        // Include FileIdentity if this file has a non-zero file_id
-        let fi = if self.file_identity.file_id != [0u8; 16] {
+        let (seg_id, offset) = {
+            let mut buf_writer = BufWriter::new(&self.file);
+            buf_writer
+                .seek(SeekFrom::End(0))
+                .map_err(|_| err(ErrorCode::FsyncFailed))?;
+            writer
+                .write_membership_seg(&mut buf_writer, &payload)
+                .map_err(|_| err(ErrorCode::FsyncFailed))?
+        };
+        let payload_len =
+            payload.len() as u64 + writer.payload_overhead(SegmentType::Membership as u8);
+        self.segment_dir
+            .retain(|&(_, _, _, seg_type)| seg_type != SegmentType::Membership as u8);
+        self.segment_dir
+            .push((seg_id, offset, payload_len, SegmentType::Membership as u8));
+        self.membership_dirty = false;
+        Ok(())
+    }
+
+    /// Hash identifying the set of live vector IDs a membership filter was
+    /// built against.
+    fn membership_content_hash<'a>(ids: impl Iterator<Item = &'a u64>) -> [u8; 32] {
+        let mut ids: Vec<u64> = ids.copied().collect();
+        ids.sort_unstable();
+        let bytes: Vec<u8> = ids.iter().flat_map(|id| id.to_le_bytes()).collect();
+        simple_shake256_256(&bytes)
+    }
+
+    fn write_manifest(&mut self) -> Result<(), RvfError> {
+        self.write_membership()?;
+        let writer = self
+            .seg_writer
+            .as_mut()
+            .ok_or_else(|| err(ErrorCode::InvalidManifest))?;
+
+        let total_vectors = self.vectors.len() as u64;
+        let deleted_ids = self.deletion_bitmap.to_sorted_ids();
+
+        // Include FileIdentity if this file has a non-zero file_id
+        let fi = if self.file_identity.file_id != [0u8; 16] {
             Some(&self.file_identity)
         } else {
             None
@@ -1845,6 +3125,7 @@ impl RvfStore {
                     &self.segment_dir,
                     &deleted_ids,
                     fi,
+                    self.replicated_lsn,
                 )
                 .map_err(|_| err(ErrorCode::FsyncFailed))?
         };
@@ -1854,6 +3135,10 @@ impl RvfStore {
         if fi.is_some() {
             manifest_payload_len += 4 + 68; // FIDI marker + FileIdentity
         }
+        if self.replicated_lsn != 0 {
+            manifest_payload_len += 4 + 8; // RLSN marker + LSN
+        }
+        self.manifest_offset = manifest_offset;
         self.segment_dir.push((
             manifest_seg_id,
             manifest_offset,
@@ -1864,8 +3149,72 @@ impl RvfStore {
         self.file
             .sync_all()
             .map_err(|_| err(ErrorCode::FsyncFailed))?;
+        if let Some(log) = self.intent_log.as_mut() {
+            log.commit().map_err(|_| err(ErrorCode::FsyncFailed))?;
+        }
+        Ok(())
+    }
+
+    /// Append the settings segment if any option must outlive this handle.
+    fn write_settings(&mut self) -> Result<(), RvfError> {
+        let Some(payload) = settings::encode(&self.options) else {
+            return Ok(());
+        };
+        let writer = self
+            .seg_writer
+            .as_mut()
+            .ok_or_else(|| err(ErrorCode::InvalidManifest))?;
+        let (seg_id, offset) = {
+            let mut buf_writer = BufWriter::new(&self.file);
+            buf_writer
+                .seek(SeekFrom::End(0))
+                .map_err(|_| err(ErrorCode::FsyncFailed))?;
+            writer
+                .write_settings_seg(&mut buf_writer, &payload)
+                .map_err(|_| err(ErrorCode::FsyncFailed))?
+        };
+        let payload_len =
+            payload.len() as u64 + writer.payload_overhead(SegmentType::Settings as u8);
+        self.segment_dir
+            .push((seg_id, offset, payload_len, SegmentType::Settings as u8));
         Ok(())
     }
+
+    /// Record in the intent log that segments are about to be appended,
+    /// so a crash before the next manifest can be rolled back on open.
+    fn begin_append(&mut self) -> Result<(), RvfError> {
+        // A writer that stalled long enough to have its lock reclaimed
+        // must not append on top of the new holder.
+        if let Some(lock) = self.writer_lock.as_ref() {
+            if !lock.heartbeat().map_err(|_| err(ErrorCode::FsyncFailed))? {
+                return Err(err(ErrorCode::GenerationStale));
+            }
+        }
+        let (Some(log), Some(writer)) = (self.intent_log.as_mut(), self.seg_writer.as_ref()) else {
+            return Ok(());
+        };
+        let offset = self
+            .file
+            .metadata()
+            .map_err(|_| err(ErrorCode::FsyncFailed))?
+            .len();
+        log.begin(writer.next_id(), offset)
+            .map_err(|_| err(ErrorCode::FsyncFailed))
+    }
+}
+
+fn l2_norm(v: &[f32]) -> f32 {
+    v.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+/// Scale `v` to unit length; zero vectors are returned unchanged.
+fn l2_normalized(v: &[f32]) -> Vec<f32> {
+    let norm = l2_norm(v);
+    if norm < f32::EPSILON {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / norm).collect()
+    }
 }
 
 fn compute_distance(a: &[f32], b: &[f32], metric: &DistanceMetric) -> f32 {
@@ -1901,25 +3250,6 @@ fn compute_distance(a: &[f32], b: &[f32], metric: &DistanceMetric) -> f32 {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct OrderedFloat(f32);
-
-impl Eq for OrderedFloat {}
-
-impl PartialOrd for OrderedFloat {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for OrderedFloat {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.0
-            .partial_cmp(&other.0)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    }
-}
-
 /// Generate a file_id from path + timestamp using `simple_shake256_256`.
 ///
 /// Previous implementation used XOR mixing which has very poor distribution
@@ -2015,11 +3345,12 @@ fn scan_preservable_segments(file_bytes: &[u8]) -> Vec<(usize, u64, u64, u8)> {
                 }
             };
 
-            // Skip Vec, Manifest, and Journal segments -- these are
-            // reconstructed by the compaction logic itself.
+            // Skip Vec, Manifest, Journal and Membership segments -- these
+            // are reconstructed by the compaction logic itself.
             if seg_type != SegmentType::Vec as u8
                 && seg_type != SegmentType::Manifest as u8
                 && seg_type != SegmentType::Journal as u8
+                && seg_type != SegmentType::Membership as u8
             {
                 // Only include if the full segment fits in the file.
                 if i.checked_add(total)
@@ -2054,6 +3385,13 @@ fn now_secs() -> u64 {
         .unwrap_or(0)
 }
 
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2216,6 +3554,20 @@ mod tests {
         assert_eq!(results.len(), 2);
         assert!(results.iter().all(|r| r.id == 1 || r.id == 3));
 
+        let mut deep = FilterExpr::Eq(0, FilterValue::String("cat_a".into()));
+        for _ in 0..crate::filter::MAX_FILTER_DEPTH {
+            deep = FilterExpr::Not(Box::new(deep));
+        }
+        let query_opts = QueryOptions {
+            filter: Some(deep.clone()),
+            ..Default::default()
+        };
+        assert_eq!(
+            store.query(&query, 10, &query_opts).unwrap_err(),
+            err(ErrorCode::FilterParseError)
+        );
+        assert!(store.delete_by_filter(&deep).is_err());
+
         store.close().unwrap();
     }
 
@@ -2246,6 +3598,313 @@ mod tests {
         store.close().unwrap();
     }
 
+    #[test]
+    fn metrics_track_ingest_and_queries() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("metrics.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let empty = store.metrics();
+        assert_eq!(empty.total_vectors, 0);
+        assert_eq!(empty.query_count, 0);
+
+        let vecs: Vec<Vec<f32>> = (0..20).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..20).collect();
+        store.ingest_batch(&refs, &ids, None).unwrap();
+        store.delete(&[3, 4, 5]).unwrap();
+
+        let opts = QueryOptions::default();
+        store.query(&vecs[0], 5, &opts).unwrap();
+        store.query_with_envelope(&vecs[1], 5, &opts).unwrap();
+        assert!(store.query(&[0.0; 3], 5, &opts).is_err());
+
+        let m = store.metrics();
+        assert_eq!(m.total_vectors, 20);
+        assert_eq!(m.live_vectors, 17);
+        assert!(m.live_vectors <= m.total_vectors);
+        assert_eq!(m.query_count, 2);
+        assert_eq!(m.query_latency.count(), m.query_count);
+        assert_eq!(
+            m.query_latency.counts.len(),
+            m.query_latency.bounds_us.len() + 1
+        );
+        assert_eq!(m.segments_by_type.values().sum::<u32>(), m.total_segments);
+        assert_eq!(m.segments_by_type[&(SegmentType::Vec as u8)], 1);
+        assert_eq!(m.logical_bytes, 17 * 4 * 4);
+        assert!(m.file_bytes >= m.logical_bytes);
+        assert_eq!(m.epoch, store.epoch());
+        assert_eq!(m.total_segments, store.status().total_segments);
+
+        store.close().unwrap();
+    }
+
+    #[test]
+    fn normalization_policy_yields_cosine_rankings() {
+        let dir = TempDir::new().unwrap();
+        let vecs: Vec<Vec<f32>> = (0..30)
+            .map(|i| {
+                let scale = 0.1 + (i % 7) as f32 * 3.0;
+                random_vector(8, i).iter().map(|x| x * scale).collect()
+            })
+            .collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..30).collect();
+        let query: Vec<f32> = random_vector(8, 99).iter().map(|x| x * 25.0).collect();
+        let opts = QueryOptions::default();
+
+        let mut cosine = RvfStore::create(
+            &dir.path().join("cosine.rvf"),
+            RvfOptions {
+                dimension: 8,
+                metric: DistanceMetric::Cosine,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cosine.ingest_batch(&refs, &ids, None).unwrap();
+
+        // Inner product over unit vectors ranks exactly like cosine.
+        let mut normalized = RvfStore::create(
+            &dir.path().join("normalized.rvf"),
+            RvfOptions {
+                dimension: 8,
+                metric: DistanceMetric::InnerProduct,
+                normalization: NormalizePolicy::L2Both,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        normalized.ingest_batch(&refs, &ids, None).unwrap();
+
+        let expected: Vec<u64> = cosine
+            .query(&query, 10, &opts)
+            .unwrap()
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        let actual: Vec<u64> = normalized
+            .query(&query, 10, &opts)
+            .unwrap()
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(actual, expected);
+
+        let report = normalized.verify_normalization();
+        assert_eq!(report.sampled, 30);
+        assert!(report.is_normalized());
+        assert!(report.max_deviation <= report.tolerance);
+
+        cosine.close().unwrap();
+        normalized.close().unwrap();
+    }
+
+    #[test]
+    fn normalization_policy_survives_reopen_and_compaction() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("normalized.rvf");
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::InnerProduct,
+            normalization: NormalizePolicy::L2OnIngest,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        store
+            .ingest_batch(&[&[3.0, 4.0, 0.0, 0.0]], &[1], None)
+            .unwrap();
+
+        // Settings get their own segment type, not the domain profile's,
+        // and the directory records the length written to disk.
+        let seg_dir = store.segment_dir().to_vec();
+        assert!(seg_dir.iter().all(|e| e.3 != SegmentType::Profile as u8));
+        let &(_, offset, payload_len, _) = seg_dir
+            .iter()
+            .find(|e| e.3 == SegmentType::Settings as u8)
+            .unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let at = offset as usize + 0x10;
+        assert_eq!(
+            u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap()),
+            payload_len
+        );
+        store.close().unwrap();
+
+        // Vectors ingested after a reopen are normalized too.
+        let mut store = RvfStore::open(&path).unwrap();
+        assert_eq!(store.options().normalization, NormalizePolicy::L2OnIngest);
+        store
+            .ingest_batch(&[&[0.0, 0.0, 0.0, 2.0]], &[2], None)
+            .unwrap();
+        assert!(store.verify_normalization().is_normalized());
+
+        store.compact().unwrap();
+        store.close().unwrap();
+        let store = RvfStore::open_readonly(&path).unwrap();
+        assert_eq!(store.options().normalization, NormalizePolicy::L2OnIngest);
+        assert_eq!(store.verify_normalization().sampled, 2);
+        assert!(store.verify_normalization().is_normalized());
+    }
+
+    #[test]
+    fn verify_normalization_flags_raw_cosine_store() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("raw.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::Cosine,
+            ..Default::default()
+        };
+
+        let mut store = RvfStore::create(&path, options).unwrap();
+        assert_eq!(store.verify_normalization().sampled, 0);
+
+        let vecs = [
+            vec![1.0, 0.0, 0.0, 0.0],
+            vec![0.0, 3.0, 4.0, 0.0],
+            vec![0.0, 0.0, 0.0, 0.5],
+        ];
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        store.ingest_batch(&refs, &[1, 2, 3], None).unwrap();
+
+        let report = store.verify_normalization();
+        assert_eq!(report.sampled, 3);
+        assert_eq!(report.deviating, 2);
+        assert!(!report.is_normalized());
+        assert!((report.max_deviation - 4.0).abs() < 1e-6);
+
+        // Deleted vectors are not sampled.
+        store.delete(&[2, 3]).unwrap();
+        let report = store.verify_normalization();
+        assert_eq!(report.sampled, 1);
+        assert!(report.is_normalized());
+
+        store.close().unwrap();
+    }
+
+    #[test]
+    fn wrong_dimension_ingest_fails_batch() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("dim_strict.rvf");
+        let options = RvfOptions {
+            dimension: 4,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+
+        let good = [1.0f32, 2.0, 3.0, 4.0];
+        let long = [1.0f32; 6];
+        let result = store.ingest_batch(&[&good, &long], &[1, 2], None);
+        assert_eq!(
+            result.unwrap_err(),
+            RvfError::DimensionMismatch {
+                expected: 4,
+                got: 6
+            }
+        );
+        // Nothing from the failed batch was written.
+        assert_eq!(store.status().total_vectors, 0);
+
+        let result = store.ingest_batch(&[&good], &[1], None).unwrap();
+        assert_eq!(result.accepted, 1);
+        assert!(result.warnings.is_empty());
+
+        store.close().unwrap();
+    }
+
+    #[test]
+    fn pad_projection_zero_extends() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("dim_pad.rvf");
+        let options = RvfOptions {
+            dimension: 4,
+            auto_project: Some(Projection::Pad),
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+
+        let short = [1.0f32, 2.0];
+        let result = store.ingest_batch(&[&short], &[7], None).unwrap();
+        assert_eq!(result.accepted, 1);
+        assert_eq!(
+            result.warnings,
+            vec![IngestWarning::Projected {
+                id: 7,
+                from: 2,
+                to: 4
+            }]
+        );
+        assert_eq!(store.vectors.get(7), Some(&[1.0, 2.0, 0.0, 0.0][..]));
+
+        // Pad never truncates.
+        let long = [1.0f32; 5];
+        assert_eq!(
+            store.ingest_batch(&[&long], &[8], None).unwrap_err(),
+            RvfError::DimensionMismatch {
+                expected: 4,
+                got: 5
+            }
+        );
+
+        store.close().unwrap();
+    }
+
+    #[test]
+    fn matrix_projection_is_deterministic() {
+        let dir = TempDir::new().unwrap();
+        let projection = Projection::random(32, 8, 42);
+        assert_eq!(projection, Projection::random(32, 8, 42));
+        assert_ne!(projection, Projection::random(32, 8, 43));
+
+        let inputs: Vec<Vec<f32>> = (0..3).map(|i| random_vector(32, i)).collect();
+        let refs: Vec<&[f32]> = inputs.iter().map(|v| v.as_slice()).collect();
+
+        let mut stored = Vec::new();
+        for name in ["proj_a.rvf", "proj_b.rvf"] {
+            let options = RvfOptions {
+                dimension: 8,
+                auto_project: Some(projection.clone()),
+                ..Default::default()
+            };
+            let mut store = RvfStore::create(&dir.path().join(name), options).unwrap();
+            let result = store.ingest_batch(&refs, &[0, 1, 2], None).unwrap();
+            assert_eq!(result.accepted, 3);
+            assert_eq!(result.warnings.len(), 3);
+            let vecs: Vec<Vec<f32>> = (0..3)
+                .map(|id| store.vectors.get(id).unwrap().to_vec())
+                .collect();
+            assert!(vecs.iter().all(|v| v.len() == 8));
+            stored.push(vecs);
+
+            // The matrix only accepts its input dimension.
+            let other = random_vector(16, 9);
+            assert_eq!(
+                store.ingest_batch(&[&other], &[9], None).unwrap_err(),
+                RvfError::DimensionMismatch {
+                    expected: 32,
+                    got: 16
+                }
+            );
+            store.close().unwrap();
+        }
+        assert_eq!(stored[0], stored[1]);
+        assert_eq!(stored[0][0], projection.apply(&inputs[0], 8).unwrap());
+
+        // The matrix is restored on reopen, so later ingests project alike.
+        let mut store = RvfStore::open(&dir.path().join("proj_a.rvf")).unwrap();
+        assert_eq!(store.options().auto_project, Some(projection.clone()));
+        store.ingest_batch(&[refs[0]], &[3], None).unwrap();
+        assert_eq!(store.vectors.get(3), Some(&stored[0][0][..]));
+    }
+
     #[test]
     fn compact_reclaims_space() {
         let dir = TempDir::new().unwrap();
@@ -2284,6 +3943,583 @@ mod tests {
         store.close().unwrap();
     }
 
+    #[test]
+    fn snapshot_ignores_later_ingest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.rvf");
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let vecs: Vec<Vec<f32>> = (0..5).map(|i| vec![i as f32, 0.0, 0.0, 0.0]).collect();
+        let vec_refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        store
+            .ingest_batch(&vec_refs, &[0, 1, 2, 3, 4], None)
+            .unwrap();
+
+        let snapshot = store.snapshot().unwrap();
+
+        let late = [10.0, 0.0, 0.0, 0.0];
+        store.ingest_batch(&[&late], &[10], None).unwrap();
+        store.delete(&[0]).unwrap();
+
+        let fresh = store.query(&late, 10, &QueryOptions::default()).unwrap();
+        assert_eq!(fresh[0].id, 10);
+        assert_eq!(fresh.len(), 5);
+
+        let pinned = snapshot.query(&late, 10, &QueryOptions::default()).unwrap();
+        let mut pinned_ids: Vec<u64> = pinned.iter().map(|r| r.id).collect();
+        pinned_ids.sort_unstable();
+        assert_eq!(pinned_ids, vec![0, 1, 2, 3, 4]);
+        assert!(snapshot.epoch() < store.epoch());
+    }
+
+    #[test]
+    fn checkpoint_reopens_at_marker() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("checkpoint.rvf");
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let v = [1.0, 2.0, 3.0, 4.0];
+        store.ingest_batch(&[&v], &[1], None).unwrap();
+        let checkpoint = store.checkpoint().unwrap();
+        store.ingest_batch(&[&v], &[2], None).unwrap();
+        store.close().unwrap();
+
+        let bytes = checkpoint.to_bytes();
+        let restored = Checkpoint::from_bytes(&bytes).unwrap();
+        let snapshot = RvfStore::open_checkpoint(&path, restored).unwrap();
+        assert_eq!(snapshot.checkpoint(), checkpoint);
+        let results = snapshot.query(&v, 10, &QueryOptions::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, 1);
+
+        let latest = RvfStore::open_readonly(&path).unwrap();
+        assert_eq!(
+            latest
+                .query(&v, 10, &QueryOptions::default())
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // An offset that is not a manifest is rejected.
+        let bogus = Checkpoint {
+            manifest_offset: checkpoint.manifest_offset + 1,
+            ..checkpoint
+        };
+        assert!(RvfStore::open_checkpoint(&path, bogus).is_err());
+    }
+
+    #[test]
+    fn compact_reports_monotonic_progress() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("progress.rvf");
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let vecs: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, 0.0, 0.0, 0.0]).collect();
+        let vec_refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..20).collect();
+        store.ingest_batch(&vec_refs, &ids, None).unwrap();
+        store
+            .embed_wasm(0, 0, 0, b"\0asm\x01\0\0\0", 0, 0, 0)
+            .unwrap();
+        store.delete(&[1, 3, 5]).unwrap();
+
+        let mut events = Vec::new();
+        let result = store.compact_with_progress(|p| events.push(p)).unwrap();
+
+        assert!(events.len() >= 3, "start, VEC, preserved WASM, manifest");
+        assert_eq!(events[0].segments_processed, 0);
+        for pair in events.windows(2) {
+            assert_eq!(pair[1].segments_processed, pair[0].segments_processed + 1);
+            assert!(pair[1].bytes_reclaimed >= pair[0].bytes_reclaimed);
+            assert!(pair[1].bytes_remaining <= pair[0].bytes_remaining);
+        }
+        let last = events.last().unwrap();
+        assert!(last.is_done());
+        assert_eq!(events.len() as u32, last.segments_total + 1);
+        assert_eq!(last.fraction(), 1.0);
+        assert_eq!(last.bytes_remaining, 0);
+        assert_eq!(last.bytes_reclaimed, result.bytes_reclaimed);
+        assert_eq!(result.bytes_reclaimed, 3 * 4 * 4);
+
+        // With nothing live left, the final event still carries the total.
+        store.delete(&(0..20).collect::<Vec<_>>()).unwrap();
+        let mut last = None;
+        let result = store.compact_with_progress(|p| last = Some(p)).unwrap();
+        let last = last.unwrap();
+        assert!(last.is_done());
+        assert_eq!(last.bytes_reclaimed, result.bytes_reclaimed);
+    }
+
+    #[test]
+    fn multi_vector_records_query_by_sub_field() {
+        const TITLE: u8 = 0;
+        const BODY: u8 = 1;
+        const SUMMARY: u8 = 2;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("multi.rvf");
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        store
+            .ingest_batch(&[&[0.0, 0.0, 9.0, 9.0]], &[500], None)
+            .unwrap();
+
+        let records: Vec<MultiVectorRecord> = (0..3)
+            .map(|i| {
+                let x = i as f32;
+                MultiVectorRecord::new(i)
+                    .with_field(TITLE, vec![x, 0.0, 0.0, 0.0])
+                    .with_field(BODY, vec![0.0, x, 0.0, 0.0])
+                    .with_field(SUMMARY, vec![0.0, 0.0, x, 0.0])
+            })
+            .collect();
+        let result = store.ingest_multi_vector(&records, None).unwrap();
+        assert_eq!(result.accepted, 9);
+
+        // A sub-field query sees only that sub-field and reports entry ids.
+        let by_body = QueryOptions {
+            sub_field: Some(BODY),
+            ..Default::default()
+        };
+        let hits = store.query(&[0.0, 2.0, 0.0, 0.0], 10, &by_body).unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].id, 2);
+        assert_eq!(hits[0].distance, 0.0);
+
+        // Plain queries ignore sub-vectors.
+        let plain = store
+            .query(&[0.0, 2.0, 0.0, 0.0], 10, &QueryOptions::default())
+            .unwrap();
+        assert_eq!(plain.iter().map(|r| r.id).collect::<Vec<_>>(), vec![500]);
+
+        // The matched entry comes back with every sub-vector, also after reopen.
+        assert_eq!(
+            store.get_multi_vector(hits[0].id).as_ref(),
+            Some(&records[2])
+        );
+        store.close().unwrap();
+        let mut store = RvfStore::open(&path).unwrap();
+        let fetched = store.get_multi_vector(1).unwrap();
+        assert_eq!(fetched.field(SUMMARY), Some(&[0.0, 0.0, 1.0, 0.0][..]));
+        assert!(store.get_multi_vector(500).is_none());
+
+        // Deleting the entry removes all of its sub-vectors.
+        assert_eq!(store.delete(&[2]).unwrap().deleted, 1);
+        assert!(store.get_multi_vector(2).is_none());
+        let hits = store.query(&[0.0, 2.0, 0.0, 0.0], 10, &by_body).unwrap();
+        assert!(hits.iter().all(|r| r.id != 2));
+        store.compact().unwrap();
+        assert!(store.get_multi_vector(2).is_none());
+        assert_eq!(store.query(&[0.0; 4], 10, &by_body).unwrap().len(), 2);
+
+        let duplicate = MultiVectorRecord::new(7)
+            .with_field(TITLE, vec![0.0; 4])
+            .with_field(TITLE, vec![1.0; 4]);
+        assert_eq!(
+            store.ingest_multi_vector(&[duplicate], None).unwrap_err(),
+            RvfError::IdConflict {
+                id: sub_vector_id(7, TITLE),
+                reason: "duplicate sub-field tag",
+            }
+        );
+
+        // Plain ids and entry ids live in one id space.
+        let flagged = sub_vector_id(0, TITLE);
+        let conflict = |e: RvfError| match e {
+            RvfError::IdConflict { id, .. } => id,
+            other => panic!("expected IdConflict, got {other:?}"),
+        };
+        let err = store
+            .ingest_batch(&[&[0.0; 4]], &[flagged], None)
+            .unwrap_err();
+        assert_eq!(conflict(err), flagged);
+        let err = store.ingest_batch(&[&[0.0; 4]], &[1], None).unwrap_err();
+        assert_eq!(conflict(err), 1);
+        let reused = MultiVectorRecord::new(500).with_field(TITLE, vec![0.0; 4]);
+        let err = store.ingest_multi_vector(&[reused], None).unwrap_err();
+        assert_eq!(conflict(err), 500);
+        assert_eq!(store.get_multi_vector(1), Some(fetched));
+    }
+
+    #[test]
+    fn open_rolls_back_torn_append() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("torn.rvf");
+        let log_path = crate::intent_log::intent_path_for(&path);
+
+        let options = RvfOptions {
+            dimension: 128,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+
+        let first: Vec<Vec<f32>> = (0..10).map(|i| random_vector(128, i)).collect();
+        let refs: Vec<&[f32]> = first.iter().map(|v| v.as_slice()).collect();
+        let mut store = RvfStore::create(&path, options).unwrap();
+        store
+            .ingest_batch(&refs, &(0..10).collect::<Vec<_>>(), None)
+            .unwrap();
+        store.close().unwrap();
+        assert!(!log_path.exists());
+        let committed_len = fs::metadata(&path).unwrap().len();
+
+        // A second batch large enough that its torn tail would hide the
+        // previous manifest from the 64 KB tail scan.
+        let second: Vec<Vec<f32>> = (10..210).map(|i| random_vector(128, i)).collect();
+        let refs: Vec<&[f32]> = second.iter().map(|v| v.as_slice()).collect();
+        let mut store = RvfStore::open(&path).unwrap();
+        store
+            .ingest_batch(&refs, &(10..210).collect::<Vec<_>>(), None)
+            .unwrap();
+        drop(store);
+
+        // Simulate dying mid-append: drop the commit record and cut the
+        // file inside the new VEC_SEG.
+        let log = fs::read(&log_path).unwrap();
+        fs::write(&log_path, &log[..24]).unwrap();
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(committed_len + 80_000).unwrap();
+        drop(file);
+
+        let mut store = RvfStore::open(&path).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), committed_len);
+        assert!(!log_path.exists());
+        assert_eq!(store.status().total_vectors, 10);
+        let results = store.query(&first[3], 1, &QueryOptions::default()).unwrap();
+        assert_eq!(results[0].id, 3);
+
+        // The store keeps working from the recovered state.
+        store
+            .ingest_batch(&refs, &(10..210).collect::<Vec<_>>(), None)
+            .unwrap();
+        store.close().unwrap();
+        let store = RvfStore::open_readonly(&path).unwrap();
+        assert_eq!(store.status().total_vectors, 210);
+    }
+
+    #[test]
+    fn open_keeps_committed_appends() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("committed.rvf");
+        let log_path = crate::intent_log::intent_path_for(&path);
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+
+        let vecs: Vec<Vec<f32>> = (0..8).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let mut store = RvfStore::create(&path, options).unwrap();
+        store
+            .ingest_batch(&refs, &(0..8).collect::<Vec<_>>(), None)
+            .unwrap();
+        store.delete(&[2]).unwrap();
+        let len = fs::metadata(&path).unwrap().len();
+
+        // Dropped without close: the log holds an intent and its commit.
+        drop(store);
+        assert!(log_path.exists());
+
+        let store = RvfStore::open(&path).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), len);
+        assert_eq!(store.status().total_vectors, 7);
+        store.close().unwrap();
+        assert!(!log_path.exists());
+    }
+
+    #[test]
+    fn follower_replays_leader_stream() {
+        let dir = TempDir::new().unwrap();
+        let options = RvfOptions {
+            dimension: 8,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut leader = RvfStore::create(&dir.path().join("leader.rvf"), options.clone()).unwrap();
+        let mut follower =
+            RvfStore::create(&dir.path().join("follower.rvf"), options.clone()).unwrap();
+
+        let ingest = |store: &mut RvfStore, ids: std::ops::Range<u64>| {
+            let vecs: Vec<Vec<f32>> = ids.clone().map(|i| random_vector(8, i)).collect();
+            let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+            store
+                .ingest_batch(&refs, &ids.collect::<Vec<_>>(), None)
+                .unwrap();
+        };
+        let same_results = |a: &RvfStore, b: &RvfStore| {
+            for seed in 100..105 {
+                let q = random_vector(8, seed);
+                let ra = a.query(&q, 10, &QueryOptions::default()).unwrap();
+                let rb = b.query(&q, 10, &QueryOptions::default()).unwrap();
+                assert_eq!(ra, rb);
+            }
+            assert_eq!(a.status().total_vectors, b.status().total_vectors);
+        };
+
+        ingest(&mut leader, 0..20);
+        leader.delete(&[3, 7]).unwrap();
+        ingest(&mut leader, 20..30);
+
+        let mut last = None;
+        for record in leader.replication_stream(None).unwrap() {
+            last = Some(record.lsn);
+            assert!(follower.apply_replication(record).unwrap());
+        }
+        assert_eq!(last, Some(leader.epoch() as Lsn));
+        assert_eq!(follower.replicated_lsn(), last.unwrap());
+        same_results(&leader, &follower);
+
+        // The applied LSN is kept in the manifest, so a reopened follower
+        // resumes where it stopped.
+        follower.close().unwrap();
+        let mut follower = RvfStore::open(&dir.path().join("follower.rvf")).unwrap();
+        assert_eq!(follower.replicated_lsn(), last.unwrap());
+
+        // Compaction drops the history; the follower catches up from a
+        // snapshot followed by the operations appended after it.
+        leader.delete(&[0, 25]).unwrap();
+        leader.compact().unwrap();
+        leader.delete(&[11]).unwrap();
+        ingest(&mut leader, 30..35);
+
+        let records: Vec<_> = leader.replication_stream(last).unwrap().collect();
+        assert!(records[0].is_snapshot());
+        assert_eq!(records.len(), 3);
+        for record in records {
+            assert!(follower.apply_replication(record).unwrap());
+        }
+        same_results(&leader, &follower);
+        assert_eq!(follower.status().total_vectors, 30);
+    }
+
+    #[test]
+    fn apply_replication_handles_duplicates_and_gaps() {
+        let dir = TempDir::new().unwrap();
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut leader = RvfStore::create(&dir.path().join("leader.rvf"), options.clone()).unwrap();
+        for i in 0..3u64 {
+            let v = random_vector(4, i);
+            leader.ingest_batch(&[v.as_slice()], &[i], None).unwrap();
+        }
+        let records: Vec<_> = leader.replication_stream(None).unwrap().collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].prev_lsn, records[0].lsn);
+
+        let mut follower =
+            RvfStore::create(&dir.path().join("follower.rvf"), options.clone()).unwrap();
+
+        // Skipping a record breaks the chain.
+        assert_eq!(
+            follower.apply_replication(records[1].clone()),
+            Err(err(ErrorCode::LineageBroken))
+        );
+        assert_eq!(follower.replicated_lsn(), 0);
+
+        assert!(follower.apply_replication(records[0].clone()).unwrap());
+        assert!(follower.apply_replication(records[1].clone()).unwrap());
+        let epoch = follower.epoch();
+
+        // Redelivered and out-of-order older records are ignored.
+        assert!(!follower.apply_replication(records[1].clone()).unwrap());
+        assert!(!follower.apply_replication(records[0].clone()).unwrap());
+        assert_eq!(follower.epoch(), epoch);
+        assert_eq!(follower.status().total_vectors, 2);
+
+        assert!(follower.apply_replication(records[2].clone()).unwrap());
+        assert_eq!(follower.status().total_vectors, 3);
+        assert_eq!(
+            leader
+                .replication_stream(Some(records[2].lsn))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn mmap_open_matches_buffered_open() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mmap.rvf");
+
+        let options = RvfOptions {
+            dimension: 8,
+            metric: DistanceMetric::Cosine,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        for batch in 0..3u64 {
+            let vecs: Vec<Vec<f32>> = (0..50).map(|i| random_vector(8, batch * 50 + i)).collect();
+            let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+            let ids: Vec<u64> = (batch * 50..batch * 50 + 50).collect();
+            store.ingest_batch(&refs, &ids, None).unwrap();
+        }
+        store.delete(&[4, 60, 149]).unwrap();
+        store.close().unwrap();
+
+        let buffered = RvfStore::open_readonly(&path).unwrap();
+        let mapped = RvfStore::open_mmap(&path, MmapOptions::default()).unwrap();
+        assert_eq!(mapped.status().total_vectors, 147);
+        assert_eq!(mapped.epoch(), buffered.epoch());
+        for seed in 1000..1010 {
+            let q = random_vector(8, seed);
+            assert_eq!(
+                mapped.query(&q, 10, &QueryOptions::default()).unwrap(),
+                buffered.query(&q, 10, &QueryOptions::default()).unwrap()
+            );
+        }
+        drop(buffered);
+        drop(mapped);
+
+        // A writable mmap open takes the lock and accepts mutations.
+        let mut writable = RvfStore::open_mmap(
+            &path,
+            MmapOptions {
+                read_only: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(RvfStore::open(&path).is_err());
+
+        // The caller's lock timeout is honoured while waiting on the holder.
+        let timeout = Duration::from_millis(100);
+        let start = Instant::now();
+        let waited = RvfStore::open_mmap(
+            &path,
+            MmapOptions {
+                read_only: false,
+                lock: LockOptions {
+                    acquire_timeout: timeout,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        assert!(waited.is_err());
+        assert!(start.elapsed() >= timeout);
+
+        writable.delete(&[0]).unwrap();
+        assert_eq!(writable.status().total_vectors, 146);
+        writable.close().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn recovery_waits_for_mapped_readers() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mapped.rvf");
+        let log_path = crate::intent_log::intent_path_for(&path);
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let vecs: Vec<Vec<f32>> = (0..8).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let mut store = RvfStore::create(&path, options).unwrap();
+        store
+            .ingest_batch(&refs, &(0..8).collect::<Vec<_>>(), None)
+            .unwrap();
+        store.close().unwrap();
+        let committed_len = fs::metadata(&path).unwrap().len();
+
+        // Leave a torn append behind for the next writer to roll back.
+        let mut store = RvfStore::open(&path).unwrap();
+        store
+            .ingest_batch(&refs, &(8..16).collect::<Vec<_>>(), None)
+            .unwrap();
+        drop(store);
+        let log = fs::read(&log_path).unwrap();
+        fs::write(&log_path, &log[..24]).unwrap();
+        let torn_len = fs::metadata(&path).unwrap().len();
+        assert!(torn_len > committed_len);
+
+        // Hold the lock an mmap boot takes while the writer recovers.
+        let reader = fs::File::open(&path).unwrap();
+        let mapped = FileLock::shared(&reader).unwrap();
+        let writer_path = path.clone();
+        let writer = std::thread::spawn(move || RvfStore::open(&writer_path).unwrap());
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(fs::metadata(&path).unwrap().len(), torn_len);
+
+        drop(mapped);
+        let store = writer.join().unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), committed_len);
+        assert_eq!(store.status().total_vectors, 8);
+    }
+
+    #[test]
+    fn open_rejects_manifest_pointing_past_eof() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("truncated.rvf");
+
+        let options = RvfOptions {
+            dimension: 16,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let vecs: Vec<Vec<f32>> = (0..500).map(|i| random_vector(16, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        store
+            .ingest_batch(&refs, &(0..500).collect::<Vec<_>>(), None)
+            .unwrap();
+        let vec_offset = store
+            .segment_dir
+            .iter()
+            .find(|e| e.3 == SegmentType::Vec as u8)
+            .unwrap()
+            .1 as usize;
+        store.close().unwrap();
+
+        // Cut the vector segment short but keep the latest manifest, so the
+        // manifest still points at data past the end of the file.
+        let bytes = fs::read(&path).unwrap();
+        let magic = SEGMENT_MAGIC.to_le_bytes();
+        let manifest_offset = (0..bytes.len() - SEGMENT_HEADER_SIZE)
+            .rev()
+            .find(|&i| bytes[i..i + 4] == magic && bytes[i + 5] == SegmentType::Manifest as u8)
+            .unwrap();
+        let mut truncated = bytes[..vec_offset + SEGMENT_HEADER_SIZE + 64].to_vec();
+        truncated.extend_from_slice(&bytes[manifest_offset..]);
+        fs::write(&path, &truncated).unwrap();
+
+        assert_eq!(
+            RvfStore::open_readonly(&path).err(),
+            Some(err(ErrorCode::TruncatedSegment))
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            RvfStore::open_mmap(&path, MmapOptions::default()).err(),
+            Some(err(ErrorCode::TruncatedSegment))
+        );
+    }
+
     #[test]
     fn lock_prevents_two_writers() {
         let dir = TempDir::new().unwrap();
@@ -2301,6 +4537,38 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn reclaimed_lock_rejects_revived_writer() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("reclaimed.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+
+        let mut stalled = RvfStore::create(&path, options).unwrap();
+        let v = random_vector(4, 1);
+        stalled.ingest_batch(&[v.as_slice()], &[1], None).unwrap();
+
+        // Another writer reclaims the lock while the first is presumed dead.
+        std::fs::remove_file(crate::locking::lock_path_for(&path)).unwrap();
+        let mut current = RvfStore::open(&path).unwrap();
+
+        let v2 = random_vector(4, 2);
+        let err = stalled
+            .ingest_batch(&[v2.as_slice()], &[2], None)
+            .unwrap_err();
+        assert_eq!(err, RvfError::Code(ErrorCode::GenerationStale));
+        drop(stalled);
+
+        // The revived writer neither appended nor released the new lock.
+        current.ingest_batch(&[v2.as_slice()], &[2], None).unwrap();
+        assert_eq!(current.status().total_vectors, 2);
+        assert!(RvfStore::open(&path).is_err());
+    }
+
     #[test]
     fn readonly_open() {
         let dir = TempDir::new().unwrap();
@@ -2375,6 +4643,337 @@ mod tests {
         store.close().unwrap();
     }
 
+    #[test]
+    fn delete_by_filter_matches_brute_force() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("del_where.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+
+        let vecs: Vec<Vec<f32>> = (0..60).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..60).collect();
+        // One metadata entry per vector: tenant 0..5 on field 0.
+        let metadata: Vec<MetadataEntry> = ids
+            .iter()
+            .map(|&id| MetadataEntry {
+                field_id: 0,
+                value: MetadataValue::U64(id % 5),
+            })
+            .collect();
+        store.ingest_batch(&refs, &ids, Some(&metadata)).unwrap();
+
+        let predicate = FilterExpr::Or(vec![
+            FilterExpr::Eq(0, FilterValue::U64(1)),
+            FilterExpr::Eq(0, FilterValue::U64(3)),
+        ]);
+        let expected: Vec<u64> = ids
+            .iter()
+            .copied()
+            .filter(|&id| filter::evaluate(&predicate, id, &store.metadata))
+            .collect();
+        assert_eq!(expected.len(), 24);
+
+        let result = store.delete_by_filter(&predicate).unwrap();
+        assert_eq!(result.deleted, expected.len() as u64);
+        for &id in &ids {
+            assert_eq!(
+                store.deletion_bitmap.is_deleted(id),
+                expected.contains(&id),
+                "id {id}"
+            );
+        }
+
+        // Nothing left to match.
+        assert_eq!(store.delete_by_filter(&predicate).unwrap().deleted, 0);
+        assert_eq!(store.status().total_vectors, 36);
+    }
+
+    #[test]
+    fn delete_by_filter_skips_vectors_outside_membership() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("del_where_branch.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+
+        let vecs: Vec<Vec<f32>> = (0..10).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..10).collect();
+        let metadata: Vec<MetadataEntry> = ids
+            .iter()
+            .map(|_| MetadataEntry {
+                field_id: 0,
+                value: MetadataValue::U64(7),
+            })
+            .collect();
+        store.ingest_batch(&refs, &ids, Some(&metadata)).unwrap();
+
+        // Only even IDs are visible through the membership filter.
+        let mut membership = MembershipFilter::new_include(10);
+        for id in (0..10).step_by(2) {
+            membership.add(id);
+        }
+        store.membership_filter = Some(membership);
+
+        let result = store
+            .delete_by_filter(&FilterExpr::Eq(0, FilterValue::U64(7)))
+            .unwrap();
+        assert_eq!(result.deleted, 5);
+        for id in 0..10 {
+            assert_eq!(store.deletion_bitmap.is_deleted(id), id % 2 == 0);
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_scan_matches_sequential() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("parallel_scan.rvf");
+
+        let options = RvfOptions {
+            dimension: 8,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        // Eight VEC segments; every vector appears twice so distances tie.
+        for batch in 0..8u64 {
+            let vecs: Vec<Vec<f32>> = (0..50)
+                .map(|i| random_vector(8, (batch * 50 + i) / 2))
+                .collect();
+            let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+            let ids: Vec<u64> = (batch * 50..batch * 50 + 50).collect();
+            store.ingest_batch(&refs, &ids, None).unwrap();
+        }
+        store.delete(&[3, 77, 201]).unwrap();
+        assert_eq!(
+            store.parallel_scan_partitions(&QueryOptions::default()),
+            Some(8)
+        );
+
+        let sequential_opts = QueryOptions {
+            deadline: Some(std::time::Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        assert_eq!(store.parallel_scan_partitions(&sequential_opts), None);
+        for seed in 0..5 {
+            let query = random_vector(8, 1000 + seed);
+            let parallel = store.query(&query, 60, &QueryOptions::default()).unwrap();
+            let sequential = store.query(&query, 60, &sequential_opts).unwrap();
+            let bits = |rs: &[SearchResult]| -> Vec<(u64, u32)> {
+                rs.iter().map(|r| (r.id, r.distance.to_bits())).collect()
+            };
+            assert_eq!(parallel.len(), 60);
+            assert_eq!(bits(&parallel), bits(&sequential));
+        }
+    }
+
+    #[test]
+    fn tombstone_ttl_defers_reclamation() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ttl.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let vecs: Vec<Vec<f32>> = (0..4).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        store.ingest_batch(&refs, &[0, 1, 2, 3], None).unwrap();
+
+        let short = store
+            .delete_with_ttl(&[1], Duration::from_millis(1))
+            .unwrap();
+        assert!(short.expires_at_ms.is_some());
+        let long = store
+            .delete_with_ttl(&[2], Duration::from_secs(3600))
+            .unwrap();
+        assert!(long.expires_at_ms > short.expires_at_ms);
+        assert_eq!(store.delete(&[3]).unwrap().expires_at_ms, None);
+        std::thread::sleep(Duration::from_millis(20));
+
+        let result = store.compact().unwrap();
+        assert_eq!(result.segments_compacted, 2);
+        assert!(store.vectors.get(1).is_none());
+        assert!(store.vectors.get(3).is_none());
+        // Still within its TTL: hidden but kept.
+        assert!(store.vectors.get(2).is_some());
+        assert!(store.deletion_bitmap.is_deleted(2));
+        let hits = store.query(&vecs[2], 4, &QueryOptions::default()).unwrap();
+        assert_eq!(hits.iter().map(|r| r.id).collect::<Vec<_>>(), vec![0]);
+        store.close().unwrap();
+
+        // The expiry survives compaction and reopen.
+        let store = RvfStore::open(&path).unwrap();
+        assert!(store.deletion_bitmap.is_deleted(2));
+        assert_eq!(store.deletion_bitmap.expiry(2), long.expires_at_ms);
+        assert_eq!(store.status().total_vectors, 1);
+    }
+
+    #[test]
+    fn shared_cluster_keeps_tombstoned_vector() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("refcount.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let vecs: Vec<Vec<f32>> = (0..4).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        store.ingest_batch(&refs, &[0, 1, 2, 3], None).unwrap();
+
+        // Vector 2's cluster is still referenced by another snapshot.
+        store.write_refcounts(&[1, 1, 2, 1]).unwrap();
+        store.delete(&[1, 2]).unwrap();
+        let result = store.compact().unwrap();
+        assert_eq!(result.segments_compacted, 1);
+        assert!(store.vectors.get(1).is_none());
+        assert!(store.vectors.get(2).is_some());
+        assert!(store.deletion_bitmap.is_deleted(2));
+
+        // Once the reference is dropped the tombstone is reclaimed.
+        store.write_refcounts(&[1, 1, 1, 1]).unwrap();
+        assert_eq!(store.compact().unwrap().segments_compacted, 1);
+        assert!(store.vectors.get(2).is_none());
+        assert_eq!(store.deletion_bitmap.count(), 0);
+    }
+
+    #[test]
+    fn metadata_schema_validates_ingest() {
+        use crate::options::{MetadataKind, MetadataSchema};
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("schema.rvf");
+
+        let schema = MetadataSchema::new()
+            .with_field(0, MetadataKind::String)
+            .with_field(1, MetadataKind::U64);
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            metadata_schema: Some(schema.clone()),
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let v = [1.0, 0.0, 0.0, 0.0];
+        let entry = |field_id, value| MetadataEntry { field_id, value };
+
+        // Conforming, plus an undeclared field allowed by a lenient schema.
+        let ok = [
+            entry(0, MetadataValue::String("books".into())),
+            entry(1, MetadataValue::U64(12)),
+            entry(9, MetadataValue::F64(0.5)),
+        ];
+        assert_eq!(
+            store.ingest_batch(&[&v], &[1], Some(&ok)).unwrap().accepted,
+            1
+        );
+
+        let wrong_type = [
+            entry(0, MetadataValue::String("books".into())),
+            entry(1, MetadataValue::String("12".into())),
+        ];
+        assert_eq!(
+            store
+                .ingest_batch(&[&v], &[2], Some(&wrong_type))
+                .unwrap_err(),
+            RvfError::MetadataSchemaViolation {
+                field_id: 1,
+                reason: "expected u64",
+            }
+        );
+
+        // The schema is still enforced after a reopen.
+        store.close().unwrap();
+        let mut store = RvfStore::open(&path).unwrap();
+        assert_eq!(store.options().metadata_schema, Some(schema.clone()));
+        assert!(store.ingest_batch(&[&v], &[2], Some(&wrong_type)).is_err());
+
+        store.options.metadata_schema = Some(schema.strict(true));
+        assert_eq!(
+            store.ingest_batch(&[&v], &[3], Some(&ok)).unwrap_err(),
+            RvfError::MetadataSchemaViolation {
+                field_id: 9,
+                reason: "field not declared in strict schema",
+            }
+        );
+        assert_eq!(store.status().total_vectors, 1);
+    }
+
+    #[test]
+    fn membership_filter_persists_across_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("membership_persist.rvf");
+
+        let options = RvfOptions {
+            dimension: 4,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let vecs: Vec<Vec<f32>> = (0..100).map(|i| random_vector(4, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..100).collect();
+        store.ingest_batch(&refs, &ids, None).unwrap();
+
+        let build = || {
+            let mut filter = MembershipFilter::new_include(100);
+            for id in (0..100).filter(|id| id % 3 != 0) {
+                filter.add(id);
+            }
+            filter
+        };
+        store.membership_filter = Some(build());
+        store.compact().unwrap();
+        store.close().unwrap();
+
+        let mut store = RvfStore::open(&path).unwrap();
+        let fresh = build();
+        let reloaded = store.membership_filter().expect("filter reloaded");
+        assert_eq!(reloaded.member_count(), fresh.member_count());
+        for id in 0..120 {
+            assert_eq!(reloaded.contains(id), fresh.contains(id), "id {id}");
+        }
+
+        // The filter is rewritten with every manifest, so it survives
+        // writes that change the vectors, as do edits made through
+        // `membership_filter_mut`.
+        store.delete(&[5]).unwrap();
+        store.membership_filter_mut().unwrap().remove(4);
+        store.close().unwrap();
+        let mut store = RvfStore::open(&path).unwrap();
+        let reloaded = store.membership_filter().unwrap();
+        assert_eq!(reloaded.member_count(), fresh.member_count() - 1);
+        assert!(!reloaded.contains(3));
+        assert!(!reloaded.contains(4));
+        assert!(reloaded.contains(5));
+
+        // A filter that no longer matches the vectors fails the open
+        // instead of silently widening visibility.
+        store.membership_filter = None;
+        store.delete(&[7]).unwrap();
+        store.close().unwrap();
+        assert_eq!(
+            RvfStore::open(&path).err(),
+            Some(err(ErrorCode::MembershipInvalid))
+        );
+    }
+
     #[test]
     fn embed_extract_kernel_round_trip() {
         let dir = TempDir::new().unwrap();
@@ -2763,4 +5362,309 @@ mod tests {
 
         store.close().unwrap();
     }
+
+    fn reranker_store(dir: &TempDir) -> RvfStore {
+        let path = dir.path().join("rerank.rvf");
+        let options = RvfOptions {
+            dimension: 2,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+
+        // Vectors along the x axis at increasing distance from the origin.
+        let vecs: Vec<Vec<f32>> = (0..10).map(|i| vec![i as f32, 0.0]).collect();
+        let vec_refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..10).collect();
+        let metadata: Vec<MetadataEntry> = ids
+            .iter()
+            .map(|&id| MetadataEntry {
+                field_id: 0,
+                value: MetadataValue::U64(id * 10),
+            })
+            .collect();
+        store
+            .ingest_batch(&vec_refs, &ids, Some(&metadata))
+            .unwrap();
+        store
+    }
+
+    #[test]
+    fn query_rerank_hook_reorders_approximate_results() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("rerank_hook.rvf");
+        let options = RvfOptions {
+            dimension: 2,
+            metric: DistanceMetric::L2,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+
+        // Coarse stand-ins for the stored vectors: they tie on x, so the
+        // approximate pass orders them only by id.
+        let coarse: Vec<Vec<f32>> = (0..8).map(|i| vec![(i % 2) as f32, 0.0]).collect();
+        let refs: Vec<&[f32]> = coarse.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..8).collect();
+        store.ingest_batch(&refs, &ids, None).unwrap();
+
+        let approx = store
+            .query(&[0.0, 0.0], 3, &QueryOptions::default())
+            .unwrap();
+        let approx_ids: Vec<u64> = approx.iter().map(|r| r.id).collect();
+        assert_eq!(approx_ids, vec![0, 2, 4]);
+
+        // The exact reranker knows the full-precision vectors, in which a
+        // higher id is closer to the query.
+        let query = [0.0f32, 0.0];
+        let exact = move |id: u64| -> Vec<f32> { vec![(id % 2) as f32, 10.0 - id as f32] };
+        let opts = QueryOptions {
+            rerank_pool_size: 8,
+            rerank: Some(Reranker::new(move |r, stored| {
+                assert_eq!(stored[0], (r.id % 2) as f32);
+                let v = exact(r.id);
+                query.iter().zip(&v).map(|(a, b)| (a - b) * (a - b)).sum()
+            })),
+            ..Default::default()
+        };
+        let reranked = store.query(&query, 3, &opts).unwrap();
+        let reranked_ids: Vec<u64> = reranked.iter().map(|r| r.id).collect();
+        assert_eq!(reranked_ids, vec![7, 6, 5]);
+        assert!((reranked[0].distance - 10.0).abs() < f32::EPSILON);
+        assert!(reranked.windows(2).all(|w| w[0].distance <= w[1].distance));
+    }
+
+    #[test]
+    fn reranker_inverting_distance_reverses_ranking() {
+        let dir = TempDir::new().unwrap();
+        let store = reranker_store(&dir);
+        let opts = QueryOptions {
+            rerank_pool_size: 5,
+            ..Default::default()
+        };
+
+        let plain = store.query(&[0.0, 0.0], 5, &opts).unwrap();
+        let reranked = store
+            .search_with_reranker(&[0.0, 0.0], 5, &opts, |r, _| -r.distance)
+            .unwrap();
+
+        let plain_ids: Vec<u64> = plain.iter().map(|r| r.id).collect();
+        let mut reranked_ids: Vec<u64> = reranked.iter().map(|r| r.result.id).collect();
+        reranked_ids.reverse();
+        assert_eq!(plain_ids, reranked_ids);
+
+        // Original ANN distances are preserved.
+        for r in &reranked {
+            assert_eq!(r.result.distance, -r.score);
+            assert_eq!(r.result.distance, (r.result.id * r.result.id) as f32);
+        }
+    }
+
+    #[test]
+    fn reranker_pool_size_controls_candidates() {
+        let dir = TempDir::new().unwrap();
+        let store = reranker_store(&dir);
+        let seen = std::cell::RefCell::new(Vec::new());
+        let opts = QueryOptions {
+            rerank_pool_size: 6,
+            ..Default::default()
+        };
+
+        let results = store
+            .search_with_reranker(&[0.0, 0.0], 2, &opts, |r, meta| {
+                seen.borrow_mut().push(r.id);
+                assert_eq!(meta.len(), 1);
+                match meta[0].value {
+                    MetadataValue::U64(v) => -(v as f32),
+                    _ => panic!("unexpected metadata type"),
+                }
+            })
+            .unwrap();
+
+        let mut seen = seen.into_inner();
+        seen.sort_unstable();
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5]);
+        // Highest metadata value wins among the 6 nearest candidates.
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].result.id, 5);
+        assert_eq!(results[1].result.id, 4);
+
+        // A pool smaller than k is raised to k.
+        let small = QueryOptions {
+            rerank_pool_size: 1,
+            ..Default::default()
+        };
+        let results = store
+            .search_with_reranker(&[0.0, 0.0], 3, &small, |_, _| 0.0)
+            .unwrap();
+        assert_eq!(results.len(), 3);
+
+        // The scorer passed here replaces `options.rerank`; candidates are
+        // not rescored twice.
+        let hooked = QueryOptions {
+            rerank_pool_size: 4,
+            rerank: Some(Reranker::new(|_, _| unreachable!("options.rerank applied"))),
+            ..Default::default()
+        };
+        let results = store
+            .search_with_reranker(&[0.0, 0.0], 2, &hooked, |r, _| r.distance)
+            .unwrap();
+        let ids: Vec<u64> = results.iter().map(|r| r.result.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn explain_search_reports_plan() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("explain.rvf");
+        let options = RvfOptions {
+            dimension: 8,
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+
+        // Three ingest batches produce three VEC segments.
+        for batch in 0..3u64 {
+            let vecs: Vec<Vec<f32>> = (0..200)
+                .map(|i| random_vector(8, batch * 200 + i))
+                .collect();
+            let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+            let ids: Vec<u64> = (batch * 200..(batch + 1) * 200).collect();
+            let meta: Vec<MetadataEntry> = ids
+                .iter()
+                .map(|&id| MetadataEntry {
+                    field_id: 0,
+                    value: MetadataValue::U64(id % 4),
+                })
+                .collect();
+            store.ingest_batch(&refs, &ids, Some(&meta)).unwrap();
+        }
+        store.delete(&[0, 1, 2]).unwrap();
+
+        let query = random_vector(8, 9999);
+        let plan = store
+            .explain_search(&query, 10, &QueryOptions::default())
+            .unwrap();
+        assert_eq!(plan.segments.len(), 3);
+        assert_eq!(plan.live_vectors, 597);
+        assert_eq!(plan.candidates, 597);
+        assert!(plan.safety_net_likely);
+
+        // The candidates are exactly what the filtered scan scores.
+        let filtered = QueryOptions {
+            filter: Some(FilterExpr::Eq(0, FilterValue::U64(1))),
+            ..Default::default()
+        };
+        let plan = store.explain_search(&query, 10, &filtered).unwrap();
+        assert_eq!(plan.live_vectors, 597);
+        assert_eq!(plan.candidates, 149);
+        let all = store.query(&query, 1000, &filtered).unwrap();
+        assert_eq!(all.len() as u64, plan.candidates);
+
+        let latency = QueryOptions {
+            quality_preference: rvf_types::quality::QualityPreference::PreferLatency,
+            ..Default::default()
+        };
+        let plan = store.explain_search(&query, 10, &latency).unwrap();
+        assert!(!plan.safety_net_likely);
+
+        let envelope = store
+            .query_with_envelope(&query, 10, &QueryOptions::default())
+            .unwrap();
+        assert!(!envelope.evidence.layers_used.layer_a);
+
+        let options = QueryOptions::default();
+        assert!(store.explain_search(&[0.0; 3], 10, &options).is_err());
+    }
+
+    #[cfg(feature = "encryption")]
+    fn encrypted_store(dir: &TempDir, key: [u8; 32]) -> (std::path::PathBuf, Vec<Vec<f32>>) {
+        let path = dir.path().join("encrypted.rvf");
+        let options = RvfOptions {
+            dimension: 8,
+            encryption: Some(EncryptionConfig::from_key(key)),
+            ..Default::default()
+        };
+        let mut store = RvfStore::create(&path, options).unwrap();
+        let vecs: Vec<Vec<f32>> = (0..50).map(|i| random_vector(8, i)).collect();
+        let refs: Vec<&[f32]> = vecs.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (0..50).collect();
+        store.ingest_batch(&refs, &ids, None).unwrap();
+        store.close().unwrap();
+        (path, vecs)
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypted_store_round_trips_with_key() {
+        let dir = TempDir::new().unwrap();
+        let key = [0x42; 32];
+        let (path, vecs) = encrypted_store(&dir, key);
+
+        let mut store =
+            RvfStore::open_with_encryption(&path, EncryptionConfig::from_key(key)).unwrap();
+        let results = store.query(&vecs[7], 1, &QueryOptions::default()).unwrap();
+        assert_eq!(results[0].id, 7);
+        assert!(results[0].distance < f32::EPSILON);
+
+        // Compaction rewrites the VEC segment, still encrypted.
+        store.delete(&[0, 1]).unwrap();
+        store.compact().unwrap();
+        store.close().unwrap();
+        let store = RvfStore::open_with_encryption(&path, EncryptionConfig::from_key(key)).unwrap();
+        assert_eq!(store.status().total_vectors, 48);
+        store.close().unwrap();
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypted_store_rejects_wrong_or_missing_key() {
+        let dir = TempDir::new().unwrap();
+        let (path, _) = encrypted_store(&dir, [0x42; 32]);
+
+        let wrong = RvfStore::open_with_encryption(&path, EncryptionConfig::from_key([0x43; 32]));
+        assert!(matches!(
+            wrong,
+            Err(RvfError::Code(ErrorCode::DecryptFailed))
+        ));
+        assert!(matches!(
+            RvfStore::open_readonly(&path),
+            Err(RvfError::Code(ErrorCode::DecryptFailed))
+        ));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypted_segment_headers_readable_without_key() {
+        use rvf_types::SegmentFlags;
+
+        let dir = TempDir::new().unwrap();
+        let (path, vecs) = encrypted_store(&dir, [0x42; 32]);
+
+        let file = File::open(&path).unwrap();
+        let mut reader = BufReader::new(&file);
+        let manifest = read_path::find_latest_manifest(&mut reader)
+            .unwrap()
+            .unwrap();
+        let vec_entry = manifest
+            .segment_dir
+            .iter()
+            .find(|e| e.seg_type == SegmentType::Vec as u8)
+            .unwrap();
+
+        let (header, payload) =
+            read_path::read_segment_payload(&mut reader, vec_entry.offset).unwrap();
+        assert_eq!(header.seg_type, SegmentType::Vec as u8);
+        assert_ne!(header.flags & SegmentFlags::ENCRYPTED, 0);
+        assert_eq!(header.payload_length, vec_entry.payload_length);
+        assert_eq!(
+            payload.len(),
+            2 + 4 + 50 * (8 + 8 * 4) + encryption::GCM_TAG_SIZE
+        );
+
+        // The plaintext vector bytes do not appear in the ciphertext.
+        let needle: Vec<u8> = vecs[3].iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert!(!payload
+            .windows(needle.len())
+            .any(|w| w == needle.as_slice()));
+    }
 }
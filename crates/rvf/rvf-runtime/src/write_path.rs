@@ -6,19 +6,41 @@
 //! 3. Write segment header + payload, fsync
 //! 4. Build new MANIFEST_SEG, fsync (two-fsync protocol)
 
+use crate::encryption::{self, EncryptionConfig};
 use rvf_types::{SegmentHeader, SegmentType, SEGMENT_HEADER_SIZE};
 use std::io::{self, Seek, Write};
 
+/// Marker (0x524C534E, "RLSN") before the replicated LSN in a manifest.
+pub(crate) const MANIFEST_LSN_MARKER: u32 = 0x524C_534E;
+
 /// Segment writer that handles the append-only write protocol.
 pub(crate) struct SegmentWriter {
     /// Next segment ID to assign (monotonic counter).
     next_seg_id: u64,
+    /// Key used to encrypt data-bearing segment payloads, if any.
+    encryption: Option<EncryptionConfig>,
 }
 
 impl SegmentWriter {
     pub(crate) fn new(starting_id: u64) -> Self {
         Self {
             next_seg_id: starting_id,
+            encryption: None,
+        }
+    }
+
+    /// Encrypt VEC/INDEX/META payloads with the given key.
+    pub(crate) fn with_encryption(mut self, encryption: Option<EncryptionConfig>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Bytes added to a payload of `seg_type` on disk (the GCM tag, if encrypted).
+    pub(crate) fn payload_overhead(&self, seg_type: u8) -> u64 {
+        if self.encryption.is_some() && encryption::is_encrypted_type(seg_type) {
+            encryption::GCM_TAG_SIZE as u64
+        } else {
+            0
         }
     }
 
@@ -65,32 +87,42 @@ impl SegmentWriter {
         Ok((seg_id, offset))
     }
 
-    /// Write a JOURNAL_SEG with tombstone entries for deleted vector IDs.
+    /// Write a JOURNAL_SEG with tombstones, each with an optional expiry
+    /// (Unix milliseconds).
+    ///
+    /// An expiry is recorded as a TOMBSTONE_EXPIRY (0x02) entry directly
+    /// after the DELETE_VECTOR (0x01) entry it applies to; readers that
+    /// only know DELETE_VECTOR skip it.
     pub(crate) fn write_journal_seg<W: Write + Seek>(
         &mut self,
         writer: &mut W,
-        deleted_ids: &[u64],
+        tombstones: &[(u64, Option<u64>)],
         epoch: u32,
     ) -> io::Result<(u64, u64)> {
         let seg_id = self.alloc_seg_id();
 
         // Journal header (simplified): entry_count(u32) + epoch(u32) + prev_seg_id(u64)
-        // Then entries: each is entry_type(u8) + pad(u8) + len(u16) + vector_id(u64)
-        let entry_count = deleted_ids.len() as u32;
-        let payload_size = 16 + (deleted_ids.len() * 12); // header + entries
+        // Then entries: each is entry_type(u8) + pad(u8) + len(u16) + value(u64)
+        let entry_count = journal_entry_count(tombstones);
+        let payload_size = 16 + (entry_count * 12); // header + entries
         let mut payload = Vec::with_capacity(payload_size);
 
         // Journal header.
-        payload.extend_from_slice(&entry_count.to_le_bytes());
+        payload.extend_from_slice(&(entry_count as u32).to_le_bytes());
         payload.extend_from_slice(&epoch.to_le_bytes());
         payload.extend_from_slice(&0u64.to_le_bytes()); // prev_journal_seg_id
 
-        // Entries: DELETE_VECTOR (type 0x01).
-        for &vid in deleted_ids {
+        for &(vid, expires_at_ms) in tombstones {
             payload.push(0x01); // DELETE_VECTOR
             payload.push(0x00); // reserved
             payload.extend_from_slice(&8u16.to_le_bytes()); // entry_length
             payload.extend_from_slice(&vid.to_le_bytes());
+            if let Some(at) = expires_at_ms {
+                payload.push(0x02); // TOMBSTONE_EXPIRY
+                payload.push(0x00);
+                payload.extend_from_slice(&8u16.to_le_bytes());
+                payload.extend_from_slice(&at.to_le_bytes());
+            }
         }
 
         let offset = self.write_segment(writer, SegmentType::Journal as u8, seg_id, &payload)?;
@@ -117,6 +149,7 @@ impl SegmentWriter {
     /// - segment directory entries (seg_id, offset, length, type)
     /// - deletion bitmap (vector IDs as simple packed u64 array)
     /// - file identity (68 bytes, appended for lineage provenance)
+    /// - replicated LSN (12 bytes, when the store follows a leader)
     #[allow(clippy::too_many_arguments, dead_code)]
     pub(crate) fn write_manifest_seg<W: Write + Seek>(
         &mut self,
@@ -137,10 +170,14 @@ impl SegmentWriter {
             segment_dir,
             deleted_ids,
             None,
+            0,
         )
     }
 
     /// Write a MANIFEST_SEG with optional FileIdentity appended.
+    ///
+    /// A non-zero `replicated_lsn` is appended after the identity so a
+    /// follower resumes from it after a reopen.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn write_manifest_seg_with_identity<W: Write + Seek>(
         &mut self,
@@ -152,6 +189,7 @@ impl SegmentWriter {
         segment_dir: &[(u64, u64, u64, u8)],
         deleted_ids: &[u64],
         file_identity: Option<&rvf_types::FileIdentity>,
+        replicated_lsn: u64,
     ) -> io::Result<(u64, u64)> {
         let seg_id = self.alloc_seg_id();
 
@@ -161,7 +199,8 @@ impl SegmentWriter {
         let payload_size = 4 + 2 + 8 + 4 + 1 + 3 // header fields
             + (segment_dir.len() * (8 + 8 + 8 + 1)) // directory
             + 4 + (deleted_ids.len() * 8) // deletion bitmap
-            + if file_identity.is_some() { 4 + 68 } else { 0 }; // lineage marker + identity
+            + if file_identity.is_some() { 4 + 68 } else { 0 } // lineage marker + identity
+            + if replicated_lsn != 0 { 4 + 8 } else { 0 }; // LSN marker + LSN
 
         let mut payload = Vec::with_capacity(payload_size);
 
@@ -194,6 +233,12 @@ impl SegmentWriter {
             payload.extend_from_slice(&fi.to_bytes());
         }
 
+        // Replicated LSN (optional, backward-compatible trailer).
+        if replicated_lsn != 0 {
+            payload.extend_from_slice(&MANIFEST_LSN_MARKER.to_le_bytes());
+            payload.extend_from_slice(&replicated_lsn.to_le_bytes());
+        }
+
         let offset = self.write_segment(writer, SegmentType::Manifest as u8, seg_id, &payload)?;
         Ok((seg_id, offset))
     }
@@ -369,6 +414,48 @@ impl SegmentWriter {
         Ok((seg_id, offset))
     }
 
+    /// Write a REFCOUNT_SEG: `refcount_header_bytes` (32) + refcount array.
+    ///
+    /// Returns the segment ID and byte offset where it was written.
+    pub(crate) fn write_refcount_seg<W: Write + Seek>(
+        &mut self,
+        writer: &mut W,
+        refcount_header_bytes: &[u8; 32],
+        array: &[u8],
+    ) -> io::Result<(u64, u64)> {
+        let seg_id = self.alloc_seg_id();
+        let mut payload = Vec::with_capacity(32 + array.len());
+        payload.extend_from_slice(refcount_header_bytes);
+        payload.extend_from_slice(array);
+        let offset = self.write_segment(writer, SegmentType::Refcount as u8, seg_id, &payload)?;
+        Ok((seg_id, offset))
+    }
+
+    /// Write a MEMBERSHIP_SEG from a payload built by
+    /// `MembershipFilter::to_segment_payload`.
+    ///
+    /// Returns the segment ID and byte offset where it was written.
+    pub(crate) fn write_membership_seg<W: Write + Seek>(
+        &mut self,
+        writer: &mut W,
+        payload: &[u8],
+    ) -> io::Result<(u64, u64)> {
+        let seg_id = self.alloc_seg_id();
+        let offset = self.write_segment(writer, SegmentType::Membership as u8, seg_id, payload)?;
+        Ok((seg_id, offset))
+    }
+
+    /// Write a SETTINGS_SEG holding the store settings (see `settings`).
+    pub(crate) fn write_settings_seg<W: Write + Seek>(
+        &mut self,
+        writer: &mut W,
+        payload: &[u8],
+    ) -> io::Result<(u64, u64)> {
+        let seg_id = self.alloc_seg_id();
+        let offset = self.write_segment(writer, SegmentType::Settings as u8, seg_id, payload)?;
+        Ok((seg_id, offset))
+    }
+
     /// Write a WITNESS_SEG containing a serialized witness entry.
     ///
     /// Payload layout:
@@ -412,6 +499,14 @@ impl SegmentWriter {
         let offset = writer.stream_position()?;
 
         let mut header = SegmentHeader::new(seg_type, seg_id);
+        let encrypted;
+        let payload = match &self.encryption {
+            Some(config) if encryption::is_encrypted_type(seg_type) => {
+                encrypted = encryption::encrypt_payload(config, &mut header, payload)?;
+                encrypted.as_slice()
+            }
+            _ => payload,
+        };
         header.payload_length = payload.len() as u64;
 
         // Compute a simple content hash (first 16 bytes of CRC-based hash).
@@ -429,12 +524,16 @@ impl SegmentWriter {
     }
 
     /// Current next segment ID.
-    #[allow(dead_code)]
     pub(crate) fn next_id(&self) -> u64 {
         self.next_seg_id
     }
 }
 
+/// Number of 12-byte journal entries needed for `tombstones`.
+pub(crate) fn journal_entry_count(tombstones: &[(u64, Option<u64>)]) -> usize {
+    tombstones.len() + tombstones.iter().filter(|t| t.1.is_some()).count()
+}
+
 /// Convert a SegmentHeader to its 64-byte wire representation.
 fn header_to_bytes(h: &SegmentHeader) -> [u8; SEGMENT_HEADER_SIZE] {
     let mut buf = [0u8; SEGMENT_HEADER_SIZE];
@@ -0,0 +1,415 @@
+//! XOR filters for static membership sets.
+//!
+//! An XOR filter (Graf & Lemire, 2020) answers "is `key` in the set?" with
+//! no false negatives and a false-positive rate of about `2^-bits`, using
+//! ~1.23 fingerprints per key. That is roughly 23% smaller than a Bloom
+//! filter at the same rate, and a lookup is three unconditional loads and
+//! two XORs. The trade-off is that the filter is immutable: the whole key
+//! set must be known before it is built.
+//!
+//! Wire format (after the `MembershipHeader`):
+//!   seed(u64) | block_length(u32) | fingerprints[3 * block_length]
+//! Fingerprints are 1 byte (`Xor8`) or 2 bytes little-endian (`Xor16`).
+
+use rvf_types::membership::FilterType;
+use rvf_types::{ErrorCode, RvfError};
+
+/// Peeling fails with low probability; give up after this many seeds.
+const MAX_BUILD_ATTEMPTS: u32 = 100;
+
+/// Bytes before the fingerprint array in the serialized form.
+const XOR_HEADER_SIZE: usize = 12;
+
+/// An immutable XOR filter over 64-bit keys.
+#[derive(Clone, Debug)]
+pub struct XorFilter {
+    filter_type: FilterType,
+    seed: u64,
+    block_length: u32,
+    /// `3 * block_length` fingerprints, 1 or 2 bytes each.
+    fingerprints: Vec<u8>,
+    key_count: u64,
+}
+
+impl XorFilter {
+    /// Whether `key` may be in the set. Never false for a key that was added.
+    #[inline]
+    pub fn contains(&self, key: u64) -> bool {
+        let h = mix(key, self.seed);
+        let [a, b, c] = self.positions(h);
+        let f = self.fingerprint_at(a) ^ self.fingerprint_at(b) ^ self.fingerprint_at(c);
+        f == self.fingerprint(h)
+    }
+
+    /// Filter type (`Xor8` or `Xor16`).
+    pub fn filter_type(&self) -> FilterType {
+        self.filter_type
+    }
+
+    /// Number of distinct keys the filter was built from.
+    pub fn len(&self) -> u64 {
+        self.key_count
+    }
+
+    /// Whether the filter was built from an empty key set.
+    pub fn is_empty(&self) -> bool {
+        self.key_count == 0
+    }
+
+    /// Serialized size in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        XOR_HEADER_SIZE + self.fingerprints.len()
+    }
+
+    /// Serialize the filter (see the module docs for the layout).
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.size_in_bytes());
+        buf.extend_from_slice(&self.seed.to_le_bytes());
+        buf.extend_from_slice(&self.block_length.to_le_bytes());
+        buf.extend_from_slice(&self.fingerprints);
+        buf
+    }
+
+    /// Deserialize a filter of the given type holding `key_count` keys.
+    pub fn deserialize(
+        data: &[u8],
+        filter_type: FilterType,
+        key_count: u64,
+    ) -> Result<Self, RvfError> {
+        let width = fingerprint_width(filter_type)?;
+        if data.len() < XOR_HEADER_SIZE {
+            return Err(RvfError::Code(ErrorCode::MembershipInvalid));
+        }
+        let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let block_length = u32::from_le_bytes(data[8..12].try_into().unwrap());
+        // A zero block length leaves no slots for lookups to index, and
+        // slot indices are computed in u32.
+        if block_length == 0 || block_length.checked_mul(3).is_none() {
+            return Err(RvfError::Code(ErrorCode::MembershipInvalid));
+        }
+        let fp_bytes = (block_length as usize)
+            .checked_mul(3 * width)
+            .ok_or(RvfError::Code(ErrorCode::MembershipInvalid))?;
+        if data.len() != XOR_HEADER_SIZE + fp_bytes {
+            return Err(RvfError::Code(ErrorCode::MembershipInvalid));
+        }
+        Ok(Self {
+            filter_type,
+            seed,
+            block_length,
+            fingerprints: data[XOR_HEADER_SIZE..XOR_HEADER_SIZE + fp_bytes].to_vec(),
+            key_count,
+        })
+    }
+
+    fn positions(&self, h: u64) -> [usize; 3] {
+        let bl = self.block_length;
+        [
+            reduce(h as u32, bl) as usize,
+            (reduce(h.rotate_left(21) as u32, bl) + bl) as usize,
+            (reduce(h.rotate_left(42) as u32, bl) + 2 * bl) as usize,
+        ]
+    }
+
+    fn fingerprint(&self, h: u64) -> u16 {
+        let f = (h ^ (h >> 32)) as u16;
+        match self.filter_type {
+            FilterType::Xor8 => f & 0xFF,
+            _ => f,
+        }
+    }
+
+    fn fingerprint_at(&self, i: usize) -> u16 {
+        match self.filter_type {
+            FilterType::Xor8 => self.fingerprints[i] as u16,
+            _ => u16::from_le_bytes([self.fingerprints[2 * i], self.fingerprints[2 * i + 1]]),
+        }
+    }
+
+    fn set_fingerprint(&mut self, i: usize, f: u16) {
+        match self.filter_type {
+            FilterType::Xor8 => self.fingerprints[i] = f as u8,
+            _ => self.fingerprints[2 * i..2 * i + 2].copy_from_slice(&f.to_le_bytes()),
+        }
+    }
+}
+
+/// Collects keys and builds an [`XorFilter`].
+///
+/// Once [`finalize`](Self::finalize) has succeeded the builder is frozen and
+/// further [`add`](Self::add) calls fail with `SnapshotFrozen`.
+#[derive(Clone, Debug)]
+pub struct XorFilterBuilder {
+    filter_type: FilterType,
+    keys: Vec<u64>,
+    finalized: bool,
+}
+
+impl XorFilterBuilder {
+    /// Start a builder for `Xor8` or `Xor16`.
+    pub fn new(filter_type: FilterType) -> Result<Self, RvfError> {
+        fingerprint_width(filter_type)?;
+        Ok(Self {
+            filter_type,
+            keys: Vec::new(),
+            finalized: false,
+        })
+    }
+
+    /// Add a key. Duplicates are allowed and collapse at build time.
+    pub fn add(&mut self, key: u64) -> Result<(), RvfError> {
+        if self.finalized {
+            return Err(RvfError::Code(ErrorCode::SnapshotFrozen));
+        }
+        self.keys.push(key);
+        Ok(())
+    }
+
+    /// Add every key from an iterator.
+    pub fn extend<I: IntoIterator<Item = u64>>(&mut self, keys: I) -> Result<(), RvfError> {
+        if self.finalized {
+            return Err(RvfError::Code(ErrorCode::SnapshotFrozen));
+        }
+        self.keys.extend(keys);
+        Ok(())
+    }
+
+    /// Whether the filter has already been built.
+    pub fn is_finalized(&self) -> bool {
+        self.finalized
+    }
+
+    /// Build the filter and freeze the builder.
+    pub fn finalize(&mut self) -> Result<XorFilter, RvfError> {
+        if self.finalized {
+            return Err(RvfError::Code(ErrorCode::SnapshotFrozen));
+        }
+        let mut keys = core::mem::take(&mut self.keys);
+        keys.sort_unstable();
+        keys.dedup();
+        let filter = build(self.filter_type, &keys)?;
+        self.finalized = true;
+        Ok(filter)
+    }
+}
+
+fn fingerprint_width(filter_type: FilterType) -> Result<usize, RvfError> {
+    match filter_type {
+        FilterType::Xor8 => Ok(1),
+        FilterType::Xor16 => Ok(2),
+        _ => Err(RvfError::Code(ErrorCode::MembershipInvalid)),
+    }
+}
+
+/// Build a filter from distinct keys by hypergraph peeling.
+fn build(filter_type: FilterType, keys: &[u64]) -> Result<XorFilter, RvfError> {
+    let width = fingerprint_width(filter_type)?;
+    let capacity = 32 + (keys.len() as u64 * 123).div_ceil(100);
+    let block_length =
+        u32::try_from(capacity / 3).map_err(|_| RvfError::Code(ErrorCode::MembershipInvalid))?;
+    let slots = 3 * block_length as usize;
+
+    let mut filter = XorFilter {
+        filter_type,
+        seed: 0,
+        block_length,
+        fingerprints: vec![0u8; slots * width],
+        key_count: keys.len() as u64,
+    };
+
+    let mut rng = 0x2545_F491_4F6C_DD1Du64;
+    let mut xor_masks = vec![0u64; slots];
+    let mut counts = vec![0u32; slots];
+    let mut queue = Vec::with_capacity(slots);
+    let mut stack: Vec<(u64, usize)> = Vec::with_capacity(keys.len());
+
+    for _ in 0..MAX_BUILD_ATTEMPTS {
+        rng = splitmix64(rng);
+        filter.seed = rng;
+        xor_masks.fill(0);
+        counts.fill(0);
+        queue.clear();
+        stack.clear();
+
+        for &key in keys {
+            let h = mix(key, filter.seed);
+            for p in filter.positions(h) {
+                xor_masks[p] ^= h;
+                counts[p] += 1;
+            }
+        }
+        queue.extend((0..slots).filter(|&i| counts[i] == 1));
+
+        while let Some(i) = queue.pop() {
+            if counts[i] != 1 {
+                continue;
+            }
+            let h = xor_masks[i];
+            stack.push((h, i));
+            for p in filter.positions(h) {
+                xor_masks[p] ^= h;
+                counts[p] -= 1;
+                if counts[p] == 1 {
+                    queue.push(p);
+                }
+            }
+        }
+
+        if stack.len() == keys.len() {
+            filter.fingerprints.fill(0);
+            for &(h, i) in stack.iter().rev() {
+                let [a, b, c] = filter.positions(h);
+                let f = filter.fingerprint(h)
+                    ^ filter.fingerprint_at(a)
+                    ^ filter.fingerprint_at(b)
+                    ^ filter.fingerprint_at(c);
+                filter.set_fingerprint(i, f);
+            }
+            return Ok(filter);
+        }
+    }
+
+    Err(RvfError::Code(ErrorCode::MembershipInvalid))
+}
+
+#[inline]
+pub(crate) fn mix(key: u64, seed: u64) -> u64 {
+    // murmur3 fmix64
+    let mut h = key.wrapping_add(seed);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+    h ^ (h >> 33)
+}
+
+#[inline]
+fn reduce(x: u32, n: u32) -> u32 {
+    ((x as u64 * n as u64) >> 32) as u32
+}
+
+fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_keys(n: usize, seed: u64) -> Vec<u64> {
+        let mut state = seed;
+        (0..n)
+            .map(|_| {
+                state = splitmix64(state);
+                state
+            })
+            .collect()
+    }
+
+    fn build_from(filter_type: FilterType, keys: &[u64]) -> XorFilter {
+        let mut builder = XorFilterBuilder::new(filter_type).unwrap();
+        builder.extend(keys.iter().copied()).unwrap();
+        builder.finalize().unwrap()
+    }
+
+    fn false_positive_rate(filter: &XorFilter, probes: &[u64]) -> f64 {
+        let hits = probes.iter().filter(|&&k| filter.contains(k)).count();
+        hits as f64 / probes.len() as f64
+    }
+
+    #[test]
+    fn xor8_no_false_negatives_and_low_fpr() {
+        let keys = random_keys(100_000, 1);
+        let filter = build_from(FilterType::Xor8, &keys);
+        assert!(keys.iter().all(|&k| filter.contains(k)));
+
+        // Theoretical rate is 1/256 ~ 0.39%.
+        let fpr = false_positive_rate(&filter, &random_keys(100_000, 2));
+        assert!(fpr < 0.006, "xor8 false-positive rate {fpr}");
+        // ~1.23 bytes per key.
+        assert!(filter.size_in_bytes() < 125_000);
+    }
+
+    #[test]
+    fn xor16_no_false_negatives_and_lower_fpr() {
+        let keys = random_keys(100_000, 3);
+        let filter = build_from(FilterType::Xor16, &keys);
+        assert!(keys.iter().all(|&k| filter.contains(k)));
+
+        // Theoretical rate is 1/65536 ~ 0.0015%.
+        let fpr = false_positive_rate(&filter, &random_keys(100_000, 4));
+        assert!(fpr < 0.0002, "xor16 false-positive rate {fpr}");
+    }
+
+    #[test]
+    fn add_after_finalize_is_rejected() {
+        let mut builder = XorFilterBuilder::new(FilterType::Xor8).unwrap();
+        builder.add(1).unwrap();
+        builder.add(2).unwrap();
+        let filter = builder.finalize().unwrap();
+        assert!(builder.is_finalized());
+        assert_eq!(filter.len(), 2);
+
+        assert_eq!(
+            builder.add(3),
+            Err(RvfError::Code(ErrorCode::SnapshotFrozen))
+        );
+        assert_eq!(
+            builder.extend([4, 5]),
+            Err(RvfError::Code(ErrorCode::SnapshotFrozen))
+        );
+        assert!(builder.finalize().is_err());
+    }
+
+    #[test]
+    fn duplicates_and_empty_sets() {
+        let filter = build_from(FilterType::Xor8, &[7, 7, 7, 9]);
+        assert_eq!(filter.len(), 2);
+        assert!(filter.contains(7));
+        assert!(filter.contains(9));
+
+        let empty = build_from(FilterType::Xor16, &[]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn non_xor_type_rejected() {
+        assert!(XorFilterBuilder::new(FilterType::Bitmap).is_err());
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let keys = random_keys(1_000, 5);
+        for filter_type in [FilterType::Xor8, FilterType::Xor16] {
+            let filter = build_from(filter_type, &keys);
+            let bytes = filter.serialize();
+            assert_eq!(bytes.len(), filter.size_in_bytes());
+
+            let restored = XorFilter::deserialize(&bytes, filter_type, filter.len()).unwrap();
+            assert!(keys.iter().all(|&k| restored.contains(k)));
+            assert!(XorFilter::deserialize(&bytes[..bytes.len() - 1], filter_type, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn deserialize_rejects_bad_block_length() {
+        let filter = build_from(FilterType::Xor8, &random_keys(100, 9));
+        let bytes = filter.serialize();
+
+        let mut zero = bytes[..XOR_HEADER_SIZE].to_vec();
+        zero[8..12].copy_from_slice(&0u32.to_le_bytes());
+        assert!(XorFilter::deserialize(&zero, FilterType::Xor8, 0).is_err());
+
+        // The fingerprint array is longer than the block length implies.
+        let mut short = bytes.clone();
+        short[8..12].copy_from_slice(&(filter.block_length - 1).to_le_bytes());
+        assert!(XorFilter::deserialize(&short, FilterType::Xor8, 0).is_err());
+
+        let mut huge = bytes;
+        huge[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(XorFilter::deserialize(&huge, FilterType::Xor8, 0).is_err());
+    }
+}
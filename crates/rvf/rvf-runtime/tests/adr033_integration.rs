@@ -65,8 +65,8 @@ fn quality_envelope_returned_for_normal_query() {
         envelope.quality,
         ResponseQuality::Verified | ResponseQuality::Usable | ResponseQuality::Degraded
     ));
-    // Evidence must be populated.
-    assert!(envelope.evidence.layers_used.layer_a);
+    // Queries are exact scans, so no index layer is reported.
+    assert!(!envelope.evidence.layers_used.layer_a);
     // Budget report must have non-zero total_us.
     assert!(envelope.budgets.total_us > 0 || envelope.results.is_empty());
 }
@@ -151,6 +151,67 @@ fn quality_envelope_accept_degraded_succeeds() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn tight_deadline_returns_partial_results() {
+    let (_dir, store) = create_test_store(8, 5_000);
+    let query = vec![0.5; 8];
+
+    // An already-expired deadline still scans one block before stopping.
+    let opts = QueryOptions {
+        deadline: Some(Duration::ZERO),
+        ..QueryOptions::default()
+    };
+
+    let envelope = store.query_with_envelope(&query, 10, &opts).unwrap();
+    assert_eq!(envelope.results.len(), 10);
+    assert_eq!(envelope.quality, ResponseQuality::Degraded);
+
+    let report = envelope.degradation.expect("deadline must be reported");
+    assert_eq!(report.fallback_path, FallbackPath::DeadlineTruncated);
+    match report.reason {
+        DegradationReason::DeadlineExceeded { scanned, total } => {
+            assert!(scanned > 0 && scanned < total);
+            assert_eq!(total, 5_000);
+        }
+        other => panic!("unexpected degradation reason: {other:?}"),
+    }
+}
+
+#[test]
+fn generous_deadline_matches_undeadlined_query() {
+    let (_dir, store) = create_test_store(8, 2_000);
+    let query = vec![0.5; 8];
+
+    let baseline = store.query(&query, 10, &QueryOptions::default()).unwrap();
+    let opts = QueryOptions {
+        deadline: Some(Duration::from_secs(60)),
+        ..QueryOptions::default()
+    };
+    let results = store.query(&query, 10, &opts).unwrap();
+    assert_eq!(results, baseline);
+
+    let envelope = store
+        .query_with_envelope(
+            &query,
+            10,
+            &QueryOptions {
+                quality_preference: QualityPreference::AcceptDegraded,
+                ..opts
+            },
+        )
+        .unwrap();
+    assert!(!matches!(
+        envelope.degradation,
+        Some(DegradationReport {
+            fallback_path: FallbackPath::DeadlineTruncated,
+            ..
+        })
+    ));
+    let ids: Vec<u64> = envelope.results.iter().map(|r| r.id).collect();
+    let baseline_ids: Vec<u64> = baseline.iter().map(|r| r.id).collect();
+    assert_eq!(ids, baseline_ids);
+}
+
 // ========================================================================
 // §2 Budget Cap Enforcement
 // ========================================================================
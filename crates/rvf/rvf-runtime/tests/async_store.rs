@@ -0,0 +1,179 @@
+//! Integration tests for the tokio-based `open_async` / `query_async` API.
+
+use std::sync::Arc;
+
+use rvf_runtime::options::{DistanceMetric, QueryOptions, RvfOptions};
+use rvf_runtime::{AsyncBoot, AsyncOpenOptions, RvfStore};
+use tempfile::TempDir;
+
+const DIM: u16 = 8;
+
+fn vector(i: u64) -> Vec<f32> {
+    // Deterministic pseudo-random components, so distances rarely tie.
+    let mut state = i.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..DIM)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 10_000) as f32 / 100.0
+        })
+        .collect()
+}
+
+fn build_store(dir: &TempDir, count: u64) -> std::path::PathBuf {
+    let path = dir.path().join("async.rvf");
+    let options = RvfOptions {
+        dimension: DIM,
+        metric: DistanceMetric::L2,
+        ..Default::default()
+    };
+    let mut store = RvfStore::create(&path, options).unwrap();
+    let vectors: Vec<Vec<f32>> = (0..count).map(vector).collect();
+    let refs: Vec<&[f32]> = vectors.iter().map(|v| v.as_slice()).collect();
+    let ids: Vec<u64> = (0..count).collect();
+    store.ingest_batch(&refs, &ids, None).unwrap();
+    store.close().unwrap();
+    path
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_async_queries_match_sync() {
+    let dir = TempDir::new().unwrap();
+    let path = build_store(&dir, 500);
+
+    let expected: Vec<Vec<u64>> = {
+        let store = RvfStore::open_readonly(&path).unwrap();
+        (0..32)
+            .map(|q| {
+                store
+                    .query(&vector(q * 13), 10, &QueryOptions::default())
+                    .unwrap()
+                    .iter()
+                    .map(|r| r.id)
+                    .collect()
+            })
+            .collect()
+    };
+
+    let store = Arc::new(RvfStore::open_async(&path).await.unwrap());
+    assert_eq!(store.dimension(), DIM);
+
+    let tasks: Vec<_> = (0..32u64)
+        .map(|q| {
+            let store = Arc::clone(&store);
+            tokio::spawn(async move {
+                let results = store
+                    .query_async(&vector(q * 13), 10, &QueryOptions::default())
+                    .await
+                    .unwrap();
+                (q, results.iter().map(|r| r.id).collect::<Vec<_>>())
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        let (q, ids) = task.await.unwrap();
+        assert_eq!(ids.len(), 10);
+        assert_eq!(ids, expected[q as usize], "query {q} diverged");
+    }
+    assert_eq!(store.metrics().query_count, 32);
+}
+
+#[tokio::test]
+async fn async_open_on_current_thread_runtime() {
+    let dir = TempDir::new().unwrap();
+    let path = build_store(&dir, 20);
+
+    let store = RvfStore::open_readonly_async(&path).await.unwrap();
+    let results = store
+        .query_async(&vector(3), 1, &QueryOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(results[0].id, 3);
+    assert_eq!(results[0].distance, 0.0);
+
+    let missing = dir.path().join("missing.rvf");
+    assert!(RvfStore::open_async(&missing).await.is_err());
+}
+
+#[tokio::test]
+async fn staged_open_answers_queries_as_segments_load() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("staged.rvf");
+    let options = RvfOptions {
+        dimension: DIM,
+        metric: DistanceMetric::L2,
+        ..Default::default()
+    };
+    let mut store = RvfStore::create(&path, options).unwrap();
+    for batch in 0..3u64 {
+        let vectors: Vec<Vec<f32>> = (batch * 100..(batch + 1) * 100).map(vector).collect();
+        let refs: Vec<&[f32]> = vectors.iter().map(|v| v.as_slice()).collect();
+        let ids: Vec<u64> = (batch * 100..(batch + 1) * 100).collect();
+        store.ingest_batch(&refs, &ids, None).unwrap();
+    }
+    store.delete(&[5]).unwrap();
+    store.close().unwrap();
+
+    let mut boot = AsyncBoot::open(&path, AsyncOpenOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(boot.progress(), (0, 3));
+    assert_eq!(boot.store().status().total_vectors, 0);
+
+    // After the first segment, queries see only its vectors.
+    assert!(boot.load_next().await.unwrap());
+    assert_eq!(boot.progress(), (1, 3));
+    let partial = boot
+        .store()
+        .query(&vector(250), 10, &QueryOptions::default())
+        .unwrap();
+    assert_eq!(partial.len(), 10);
+    assert!(partial.iter().all(|r| r.id < 100 && r.id != 5));
+
+    let mut store = boot.finish().await.unwrap();
+    assert_eq!(store.status().total_vectors, 299);
+    let results = store
+        .query(&vector(250), 1, &QueryOptions::default())
+        .unwrap();
+    assert_eq!(results[0].id, 250);
+    assert!(store.delete(&[1]).is_err());
+}
+
+#[cfg(feature = "encryption")]
+#[tokio::test]
+async fn async_open_reads_encrypted_store() {
+    use rvf_runtime::EncryptionConfig;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("encrypted.rvf");
+    let key = [0x17; 32];
+    let options = RvfOptions {
+        dimension: DIM,
+        metric: DistanceMetric::L2,
+        encryption: Some(EncryptionConfig::from_key(key)),
+        ..Default::default()
+    };
+    let mut store = RvfStore::create(&path, options).unwrap();
+    let vectors: Vec<Vec<f32>> = (0..50).map(vector).collect();
+    let refs: Vec<&[f32]> = vectors.iter().map(|v| v.as_slice()).collect();
+    let ids: Vec<u64> = (0..50).collect();
+    store.ingest_batch(&refs, &ids, None).unwrap();
+    store.close().unwrap();
+
+    let mut store = RvfStore::open_async_with_encryption(&path, EncryptionConfig::from_key(key))
+        .await
+        .unwrap();
+    let results = store
+        .query_async(&vector(7), 1, &QueryOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(results[0].id, 7);
+    store.delete(&[7]).unwrap();
+    store.close().unwrap();
+
+    let wrong = RvfStore::open_async_with_encryption(&path, EncryptionConfig::from_key([0; 32]));
+    assert!(wrong.await.is_err());
+    assert!(RvfStore::open_readonly_async(&path).await.is_err());
+}
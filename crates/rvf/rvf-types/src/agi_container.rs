@@ -462,6 +462,15 @@ impl AgiContainerHeader {
     }
 }
 
+/// Read the TLV manifest sections that follow the header in `data`.
+///
+/// Checks the header magic and size; the sections themselves are validated
+/// as the returned reader is iterated.
+pub fn manifest_sections(data: &[u8]) -> Result<crate::tlv::TlvReader<'_>, crate::RvfError> {
+    AgiContainerHeader::from_bytes(data)?;
+    Ok(crate::tlv::TlvReader::new(&data[AGI_HEADER_SIZE..]))
+}
+
 /// Required segments for a valid AGI container.
 ///
 /// Used by the container builder/validator to ensure completeness.
@@ -610,7 +619,10 @@ impl core::fmt::Display for ContainerError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tlv::TlvWriter;
+    use crate::RvfError;
     use alloc::format;
+    use alloc::vec::Vec;
 
     #[test]
     fn agi_header_size() {
@@ -959,4 +971,91 @@ mod tests {
             ))
         );
     }
+
+    fn manifest_with(build: impl FnOnce(&mut TlvWriter)) -> Vec<u8> {
+        let hdr = AgiContainerHeader {
+            magic: AGI_MAGIC,
+            version: 1,
+            flags: AGI_HAS_ORCHESTRATOR,
+            container_id: [0x01; 16],
+            build_id: [0x02; 16],
+            created_ns: 0,
+            model_id_hash: [0; 8],
+            policy_hash: [0; 8],
+        };
+        let mut w = TlvWriter::new();
+        build(&mut w);
+        let mut data = hdr.to_bytes().to_vec();
+        data.extend_from_slice(w.as_bytes());
+        data
+    }
+
+    #[test]
+    fn manifest_sections_iterate_in_order() {
+        let data = manifest_with(|w| {
+            w.uuid(AGI_TAG_CONTAINER_ID, &[0x01; 16])
+                .str(AGI_TAG_MODEL_ID, "rv-model-7b")
+                .bytes(AGI_TAG_ORCHESTRATOR, b"{\"max_turns\":100}")
+                .bytes(0x7FFF, b"future");
+        });
+        let sections = manifest_sections(&data).unwrap();
+        let tags: Vec<u16> = sections.clone().map(|r| r.unwrap().tag).collect();
+        assert_eq!(
+            tags,
+            [
+                AGI_TAG_CONTAINER_ID,
+                AGI_TAG_MODEL_ID,
+                AGI_TAG_ORCHESTRATOR,
+                0x7FFF
+            ]
+        );
+        assert_eq!(
+            sections.find_tag(AGI_TAG_MODEL_ID),
+            Some(&b"rv-model-7b"[..])
+        );
+        assert_eq!(sections.find_tag(AGI_TAG_POLICY), None);
+
+        let bare = manifest_with(|_| {});
+        assert!(manifest_sections(&bare).unwrap().is_empty());
+        assert!(manifest_sections(&data[..AGI_HEADER_SIZE - 1]).is_err());
+    }
+
+    #[test]
+    fn manifest_sections_reject_truncated_length() {
+        let mut data = manifest_with(|w| {
+            w.str(AGI_TAG_MODEL_ID, "model")
+                .bytes(AGI_TAG_POLICY, &[0xAB; 32]);
+        });
+        data.truncate(data.len() - 4);
+        let sections = manifest_sections(&data).unwrap();
+        let results: Vec<_> = sections.clone().collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1],
+            Err(RvfError::SizeMismatch {
+                expected: 32,
+                got: 28
+            })
+        );
+        // A malformed manifest yields nothing, even for intact sections.
+        assert_eq!(sections.find_tag(AGI_TAG_MODEL_ID), None);
+    }
+
+    #[test]
+    fn manifest_sections_reject_duplicate_tag() {
+        let data = manifest_with(|w| {
+            w.str(AGI_TAG_MODEL_ID, "first")
+                .str(AGI_TAG_MODEL_ID, "second");
+        });
+        let sections = manifest_sections(&data).unwrap();
+        assert!(matches!(
+            sections.clone().nth(1),
+            Some(Err(RvfError::InvalidTlv {
+                tag: AGI_TAG_MODEL_ID,
+                ..
+            }))
+        ));
+        assert_eq!(sections.find_tag(AGI_TAG_MODEL_ID), None);
+    }
 }
@@ -52,6 +52,8 @@ pub enum AttestationWitnessType {
     ComputationProof = 0x07,
     /// Chain of custody from model to TEE to RVF.
     DataProvenance = 0x08,
+    /// AMD SEV-SNP attestation report (quote is the raw 1184-byte report).
+    SnpReport = 0x09,
 }
 
 impl TryFrom<u8> for AttestationWitnessType {
@@ -63,6 +65,7 @@ impl TryFrom<u8> for AttestationWitnessType {
             0x06 => Ok(Self::KeyBinding),
             0x07 => Ok(Self::ComputationProof),
             0x08 => Ok(Self::DataProvenance),
+            0x09 => Ok(Self::SnpReport),
             other => Err(other),
         }
     }
@@ -204,6 +207,7 @@ mod tests {
             (0x06, AttestationWitnessType::KeyBinding),
             (0x07, AttestationWitnessType::ComputationProof),
             (0x08, AttestationWitnessType::DataProvenance),
+            (0x09, AttestationWitnessType::SnpReport),
         ];
         for &(raw, expected) in variants {
             let parsed = AttestationWitnessType::try_from(raw).unwrap();
@@ -216,7 +220,7 @@ mod tests {
     fn attestation_witness_type_invalid() {
         assert_eq!(AttestationWitnessType::try_from(0x00), Err(0x00));
         assert_eq!(AttestationWitnessType::try_from(0x04), Err(0x04));
-        assert_eq!(AttestationWitnessType::try_from(0x09), Err(0x09));
+        assert_eq!(AttestationWitnessType::try_from(0x0A), Err(0x0A));
         assert_eq!(AttestationWitnessType::try_from(0xFF), Err(0xFF));
     }
 
@@ -1,4 +1,12 @@
 //! Compression algorithm identifiers.
+//!
+//! With the `zstd` feature, `Zstd` can also compress with a trained
+//! dictionary (see `compression_dict`).
+
+#[cfg(feature = "zstd")]
+use crate::error::{ErrorCode, RvfError};
+#[cfg(feature = "zstd")]
+use alloc::vec::Vec;
 
 /// Identifies the compression algorithm applied to a segment payload.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -29,6 +37,64 @@ impl TryFrom<u8> for CompressionAlgo {
     }
 }
 
+/// Zstd level used for dictionary compression.
+#[cfg(feature = "zstd")]
+const ZSTD_DICT_LEVEL: i32 = 3;
+
+#[cfg(feature = "zstd")]
+impl CompressionAlgo {
+    /// Compress `data` primed with a dictionary from
+    /// [`train_dictionary`](crate::compression_dict::train_dictionary).
+    ///
+    /// Only `Zstd` supports dictionaries. The dictionary ID is written into
+    /// the frame header, so a reader can find the matching
+    /// `CompressionDictionary` segment and recover the bytes from it.
+    pub fn compress_with_dict(&self, data: &[u8], dict: &[u8]) -> Result<Vec<u8>, RvfError> {
+        self.require_dict_support()?;
+        zstd::bulk::Compressor::with_dictionary(ZSTD_DICT_LEVEL, dict)
+            .and_then(|mut c| c.compress(data))
+            .map_err(|_| RvfError::InvalidDictionary {
+                reason: "zstd compression with dictionary failed",
+            })
+    }
+
+    /// Decompress a frame produced by [`compress_with_dict`](Self::compress_with_dict)
+    /// with the same dictionary.
+    ///
+    /// `max_len` is the expected decompressed size, normally the segment's
+    /// uncompressed payload length. A frame that inflates past it fails with
+    /// `SegmentTooLarge` instead of growing the output without bound.
+    pub fn decompress_with_dict(
+        &self,
+        data: &[u8],
+        dict: &[u8],
+        max_len: usize,
+    ) -> Result<Vec<u8>, RvfError> {
+        use std::io::Read;
+
+        self.require_dict_support()?;
+        let mut out = Vec::new();
+        zstd::stream::Decoder::with_dictionary(data, dict)
+            .and_then(|d| d.take(max_len as u64 + 1).read_to_end(&mut out))
+            .map_err(|_| RvfError::InvalidDictionary {
+                reason: "zstd decompression with dictionary failed",
+            })?;
+        if out.len() > max_len {
+            return Err(RvfError::Code(ErrorCode::SegmentTooLarge));
+        }
+        Ok(out)
+    }
+
+    fn require_dict_support(&self) -> Result<(), RvfError> {
+        if *self != Self::Zstd {
+            return Err(RvfError::InvalidDictionary {
+                reason: "dictionaries are only supported for zstd",
+            });
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +111,85 @@ mod tests {
     fn invalid_value() {
         assert_eq!(CompressionAlgo::try_from(4), Err(4));
     }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn shared_dictionary_beats_independent_frames() {
+        use crate::compression_dict::train_dictionary;
+
+        // 1000 similar 200-byte metadata records.
+        let records: Vec<Vec<u8>> = (0..1000)
+            .map(|i| {
+                let mut r = alloc::format!(
+                    "{{\"kind\":\"observation\",\"source\":\"camera-{}\",\"frame\":{i},\
+                     \"labels\":[\"person\",\"vehicle\"],\"confidence\":0.{:03},\
+                     \"model\":\"detector-v2\",\"site\":\"north-{}\"}}",
+                    i % 12,
+                    (i * 37) % 1000,
+                    i % 5
+                )
+                .into_bytes();
+                r.resize(200, b' ');
+                r
+            })
+            .collect();
+        let samples: Vec<&[u8]> = records.iter().map(Vec::as_slice).collect();
+        let dict = train_dictionary(&samples, 4096).unwrap();
+
+        let algo = CompressionAlgo::Zstd;
+        let independent: usize = records
+            .iter()
+            .map(|r| zstd::bulk::compress(r, ZSTD_DICT_LEVEL).unwrap().len())
+            .sum();
+        let mut shared = dict.len();
+        for r in &records {
+            let frame = algo.compress_with_dict(r, &dict).unwrap();
+            assert_eq!(
+                &algo.decompress_with_dict(&frame, &dict, r.len()).unwrap(),
+                r
+            );
+            shared += frame.len();
+        }
+
+        // Even counting the dictionary itself, sharing it must save at
+        // least a quarter of the independent total.
+        assert!(
+            shared * 4 < independent * 3,
+            "shared {shared} vs independent {independent}"
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn dictionaries_require_zstd() {
+        let dict = [0u8; 16];
+        assert!(matches!(
+            CompressionAlgo::Lz4.compress_with_dict(b"data", &dict),
+            Err(RvfError::InvalidDictionary { .. })
+        ));
+        assert!(CompressionAlgo::None
+            .decompress_with_dict(b"data", &dict, 4)
+            .is_err());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn decompression_is_capped_at_expected_size() {
+        let algo = CompressionAlgo::Zstd;
+        let dict = [0u8; 16];
+        // A small frame that inflates to 1 MiB of zeros.
+        let data = alloc::vec![0u8; 1 << 20];
+        let frame = algo.compress_with_dict(&data, &dict).unwrap();
+        assert!(frame.len() < 1024);
+
+        assert_eq!(
+            algo.decompress_with_dict(&frame, &dict, data.len())
+                .unwrap(),
+            data
+        );
+        assert_eq!(
+            algo.decompress_with_dict(&frame, &dict, data.len() - 1),
+            Err(RvfError::Code(ErrorCode::SegmentTooLarge))
+        );
+    }
 }
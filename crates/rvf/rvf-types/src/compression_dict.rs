@@ -0,0 +1,350 @@
+//! Compression dictionary payload stored in a COMPRESSION_DICT_SEG.
+//!
+//! Small segments compress poorly on their own because every payload starts
+//! from an empty window. A zstd dictionary trained on representative
+//! segments primes that window. Each trained dictionary is stored in its own
+//! segment as a fixed 16-byte header followed by the raw dictionary bytes:
+//!
+//! ```text
+//! Offset  Type   Field
+//! 0x00    u32    magic (COMPRESSION_DICT_MAGIC, "RVZD")
+//! 0x04    u16    version
+//! 0x06    u8     algo (CompressionAlgo)
+//! 0x07    u8     reserved (must be zero)
+//! 0x08    u32    dictionary id (DictionaryId, non-zero)
+//! 0x0C    u32    dictionary length in bytes
+//! 0x10    [u8]   dictionary bytes
+//! ```
+//!
+//! Requires the `alloc` feature. Training requires the `zstd` feature.
+
+use crate::compression::CompressionAlgo;
+use crate::error::{ErrorCode, RvfError};
+use crate::sha256::sha256;
+use alloc::vec::Vec;
+
+/// Magic number for a compression dictionary: "RVZD" in big-endian.
+pub const COMPRESSION_DICT_MAGIC: u32 = 0x5256_5A44;
+
+/// Current compression dictionary format version.
+pub const COMPRESSION_DICT_VERSION: u16 = 1;
+
+/// Size of the fixed dictionary header in bytes.
+pub const COMPRESSION_DICT_HEADER_SIZE: usize = 16;
+
+/// Minimum number of samples accepted by `train_dictionary`.
+pub const MIN_DICT_SAMPLES: usize = 8;
+
+/// Minimum length of each training sample; zstd skips shorter inputs.
+pub const MIN_DICT_SAMPLE_SIZE: usize = 8;
+
+/// Smallest dictionary size zstd will train.
+pub const MIN_DICT_SIZE: usize = 256;
+
+/// Magic number opening a zstd-format dictionary.
+const ZSTD_DICT_MAGIC: u32 = 0xEC30_A437;
+
+/// Identifies a compression dictionary.
+///
+/// Zstd dictionaries carry their own ID, which zstd also writes into the
+/// header of every frame compressed with them; that ID is reused so a frame
+/// can be matched to its dictionary segment. Raw-content dictionaries get
+/// the first four bytes of their SHA-256. Zero is reserved for "no
+/// dictionary".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DictionaryId(pub u32);
+
+impl DictionaryId {
+    /// Reserved ID meaning no dictionary was used.
+    pub const NONE: Self = Self(0);
+
+    /// Assign the ID for the given dictionary bytes.
+    pub fn for_dictionary(data: &[u8]) -> Self {
+        let read_u32 = |off: usize| {
+            u32::from_le_bytes([data[off], data[off + 1], data[off + 2], data[off + 3]])
+        };
+        if data.len() >= 8 && read_u32(0) == ZSTD_DICT_MAGIC && read_u32(4) != 0 {
+            return Self(read_u32(4));
+        }
+        let digest = sha256(data);
+        let id = u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]]);
+        Self(id.max(1))
+    }
+
+    /// Whether this is the reserved "no dictionary" ID.
+    pub const fn is_none(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// A trained compression dictionary and its identity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompressionDictionary {
+    /// Identifier recorded alongside payloads compressed with this dictionary.
+    pub id: DictionaryId,
+    /// Compression algorithm the dictionary was trained for.
+    pub algo: CompressionAlgo,
+    /// Raw dictionary bytes.
+    pub data: Vec<u8>,
+}
+
+impl CompressionDictionary {
+    /// Wrap dictionary bytes, assigning their [`DictionaryId`].
+    pub fn new(algo: CompressionAlgo, data: Vec<u8>) -> Result<Self, RvfError> {
+        let dict = Self {
+            id: DictionaryId::for_dictionary(&data),
+            algo,
+            data,
+        };
+        dict.validate()?;
+        Ok(dict)
+    }
+
+    /// Total serialized size in bytes.
+    pub fn wire_size(&self) -> usize {
+        COMPRESSION_DICT_HEADER_SIZE + self.data.len()
+    }
+
+    /// Serialize the dictionary (header + bytes).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.wire_size());
+        buf.extend_from_slice(&COMPRESSION_DICT_MAGIC.to_le_bytes());
+        buf.extend_from_slice(&COMPRESSION_DICT_VERSION.to_le_bytes());
+        buf.push(self.algo as u8);
+        buf.push(0);
+        buf.extend_from_slice(&self.id.0.to_le_bytes());
+        buf.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&self.data);
+        buf
+    }
+
+    /// Deserialize a dictionary segment payload.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, RvfError> {
+        if data.len() < COMPRESSION_DICT_HEADER_SIZE {
+            return Err(RvfError::SizeMismatch {
+                expected: COMPRESSION_DICT_HEADER_SIZE,
+                got: data.len(),
+            });
+        }
+        let read_u32 = |off: usize| {
+            u32::from_le_bytes([data[off], data[off + 1], data[off + 2], data[off + 3]])
+        };
+
+        let magic = read_u32(0x00);
+        if magic != COMPRESSION_DICT_MAGIC {
+            return Err(RvfError::BadMagic {
+                expected: COMPRESSION_DICT_MAGIC,
+                got: magic,
+            });
+        }
+        let version = u16::from_le_bytes([data[0x04], data[0x05]]);
+        if version != COMPRESSION_DICT_VERSION {
+            return Err(RvfError::Code(ErrorCode::InvalidVersion));
+        }
+        let algo =
+            CompressionAlgo::try_from(data[0x06]).map_err(|v| RvfError::InvalidEnumValue {
+                type_name: "CompressionAlgo",
+                value: v as u64,
+            })?;
+        if data[0x07] != 0 {
+            return Err(RvfError::InvalidDictionary {
+                reason: "reserved byte is non-zero",
+            });
+        }
+        let id = DictionaryId(read_u32(0x08));
+        let len = read_u32(0x0C) as usize;
+        let expected = COMPRESSION_DICT_HEADER_SIZE + len;
+        if data.len() != expected {
+            return Err(RvfError::SizeMismatch {
+                expected,
+                got: data.len(),
+            });
+        }
+
+        let dict = Self {
+            id,
+            algo,
+            data: data[COMPRESSION_DICT_HEADER_SIZE..].to_vec(),
+        };
+        dict.validate()?;
+        if dict.id != DictionaryId::for_dictionary(&dict.data) {
+            return Err(RvfError::InvalidDictionary {
+                reason: "dictionary id does not match its contents",
+            });
+        }
+        Ok(dict)
+    }
+
+    fn validate(&self) -> Result<(), RvfError> {
+        if !matches!(self.algo, CompressionAlgo::Zstd | CompressionAlgo::Custom) {
+            return Err(RvfError::InvalidEnumValue {
+                type_name: "CompressionAlgo",
+                value: self.algo as u64,
+            });
+        }
+        if self.data.is_empty() || self.id.is_none() {
+            return Err(RvfError::InvalidDictionary {
+                reason: "dictionary is empty or has no id",
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Train a zstd dictionary of at most `dict_size` bytes from representative
+/// segment payloads.
+///
+/// Rejects fewer than [`MIN_DICT_SAMPLES`] samples, samples shorter than
+/// [`MIN_DICT_SAMPLE_SIZE`], a `dict_size` below [`MIN_DICT_SIZE`], and
+/// corpora smaller than the requested dictionary.
+#[cfg(feature = "zstd")]
+pub fn train_dictionary(samples: &[&[u8]], dict_size: usize) -> Result<Vec<u8>, RvfError> {
+    if samples.len() < MIN_DICT_SAMPLES {
+        return Err(RvfError::InvalidDictionary {
+            reason: "too few training samples",
+        });
+    }
+    if samples.iter().any(|s| s.len() < MIN_DICT_SAMPLE_SIZE) {
+        return Err(RvfError::InvalidDictionary {
+            reason: "training sample too small",
+        });
+    }
+    if dict_size < MIN_DICT_SIZE {
+        return Err(RvfError::InvalidDictionary {
+            reason: "requested dictionary size too small",
+        });
+    }
+    let total: usize = samples.iter().map(|s| s.len()).sum();
+    if total < dict_size {
+        return Err(RvfError::InvalidDictionary {
+            reason: "training corpus smaller than requested dictionary",
+        });
+    }
+
+    zstd::dict::from_samples(samples, dict_size).map_err(|_| RvfError::InvalidDictionary {
+        reason: "zstd dictionary training failed",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dict =
+            CompressionDictionary::new(CompressionAlgo::Zstd, b"shared prefix".to_vec()).unwrap();
+        let bytes = dict.to_bytes();
+        assert_eq!(bytes.len(), dict.wire_size());
+        assert_eq!(CompressionDictionary::from_bytes(&bytes).unwrap(), dict);
+    }
+
+    #[test]
+    fn rejects_tampered_payload() {
+        let dict =
+            CompressionDictionary::new(CompressionAlgo::Zstd, b"shared prefix".to_vec()).unwrap();
+
+        let mut bytes = dict.to_bytes();
+        bytes[0] ^= 0xFF;
+        assert!(matches!(
+            CompressionDictionary::from_bytes(&bytes),
+            Err(RvfError::BadMagic { .. })
+        ));
+
+        let mut bytes = dict.to_bytes();
+        *bytes.last_mut().unwrap() ^= 0xFF;
+        assert!(matches!(
+            CompressionDictionary::from_bytes(&bytes),
+            Err(RvfError::InvalidDictionary { .. })
+        ));
+
+        let bytes = dict.to_bytes();
+        assert!(matches!(
+            CompressionDictionary::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(RvfError::SizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn dictionary_id_assignment() {
+        let mut zstd_dict = Vec::new();
+        zstd_dict.extend_from_slice(&ZSTD_DICT_MAGIC.to_le_bytes());
+        zstd_dict.extend_from_slice(&0x1234_5678u32.to_le_bytes());
+        zstd_dict.extend_from_slice(b"entropy tables");
+        assert_eq!(
+            DictionaryId::for_dictionary(&zstd_dict),
+            DictionaryId(0x1234_5678)
+        );
+
+        let raw = DictionaryId::for_dictionary(b"raw content");
+        assert!(!raw.is_none());
+        assert_eq!(raw, DictionaryId::for_dictionary(b"raw content"));
+        assert_ne!(raw, DictionaryId::for_dictionary(b"other content"));
+    }
+
+    #[cfg(feature = "zstd")]
+    fn segment_sample(i: usize) -> Vec<u8> {
+        alloc::format!(
+            "{{\"segment\":\"vec\",\"id\":{i},\"dimension\":128,\"metric\":\"cosine\",\
+             \"quant\":\"scalar\",\"tags\":[\"sensor-{}\",\"region-{}\"],\"epoch\":{}}}",
+            i % 7,
+            i % 3,
+            i / 10
+        )
+        .into_bytes()
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn trained_dictionary_improves_compression() {
+        let corpus: Vec<Vec<u8>> = (0..400).map(segment_sample).collect();
+        let held_out: Vec<Vec<u8>> = (1000..1050).map(segment_sample).collect();
+        let samples: Vec<&[u8]> = corpus.iter().map(Vec::as_slice).collect();
+
+        let trained = train_dictionary(&samples, 1024).unwrap();
+        let dict = CompressionDictionary::new(CompressionAlgo::Zstd, trained).unwrap();
+        assert!(!dict.id.is_none());
+
+        let mut plain = zstd::bulk::Compressor::new(3).unwrap();
+        let mut primed = zstd::bulk::Compressor::with_dictionary(3, &dict.data).unwrap();
+        let plain_size: usize = held_out
+            .iter()
+            .map(|s| plain.compress(s).unwrap().len())
+            .sum();
+        let primed_size: usize = held_out
+            .iter()
+            .map(|s| primed.compress(s).unwrap().len())
+            .sum();
+        assert!(
+            primed_size < plain_size,
+            "dictionary {primed_size} vs plain {plain_size}"
+        );
+
+        // The stored dictionary still decompresses what it compressed.
+        let restored = CompressionDictionary::from_bytes(&dict.to_bytes()).unwrap();
+        let frame = primed.compress(&held_out[0]).unwrap();
+        let mut decompressor = zstd::bulk::Decompressor::with_dictionary(&restored.data).unwrap();
+        let out = decompressor.decompress(&frame, held_out[0].len()).unwrap();
+        assert_eq!(out, held_out[0]);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn training_rejects_insufficient_samples() {
+        let corpus: Vec<Vec<u8>> = (0..400).map(segment_sample).collect();
+        let samples: Vec<&[u8]> = corpus.iter().map(Vec::as_slice).collect();
+
+        assert!(matches!(
+            train_dictionary(&samples[..3], 1024),
+            Err(RvfError::InvalidDictionary { .. })
+        ));
+
+        let mut with_tiny = samples.clone();
+        with_tiny.push(b"tiny");
+        assert!(train_dictionary(&with_tiny, 1024).is_err());
+
+        assert!(train_dictionary(&samples[..MIN_DICT_SAMPLES], 1 << 20).is_err());
+        assert!(train_dictionary(&samples, 16).is_err());
+    }
+}
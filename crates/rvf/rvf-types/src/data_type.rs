@@ -1,4 +1,8 @@
-//! Vector data type discriminator.
+//! Vector data type discriminator and element conversions.
+//!
+//! `bf16` and `fp8` (E4M3) elements are stored as raw bits; the helpers
+//! here convert them to and from `f32` so the rest of the pipeline can
+//! normalize. All conversions round to nearest, ties to even.
 
 /// Identifies the numeric encoding of vector elements.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -23,6 +27,8 @@ pub enum DataType {
     PQ = 7,
     /// Custom encoding (see QUANT_SEG for details).
     Custom = 8,
+    /// 8-bit float, E4M3 layout (OCP FP8: bias 7, no infinities, max 448).
+    Fp8E4m3 = 9,
 }
 
 impl DataType {
@@ -33,6 +39,7 @@ impl DataType {
             Self::F16 => Some(16),
             Self::BF16 => Some(16),
             Self::I8 => Some(8),
+            Self::Fp8E4m3 => Some(8),
             Self::U8 => Some(8),
             Self::I4 => Some(4),
             Self::Binary => Some(1),
@@ -55,18 +62,99 @@ impl TryFrom<u8> for DataType {
             6 => Ok(Self::Binary),
             7 => Ok(Self::PQ),
             8 => Ok(Self::Custom),
+            9 => Ok(Self::Fp8E4m3),
             other => Err(other),
         }
     }
 }
 
+/// Largest finite E4M3 value.
+pub const FP8_E4M3_MAX: f32 = 448.0;
+
+/// Convert bfloat16 bits to `f32`. Exact.
+pub const fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits((bits as u32) << 16)
+}
+
+/// Convert an `f32` to bfloat16 bits.
+///
+/// Values beyond the bf16 range round to infinity, as in IEEE 754. NaN
+/// stays NaN.
+pub fn f32_to_bf16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    if value.is_nan() {
+        // Keep the sign and force a quiet NaN so truncation can't yield inf.
+        return ((bits >> 16) as u16) | 0x0040;
+    }
+    let round = 0x7FFF + ((bits >> 16) & 1);
+    (bits.wrapping_add(round) >> 16) as u16
+}
+
+/// Convert FP8 E4M3 bits to `f32`. Exact.
+pub fn fp8_e4m3_to_f32(bits: u8) -> f32 {
+    let sign = if bits & 0x80 != 0 { -1.0 } else { 1.0 };
+    let exp = (bits >> 3) & 0x0F;
+    let mantissa = (bits & 0x07) as f32;
+    if exp == 0x0F && bits & 0x07 == 0x07 {
+        return f32::NAN;
+    }
+    if exp == 0 {
+        // Subnormal: mantissa / 8 * 2^-6.
+        return sign * mantissa / 512.0;
+    }
+    let scale = f32::from_bits(((exp as u32) + 127 - 7) << 23);
+    sign * (1.0 + mantissa / 8.0) * scale
+}
+
+/// Convert an `f32` to FP8 E4M3 bits.
+///
+/// E4M3 has no infinities, so the conversion saturates: any value whose
+/// magnitude exceeds [`FP8_E4M3_MAX`], including infinity, becomes
+/// `±448`. NaN maps to the E4M3 NaN encoding (`0x7F`, sign preserved).
+/// Magnitudes below the smallest subnormal (2^-9) round to signed zero.
+pub fn f32_to_fp8_e4m3(value: f32) -> u8 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 24) & 0x80) as u8;
+    if value.is_nan() {
+        return sign | 0x7F;
+    }
+    let magnitude = f32::from_bits(bits & 0x7FFF_FFFF);
+    if magnitude >= FP8_E4M3_MAX {
+        return sign | 0x7E;
+    }
+
+    let f32_exp = (bits >> 23) & 0xFF;
+    if f32_exp < 127 - 6 {
+        // Subnormal range: quantize to multiples of 2^-9. A result of 8
+        // is the smallest normal, whose encoding is also 8.
+        let scaled = magnitude * 512.0;
+        let whole = scaled as u32;
+        let frac = scaled - whole as f32;
+        let q = if frac > 0.5 || (frac == 0.5 && whole & 1 == 1) {
+            whole + 1
+        } else {
+            whole
+        };
+        return sign | q as u8;
+    }
+
+    let mantissa = bits & 0x007F_FFFF;
+    let mut q = ((f32_exp + 7 - 127) << 3) | (mantissa >> 20);
+    let rem = mantissa & 0x000F_FFFF;
+    if rem > 0x0008_0000 || (rem == 0x0008_0000 && q & 1 == 1) {
+        // A mantissa carry bumps the exponent, which is the right result.
+        q += 1;
+    }
+    sign | q.min(0x7E) as u8
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn round_trip() {
-        for raw in 0..=8u8 {
+        for raw in 0..=9u8 {
             let dt = DataType::try_from(raw).unwrap();
             assert_eq!(dt as u8, raw);
         }
@@ -74,7 +162,7 @@ mod tests {
 
     #[test]
     fn invalid_value() {
-        assert_eq!(DataType::try_from(9), Err(9));
+        assert_eq!(DataType::try_from(10), Err(10));
         assert_eq!(DataType::try_from(255), Err(255));
     }
 
@@ -82,8 +170,69 @@ mod tests {
     fn bits_per_element() {
         assert_eq!(DataType::F32.bits_per_element(), Some(32));
         assert_eq!(DataType::F16.bits_per_element(), Some(16));
+        assert_eq!(DataType::Fp8E4m3.bits_per_element(), Some(8));
         assert_eq!(DataType::I4.bits_per_element(), Some(4));
         assert_eq!(DataType::Binary.bits_per_element(), Some(1));
         assert_eq!(DataType::PQ.bits_per_element(), None);
     }
+
+    const SAMPLES: [f32; 12] = [
+        0.0, 1.0, -1.0, 0.1, -0.333, 3.3, 0.0625, 7.5, -42.42, 100.0, 0.02, -0.875,
+    ];
+
+    #[test]
+    fn bf16_round_trip_error() {
+        for &x in &SAMPLES {
+            let y = bf16_to_f32(f32_to_bf16(x));
+            // 8 significant bits: relative error at most 2^-8.
+            assert!((y - x).abs() <= x.abs() / 256.0, "{x} -> {y}");
+        }
+        assert_eq!(f32_to_bf16(1.0), 0x3F80);
+        // Ties round to even: 1 + 2^-8 is halfway between 1 and 1 + 2^-7.
+        assert_eq!(f32_to_bf16(1.0 + 1.0 / 256.0), 0x3F80);
+        assert_eq!(bf16_to_f32(f32_to_bf16(f32::INFINITY)), f32::INFINITY);
+        assert_eq!(bf16_to_f32(f32_to_bf16(f32::MAX)), f32::INFINITY);
+        assert!(bf16_to_f32(f32_to_bf16(f32::NAN)).is_nan());
+    }
+
+    #[test]
+    fn fp8_round_trip_error() {
+        for &x in &SAMPLES {
+            let y = fp8_e4m3_to_f32(f32_to_fp8_e4m3(x));
+            if x.abs() >= 1.0 / 64.0 {
+                // 4 significant bits: relative error at most 2^-4.
+                assert!((y - x).abs() <= x.abs() / 16.0, "{x} -> {y}");
+            } else {
+                // Subnormal spacing is 2^-9.
+                assert!((y - x).abs() <= 1.0 / 1024.0, "{x} -> {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn fp8_codes_are_exact() {
+        for bits in 0..=255u8 {
+            let x = fp8_e4m3_to_f32(bits);
+            if bits & 0x7F == 0x7F {
+                assert!(x.is_nan());
+            } else {
+                assert_eq!(f32_to_fp8_e4m3(x), bits, "{bits:#04x} -> {x}");
+            }
+        }
+        assert_eq!(fp8_e4m3_to_f32(0x7E), FP8_E4M3_MAX);
+        assert_eq!(fp8_e4m3_to_f32(0x01), 1.0 / 512.0);
+    }
+
+    #[test]
+    fn fp8_saturates_on_overflow() {
+        assert_eq!(fp8_e4m3_to_f32(f32_to_fp8_e4m3(1000.0)), 448.0);
+        assert_eq!(fp8_e4m3_to_f32(f32_to_fp8_e4m3(-1e9)), -448.0);
+        assert_eq!(fp8_e4m3_to_f32(f32_to_fp8_e4m3(f32::INFINITY)), 448.0);
+        assert_eq!(fp8_e4m3_to_f32(f32_to_fp8_e4m3(f32::NEG_INFINITY)), -448.0);
+        // Just under the max rounds to it rather than past it.
+        assert_eq!(fp8_e4m3_to_f32(f32_to_fp8_e4m3(447.0)), 448.0);
+        assert!(fp8_e4m3_to_f32(f32_to_fp8_e4m3(f32::NAN)).is_nan());
+        assert_eq!(f32_to_fp8_e4m3(1e-6), 0x00);
+        assert_eq!(f32_to_fp8_e4m3(-1e-6), 0x80);
+    }
 }
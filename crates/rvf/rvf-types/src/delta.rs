@@ -4,7 +4,11 @@
 //! The DELTA_SEG stores sparse delta patches between clusters,
 //! enabling efficient incremental updates without full cluster rewrites.
 
+#[cfg(any(feature = "alloc", test))]
+use crate::error::ErrorCode;
 use crate::error::RvfError;
+#[cfg(any(feature = "alloc", test))]
+use alloc::vec::Vec;
 
 /// Magic number for `DeltaHeader`: "RVDL" in big-endian.
 pub const DELTA_MAGIC: u32 = 0x5256_444C;
@@ -20,6 +24,9 @@ pub enum DeltaEncoding {
     LowRank = 1,
     /// Full cluster patch (complete replacement).
     FullPatch = 2,
+    /// Per-element wrapping differences between int8-quantized vectors,
+    /// with zero runs collapsed (see [`encode_i8_delta`]).
+    QuantizedI8 = 3,
 }
 
 impl TryFrom<u8> for DeltaEncoding {
@@ -30,6 +37,7 @@ impl TryFrom<u8> for DeltaEncoding {
             0 => Ok(Self::SparseRows),
             1 => Ok(Self::LowRank),
             2 => Ok(Self::FullPatch),
+            3 => Ok(Self::QuantizedI8),
             _ => Err(RvfError::InvalidEnumValue {
                 type_name: "DeltaEncoding",
                 value: value as u64,
@@ -119,9 +127,101 @@ impl DeltaHeader {
     }
 }
 
+/// Encode `target` as a `QuantizedI8` delta against `base`.
+///
+/// Each element stores `target[i].wrapping_sub(base[i])`, so the delta is
+/// exact and reversible even when the difference overflows `i8`. The
+/// differences are written as a sequence of runs:
+///
+/// ```text
+/// [zero_run: u8] [literal_len: u8] [literal_len difference bytes]
+/// ```
+///
+/// A delta between identical vectors therefore costs two bytes per 255
+/// elements.
+#[cfg(any(feature = "alloc", test))]
+pub fn encode_i8_delta(base: &[i8], target: &[i8]) -> Result<Vec<u8>, RvfError> {
+    if base.len() != target.len() {
+        return Err(RvfError::SizeMismatch {
+            expected: base.len(),
+            got: target.len(),
+        });
+    }
+
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < base.len() {
+        let diff = |j: usize| target[j].wrapping_sub(base[j]);
+
+        let mut zeros = 0usize;
+        while i + zeros < base.len() && zeros < u8::MAX as usize && diff(i + zeros) == 0 {
+            zeros += 1;
+        }
+        i += zeros;
+
+        let mut literals = 0usize;
+        while i + literals < base.len() && literals < u8::MAX as usize && diff(i + literals) != 0 {
+            literals += 1;
+        }
+
+        out.push(zeros as u8);
+        out.push(literals as u8);
+        out.extend((i..i + literals).map(|j| diff(j) as u8));
+        i += literals;
+    }
+    Ok(out)
+}
+
+/// Reconstruct a vector by applying a `QuantizedI8` delta to `base`.
+///
+/// Returns `TruncatedSegment` if the payload ends mid-run and
+/// `SizeMismatch` if it does not describe exactly `base.len()` elements.
+#[cfg(any(feature = "alloc", test))]
+pub fn apply_i8_delta(base: &[i8], delta: &[u8]) -> Result<Vec<i8>, RvfError> {
+    let mut out = Vec::with_capacity(base.len());
+    let mut pos = 0;
+    while pos < delta.len() {
+        if pos + 2 > delta.len() {
+            return Err(RvfError::Code(ErrorCode::TruncatedSegment));
+        }
+        let zeros = delta[pos] as usize;
+        let literals = delta[pos + 1] as usize;
+        pos += 2;
+        if pos + literals > delta.len() {
+            return Err(RvfError::Code(ErrorCode::TruncatedSegment));
+        }
+        if out.len() + zeros + literals > base.len() {
+            return Err(RvfError::SizeMismatch {
+                expected: base.len(),
+                got: out.len() + zeros + literals,
+            });
+        }
+
+        let start = out.len();
+        out.extend_from_slice(&base[start..start + zeros]);
+        let start = out.len();
+        out.extend(
+            delta[pos..pos + literals]
+                .iter()
+                .zip(&base[start..start + literals])
+                .map(|(&d, &b)| b.wrapping_add(d as i8)),
+        );
+        pos += literals;
+    }
+
+    if out.len() != base.len() {
+        return Err(RvfError::SizeMismatch {
+            expected: base.len(),
+            got: out.len(),
+        });
+    }
+    Ok(out)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     fn sample_header() -> DeltaHeader {
         DeltaHeader {
@@ -197,7 +297,123 @@ mod tests {
         assert_eq!(DeltaEncoding::try_from(0), Ok(DeltaEncoding::SparseRows));
         assert_eq!(DeltaEncoding::try_from(1), Ok(DeltaEncoding::LowRank));
         assert_eq!(DeltaEncoding::try_from(2), Ok(DeltaEncoding::FullPatch));
-        assert!(DeltaEncoding::try_from(3).is_err());
+        assert_eq!(DeltaEncoding::try_from(3), Ok(DeltaEncoding::QuantizedI8));
+        assert!(DeltaEncoding::try_from(4).is_err());
         assert!(DeltaEncoding::try_from(0xFF).is_err());
     }
+
+    fn quantized(dim: usize, seed: u64) -> Vec<i8> {
+        let mut x = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        (0..dim)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x as i8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn i8_delta_wraps_on_overflow() {
+        let base = [i8::MAX, i8::MIN, 0, -1];
+        let target = [i8::MIN, i8::MAX, 0, 1];
+        let delta = encode_i8_delta(&base, &target).unwrap();
+        // Two literals, then a one-element zero run before the last literal.
+        assert_eq!(delta[..2], [0, 2]);
+        assert_eq!(delta[2..4], [1, 0xFF]);
+        assert_eq!(delta[4..], [1, 1, 2]);
+        assert_eq!(apply_i8_delta(&base, &delta).unwrap(), target);
+    }
+
+    #[test]
+    fn i8_zero_delta_is_tiny() {
+        let base = quantized(512, 7);
+        let delta = encode_i8_delta(&base, &base).unwrap();
+        assert_eq!(delta, [255, 0, 255, 0, 2, 0]);
+        assert_eq!(apply_i8_delta(&base, &delta).unwrap(), base);
+        assert!(encode_i8_delta(&[], &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn i8_delta_chain_reconstructs_512_dim() {
+        let dim = 512;
+        let header = DeltaHeader {
+            encoding: DeltaEncoding::QuantizedI8 as u8,
+            affected_count: 1,
+            ..sample_header()
+        };
+
+        // Snapshots drift a few elements at a time, with some large jumps
+        // that overflow i8 when subtracted.
+        let mut snapshots = vec![quantized(dim, 1)];
+        for link in 0..10u64 {
+            let mut next = snapshots.last().unwrap().clone();
+            let noise = quantized(dim, link + 100);
+            for (i, v) in next.iter_mut().enumerate() {
+                if (i as u64 + link).is_multiple_of(7) {
+                    *v = v.wrapping_add(noise[i]);
+                }
+            }
+            snapshots.push(next);
+        }
+
+        let chain: Vec<(DeltaHeader, Vec<u8>)> = snapshots
+            .windows(2)
+            .map(|w| {
+                let payload = encode_i8_delta(&w[0], &w[1]).unwrap();
+                let h = DeltaHeader {
+                    delta_size: payload.len() as u64,
+                    ..header
+                };
+                (h, payload)
+            })
+            .collect();
+        assert_eq!(chain.len(), 10);
+
+        let mut current = snapshots[0].clone();
+        for (h, payload) in &chain {
+            let decoded = DeltaHeader::from_bytes(&h.to_bytes()).unwrap();
+            assert_eq!(decoded.magic, DELTA_MAGIC);
+            assert_eq!(decoded.version, 1);
+            assert_eq!(
+                DeltaEncoding::try_from(decoded.encoding),
+                Ok(DeltaEncoding::QuantizedI8)
+            );
+            assert_eq!(decoded.delta_size as usize, payload.len());
+            assert!(payload.len() < dim);
+            current = apply_i8_delta(&current, payload).unwrap();
+        }
+        assert_eq!(current, snapshots[10]);
+    }
+
+    #[test]
+    fn i8_delta_rejects_malformed_payloads() {
+        let base = quantized(16, 3);
+        let target = quantized(16, 4);
+        assert!(matches!(
+            encode_i8_delta(&base, &target[..8]),
+            Err(RvfError::SizeMismatch {
+                expected: 16,
+                got: 8
+            })
+        ));
+
+        let delta = encode_i8_delta(&base, &target).unwrap();
+        assert_eq!(
+            apply_i8_delta(&base, &delta[..delta.len() - 1]),
+            Err(RvfError::Code(ErrorCode::TruncatedSegment))
+        );
+        assert!(matches!(
+            apply_i8_delta(&base[..8], &delta),
+            Err(RvfError::SizeMismatch { .. })
+        ));
+        assert!(matches!(
+            apply_i8_delta(&base, &[4, 0]),
+            Err(RvfError::SizeMismatch {
+                expected: 16,
+                got: 4
+            })
+        ));
+    }
 }
@@ -26,6 +26,8 @@ pub enum EbpfProgramType {
     Kprobe = 0x04,
     /// Cgroup socket buffer filter.
     CgroupSkb = 0x05,
+    /// General XDP packet filter (e.g. in front of the query API).
+    Xdp = 0x06,
     /// Custom program type.
     Custom = 0xFF,
 }
@@ -41,6 +43,7 @@ impl TryFrom<u8> for EbpfProgramType {
             0x03 => Ok(Self::Tracepoint),
             0x04 => Ok(Self::Kprobe),
             0x05 => Ok(Self::CgroupSkb),
+            0x06 => Ok(Self::Xdp),
             0xFF => Ok(Self::Custom),
             _ => Err(RvfError::InvalidEnumValue {
                 type_name: "EbpfProgramType",
@@ -50,6 +53,13 @@ impl TryFrom<u8> for EbpfProgramType {
     }
 }
 
+impl EbpfProgramType {
+    /// Returns true for program types that run at the XDP hook.
+    pub const fn is_xdp(self) -> bool {
+        matches!(self, Self::XdpDistance | Self::Xdp)
+    }
+}
+
 /// eBPF attach point classification.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -91,6 +101,48 @@ impl TryFrom<u8> for EbpfAttachType {
     }
 }
 
+/// XDP program return codes (`enum xdp_action` in the kernel UAPI).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum XdpAction {
+    /// Error path; the packet is dropped and a trace event raised.
+    Aborted = 0,
+    /// Drop the packet.
+    Drop = 1,
+    /// Pass the packet up the network stack.
+    Pass = 2,
+    /// Bounce the packet back out the receiving NIC.
+    Tx = 3,
+    /// Redirect the packet to another NIC, CPU or socket.
+    Redirect = 4,
+}
+
+impl TryFrom<u8> for XdpAction {
+    type Error = RvfError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Aborted),
+            1 => Ok(Self::Drop),
+            2 => Ok(Self::Pass),
+            3 => Ok(Self::Tx),
+            4 => Ok(Self::Redirect),
+            _ => Err(RvfError::InvalidEnumValue {
+                type_name: "XdpAction",
+                value: value as u64,
+            }),
+        }
+    }
+}
+
+/// Program flags: return codes the program may produce. Bit `n` set means
+/// the program may return action code `n`.
+pub const EBPF_FLAG_RETURN_ACTIONS_MASK: u32 = 0xFF;
+
+/// Return-action bits that are valid for XDP programs (`XdpAction` 0..=4).
+pub const EBPF_XDP_RETURN_ACTIONS: u32 = 0x1F;
+
 /// 64-byte header for EBPF_SEG payloads.
 ///
 /// Follows the standard 64-byte `SegmentHeader`. All multi-byte fields are
@@ -106,7 +158,7 @@ pub struct EbpfHeader {
     pub program_type: u8,
     /// eBPF attach point (see `EbpfAttachType`).
     pub attach_type: u8,
-    /// Bitfield flags for the eBPF program.
+    /// Bitfield flags for the eBPF program (see `EBPF_FLAG_*`).
     pub program_flags: u32,
     /// Number of BPF instructions (max 65535).
     pub insn_count: u16,
@@ -174,6 +226,50 @@ impl EbpfHeader {
             },
         })
     }
+
+    /// Declare that the program may return `action`.
+    pub fn declare_return_action(&mut self, action: u8) {
+        if action < 8 {
+            self.program_flags |= 1 << action;
+        }
+    }
+
+    /// Check that the program type, attach point and declared return
+    /// actions agree.
+    ///
+    /// XDP program types may only attach at `XdpIngress` (or not at all),
+    /// `XdpIngress` only accepts XDP program types, and XDP programs may
+    /// only declare `XdpAction` return codes.
+    pub fn validate(&self) -> Result<(), RvfError> {
+        let program_type = EbpfProgramType::try_from(self.program_type)?;
+        let attach_type = EbpfAttachType::try_from(self.attach_type)?;
+
+        let xdp_attach = attach_type == EbpfAttachType::XdpIngress;
+        if program_type.is_xdp() && !(xdp_attach || attach_type == EbpfAttachType::None) {
+            return Err(RvfError::InvalidEnumValue {
+                type_name: "EbpfAttachType",
+                value: self.attach_type as u64,
+            });
+        }
+        if xdp_attach && !program_type.is_xdp() {
+            return Err(RvfError::InvalidEnumValue {
+                type_name: "EbpfProgramType",
+                value: self.program_type as u64,
+            });
+        }
+
+        if program_type.is_xdp() {
+            let invalid =
+                self.program_flags & EBPF_FLAG_RETURN_ACTIONS_MASK & !EBPF_XDP_RETURN_ACTIONS;
+            if invalid != 0 {
+                return Err(RvfError::InvalidEnumValue {
+                    type_name: "XdpAction",
+                    value: invalid.trailing_zeros() as u64,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -278,8 +374,9 @@ mod tests {
             EbpfProgramType::try_from(0x05),
             Ok(EbpfProgramType::CgroupSkb)
         );
+        assert_eq!(EbpfProgramType::try_from(0x06), Ok(EbpfProgramType::Xdp));
         assert_eq!(EbpfProgramType::try_from(0xFF), Ok(EbpfProgramType::Custom));
-        assert!(EbpfProgramType::try_from(0x06).is_err());
+        assert!(EbpfProgramType::try_from(0x07).is_err());
         assert!(EbpfProgramType::try_from(0x80).is_err());
     }
 
@@ -330,4 +427,63 @@ mod tests {
         assert_eq!(decoded.program_size, 1_048_576);
         assert_eq!(decoded.insn_count, 65535);
     }
+
+    #[test]
+    fn xdp_filter_round_trip_and_validate() {
+        let mut h = EbpfHeader {
+            program_type: EbpfProgramType::Xdp as u8,
+            attach_type: EbpfAttachType::XdpIngress as u8,
+            ..sample_header()
+        };
+        h.declare_return_action(XdpAction::Drop as u8);
+        h.declare_return_action(XdpAction::Pass as u8);
+
+        let bytes = h.to_bytes();
+        assert_eq!(&bytes[0..4], &EBPF_MAGIC.to_le_bytes());
+        let decoded = EbpfHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            EbpfProgramType::try_from(decoded.program_type),
+            Ok(EbpfProgramType::Xdp)
+        );
+        assert_eq!(decoded.program_flags, 0b110);
+        decoded.validate().unwrap();
+        sample_header().validate().unwrap();
+    }
+
+    #[test]
+    fn xdp_validation_rejects_mismatches() {
+        let mut wrong_attach = sample_header();
+        wrong_attach.program_type = EbpfProgramType::Xdp as u8;
+        wrong_attach.attach_type = EbpfAttachType::TcIngress as u8;
+        assert!(matches!(
+            wrong_attach.validate(),
+            Err(RvfError::InvalidEnumValue {
+                type_name: "EbpfAttachType",
+                ..
+            })
+        ));
+
+        let mut wrong_program = sample_header();
+        wrong_program.program_type = EbpfProgramType::TcFilter as u8;
+        assert!(matches!(
+            wrong_program.validate(),
+            Err(RvfError::InvalidEnumValue {
+                type_name: "EbpfProgramType",
+                ..
+            })
+        ));
+
+        // TC_ACT_REDIRECT (7) is not an XDP action.
+        let mut bad_action = sample_header();
+        bad_action.declare_return_action(XdpAction::Pass as u8);
+        bad_action.declare_return_action(7);
+        assert_eq!(
+            bad_action.validate(),
+            Err(RvfError::InvalidEnumValue {
+                type_name: "XdpAction",
+                value: 7,
+            })
+        );
+        assert!(XdpAction::try_from(5).is_err());
+    }
 }
@@ -142,6 +142,8 @@ pub enum ErrorCode {
     KernelBindingMismatch = 0x0707,
     /// Double-root manifest is corrupt.
     DoubleRootCorrupt = 0x0708,
+    /// Membership filter has no room for another key.
+    MembershipFull = 0x0709,
 }
 
 impl ErrorCode {
@@ -247,6 +249,7 @@ impl TryFrom<u16> for ErrorCode {
             0x0706 => Ok(Self::GenerationStale),
             0x0707 => Ok(Self::KernelBindingMismatch),
             0x0708 => Ok(Self::DoubleRootCorrupt),
+            0x0709 => Ok(Self::MembershipFull),
 
             other => Err(other),
         }
@@ -275,6 +278,23 @@ pub enum RvfError {
         quality: crate::quality::ResponseQuality,
         reason: &'static str,
     },
+    /// A kernel header declares a contradictory flag combination.
+    /// `flags` holds the bits involved in the violated rule.
+    InvalidKernelConfig { flags: u32, reason: &'static str },
+    /// A TLV record is malformed (duplicate tag or wrong value shape).
+    InvalidTlv { tag: u16, reason: &'static str },
+    /// A compression dictionary is malformed or cannot be trained.
+    InvalidDictionary { reason: &'static str },
+    /// A vector's dimension does not match the store's.
+    DimensionMismatch { expected: usize, got: usize },
+    /// An ingested metadata entry violates the store's metadata schema.
+    MetadataSchemaViolation { field_id: u16, reason: &'static str },
+    /// A task an async API handed to the blocking pool did not complete
+    /// (`reason` is "panicked" or "cancelled").
+    TaskFailed { reason: &'static str },
+    /// An ingested id collides with an id already in use, or reserved,
+    /// in the store's id space.
+    IdConflict { id: u64, reason: &'static str },
 }
 
 impl core::fmt::Display for RvfError {
@@ -295,6 +315,23 @@ impl core::fmt::Display for RvfError {
             Self::QualityBelowThreshold { quality, reason } => {
                 write!(f, "quality below threshold ({quality:?}): {reason}")
             }
+            Self::InvalidKernelConfig { flags, reason } => {
+                write!(f, "invalid kernel config (flags 0x{flags:08X}): {reason}")
+            }
+            Self::InvalidTlv { tag, reason } => {
+                write!(f, "invalid TLV record (tag 0x{tag:04X}): {reason}")
+            }
+            Self::InvalidDictionary { reason } => {
+                write!(f, "invalid compression dictionary: {reason}")
+            }
+            Self::DimensionMismatch { expected, got } => {
+                write!(f, "dimension mismatch: expected {expected}, got {got}")
+            }
+            Self::MetadataSchemaViolation { field_id, reason } => {
+                write!(f, "metadata field {field_id} violates schema: {reason}")
+            }
+            Self::TaskFailed { reason } => write!(f, "blocking task {reason}"),
+            Self::IdConflict { id, reason } => write!(f, "id {id} conflicts: {reason}"),
         }
     }
 }
@@ -364,6 +401,7 @@ mod tests {
             (0x0706, ErrorCode::GenerationStale),
             (0x0707, ErrorCode::KernelBindingMismatch),
             (0x0708, ErrorCode::DoubleRootCorrupt),
+            (0x0709, ErrorCode::MembershipFull),
         ];
         for &(raw, expected) in codes {
             assert_eq!(ErrorCode::try_from(raw), Ok(expected), "code 0x{raw:04X}");
@@ -438,6 +476,7 @@ mod tests {
         assert_eq!(ErrorCode::GenerationStale as u16, 0x0706);
         assert_eq!(ErrorCode::KernelBindingMismatch as u16, 0x0707);
         assert_eq!(ErrorCode::DoubleRootCorrupt as u16, 0x0708);
+        assert_eq!(ErrorCode::MembershipFull as u16, 0x0709);
         // All COW errors should be category 0x07
         assert_eq!(ErrorCode::CowMapCorrupt.category(), 0x07);
         assert_eq!(ErrorCode::DoubleRootCorrupt.category(), 0x07);
@@ -2,7 +2,7 @@
 
 /// Filter operator discriminator.
 ///
-/// Comparison operators use the low nibble (0x00..0x07), logical combinators
+/// Comparison operators use the low nibble (0x00..0x08), logical combinators
 /// use the 0x10 range.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -24,6 +24,8 @@ pub enum FilterOp {
     In = 0x06,
     /// field in [low, high)
     Range = 0x07,
+    /// field between optional bounds, each inclusive or exclusive
+    Interval = 0x08,
     /// All children must match.
     And = 0x10,
     /// Any child must match.
@@ -59,6 +61,7 @@ impl TryFrom<u8> for FilterOp {
             0x05 => Ok(Self::Ge),
             0x06 => Ok(Self::In),
             0x07 => Ok(Self::Range),
+            0x08 => Ok(Self::Interval),
             0x10 => Ok(Self::And),
             0x11 => Ok(Self::Or),
             0x12 => Ok(Self::Not),
@@ -73,7 +76,7 @@ mod tests {
 
     #[test]
     fn round_trip_comparison_ops() {
-        for raw in 0x00..=0x07u8 {
+        for raw in 0x00..=0x08u8 {
             let op = FilterOp::try_from(raw).unwrap();
             assert_eq!(op as u8, raw);
             assert!(op.is_comparison());
@@ -93,7 +96,7 @@ mod tests {
 
     #[test]
     fn gap_values_are_invalid() {
-        for raw in 0x08..=0x0Fu8 {
+        for raw in 0x09..=0x0Fu8 {
             assert_eq!(FilterOp::try_from(raw), Err(raw));
         }
     }
@@ -288,6 +288,66 @@ impl KernelHeader {
             reserved_1: u32::from_le_bytes([data[0x7C], data[0x7D], data[0x7E], data[0x7F]]),
         })
     }
+
+    /// Check that the declared boot parameters are mutually consistent.
+    ///
+    /// Enum fields must hold known values, and the flag set must not
+    /// contradict the kernel type or API transport. The error names the
+    /// offending flags and the rule they break.
+    pub fn validate(&self) -> Result<(), RvfError> {
+        KernelArch::try_from(self.arch)?;
+        let kernel_type = KernelType::try_from(self.kernel_type)?;
+        let transport = ApiTransport::try_from(self.api_transport)?;
+        let flags = self.kernel_flags;
+        let invalid =
+            |flags: u32, reason: &'static str| Err(RvfError::InvalidKernelConfig { flags, reason });
+
+        let vm_only = KERNEL_FLAG_REQUIRES_KVM
+            | KERNEL_FLAG_REQUIRES_UEFI
+            | KERNEL_FLAG_HAS_VIRTIO_NET
+            | KERNEL_FLAG_HAS_VIRTIO_BLK
+            | KERNEL_FLAG_HAS_VSOCK;
+        if kernel_type == KernelType::WasiPreview2 && flags & vm_only != 0 {
+            return invalid(
+                flags & vm_only,
+                "WASI components cannot require VM devices or firmware",
+            );
+        }
+        if matches!(kernel_type, KernelType::Hermit | KernelType::TestStub)
+            && flags & KERNEL_FLAG_REQUIRES_UEFI != 0
+        {
+            return invalid(
+                KERNEL_FLAG_REQUIRES_UEFI,
+                "direct-boot microVM kernels cannot require UEFI",
+            );
+        }
+
+        let apis =
+            KERNEL_FLAG_HAS_QUERY_API | KERNEL_FLAG_HAS_INGEST_API | KERNEL_FLAG_HAS_ADMIN_API;
+        if transport == ApiTransport::None && flags & (apis | KERNEL_FLAG_HAS_NETWORKING) != 0 {
+            return invalid(
+                flags & (apis | KERNEL_FLAG_HAS_NETWORKING),
+                "API or networking flags set without an API transport",
+            );
+        }
+        if transport == ApiTransport::Vsock && flags & KERNEL_FLAG_HAS_VSOCK == 0 {
+            return invalid(KERNEL_FLAG_HAS_VSOCK, "vsock transport requires HAS_VSOCK");
+        }
+        if flags & KERNEL_FLAG_HAS_VIRTIO_NET != 0 && flags & KERNEL_FLAG_HAS_NETWORKING == 0 {
+            return invalid(
+                KERNEL_FLAG_HAS_VIRTIO_NET,
+                "virtio-net driver declared without HAS_NETWORKING",
+            );
+        }
+        if flags & KERNEL_FLAG_ATTESTATION_READY != 0 && flags & KERNEL_FLAG_REQUIRES_TEE == 0 {
+            return invalid(
+                KERNEL_FLAG_ATTESTATION_READY,
+                "attestation requires REQUIRES_TEE",
+            );
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -363,6 +423,23 @@ mod tests {
         assert_eq!(decoded.reserved_1, 0);
     }
 
+    #[test]
+    fn riscv64_relocatable_round_trip() {
+        let original = KernelHeader {
+            arch: KernelArch::Riscv64 as u8,
+            kernel_flags: KERNEL_FLAG_HAS_QUERY_API | KERNEL_FLAG_RELOCATABLE,
+            entry_point: 0x8020_0000, // OpenSBI hands off here on SiFive boards
+            ..sample_header()
+        };
+        original.validate().unwrap();
+
+        let decoded = KernelHeader::from_bytes(&original.to_bytes()).unwrap();
+        assert_eq!(KernelArch::try_from(decoded.arch), Ok(KernelArch::Riscv64));
+        assert_ne!(decoded.kernel_flags & KERNEL_FLAG_RELOCATABLE, 0);
+        assert_eq!(decoded.entry_point, 0x8020_0000);
+        decoded.validate().unwrap();
+    }
+
     #[test]
     fn bad_magic_returns_error() {
         let mut bytes = sample_header().to_bytes();
@@ -476,4 +553,79 @@ mod tests {
         // reserved_1 at 0x7C..0x80 should be zero
         assert_eq!(&bytes[0x7C..0x80], &[0, 0, 0, 0]);
     }
+
+    #[test]
+    fn validate_accepts_coherent_configs() {
+        sample_header().validate().unwrap();
+
+        let mut linux = sample_header();
+        linux.kernel_type = KernelType::MicroLinux as u8;
+        linux.api_transport = ApiTransport::Vsock as u8;
+        linux.kernel_flags = KERNEL_FLAG_REQUIRES_KVM
+            | KERNEL_FLAG_REQUIRES_UEFI
+            | KERNEL_FLAG_HAS_NETWORKING
+            | KERNEL_FLAG_HAS_VIRTIO_NET
+            | KERNEL_FLAG_HAS_VIRTIO_BLK
+            | KERNEL_FLAG_HAS_VSOCK
+            | KERNEL_FLAG_HAS_QUERY_API
+            | KERNEL_FLAG_REQUIRES_TEE
+            | KERNEL_FLAG_ATTESTATION_READY;
+        linux.validate().unwrap();
+
+        let mut batch = sample_header();
+        batch.kernel_type = KernelType::WasiPreview2 as u8;
+        batch.api_transport = ApiTransport::None as u8;
+        batch.kernel_flags = KERNEL_FLAG_COMPRESSED | KERNEL_FLAG_SIGNED;
+        batch.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_contradictions() {
+        // The error reports the flags involved in the violated rule.
+        let reject = |h: KernelHeader, flags: u32| match h.validate() {
+            Err(RvfError::InvalidKernelConfig { flags: got, reason }) => {
+                assert_eq!(got, flags, "{reason}");
+                assert!(!reason.is_empty());
+            }
+            other => panic!("expected InvalidKernelConfig, got {other:?}"),
+        };
+
+        let mut uefi = sample_header();
+        uefi.kernel_flags |= KERNEL_FLAG_REQUIRES_UEFI;
+        reject(uefi, KERNEL_FLAG_REQUIRES_UEFI);
+
+        let mut no_transport = sample_header();
+        no_transport.api_transport = ApiTransport::None as u8;
+        reject(no_transport, KERNEL_FLAG_HAS_QUERY_API);
+
+        let mut vsock = sample_header();
+        vsock.api_transport = ApiTransport::Vsock as u8;
+        reject(vsock, KERNEL_FLAG_HAS_VSOCK);
+
+        let mut wasi = sample_header();
+        wasi.kernel_type = KernelType::WasiPreview2 as u8;
+        wasi.kernel_flags |= KERNEL_FLAG_REQUIRES_KVM;
+        reject(wasi, KERNEL_FLAG_REQUIRES_KVM);
+
+        let mut nic = sample_header();
+        nic.kernel_flags |= KERNEL_FLAG_HAS_VIRTIO_NET;
+        reject(nic, KERNEL_FLAG_HAS_VIRTIO_NET);
+
+        let mut attest = sample_header();
+        attest.kernel_flags |= KERNEL_FLAG_ATTESTATION_READY;
+        reject(attest, KERNEL_FLAG_ATTESTATION_READY);
+    }
+
+    #[test]
+    fn validate_rejects_unknown_enums() {
+        let mut h = sample_header();
+        h.kernel_type = 0x42;
+        assert!(matches!(
+            h.validate(),
+            Err(RvfError::InvalidEnumValue {
+                type_name: "KernelType",
+                ..
+            })
+        ));
+    }
 }
@@ -18,6 +18,8 @@ pub mod agi_container;
 pub mod attestation;
 pub mod checksum;
 pub mod compression;
+#[cfg(any(feature = "alloc", test))]
+pub mod compression_dict;
 pub mod constants;
 pub mod cow_map;
 pub mod dashboard;
@@ -37,6 +39,8 @@ pub mod membership;
 pub mod profile;
 pub mod qr_seed;
 pub mod quality;
+#[cfg(any(feature = "alloc", test))]
+pub mod quant_dict;
 pub mod quant_type;
 pub mod refcount;
 pub mod security;
@@ -44,27 +48,43 @@ pub mod segment;
 pub mod segment_type;
 pub mod sha256;
 pub mod signature;
+pub mod tlv;
+pub mod versioned;
 pub mod wasm_bootstrap;
 pub mod witness;
 
 pub use agi_container::{
-    AgiContainerHeader, AuthorityLevel, CoherenceThresholds, ContainerError, ContainerSegments,
-    ExecutionMode, ResourceBudget, AGI_HAS_COHERENCE_GATES, AGI_HAS_DOMAIN_EXPANSION, AGI_HAS_EVAL,
-    AGI_HAS_KERNEL, AGI_HAS_ORCHESTRATOR, AGI_HAS_SKILLS, AGI_HAS_TOOLS, AGI_HAS_WASM,
-    AGI_HAS_WITNESS, AGI_HAS_WORLD_MODEL, AGI_HEADER_SIZE, AGI_MAGIC, AGI_MAX_CONTAINER_SIZE,
-    AGI_OFFLINE_CAPABLE, AGI_REPLAY_CAPABLE, AGI_SIGNED, AGI_TAG_AUTHORITY_CONFIG,
-    AGI_TAG_COST_CURVE, AGI_TAG_COUNTEREXAMPLES, AGI_TAG_DOMAIN_PROFILE, AGI_TAG_POLICY_KERNEL,
-    AGI_TAG_TRANSFER_PRIOR,
+    manifest_sections, AgiContainerHeader, AuthorityLevel, CoherenceThresholds, ContainerError,
+    ContainerSegments, ExecutionMode, ResourceBudget, AGI_HAS_COHERENCE_GATES,
+    AGI_HAS_DOMAIN_EXPANSION, AGI_HAS_EVAL, AGI_HAS_KERNEL, AGI_HAS_ORCHESTRATOR, AGI_HAS_SKILLS,
+    AGI_HAS_TOOLS, AGI_HAS_WASM, AGI_HAS_WITNESS, AGI_HAS_WORLD_MODEL, AGI_HEADER_SIZE, AGI_MAGIC,
+    AGI_MAX_CONTAINER_SIZE, AGI_OFFLINE_CAPABLE, AGI_REPLAY_CAPABLE, AGI_SIGNED,
+    AGI_TAG_AUTHORITY_CONFIG, AGI_TAG_COST_CURVE, AGI_TAG_COUNTEREXAMPLES, AGI_TAG_DOMAIN_PROFILE,
+    AGI_TAG_POLICY_KERNEL, AGI_TAG_TRANSFER_PRIOR,
 };
 pub use attestation::{AttestationHeader, AttestationWitnessType, TeePlatform, KEY_TYPE_TEE_BOUND};
 pub use checksum::ChecksumAlgo;
 pub use compression::CompressionAlgo;
+#[cfg(feature = "zstd")]
+pub use compression_dict::train_dictionary;
+#[cfg(any(feature = "alloc", test))]
+pub use compression_dict::{
+    CompressionDictionary, DictionaryId, COMPRESSION_DICT_HEADER_SIZE, COMPRESSION_DICT_MAGIC,
+    COMPRESSION_DICT_VERSION, MIN_DICT_SAMPLES, MIN_DICT_SAMPLE_SIZE, MIN_DICT_SIZE,
+};
 pub use constants::*;
 pub use cow_map::{CowMapEntry, CowMapHeader, MapFormat, COWMAP_MAGIC};
 pub use dashboard::{DashboardHeader, DASHBOARD_MAGIC, DASHBOARD_MAX_SIZE};
-pub use data_type::DataType;
+pub use data_type::{
+    bf16_to_f32, f32_to_bf16, f32_to_fp8_e4m3, fp8_e4m3_to_f32, DataType, FP8_E4M3_MAX,
+};
+#[cfg(any(feature = "alloc", test))]
+pub use delta::{apply_i8_delta, encode_i8_delta};
 pub use delta::{DeltaEncoding, DeltaHeader, DELTA_MAGIC};
-pub use ebpf::{EbpfAttachType, EbpfHeader, EbpfProgramType, EBPF_MAGIC};
+pub use ebpf::{
+    EbpfAttachType, EbpfHeader, EbpfProgramType, XdpAction, EBPF_FLAG_RETURN_ACTIONS_MASK,
+    EBPF_MAGIC, EBPF_XDP_RETURN_ACTIONS,
+};
 #[cfg(feature = "ed25519")]
 pub use ed25519::{
     ct_eq_sig, ed25519_sign, ed25519_verify, Ed25519Keypair,
@@ -83,10 +103,12 @@ pub use kernel::{
     KERNEL_FLAG_REQUIRES_UEFI, KERNEL_FLAG_SIGNED, KERNEL_MAGIC,
 };
 pub use kernel_binding::KernelBinding;
+#[cfg(feature = "ed25519")]
+pub use lineage::verify_signed_lineage;
 pub use lineage::{
-    DerivationType, FileIdentity, LineageRecord, LINEAGE_RECORD_SIZE, WITNESS_DERIVATION,
-    WITNESS_LINEAGE_MERGE, WITNESS_LINEAGE_SNAPSHOT, WITNESS_LINEAGE_TRANSFORM,
-    WITNESS_LINEAGE_VERIFY,
+    ConflictKind, DerivationType, FileIdentity, LineageRecord, SignedLineageRecord,
+    LINEAGE_RECORD_SIZE, SIGNED_LINEAGE_RECORD_SIZE, WITNESS_DERIVATION, WITNESS_LINEAGE_MERGE,
+    WITNESS_LINEAGE_SNAPSHOT, WITNESS_LINEAGE_TRANSFORM, WITNESS_LINEAGE_VERIFY,
 };
 pub use manifest::{
     CentroidPtr, EntrypointPtr, HotCachePtr, Level0Root, PrefetchMapPtr, QuantDictPtr, TopLayerPtr,
@@ -103,17 +125,29 @@ pub use quality::{
     FallbackPath, IndexLayersUsed, QualityPreference, ResponseQuality, RetrievalQuality,
     SafetyNetBudget, SearchEvidenceSummary,
 };
+#[cfg(feature = "alloc")]
+pub use quant_dict::{
+    QuantDictionary, QUANT_DICT_HEADER_SIZE, QUANT_DICT_MAGIC, QUANT_DICT_VERSION,
+};
 pub use quant_type::QuantType;
+#[cfg(any(feature = "alloc", test))]
+pub use quant_type::{pack_int4, unpack_int4, PackedInt4};
+pub use quant_type::{INT4_MAX, INT4_MIN};
 pub use refcount::{RefcountHeader, REFCOUNT_MAGIC};
-pub use security::{HardeningFields, SecurityError, SecurityPolicy};
+pub use security::{HardeningFields, HardeningFieldsBuilder, SecurityError, SecurityPolicy};
 pub use segment::SegmentHeader;
 pub use segment_type::SegmentType;
 pub use sha256::{hmac_sha256, sha256, Sha256};
 pub use signature::{SignatureAlgo, SignatureFooter};
+#[cfg(feature = "alloc")]
+pub use tlv::TlvWriter;
+pub use tlv::{TlvReader, TlvRecord, TLV_HEADER_SIZE};
+pub use versioned::{Versioned, WireEnum};
 pub use wasm_bootstrap::{
-    WasmHeader, WasmRole, WasmTarget, WASM_FEAT_BULK_MEMORY, WASM_FEAT_EXCEPTION_HANDLING,
-    WASM_FEAT_GC, WASM_FEAT_MULTI_VALUE, WASM_FEAT_REFERENCE_TYPES, WASM_FEAT_SIMD,
-    WASM_FEAT_TAIL_CALL, WASM_FEAT_THREADS, WASM_MAGIC,
+    wasm_preamble_is_component, WasmHeader, WasmRole, WasmTarget, WASM_BINARY_MAGIC,
+    WASM_COMPONENT_PREAMBLE, WASM_CORE_PREAMBLE, WASM_FEAT_BULK_MEMORY, WASM_FEAT_COMPONENT_MODEL,
+    WASM_FEAT_EXCEPTION_HANDLING, WASM_FEAT_GC, WASM_FEAT_MULTI_VALUE, WASM_FEAT_REFERENCE_TYPES,
+    WASM_FEAT_SIMD, WASM_FEAT_TAIL_CALL, WASM_FEAT_THREADS, WASM_MAGIC, WASM_PREAMBLE_LEN,
 };
 pub use witness::{
     GovernanceMode, PolicyCheck, Scorecard, TaskOutcome, WitnessHeader, WITNESS_HEADER_SIZE,
@@ -3,6 +3,10 @@
 //! Each RVF file carries a `FileIdentity` in the Level0Root reserved area,
 //! enabling provenance chains: parent→child→grandchild with hash verification.
 
+#[cfg(feature = "ed25519")]
+use crate::error::ErrorCode;
+use crate::error::RvfError;
+
 /// Derivation type describing how a child file was produced from its parent.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -44,6 +48,25 @@ impl TryFrom<u8> for DerivationType {
     }
 }
 
+/// Why two lineage records for the same output cannot be merged as-is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConflictKind {
+    /// The records claim different parents (id or manifest hash) for the
+    /// same output.
+    DivergentParent,
+    /// The records derive the output from the same parent in different ways.
+    TransformMismatch {
+        /// Derivation recorded by `self`.
+        ours: DerivationType,
+        /// Derivation recorded by the other record.
+        theirs: DerivationType,
+    },
+    /// Same parent and derivation type, but the transforms applied differ
+    /// (different mutation counts).
+    DivergentTransform,
+}
+
 /// File identity embedded in the Level0Root reserved area at offset 0xF00.
 ///
 /// Exactly 68 bytes, fitting within the 252-byte reserved area.
@@ -196,11 +219,210 @@ impl LineageRecord {
         }
     }
 
+    /// Check whether `other` records an incompatible derivation of the same
+    /// output.
+    ///
+    /// Records for different `file_id`s never conflict. Two `Merge` records
+    /// may name different parents, since a merged file has one record per
+    /// contributing parent. Timestamps and descriptions are ignored.
+    pub fn detect_conflict(&self, other: &LineageRecord) -> Option<ConflictKind> {
+        if self.file_id != other.file_id {
+            return None;
+        }
+        let same_parent =
+            self.parent_id == other.parent_id && self.parent_hash == other.parent_hash;
+        if !same_parent {
+            let both_merge = self.derivation_type == DerivationType::Merge
+                && other.derivation_type == DerivationType::Merge;
+            return (!both_merge).then_some(ConflictKind::DivergentParent);
+        }
+        if self.derivation_type != other.derivation_type {
+            return Some(ConflictKind::TransformMismatch {
+                ours: self.derivation_type,
+                theirs: other.derivation_type,
+            });
+        }
+        if self.mutation_count != other.mutation_count {
+            return Some(ConflictKind::DivergentTransform);
+        }
+        None
+    }
+
     /// Get the description as a string slice.
     pub fn description_str(&self) -> &str {
         let len = (self.description_len as usize).min(47);
         core::str::from_utf8(&self.description[..len]).unwrap_or("")
     }
+
+    /// Serialize to the canonical 128-byte layout.
+    pub fn to_bytes(&self) -> [u8; LINEAGE_RECORD_SIZE] {
+        let mut buf = [0u8; LINEAGE_RECORD_SIZE];
+        buf[0x00..0x10].copy_from_slice(&self.file_id);
+        buf[0x10..0x20].copy_from_slice(&self.parent_id);
+        buf[0x20..0x40].copy_from_slice(&self.parent_hash);
+        buf[0x40] = self.derivation_type as u8;
+        // 3 bytes padding at 0x41..0x44
+        buf[0x44..0x48].copy_from_slice(&self.mutation_count.to_le_bytes());
+        buf[0x48..0x50].copy_from_slice(&self.timestamp_ns.to_le_bytes());
+        buf[0x50] = self.description_len;
+        let desc_len = (self.description_len as usize).min(47);
+        buf[0x51..0x51 + desc_len].copy_from_slice(&self.description[..desc_len]);
+        buf
+    }
+
+    /// Deserialize from the canonical 128-byte layout.
+    pub fn from_bytes(data: &[u8; LINEAGE_RECORD_SIZE]) -> Result<Self, RvfError> {
+        let mut file_id = [0u8; 16];
+        file_id.copy_from_slice(&data[0x00..0x10]);
+        let mut parent_id = [0u8; 16];
+        parent_id.copy_from_slice(&data[0x10..0x20]);
+        let mut parent_hash = [0u8; 32];
+        parent_hash.copy_from_slice(&data[0x20..0x40]);
+
+        let derivation_type =
+            DerivationType::try_from(data[0x40]).map_err(|v| RvfError::InvalidEnumValue {
+                type_name: "DerivationType",
+                value: v as u64,
+            })?;
+
+        let mutation_count = u32::from_le_bytes([data[0x44], data[0x45], data[0x46], data[0x47]]);
+        let mut ts = [0u8; 8];
+        ts.copy_from_slice(&data[0x48..0x50]);
+        let timestamp_ns = u64::from_le_bytes(ts);
+        let description_len = data[0x50].min(47);
+        let mut description = [0u8; 47];
+        description[..description_len as usize]
+            .copy_from_slice(&data[0x51..0x51 + description_len as usize]);
+
+        Ok(Self {
+            file_id,
+            parent_id,
+            parent_hash,
+            derivation_type,
+            mutation_count,
+            timestamp_ns,
+            description_len,
+            description,
+        })
+    }
+}
+
+/// Size of a serialized SignedLineageRecord.
+pub const SIGNED_LINEAGE_RECORD_SIZE: usize = LINEAGE_RECORD_SIZE + 32 + 64;
+
+/// A lineage record with an optional Ed25519 signature by its author.
+///
+/// The signature covers the record's canonical bytes followed by the
+/// author's public key, so neither the derivation nor the claimed author
+/// can be changed without invalidating it.
+///
+/// Layout:
+/// | Offset | Size | Field               |
+/// |--------|------|---------------------|
+/// | 0x00   | 128  | record              |
+/// | 0x80   | 32   | author public key   |
+/// | 0xA0   | 64   | signature (0 = none)|
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedLineageRecord {
+    /// The lineage record being attested.
+    pub record: LineageRecord,
+    /// Ed25519 public key of the claimed author (all zeros if unsigned).
+    pub author: [u8; 32],
+    /// Ed25519 signature, or `None` if the record is unsigned.
+    pub signature: Option<[u8; 64]>,
+}
+
+impl SignedLineageRecord {
+    /// Wrap a record without signing it.
+    pub fn unsigned(record: LineageRecord) -> Self {
+        Self {
+            record,
+            author: [0u8; 32],
+            signature: None,
+        }
+    }
+
+    /// Whether the record carries a signature.
+    pub fn is_signed(&self) -> bool {
+        self.signature.is_some()
+    }
+
+    /// The bytes covered by the signature: record bytes then author key.
+    pub fn signing_message(&self) -> [u8; LINEAGE_RECORD_SIZE + 32] {
+        let mut msg = [0u8; LINEAGE_RECORD_SIZE + 32];
+        msg[..LINEAGE_RECORD_SIZE].copy_from_slice(&self.record.to_bytes());
+        msg[LINEAGE_RECORD_SIZE..].copy_from_slice(&self.author);
+        msg
+    }
+
+    /// Sign the record with an Ed25519 secret key, recording its public key
+    /// as the author.
+    #[cfg(feature = "ed25519")]
+    pub fn sign(&mut self, secret: &[u8; 32]) {
+        self.author = crate::ed25519::Ed25519Keypair::from_secret(secret).public_key();
+        self.signature = Some(crate::ed25519::ed25519_sign(
+            secret,
+            &self.signing_message(),
+        ));
+    }
+
+    /// Whether the record is signed by `public` and the signature is valid.
+    #[cfg(feature = "ed25519")]
+    pub fn verify(&self, public: &[u8; 32]) -> bool {
+        match &self.signature {
+            Some(sig) => {
+                self.author == *public
+                    && crate::ed25519::ed25519_verify(public, &self.signing_message(), sig)
+            }
+            None => false,
+        }
+    }
+
+    /// Serialize to a fixed 224-byte array.
+    pub fn to_bytes(&self) -> [u8; SIGNED_LINEAGE_RECORD_SIZE] {
+        let mut buf = [0u8; SIGNED_LINEAGE_RECORD_SIZE];
+        buf[..LINEAGE_RECORD_SIZE].copy_from_slice(&self.record.to_bytes());
+        buf[0x80..0xA0].copy_from_slice(&self.author);
+        if let Some(sig) = &self.signature {
+            buf[0xA0..0xE0].copy_from_slice(sig);
+        }
+        buf
+    }
+
+    /// Deserialize from a 224-byte array. An all-zero signature field
+    /// decodes as unsigned.
+    pub fn from_bytes(data: &[u8; SIGNED_LINEAGE_RECORD_SIZE]) -> Result<Self, RvfError> {
+        let mut record_bytes = [0u8; LINEAGE_RECORD_SIZE];
+        record_bytes.copy_from_slice(&data[..LINEAGE_RECORD_SIZE]);
+        let record = LineageRecord::from_bytes(&record_bytes)?;
+        let mut author = [0u8; 32];
+        author.copy_from_slice(&data[0x80..0xA0]);
+        let mut sig = [0u8; 64];
+        sig.copy_from_slice(&data[0xA0..0xE0]);
+        Ok(Self {
+            record,
+            author,
+            signature: (sig != [0u8; 64]).then_some(sig),
+        })
+    }
+}
+
+/// Verify a chain of signed lineage records ordered from root to leaf.
+///
+/// Every record must carry a valid signature from its claimed author, and
+/// each record's `parent_id` must be the previous record's `file_id`.
+/// Whether those authors are trusted is left to the caller.
+#[cfg(feature = "ed25519")]
+pub fn verify_signed_lineage(records: &[SignedLineageRecord]) -> Result<(), RvfError> {
+    for (i, signed) in records.iter().enumerate() {
+        if !signed.verify(&signed.author) {
+            return Err(RvfError::Code(ErrorCode::InvalidSignature));
+        }
+        if i > 0 && signed.record.parent_id != records[i - 1].record.file_id {
+            return Err(RvfError::Code(ErrorCode::LineageBroken));
+        }
+    }
+    Ok(())
 }
 
 // ---- Witness type constants for lineage entries ----
@@ -326,6 +548,197 @@ mod tests {
         assert_eq!(record.description_len, 47);
     }
 
+    #[test]
+    fn lineage_record_bytes_round_trip() {
+        let record = LineageRecord::new(
+            [1u8; 16],
+            [2u8; 16],
+            [3u8; 32],
+            DerivationType::Quantize,
+            9,
+            1_700_000_000_000_000_000,
+            "re-quantized",
+        );
+        let bytes = record.to_bytes();
+        assert_eq!(LineageRecord::from_bytes(&bytes), Ok(record.clone()));
+
+        let signed = SignedLineageRecord::unsigned(record);
+        let decoded = SignedLineageRecord::from_bytes(&signed.to_bytes()).unwrap();
+        assert_eq!(decoded, signed);
+    }
+
+    fn record(
+        file_id: u8,
+        parent_id: u8,
+        derivation: DerivationType,
+        mutations: u32,
+    ) -> LineageRecord {
+        LineageRecord::new(
+            [file_id; 16],
+            [parent_id; 16],
+            [parent_id; 32],
+            derivation,
+            mutations,
+            1_000,
+            "",
+        )
+    }
+
+    #[test]
+    fn clean_merge_has_no_conflict() {
+        let ours = record(2, 1, DerivationType::Filter, 4);
+        let mut theirs = ours.clone();
+        theirs.timestamp_ns = 2_000;
+        assert_eq!(ours.detect_conflict(&theirs), None);
+
+        // Different outputs never conflict.
+        let other_output = record(3, 9, DerivationType::Quantize, 1);
+        assert_eq!(ours.detect_conflict(&other_output), None);
+
+        // A merged file has one record per contributing parent.
+        let left = record(5, 1, DerivationType::Merge, 0);
+        let right = record(5, 2, DerivationType::Merge, 0);
+        assert_eq!(left.detect_conflict(&right), None);
+    }
+
+    #[test]
+    fn divergent_parent_conflict() {
+        let ours = record(2, 1, DerivationType::Filter, 4);
+        let theirs = record(2, 7, DerivationType::Filter, 4);
+        assert_eq!(
+            ours.detect_conflict(&theirs),
+            Some(ConflictKind::DivergentParent)
+        );
+
+        // Same parent id but a different parent manifest.
+        let mut rehashed = ours.clone();
+        rehashed.parent_hash[0] ^= 0xFF;
+        assert_eq!(
+            ours.detect_conflict(&rehashed),
+            Some(ConflictKind::DivergentParent)
+        );
+    }
+
+    #[test]
+    fn transform_mismatch_conflict() {
+        let ours = record(2, 1, DerivationType::Filter, 4);
+        let theirs = record(2, 1, DerivationType::Quantize, 4);
+        assert_eq!(
+            ours.detect_conflict(&theirs),
+            Some(ConflictKind::TransformMismatch {
+                ours: DerivationType::Filter,
+                theirs: DerivationType::Quantize,
+            })
+        );
+
+        let more_mutations = record(2, 1, DerivationType::Filter, 5);
+        assert_eq!(
+            ours.detect_conflict(&more_mutations),
+            Some(ConflictKind::DivergentTransform)
+        );
+    }
+
+    #[test]
+    fn unsigned_record_reports_no_signature() {
+        let signed = SignedLineageRecord::unsigned(LineageRecord::new(
+            [1u8; 16],
+            [0u8; 16],
+            [0u8; 32],
+            DerivationType::Clone,
+            0,
+            0,
+            "",
+        ));
+        assert!(!signed.is_signed());
+        assert_eq!(signed.signature, None);
+        #[cfg(feature = "ed25519")]
+        assert!(!signed.verify(&signed.author));
+    }
+
+    #[cfg(feature = "ed25519")]
+    mod signing {
+        use super::*;
+        use crate::ed25519::Ed25519Keypair;
+
+        const ROOT_KEY: [u8; 32] = [0x11; 32];
+        const CHILD_KEY: [u8; 32] = [0x22; 32];
+
+        fn derived(file_id: u8, parent_id: u8, derivation: DerivationType) -> LineageRecord {
+            LineageRecord::new(
+                [file_id; 16],
+                [parent_id; 16],
+                [0xAB; 32],
+                derivation,
+                3,
+                1_000,
+                "derived",
+            )
+        }
+
+        #[test]
+        fn signed_record_verifies() {
+            let mut signed = SignedLineageRecord::unsigned(derived(2, 1, DerivationType::Filter));
+            signed.sign(&ROOT_KEY);
+            let public = Ed25519Keypair::from_secret(&ROOT_KEY).public_key();
+
+            assert!(signed.is_signed());
+            assert_eq!(signed.author, public);
+            assert!(signed.verify(&public));
+            let other = Ed25519Keypair::from_secret(&CHILD_KEY).public_key();
+            assert!(!signed.verify(&other));
+
+            // The signature survives serialization.
+            let decoded = SignedLineageRecord::from_bytes(&signed.to_bytes()).unwrap();
+            assert!(decoded.verify(&public));
+        }
+
+        #[test]
+        fn tampered_derivation_type_fails() {
+            let mut signed = SignedLineageRecord::unsigned(derived(2, 1, DerivationType::Filter));
+            signed.sign(&ROOT_KEY);
+            let public = signed.author;
+
+            signed.record.derivation_type = DerivationType::Merge;
+            assert!(!signed.verify(&public));
+
+            // Swapping in another author's key breaks it too.
+            signed.record.derivation_type = DerivationType::Filter;
+            signed.author = Ed25519Keypair::from_secret(&CHILD_KEY).public_key();
+            assert!(!signed.verify(&signed.author));
+        }
+
+        #[test]
+        fn chain_checks_signatures_and_links() {
+            let mut root = SignedLineageRecord::unsigned(derived(1, 0, DerivationType::Clone));
+            root.sign(&ROOT_KEY);
+            let mut child = SignedLineageRecord::unsigned(derived(2, 1, DerivationType::Filter));
+            child.sign(&CHILD_KEY);
+            assert_eq!(
+                verify_signed_lineage(&[root.clone(), child.clone()]),
+                Ok(())
+            );
+
+            let mut orphan = SignedLineageRecord::unsigned(derived(3, 9, DerivationType::Filter));
+            orphan.sign(&CHILD_KEY);
+            assert_eq!(
+                verify_signed_lineage(&[root.clone(), orphan]),
+                Err(RvfError::Code(ErrorCode::LineageBroken))
+            );
+
+            let unsigned = SignedLineageRecord::unsigned(child.record.clone());
+            assert_eq!(
+                verify_signed_lineage(&[root.clone(), unsigned]),
+                Err(RvfError::Code(ErrorCode::InvalidSignature))
+            );
+
+            child.record.mutation_count += 1;
+            assert_eq!(
+                verify_signed_lineage(&[root, child]),
+                Err(RvfError::Code(ErrorCode::InvalidSignature))
+            );
+        }
+    }
+
     #[test]
     fn witness_type_constants() {
         assert_eq!(WITNESS_DERIVATION, 0x09);
@@ -52,7 +52,8 @@ pub struct CentroidPtr {
 
 /// Inline hotset pointer for quantization dictionary.
 ///
-/// Offset 0x068 in Level0Root.
+/// Offset 0x068 in Level0Root. The referenced bytes are a serialized
+/// `QuantDictionary` (see `quant_dict`).
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
@@ -18,6 +18,12 @@ pub enum FilterType {
     Bitmap = 0,
     /// Roaring bitmap (compressed sparse).
     RoaringBitmap = 1,
+    /// Immutable XOR filter with 8-bit fingerprints (~0.39% false positives).
+    Xor8 = 2,
+    /// Immutable XOR filter with 16-bit fingerprints (~0.0015% false positives).
+    Xor16 = 3,
+    /// Cuckoo filter with 16-bit fingerprints; supports deletion.
+    Cuckoo = 4,
 }
 
 impl TryFrom<u8> for FilterType {
@@ -27,6 +33,9 @@ impl TryFrom<u8> for FilterType {
         match value {
             0 => Ok(Self::Bitmap),
             1 => Ok(Self::RoaringBitmap),
+            2 => Ok(Self::Xor8),
+            3 => Ok(Self::Xor16),
+            4 => Ok(Self::Cuckoo),
             _ => Err(RvfError::InvalidEnumValue {
                 type_name: "FilterType",
                 value: value as u64,
@@ -263,7 +272,10 @@ mod tests {
     fn filter_type_try_from() {
         assert_eq!(FilterType::try_from(0), Ok(FilterType::Bitmap));
         assert_eq!(FilterType::try_from(1), Ok(FilterType::RoaringBitmap));
-        assert!(FilterType::try_from(2).is_err());
+        assert_eq!(FilterType::try_from(2), Ok(FilterType::Xor8));
+        assert_eq!(FilterType::try_from(3), Ok(FilterType::Xor16));
+        assert_eq!(FilterType::try_from(4), Ok(FilterType::Cuckoo));
+        assert!(FilterType::try_from(5).is_err());
         assert!(FilterType::try_from(0xFF).is_err());
     }
 
@@ -31,6 +31,31 @@ impl TryFrom<u8> for ProfileId {
     }
 }
 
+impl ProfileId {
+    /// Every built-in hardware profile, in discriminant order.
+    pub const ALL: [ProfileId; 4] = [Self::Generic, Self::Core, Self::Hot, Self::Full];
+
+    /// Iterate over every built-in hardware profile.
+    pub fn all() -> impl Iterator<Item = ProfileId> {
+        Self::ALL.into_iter()
+    }
+
+    /// Stable human-readable name for this profile.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Generic => "generic",
+            Self::Core => "core",
+            Self::Hot => "hot",
+            Self::Full => "full",
+        }
+    }
+
+    /// Look up a profile by its stable name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().find(|p| eq_ignore_ascii_case(name.as_bytes(), p.name().as_bytes()))
+    }
+}
+
 /// Domain profile discriminator (semantic overlay on the RVF substrate).
 ///
 /// Stored in the root manifest `profile_id` field and declared in PROFILE_SEG.
@@ -51,6 +76,15 @@ pub enum DomainProfile {
 }
 
 impl DomainProfile {
+    /// Every built-in domain profile, in discriminant order.
+    pub const ALL: [DomainProfile; 5] = [
+        Self::Generic,
+        Self::Rvdna,
+        Self::RvText,
+        Self::RvGraph,
+        Self::RvVision,
+    ];
+
     /// The 4-byte magic number associated with each domain profile.
     pub const fn magic(self) -> u32 {
         match self {
@@ -133,6 +167,23 @@ mod tests {
         assert_eq!(ProfileId::try_from(4), Err(4));
     }
 
+    #[test]
+    fn profile_id_name_round_trip() {
+        assert_eq!(ProfileId::all().count(), 4);
+        for p in ProfileId::all() {
+            assert_eq!(ProfileId::from_name(p.name()), Some(p));
+            assert_eq!(ProfileId::try_from(p as u8), Ok(p));
+        }
+        assert_eq!(ProfileId::Hot.name(), "hot");
+        assert_eq!(ProfileId::from_name("FULL"), Some(ProfileId::Full));
+    }
+
+    #[test]
+    fn profile_id_unknown_name() {
+        assert_eq!(ProfileId::from_name("genomics"), None);
+        assert_eq!(ProfileId::from_name(""), None);
+    }
+
     #[test]
     fn domain_profile_round_trip() {
         for raw in 0..=4u8 {
@@ -148,6 +148,14 @@ pub enum FallbackPath {
     SafetyNetSelective = 0x03,
     /// Safety net budget exhausted before completion.
     SafetyNetBudgetExhausted = 0x04,
+    /// Query deadline reached; results cover only the scanned vectors.
+    DeadlineTruncated = 0x05,
+    /// Safety net fell back to an exhaustive scan of every stored vector.
+    SafetyNetExhaustive = 0x06,
+    /// Safety net scanned on coarse-quantized distances, then re-ranked.
+    SafetyNetCoarseQuantized = 0x07,
+    /// Safety net declined to scan; results are the index candidates only.
+    SafetyNetAborted = 0x08,
 }
 
 /// Structured reason for quality degradation.
@@ -166,6 +174,8 @@ pub enum DegradationReason {
     },
     /// Index layer not yet loaded.
     IndexNotLoaded { available: IndexLayersUsed },
+    /// Caller-supplied query deadline reached before the scan completed.
+    DeadlineExceeded { scanned: u64, total: u64 },
 }
 
 /// Which budget cap was hit.
@@ -372,6 +382,9 @@ mod tests {
     fn fallback_path_repr() {
         assert_eq!(FallbackPath::None as u8, 0x00);
         assert_eq!(FallbackPath::SafetyNetBudgetExhausted as u8, 0x04);
+        assert_eq!(FallbackPath::SafetyNetExhaustive as u8, 0x06);
+        assert_eq!(FallbackPath::SafetyNetCoarseQuantized as u8, 0x07);
+        assert_eq!(FallbackPath::SafetyNetAborted as u8, 0x08);
     }
 
     #[test]
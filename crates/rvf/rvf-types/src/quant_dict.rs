@@ -0,0 +1,277 @@
+//! Quantization dictionary payload referenced by `QuantDictPtr`.
+//!
+//! The dictionary holds the per-subspace centroid codebooks used by product
+//! quantization. It is serialized as a fixed 24-byte header followed by the
+//! centroids as little-endian `f32` values, subspace-major:
+//!
+//! ```text
+//! Offset  Type   Field
+//! 0x00    u32    magic (QUANT_DICT_MAGIC, "RVQD")
+//! 0x04    u16    version
+//! 0x06    u8     quant_type (QuantType)
+//! 0x07    u8     bits_per_code
+//! 0x08    u32    subvectors
+//! 0x0C    u32    centroids per subspace
+//! 0x10    u32    centroid dimension (sub_dim)
+//! 0x14    u32    reserved (must be zero)
+//! 0x18    [f32]  subvectors * centroids * sub_dim values
+//! ```
+//!
+//! Requires the `alloc` feature because the codebooks are heap-allocated.
+
+use crate::error::{ErrorCode, RvfError};
+use crate::quant_type::QuantType;
+use alloc::vec::Vec;
+
+/// Magic number for a quantization dictionary: "RVQD" in big-endian.
+pub const QUANT_DICT_MAGIC: u32 = 0x5256_5144;
+
+/// Current quantization dictionary format version.
+pub const QUANT_DICT_VERSION: u16 = 1;
+
+/// Size of the fixed dictionary header in bytes.
+pub const QUANT_DICT_HEADER_SIZE: usize = 24;
+
+/// Centroid codebooks for product quantization.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuantDictionary {
+    /// Quantization method these codebooks belong to.
+    pub quant_type: QuantType,
+    /// Bits used to encode one centroid index (1..=8).
+    pub bits_per_code: u8,
+    /// Centroid dimension of every subspace.
+    pub sub_dim: u32,
+    /// Codebooks: `codebooks[subspace][centroid]` has `sub_dim` values.
+    pub codebooks: Vec<Vec<Vec<f32>>>,
+}
+
+impl QuantDictionary {
+    /// Create a dictionary, validating that the codebooks are rectangular and
+    /// consistent with `quant_type` and `bits_per_code`.
+    pub fn new(
+        quant_type: QuantType,
+        bits_per_code: u8,
+        sub_dim: u32,
+        codebooks: Vec<Vec<Vec<f32>>>,
+    ) -> Result<Self, RvfError> {
+        let dict = Self {
+            quant_type,
+            bits_per_code,
+            sub_dim,
+            codebooks,
+        };
+        dict.validate()?;
+        Ok(dict)
+    }
+
+    /// Number of subspaces (subvectors).
+    pub fn subvectors(&self) -> u32 {
+        self.codebooks.len() as u32
+    }
+
+    /// Number of centroids in each subspace codebook.
+    pub fn centroids_per_subspace(&self) -> u32 {
+        self.codebooks.first().map_or(0, |cb| cb.len() as u32)
+    }
+
+    /// Total serialized size in bytes.
+    pub fn wire_size(&self) -> usize {
+        QUANT_DICT_HEADER_SIZE
+            + self.subvectors() as usize
+                * self.centroids_per_subspace() as usize
+                * self.sub_dim as usize
+                * 4
+    }
+
+    /// Serialize the dictionary (header + centroids) to bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.wire_size());
+        buf.extend_from_slice(&QUANT_DICT_MAGIC.to_le_bytes());
+        buf.extend_from_slice(&QUANT_DICT_VERSION.to_le_bytes());
+        buf.push(self.quant_type as u8);
+        buf.push(self.bits_per_code);
+        buf.extend_from_slice(&self.subvectors().to_le_bytes());
+        buf.extend_from_slice(&self.centroids_per_subspace().to_le_bytes());
+        buf.extend_from_slice(&self.sub_dim.to_le_bytes());
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        for centroid in self.codebooks.iter().flatten() {
+            for &v in centroid {
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    /// Deserialize a dictionary, rejecting it unless it was written for the
+    /// `declared` quantization type recorded in the manifest.
+    pub fn from_bytes(data: &[u8], declared: QuantType) -> Result<Self, RvfError> {
+        if data.len() < QUANT_DICT_HEADER_SIZE {
+            return Err(RvfError::SizeMismatch {
+                expected: QUANT_DICT_HEADER_SIZE,
+                got: data.len(),
+            });
+        }
+        let read_u32 = |off: usize| {
+            u32::from_le_bytes([data[off], data[off + 1], data[off + 2], data[off + 3]])
+        };
+
+        let magic = read_u32(0x00);
+        if magic != QUANT_DICT_MAGIC {
+            return Err(RvfError::BadMagic {
+                expected: QUANT_DICT_MAGIC,
+                got: magic,
+            });
+        }
+        let version = u16::from_le_bytes([data[0x04], data[0x05]]);
+        if version != QUANT_DICT_VERSION {
+            return Err(RvfError::Code(ErrorCode::InvalidVersion));
+        }
+        let quant_type =
+            QuantType::try_from(data[0x06]).map_err(|v| RvfError::InvalidEnumValue {
+                type_name: "QuantType",
+                value: v as u64,
+            })?;
+        if quant_type != declared {
+            return Err(RvfError::Code(ErrorCode::InvalidManifest));
+        }
+        let bits_per_code = data[0x07];
+        let subvectors = read_u32(0x08) as usize;
+        let k = read_u32(0x0C) as usize;
+        let sub_dim = read_u32(0x10);
+        if read_u32(0x14) != 0 {
+            return Err(RvfError::Code(ErrorCode::InvalidManifest));
+        }
+
+        let values = subvectors
+            .checked_mul(k)
+            .and_then(|n| n.checked_mul(sub_dim as usize))
+            .ok_or(RvfError::Code(ErrorCode::InvalidManifest))?;
+        let expected = values
+            .checked_mul(4)
+            .and_then(|n| n.checked_add(QUANT_DICT_HEADER_SIZE))
+            .ok_or(RvfError::Code(ErrorCode::InvalidManifest))?;
+        if data.len() != expected {
+            return Err(RvfError::SizeMismatch {
+                expected,
+                got: data.len(),
+            });
+        }
+
+        let mut floats = data[QUANT_DICT_HEADER_SIZE..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+        let codebooks = (0..subvectors)
+            .map(|_| {
+                (0..k)
+                    .map(|_| floats.by_ref().take(sub_dim as usize).collect())
+                    .collect()
+            })
+            .collect();
+
+        Self::new(quant_type, bits_per_code, sub_dim, codebooks)
+    }
+
+    fn validate(&self) -> Result<(), RvfError> {
+        if !matches!(self.quant_type, QuantType::Product | QuantType::ResidualPq) {
+            return Err(RvfError::InvalidEnumValue {
+                type_name: "QuantType",
+                value: self.quant_type as u64,
+            });
+        }
+        if self.bits_per_code == 0 || self.bits_per_code > 8 {
+            return Err(RvfError::Code(ErrorCode::InvalidManifest));
+        }
+        let k = self.centroids_per_subspace() as usize;
+        if self.codebooks.is_empty() || self.sub_dim == 0 || k == 0 {
+            return Err(RvfError::Code(ErrorCode::InvalidManifest));
+        }
+        if k > 1usize << self.bits_per_code {
+            return Err(RvfError::Code(ErrorCode::InvalidManifest));
+        }
+        let rectangular = self
+            .codebooks
+            .iter()
+            .all(|cb| cb.len() == k && cb.iter().all(|c| c.len() == self.sub_dim as usize));
+        if !rectangular {
+            return Err(RvfError::Code(ErrorCode::InvalidManifest));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn sample_dict() -> QuantDictionary {
+        // 2 subspaces, 4 centroids each (2 bits), sub_dim 3.
+        let codebooks = (0..2)
+            .map(|s| {
+                (0..4)
+                    .map(|c| vec![s as f32, c as f32, (s * 4 + c) as f32 * 0.5])
+                    .collect()
+            })
+            .collect();
+        QuantDictionary::new(QuantType::Product, 2, 3, codebooks).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let dict = sample_dict();
+        let bytes = dict.to_bytes();
+        assert_eq!(bytes.len(), dict.wire_size());
+        assert_eq!(bytes.len(), QUANT_DICT_HEADER_SIZE + 2 * 4 * 3 * 4);
+
+        let decoded = QuantDictionary::from_bytes(&bytes, QuantType::Product).unwrap();
+        assert_eq!(decoded, dict);
+        assert_eq!(decoded.subvectors(), 2);
+        assert_eq!(decoded.centroids_per_subspace(), 4);
+    }
+
+    #[test]
+    fn magic_bytes_match_ascii() {
+        assert_eq!(&QUANT_DICT_MAGIC.to_be_bytes(), b"RVQD");
+    }
+
+    #[test]
+    fn rejects_quant_type_mismatch() {
+        let bytes = sample_dict().to_bytes();
+        assert_eq!(
+            QuantDictionary::from_bytes(&bytes, QuantType::ResidualPq),
+            Err(RvfError::Code(ErrorCode::InvalidManifest))
+        );
+        assert_eq!(
+            QuantDictionary::from_bytes(&bytes, QuantType::Scalar),
+            Err(RvfError::Code(ErrorCode::InvalidManifest))
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_and_corrupt() {
+        let bytes = sample_dict().to_bytes();
+        assert!(matches!(
+            QuantDictionary::from_bytes(&bytes[..bytes.len() - 4], QuantType::Product),
+            Err(RvfError::SizeMismatch { .. })
+        ));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = 0;
+        assert!(matches!(
+            QuantDictionary::from_bytes(&bad_magic, QuantType::Product),
+            Err(RvfError::BadMagic { .. })
+        ));
+
+        // More centroids than 2 bits can address.
+        let mut bad_bits = bytes;
+        bad_bits[0x07] = 1;
+        assert!(QuantDictionary::from_bytes(&bad_bits, QuantType::Product).is_err());
+    }
+
+    #[test]
+    fn new_rejects_ragged_codebooks() {
+        let codebooks = vec![vec![vec![0.0; 2]; 2], vec![vec![0.0; 2]; 3]];
+        assert!(QuantDictionary::new(QuantType::Product, 2, 2, codebooks).is_err());
+        assert!(QuantDictionary::new(QuantType::Scalar, 2, 2, vec![vec![vec![0.0; 2]]]).is_err());
+    }
+}
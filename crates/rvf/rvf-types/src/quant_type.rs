@@ -1,5 +1,10 @@
 //! Quantization type discriminator for QUANT_SEG payloads.
 
+#[cfg(any(feature = "alloc", test))]
+use crate::error::RvfError;
+#[cfg(any(feature = "alloc", test))]
+use alloc::vec::Vec;
+
 /// Identifies the quantization method stored in a QUANT_SEG.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -13,6 +18,8 @@ pub enum QuantType {
     BinaryThreshold = 2,
     /// Residual product quantization.
     ResidualPq = 3,
+    /// Signed 4-bit scalar quantization, two values per byte.
+    Int4 = 4,
 }
 
 impl TryFrom<u8> for QuantType {
@@ -24,18 +31,123 @@ impl TryFrom<u8> for QuantType {
             1 => Ok(Self::Product),
             2 => Ok(Self::BinaryThreshold),
             3 => Ok(Self::ResidualPq),
+            4 => Ok(Self::Int4),
             other => Err(other),
         }
     }
 }
 
+/// Smallest value representable in int4.
+pub const INT4_MIN: i8 = -8;
+
+/// Largest value representable in int4.
+pub const INT4_MAX: i8 = 7;
+
+/// Signed values packed as 4-bit two's-complement nibbles, two per byte.
+///
+/// Element `2i` is the low nibble of byte `i` and element `2i + 1` the high
+/// nibble. An odd count leaves the final high nibble zero, so the element
+/// count is kept alongside the bytes.
+#[cfg(any(feature = "alloc", test))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackedInt4 {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl PackedInt4 {
+    /// Wrap bytes read back from storage, checking that `len` values fit.
+    pub fn from_bytes(bytes: Vec<u8>, len: usize) -> Result<Self, RvfError> {
+        check_int4_len(&bytes, len)?;
+        Ok(Self { bytes, len })
+    }
+
+    /// Number of packed values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no values are packed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed bytes, `len.div_ceil(2)` of them.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consume the packing and return its bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Sign-extend every value back to `i8`.
+    pub fn unpack(&self) -> Vec<i8> {
+        unpack_nibbles(&self.bytes, self.len)
+    }
+}
+
+/// Pack signed values into 4-bit nibbles. Values outside
+/// `INT4_MIN..=INT4_MAX` are clamped.
+#[cfg(any(feature = "alloc", test))]
+pub fn pack_int4(values: &[i8]) -> PackedInt4 {
+    let nibble = |v: i8| (v.clamp(INT4_MIN, INT4_MAX) as u8) & 0x0F;
+    let bytes = values
+        .chunks(2)
+        .map(|pair| {
+            let high = pair.get(1).map_or(0, |&v| nibble(v));
+            nibble(pair[0]) | (high << 4)
+        })
+        .collect();
+    PackedInt4 {
+        bytes,
+        len: values.len(),
+    }
+}
+
+/// Unpack `count` sign-extended values from raw packed bytes.
+///
+/// Fails with `SizeMismatch` if `packed` is not exactly
+/// `count.div_ceil(2)` bytes long.
+#[cfg(any(feature = "alloc", test))]
+pub fn unpack_int4(packed: &[u8], count: usize) -> Result<Vec<i8>, RvfError> {
+    check_int4_len(packed, count)?;
+    Ok(unpack_nibbles(packed, count))
+}
+
+#[cfg(any(feature = "alloc", test))]
+fn check_int4_len(packed: &[u8], count: usize) -> Result<(), RvfError> {
+    let expected = count.div_ceil(2);
+    if packed.len() != expected {
+        return Err(RvfError::SizeMismatch {
+            expected,
+            got: packed.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(any(feature = "alloc", test))]
+fn unpack_nibbles(packed: &[u8], count: usize) -> Vec<i8> {
+    (0..count)
+        .map(|i| {
+            let byte = packed[i / 2];
+            let nibble = if i % 2 == 0 { byte << 4 } else { byte & 0xF0 };
+            // Arithmetic shift sign-extends the nibble.
+            (nibble as i8) >> 4
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn round_trip() {
-        for raw in 0..=3u8 {
+        for raw in 0..=4u8 {
             let qt = QuantType::try_from(raw).unwrap();
             assert_eq!(qt as u8, raw);
         }
@@ -43,7 +155,51 @@ mod tests {
 
     #[test]
     fn invalid_value() {
-        assert_eq!(QuantType::try_from(4), Err(4));
+        assert_eq!(QuantType::try_from(5), Err(5));
         assert_eq!(QuantType::try_from(255), Err(255));
     }
+
+    #[test]
+    fn int4_round_trip_even_and_odd() {
+        let even: Vec<i8> = (INT4_MIN..=INT4_MAX).collect();
+        let packed = pack_int4(&even);
+        assert_eq!(packed.len(), even.len());
+        assert_eq!(packed.as_bytes().len(), 8);
+        assert_eq!(packed.unpack(), even);
+
+        let odd = [3i8, -1, -8, 7, 0];
+        let packed = pack_int4(&odd);
+        assert_eq!(packed.len(), 5);
+        assert_eq!(packed.as_bytes().len(), 3);
+        assert_eq!(packed.as_bytes()[2] >> 4, 0, "padding nibble must be zero");
+        assert_eq!(unpack_int4(packed.as_bytes(), packed.len()).unwrap(), odd);
+
+        let restored = PackedInt4::from_bytes(packed.clone().into_bytes(), 5).unwrap();
+        assert_eq!(restored, packed);
+
+        assert!(pack_int4(&[]).is_empty());
+        assert!(unpack_int4(&[], 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn int4_clamps_out_of_range() {
+        let packed = pack_int4(&[100, -100, 8, -9, i8::MAX, i8::MIN]);
+        assert_eq!(
+            packed.unpack(),
+            [INT4_MAX, INT4_MIN, INT4_MAX, INT4_MIN, INT4_MAX, INT4_MIN]
+        );
+    }
+
+    #[test]
+    fn int4_unpack_rejects_mismatched_count() {
+        assert_eq!(
+            unpack_int4(&[0x00], 3),
+            Err(RvfError::SizeMismatch {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert!(unpack_int4(&[0x00, 0x00], 1).is_err());
+        assert!(PackedInt4::from_bytes(alloc::vec![0x00], 4).is_err());
+    }
 }
@@ -3,6 +3,8 @@
 //! Defines the `SecurityPolicy` mount policy (default: Strict) and
 //! structured `SecurityError` diagnostics for deterministic failure reasons.
 
+use crate::attestation::TeePlatform;
+
 /// Manifest signature verification policy.
 ///
 /// Controls how the runtime handles unsigned or invalid signatures
@@ -103,6 +105,12 @@ pub enum SecurityError {
         /// Byte offset of the Level 1 manifest.
         manifest_offset: u64,
     },
+
+    /// Hardening toggles form a contradictory combination.
+    InvalidHardening {
+        /// Which combination was rejected.
+        reason: &'static str,
+    },
 }
 
 impl core::fmt::Display for SecurityError {
@@ -152,6 +160,9 @@ impl core::fmt::Display for SecurityError {
                     "Level 1 manifest invalid signature at offset 0x{manifest_offset:X}"
                 )
             }
+            Self::InvalidHardening { reason } => {
+                write!(f, "invalid hardening configuration: {reason}")
+            }
         }
     }
 }
@@ -160,6 +171,11 @@ impl core::fmt::Display for SecurityError {
 ///
 /// 96 bytes total: 5 content hashes (16 bytes each) + centroid_epoch (4) +
 /// max_epoch_drift (4) + reserved (8).
+///
+/// The first two reserved bytes hold hardening toggles: `reserved[0]` is a
+/// set of `FLAG_*` bits and `reserved[1]` the `TeePlatform` discriminant
+/// (meaningful only with `FLAG_HAS_TEE_PLATFORM`). Prefer
+/// [`HardeningFields::builder`] over setting them by hand.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
@@ -178,7 +194,7 @@ pub struct HardeningFields {
     pub centroid_epoch: u32,
     /// Maximum allowed drift before forced recompute.
     pub max_epoch_drift: u32,
-    /// Reserved for future hardening fields.
+    /// Hardening toggles (bytes 0-1), rest reserved for future fields.
     pub reserved: [u8; 8],
 }
 
@@ -190,6 +206,20 @@ impl HardeningFields {
     /// and double-root mechanism (17 bytes).
     pub const RESERVED_OFFSET: usize = 109;
 
+    /// Manifest must carry a valid signature.
+    pub const FLAG_REQUIRE_SIGNED: u8 = 0x01;
+    /// Debug-mode TEEs and debug entrypoints are refused.
+    pub const FLAG_DISABLE_DEBUG: u8 = 0x02;
+    /// A platform attestation must be verified before mount.
+    pub const FLAG_ENFORCE_ATTESTATION: u8 = 0x04;
+    /// `reserved[1]` holds a `TeePlatform`.
+    pub const FLAG_HAS_TEE_PLATFORM: u8 = 0x08;
+
+    /// Start a builder with the most conservative settings.
+    pub fn builder() -> HardeningFieldsBuilder {
+        HardeningFieldsBuilder::default()
+    }
+
     /// Create zeroed hardening fields.
     pub const fn zeroed() -> Self {
         Self {
@@ -280,6 +310,150 @@ impl HardeningFields {
     pub fn is_epoch_drift_exceeded(&self, manifest_epoch: u32) -> bool {
         self.epoch_drift(manifest_epoch) > self.max_epoch_drift
     }
+
+    /// Returns true if the manifest must be signed.
+    pub const fn requires_signed(&self) -> bool {
+        self.reserved[0] & Self::FLAG_REQUIRE_SIGNED != 0
+    }
+
+    /// Returns true if debug mode is refused.
+    pub const fn debug_disabled(&self) -> bool {
+        self.reserved[0] & Self::FLAG_DISABLE_DEBUG != 0
+    }
+
+    /// Returns true if a platform attestation is required.
+    pub const fn enforces_attestation(&self) -> bool {
+        self.reserved[0] & Self::FLAG_ENFORCE_ATTESTATION != 0
+    }
+
+    /// The configured TEE platform, if any.
+    pub fn tee_platform(&self) -> Option<TeePlatform> {
+        if self.reserved[0] & Self::FLAG_HAS_TEE_PLATFORM == 0 {
+            return None;
+        }
+        TeePlatform::try_from(self.reserved[1]).ok()
+    }
+}
+
+/// Fluent builder for [`HardeningFields`].
+///
+/// Starts from the most conservative settings: signatures required, debug
+/// disabled, and the tightest centroid drift (0). Attestation is off until a
+/// TEE platform is configured, since it cannot be enforced without one.
+#[derive(Clone, Debug)]
+pub struct HardeningFieldsBuilder {
+    fields: HardeningFields,
+    require_signed: bool,
+    disable_debug: bool,
+    enforce_attestation: bool,
+    tee_platform: Option<TeePlatform>,
+}
+
+impl Default for HardeningFieldsBuilder {
+    fn default() -> Self {
+        Self {
+            fields: HardeningFields {
+                max_epoch_drift: 0,
+                ..HardeningFields::zeroed()
+            },
+            require_signed: true,
+            disable_debug: true,
+            enforce_attestation: false,
+            tee_platform: None,
+        }
+    }
+}
+
+impl HardeningFieldsBuilder {
+    /// Require a valid manifest signature (default).
+    pub fn require_signed(mut self) -> Self {
+        self.require_signed = true;
+        self
+    }
+
+    /// Allow unsigned manifests.
+    pub fn allow_unsigned(mut self) -> Self {
+        self.require_signed = false;
+        self
+    }
+
+    /// Refuse debug-mode TEEs and entrypoints (default).
+    pub fn disable_debug(mut self) -> Self {
+        self.disable_debug = true;
+        self
+    }
+
+    /// Permit debug mode.
+    pub fn allow_debug(mut self) -> Self {
+        self.disable_debug = false;
+        self
+    }
+
+    /// Require a verified platform attestation before mount.
+    pub fn enforce_attestation(mut self) -> Self {
+        self.enforce_attestation = true;
+        self
+    }
+
+    /// TEE platform the file is expected to run on.
+    pub fn tee_platform(mut self, platform: TeePlatform) -> Self {
+        self.tee_platform = Some(platform);
+        self
+    }
+
+    /// Centroid epoch at build time.
+    pub fn centroid_epoch(mut self, epoch: u32) -> Self {
+        self.fields.centroid_epoch = epoch;
+        self
+    }
+
+    /// Maximum allowed centroid drift before forced recompute.
+    pub fn max_epoch_drift(mut self, drift: u32) -> Self {
+        self.fields.max_epoch_drift = drift;
+        self
+    }
+
+    /// Validate the combination and produce the fields.
+    ///
+    /// Attestation requires a TEE platform, a non-debug TEE, and signed
+    /// manifests; any other combination of toggles is accepted.
+    pub fn build(self) -> Result<HardeningFields, SecurityError> {
+        if self.enforce_attestation {
+            if self.tee_platform.is_none() {
+                return Err(SecurityError::InvalidHardening {
+                    reason: "attestation enforced without a TEE platform",
+                });
+            }
+            if !self.disable_debug {
+                return Err(SecurityError::InvalidHardening {
+                    reason: "attestation enforced while debug is allowed",
+                });
+            }
+            if !self.require_signed {
+                return Err(SecurityError::InvalidHardening {
+                    reason: "attestation enforced on unsigned manifests",
+                });
+            }
+        }
+
+        let mut fields = self.fields;
+        let mut flags = 0u8;
+        if self.require_signed {
+            flags |= HardeningFields::FLAG_REQUIRE_SIGNED;
+        }
+        if self.disable_debug {
+            flags |= HardeningFields::FLAG_DISABLE_DEBUG;
+        }
+        if self.enforce_attestation {
+            flags |= HardeningFields::FLAG_ENFORCE_ATTESTATION;
+        }
+        if let Some(platform) = self.tee_platform {
+            flags |= HardeningFields::FLAG_HAS_TEE_PLATFORM;
+            fields.reserved[1] = platform as u8;
+        }
+        fields.reserved[0] = flags;
+        Ok(fields)
+    }
 }
 
 #[cfg(test)]
@@ -370,6 +544,52 @@ mod tests {
         assert!(fields.is_epoch_drift_exceeded(100));
     }
 
+    #[test]
+    fn builder_conservative_defaults() {
+        let fields = HardeningFields::builder().build().unwrap();
+        assert!(fields.requires_signed());
+        assert!(fields.debug_disabled());
+        assert!(!fields.enforces_attestation());
+        assert_eq!(fields.tee_platform(), None);
+        assert_eq!(fields.max_epoch_drift, 0);
+
+        let decoded = HardeningFields::from_bytes(&fields.to_bytes());
+        assert!(decoded.requires_signed());
+        assert!(decoded.debug_disabled());
+
+        let attested = HardeningFields::builder()
+            .tee_platform(TeePlatform::SevSnp)
+            .enforce_attestation()
+            .max_epoch_drift(8)
+            .build()
+            .unwrap();
+        assert!(attested.enforces_attestation());
+        assert_eq!(attested.tee_platform(), Some(TeePlatform::SevSnp));
+        assert_eq!(attested.max_epoch_drift, 8);
+    }
+
+    #[test]
+    fn builder_rejects_attestation_without_tee() {
+        let err = HardeningFields::builder()
+            .enforce_attestation()
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            SecurityError::InvalidHardening {
+                reason: "attestation enforced without a TEE platform",
+            }
+        );
+        assert!(alloc::format!("{err}").contains("TEE platform"));
+
+        let debug = HardeningFields::builder()
+            .tee_platform(TeePlatform::Tdx)
+            .enforce_attestation()
+            .allow_debug()
+            .build();
+        assert!(matches!(debug, Err(SecurityError::InvalidHardening { .. })));
+    }
+
     #[test]
     fn security_error_display() {
         let err = SecurityError::UnsignedManifest {
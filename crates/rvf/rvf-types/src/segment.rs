@@ -1,5 +1,8 @@
 //! 64-byte segment header for the RVF format.
 
+use crate::constants::{SEGMENT_HEADER_SIZE, SEGMENT_MAGIC};
+use crate::error::RvfError;
+
 /// The fixed 64-byte header that precedes every segment payload.
 ///
 /// Layout matches the wire format exactly (repr(C), little-endian fields).
@@ -68,12 +71,89 @@ impl SegmentHeader {
     pub const fn is_valid_magic(&self) -> bool {
         self.magic == crate::constants::SEGMENT_MAGIC
     }
+
+    /// Serialize the header to its 64-byte on-disk form.
+    ///
+    /// All multi-byte fields are little-endian:
+    ///
+    /// | Offset | Size | Field              |
+    /// |--------|------|--------------------|
+    /// | 0x00   | 4    | `magic`            |
+    /// | 0x04   | 1    | `version`          |
+    /// | 0x05   | 1    | `seg_type`         |
+    /// | 0x06   | 2    | `flags`            |
+    /// | 0x08   | 8    | `segment_id`       |
+    /// | 0x10   | 8    | `payload_length`   |
+    /// | 0x18   | 8    | `timestamp_ns`     |
+    /// | 0x20   | 1    | `checksum_algo`    |
+    /// | 0x21   | 1    | `compression`      |
+    /// | 0x22   | 2    | `reserved_0`       |
+    /// | 0x24   | 4    | `reserved_1`       |
+    /// | 0x28   | 16   | `content_hash`     |
+    /// | 0x38   | 4    | `uncompressed_len` |
+    /// | 0x3C   | 4    | `alignment_pad`    |
+    pub fn to_bytes(&self) -> [u8; SEGMENT_HEADER_SIZE] {
+        let mut buf = [0u8; SEGMENT_HEADER_SIZE];
+        buf[0x00..0x04].copy_from_slice(&self.magic.to_le_bytes());
+        buf[0x04] = self.version;
+        buf[0x05] = self.seg_type;
+        buf[0x06..0x08].copy_from_slice(&self.flags.to_le_bytes());
+        buf[0x08..0x10].copy_from_slice(&self.segment_id.to_le_bytes());
+        buf[0x10..0x18].copy_from_slice(&self.payload_length.to_le_bytes());
+        buf[0x18..0x20].copy_from_slice(&self.timestamp_ns.to_le_bytes());
+        buf[0x20] = self.checksum_algo;
+        buf[0x21] = self.compression;
+        buf[0x22..0x24].copy_from_slice(&self.reserved_0.to_le_bytes());
+        buf[0x24..0x28].copy_from_slice(&self.reserved_1.to_le_bytes());
+        buf[0x28..0x38].copy_from_slice(&self.content_hash);
+        buf[0x38..0x3C].copy_from_slice(&self.uncompressed_len.to_le_bytes());
+        buf[0x3C..0x40].copy_from_slice(&self.alignment_pad.to_le_bytes());
+        buf
+    }
+
+    /// Deserialize a header from the first 64 bytes of `data`.
+    ///
+    /// Checks the length and magic only; version and field validation are
+    /// left to the reader.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, RvfError> {
+        if data.len() < SEGMENT_HEADER_SIZE {
+            return Err(RvfError::SizeMismatch {
+                expected: SEGMENT_HEADER_SIZE,
+                got: data.len(),
+            });
+        }
+        let magic = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+        if magic != SEGMENT_MAGIC {
+            return Err(RvfError::BadMagic {
+                expected: SEGMENT_MAGIC,
+                got: magic,
+            });
+        }
+        let mut content_hash = [0u8; 16];
+        content_hash.copy_from_slice(&data[0x28..0x38]);
+
+        Ok(Self {
+            magic,
+            version: data[0x04],
+            seg_type: data[0x05],
+            flags: u16::from_le_bytes([data[0x06], data[0x07]]),
+            segment_id: u64::from_le_bytes(data[0x08..0x10].try_into().unwrap()),
+            payload_length: u64::from_le_bytes(data[0x10..0x18].try_into().unwrap()),
+            timestamp_ns: u64::from_le_bytes(data[0x18..0x20].try_into().unwrap()),
+            checksum_algo: data[0x20],
+            compression: data[0x21],
+            reserved_0: u16::from_le_bytes([data[0x22], data[0x23]]),
+            reserved_1: u32::from_le_bytes(data[0x24..0x28].try_into().unwrap()),
+            content_hash,
+            uncompressed_len: u32::from_le_bytes(data[0x38..0x3C].try_into().unwrap()),
+            alignment_pad: u32::from_le_bytes(data[0x3C..0x40].try_into().unwrap()),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constants::SEGMENT_MAGIC;
 
     #[test]
     fn header_size_is_64() {
@@ -129,4 +209,52 @@ mod tests {
         assert_eq!(uncompressed_len_off, 0x38);
         assert_eq!(alignment_pad_off, 0x3C);
     }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut h = SegmentHeader::new(0x01, 0x0102_0304_0506_0708);
+        h.flags = 0x0048;
+        h.payload_length = 4096;
+        h.timestamp_ns = 1_700_000_000_000_000_000;
+        h.checksum_algo = 1;
+        h.compression = 2;
+        h.content_hash = [0xAB; 16];
+        h.uncompressed_len = 8192;
+        h.alignment_pad = 0;
+
+        let bytes = h.to_bytes();
+        assert_eq!(&bytes[0..4], b"SFVR");
+        assert_eq!(bytes[0x08], 0x08);
+        assert_eq!(&bytes[0x28..0x38], &[0xAB; 16]);
+
+        let decoded = SegmentHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.segment_id, h.segment_id);
+        assert_eq!(decoded.flags, h.flags);
+        assert_eq!(decoded.payload_length, h.payload_length);
+        assert_eq!(decoded.timestamp_ns, h.timestamp_ns);
+        assert_eq!(decoded.uncompressed_len, h.uncompressed_len);
+    }
+
+    #[test]
+    fn from_bytes_rejects_bad_magic() {
+        let mut bytes = SegmentHeader::new(0x01, 0).to_bytes();
+        bytes[0] ^= 0xFF;
+        match SegmentHeader::from_bytes(&bytes) {
+            Err(RvfError::BadMagic { expected, .. }) => assert_eq!(expected, SEGMENT_MAGIC),
+            other => panic!("expected BadMagic, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn from_bytes_rejects_short_input() {
+        let bytes = SegmentHeader::new(0x01, 0).to_bytes();
+        match SegmentHeader::from_bytes(&bytes[..63]) {
+            Err(RvfError::SizeMismatch { expected, got }) => {
+                assert_eq!(expected, 64);
+                assert_eq!(got, 63);
+            }
+            other => panic!("expected SizeMismatch, got {other:?}"),
+        }
+    }
 }
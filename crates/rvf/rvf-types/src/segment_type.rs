@@ -63,6 +63,10 @@ pub enum SegmentType {
     Membership = 0x22,
     /// Sparse delta patches.
     Delta = 0x23,
+    /// Trained compression dictionary (see `compression_dict`).
+    CompressionDict = 0x24,
+    /// Store settings that must survive a reopen, as TLV records.
+    Settings = 0x25,
     /// Serialized transfer prior (cross-domain posterior summaries + cost EMAs).
     TransferPrior = 0x30,
     /// Policy kernel configuration and performance history.
@@ -98,6 +102,8 @@ impl TryFrom<u8> for SegmentType {
             0x21 => Ok(Self::Refcount),
             0x22 => Ok(Self::Membership),
             0x23 => Ok(Self::Delta),
+            0x24 => Ok(Self::CompressionDict),
+            0x25 => Ok(Self::Settings),
             0x30 => Ok(Self::TransferPrior),
             0x31 => Ok(Self::PolicyKernel),
             0x32 => Ok(Self::CostCurve),
@@ -135,6 +141,8 @@ mod tests {
             SegmentType::Refcount,
             SegmentType::Membership,
             SegmentType::Delta,
+            SegmentType::CompressionDict,
+            SegmentType::Settings,
             SegmentType::TransferPrior,
             SegmentType::PolicyKernel,
             SegmentType::CostCurve,
@@ -11,13 +11,15 @@ pub enum SignatureAlgo {
     MlDsa65 = 1,
     /// SLH-DSA-128s (7,856-byte signature, NIST Level 1 post-quantum).
     SlhDsa128s = 2,
+    /// Ed25519ph (64-byte signature over a SHA-512 prehash, RFC 8032 §5.1).
+    Ed25519ph = 3,
 }
 
 impl SignatureAlgo {
     /// Expected signature byte length for this algorithm.
     pub const fn sig_length(self) -> u16 {
         match self {
-            Self::Ed25519 => 64,
+            Self::Ed25519 | Self::Ed25519ph => 64,
             Self::MlDsa65 => 3309,
             Self::SlhDsa128s => 7856,
         }
@@ -26,7 +28,7 @@ impl SignatureAlgo {
     /// Whether this algorithm provides post-quantum security.
     pub const fn is_post_quantum(self) -> bool {
         match self {
-            Self::Ed25519 => false,
+            Self::Ed25519 | Self::Ed25519ph => false,
             Self::MlDsa65 | Self::SlhDsa128s => true,
         }
     }
@@ -40,6 +42,7 @@ impl TryFrom<u16> for SignatureAlgo {
             0 => Ok(Self::Ed25519),
             1 => Ok(Self::MlDsa65),
             2 => Ok(Self::SlhDsa128s),
+            3 => Ok(Self::Ed25519ph),
             other => Err(other),
         }
     }
@@ -89,16 +92,17 @@ mod tests {
 
     #[test]
     fn algo_round_trip() {
-        for raw in 0..=2u16 {
+        for raw in 0..=3u16 {
             let a = SignatureAlgo::try_from(raw).unwrap();
             assert_eq!(a as u16, raw);
         }
-        assert_eq!(SignatureAlgo::try_from(3), Err(3));
+        assert_eq!(SignatureAlgo::try_from(4), Err(4));
     }
 
     #[test]
     fn sig_lengths() {
         assert_eq!(SignatureAlgo::Ed25519.sig_length(), 64);
+        assert_eq!(SignatureAlgo::Ed25519ph.sig_length(), 64);
         assert_eq!(SignatureAlgo::MlDsa65.sig_length(), 3309);
         assert_eq!(SignatureAlgo::SlhDsa128s.sig_length(), 7856);
     }
@@ -106,6 +110,7 @@ mod tests {
     #[test]
     fn post_quantum_flag() {
         assert!(!SignatureAlgo::Ed25519.is_post_quantum());
+        assert!(!SignatureAlgo::Ed25519ph.is_post_quantum());
         assert!(SignatureAlgo::MlDsa65.is_post_quantum());
         assert!(SignatureAlgo::SlhDsa128s.is_post_quantum());
     }
@@ -0,0 +1,353 @@
+//! Tag-length-value records shared by manifest-style payloads.
+//!
+//! Each record is `[u16 tag][u32 length][length bytes]`, little-endian.
+//! Records are laid out back to back; a value may itself be a sequence of
+//! records (nested TLV).
+//!
+//! [`TlvReader`] borrows its input and never allocates. It fails with
+//! `RvfError::SizeMismatch` when a header or value runs past the end of
+//! the buffer and with `RvfError::InvalidTlv` when a tag repeats. Unknown
+//! tags are returned like any other, so callers can skip them for forward
+//! compatibility. [`TlvWriter`] requires the `alloc` feature.
+
+use crate::error::RvfError;
+
+/// Size of a record header (tag + length) in bytes.
+pub const TLV_HEADER_SIZE: usize = 6;
+
+/// A single decoded record borrowing its value from the input buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TlvRecord<'a> {
+    /// Record tag.
+    pub tag: u16,
+    /// Raw value bytes.
+    pub value: &'a [u8],
+}
+
+impl<'a> TlvRecord<'a> {
+    /// The value as UTF-8 text.
+    pub fn as_str(&self) -> Result<&'a str, RvfError> {
+        core::str::from_utf8(self.value).map_err(|_| RvfError::InvalidTlv {
+            tag: self.tag,
+            reason: "value is not valid UTF-8",
+        })
+    }
+
+    /// The value as a little-endian u64.
+    pub fn as_u64(&self) -> Result<u64, RvfError> {
+        self.value
+            .try_into()
+            .map(u64::from_le_bytes)
+            .map_err(|_| RvfError::InvalidTlv {
+                tag: self.tag,
+                reason: "u64 value must be 8 bytes",
+            })
+    }
+
+    /// The value as a 16-byte UUID.
+    pub fn as_uuid(&self) -> Result<[u8; 16], RvfError> {
+        self.value.try_into().map_err(|_| RvfError::InvalidTlv {
+            tag: self.tag,
+            reason: "UUID value must be 16 bytes",
+        })
+    }
+
+    /// Read the value as a nested sequence of records.
+    pub fn nested(&self) -> TlvReader<'a> {
+        TlvReader::new(self.value)
+    }
+}
+
+/// Bounds-checked iterator over the records in a buffer.
+///
+/// Yields `Err` at most once; iteration stops after the first error.
+#[derive(Clone, Debug)]
+pub struct TlvReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Bit `tag % 64` is set for every tag read so far. A set bit only
+    /// means the tag may repeat; [`TlvReader::seen_before`] confirms it.
+    seen: u64,
+}
+
+impl<'a> TlvReader<'a> {
+    /// Read records from `data`, starting at its first byte.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            seen: 0,
+        }
+    }
+
+    /// Whether every byte of the input has been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Validate the whole buffer and return the record with `tag`, if any.
+    pub fn find(&self, tag: u16) -> Result<Option<TlvRecord<'a>>, RvfError> {
+        let mut found = None;
+        for record in self.clone() {
+            let record = record?;
+            if record.tag == tag {
+                found = Some(record);
+            }
+        }
+        Ok(found)
+    }
+
+    /// The value of the record with `tag`, or `None` if it is absent or
+    /// the buffer is malformed. Use [`TlvReader::find`] to tell the two
+    /// apart.
+    pub fn find_tag(&self, tag: u16) -> Option<&'a [u8]> {
+        self.find(tag).ok().flatten().map(|record| record.value)
+    }
+
+    /// Whether a record before the current position has `tag`. Those
+    /// records were already validated, so their headers are in bounds.
+    fn seen_before(&self, tag: u16) -> bool {
+        let mut pos = 0;
+        while pos < self.pos {
+            let d = &self.data[pos..];
+            if u16::from_le_bytes([d[0], d[1]]) == tag {
+                return true;
+            }
+            let len = u32::from_le_bytes([d[2], d[3], d[4], d[5]]) as usize;
+            pos += TLV_HEADER_SIZE + len;
+        }
+        false
+    }
+
+    /// Stop iterating and return `e`.
+    fn fail(&mut self, e: RvfError) -> RvfError {
+        self.pos = self.data.len();
+        e
+    }
+}
+
+impl<'a> Iterator for TlvReader<'a> {
+    type Item = Result<TlvRecord<'a>, RvfError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (data, pos) = (self.data, self.pos);
+        if pos >= data.len() {
+            return None;
+        }
+        let remaining = data.len() - pos;
+        if remaining < TLV_HEADER_SIZE {
+            return Some(Err(self.fail(RvfError::SizeMismatch {
+                expected: TLV_HEADER_SIZE,
+                got: remaining,
+            })));
+        }
+        let tag = u16::from_le_bytes([data[pos], data[pos + 1]]);
+        let len = u32::from_le_bytes([data[pos + 2], data[pos + 3], data[pos + 4], data[pos + 5]])
+            as usize;
+        let available = remaining - TLV_HEADER_SIZE;
+        if len > available {
+            return Some(Err(self.fail(RvfError::SizeMismatch {
+                expected: len,
+                got: available,
+            })));
+        }
+        let bit = 1u64 << (tag & 63);
+        if self.seen & bit != 0 && self.seen_before(tag) {
+            return Some(Err(self.fail(RvfError::InvalidTlv {
+                tag,
+                reason: "duplicate tag",
+            })));
+        }
+        self.seen |= bit;
+        let start = pos + TLV_HEADER_SIZE;
+        self.pos = start + len;
+        Some(Ok(TlvRecord {
+            tag,
+            value: &data[start..start + len],
+        }))
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+pub use writer::TlvWriter;
+
+#[cfg(any(feature = "alloc", test))]
+mod writer {
+    use alloc::vec::Vec;
+
+    /// Builder that appends records to an owned buffer.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct TlvWriter {
+        buf: Vec<u8>,
+    }
+
+    impl TlvWriter {
+        /// Create an empty writer.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Append a record with raw bytes.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `value` is longer than `u32::MAX` bytes.
+        pub fn bytes(&mut self, tag: u16, value: &[u8]) -> &mut Self {
+            let len = u32::try_from(value.len()).expect("TLV value exceeds u32::MAX bytes");
+            self.buf.extend_from_slice(&tag.to_le_bytes());
+            self.buf.extend_from_slice(&len.to_le_bytes());
+            self.buf.extend_from_slice(value);
+            self
+        }
+
+        /// Append a UTF-8 string record.
+        pub fn str(&mut self, tag: u16, value: &str) -> &mut Self {
+            self.bytes(tag, value.as_bytes())
+        }
+
+        /// Append a little-endian u64 record.
+        pub fn u64(&mut self, tag: u16, value: u64) -> &mut Self {
+            self.bytes(tag, &value.to_le_bytes())
+        }
+
+        /// Append a 16-byte UUID record.
+        pub fn uuid(&mut self, tag: u16, value: &[u8; 16]) -> &mut Self {
+            self.bytes(tag, value)
+        }
+
+        /// Append a record whose value is the records written by `build`.
+        pub fn nested(&mut self, tag: u16, build: impl FnOnce(&mut TlvWriter)) -> &mut Self {
+            let mut inner = TlvWriter::new();
+            build(&mut inner);
+            self.bytes(tag, &inner.buf)
+        }
+
+        /// The encoded records so far.
+        pub fn as_bytes(&self) -> &[u8] {
+            &self.buf
+        }
+
+        /// Consume the writer, returning the encoded records.
+        pub fn into_bytes(self) -> Vec<u8> {
+            self.buf
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const NAME: u16 = 1;
+    const COUNT: u16 = 2;
+    const ID: u16 = 3;
+    const CHILD: u16 = 4;
+    const BLOB: u16 = 5;
+
+    #[test]
+    fn mixed_records_round_trip() {
+        let uuid = [0xA5; 16];
+        let mut w = TlvWriter::new();
+        w.str(NAME, "container")
+            .u64(COUNT, 42)
+            .uuid(ID, &uuid)
+            .nested(CHILD, |inner| {
+                inner.str(NAME, "nested").u64(COUNT, 7);
+            })
+            .bytes(BLOB, &[]);
+        let bytes = w.into_bytes();
+
+        let records: Vec<TlvRecord> = TlvReader::new(&bytes).collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].as_str(), Ok("container"));
+        assert_eq!(records[1].as_u64(), Ok(42));
+        assert_eq!(records[2].as_uuid(), Ok(uuid));
+        assert!(records[4].value.is_empty());
+
+        let child = TlvReader::new(&bytes).find(CHILD).unwrap().unwrap();
+        let nested = child.nested();
+        assert_eq!(nested.find(NAME).unwrap().unwrap().as_str(), Ok("nested"));
+        assert_eq!(nested.find(COUNT).unwrap().unwrap().as_u64(), Ok(7));
+        assert_eq!(nested.find(ID), Ok(None));
+
+        // Type mismatches are reported against the record's tag.
+        assert_eq!(
+            records[0].as_u64(),
+            Err(RvfError::InvalidTlv {
+                tag: NAME,
+                reason: "u64 value must be 8 bytes",
+            })
+        );
+    }
+
+    #[test]
+    fn truncated_value_errors() {
+        let mut w = TlvWriter::new();
+        w.u64(COUNT, 1).str(NAME, "abcdef");
+        let bytes = w.into_bytes();
+
+        let cut = &bytes[..bytes.len() - 2];
+        let mut reader = TlvReader::new(cut);
+        assert_eq!(reader.next().unwrap().unwrap().as_u64(), Ok(1));
+        assert_eq!(
+            reader.next(),
+            Some(Err(RvfError::SizeMismatch {
+                expected: 6,
+                got: 4
+            }))
+        );
+        assert_eq!(reader.next(), None);
+        assert!(TlvReader::new(cut).find(COUNT).is_err());
+    }
+
+    #[test]
+    fn reading_past_end_errors_without_panicking() {
+        // Every proper prefix of a valid buffer either parses or errors.
+        let mut w = TlvWriter::new();
+        w.uuid(ID, &[1; 16]).nested(CHILD, |inner| {
+            inner.u64(COUNT, 9);
+        });
+        let bytes = w.into_bytes();
+        for end in 0..bytes.len() {
+            let prefix = &bytes[..end];
+            let ok = TlvReader::new(prefix).all(|r| r.is_ok());
+            assert_eq!(ok, end == 0 || end == TLV_HEADER_SIZE + 16, "prefix {end}");
+        }
+
+        // A length field claiming more than remains.
+        let mut bogus = Vec::new();
+        bogus.extend_from_slice(&NAME.to_le_bytes());
+        bogus.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            TlvReader::new(&bogus).next(),
+            Some(Err(RvfError::SizeMismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn duplicate_tags_error() {
+        let mut w = TlvWriter::new();
+        w.u64(COUNT, 1).str(NAME, "x").u64(COUNT, 2);
+        let bytes = w.into_bytes();
+
+        let results: Vec<_> = TlvReader::new(&bytes).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_ok());
+        assert_eq!(
+            results[2],
+            Err(RvfError::InvalidTlv {
+                tag: COUNT,
+                reason: "duplicate tag",
+            })
+        );
+        assert!(TlvReader::new(&bytes).find(NAME).is_err());
+
+        // Distinct tags 64 apart share a bit in the seen mask.
+        let mut w = TlvWriter::new();
+        w.u64(COUNT, 1).u64(COUNT + 64, 2).u64(COUNT + 128, 3);
+        let bytes = w.into_bytes();
+        assert!(TlvReader::new(&bytes).all(|r| r.is_ok()));
+    }
+}
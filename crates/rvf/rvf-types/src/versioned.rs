@@ -0,0 +1,133 @@
+//! Forward-compatible decoding of wire enums.
+//!
+//! A newer writer may emit discriminants this reader does not know. For
+//! enums where that is harmless to carry along (segment types, quantization
+//! and compression identifiers), [`Versioned`] keeps the raw byte so a
+//! reader can re-emit it unchanged. Security-relevant enums (checksum and
+//! signature algorithms, kernel architecture, ...) deliberately do not
+//! implement [`WireEnum`] and keep rejecting unknown values.
+
+use crate::compression::CompressionAlgo;
+use crate::error::RvfError;
+use crate::quant_type::QuantType;
+use crate::segment_type::SegmentType;
+
+/// A single-byte wire enum that is safe to carry through unrecognized.
+pub trait WireEnum: Copy + TryFrom<u8> {
+    /// Type name reported in `RvfError::InvalidEnumValue`.
+    const TYPE_NAME: &'static str;
+
+    /// The wire discriminant of this variant.
+    fn to_byte(self) -> u8;
+}
+
+macro_rules! impl_wire_enum {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl WireEnum for $ty {
+                const TYPE_NAME: &'static str = stringify!($ty);
+
+                fn to_byte(self) -> u8 {
+                    self as u8
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_enum!(SegmentType, QuantType, CompressionAlgo);
+
+/// A wire enum value that may come from a newer format version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Versioned<T> {
+    /// A discriminant this reader understands.
+    Known(T),
+    /// An unrecognized discriminant, preserved verbatim.
+    Unknown(u8),
+}
+
+impl<T: WireEnum> Versioned<T> {
+    /// Decode a byte, keeping unrecognized values instead of failing.
+    pub fn from_byte(value: u8) -> Self {
+        match T::try_from(value) {
+            Ok(known) => Self::Known(known),
+            Err(_) => Self::Unknown(value),
+        }
+    }
+
+    /// Re-encode to the original byte.
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Self::Known(known) => known.to_byte(),
+            Self::Unknown(value) => value,
+        }
+    }
+
+    /// Whether this reader recognizes the value.
+    pub fn is_known(&self) -> bool {
+        matches!(self, Self::Known(_))
+    }
+
+    /// The known variant, or `InvalidEnumValue` for operations that cannot
+    /// proceed on an unrecognized value.
+    pub fn known(self) -> Result<T, RvfError> {
+        match self {
+            Self::Known(known) => Ok(known),
+            Self::Unknown(value) => Err(RvfError::InvalidEnumValue {
+                type_name: T::TYPE_NAME,
+                value: value as u64,
+            }),
+        }
+    }
+}
+
+impl<T: WireEnum> From<T> for Versioned<T> {
+    fn from(known: T) -> Self {
+        Self::Known(known)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_bytes_round_trip() {
+        for raw in 0..=u8::MAX {
+            assert_eq!(Versioned::<SegmentType>::from_byte(raw).to_byte(), raw);
+            assert_eq!(Versioned::<QuantType>::from_byte(raw).to_byte(), raw);
+            assert_eq!(Versioned::<CompressionAlgo>::from_byte(raw).to_byte(), raw);
+        }
+
+        let future = Versioned::<SegmentType>::from_byte(0x40);
+        assert_eq!(future, Versioned::Unknown(0x40));
+        assert!(!future.is_known());
+    }
+
+    #[test]
+    fn known_values_decode() {
+        let seg = Versioned::<SegmentType>::from_byte(SegmentType::Vec as u8);
+        assert!(seg.is_known());
+        assert_eq!(seg.known(), Ok(SegmentType::Vec));
+        assert_eq!(Versioned::from(QuantType::Product).to_byte(), 1);
+    }
+
+    #[test]
+    fn unknown_value_errors_when_required() {
+        assert_eq!(
+            Versioned::<CompressionAlgo>::from_byte(9).known(),
+            Err(RvfError::InvalidEnumValue {
+                type_name: "CompressionAlgo",
+                value: 9,
+            })
+        );
+        assert_eq!(
+            Versioned::<QuantType>::from_byte(0xFF).known(),
+            Err(RvfError::InvalidEnumValue {
+                type_name: "QuantType",
+                value: 0xFF,
+            })
+        );
+    }
+}
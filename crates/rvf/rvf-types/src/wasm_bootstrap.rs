@@ -33,6 +33,20 @@ use crate::error::RvfError;
 /// Magic number for `WasmHeader`: "RVWM" in big-endian.
 pub const WASM_MAGIC: u32 = 0x5256_574D;
 
+/// Length of the preamble at the start of every WASM binary.
+pub const WASM_PREAMBLE_LEN: usize = 8;
+
+/// Binary magic at the start of WASM bytecode (`\0asm`).
+pub const WASM_BINARY_MAGIC: [u8; 4] = *b"\0asm";
+
+/// Preamble of a core module: magic, version 1, layer 0.
+pub const WASM_CORE_PREAMBLE: [u8; WASM_PREAMBLE_LEN] =
+    [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+
+/// Preamble of a component: magic, version 0x0d, layer 1.
+pub const WASM_COMPONENT_PREAMBLE: [u8; WASM_PREAMBLE_LEN] =
+    [0x00, 0x61, 0x73, 0x6D, 0x0D, 0x00, 0x01, 0x00];
+
 /// Role of the embedded WASM module within the bootstrap chain.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -90,6 +104,8 @@ pub enum WasmTarget {
     Browser = 0x03,
     /// Bare-metal tile (no imports beyond host-tile protocol).
     BareTile = 0x04,
+    /// Component-model binary (component preamble, not a core module).
+    Component = 0x05,
 }
 
 impl TryFrom<u8> for WasmTarget {
@@ -102,6 +118,7 @@ impl TryFrom<u8> for WasmTarget {
             0x02 => Ok(Self::WasiP2),
             0x03 => Ok(Self::Browser),
             0x04 => Ok(Self::BareTile),
+            0x05 => Ok(Self::Component),
             _ => Err(RvfError::InvalidEnumValue {
                 type_name: "WasmTarget",
                 value: value as u64,
@@ -119,6 +136,39 @@ pub const WASM_FEAT_THREADS: u16 = 1 << 4;
 pub const WASM_FEAT_TAIL_CALL: u16 = 1 << 5;
 pub const WASM_FEAT_GC: u16 = 1 << 6;
 pub const WASM_FEAT_EXCEPTION_HANDLING: u16 = 1 << 7;
+pub const WASM_FEAT_COMPONENT_MODEL: u16 = 1 << 8;
+
+/// Whether WASM bytecode starts with a component preamble.
+///
+/// Returns `Ok(false)` for a core module and `Ok(true)` for a component.
+/// Fails with `SizeMismatch` if fewer than 8 bytes are given, `BadMagic`
+/// if the bytes do not start with `\0asm`, and `InvalidEnumValue` for an
+/// unknown version/layer pair.
+pub fn wasm_preamble_is_component(bytecode: &[u8]) -> Result<bool, RvfError> {
+    if bytecode.len() < WASM_PREAMBLE_LEN {
+        return Err(RvfError::SizeMismatch {
+            expected: WASM_PREAMBLE_LEN,
+            got: bytecode.len(),
+        });
+    }
+    if bytecode[..4] != WASM_BINARY_MAGIC {
+        return Err(RvfError::BadMagic {
+            expected: u32::from_le_bytes(WASM_BINARY_MAGIC),
+            got: u32::from_le_bytes([bytecode[0], bytecode[1], bytecode[2], bytecode[3]]),
+        });
+    }
+    let preamble = &bytecode[..WASM_PREAMBLE_LEN];
+    if preamble == WASM_CORE_PREAMBLE {
+        Ok(false)
+    } else if preamble == WASM_COMPONENT_PREAMBLE {
+        Ok(true)
+    } else {
+        Err(RvfError::InvalidEnumValue {
+            type_name: "WasmLayer",
+            value: u32::from_le_bytes([bytecode[4], bytecode[5], bytecode[6], bytecode[7]]) as u64,
+        })
+    }
+}
 
 /// 64-byte header for WASM_SEG payloads.
 ///
@@ -178,6 +228,28 @@ pub struct WasmHeader {
 const _: () = assert!(core::mem::size_of::<WasmHeader>() == 64);
 
 impl WasmHeader {
+    /// Returns true if the module is a component rather than a core module.
+    pub const fn is_component(&self) -> bool {
+        self.target == WasmTarget::Component as u8
+            || self.required_features & WASM_FEAT_COMPONENT_MODEL != 0
+    }
+
+    /// Check that `bytecode` starts with the preamble this header declares:
+    /// a component preamble if [`is_component`](Self::is_component), a core
+    /// module preamble otherwise.
+    ///
+    /// Errors are those of [`wasm_preamble_is_component`], plus
+    /// `InvalidEnumValue` for `WasmTarget` when the kinds disagree.
+    pub fn validate_bytecode(&self, bytecode: &[u8]) -> Result<(), RvfError> {
+        if wasm_preamble_is_component(bytecode)? != self.is_component() {
+            return Err(RvfError::InvalidEnumValue {
+                type_name: "WasmTarget",
+                value: self.target as u64,
+            });
+        }
+        Ok(())
+    }
+
     /// Serialize the header to a 64-byte little-endian array.
     pub fn to_bytes(&self) -> [u8; 64] {
         let mut buf = [0u8; 64];
@@ -384,7 +456,8 @@ mod tests {
         assert_eq!(WasmTarget::try_from(0x02), Ok(WasmTarget::WasiP2));
         assert_eq!(WasmTarget::try_from(0x03), Ok(WasmTarget::Browser));
         assert_eq!(WasmTarget::try_from(0x04), Ok(WasmTarget::BareTile));
-        assert!(WasmTarget::try_from(0x05).is_err());
+        assert_eq!(WasmTarget::try_from(0x05), Ok(WasmTarget::Component));
+        assert!(WasmTarget::try_from(0x06).is_err());
         assert!(WasmTarget::try_from(0xFF).is_err());
     }
 
@@ -398,5 +471,58 @@ mod tests {
         assert_eq!(WASM_FEAT_TAIL_CALL, 0x0020);
         assert_eq!(WASM_FEAT_GC, 0x0040);
         assert_eq!(WASM_FEAT_EXCEPTION_HANDLING, 0x0080);
+        assert_eq!(WASM_FEAT_COMPONENT_MODEL, 0x0100);
+    }
+
+    #[test]
+    fn core_module_preamble_validates() {
+        let h = sample_header();
+        assert!(!h.is_component());
+        let mut bytecode = WASM_CORE_PREAMBLE.to_vec();
+        bytecode.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        assert_eq!(wasm_preamble_is_component(&bytecode), Ok(false));
+        assert_eq!(h.validate_bytecode(&bytecode), Ok(()));
+        assert!(h.validate_bytecode(&WASM_COMPONENT_PREAMBLE).is_err());
+    }
+
+    #[test]
+    fn component_preamble_validates() {
+        let h = WasmHeader {
+            target: WasmTarget::Component as u8,
+            required_features: WASM_FEAT_COMPONENT_MODEL,
+            ..sample_header()
+        };
+        let decoded = WasmHeader::from_bytes(&h.to_bytes()).unwrap();
+        assert!(decoded.is_component());
+        assert_eq!(
+            wasm_preamble_is_component(&WASM_COMPONENT_PREAMBLE),
+            Ok(true)
+        );
+        assert_eq!(decoded.validate_bytecode(&WASM_COMPONENT_PREAMBLE), Ok(()));
+        assert!(decoded.validate_bytecode(&WASM_CORE_PREAMBLE).is_err());
+    }
+
+    #[test]
+    fn truncated_or_foreign_preamble_rejected() {
+        assert_eq!(
+            wasm_preamble_is_component(&WASM_COMPONENT_PREAMBLE[..6]),
+            Err(RvfError::SizeMismatch {
+                expected: WASM_PREAMBLE_LEN,
+                got: 6
+            })
+        );
+        assert!(matches!(
+            wasm_preamble_is_component(b"\x7fELF\x02\x01\x01\x00"),
+            Err(RvfError::BadMagic { .. })
+        ));
+        let mut unknown_layer = WASM_COMPONENT_PREAMBLE;
+        unknown_layer[6] = 0x02;
+        assert!(matches!(
+            wasm_preamble_is_component(&unknown_layer),
+            Err(RvfError::InvalidEnumValue {
+                type_name: "WasmLayer",
+                ..
+            })
+        ));
     }
 }
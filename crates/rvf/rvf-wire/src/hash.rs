@@ -4,7 +4,46 @@
 //! identified by the `checksum_algo` field: 0=deprecated CRC32C (now
 //! upgraded to XXH3-128), 1=XXH3-128, 2=SHAKE-256 (first 128 bits).
 
-use rvf_types::SegmentHeader;
+use rvf_types::{ChecksumAlgo, SegmentHeader};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Incremental content hash for payloads read or written in chunks.
+///
+/// Feeding a payload through [`update`](Self::update) in any split yields
+/// the same value as [`compute_content_hash`] over the whole buffer, so
+/// large segments can be verified as they stream off disk.
+pub struct ChecksumState {
+    algo: ChecksumAlgo,
+    hasher: Xxh3,
+}
+
+impl ChecksumState {
+    /// Start a new hash for the given algorithm.
+    ///
+    /// Every algorithm currently hashes with XXH3-128; see
+    /// [`compute_content_hash`] for why CRC32C was upgraded.
+    pub fn new(algo: ChecksumAlgo) -> Self {
+        Self {
+            algo,
+            hasher: Xxh3::new(),
+        }
+    }
+
+    /// The algorithm recorded for this hash.
+    pub fn algo(&self) -> ChecksumAlgo {
+        self.algo
+    }
+
+    /// Feed the next chunk of the payload.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+    }
+
+    /// Finish and return the 16-byte content hash field value.
+    pub fn finalize(self) -> [u8; 16] {
+        self.hasher.digest128().to_le_bytes()
+    }
+}
 
 /// Compute the XXH3-128 hash of `data`, returning a 16-byte array.
 pub fn compute_xxh3_128(data: &[u8]) -> [u8; 16] {
@@ -12,7 +51,10 @@ pub fn compute_xxh3_128(data: &[u8]) -> [u8; 16] {
     h.to_le_bytes()
 }
 
-/// Compute the CRC32C checksum of `data`.
+/// Compute the CRC32C (Castagnoli) checksum of `data`.
+///
+/// Uses the SSE4.2 or ARMv8 CRC instructions when the CPU supports them
+/// (detected at runtime) and a table-driven implementation otherwise.
 pub fn compute_crc32c(data: &[u8]) -> u32 {
     crc32c::crc32c(data)
 }
@@ -37,11 +79,14 @@ pub fn compute_crc32c_hash(data: &[u8]) -> [u8; 16] {
 ///   now use the full 128-bit XXH3 hash.
 /// - 1 = XXH3-128 (16 bytes)
 /// - Other values fall back to XXH3-128.
-pub fn compute_content_hash(_algo: u8, data: &[u8]) -> [u8; 16] {
+pub fn compute_content_hash(algo: u8, data: &[u8]) -> [u8; 16] {
     // All algorithms now use XXH3-128 for full 128-bit collision resistance.
     // algo=0 (CRC32C) is deprecated: its 32-bit output zero-padded to 128 bits
     // provided only ~32 bits of security, making collisions trivially findable.
-    compute_xxh3_128(data)
+    let algo = ChecksumAlgo::try_from(algo).unwrap_or(ChecksumAlgo::Xxh3_128);
+    let mut state = ChecksumState::new(algo);
+    state.update(data);
+    state.finalize()
 }
 
 /// Verify the content hash stored in a segment header against the actual
@@ -75,6 +120,13 @@ mod tests {
         assert_ne!(c1, 0);
     }
 
+    #[test]
+    fn crc32c_check_value() {
+        // Standard CRC-32C check value for the ASCII string "123456789".
+        assert_eq!(compute_crc32c(b"123456789"), 0xE306_9283);
+        assert_eq!(compute_crc32c(b""), 0);
+    }
+
     #[test]
     fn crc32c_hash_is_zero_padded() {
         let data = b"test payload";
@@ -84,6 +136,44 @@ mod tests {
         assert_eq!(&h[4..], &[0u8; 12]);
     }
 
+    #[test]
+    fn incremental_matches_one_shot() {
+        // xorshift64 keeps the chunk boundaries varied but reproducible.
+        let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for round in 0..64 {
+            let len = (next() % 8192) as usize + round;
+            let payload: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            let algo = ChecksumAlgo::try_from((round % 3) as u8).unwrap();
+
+            let mut state = ChecksumState::new(algo);
+            let mut offset = 0;
+            while offset < payload.len() {
+                let chunk = ((next() % 600) as usize).min(payload.len() - offset);
+                state.update(&payload[offset..offset + chunk]);
+                offset += chunk;
+            }
+            assert_eq!(state.algo(), algo);
+            assert_eq!(
+                state.finalize(),
+                compute_content_hash(algo as u8, &payload),
+                "round {round}, len {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn incremental_empty_matches_one_shot() {
+        let state = ChecksumState::new(ChecksumAlgo::Xxh3_128);
+        assert_eq!(state.finalize(), compute_xxh3_128(b""));
+    }
+
     #[test]
     fn verify_content_hash_xxh3() {
         let payload = b"some vector data";
@@ -4,9 +4,7 @@
 //! magic and version fields, and optionally verifies the content hash.
 
 use crate::hash::verify_content_hash;
-use rvf_types::{
-    ErrorCode, RvfError, SegmentHeader, SEGMENT_HEADER_SIZE, SEGMENT_MAGIC, SEGMENT_VERSION,
-};
+use rvf_types::{ErrorCode, RvfError, SegmentHeader, SEGMENT_HEADER_SIZE, SEGMENT_VERSION};
 
 /// Read and parse a segment header from the first 64 bytes of `data`.
 ///
@@ -23,49 +21,11 @@ pub fn read_segment_header(data: &[u8]) -> Result<SegmentHeader, RvfError> {
         return Err(RvfError::Code(ErrorCode::TruncatedSegment));
     }
 
-    let magic = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
-    if magic != SEGMENT_MAGIC {
-        return Err(RvfError::BadMagic {
-            expected: SEGMENT_MAGIC,
-            got: magic,
-        });
-    }
-
-    let version = data[4];
-    if version != SEGMENT_VERSION {
+    let header = SegmentHeader::from_bytes(data)?;
+    if header.version != SEGMENT_VERSION {
         return Err(RvfError::Code(ErrorCode::InvalidVersion));
     }
-
-    let seg_type = data[5];
-    let flags = u16::from_le_bytes([data[6], data[7]]);
-    let segment_id = u64::from_le_bytes(data[0x08..0x10].try_into().unwrap());
-    let payload_length = u64::from_le_bytes(data[0x10..0x18].try_into().unwrap());
-    let timestamp_ns = u64::from_le_bytes(data[0x18..0x20].try_into().unwrap());
-    let checksum_algo = data[0x20];
-    let compression = data[0x21];
-    let reserved_0 = u16::from_le_bytes([data[0x22], data[0x23]]);
-    let reserved_1 = u32::from_le_bytes(data[0x24..0x28].try_into().unwrap());
-    let mut content_hash = [0u8; 16];
-    content_hash.copy_from_slice(&data[0x28..0x38]);
-    let uncompressed_len = u32::from_le_bytes(data[0x38..0x3C].try_into().unwrap());
-    let alignment_pad = u32::from_le_bytes(data[0x3C..0x40].try_into().unwrap());
-
-    Ok(SegmentHeader {
-        magic,
-        version,
-        seg_type,
-        flags,
-        segment_id,
-        payload_length,
-        timestamp_ns,
-        checksum_algo,
-        compression,
-        reserved_0,
-        reserved_1,
-        content_hash,
-        uncompressed_len,
-        alignment_pad,
-    })
+    Ok(header)
 }
 
 /// Validate the content hash of a segment.
@@ -116,7 +76,7 @@ pub fn read_segment(data: &[u8]) -> Result<(SegmentHeader, &[u8]), RvfError> {
 mod tests {
     use super::*;
     use crate::writer::write_segment;
-    use rvf_types::{SegmentFlags, SegmentType};
+    use rvf_types::{SegmentFlags, SegmentType, SEGMENT_MAGIC};
 
     #[test]
     fn read_write_round_trip() {
@@ -64,23 +64,7 @@ pub fn write_segment_with_algo(
     };
 
     let mut buf = Vec::with_capacity(total_size);
-    // Serialize header fields in little-endian order
-    buf.extend_from_slice(&header.magic.to_le_bytes());
-    buf.push(header.version);
-    buf.push(header.seg_type);
-    buf.extend_from_slice(&header.flags.to_le_bytes());
-    buf.extend_from_slice(&header.segment_id.to_le_bytes());
-    buf.extend_from_slice(&header.payload_length.to_le_bytes());
-    buf.extend_from_slice(&header.timestamp_ns.to_le_bytes());
-    buf.push(header.checksum_algo);
-    buf.push(header.compression);
-    buf.extend_from_slice(&header.reserved_0.to_le_bytes());
-    buf.extend_from_slice(&header.reserved_1.to_le_bytes());
-    buf.extend_from_slice(&header.content_hash);
-    buf.extend_from_slice(&header.uncompressed_len.to_le_bytes());
-    buf.extend_from_slice(&header.alignment_pad.to_le_bytes());
-
-    debug_assert_eq!(buf.len(), SEGMENT_HEADER_SIZE);
+    buf.extend_from_slice(&header.to_bytes());
 
     // Payload
     buf.extend_from_slice(payload);
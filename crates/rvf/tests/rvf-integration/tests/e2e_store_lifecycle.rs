@@ -6,6 +6,7 @@
 
 use rvf_runtime::options::{DistanceMetric, QueryOptions, RvfOptions};
 use rvf_runtime::RvfStore;
+use rvf_types::RvfError;
 use tempfile::TempDir;
 
 /// Deterministic pseudo-random vector generation using an LCG.
@@ -513,14 +514,20 @@ fn lifecycle_dimension_mismatch_rejected() {
     let result = store.ingest_batch(&[good.as_slice()], &[1], None).unwrap();
     assert_eq!(result.accepted, 1);
 
-    // Wrong dimension: should be rejected.
+    // Wrong dimension: the whole batch should be rejected.
     let bad = vec![1.0f32; 4]; // dim=4 when store expects dim=8
-    let result = store.ingest_batch(&[bad.as_slice()], &[2], None).unwrap();
+    let err = store
+        .ingest_batch(&[good.as_slice(), bad.as_slice()], &[2, 3], None)
+        .unwrap_err();
     assert_eq!(
-        result.accepted, 0,
+        err,
+        RvfError::DimensionMismatch {
+            expected: 8,
+            got: 4
+        },
         "wrong-dimension vector should be rejected"
     );
-    assert_eq!(result.rejected, 1);
+    assert_eq!(store.status().total_vectors, 1);
 
     // Query with wrong dimension should fail.
     let bad_query = vec![1.0f32; 4];
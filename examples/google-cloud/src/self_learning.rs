@@ -12,8 +12,8 @@ use std::time::Instant;
 
 // Import RuVector crates
 use ruvector_attention::{
-    traits::Attention, HyperbolicAttention, HyperbolicAttentionConfig, MoEAttention, MoEConfig,
-    MultiHeadAttention, ScaledDotProductAttention,
+    traits::Attention, HyperbolicAttention, HyperbolicAttentionConfig, HyperbolicModel,
+    MoEAttention, MoEConfig, MultiHeadAttention, ScaledDotProductAttention,
 };
 use ruvector_gnn::{
     ewc::ElasticWeightConsolidation,
@@ -190,6 +190,7 @@ impl FinancialModel {
             temperature: 0.5,
             frechet_max_iter: 50,
             frechet_tol: 1e-5,
+            model: HyperbolicModel::Poincare,
         });
 
         let optimizer = Optimizer::new(OptimizerType::Adam {